@@ -43,15 +43,15 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     bus.register::<CreateUser, _>(Arc::new(CreateUserHandler))?;
     bus.register::<DeleteUser, _>(Arc::new(DeleteUserHandler))?;
 
-    let ctx = AppContext {
-        event_context: EventContext::builder()
+    let ctx = AppContext::new(
+        EventContext::builder()
             .maybe_correlation_id(Some("cor-1".into()))
             .maybe_causation_id(Some("cau-1".into()))
             .maybe_actor_type(Some("user".into()))
             .maybe_actor_id(Some("u-1".into()))
             .build(),
-        idempotency_key: Some("idem-1".into()),
-    };
+    )
+    .with_idempotency_key("idem-1");
     bus.dispatch(
         &ctx,
         CreateUser {
@@ -61,15 +61,14 @@ async fn main() -> Result<(), Box<dyn std::error::Error>> {
     bus.register::<GetUser, UserDto, _>(Arc::new(GetUserHandler))?;
     bus.register::<ListUsers, UsersDto, _>(Arc::new(ListUsersHandler))?;
 
-    let ctx = AppContext {
-        event_context: EventContext::builder()
+    let ctx = AppContext::new(
+        EventContext::builder()
             .maybe_correlation_id(Some("cor-2".into()))
             .maybe_causation_id(Some("cau-2".into()))
             .maybe_actor_type(Some("user".into()))
             .maybe_actor_id(Some("u-2".into()))
             .build(),
-        idempotency_key: None,
-    };
+    );
     let dto = bus
         .dispatch::<GetUser, UserDto>(&ctx, GetUser { id: 1 })
         .await?;
@@ -0,0 +1,405 @@
+//! 一对多子集合读模型（Child Collection）
+//!
+//! 维护父/子读模型（如订单 + 订单行）的投影需要处理：子集合的增量变更、整体替换时的差异计算、
+//! 父记录删除后遗留子行的清理，以及按批写入存储。本模块提供与存储无关的辅助：
+//! - `ChildDiff::between`：按子记录键比较当前与期望的子集合，得到需写入与需删除的子记录；
+//! - `ChildChange`：由事件映射出的增量变更，同一子记录的多次变更按顺序合并为最终结果；
+//! - `ChildCollectionProjector`：应用整体替换（`replace`）或增量变更（`apply`），按 `with_batch_size`
+//!   分批调用 `ChildStore`，并提供孤儿清理（`remove_orphans`）；
+//! - `ChildStore`：子行存储协议，SQL 实现以单条多行语句完成一批写入/删除（如
+//!   `INSERT ... ON CONFLICT DO UPDATE`、`DELETE ... WHERE key = ANY($1)`）；`InMemoryChildStore` 用于原型与测试。
+//!
+use crate::error::AppError;
+use async_trait::async_trait;
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::{Arc, RwLock};
+
+type KeyFn<C> = Arc<dyn Fn(&C) -> String + Send + Sync>;
+
+/// 子集合差异
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChildDiff<C> {
+    /// 新增或内容变化的子记录
+    pub upserts: Vec<C>,
+    /// 需删除的子记录键
+    pub removals: Vec<String>,
+}
+
+impl<C> Default for ChildDiff<C> {
+    fn default() -> Self {
+        Self {
+            upserts: Vec::new(),
+            removals: Vec::new(),
+        }
+    }
+}
+
+impl<C> ChildDiff<C>
+where
+    C: Clone + PartialEq,
+{
+    /// 按子记录键比较 `current` 与 `desired`：未变化的子记录不出现在差异中
+    pub fn between<F>(current: &[C], desired: &[C], key: F) -> Self
+    where
+        F: Fn(&C) -> String,
+    {
+        let existing: BTreeMap<String, &C> = current.iter().map(|c| (key(c), c)).collect();
+        let wanted: BTreeSet<String> = desired.iter().map(&key).collect();
+
+        Self {
+            upserts: desired
+                .iter()
+                .filter(|c| existing.get(&key(c)) != Some(c))
+                .cloned()
+                .collect(),
+            removals: existing
+                .into_keys()
+                .filter(|k| !wanted.contains(k))
+                .collect(),
+        }
+    }
+}
+
+impl<C> ChildDiff<C> {
+    pub fn is_empty(&self) -> bool {
+        self.upserts.is_empty() && self.removals.is_empty()
+    }
+}
+
+/// 由事件映射出的子记录变更
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ChildChange<C> {
+    Upsert(C),
+    Remove(String),
+}
+
+/// 子行存储协议（按父记录 ID 分组）
+#[async_trait]
+pub trait ChildStore<C>: Send + Sync {
+    /// 父记录的全部子行
+    async fn children(&self, parent_id: &str) -> Result<Vec<C>, AppError>;
+
+    /// 批量写入或替换子行（一次往返）
+    async fn upsert_batch(&self, parent_id: &str, children: Vec<C>) -> Result<(), AppError>;
+
+    /// 按键批量删除子行（一次往返）
+    async fn remove_batch(&self, parent_id: &str, keys: Vec<String>) -> Result<(), AppError>;
+
+    /// 拥有子行的全部父记录 ID
+    async fn parent_ids(&self) -> Result<Vec<String>, AppError>;
+
+    /// 删除父记录的全部子行，返回删除数量
+    async fn remove_parent(&self, parent_id: &str) -> Result<usize, AppError>;
+}
+
+/// 一对多子集合投影辅助
+pub struct ChildCollectionProjector<C, S> {
+    store: Arc<S>,
+    key: KeyFn<C>,
+    batch_size: usize,
+}
+
+impl<C, S> ChildCollectionProjector<C, S>
+where
+    C: Clone + PartialEq + Send + Sync + 'static,
+    S: ChildStore<C>,
+{
+    /// `key` 从子记录中取其在父记录内唯一的键（如订单行的 SKU）
+    pub fn new<F>(store: Arc<S>, key: F) -> Self
+    where
+        F: Fn(&C) -> String + Send + Sync + 'static,
+    {
+        Self {
+            store,
+            key: Arc::new(key),
+            batch_size: 500,
+        }
+    }
+
+    /// 每批写入/删除的最大行数（默认 500）
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    pub fn store(&self) -> &Arc<S> {
+        &self.store
+    }
+
+    /// 以事件携带的完整子集合替换父记录的子行，仅写入差异
+    pub async fn replace(
+        &self,
+        parent_id: &str,
+        desired: Vec<C>,
+    ) -> Result<ChildDiff<C>, AppError> {
+        let current = self.store.children(parent_id).await?;
+        let diff = ChildDiff::between(&current, &desired, |c| (self.key)(c));
+        self.write(parent_id, diff.clone()).await?;
+        Ok(diff)
+    }
+
+    /// 应用增量变更：同一子记录的多次变更按顺序合并，仅保留最终结果
+    pub async fn apply(
+        &self,
+        parent_id: &str,
+        changes: Vec<ChildChange<C>>,
+    ) -> Result<ChildDiff<C>, AppError> {
+        let mut last: BTreeMap<String, ChildChange<C>> = BTreeMap::new();
+        for change in changes {
+            let key = match &change {
+                ChildChange::Upsert(child) => (self.key)(child),
+                ChildChange::Remove(key) => key.clone(),
+            };
+            last.insert(key, change);
+        }
+
+        let mut diff = ChildDiff::default();
+        for (key, change) in last {
+            match change {
+                ChildChange::Upsert(child) => diff.upserts.push(child),
+                ChildChange::Remove(_) => diff.removals.push(key),
+            }
+        }
+        self.write(parent_id, diff.clone()).await?;
+        Ok(diff)
+    }
+
+    /// 删除父记录的全部子行（父记录删除时调用）
+    pub async fn remove_parent(&self, parent_id: &str) -> Result<usize, AppError> {
+        self.store.remove_parent(parent_id).await
+    }
+
+    /// 清理父记录已不存在的子行，返回被清理的父记录 ID
+    pub async fn remove_orphans<F>(&self, parent_exists: F) -> Result<Vec<String>, AppError>
+    where
+        F: Fn(&str) -> bool + Send + Sync,
+    {
+        let mut orphaned = Vec::new();
+        for parent_id in self.store.parent_ids().await? {
+            if !parent_exists(&parent_id) {
+                self.store.remove_parent(&parent_id).await?;
+                orphaned.push(parent_id);
+            }
+        }
+        Ok(orphaned)
+    }
+
+    async fn write(&self, parent_id: &str, diff: ChildDiff<C>) -> Result<(), AppError> {
+        for keys in diff.removals.chunks(self.batch_size) {
+            self.store.remove_batch(parent_id, keys.to_vec()).await?;
+        }
+        for children in diff.upserts.chunks(self.batch_size) {
+            self.store
+                .upsert_batch(parent_id, children.to_vec())
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// 内存子行存储（按父记录 ID、子记录键有序存放）
+pub struct InMemoryChildStore<C> {
+    key: KeyFn<C>,
+    rows: RwLock<BTreeMap<String, BTreeMap<String, C>>>,
+}
+
+impl<C> InMemoryChildStore<C>
+where
+    C: Clone + Send + Sync + 'static,
+{
+    pub fn new<F>(key: F) -> Self
+    where
+        F: Fn(&C) -> String + Send + Sync + 'static,
+    {
+        Self {
+            key: Arc::new(key),
+            rows: RwLock::new(BTreeMap::new()),
+        }
+    }
+
+    /// 子行总数
+    pub fn len(&self) -> usize {
+        self.rows.read().unwrap().values().map(BTreeMap::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[async_trait]
+impl<C> ChildStore<C> for InMemoryChildStore<C>
+where
+    C: Clone + Send + Sync + 'static,
+{
+    async fn children(&self, parent_id: &str) -> Result<Vec<C>, AppError> {
+        Ok(self
+            .rows
+            .read()
+            .unwrap()
+            .get(parent_id)
+            .map(|rows| rows.values().cloned().collect())
+            .unwrap_or_default())
+    }
+
+    async fn upsert_batch(&self, parent_id: &str, children: Vec<C>) -> Result<(), AppError> {
+        let mut rows = self.rows.write().unwrap();
+        let parent = rows.entry(parent_id.to_string()).or_default();
+        for child in children {
+            parent.insert((self.key)(&child), child);
+        }
+        Ok(())
+    }
+
+    async fn remove_batch(&self, parent_id: &str, keys: Vec<String>) -> Result<(), AppError> {
+        let mut rows = self.rows.write().unwrap();
+        if let Some(parent) = rows.get_mut(parent_id) {
+            for key in keys {
+                parent.remove(&key);
+            }
+            if parent.is_empty() {
+                rows.remove(parent_id);
+            }
+        }
+        Ok(())
+    }
+
+    async fn parent_ids(&self) -> Result<Vec<String>, AppError> {
+        Ok(self.rows.read().unwrap().keys().cloned().collect())
+    }
+
+    async fn remove_parent(&self, parent_id: &str) -> Result<usize, AppError> {
+        Ok(self
+            .rows
+            .write()
+            .unwrap()
+            .remove(parent_id)
+            .map_or(0, |rows| rows.len()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct OrderLine {
+        sku: String,
+        quantity: u32,
+    }
+
+    fn line(sku: &str, quantity: u32) -> OrderLine {
+        OrderLine {
+            sku: sku.to_string(),
+            quantity,
+        }
+    }
+
+    /// 统计写入批次的存储
+    struct CountingStore {
+        inner: InMemoryChildStore<OrderLine>,
+        batches: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl ChildStore<OrderLine> for CountingStore {
+        async fn children(&self, parent_id: &str) -> Result<Vec<OrderLine>, AppError> {
+            self.inner.children(parent_id).await
+        }
+
+        async fn upsert_batch(
+            &self,
+            parent_id: &str,
+            children: Vec<OrderLine>,
+        ) -> Result<(), AppError> {
+            self.batches.fetch_add(1, Ordering::SeqCst);
+            self.inner.upsert_batch(parent_id, children).await
+        }
+
+        async fn remove_batch(&self, parent_id: &str, keys: Vec<String>) -> Result<(), AppError> {
+            self.batches.fetch_add(1, Ordering::SeqCst);
+            self.inner.remove_batch(parent_id, keys).await
+        }
+
+        async fn parent_ids(&self) -> Result<Vec<String>, AppError> {
+            self.inner.parent_ids().await
+        }
+
+        async fn remove_parent(&self, parent_id: &str) -> Result<usize, AppError> {
+            self.inner.remove_parent(parent_id).await
+        }
+    }
+
+    fn sku(l: &OrderLine) -> String {
+        l.sku.clone()
+    }
+
+    #[test]
+    fn diff_skips_unchanged_children() {
+        let current = [line("a", 1), line("b", 2), line("c", 3)];
+        let desired = [line("a", 1), line("b", 5), line("d", 1)];
+
+        let diff = ChildDiff::between(&current, &desired, sku);
+        assert_eq!(diff.upserts, [line("b", 5), line("d", 1)]);
+        assert_eq!(diff.removals, ["c"]);
+        assert!(ChildDiff::between(&current, &current, sku).is_empty());
+    }
+
+    #[tokio::test]
+    async fn replaces_and_applies_changes_in_batches() -> Result<(), AppError> {
+        let store = Arc::new(CountingStore {
+            inner: InMemoryChildStore::new(sku),
+            batches: AtomicUsize::new(0),
+        });
+        let projector = ChildCollectionProjector::new(store.clone(), sku).with_batch_size(2);
+
+        let lines: Vec<_> = (0..5).map(|i| line(&format!("s-{i}"), 1)).collect();
+        projector.replace("o-1", lines.clone()).await?;
+        assert_eq!(store.batches.load(Ordering::SeqCst), 3);
+
+        // 重复替换相同集合不产生写入
+        assert!(projector.replace("o-1", lines).await?.is_empty());
+        assert_eq!(store.batches.load(Ordering::SeqCst), 3);
+
+        // 同一子记录的多次变更只保留最终结果
+        let diff = projector
+            .apply(
+                "o-1",
+                vec![
+                    ChildChange::Upsert(line("s-9", 1)),
+                    ChildChange::Upsert(line("s-0", 4)),
+                    ChildChange::Remove("s-9".to_string()),
+                    ChildChange::Remove("s-1".to_string()),
+                ],
+            )
+            .await?;
+        assert_eq!(diff.upserts, [line("s-0", 4)]);
+        assert_eq!(diff.removals, ["s-1", "s-9"]);
+
+        let children = store.children("o-1").await?;
+        assert_eq!(children.len(), 4);
+        assert_eq!(children[0], line("s-0", 4));
+        Ok(())
+    }
+
+    #[tokio::test]
+    async fn removes_orphaned_children() -> Result<(), AppError> {
+        let store = Arc::new(InMemoryChildStore::new(sku));
+        let projector = ChildCollectionProjector::new(store.clone(), sku);
+        projector.replace("o-1", vec![line("a", 1)]).await?;
+        projector
+            .replace("o-2", vec![line("a", 1), line("b", 1)])
+            .await?;
+        projector.replace("o-3", vec![line("a", 1)]).await?;
+
+        assert_eq!(projector.remove_parent("o-3").await?, 1);
+        let parents = BTreeSet::from(["o-1".to_string()]);
+        let orphaned = projector
+            .remove_orphans(|parent_id| parents.contains(parent_id))
+            .await?;
+        assert_eq!(orphaned, ["o-2"]);
+        assert_eq!(store.len(), 1);
+        Ok(())
+    }
+}
@@ -0,0 +1,388 @@
+//! 命令审计（Command Audit Trail）
+//!
+//! 与事件侧审计对称，在命令总线上记录每次命令分发：
+//! - 命令类型、（脱敏/摘要后的）负载、执行主体、关联 ID 与幂等键；
+//! - 执行结果（成功/失败错误码）、耗时，以及处理过程中产生的事件 ID。
+//!
+//! `AuditedCommandBus` 作为 `CommandBus` 的装饰器（中间件）使用；
+//! 处理器在持久化事件后调用 `record_event_ids` 上报本次命令产生的事件。
+//!
+//! 经 `with_replayable` 登记的命令额外保存完整负载（`ReplayableCommand`，按 `CommandRouter`
+//! 的命令类型名），可由 `command_replay::CommandReplayer` 在沙箱系统中重新分发。
+//!
+use crate::{
+    command_bus::{CommandBus, CommandEnvelope, EnvelopeCommandBus},
+    context::AppContext,
+    error::AppError,
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use dashmap::DashMap;
+use ddd_domain::error::ErrorCode;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::any::{Any, TypeId, type_name};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+
+tokio::task_local! {
+    static EVENT_IDS: Mutex<Vec<String>>;
+}
+
+/// 上报当前命令产生的事件 ID；不在审计范围内调用时忽略
+pub fn record_event_ids<I, S>(event_ids: I)
+where
+    I: IntoIterator<Item = S>,
+    S: Into<String>,
+{
+    let _ = EVENT_IDS.try_with(|ids| {
+        ids.lock()
+            .unwrap()
+            .extend(event_ids.into_iter().map(Into::into));
+    });
+}
+
+/// 命令执行结果
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum CommandOutcome {
+    Succeeded,
+    Failed { code: String, message: String },
+}
+
+/// 可重放的命令：命令路由名与完整（未脱敏）负载
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReplayableCommand {
+    /// `CommandRouter` 中注册的命令类型名
+    pub name: String,
+    pub payload: Value,
+}
+
+/// 单次命令分发的审计记录
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CommandAuditRecord {
+    /// 命令类型名
+    pub command_type: String,
+    /// 负载描述（脱敏或摘要），未注册描述函数时为空
+    pub payload: Option<Value>,
+    pub actor_type: Option<String>,
+    pub actor_id: Option<String>,
+    pub correlation_id: Option<String>,
+    pub idempotency_key: Option<String>,
+    pub outcome: CommandOutcome,
+    /// 执行耗时（毫秒）
+    pub duration_ms: u128,
+    /// 本次命令产生的事件 ID
+    pub event_ids: Vec<String>,
+    pub recorded_at: DateTime<Utc>,
+    /// 可重放的完整命令，仅登记为可重放的命令类型存在
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replay: Option<ReplayableCommand>,
+}
+
+/// 命令审计存储
+#[async_trait]
+pub trait CommandAuditStore: Send + Sync {
+    async fn record(&self, record: CommandAuditRecord) -> Result<(), AppError>;
+
+    /// 按记录顺序读取全部审计记录（供命令重放）；默认不支持
+    async fn list(&self) -> Result<Vec<CommandAuditRecord>, AppError> {
+        Err(AppError::internal(
+            "listing is not supported by this command audit store",
+        ))
+    }
+}
+
+/// 基于内存的 CommandAuditStore 实现
+#[derive(Clone, Default)]
+pub struct InMemoryCommandAuditStore {
+    inner: Arc<Mutex<Vec<CommandAuditRecord>>>,
+}
+
+impl InMemoryCommandAuditStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn records(&self) -> Vec<CommandAuditRecord> {
+        self.inner.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl CommandAuditStore for InMemoryCommandAuditStore {
+    async fn record(&self, record: CommandAuditRecord) -> Result<(), AppError> {
+        self.inner.lock().unwrap().push(record);
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<CommandAuditRecord>, AppError> {
+        Ok(self.records())
+    }
+}
+
+type PayloadFn = Arc<dyn Fn(&dyn Any) -> Option<Value> + Send + Sync>;
+type ReplayFn = Arc<dyn Fn(&dyn Any) -> Option<ReplayableCommand> + Send + Sync>;
+
+/// 带审计的命令总线装饰器
+///
+/// - 命令执行失败时记录失败结果并返回原错误；
+/// - 命令成功但审计写入失败时返回审计错误，避免静默丢失审计记录。
+pub struct AuditedCommandBus<B, S> {
+    inner: B,
+    store: Arc<S>,
+    payloads: DashMap<TypeId, PayloadFn>,
+    replayables: DashMap<TypeId, ReplayFn>,
+}
+
+impl<B, S> AuditedCommandBus<B, S>
+where
+    B: CommandBus,
+    S: CommandAuditStore,
+{
+    pub fn new(inner: B, store: Arc<S>) -> Self {
+        Self {
+            inner,
+            store,
+            payloads: DashMap::new(),
+            replayables: DashMap::new(),
+        }
+    }
+
+    /// 为命令类型注册负载描述函数（在此完成脱敏或摘要）
+    pub fn with_payload<C, F>(self, describe: F) -> Self
+    where
+        C: 'static,
+        F: Fn(&C) -> Value + Send + Sync + 'static,
+    {
+        let f: PayloadFn = Arc::new(move |cmd| cmd.downcast_ref::<C>().map(&describe));
+        self.payloads.insert(TypeId::of::<C>(), f);
+        self
+    }
+
+    /// 登记可重放的命令类型：记录中保存以 `name`（`CommandRouter` 命令类型名）标识的完整负载
+    ///
+    /// 完整负载不经脱敏，仅应对不含敏感数据的命令启用，或确保审计存储的访问受控。
+    pub fn with_replayable<C>(self, name: impl Into<String>) -> Self
+    where
+        C: Serialize + 'static,
+    {
+        let name = name.into();
+        let f: ReplayFn = Arc::new(move |cmd| {
+            let payload = serde_json::to_value(cmd.downcast_ref::<C>()?).ok()?;
+            Some(ReplayableCommand {
+                name: name.clone(),
+                payload,
+            })
+        });
+        self.replayables.insert(TypeId::of::<C>(), f);
+        self
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    fn describe(
+        &self,
+        type_id: TypeId,
+        cmd: &dyn Any,
+    ) -> (Option<Value>, Option<ReplayableCommand>) {
+        let payload = self.payloads.get(&type_id).and_then(|f| f(cmd));
+        let replay = self.replayables.get(&type_id).and_then(|f| f(cmd));
+        (payload, replay)
+    }
+}
+
+impl<B, S> AuditedCommandBus<B, S>
+where
+    B: CommandBus,
+    S: CommandAuditStore,
+{
+    async fn audited<F>(
+        &self,
+        ctx: &AppContext,
+        command_type: &str,
+        (payload, replay): (Option<Value>, Option<ReplayableCommand>),
+        dispatch: F,
+    ) -> Result<(), AppError>
+    where
+        F: Future<Output = Result<(), AppError>>,
+    {
+        let started = Instant::now();
+        let (result, event_ids) = EVENT_IDS
+            .scope(Mutex::new(Vec::new()), async {
+                let result = dispatch.await;
+                let ids = EVENT_IDS.with(|ids| std::mem::take(&mut *ids.lock().unwrap()));
+                (result, ids)
+            })
+            .await;
+
+        let outcome = match &result {
+            Ok(()) => CommandOutcome::Succeeded,
+            Err(e) => CommandOutcome::Failed {
+                code: e.code().to_string(),
+                message: e.to_string(),
+            },
+        };
+
+        let event_context = &ctx.event_context;
+        let record = CommandAuditRecord {
+            command_type: command_type.to_string(),
+            payload,
+            actor_type: event_context.actor_type().map(ToString::to_string),
+            actor_id: event_context.actor_id().map(ToString::to_string),
+            correlation_id: event_context.correlation_id().map(ToString::to_string),
+            idempotency_key: ctx.idempotency_key.clone(),
+            outcome,
+            duration_ms: started.elapsed().as_millis(),
+            event_ids,
+            recorded_at: Utc::now(),
+            replay,
+        };
+
+        let audited = self.store.record(record).await;
+
+        result?;
+        audited
+    }
+}
+
+#[async_trait]
+impl<B, S> CommandBus for AuditedCommandBus<B, S>
+where
+    B: CommandBus,
+    S: CommandAuditStore,
+{
+    async fn dispatch<C>(&self, ctx: &AppContext, cmd: C) -> Result<(), AppError>
+    where
+        C: Send + 'static,
+    {
+        let described = self.describe(TypeId::of::<C>(), &cmd as &dyn Any);
+
+        self.audited(
+            ctx,
+            type_name::<C>(),
+            described,
+            self.inner.dispatch(ctx, cmd),
+        )
+        .await
+    }
+}
+
+#[async_trait]
+impl<B, S> EnvelopeCommandBus for AuditedCommandBus<B, S>
+where
+    B: EnvelopeCommandBus,
+    S: CommandAuditStore,
+{
+    async fn dispatch_envelope(
+        &self,
+        ctx: &AppContext,
+        envelope: CommandEnvelope,
+    ) -> Result<(), AppError> {
+        let described = self.describe(envelope.command_type_id(), envelope.command());
+
+        let command_type = envelope.command_type();
+        self.audited(
+            ctx,
+            command_type,
+            described,
+            self.inner.dispatch_envelope(ctx, envelope),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemoryCommandBus;
+    use crate::command_handler::CommandHandler;
+    use ddd_domain::domain_event::EventContext;
+
+    struct ResetPassword {
+        user_id: String,
+        new_password: String,
+    }
+
+    struct ResetPasswordHandler;
+
+    #[async_trait]
+    impl CommandHandler<ResetPassword> for ResetPasswordHandler {
+        async fn handle(&self, _ctx: &AppContext, cmd: ResetPassword) -> Result<(), AppError> {
+            if cmd.new_password.len() < 8 {
+                return Err(AppError::validation("password too short"));
+            }
+            record_event_ids([format!("evt-{}", cmd.user_id)]);
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn records_success_and_failure() {
+        let inner = InMemoryCommandBus::new();
+        inner
+            .register::<ResetPassword, _>(Arc::new(ResetPasswordHandler))
+            .unwrap();
+
+        let store = Arc::new(InMemoryCommandAuditStore::new());
+        let bus = AuditedCommandBus::new(inner, store.clone()).with_payload(
+            |cmd: &ResetPassword| serde_json::json!({"user_id": cmd.user_id, "new_password": "***"}),
+        );
+
+        let ctx = AppContext::new(
+            EventContext::builder()
+                .correlation_id("cor-1".into())
+                .actor_type("admin".into())
+                .actor_id("root".into())
+                .build(),
+        )
+        .with_idempotency_key("idem-1");
+
+        bus.dispatch(
+            &ctx,
+            ResetPassword {
+                user_id: "u-1".into(),
+                new_password: "s3cret-pass".into(),
+            },
+        )
+        .await
+        .unwrap();
+
+        let err = bus
+            .dispatch(
+                &ctx,
+                ResetPassword {
+                    user_id: "u-2".into(),
+                    new_password: "short".into(),
+                },
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), "VALIDATION_ERROR");
+
+        let records = store.records();
+        assert_eq!(records.len(), 2);
+
+        let ok = &records[0];
+        assert!(ok.command_type.ends_with("ResetPassword"));
+        assert_eq!(
+            ok.payload,
+            Some(serde_json::json!({"user_id": "u-1", "new_password": "***"}))
+        );
+        assert_eq!(ok.actor_id.as_deref(), Some("root"));
+        assert_eq!(ok.correlation_id.as_deref(), Some("cor-1"));
+        assert_eq!(ok.idempotency_key.as_deref(), Some("idem-1"));
+        assert_eq!(ok.outcome, CommandOutcome::Succeeded);
+        assert_eq!(ok.event_ids, vec!["evt-u-1".to_string()]);
+
+        let failed = &records[1];
+        assert!(failed.event_ids.is_empty());
+        assert!(matches!(
+            &failed.outcome,
+            CommandOutcome::Failed { code, .. } if code == "VALIDATION_ERROR"
+        ));
+    }
+}
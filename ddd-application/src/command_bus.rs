@@ -1,5 +1,45 @@
-use crate::{context::AppContext, error::AppError};
+use crate::{context::AppContext, error::AppError, unit_of_work::UnitOfWork};
 use async_trait::async_trait;
+use std::any::{Any, TypeId, type_name};
+
+/// 类型擦除的命令信封
+///
+/// 用于在一次调用中分发不同类型的命令（见 [`EnvelopeCommandBus::dispatch_all`]）。
+pub struct CommandEnvelope {
+    command_type_id: TypeId,
+    command_type: &'static str,
+    command: Box<dyn Any + Send>,
+}
+
+impl CommandEnvelope {
+    pub fn new<C>(cmd: C) -> Self
+    where
+        C: Send + 'static,
+    {
+        Self {
+            command_type_id: TypeId::of::<C>(),
+            command_type: type_name::<C>(),
+            command: Box::new(cmd),
+        }
+    }
+
+    pub fn command_type_id(&self) -> TypeId {
+        self.command_type_id
+    }
+
+    /// 命令类型名
+    pub fn command_type(&self) -> &'static str {
+        self.command_type
+    }
+
+    pub fn command(&self) -> &dyn Any {
+        &*self.command
+    }
+
+    pub fn into_command(self) -> Box<dyn Any + Send> {
+        self.command
+    }
+}
 
 /// 命令总线（Command Bus）
 ///
@@ -27,3 +67,45 @@ pub trait CommandBus: Send + Sync {
         Ok(())
     }
 }
+
+/// 分发类型擦除命令的命令总线
+///
+/// [`CommandBus`] 按泛型命令类型分发；按 `TypeId` 路由的实现（如 `InMemoryCommandBus`）
+/// 额外实现本 trait，以支持 [`CommandEnvelope`] 与 [`EnvelopeCommandBus::dispatch_all`]。
+/// 命令总线装饰器在内层支持时同样实现并转发。
+#[async_trait]
+pub trait EnvelopeCommandBus: CommandBus {
+    /// 分发类型擦除的命令；未注册的命令类型返回 `handler_not_found`
+    async fn dispatch_envelope(
+        &self,
+        ctx: &AppContext,
+        envelope: CommandEnvelope,
+    ) -> Result<(), AppError>;
+
+    /// 以全有或全无的方式分发多条命令
+    ///
+    /// - 命令在同一 [`UnitOfWork`] 范围内依次执行，事件经 `UnitOfWorkEventRepository` 暂存；
+    /// - 全部成功后统一提交，返回已提交的事件 ID；
+    /// - 任一命令失败时丢弃此前命令暂存的事件，并返回该命令的错误。
+    async fn dispatch_all(
+        &self,
+        ctx: &AppContext,
+        cmds: Vec<CommandEnvelope>,
+    ) -> Result<Vec<String>, AppError> {
+        let uow = UnitOfWork::new();
+        let dispatched = uow
+            .scope(async {
+                for cmd in cmds {
+                    self.dispatch_envelope(ctx, cmd).await?;
+                }
+                Ok::<_, AppError>(())
+            })
+            .await;
+
+        if let Err(e) = dispatched {
+            uow.rollback();
+            return Err(e);
+        }
+        Ok(uow.commit().await?)
+    }
+}
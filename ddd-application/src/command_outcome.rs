@@ -0,0 +1,251 @@
+//! 命令结果事件（Command Outcome Events）
+//!
+//! 工作流引擎与界面常需要得知某条命令何时执行完成，轮询读模型既慢又难以区分失败。
+//! `OutcomePublishingCommandBus` 作为 `CommandBus` 的装饰器，在每次分发后向事件总线发布标准化事件：
+//! - 成功：`command.completed`；失败：`command.failed`（附错误码与消息）；
+//! - 负载为 `CommandOutcomeEvent`（命令类型、耗时、幂等键、错误码），信封沿用调用方 `EventContext`
+//!   的关联/因果 ID 与执行主体，订阅方可按关联 ID 匹配自己发出的命令；
+//! - 发布为尽力而为：命令已执行，发布失败不改变分发结果。
+//!
+use crate::{
+    command_bus::{CommandBus, CommandEnvelope, EnvelopeCommandBus},
+    context::AppContext,
+    error::AppError,
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use ddd_domain::domain_event::next_event_id;
+use ddd_domain::error::ErrorCode;
+use ddd_domain::eventing::EventBus;
+use ddd_domain::persist::SerializedEvent;
+use serde::{Deserialize, Serialize};
+use std::any::type_name;
+use std::future::Future;
+use std::sync::Arc;
+use std::time::Instant;
+
+/// 命令执行成功的事件类型
+pub const COMMAND_COMPLETED: &str = "command.completed";
+/// 命令执行失败的事件类型
+pub const COMMAND_FAILED: &str = "command.failed";
+
+/// 结果事件的聚合类型（以关联 ID 或事件 ID 作为聚合 ID）
+const OUTCOME_AGGREGATE_TYPE: &str = "command";
+
+/// 结果事件负载
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CommandOutcomeEvent {
+    /// 命令类型名
+    pub command_type: String,
+    /// 执行耗时（毫秒）
+    pub duration_ms: u64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+    /// 失败时的错误码
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_code: Option<String>,
+    /// 失败时的错误消息
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub error_message: Option<String>,
+}
+
+impl CommandOutcomeEvent {
+    pub fn succeeded(&self) -> bool {
+        self.error_code.is_none()
+    }
+
+    /// 从总线事件解析结果负载；非结果事件返回 `None`
+    pub fn from_event(event: &SerializedEvent) -> Option<Self> {
+        if event.event_type() != COMMAND_COMPLETED && event.event_type() != COMMAND_FAILED {
+            return None;
+        }
+        serde_json::from_value(event.payload().clone()).ok()
+    }
+}
+
+/// 分发后发布命令结果事件的命令总线装饰器
+pub struct OutcomePublishingCommandBus<B> {
+    inner: B,
+    bus: Arc<dyn EventBus>,
+}
+
+impl<B> OutcomePublishingCommandBus<B>
+where
+    B: CommandBus,
+{
+    pub fn new(inner: B, bus: Arc<dyn EventBus>) -> Self {
+        Self { inner, bus }
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    async fn published<F>(
+        &self,
+        ctx: &AppContext,
+        command_type: &str,
+        dispatch: F,
+    ) -> Result<(), AppError>
+    where
+        F: Future<Output = Result<(), AppError>>,
+    {
+        let started = Instant::now();
+        let result = dispatch.await;
+        let duration_ms = u64::try_from(started.elapsed().as_millis()).unwrap_or(u64::MAX);
+
+        let outcome = CommandOutcomeEvent {
+            command_type: command_type.to_string(),
+            duration_ms,
+            idempotency_key: ctx.idempotency_key.clone(),
+            error_code: result.as_ref().err().map(|e| e.code().to_string()),
+            error_message: result.as_ref().err().map(ToString::to_string),
+        };
+        let _ = self.bus.publish(&outcome_event(ctx, &outcome)).await;
+        result
+    }
+}
+
+fn outcome_event(ctx: &AppContext, outcome: &CommandOutcomeEvent) -> SerializedEvent {
+    let event_context = &ctx.event_context;
+    let event_id = next_event_id();
+    let event_type = if outcome.succeeded() {
+        COMMAND_COMPLETED
+    } else {
+        COMMAND_FAILED
+    };
+
+    SerializedEvent::builder()
+        .aggregate_id(
+            event_context
+                .correlation_id()
+                .map_or_else(|| event_id.clone(), ToString::to_string),
+        )
+        .event_id(event_id)
+        .event_type(event_type.to_string())
+        .event_version(1)
+        .aggregate_type(OUTCOME_AGGREGATE_TYPE.to_string())
+        .aggregate_version(1)
+        .maybe_correlation_id(event_context.correlation_id().map(ToString::to_string))
+        .maybe_causation_id(event_context.causation_id().map(ToString::to_string))
+        .maybe_actor_type(event_context.actor_type().map(ToString::to_string))
+        .maybe_actor_id(event_context.actor_id().map(ToString::to_string))
+        .occurred_at(Utc::now())
+        .payload(serde_json::to_value(outcome).unwrap_or_default())
+        .context(serde_json::to_value(event_context).unwrap_or_default())
+        .build()
+}
+
+#[async_trait]
+impl<B> CommandBus for OutcomePublishingCommandBus<B>
+where
+    B: CommandBus,
+{
+    async fn dispatch<C>(&self, ctx: &AppContext, cmd: C) -> Result<(), AppError>
+    where
+        C: Send + 'static,
+    {
+        self.published(ctx, type_name::<C>(), self.inner.dispatch(ctx, cmd))
+            .await
+    }
+}
+
+#[async_trait]
+impl<B> EnvelopeCommandBus for OutcomePublishingCommandBus<B>
+where
+    B: EnvelopeCommandBus,
+{
+    async fn dispatch_envelope(
+        &self,
+        ctx: &AppContext,
+        envelope: CommandEnvelope,
+    ) -> Result<(), AppError> {
+        let command_type = envelope.command_type();
+        self.published(
+            ctx,
+            command_type,
+            self.inner.dispatch_envelope(ctx, envelope),
+        )
+        .await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemoryCommandBus;
+    use crate::command_handler::CommandHandler;
+    use ddd_domain::domain_event::EventContext;
+    use ddd_domain::eventing::InMemoryEventBus;
+    use futures_util::StreamExt;
+
+    struct ShipOrder {
+        order_id: String,
+    }
+
+    struct ShipOrderHandler;
+
+    #[async_trait]
+    impl CommandHandler<ShipOrder> for ShipOrderHandler {
+        async fn handle(&self, _ctx: &AppContext, cmd: ShipOrder) -> Result<(), AppError> {
+            if cmd.order_id.is_empty() {
+                return Err(AppError::validation("order id is required"));
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn publishes_completed_and_failed_events() {
+        let inner = InMemoryCommandBus::new();
+        inner
+            .register::<ShipOrder, _>(Arc::new(ShipOrderHandler))
+            .unwrap();
+        let events = Arc::new(InMemoryEventBus::new(16));
+        let mut stream = events.subscribe().await;
+        let bus = OutcomePublishingCommandBus::new(inner, events.clone());
+
+        let ctx = AppContext::new(
+            EventContext::builder()
+                .correlation_id("cor-1".into())
+                .actor_type("user".into())
+                .actor_id("u-1".into())
+                .build(),
+        )
+        .with_idempotency_key("idem-1");
+        bus.dispatch(
+            &ctx,
+            ShipOrder {
+                order_id: "o-1".into(),
+            },
+        )
+        .await
+        .unwrap();
+        let err = bus
+            .dispatch(
+                &ctx,
+                ShipOrder {
+                    order_id: String::new(),
+                },
+            )
+            .await
+            .unwrap_err();
+        assert_eq!(err.code(), "VALIDATION_ERROR");
+
+        let completed = stream.next().await.unwrap().unwrap();
+        assert_eq!(completed.event_type(), COMMAND_COMPLETED);
+        assert_eq!(completed.correlation_id(), Some("cor-1"));
+        assert_eq!(completed.aggregate_id(), "cor-1");
+        assert_eq!(completed.actor_id(), Some("u-1"));
+        let outcome = CommandOutcomeEvent::from_event(&completed).unwrap();
+        assert!(outcome.succeeded());
+        assert!(outcome.command_type.ends_with("ShipOrder"));
+        assert_eq!(outcome.idempotency_key.as_deref(), Some("idem-1"));
+
+        let failed = stream.next().await.unwrap().unwrap();
+        assert_eq!(failed.event_type(), COMMAND_FAILED);
+        let outcome = CommandOutcomeEvent::from_event(&failed).unwrap();
+        assert_eq!(outcome.error_code.as_deref(), Some("VALIDATION_ERROR"));
+        assert!(!outcome.succeeded());
+    }
+}
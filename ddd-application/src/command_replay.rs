@@ -0,0 +1,138 @@
+//! 命令日志重放（Command Replay）
+//!
+//! 以命令审计中保存的完整负载（`CommandAuditRecord::replay`，经 `AuditedCommandBus::with_replayable` 登记）
+//! 为输入，在重建的沙箱系统（全新的仓储与总线）中按原顺序重新分发命令，逐条比较执行结果：
+//! - 命令经 `CommandRouter` 按类型名解码，沿用原执行主体、关联 ID 与幂等键，
+//!   业务语境扩展字段标记 `replay: true`，处理器可据此跳过外部副作用；
+//! - 结果以状态与错误码比较（错误消息不参与比较），不一致的命令列入 `ReplayReport::divergences`；
+//! - 未保存完整负载的记录计为跳过。
+//!
+//! 典型用途：以生产中的真实命令验证 `execute` 逻辑重构前后行为一致。
+//!
+use crate::{
+    command_audit::{CommandAuditRecord, CommandAuditStore, CommandOutcome},
+    command_bus::EnvelopeCommandBus,
+    command_router::CommandRouter,
+    context::AppContext,
+    error::AppError,
+};
+use ddd_domain::domain_event::EventContext;
+use ddd_domain::error::ErrorCode;
+use std::sync::Arc;
+
+/// 重放结果与原结果不一致的命令
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReplayDivergence {
+    /// 在输入记录中的下标
+    pub index: usize,
+    /// 命令路由名
+    pub name: String,
+    pub expected: CommandOutcome,
+    pub actual: CommandOutcome,
+}
+
+/// 重放报告
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ReplayReport {
+    /// 已重放的命令数
+    pub replayed: usize,
+    /// 未保存完整负载而跳过的记录数
+    pub skipped: usize,
+    pub divergences: Vec<ReplayDivergence>,
+}
+
+impl ReplayReport {
+    /// 全部重放结果与原结果一致
+    pub fn is_consistent(&self) -> bool {
+        self.divergences.is_empty()
+    }
+}
+
+/// 命令重放器
+pub struct CommandReplayer {
+    router: Arc<CommandRouter>,
+}
+
+impl CommandReplayer {
+    pub fn new(router: Arc<CommandRouter>) -> Self {
+        Self { router }
+    }
+
+    /// 按顺序将 `records` 重放到 `bus`（应为重建的沙箱系统，而非生产总线）
+    pub async fn replay<B>(&self, bus: &B, records: &[CommandAuditRecord]) -> ReplayReport
+    where
+        B: EnvelopeCommandBus + ?Sized,
+    {
+        let mut report = ReplayReport::default();
+
+        for (index, record) in records.iter().enumerate() {
+            let Some(command) = &record.replay else {
+                report.skipped += 1;
+                continue;
+            };
+
+            let result = self
+                .router
+                .dispatch(
+                    bus,
+                    &replay_context(record),
+                    &command.name,
+                    command.payload.clone(),
+                )
+                .await;
+            report.replayed += 1;
+
+            let actual = outcome_of(&result);
+            if !same_outcome(&record.outcome, &actual) {
+                report.divergences.push(ReplayDivergence {
+                    index,
+                    name: command.name.clone(),
+                    expected: record.outcome.clone(),
+                    actual,
+                });
+            }
+        }
+        report
+    }
+
+    /// 读取审计存储中的全部记录并重放
+    pub async fn replay_store<B, S>(&self, bus: &B, store: &S) -> Result<ReplayReport, AppError>
+    where
+        B: EnvelopeCommandBus + ?Sized,
+        S: CommandAuditStore + ?Sized,
+    {
+        let records = store.list().await?;
+        Ok(self.replay(bus, &records).await)
+    }
+}
+
+fn replay_context(record: &CommandAuditRecord) -> AppContext {
+    let mut ctx = AppContext::new(
+        EventContext::builder()
+            .maybe_correlation_id(record.correlation_id.clone())
+            .maybe_actor_type(record.actor_type.clone())
+            .maybe_actor_id(record.actor_id.clone())
+            .maybe_extensions(Some(serde_json::json!({ "replay": true })))
+            .build(),
+    );
+    ctx.idempotency_key = record.idempotency_key.clone();
+    ctx
+}
+
+fn outcome_of(result: &Result<(), AppError>) -> CommandOutcome {
+    match result {
+        Ok(()) => CommandOutcome::Succeeded,
+        Err(e) => CommandOutcome::Failed {
+            code: e.code().to_string(),
+            message: e.to_string(),
+        },
+    }
+}
+
+fn same_outcome(expected: &CommandOutcome, actual: &CommandOutcome) -> bool {
+    match (expected, actual) {
+        (CommandOutcome::Succeeded, CommandOutcome::Succeeded) => true,
+        (CommandOutcome::Failed { code: a, .. }, CommandOutcome::Failed { code: b, .. }) => a == b,
+        _ => false,
+    }
+}
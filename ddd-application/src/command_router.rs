@@ -0,0 +1,186 @@
+//! 命令路由（Command Router）
+//!
+//! 将「命令类型名 + JSON 负载」（如通用管理端点、消息队列消息）映射为已注册的强类型命令：
+//! 按名称查找路由，经 serde 反序列化并执行可选的校验，再封装为 `CommandEnvelope`
+//! 通过命令总线分发，通用工具无需为每个命令手写 match 分支。
+//!
+use crate::{
+    command_bus::{CommandEnvelope, EnvelopeCommandBus},
+    context::AppContext,
+    error::AppError,
+};
+use dashmap::DashMap;
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+use std::any::type_name;
+use std::sync::Arc;
+
+type DecodeFn = Arc<dyn Fn(Value) -> Result<CommandEnvelope, AppError> + Send + Sync>;
+
+/// 按命令类型名路由 JSON 负载到强类型命令
+#[derive(Default)]
+pub struct CommandRouter {
+    routes: DashMap<String, (&'static str, DecodeFn)>,
+}
+
+impl CommandRouter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 注册命令类型名 `name` 对应的命令类型 `C`
+    pub fn register<C>(&self, name: impl Into<String>) -> Result<(), AppError>
+    where
+        C: DeserializeOwned + Send + 'static,
+    {
+        self.register_validated::<C, _>(name, |_| Ok(()))
+    }
+
+    /// 注册命令类型，并在反序列化后执行 `validate`
+    pub fn register_validated<C, F>(
+        &self,
+        name: impl Into<String>,
+        validate: F,
+    ) -> Result<(), AppError>
+    where
+        C: DeserializeOwned + Send + 'static,
+        F: Fn(&C) -> Result<(), AppError> + Send + Sync + 'static,
+    {
+        let name = name.into();
+        let decode: DecodeFn = {
+            let name = name.clone();
+            Arc::new(move |payload| {
+                let cmd: C = serde_json::from_value(payload).map_err(|e| {
+                    AppError::validation(format!("invalid payload for command {name}: {e}"))
+                })?;
+                validate(&cmd)?;
+                Ok(CommandEnvelope::new(cmd))
+            })
+        };
+
+        match self.routes.entry(name) {
+            dashmap::mapref::entry::Entry::Occupied(e) => {
+                Err(AppError::handler_already_registered(e.key()))
+            }
+            dashmap::mapref::entry::Entry::Vacant(e) => {
+                e.insert((type_name::<C>(), decode));
+                Ok(())
+            }
+        }
+    }
+
+    /// 已注册的命令类型名（按字典序）
+    pub fn command_names(&self) -> Vec<String> {
+        let mut names: Vec<String> = self.routes.iter().map(|e| e.key().clone()).collect();
+        names.sort();
+        names
+    }
+
+    /// 命令类型名对应的 Rust 类型全名
+    pub fn command_type(&self, name: &str) -> Option<&'static str> {
+        self.routes.get(name).map(|e| e.value().0)
+    }
+
+    /// 将 JSON 负载解码为类型擦除的命令
+    pub fn decode(&self, name: &str, payload: Value) -> Result<CommandEnvelope, AppError> {
+        let Some(decode) = self.routes.get(name).map(|e| e.value().1.clone()) else {
+            return Err(AppError::validation(format!(
+                "unknown command type: {name}"
+            )));
+        };
+        decode(payload)
+    }
+
+    /// 解码并通过命令总线分发
+    pub async fn dispatch<B>(
+        &self,
+        bus: &B,
+        ctx: &AppContext,
+        name: &str,
+        payload: Value,
+    ) -> Result<(), AppError>
+    where
+        B: EnvelopeCommandBus + ?Sized,
+    {
+        let envelope = self.decode(name, payload)?;
+        bus.dispatch_envelope(ctx, envelope).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemoryCommandBus;
+    use crate::command_handler::CommandHandler;
+    use async_trait::async_trait;
+    use ddd_domain::error::ErrorCode;
+    use serde::Deserialize;
+    use std::sync::Mutex;
+
+    #[derive(Debug, Deserialize)]
+    struct Deposit {
+        account_id: String,
+        amount: i64,
+    }
+
+    #[derive(Default)]
+    struct DepositHandler {
+        seen: Mutex<Vec<(String, i64)>>,
+    }
+
+    #[async_trait]
+    impl CommandHandler<Deposit> for DepositHandler {
+        async fn handle(&self, _ctx: &AppContext, cmd: Deposit) -> Result<(), AppError> {
+            self.seen.lock().unwrap().push((cmd.account_id, cmd.amount));
+            Ok(())
+        }
+    }
+
+    #[tokio::test]
+    async fn routes_json_payloads_to_typed_commands() {
+        let bus = InMemoryCommandBus::new();
+        let handler = Arc::new(DepositHandler::default());
+        bus.register::<Deposit, _>(handler.clone()).unwrap();
+
+        let router = CommandRouter::new();
+        router
+            .register_validated::<Deposit, _>("account.deposit", |cmd| {
+                if cmd.amount <= 0 {
+                    return Err(AppError::validation("amount must be positive"));
+                }
+                Ok(())
+            })
+            .unwrap();
+        assert!(router.register::<Deposit>("account.deposit").is_err());
+        assert_eq!(router.command_names(), vec!["account.deposit"]);
+
+        let ctx = AppContext::default();
+        router
+            .dispatch(
+                &bus,
+                &ctx,
+                "account.deposit",
+                serde_json::json!({"account_id": "a-1", "amount": 10}),
+            )
+            .await
+            .unwrap();
+        assert_eq!(*handler.seen.lock().unwrap(), vec![("a-1".to_string(), 10)]);
+
+        let invalid = [
+            ("account.deposit", serde_json::json!({"account_id": "a-1"})),
+            (
+                "account.deposit",
+                serde_json::json!({"account_id": "a-1", "amount": -1}),
+            ),
+            ("account.withdraw", serde_json::json!({})),
+        ];
+        for (name, payload) in invalid {
+            let err = router
+                .dispatch(&bus, &ctx, name, payload)
+                .await
+                .unwrap_err();
+            assert_eq!(err.code(), "VALIDATION_ERROR");
+        }
+        assert_eq!(handler.seen.lock().unwrap().len(), 1);
+    }
+}
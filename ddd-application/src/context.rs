@@ -1,11 +1,37 @@
-use ddd_domain::{domain_event::EventContext, persist::SerializedEvent};
+use crate::{
+    error::AppError,
+    external_ref::{ExternalRefStore, ExternalRefs},
+    read_requirement::{ReadModelGate, ReadRequirement},
+    sequence::{SequenceGenerator, SequenceKey},
+};
+use chrono::{DateTime, Utc};
+use ddd_domain::{
+    aggregate::Aggregate, domain_event::EventContext, persist::SerializedEvent,
+    specification::SpecCache,
+};
+use std::fmt;
+use std::future::{Future, pending};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+use tokio_util::sync::CancellationToken;
+
+tokio::task_local! {
+    static SPEC_CACHE: Arc<SpecCache>;
+}
 
 /// 应用层上下文（Application Context）
 ///
 /// 承载一次应用层调用（命令/查询）所需的横切信息，例如：
 /// - 业务语境（`EventContext`）：关联追踪 `correlation_id`、因果链 `causation_id`、
 ///   执行者类型/ID 等；
-/// - 幂等键（`idempotency_key`）：用于在基础设施层实现请求幂等（如 API 层重复提交保护）。
+/// - 幂等键（`idempotency_key`）：用于在基础设施层实现请求幂等（如 API 层重复提交保护）；
+/// - 业务序号生成器（`sequences`）：命令处理器通过 `next_sequence` 获取订单号等可读编号；
+/// - 外部引用映射（`external_refs`）：防腐层与命令处理器通过 `external_refs::<A>()` 在外部系统标识与聚合 ID 间互查；
+/// - 读模型新鲜度校验（`read_models`）：命令处理器依据投影校验前通过 `ensure_fresh` 声明新鲜度要求；
+/// - 截止时间与取消（`deadline`/`cancellation`）：命令/查询总线在到期或取消时中止处理器，
+///   返回 `DEADLINE_EXCEEDED`/`CANCELLED`；长耗时的加载可通过 `remaining`/`check_deadline` 提前放弃；
+/// - 异步规约缓存（`spec_cache`）：每次经总线执行的命令/查询拥有独立的 `SpecCache`，
+///   处理器内重复的规约检查（如存在性、唯一性）只查询一次仓储。
 ///
 /// 典型用法：
 /// ```rust
@@ -20,26 +46,250 @@ use ddd_domain::{domain_event::EventContext, persist::SerializedEvent};
 ///         .maybe_actor_id(Some("u-1".into()))
 ///         .build(),
 ///     idempotency_key: Some("idem-xyz".into()),
+///     ..Default::default()
 /// };
 /// ```
-#[derive(Clone, Debug, Default)]
+///
+/// 除业务语境与幂等键外，其余服务与约束均收拢在 `extensions`（`AppExtensions`，字段私有）中，
+/// 经 `with_*` 方法设置、经同名访问器读取；新增扩展不再改变 `AppContext` 的字段。
+#[derive(Clone, Default)]
 pub struct AppContext {
     /// 业务语境（链路追踪、审计主体、操作因果）
     pub event_context: EventContext,
     /// 幂等键（可选）：为空则由上层或基础设施决定是否参与幂等
     pub idempotency_key: Option<String>,
+    /// 可选的服务与约束，默认均未配置
+    pub extensions: AppExtensions,
 }
 
-impl From<&SerializedEvent> for AppContext {
-    fn from(event: &SerializedEvent) -> Self {
+/// `AppContext` 的可选服务与约束，经 `AppContext::with_*` 设置
+#[derive(Clone, Default)]
+pub struct AppExtensions {
+    /// 业务序号生成器
+    sequences: Option<Arc<dyn SequenceGenerator>>,
+    /// 外部引用映射
+    external_refs: Option<Arc<dyn ExternalRefStore>>,
+    /// 读模型新鲜度校验
+    read_models: Option<Arc<ReadModelGate>>,
+    /// 截止时间，通常来自服务端请求超时
+    deadline: Option<Instant>,
+    /// 取消令牌，如客户端断开连接时取消
+    cancellation: Option<CancellationToken>,
+}
+
+impl AppContext {
+    pub fn new(event_context: EventContext) -> Self {
         Self {
-            event_context: EventContext::builder()
-                .maybe_correlation_id(event.correlation_id().map(ToString::to_string))
-                .causation_id(event.event_id().to_string())
-                .maybe_actor_type(event.actor_type().map(ToString::to_string))
-                .maybe_actor_id(event.actor_id().map(ToString::to_string))
-                .build(),
-            idempotency_key: None,
+            event_context,
+            ..Default::default()
         }
     }
+
+    pub fn with_idempotency_key(mut self, idempotency_key: impl Into<String>) -> Self {
+        self.idempotency_key = Some(idempotency_key.into());
+        self
+    }
+
+    pub fn with_sequences(mut self, sequences: Arc<dyn SequenceGenerator>) -> Self {
+        self.extensions.sequences = Some(sequences);
+        self
+    }
+
+    pub fn sequences(&self) -> Option<&Arc<dyn SequenceGenerator>> {
+        self.extensions.sequences.as_ref()
+    }
+
+    /// 获取下一个业务序号；未配置生成器时返回内部错误
+    pub async fn next_sequence(&self, key: &SequenceKey) -> Result<u64, AppError> {
+        self.sequence_generator()?.next(key).await
+    }
+
+    /// 归还未使用的业务序号（命令失败时调用）
+    pub async fn release_sequence(&self, key: &SequenceKey, value: u64) -> Result<(), AppError> {
+        self.sequence_generator()?.release(key, value).await
+    }
+
+    pub fn with_external_refs(mut self, external_refs: Arc<dyn ExternalRefStore>) -> Self {
+        self.extensions.external_refs = Some(external_refs);
+        self
+    }
+
+    pub fn external_ref_store(&self) -> Option<&Arc<dyn ExternalRefStore>> {
+        self.extensions.external_refs.as_ref()
+    }
+
+    /// 聚合 `A` 的外部引用映射；未配置映射存储时返回内部错误
+    pub fn external_refs<A: Aggregate>(&self) -> Result<ExternalRefs<A>, AppError> {
+        self.extensions
+            .external_refs
+            .clone()
+            .map(ExternalRefs::new)
+            .ok_or_else(|| AppError::internal("external ref store is not configured"))
+    }
+
+    pub fn with_read_models(mut self, read_models: Arc<ReadModelGate>) -> Self {
+        self.extensions.read_models = Some(read_models);
+        self
+    }
+
+    pub fn read_models(&self) -> Option<&Arc<ReadModelGate>> {
+        self.extensions.read_models.as_ref()
+    }
+
+    /// 校验投影满足新鲜度要求；未配置校验器时返回内部错误
+    pub async fn ensure_fresh(
+        &self,
+        projection: &str,
+        requirement: &ReadRequirement,
+    ) -> Result<(), AppError> {
+        self.extensions
+            .read_models
+            .as_deref()
+            .ok_or_else(|| AppError::internal("read model gate is not configured"))?
+            .ensure(projection, requirement)
+            .await
+    }
+
+    /// 执行主体是否具有指定角色
+    ///
+    /// 角色来源：执行主体类型（`actor_type`），以及业务语境扩展字段中的 `roles` 数组。
+    pub fn has_role(&self, role: &str) -> bool {
+        let event_context = &self.event_context;
+        event_context.actor_type() == Some(role)
+            || event_context
+                .extensions()
+                .and_then(|ext| ext.get("roles"))
+                .and_then(|roles| roles.as_array())
+                .is_some_and(|roles| roles.iter().any(|r| r.as_str() == Some(role)))
+    }
+
+    /// 记录命令接收时间（已记录时保留原值），随事件元数据传递用于端到端延迟统计
+    pub fn with_command_received_at(mut self, at: DateTime<Utc>) -> Self {
+        self.event_context.stamp_command_received(at);
+        self
+    }
+
+    pub fn with_deadline(mut self, deadline: Instant) -> Self {
+        self.extensions.deadline = Some(deadline);
+        self
+    }
+
+    /// 以当前时间加 `timeout` 作为截止时间
+    pub fn with_timeout(self, timeout: Duration) -> Self {
+        self.with_deadline(Instant::now() + timeout)
+    }
+
+    pub fn with_cancellation(mut self, token: CancellationToken) -> Self {
+        self.extensions.cancellation = Some(token);
+        self
+    }
+
+    pub fn deadline(&self) -> Option<Instant> {
+        self.extensions.deadline
+    }
+
+    pub fn cancellation(&self) -> Option<&CancellationToken> {
+        self.extensions.cancellation.as_ref()
+    }
+
+    /// 距截止时间的剩余时长；未设置截止时间时为 `None`，已到期时为零
+    pub fn remaining(&self) -> Option<Duration> {
+        self.extensions
+            .deadline
+            .map(|d| d.saturating_duration_since(Instant::now()))
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.extensions
+            .cancellation
+            .as_ref()
+            .is_some_and(CancellationToken::is_cancelled)
+    }
+
+    /// 已取消或已过截止时间时返回错误，供长耗时操作在分段之间检查
+    pub fn check_deadline(&self) -> Result<(), AppError> {
+        if self.is_cancelled() {
+            return Err(AppError::cancelled("operation"));
+        }
+        if self.remaining() == Some(Duration::ZERO) {
+            return Err(AppError::deadline_exceeded("operation"));
+        }
+        Ok(())
+    }
+
+    /// 当前命令/查询执行范围内的规约缓存；不在总线执行范围内时返回新的空缓存（不跨调用共享）
+    pub fn spec_cache(&self) -> Arc<SpecCache> {
+        SPEC_CACHE.try_with(Arc::clone).unwrap_or_default()
+    }
+
+    /// 在截止时间与取消令牌约束下执行 `fut`，到期或取消时丢弃（中止）该 future
+    ///
+    /// `fut` 在新的规约缓存范围内执行（见 `spec_cache`）。
+    pub async fn run<T, F>(&self, operation: &str, fut: F) -> Result<T, AppError>
+    where
+        F: Future<Output = Result<T, AppError>>,
+    {
+        if self.is_cancelled() {
+            return Err(AppError::cancelled(operation));
+        }
+        if self.remaining() == Some(Duration::ZERO) {
+            return Err(AppError::deadline_exceeded(operation));
+        }
+
+        let deadline = async {
+            match self.extensions.deadline {
+                Some(d) => tokio::time::sleep_until(d.into()).await,
+                None => pending().await,
+            }
+        };
+        let cancelled = async {
+            match &self.extensions.cancellation {
+                Some(token) => token.cancelled().await,
+                None => pending().await,
+            }
+        };
+
+        tokio::select! {
+            biased;
+            out = SPEC_CACHE.scope(Arc::new(SpecCache::new()), fut) => out,
+            _ = cancelled => Err(AppError::cancelled(operation)),
+            _ = deadline => Err(AppError::deadline_exceeded(operation)),
+        }
+    }
+
+    fn sequence_generator(&self) -> Result<&dyn SequenceGenerator, AppError> {
+        self.extensions
+            .sequences
+            .as_deref()
+            .ok_or_else(|| AppError::internal("sequence generator is not configured"))
+    }
+}
+
+impl fmt::Debug for AppContext {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AppContext")
+            .field("event_context", &self.event_context)
+            .field("idempotency_key", &self.idempotency_key)
+            .field("extensions", &self.extensions)
+            .finish()
+    }
+}
+
+impl fmt::Debug for AppExtensions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("AppExtensions")
+            .field("sequences", &self.sequences.is_some())
+            .field("external_refs", &self.external_refs.is_some())
+            .field("read_models", &self.read_models.is_some())
+            .field("deadline", &self.deadline)
+            .field("cancellation", &self.cancellation.is_some())
+            .finish()
+    }
+}
+
+/// 由事件触发后续命令（策略/Saga）时的上下文，因果链深度随之加 1
+impl From<&SerializedEvent> for AppContext {
+    fn from(event: &SerializedEvent) -> Self {
+        Self::new(EventContext::caused_by(event))
+    }
 }
@@ -0,0 +1,147 @@
+//! 聚合 CRUD 应用服务模板
+//!
+//! 简单聚合的应用服务形状大多相同：创建/更新等写操作经命令总线分发，
+//! 详情/列表读取投影维护的读模型。`CrudService` 以类型化 DTO 收敛这部分样板：
+//! - 输入 DTO 实现 `IntoCommand<A::Id>`，结合聚合 ID 转换为具体命令；
+//! - `create` 由 ID 生成器分配新聚合 ID 后分发命令，返回该 ID；
+//! - `update` 将 DTO 转换为针对已有聚合的命令并分发（更新与其他业务命令共用此入口）；
+//! - `get`/`get_required`/`list` 委托 `CrudReadModel`，`get_required` 不存在时返回 `AGGREGATE_NOT_FOUND`。
+//!
+use crate::{command_bus::CommandBus, context::AppContext, error::AppError};
+use async_trait::async_trait;
+use ddd_domain::aggregate::Aggregate;
+use std::marker::PhantomData;
+use std::sync::Arc;
+
+/// 输入 DTO 到命令的转换
+pub trait IntoCommand<Id>: Send {
+    type Command: Send + 'static;
+
+    /// 以目标聚合 ID 构造命令
+    fn into_command(self, id: &Id) -> Self::Command;
+}
+
+/// 列表分页参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ListParams {
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl Default for ListParams {
+    fn default() -> Self {
+        Self {
+            offset: 0,
+            limit: 50,
+        }
+    }
+}
+
+impl ListParams {
+    pub fn new(offset: usize, limit: usize) -> Self {
+        Self { offset, limit }
+    }
+}
+
+/// CRUD 服务使用的读模型（通常由投影维护）
+#[async_trait]
+pub trait CrudReadModel<Id>: Send + Sync {
+    type Dto: Send;
+
+    async fn get(&self, ctx: &AppContext, id: &Id) -> Result<Option<Self::Dto>, AppError>;
+
+    async fn list(&self, ctx: &AppContext, params: ListParams) -> Result<Vec<Self::Dto>, AppError>;
+}
+
+type IdGenerator<Id> = Arc<dyn Fn() -> Id + Send + Sync>;
+
+/// 聚合的标准应用服务：写操作经命令总线，读操作经读模型
+pub struct CrudService<A, B, R>
+where
+    A: Aggregate,
+{
+    bus: Arc<B>,
+    read_model: Arc<R>,
+    new_id: IdGenerator<A::Id>,
+    _aggregate: PhantomData<fn() -> A>,
+}
+
+impl<A, B, R> CrudService<A, B, R>
+where
+    A: Aggregate,
+    B: CommandBus,
+    R: CrudReadModel<A::Id>,
+{
+    /// `new_id` 为 `create` 分配新聚合 ID
+    pub fn new<F>(bus: Arc<B>, read_model: Arc<R>, new_id: F) -> Self
+    where
+        F: Fn() -> A::Id + Send + Sync + 'static,
+    {
+        Self {
+            bus,
+            read_model,
+            new_id: Arc::new(new_id),
+            _aggregate: PhantomData,
+        }
+    }
+
+    pub fn bus(&self) -> &Arc<B> {
+        &self.bus
+    }
+
+    pub fn read_model(&self) -> &Arc<R> {
+        &self.read_model
+    }
+
+    /// 分配新 ID 并分发创建命令，返回新聚合 ID
+    pub async fn create<I>(&self, ctx: &AppContext, input: I) -> Result<A::Id, AppError>
+    where
+        I: IntoCommand<A::Id>,
+    {
+        let id = (self.new_id)();
+        self.bus.dispatch(ctx, input.into_command(&id)).await?;
+        Ok(id)
+    }
+
+    /// 分发针对已有聚合的命令（更新或其他业务操作）
+    pub async fn update<I>(&self, ctx: &AppContext, id: &A::Id, input: I) -> Result<(), AppError>
+    where
+        I: IntoCommand<A::Id>,
+    {
+        self.bus.dispatch(ctx, input.into_command(id)).await
+    }
+
+    pub async fn get(&self, ctx: &AppContext, id: &A::Id) -> Result<Option<R::Dto>, AppError> {
+        self.read_model.get(ctx, id).await
+    }
+
+    /// 读取详情，不存在时返回 `AGGREGATE_NOT_FOUND`
+    pub async fn get_required(&self, ctx: &AppContext, id: &A::Id) -> Result<R::Dto, AppError> {
+        self.read_model
+            .get(ctx, id)
+            .await?
+            .ok_or_else(|| AppError::aggregate_not_found(A::TYPE, &id.to_string()))
+    }
+
+    pub async fn list(
+        &self,
+        ctx: &AppContext,
+        params: ListParams,
+    ) -> Result<Vec<R::Dto>, AppError> {
+        self.read_model.list(ctx, params).await
+    }
+}
+
+impl<A, B, R> Clone for CrudService<A, B, R>
+where
+    A: Aggregate,
+{
+    fn clone(&self) -> Self {
+        Self {
+            bus: self.bus.clone(),
+            read_model: self.read_model.clone(),
+            new_id: self.new_id.clone(),
+            _aggregate: PhantomData,
+        }
+    }
+}
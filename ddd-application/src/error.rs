@@ -82,8 +82,10 @@
 //! ```
 
 use ddd_domain::error::{DomainError, ErrorCode, ErrorKind};
+use serde::Serialize;
 use std::error::Error as StdError;
 use std::fmt;
+use std::time::Duration;
 
 /// 应用层统一错误类型
 ///
@@ -251,6 +253,160 @@ impl AppError {
         )
     }
 
+    /// 创建「超过截止时间」错误
+    ///
+    /// 命令/查询总线在 `AppContext` 的截止时间到达时中止处理器并返回该错误。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use ddd_application::error::AppError;
+    /// use ddd_domain::error::ErrorCode;
+    ///
+    /// let err = AppError::deadline_exceeded("CreateUserHandler");
+    /// assert_eq!(err.code(), "DEADLINE_EXCEEDED");
+    /// ```
+    #[must_use]
+    pub fn deadline_exceeded(operation: &str) -> Self {
+        Self::new(
+            ErrorKind::Internal,
+            "DEADLINE_EXCEEDED",
+            format!("deadline exceeded: {operation}"),
+        )
+    }
+
+    /// 创建「已取消」错误
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use ddd_application::error::AppError;
+    /// use ddd_domain::error::ErrorCode;
+    ///
+    /// let err = AppError::cancelled("CreateUserHandler");
+    /// assert_eq!(err.code(), "CANCELLED");
+    /// ```
+    #[must_use]
+    pub fn cancelled(operation: &str) -> Self {
+        Self::new(
+            ErrorKind::Internal,
+            "CANCELLED",
+            format!("cancelled: {operation}"),
+        )
+    }
+
+    /// 创建「远程调用超时」错误（HTTP 504，可重试）
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use ddd_application::error::AppError;
+    /// use ddd_domain::error::ErrorCode;
+    /// use std::time::Duration;
+    ///
+    /// let err = AppError::remote_timeout("orders.summary", Duration::from_secs(2));
+    /// assert_eq!(err.code(), "REMOTE_QUERY_TIMEOUT");
+    /// assert_eq!(err.http_status(), 504);
+    /// assert!(err.is_retryable());
+    /// ```
+    #[must_use]
+    pub fn remote_timeout(operation: &str, timeout: Duration) -> Self {
+        Self::new(
+            ErrorKind::Custom {
+                http_status: 504,
+                code: "REMOTE_QUERY_TIMEOUT",
+                retryable: true,
+            },
+            "REMOTE_QUERY_TIMEOUT",
+            format!("remote call timed out after {timeout:?}: {operation}"),
+        )
+    }
+
+    /// 创建「请求过于频繁」错误（HTTP 429，可重试）
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use ddd_application::error::AppError;
+    /// use ddd_domain::error::ErrorCode;
+    /// use std::time::Duration;
+    ///
+    /// let err = AppError::rate_limited("u-1", Duration::from_millis(250));
+    /// assert_eq!(err.code(), "RATE_LIMITED");
+    /// assert_eq!(err.http_status(), 429);
+    /// assert!(err.is_retryable());
+    /// ```
+    #[must_use]
+    pub fn rate_limited(key: &str, retry_after: Duration) -> Self {
+        Self::wrap(
+            ErrorKind::Custom {
+                http_status: 429,
+                code: "RATE_LIMITED",
+                retryable: true,
+            },
+            "RATE_LIMITED",
+            RateLimitExceeded {
+                key: key.to_string(),
+                retry_after,
+            },
+        )
+    }
+
+    /// 创建「外部引用已被占用」错误（HTTP 409，不可重试）
+    ///
+    /// 外部引用已映射到其他聚合，或聚合在该外部系统中已有其他外部 ID。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use ddd_application::error::AppError;
+    /// use ddd_domain::error::ErrorCode;
+    ///
+    /// let err = AppError::external_ref_taken("stripe:cus_1", "c-1");
+    /// assert_eq!(err.code(), "EXTERNAL_REF_TAKEN");
+    /// assert_eq!(err.http_status(), 409);
+    /// assert!(!err.is_retryable());
+    /// ```
+    #[must_use]
+    pub fn external_ref_taken(reference: &str, aggregate_id: &str) -> Self {
+        Self::new(
+            ErrorKind::Custom {
+                http_status: 409,
+                code: "EXTERNAL_REF_TAKEN",
+                retryable: false,
+            },
+            "EXTERNAL_REF_TAKEN",
+            format!("external ref {reference} is already linked to {aggregate_id}"),
+        )
+    }
+
+    /// 创建「字段校验错误」，携带逐字段的违规明细
+    ///
+    /// 错误码与 [`AppError::validation`] 相同，明细可通过 [`AppError::field_violations`] 取回。
+    ///
+    /// # 示例
+    ///
+    /// ```rust
+    /// use ddd_application::error::{AppError, FieldViolation};
+    /// use ddd_domain::error::ErrorCode;
+    ///
+    /// let err = AppError::invalid_fields(vec![
+    ///     FieldViolation::new("email", "must contain @"),
+    ///     FieldViolation::new("age", "must be positive"),
+    /// ]);
+    /// assert_eq!(err.code(), "VALIDATION_ERROR");
+    /// assert_eq!(err.field_violations().len(), 2);
+    /// assert_eq!(err.to_string(), "validation failed: email: must contain @; age: must be positive");
+    /// ```
+    #[must_use]
+    pub fn invalid_fields(violations: Vec<FieldViolation>) -> Self {
+        Self::wrap(
+            ErrorKind::InvalidValue,
+            "VALIDATION_ERROR",
+            ValidationErrors(violations),
+        )
+    }
+
     /// 创建「内部错误」
     ///
     /// # 示例
@@ -270,6 +426,20 @@ impl AppError {
 
     // ==================== 查询方法 ====================
 
+    /// 逐字段的校验违规明细（仅 [`AppError::invalid_fields`] 构造的错误非空）
+    #[must_use]
+    pub fn field_violations(&self) -> &[FieldViolation] {
+        self.downcast_ref::<ValidationErrors>()
+            .map_or(&[], |errors| errors.0.as_slice())
+    }
+
+    /// 建议的重试等待时长（如 [`AppError::rate_limited`]）
+    #[must_use]
+    pub fn retry_after(&self) -> Option<Duration> {
+        self.downcast_ref::<RateLimitExceeded>()
+            .map(|e| e.retry_after)
+    }
+
     /// 获取错误分类
     #[must_use]
     pub fn kind(&self) -> ErrorKind {
@@ -410,6 +580,64 @@ impl From<DomainError> for AppError {
     }
 }
 
+// ==================== 字段校验明细 ====================
+
+/// 单个字段的校验违规
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct FieldViolation {
+    /// 字段路径（如 `items[0].quantity`）
+    pub field: String,
+    pub message: String,
+}
+
+impl FieldViolation {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+/// 一组字段校验违规，作为 [`AppError::invalid_fields`] 的来源错误
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ValidationErrors(pub Vec<FieldViolation>);
+
+impl fmt::Display for ValidationErrors {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "validation failed: ")?;
+        for (i, v) in self.0.iter().enumerate() {
+            if i > 0 {
+                write!(f, "; ")?;
+            }
+            write!(f, "{}: {}", v.field, v.message)?;
+        }
+        Ok(())
+    }
+}
+
+impl StdError for ValidationErrors {}
+
+/// [`AppError::rate_limited`] 的来源错误，保留建议等待时长
+#[derive(Debug)]
+struct RateLimitExceeded {
+    key: String,
+    retry_after: Duration,
+}
+
+impl fmt::Display for RateLimitExceeded {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "rate limit exceeded for {}, retry after {}ms",
+            self.key,
+            self.retry_after.as_millis()
+        )
+    }
+}
+
+impl StdError for RateLimitExceeded {}
+
 // ==================== Result 类型别名 ====================
 
 /// 应用层统一 Result 类型
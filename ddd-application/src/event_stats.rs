@@ -0,0 +1,227 @@
+use crate::{context::AppContext, error::AppError, query_handler::QueryHandler};
+use async_trait::async_trait;
+use chrono::{DateTime, NaiveDate, Utc};
+use ddd_domain::eventing::{EventHandler, HandledEventType, HandlerContext};
+use ddd_domain::persist::SerializedEvent;
+use serde::Serialize;
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, Mutex};
+
+/// 单个事件类型的统计信息
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EventTypeStats {
+    /// 事件类型
+    pub event_type: String,
+    /// 累计事件数
+    pub total: u64,
+    /// 按天（UTC）统计的事件数
+    pub daily: BTreeMap<NaiveDate, u64>,
+    /// 最近一次出现的事件发生时间
+    pub last_seen_at: DateTime<Utc>,
+}
+
+/// 事件统计存储（Event Stats Store）
+///
+/// - 由 `EventStatsProjection` 写入、由 `EventStatsQueryHandler` 读取；
+/// - 可替换为数据库/缓存等实现，内存实现见 `InMemoryEventStatsStore`。
+#[async_trait]
+pub trait EventStatsStore: Send + Sync {
+    /// 记录一次事件出现
+    async fn record(&self, event_type: &str, occurred_at: DateTime<Utc>) -> Result<(), AppError>;
+
+    /// 查询统计：指定事件类型或全部（按事件类型排序）
+    async fn stats(&self, event_type: Option<&str>) -> Result<Vec<EventTypeStats>, AppError>;
+}
+
+/// 基于内存的 EventStatsStore 实现
+#[derive(Clone, Default)]
+pub struct InMemoryEventStatsStore {
+    inner: Arc<Mutex<HashMap<String, EventTypeStats>>>,
+}
+
+impl InMemoryEventStatsStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl EventStatsStore for InMemoryEventStatsStore {
+    async fn record(&self, event_type: &str, occurred_at: DateTime<Utc>) -> Result<(), AppError> {
+        let mut inner = self.inner.lock().unwrap();
+        let entry = inner
+            .entry(event_type.to_string())
+            .or_insert_with(|| EventTypeStats {
+                event_type: event_type.to_string(),
+                total: 0,
+                daily: BTreeMap::new(),
+                last_seen_at: occurred_at,
+            });
+
+        entry.total += 1;
+        *entry.daily.entry(occurred_at.date_naive()).or_default() += 1;
+        entry.last_seen_at = entry.last_seen_at.max(occurred_at);
+
+        Ok(())
+    }
+
+    async fn stats(&self, event_type: Option<&str>) -> Result<Vec<EventTypeStats>, AppError> {
+        let inner = self.inner.lock().unwrap();
+        let mut out: Vec<EventTypeStats> = inner
+            .values()
+            .filter(|s| event_type.is_none_or(|t| s.event_type == t))
+            .cloned()
+            .collect();
+        out.sort_by(|a, b| a.event_type.cmp(&b.event_type));
+        Ok(out)
+    }
+}
+
+/// 事件统计投影（Event Stats Projection）
+///
+/// - 订阅全部事件，按事件类型/天累计计数并记录最近出现时间；
+/// - 作为 `EventHandler` 注册到 `EventEngine` 即可生效；
+/// - 投递为“至少一次”语义，重投会被重复计数，统计结果用于运营观测而非精确对账。
+pub struct EventStatsProjection<S> {
+    store: Arc<S>,
+}
+
+impl<S> EventStatsProjection<S>
+where
+    S: EventStatsStore,
+{
+    pub fn new(store: Arc<S>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl<S> EventHandler for EventStatsProjection<S>
+where
+    S: EventStatsStore + 'static,
+{
+    fn handler_name(&self) -> &str {
+        "event_stats_projection"
+    }
+
+    fn handled_event_type(&self) -> HandledEventType {
+        HandledEventType::All
+    }
+
+    async fn handle(&self, event: &SerializedEvent, _ctx: &HandlerContext) -> anyhow::Result<()> {
+        self.store
+            .record(event.event_type(), event.occurred_at())
+            .await?;
+        Ok(())
+    }
+}
+
+/// 查询事件统计：`event_type` 为空时返回全部事件类型
+#[derive(Debug, Clone, Default)]
+pub struct GetEventStats {
+    pub event_type: Option<String>,
+}
+
+/// `GetEventStats` 的查询处理器
+pub struct EventStatsQueryHandler<S> {
+    store: Arc<S>,
+}
+
+impl<S> EventStatsQueryHandler<S>
+where
+    S: EventStatsStore,
+{
+    pub fn new(store: Arc<S>) -> Self {
+        Self { store }
+    }
+}
+
+#[async_trait]
+impl<S> QueryHandler<GetEventStats, Vec<EventTypeStats>> for EventStatsQueryHandler<S>
+where
+    S: EventStatsStore,
+{
+    async fn handle(
+        &self,
+        _ctx: &AppContext,
+        q: GetEventStats,
+    ) -> Result<Vec<EventTypeStats>, AppError> {
+        self.store.stats(q.event_type.as_deref()).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemoryQueryBus;
+    use crate::query_bus::QueryBus;
+    use chrono::TimeZone;
+
+    fn mk_event(ty: &str, occurred_at: DateTime<Utc>) -> SerializedEvent {
+        SerializedEvent::builder()
+            .event_id(format!("{ty}-{}", occurred_at.timestamp()))
+            .event_type(ty.to_string())
+            .event_version(1)
+            .aggregate_id("a-1".to_string())
+            .aggregate_type("account".to_string())
+            .aggregate_version(1)
+            .occurred_at(occurred_at)
+            .payload(serde_json::json!({}))
+            .context(serde_json::json!({}))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn projection_counts_per_type_and_day() {
+        let store = Arc::new(InMemoryEventStatsStore::new());
+        let projection = EventStatsProjection::new(store.clone());
+
+        let d1 = Utc.with_ymd_and_hms(2025, 1, 1, 8, 0, 0).unwrap();
+        let d1_late = Utc.with_ymd_and_hms(2025, 1, 1, 23, 0, 0).unwrap();
+        let d2 = Utc.with_ymd_and_hms(2025, 1, 2, 9, 0, 0).unwrap();
+
+        for (ty, at) in [
+            ("account.deposited", d1),
+            ("account.deposited", d1_late),
+            ("account.deposited", d2),
+            ("account.withdrawn", d2),
+        ] {
+            projection
+                .handle(&mk_event(ty, at), &HandlerContext::default())
+                .await
+                .unwrap();
+        }
+
+        let bus = InMemoryQueryBus::new();
+        bus.register::<GetEventStats, Vec<EventTypeStats>, _>(Arc::new(
+            EventStatsQueryHandler::new(store),
+        ))
+        .unwrap();
+
+        let ctx = AppContext::default();
+        let all = bus
+            .dispatch::<GetEventStats, Vec<EventTypeStats>>(&ctx, GetEventStats::default())
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 2);
+
+        let deposited = &all[0];
+        assert_eq!(deposited.event_type, "account.deposited");
+        assert_eq!(deposited.total, 3);
+        assert_eq!(deposited.daily.get(&d1.date_naive()), Some(&2));
+        assert_eq!(deposited.daily.get(&d2.date_naive()), Some(&1));
+        assert_eq!(deposited.last_seen_at, d2);
+
+        let withdrawn = bus
+            .dispatch::<GetEventStats, Vec<EventTypeStats>>(
+                &ctx,
+                GetEventStats {
+                    event_type: Some("account.withdrawn".into()),
+                },
+            )
+            .await
+            .unwrap();
+        assert_eq!(withdrawn.len(), 1);
+        assert_eq!(withdrawn[0].total, 1);
+    }
+}
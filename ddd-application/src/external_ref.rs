@@ -0,0 +1,481 @@
+//! 外部系统引用映射（External Reference）
+//!
+//! 防腐层翻译器、策略与命令处理器经常需要在“外部系统中的标识”（如支付网关的客户号、
+//! ERP 的订单号）与聚合 ID 之间互查。`ExternalRefStore` 按聚合类型维护这一映射，统一语义：
+//! - 同一聚合类型下，一个外部引用（`ExternalRef`：外部系统 + 外部 ID）只能指向一个聚合；
+//! - 一个聚合在同一外部系统中只能有一个外部 ID；
+//! - 重复建立相同的映射是幂等的，违反上述唯一性时返回 `EXTERNAL_REF_TAKEN`。
+//!
+//! `ExternalRefs<A>` 在其上提供按聚合类型的强类型访问（`resolve`/`require`/`link`/`resolve_or_link`），
+//! 命令处理器通过 `AppContext::external_refs` 取得。
+//!
+//! 提供内存实现 `InMemoryExternalRefStore`；启用 `infra-sqlx` 特性后提供 `PgExternalRefStore`。
+//!
+use crate::error::AppError;
+use async_trait::async_trait;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::error::ErrorCode;
+use std::collections::HashMap;
+use std::fmt;
+use std::marker::PhantomData;
+use std::sync::{Arc, Mutex};
+
+/// 违反映射唯一性时的错误码
+pub const EXTERNAL_REF_TAKEN: &str = "EXTERNAL_REF_TAKEN";
+
+/// 外部引用：外部系统名（如 `stripe`）+ 该系统中的标识
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct ExternalRef {
+    system: String,
+    external_id: String,
+}
+
+impl ExternalRef {
+    pub fn new(system: impl Into<String>, external_id: impl Into<String>) -> Self {
+        Self {
+            system: system.into(),
+            external_id: external_id.into(),
+        }
+    }
+
+    pub fn system(&self) -> &str {
+        &self.system
+    }
+
+    pub fn external_id(&self) -> &str {
+        &self.external_id
+    }
+}
+
+impl fmt::Display for ExternalRef {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.system, self.external_id)
+    }
+}
+
+/// 外部引用 ↔ 聚合 ID 映射存储
+#[async_trait]
+pub trait ExternalRefStore: Send + Sync {
+    /// 建立映射；已存在相同映射时直接返回，违反唯一性时返回 `EXTERNAL_REF_TAKEN`
+    async fn link(
+        &self,
+        aggregate_type: &str,
+        reference: &ExternalRef,
+        aggregate_id: &str,
+    ) -> Result<(), AppError>;
+
+    /// 解除外部引用的映射（不存在时忽略）
+    async fn unlink(&self, aggregate_type: &str, reference: &ExternalRef) -> Result<(), AppError>;
+
+    /// 按外部引用查找聚合 ID
+    async fn resolve(
+        &self,
+        aggregate_type: &str,
+        reference: &ExternalRef,
+    ) -> Result<Option<String>, AppError>;
+
+    /// 聚合在各外部系统中的引用（按外部系统排序）
+    async fn references(
+        &self,
+        aggregate_type: &str,
+        aggregate_id: &str,
+    ) -> Result<Vec<ExternalRef>, AppError>;
+}
+
+/// 按聚合类型的强类型访问
+pub struct ExternalRefs<A> {
+    store: Arc<dyn ExternalRefStore>,
+    _aggregate: PhantomData<fn() -> A>,
+}
+
+impl<A: Aggregate> ExternalRefs<A> {
+    pub fn new(store: Arc<dyn ExternalRefStore>) -> Self {
+        Self {
+            store,
+            _aggregate: PhantomData,
+        }
+    }
+
+    /// 按外部引用查找聚合 ID
+    pub async fn resolve(&self, reference: &ExternalRef) -> Result<Option<A::Id>, AppError> {
+        self.store
+            .resolve(A::TYPE, reference)
+            .await?
+            .map(|id| parse_id::<A>(&id))
+            .transpose()
+    }
+
+    /// 按外部引用查找聚合 ID；未映射时返回 `AGGREGATE_NOT_FOUND`
+    pub async fn require(&self, reference: &ExternalRef) -> Result<A::Id, AppError> {
+        self.resolve(reference)
+            .await?
+            .ok_or_else(|| AppError::aggregate_not_found(A::TYPE, &reference.to_string()))
+    }
+
+    pub async fn link(
+        &self,
+        reference: &ExternalRef,
+        aggregate_id: &A::Id,
+    ) -> Result<(), AppError> {
+        self.store
+            .link(A::TYPE, reference, &aggregate_id.to_string())
+            .await
+    }
+
+    pub async fn unlink(&self, reference: &ExternalRef) -> Result<(), AppError> {
+        self.store.unlink(A::TYPE, reference).await
+    }
+
+    pub async fn references(&self, aggregate_id: &A::Id) -> Result<Vec<ExternalRef>, AppError> {
+        self.store
+            .references(A::TYPE, &aggregate_id.to_string())
+            .await
+    }
+
+    /// 查找外部引用对应的聚合，未映射时以 `new_id` 建立映射
+    ///
+    /// 返回聚合 ID 及是否为新建映射（防腐层据此决定创建聚合）；并发建立同一映射时，
+    /// 落败方返回胜出方的聚合 ID。
+    pub async fn resolve_or_link(
+        &self,
+        reference: &ExternalRef,
+        new_id: impl FnOnce() -> A::Id,
+    ) -> Result<(A::Id, bool), AppError> {
+        if let Some(id) = self.resolve(reference).await? {
+            return Ok((id, false));
+        }
+
+        let id = new_id();
+        match self.link(reference, &id).await {
+            Ok(()) => Ok((id, true)),
+            Err(err) if err.code() == EXTERNAL_REF_TAKEN => match self.resolve(reference).await? {
+                Some(existing) => Ok((existing, false)),
+                None => Err(err),
+            },
+            Err(err) => Err(err),
+        }
+    }
+}
+
+impl<A> Clone for ExternalRefs<A> {
+    fn clone(&self) -> Self {
+        Self {
+            store: self.store.clone(),
+            _aggregate: PhantomData,
+        }
+    }
+}
+
+fn parse_id<A: Aggregate>(id: &str) -> Result<A::Id, AppError> {
+    id.parse()
+        .map_err(|_| AppError::internal(format!("invalid {} id in external refs: {id}", A::TYPE)))
+}
+
+#[derive(Default)]
+struct Mappings {
+    /// (聚合类型, 外部引用) -> 聚合 ID
+    by_ref: HashMap<(String, ExternalRef), String>,
+    /// (聚合类型, 聚合 ID, 外部系统) -> 外部 ID
+    by_aggregate: HashMap<(String, String, String), String>,
+}
+
+/// 基于内存的 ExternalRefStore 实现
+#[derive(Default)]
+pub struct InMemoryExternalRefStore {
+    mappings: Mutex<Mappings>,
+}
+
+impl InMemoryExternalRefStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl ExternalRefStore for InMemoryExternalRefStore {
+    async fn link(
+        &self,
+        aggregate_type: &str,
+        reference: &ExternalRef,
+        aggregate_id: &str,
+    ) -> Result<(), AppError> {
+        let mut mappings = self.mappings.lock().unwrap();
+        let ref_key = (aggregate_type.to_string(), reference.clone());
+        let aggregate_key = (
+            aggregate_type.to_string(),
+            aggregate_id.to_string(),
+            reference.system().to_string(),
+        );
+
+        match mappings.by_ref.get(&ref_key) {
+            Some(existing) if existing == aggregate_id => return Ok(()),
+            Some(existing) => {
+                return Err(AppError::external_ref_taken(
+                    &reference.to_string(),
+                    existing,
+                ));
+            }
+            None => {}
+        }
+        if let Some(external_id) = mappings.by_aggregate.get(&aggregate_key) {
+            return Err(AppError::external_ref_taken(
+                &ExternalRef::new(reference.system(), external_id.clone()).to_string(),
+                aggregate_id,
+            ));
+        }
+
+        mappings.by_ref.insert(ref_key, aggregate_id.to_string());
+        mappings
+            .by_aggregate
+            .insert(aggregate_key, reference.external_id().to_string());
+        Ok(())
+    }
+
+    async fn unlink(&self, aggregate_type: &str, reference: &ExternalRef) -> Result<(), AppError> {
+        let mut mappings = self.mappings.lock().unwrap();
+        if let Some(aggregate_id) = mappings
+            .by_ref
+            .remove(&(aggregate_type.to_string(), reference.clone()))
+        {
+            mappings.by_aggregate.remove(&(
+                aggregate_type.to_string(),
+                aggregate_id,
+                reference.system().to_string(),
+            ));
+        }
+        Ok(())
+    }
+
+    async fn resolve(
+        &self,
+        aggregate_type: &str,
+        reference: &ExternalRef,
+    ) -> Result<Option<String>, AppError> {
+        Ok(self
+            .mappings
+            .lock()
+            .unwrap()
+            .by_ref
+            .get(&(aggregate_type.to_string(), reference.clone()))
+            .cloned())
+    }
+
+    async fn references(
+        &self,
+        aggregate_type: &str,
+        aggregate_id: &str,
+    ) -> Result<Vec<ExternalRef>, AppError> {
+        let mappings = self.mappings.lock().unwrap();
+        let mut references: Vec<_> = mappings
+            .by_aggregate
+            .iter()
+            .filter(|((ty, id, _), _)| ty == aggregate_type && id == aggregate_id)
+            .map(|((_, _, system), external_id)| ExternalRef::new(system, external_id))
+            .collect();
+        references.sort();
+        Ok(references)
+    }
+}
+
+#[cfg(feature = "infra-sqlx")]
+pub use postgres::PgExternalRefStore;
+
+#[cfg(feature = "infra-sqlx")]
+mod postgres {
+    use super::{ExternalRef, ExternalRefStore};
+    use crate::error::AppError;
+    use async_trait::async_trait;
+    use ddd_domain::error::DomainError;
+    use sqlx::PgPool;
+
+    /// 基于 Postgres 的 ExternalRefStore 实现
+    ///
+    /// 映射表 `ddd_external_refs` 以 `(aggregate_type, system, external_id)` 为主键、
+    /// `(aggregate_type, aggregate_id, system)` 为唯一约束，并发建立映射时由约束兜底。
+    pub struct PgExternalRefStore {
+        pool: PgPool,
+    }
+
+    impl PgExternalRefStore {
+        pub fn new(pool: PgPool) -> Self {
+            Self { pool }
+        }
+
+        /// 创建所需的表（幂等）
+        pub async fn migrate(&self) -> Result<(), AppError> {
+            sqlx::query(
+                "CREATE TABLE IF NOT EXISTS ddd_external_refs (
+                    aggregate_type TEXT NOT NULL,
+                    system TEXT NOT NULL,
+                    external_id TEXT NOT NULL,
+                    aggregate_id TEXT NOT NULL,
+                    PRIMARY KEY (aggregate_type, system, external_id),
+                    UNIQUE (aggregate_type, aggregate_id, system)
+                )",
+            )
+            .execute(&self.pool)
+            .await
+            .map_err(DomainError::from)?;
+
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl ExternalRefStore for PgExternalRefStore {
+        async fn link(
+            &self,
+            aggregate_type: &str,
+            reference: &ExternalRef,
+            aggregate_id: &str,
+        ) -> Result<(), AppError> {
+            let inserted = sqlx::query(
+                "INSERT INTO ddd_external_refs (aggregate_type, system, external_id, aggregate_id)
+                 VALUES ($1, $2, $3, $4)
+                 ON CONFLICT DO NOTHING",
+            )
+            .bind(aggregate_type)
+            .bind(reference.system())
+            .bind(reference.external_id())
+            .bind(aggregate_id)
+            .execute(&self.pool)
+            .await
+            .map_err(DomainError::from)?
+            .rows_affected();
+            if inserted > 0 {
+                return Ok(());
+            }
+
+            // 未插入：相同映射已存在（幂等），或违反任一唯一性
+            let existing: Option<(String, String)> = sqlx::query_as(
+                "SELECT external_id, aggregate_id FROM ddd_external_refs
+                 WHERE aggregate_type = $1 AND system = $2
+                   AND (external_id = $3 OR aggregate_id = $4)
+                 ORDER BY external_id = $3 DESC
+                 LIMIT 1",
+            )
+            .bind(aggregate_type)
+            .bind(reference.system())
+            .bind(reference.external_id())
+            .bind(aggregate_id)
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(DomainError::from)?;
+
+            match existing {
+                Some((external_id, existing_id))
+                    if external_id == reference.external_id() && existing_id == aggregate_id =>
+                {
+                    Ok(())
+                }
+                Some((external_id, existing_id)) => Err(AppError::external_ref_taken(
+                    &ExternalRef::new(reference.system(), external_id).to_string(),
+                    &existing_id,
+                )),
+                // 冲突的映射已被并发解除
+                None => Err(AppError::internal(format!(
+                    "failed to link external ref {reference}"
+                ))),
+            }
+        }
+
+        async fn unlink(
+            &self,
+            aggregate_type: &str,
+            reference: &ExternalRef,
+        ) -> Result<(), AppError> {
+            sqlx::query(
+                "DELETE FROM ddd_external_refs
+                 WHERE aggregate_type = $1 AND system = $2 AND external_id = $3",
+            )
+            .bind(aggregate_type)
+            .bind(reference.system())
+            .bind(reference.external_id())
+            .execute(&self.pool)
+            .await
+            .map_err(DomainError::from)?;
+
+            Ok(())
+        }
+
+        async fn resolve(
+            &self,
+            aggregate_type: &str,
+            reference: &ExternalRef,
+        ) -> Result<Option<String>, AppError> {
+            let aggregate_id = sqlx::query_scalar(
+                "SELECT aggregate_id FROM ddd_external_refs
+                 WHERE aggregate_type = $1 AND system = $2 AND external_id = $3",
+            )
+            .bind(aggregate_type)
+            .bind(reference.system())
+            .bind(reference.external_id())
+            .fetch_optional(&self.pool)
+            .await
+            .map_err(DomainError::from)?;
+
+            Ok(aggregate_id)
+        }
+
+        async fn references(
+            &self,
+            aggregate_type: &str,
+            aggregate_id: &str,
+        ) -> Result<Vec<ExternalRef>, AppError> {
+            let rows: Vec<(String, String)> = sqlx::query_as(
+                "SELECT system, external_id FROM ddd_external_refs
+                 WHERE aggregate_type = $1 AND aggregate_id = $2
+                 ORDER BY system",
+            )
+            .bind(aggregate_type)
+            .bind(aggregate_id)
+            .fetch_all(&self.pool)
+            .await
+            .map_err(DomainError::from)?;
+
+            Ok(rows
+                .into_iter()
+                .map(|(system, external_id)| ExternalRef::new(system, external_id))
+                .collect())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn enforces_uniqueness_in_both_directions() {
+        let store = InMemoryExternalRefStore::new();
+        let stripe = ExternalRef::new("stripe", "cus_1");
+
+        store.link("customer", &stripe, "c-1").await.unwrap();
+        // 幂等
+        store.link("customer", &stripe, "c-1").await.unwrap();
+
+        // 外部引用已指向其他聚合
+        let err = store.link("customer", &stripe, "c-2").await.unwrap_err();
+        assert_eq!(err.code(), EXTERNAL_REF_TAKEN);
+
+        // 聚合在同一外部系统中已有外部 ID
+        let other = ExternalRef::new("stripe", "cus_2");
+        let err = store.link("customer", &other, "c-1").await.unwrap_err();
+        assert_eq!(err.code(), EXTERNAL_REF_TAKEN);
+
+        // 不同聚合类型、不同外部系统互不影响
+        store.link("supplier", &stripe, "s-1").await.unwrap();
+        store
+            .link("customer", &ExternalRef::new("erp", "K-9"), "c-1")
+            .await
+            .unwrap();
+        assert_eq!(
+            store.references("customer", "c-1").await.unwrap(),
+            vec![ExternalRef::new("erp", "K-9"), stripe.clone()]
+        );
+
+        store.unlink("customer", &stripe).await.unwrap();
+        assert_eq!(store.resolve("customer", &stripe).await.unwrap(), None);
+        store.link("customer", &other, "c-1").await.unwrap();
+    }
+}
@@ -1,7 +1,11 @@
 use crate::{
-    command_bus::CommandBus, command_handler::CommandHandler, context::AppContext, error::AppError,
+    command_bus::{CommandBus, CommandEnvelope, EnvelopeCommandBus},
+    command_handler::CommandHandler,
+    context::AppContext,
+    error::AppError,
 };
 use async_trait::async_trait;
+use chrono::Utc;
 use dashmap::DashMap;
 use std::any::{Any, TypeId, type_name, type_name_of_val};
 use std::future::Future;
@@ -16,6 +20,7 @@ type CmdHandlerFn =
 /// 基于内存的 CommandBus 实现
 /// - 通过 TypeId 注册不同 Command 对应的 Handler
 /// - 运行时以类型擦除（Any）方式进行调度
+/// - 调度时记录命令接收时间（`AppContext::with_command_received_at`）
 pub struct InMemoryCommandBus {
     handlers: DashMap<TypeId, (&'static str, CmdHandlerFn)>,
 }
@@ -80,6 +85,28 @@ impl CommandBus for InMemoryCommandBus {
     }
 }
 
+#[async_trait]
+impl EnvelopeCommandBus for InMemoryCommandBus {
+    async fn dispatch_envelope(
+        &self,
+        ctx: &AppContext,
+        envelope: CommandEnvelope,
+    ) -> Result<(), AppError> {
+        let Some((_name, f)) = self
+            .handlers
+            .get(&envelope.command_type_id())
+            .map(|h| h.clone())
+        else {
+            return Err(AppError::handler_not_found(envelope.command_type()));
+        };
+
+        let ctx = ctx.clone().with_command_received_at(Utc::now());
+        let command_type = envelope.command_type();
+        ctx.run(command_type, (f)(envelope.into_command(), &ctx))
+            .await
+    }
+}
+
 impl InMemoryCommandBus {
     async fn dispatch_impl<C>(&self, ctx: &AppContext, cmd: C) -> Result<(), AppError>
     where
@@ -89,7 +116,8 @@ impl InMemoryCommandBus {
             return Err(AppError::handler_not_found(type_name::<C>()));
         };
 
-        (f)(Box::new(cmd), ctx).await
+        let ctx = ctx.clone().with_command_received_at(Utc::now());
+        ctx.run(type_name::<C>(), (f)(Box::new(cmd), &ctx)).await
     }
 }
 
@@ -194,4 +222,50 @@ mod tests {
 
         assert_eq!(counter.load(Ordering::SeqCst), 100);
     }
+
+    #[derive(Debug)]
+    struct Slow;
+
+    struct SlowHandler {
+        finished: Arc<AtomicUsize>,
+    }
+    #[async_trait]
+    impl CommandHandler<Slow> for SlowHandler {
+        async fn handle(&self, _ctx: &AppContext, _cmd: Slow) -> Result<(), AppError> {
+            tokio::time::sleep(std::time::Duration::from_secs(5)).await;
+            self.finished.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread", worker_threads = 2)]
+    async fn aborts_handler_past_deadline_or_on_cancel() {
+        use std::time::Duration;
+        use tokio_util::sync::CancellationToken;
+
+        let bus = InMemoryCommandBus::new();
+        let finished = Arc::new(AtomicUsize::new(0));
+        bus.register::<Slow, _>(Arc::new(SlowHandler {
+            finished: finished.clone(),
+        }))
+        .unwrap();
+
+        let ctx = AppContext::default().with_timeout(Duration::from_millis(20));
+        let err = bus.dispatch(&ctx, Slow).await.unwrap_err();
+        assert_eq!(err.code(), "DEADLINE_EXCEEDED");
+        assert!(err.to_string().contains("Slow"));
+        assert_eq!(ctx.remaining(), Some(Duration::ZERO));
+        assert!(ctx.check_deadline().is_err());
+
+        let token = CancellationToken::new();
+        let ctx = AppContext::default().with_cancellation(token.clone());
+        let canceller = tokio::spawn(async move {
+            tokio::time::sleep(Duration::from_millis(20)).await;
+            token.cancel();
+        });
+        let err = bus.dispatch(&ctx, Slow).await.unwrap_err();
+        assert_eq!(err.code(), "CANCELLED");
+        canceller.await.unwrap();
+        assert_eq!(finished.load(Ordering::SeqCst), 0);
+    }
 }
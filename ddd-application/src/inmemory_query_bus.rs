@@ -1,8 +1,14 @@
 use crate::{
-    context::AppContext, error::AppError, query_bus::QueryBus, query_handler::QueryHandler,
+    context::AppContext,
+    error::AppError,
+    query_bus::{QueryBus, QueryFilter},
+    query_catalog::{QueryDescriptor, openapi_document},
+    query_handler::QueryHandler,
+    result_transformer::ResultTransformer,
 };
 use async_trait::async_trait;
 use dashmap::DashMap;
+use serde_json::Value;
 use std::any::{Any, TypeId, type_name, type_name_of_val};
 use std::future::Future;
 use std::pin::Pin;
@@ -16,18 +22,28 @@ type QueryHandlerFuture<'a> =
 type QueryHandlerFn =
     Arc<dyn for<'a> Fn(BoxAnySend, &'a AppContext) -> QueryHandlerFuture<'a> + Send + Sync>;
 
+type TransformerFn = Arc<dyn Fn(&AppContext, &mut dyn Any) + Send + Sync>;
+
+type QueryFilterFn = Arc<dyn Fn(&AppContext, &mut dyn Any) -> Result<(), AppError> + Send + Sync>;
+
 /// 基于内存的 QueryBus 实现
 /// - 通过 TypeId 注册不同 Query 对应的 Handler
 /// - 以类型擦除方式调度，并在调用端进行结果还原
 pub struct InMemoryQueryBus {
     // 使用 (QueryTypeId, ResultTypeId) 作为键，避免相同 Query 不同返回类型的冲突
-    handlers: DashMap<(TypeId, TypeId), (&'static str, QueryHandlerFn)>,
+    handlers: DashMap<(TypeId, TypeId), (QueryDescriptor, QueryHandlerFn)>,
+    // 按结果类型登记的后处理器
+    transformers: DashMap<TypeId, Vec<TransformerFn>>,
+    // 按查询类型登记的前置过滤器
+    filters: DashMap<TypeId, Vec<QueryFilterFn>>,
 }
 
 impl Default for InMemoryQueryBus {
     fn default() -> Self {
         Self {
             handlers: DashMap::new(),
+            transformers: DashMap::new(),
+            filters: DashMap::new(),
         }
     }
 }
@@ -45,6 +61,12 @@ impl InMemoryQueryBus {
         H: QueryHandler<Q, R> + Send + Sync + 'static,
     {
         let key = (TypeId::of::<Q>(), TypeId::of::<R>());
+        let descriptor = QueryDescriptor {
+            query_type: type_name::<Q>(),
+            result_type: type_name::<R>(),
+            handler_name: handler.handler_name().to_string(),
+            description: handler.description().map(ToString::to_string),
+        };
 
         let f: QueryHandlerFn = {
             let handler = handler.clone();
@@ -75,10 +97,65 @@ impl InMemoryQueryBus {
             )));
         }
 
-        self.handlers.insert(key, (type_name::<Q>(), f));
+        self.handlers.insert(key, (descriptor, f));
 
         Ok(())
     }
+
+    /// 为结果类型 `R` 注册后处理器（脱敏/遮盖等），按注册顺序执行
+    ///
+    /// 同时作用于返回 `Option<R>` 与 `Vec<R>` 的查询。
+    pub fn register_transformer<R, T>(&self, transformer: Arc<T>)
+    where
+        R: Send + 'static,
+        T: ResultTransformer<R> + 'static,
+    {
+        let one = transformer.clone();
+        self.add_transformer::<R>(Arc::new(move |ctx, out| {
+            if let Some(r) = out.downcast_mut::<R>() {
+                one.transform(ctx, r);
+            }
+        }));
+
+        let optional = transformer.clone();
+        self.add_transformer::<Option<R>>(Arc::new(move |ctx, out| {
+            if let Some(Some(r)) = out.downcast_mut::<Option<R>>() {
+                optional.transform(ctx, r);
+            }
+        }));
+
+        self.add_transformer::<Vec<R>>(Arc::new(move |ctx, out| {
+            if let Some(items) = out.downcast_mut::<Vec<R>>() {
+                items.iter_mut().for_each(|r| transformer.transform(ctx, r));
+            }
+        }));
+    }
+
+    /// 为查询类型 `Q` 注册前置过滤器（如 `RowLevelSecurity`），按注册顺序在处理器之前执行
+    ///
+    /// 作用于 `Q` 的所有结果类型；任一过滤器返回错误时查询被拒绝。
+    pub fn register_query_filter<Q, F>(&self, filter: Arc<F>)
+    where
+        Q: Send + 'static,
+        F: QueryFilter<Q> + 'static,
+    {
+        self.filters
+            .entry(TypeId::of::<Q>())
+            .or_default()
+            .push(Arc::new(move |ctx, query| {
+                match query.downcast_mut::<Q>() {
+                    Some(q) => filter.apply(ctx, q),
+                    None => Ok(()),
+                }
+            }));
+    }
+
+    fn add_transformer<R: 'static>(&self, f: TransformerFn) {
+        self.transformers
+            .entry(TypeId::of::<R>())
+            .or_default()
+            .push(f);
+    }
 }
 
 #[async_trait]
@@ -93,7 +170,7 @@ impl QueryBus for InMemoryQueryBus {
 }
 
 impl InMemoryQueryBus {
-    async fn dispatch_impl<Q, R>(&self, ctx: &AppContext, q: Q) -> Result<R, AppError>
+    async fn dispatch_impl<Q, R>(&self, ctx: &AppContext, mut q: Q) -> Result<R, AppError>
     where
         Q: Send + 'static,
         R: Send + 'static,
@@ -103,7 +180,22 @@ impl InMemoryQueryBus {
             return Err(AppError::handler_not_found(type_name::<Q>()));
         };
 
-        let out = (f)(Box::new(q), ctx).await?;
+        let filters = self
+            .filters
+            .get(&TypeId::of::<Q>())
+            .map(|filters| filters.clone())
+            .unwrap_or_default();
+        for filter in filters {
+            filter(ctx, &mut q)?;
+        }
+
+        let mut out = ctx.run(type_name::<Q>(), (f)(Box::new(q), ctx)).await?;
+
+        if let Some(transformers) = self.transformers.get(&TypeId::of::<R>()) {
+            for t in transformers.iter() {
+                t(ctx, out.as_mut());
+            }
+        }
 
         match out.downcast::<R>() {
             Ok(dto_opt) => Ok(*dto_opt),
@@ -116,9 +208,30 @@ impl InMemoryQueryBus {
 }
 
 impl InMemoryQueryBus {
+    /// 是否已注册查询 `Q`（结果 `R`）的处理器
+    pub fn handles<Q: 'static, R: 'static>(&self) -> bool {
+        self.handlers
+            .contains_key(&(TypeId::of::<Q>(), TypeId::of::<R>()))
+    }
+
     /// 获取已注册的查询类型名列表（只读视图）
     pub fn registered_queries(&self) -> Vec<&'static str> {
-        self.handlers.iter().map(|e| e.value().0).collect()
+        self.handlers
+            .iter()
+            .map(|e| e.value().0.query_type)
+            .collect()
+    }
+
+    /// 获取已注册查询的结构化描述，按查询类型与结果类型排序
+    pub fn describe_queries(&self) -> Vec<QueryDescriptor> {
+        let mut out: Vec<_> = self.handlers.iter().map(|e| e.value().0.clone()).collect();
+        out.sort_by(|a, b| (a.query_type, a.result_type).cmp(&(b.query_type, b.result_type)));
+        out
+    }
+
+    /// 将已注册查询导出为 OpenAPI 文档
+    pub fn openapi(&self, title: &str, version: &str) -> Value {
+        openapi_document(title, version, &self.describe_queries())
     }
 }
 
@@ -183,9 +296,15 @@ mod tests {
         let f: QueryHandlerFn = Arc::new(|_boxed_q, _ctx| {
             Box::pin(async move { Ok(Box::new(WrongDto) as BoxAnySend) })
         });
+        let descriptor = QueryDescriptor {
+            query_type: type_name::<Get>(),
+            result_type: type_name::<NumDto>(),
+            handler_name: "wrong".into(),
+            description: None,
+        };
         bus.handlers.insert(
             (TypeId::of::<Get>(), TypeId::of::<NumDto>()),
-            (type_name::<Get>(), f),
+            (descriptor, f),
         );
 
         let ctx = AppContext::default();
@@ -259,4 +378,110 @@ mod tests {
         assert_eq!(n, 42);
         assert_eq!(name, "Alice");
     }
+
+    #[async_trait]
+    impl QueryHandler<Get, NameDto> for Get2NameHandler {
+        async fn handle(&self, _ctx: &AppContext, _q: Get) -> Result<NameDto, AppError> {
+            Ok(NameDto("Bob".to_string()))
+        }
+
+        fn handler_name(&self) -> &str {
+            "get_name"
+        }
+
+        fn description(&self) -> Option<&str> {
+            Some("Look up the display name")
+        }
+    }
+
+    #[test]
+    fn describe_queries_and_export_openapi() {
+        let bus = InMemoryQueryBus::new();
+        bus.register::<Get, NumDto, _>(Arc::new(GetHandler {
+            counter: Arc::new(AtomicUsize::new(0)),
+        }))
+        .unwrap();
+        bus.register::<Get, NameDto, _>(Arc::new(Get2NameHandler))
+            .unwrap();
+
+        let described = bus.describe_queries();
+        assert_eq!(described.len(), 2);
+        assert!(described[0].result_type.ends_with("NameDto"));
+        assert_eq!(described[0].handler_name, "get_name");
+        assert_eq!(
+            described[0].description.as_deref(),
+            Some("Look up the display name")
+        );
+        assert!(described[1].handler_name.ends_with("GetHandler"));
+        assert_eq!(described[1].description, None);
+
+        let doc = bus.openapi("queries", "1.0.0");
+        assert_eq!(doc["info"]["title"], "queries");
+        let op = &doc["paths"]["/queries/Get/NameDto"]["post"];
+        assert_eq!(op["summary"], "Look up the display name");
+        assert_eq!(op["x-handler"], "get_name");
+        assert!(doc["paths"]["/queries/Get/NumDto"]["post"].is_object());
+    }
+
+    struct ListAccounts;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct AccountDto {
+        owner: String,
+        account_number: String,
+    }
+
+    struct ListAccountsHandler;
+
+    #[async_trait]
+    impl QueryHandler<ListAccounts, Vec<AccountDto>> for ListAccountsHandler {
+        async fn handle(
+            &self,
+            _ctx: &AppContext,
+            _q: ListAccounts,
+        ) -> Result<Vec<AccountDto>, AppError> {
+            Ok(vec![AccountDto {
+                owner: "alice".into(),
+                account_number: "6222020200112233".into(),
+            }])
+        }
+    }
+
+    #[tokio::test]
+    async fn transformers_mask_results_by_actor_role() {
+        use crate::result_transformer::{FieldMask, Masking};
+        use ddd_domain::domain_event::EventContext;
+
+        let bus = InMemoryQueryBus::new();
+        bus.register::<ListAccounts, Vec<AccountDto>, _>(Arc::new(ListAccountsHandler))
+            .unwrap();
+        bus.register_transformer::<AccountDto, _>(Arc::new(FieldMask::new().mask(
+            |d: &mut AccountDto| Some(&mut d.account_number),
+            Masking::KeepLast(4),
+            ["auditor"],
+        )));
+
+        let ctx_as = |actor_type: &str, extensions: Option<serde_json::Value>| {
+            AppContext::new(
+                EventContext::builder()
+                    .actor_type(actor_type.into())
+                    .maybe_extensions(extensions)
+                    .build(),
+            )
+        };
+
+        let support = ctx_as("support", None);
+        let masked: Vec<AccountDto> = bus.dispatch(&support, ListAccounts).await.unwrap();
+        assert_eq!(masked[0].account_number, "************2233");
+        assert_eq!(masked[0].owner, "alice");
+
+        // 豁免角色可来自执行主体类型或扩展字段中的 roles
+        let auditor = ctx_as("auditor", None);
+        let raw: Vec<AccountDto> = bus.dispatch(&auditor, ListAccounts).await.unwrap();
+        assert_eq!(raw[0].account_number, "6222020200112233");
+
+        let staff = ctx_as("user", Some(serde_json::json!({"roles": ["auditor"]})));
+        let raw: Vec<AccountDto> = bus.dispatch(&staff, ListAccounts).await.unwrap();
+        assert_eq!(raw[0].account_number, "6222020200112233");
+    }
 }
@@ -1,11 +1,32 @@
+pub mod child_collection;
+pub mod command_audit;
 pub mod command_bus;
 pub mod command_handler;
+pub mod command_outcome;
+pub mod command_replay;
+pub mod command_router;
 pub mod context;
+pub mod crud_service;
 pub mod error;
+pub mod event_stats;
+pub mod external_ref;
 pub mod inmemory_command_bus;
 pub mod inmemory_query_bus;
+pub mod prefetch;
+#[cfg(feature = "problemdetails")]
+pub mod problem_details;
 pub mod query_bus;
+pub mod query_catalog;
 pub mod query_handler;
+pub mod rate_limit;
+pub mod read_requirement;
+pub mod read_store;
+#[cfg(feature = "remote-query")]
+pub mod remote_query_bus;
+pub mod result_transformer;
+pub mod row_security;
+pub mod sequence;
+pub mod unit_of_work;
 
 pub use inmemory_command_bus::InMemoryCommandBus;
 pub use inmemory_query_bus::InMemoryQueryBus;
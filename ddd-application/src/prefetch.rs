@@ -0,0 +1,145 @@
+//! 命令预取提示（Prefetch Hints）
+//!
+//! 冷启动时，涉及多个聚合的命令在处理器内逐个加载聚合，尾延迟随聚合数线性增长。
+//! 命令实现 `Prefetch` 声明将要访问的聚合，`PrefetchingCommandBus` 在调用处理器前并发预热聚合缓存
+//! （`ddd_domain::persist::CachedAggregateRepo`），处理器随后的加载直接命中缓存：
+//! - 以 `with_warmer` 按聚合类型登记预热器（如 `CachedAggregateRepo::warmer::<A>()`）；
+//! - 以 `with_prefetch::<C>()` 登记需要预取的命令类型，未登记的命令直接分发；
+//! - 预取仅为提示：未登记预热器的聚合类型被忽略，预热失败不影响分发，处理器加载时回源。
+//!
+use crate::{
+    command_bus::{CommandBus, CommandEnvelope, EnvelopeCommandBus},
+    context::AppContext,
+    error::AppError,
+};
+use async_trait::async_trait;
+use dashmap::DashMap;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::persist::AggregateWarmer;
+use futures_util::future::join_all;
+use std::any::{Any, TypeId};
+use std::collections::HashMap;
+use std::sync::Arc;
+
+/// 待预取的聚合
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct PrefetchTarget {
+    pub aggregate_type: &'static str,
+    pub aggregate_id: String,
+}
+
+impl PrefetchTarget {
+    pub fn new(aggregate_type: &'static str, aggregate_id: impl Into<String>) -> Self {
+        Self {
+            aggregate_type,
+            aggregate_id: aggregate_id.into(),
+        }
+    }
+
+    /// 聚合类型 `A` 的某个聚合
+    pub fn of<A: Aggregate>(aggregate_id: &A::Id) -> Self {
+        Self::new(A::TYPE, aggregate_id.to_string())
+    }
+}
+
+/// 声明命令将要访问的聚合
+pub trait Prefetch {
+    fn prefetch(&self) -> Vec<PrefetchTarget>;
+}
+
+type PrefetchFn = Arc<dyn Fn(&dyn Any) -> Vec<PrefetchTarget> + Send + Sync>;
+
+/// 分发前按命令预取提示预热聚合缓存的命令总线装饰器
+pub struct PrefetchingCommandBus<B> {
+    inner: B,
+    warmers: HashMap<&'static str, Arc<dyn AggregateWarmer>>,
+    prefetches: DashMap<TypeId, PrefetchFn>,
+}
+
+impl<B> PrefetchingCommandBus<B>
+where
+    B: CommandBus,
+{
+    pub fn new(inner: B) -> Self {
+        Self {
+            inner,
+            warmers: HashMap::new(),
+            prefetches: DashMap::new(),
+        }
+    }
+
+    /// 登记聚合类型的预热器，同一聚合类型后登记者覆盖先登记者
+    pub fn with_warmer(mut self, warmer: Arc<dyn AggregateWarmer>) -> Self {
+        self.warmers.insert(warmer.aggregate_type(), warmer);
+        self
+    }
+
+    /// 登记需要预取的命令类型
+    pub fn with_prefetch<C>(self) -> Self
+    where
+        C: Prefetch + 'static,
+    {
+        let f: PrefetchFn = Arc::new(|cmd| {
+            cmd.downcast_ref::<C>()
+                .map(Prefetch::prefetch)
+                .unwrap_or_default()
+        });
+        self.prefetches.insert(TypeId::of::<C>(), f);
+        self
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    fn targets(&self, type_id: TypeId, cmd: &dyn Any) -> Vec<PrefetchTarget> {
+        self.prefetches
+            .get(&type_id)
+            .map(|f| f(cmd))
+            .unwrap_or_default()
+    }
+
+    /// 并发预热全部目标，忽略预热错误
+    async fn warm(&self, mut targets: Vec<PrefetchTarget>) {
+        targets.sort_by(|a, b| {
+            (a.aggregate_type, &a.aggregate_id).cmp(&(b.aggregate_type, &b.aggregate_id))
+        });
+        targets.dedup();
+        let warming = targets.iter().filter_map(|target| {
+            let warmer = self.warmers.get(target.aggregate_type)?;
+            Some(warmer.warm(&target.aggregate_id))
+        });
+        let _ = join_all(warming).await;
+    }
+}
+
+#[async_trait]
+impl<B> CommandBus for PrefetchingCommandBus<B>
+where
+    B: CommandBus,
+{
+    async fn dispatch<C>(&self, ctx: &AppContext, cmd: C) -> Result<(), AppError>
+    where
+        C: Send + 'static,
+    {
+        let targets = self.targets(TypeId::of::<C>(), &cmd as &dyn Any);
+        self.warm(targets).await;
+        self.inner.dispatch(ctx, cmd).await
+    }
+}
+
+#[async_trait]
+impl<B> EnvelopeCommandBus for PrefetchingCommandBus<B>
+where
+    B: EnvelopeCommandBus,
+{
+    async fn dispatch_envelope(
+        &self,
+        ctx: &AppContext,
+        envelope: CommandEnvelope,
+    ) -> Result<(), AppError> {
+        let targets = self.targets(envelope.command_type_id(), envelope.command());
+        self.warm(targets).await;
+        self.inner.dispatch_envelope(ctx, envelope).await
+    }
+}
@@ -0,0 +1,180 @@
+//! HTTP 问题详情（RFC 7807 Problem Details）
+//!
+//! 将实现 [`ErrorCode`] 的错误（`AppError`/`DomainError`）统一转换为 `application/problem+json` 响应体：
+//! - `status`/`code`/`retryable` 取自错误分类，`title` 为状态码的标准短语；
+//! - `type` 默认 `about:blank`，可经 `with_type_base` 按错误码生成文档链接；
+//! - 内部错误（`ErrorKind::Internal`）不外泄原始消息，`detail` 使用分类的默认消息；
+//! - 字段校验明细（`AppError::invalid_fields`）输出为 `errors`，限流等待时长输出为 `retry_after_ms`。
+//!
+//! ```rust
+//! use ddd_application::error::{AppError, FieldViolation};
+//! use ddd_application::problem_details::ProblemDetails;
+//!
+//! let err = AppError::invalid_fields(vec![FieldViolation::new("email", "must contain @")]);
+//! let problem = ProblemDetails::from(&err).with_type_base("https://errors.example.com");
+//! assert_eq!(problem.status, 400);
+//! assert_eq!(problem.type_uri, "https://errors.example.com/validation-error");
+//! assert_eq!(problem.errors[0].field, "email");
+//! ```
+//!
+use crate::error::{AppError, FieldViolation, ValidationErrors};
+use ddd_domain::error::{DomainError, ErrorCode, ErrorKind};
+use serde::Serialize;
+use std::error::Error as StdError;
+
+/// 问题详情响应的媒体类型
+pub const CONTENT_TYPE: &str = "application/problem+json";
+
+/// RFC 7807 问题详情，附带错误码、可重试性与字段校验明细扩展成员
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub type_uri: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    pub code: String,
+    pub retryable: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retry_after_ms: Option<u64>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldViolation>,
+}
+
+impl ProblemDetails {
+    /// 由任意 [`ErrorCode`] 错误构造；字段校验明细沿 `source` 链查找
+    pub fn from_error<E: ErrorCode>(err: &E) -> Self {
+        let status = err.http_status();
+        let detail = match err.kind() {
+            ErrorKind::Internal => ErrorKind::Internal.default_message().to_string(),
+            _ => err.to_string(),
+        };
+
+        let mut errors = Vec::new();
+        let mut source: Option<&(dyn StdError + 'static)> = Some(err);
+        while let Some(e) = source {
+            if let Some(found) = e.downcast_ref::<ValidationErrors>() {
+                errors = found.0.clone();
+                break;
+            }
+            source = e.source();
+        }
+
+        Self {
+            type_uri: "about:blank".to_string(),
+            title: status_title(status).to_string(),
+            status,
+            detail,
+            instance: None,
+            code: err.code().to_string(),
+            retryable: err.is_retryable(),
+            retry_after_ms: None,
+            errors,
+        }
+    }
+
+    /// 以 `<base>/<错误码 kebab-case>` 作为 `type`（如 `VALIDATION_ERROR` → `validation-error`）
+    pub fn with_type_base(mut self, base: &str) -> Self {
+        self.type_uri = format!(
+            "{}/{}",
+            base.trim_end_matches('/'),
+            self.code.to_ascii_lowercase().replace('_', "-")
+        );
+        self
+    }
+
+    /// 出错的具体请求（如请求路径或请求 ID）
+    pub fn with_instance(mut self, instance: impl Into<String>) -> Self {
+        self.instance = Some(instance.into());
+        self
+    }
+
+    /// 序列化为 JSON 响应体
+    pub fn to_json(&self) -> serde_json::Value {
+        serde_json::to_value(self).unwrap_or_default()
+    }
+}
+
+impl From<&AppError> for ProblemDetails {
+    fn from(err: &AppError) -> Self {
+        let mut problem = Self::from_error(err);
+        problem.retry_after_ms = err.retry_after().map(|d| d.as_millis() as u64);
+        problem
+    }
+}
+
+impl From<&DomainError> for ProblemDetails {
+    fn from(err: &DomainError) -> Self {
+        Self::from_error(err)
+    }
+}
+
+fn status_title(status: u16) -> &'static str {
+    match status {
+        400 => "Bad Request",
+        401 => "Unauthorized",
+        403 => "Forbidden",
+        404 => "Not Found",
+        408 => "Request Timeout",
+        409 => "Conflict",
+        412 => "Precondition Failed",
+        422 => "Unprocessable Entity",
+        429 => "Too Many Requests",
+        499 => "Client Closed Request",
+        500 => "Internal Server Error",
+        502 => "Bad Gateway",
+        503 => "Service Unavailable",
+        504 => "Gateway Timeout",
+        s if s < 500 => "Client Error",
+        _ => "Server Error",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::time::Duration;
+
+    #[test]
+    fn maps_errors_to_problem_details() {
+        let problem =
+            ProblemDetails::from(&AppError::rate_limited("u-1", Duration::from_millis(250)))
+                .with_instance("/orders");
+        assert_eq!(problem.title, "Too Many Requests");
+        assert!(problem.retryable);
+        assert_eq!(
+            problem.to_json(),
+            serde_json::json!({
+                "type": "about:blank",
+                "title": "Too Many Requests",
+                "status": 429,
+                "detail": "rate limit exceeded for u-1, retry after 250ms",
+                "instance": "/orders",
+                "code": "RATE_LIMITED",
+                "retryable": true,
+                "retry_after_ms": 250,
+            })
+        );
+
+        let domain = DomainError::not_found("order o-1").with_code("ORDER_NOT_FOUND");
+        let problem = ProblemDetails::from(&domain).with_type_base("https://errors.example.com/");
+        assert_eq!(problem.status, 404);
+        assert_eq!(
+            problem.type_uri,
+            "https://errors.example.com/order-not-found"
+        );
+        assert!(problem.errors.is_empty());
+
+        let fields = AppError::invalid_fields(vec![FieldViolation::new("qty", "must be positive")]);
+        let json = ProblemDetails::from(&fields).to_json();
+        assert_eq!(json["errors"][0]["field"], "qty");
+        assert_eq!(json["code"], "VALIDATION_ERROR");
+
+        let problem = ProblemDetails::from(&AppError::internal("db password rejected"));
+        assert_eq!(problem.status, 500);
+        assert_eq!(problem.detail, "an internal error occurred");
+        assert!(problem.to_json().get("errors").is_none());
+    }
+}
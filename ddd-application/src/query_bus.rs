@@ -31,3 +31,17 @@ pub trait QueryBus: Send + Sync {
         Ok(out)
     }
 }
+
+/// 查询过滤器：在处理器执行前检查或改写查询（如追加行级过滤条件），返回错误时拒绝查询
+pub trait QueryFilter<Q>: Send + Sync {
+    fn apply(&self, ctx: &AppContext, query: &mut Q) -> Result<(), AppError>;
+}
+
+impl<Q, F> QueryFilter<Q> for F
+where
+    F: Fn(&AppContext, &mut Q) -> Result<(), AppError> + Send + Sync,
+{
+    fn apply(&self, ctx: &AppContext, query: &mut Q) -> Result<(), AppError> {
+        self(ctx, query)
+    }
+}
@@ -0,0 +1,149 @@
+//! 查询目录（Query Catalog）
+//!
+//! 对查询总线注册表的结构化描述：查询类型、结果类型、处理器名与说明，
+//! 并可导出为 OpenAPI 文档，供 API 网关与文档工具基于总线注册表生成查询接口。
+//!
+use serde::Serialize;
+use serde_json::{Map, Value, json};
+use std::collections::HashMap;
+
+/// 单个查询处理器的描述
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct QueryDescriptor {
+    /// 查询类型全名
+    pub query_type: &'static str,
+    /// 结果类型全名
+    pub result_type: &'static str,
+    /// 处理器名
+    pub handler_name: String,
+    /// 处理器说明
+    pub description: Option<String>,
+}
+
+impl QueryDescriptor {
+    /// 查询类型短名（去除模块路径）
+    pub fn query_name(&self) -> &'static str {
+        short_type_name(self.query_type)
+    }
+
+    /// 结果类型短名（去除模块路径）
+    pub fn result_name(&self) -> &'static str {
+        short_type_name(self.result_type)
+    }
+}
+
+/// 去除模块路径，保留泛型参数：`a::b::Page<c::Dto>` -> `Page<c::Dto>`
+fn short_type_name(full: &'static str) -> &'static str {
+    let head = full.find('<').map_or(full, |i| &full[..i]);
+    match head.rfind("::") {
+        Some(i) => &full[i + 2..],
+        None => full,
+    }
+}
+
+/// 将查询描述导出为 OpenAPI 3.0 文档
+///
+/// 每个查询映射为 `POST /queries/{Query}`；同一查询注册了多个结果类型时，
+/// 路径为 `POST /queries/{Query}/{Result}`。请求/响应结构无法从类型擦除的注册表中推导，
+/// 以 `object` 占位并通过 `x-rust-type` 标注 Rust 类型。
+pub fn openapi_document(title: &str, version: &str, queries: &[QueryDescriptor]) -> Value {
+    let mut per_query: HashMap<&str, usize> = HashMap::new();
+    for q in queries {
+        *per_query.entry(q.query_name()).or_default() += 1;
+    }
+
+    let mut paths = Map::new();
+    for q in queries {
+        let (path, operation_id) = if per_query[q.query_name()] > 1 {
+            (
+                format!("/queries/{}/{}", q.query_name(), q.result_name()),
+                format!("{}_{}", q.query_name(), q.result_name()),
+            )
+        } else {
+            (
+                format!("/queries/{}", q.query_name()),
+                q.query_name().to_string(),
+            )
+        };
+
+        let mut operation = json!({
+            "operationId": operation_id,
+            "tags": ["queries"],
+            "x-handler": q.handler_name,
+            "requestBody": {
+                "required": true,
+                "content": {
+                    "application/json": {
+                        "schema": { "type": "object", "x-rust-type": q.query_type }
+                    }
+                }
+            },
+            "responses": {
+                "200": {
+                    "description": q.result_name(),
+                    "content": {
+                        "application/json": {
+                            "schema": { "x-rust-type": q.result_type }
+                        }
+                    }
+                }
+            }
+        });
+        if let Some(description) = &q.description {
+            operation["summary"] = Value::String(description.clone());
+        }
+
+        paths.insert(path, json!({ "post": operation }));
+    }
+
+    json!({
+        "openapi": "3.0.3",
+        "info": { "title": title, "version": version },
+        "paths": paths,
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn descriptor(query_type: &'static str, result_type: &'static str) -> QueryDescriptor {
+        QueryDescriptor {
+            query_type,
+            result_type,
+            handler_name: "h".into(),
+            description: None,
+        }
+    }
+
+    #[test]
+    fn short_names_strip_module_paths() {
+        assert_eq!(short_type_name("app::queries::GetUser"), "GetUser");
+        assert_eq!(
+            short_type_name("app::Page<app::dto::UserDto>"),
+            "Page<app::dto::UserDto>"
+        );
+        assert_eq!(short_type_name("usize"), "usize");
+    }
+
+    #[test]
+    fn ambiguous_queries_are_disambiguated_by_result() {
+        let doc = openapi_document(
+            "api",
+            "1.0.0",
+            &[
+                descriptor("app::GetUser", "app::UserDto"),
+                descriptor("app::Get2", "app::NumDto"),
+                descriptor("app::Get2", "app::NameDto"),
+            ],
+        );
+        let paths = doc["paths"].as_object().unwrap();
+        assert!(paths.contains_key("/queries/GetUser"));
+        assert!(paths.contains_key("/queries/Get2/NumDto"));
+        assert!(paths.contains_key("/queries/Get2/NameDto"));
+        assert_eq!(
+            doc["paths"]["/queries/Get2/NumDto"]["post"]["operationId"],
+            "Get2_NumDto"
+        );
+    }
+}
@@ -9,4 +9,14 @@ use async_trait::async_trait;
 pub trait QueryHandler<Q, R>: Send + Sync {
     /// 处理查询并返回结果对象/类型
     async fn handle(&self, ctx: &AppContext, q: Q) -> Result<R, AppError>;
+
+    /// 处理器名（用于注册表内省），默认取实现类型名
+    fn handler_name(&self) -> &str {
+        std::any::type_name::<Self>()
+    }
+
+    /// 处理器说明（用于注册表内省与文档导出）
+    fn description(&self) -> Option<&str> {
+        None
+    }
 }
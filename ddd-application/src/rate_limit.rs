@@ -0,0 +1,321 @@
+//! 命令限流（Rate Limiting）
+//!
+//! `RateLimitedCommandBus` 作为 `CommandBus` 的装饰器（中间件），以令牌桶限制命令分发频率：
+//! - 按执行主体（`actor_id`）、租户（业务语境扩展字段 `tenant`）或自定义函数取限流键，各键独立计桶；
+//! - 取不到键的调用共享同一个匿名桶；
+//! - 闲置超过一个补满周期的桶已恢复为满桶，在新键入桶时清理，跟踪的键数量不随历史键无限增长；
+//! - 令牌不足时不调用处理器，直接返回可重试的 `RATE_LIMITED`（HTTP 429），消息包含建议的等待时长。
+//!
+use crate::{
+    command_bus::{CommandBus, CommandEnvelope, EnvelopeCommandBus},
+    context::AppContext,
+    error::AppError,
+};
+use async_trait::async_trait;
+use dashmap::DashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+type KeyFn = Arc<dyn Fn(&AppContext) -> Option<String> + Send + Sync>;
+
+/// 限流键来源
+#[derive(Clone)]
+pub enum RateLimitKey {
+    /// 执行主体 ID（`EventContext::actor_id`）
+    Actor,
+    /// 租户（业务语境扩展字段中的 `tenant`）
+    Tenant,
+    /// 自定义取键函数
+    Custom(KeyFn),
+}
+
+impl RateLimitKey {
+    pub fn custom<F>(f: F) -> Self
+    where
+        F: Fn(&AppContext) -> Option<String> + Send + Sync + 'static,
+    {
+        Self::Custom(Arc::new(f))
+    }
+
+    /// 从上下文取限流键
+    pub fn resolve(&self, ctx: &AppContext) -> Option<String> {
+        let event_context = &ctx.event_context;
+        match self {
+            Self::Actor => event_context.actor_id().map(ToString::to_string),
+            Self::Tenant => event_context
+                .extensions()
+                .and_then(|ext| ext.get("tenant"))
+                .and_then(|tenant| tenant.as_str())
+                .map(ToString::to_string),
+            Self::Custom(f) => f(ctx),
+        }
+    }
+}
+
+/// 令牌桶参数：桶容量 `capacity`（允许的突发量），每经过 `period` 补满一桶
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RateLimit {
+    pub capacity: u32,
+    pub period: Duration,
+}
+
+impl RateLimit {
+    pub fn new(capacity: u32, period: Duration) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            period,
+        }
+    }
+
+    /// 每秒 `n` 次，突发量为 `n`
+    pub fn per_second(n: u32) -> Self {
+        Self::new(n, Duration::from_secs(1))
+    }
+
+    fn refill_per_sec(&self) -> f64 {
+        f64::from(self.capacity) / self.period.as_secs_f64().max(f64::EPSILON)
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+struct Bucket {
+    tokens: f64,
+    updated: Instant,
+}
+
+/// 按键独立计数的令牌桶
+pub struct TokenBucketLimiter {
+    limit: RateLimit,
+    buckets: DashMap<String, Bucket>,
+    swept_at: Mutex<Instant>,
+}
+
+impl TokenBucketLimiter {
+    pub fn new(limit: RateLimit) -> Self {
+        Self {
+            limit,
+            buckets: DashMap::new(),
+            swept_at: Mutex::new(Instant::now()),
+        }
+    }
+
+    pub fn limit(&self) -> RateLimit {
+        self.limit
+    }
+
+    /// 取一个令牌；不足时返回需等待的时长
+    pub fn try_acquire(&self, key: &str) -> Result<(), Duration> {
+        let now = Instant::now();
+        let capacity = f64::from(self.limit.capacity);
+        let rate = self.limit.refill_per_sec();
+        if !self.buckets.contains_key(key) {
+            self.sweep(now);
+        }
+
+        let mut bucket = self.buckets.entry(key.to_string()).or_insert(Bucket {
+            tokens: capacity,
+            updated: now,
+        });
+        let elapsed = now.duration_since(bucket.updated).as_secs_f64();
+        bucket.tokens = (bucket.tokens + elapsed * rate).min(capacity);
+        bucket.updated = now;
+
+        if bucket.tokens >= 1.0 {
+            bucket.tokens -= 1.0;
+            return Ok(());
+        }
+        Err(Duration::from_secs_f64((1.0 - bucket.tokens) / rate))
+    }
+
+    /// 当前跟踪的键数量
+    pub fn tracked_keys(&self) -> usize {
+        self.buckets.len()
+    }
+
+    /// 移除闲置达一个补满周期的桶（此时已是满桶，与新建等价）；每个周期至多清理一次
+    fn sweep(&self, now: Instant) {
+        let period = self.limit.period;
+        {
+            let mut swept_at = self.swept_at.lock().unwrap();
+            if now.duration_since(*swept_at) < period {
+                return;
+            }
+            *swept_at = now;
+        }
+        self.buckets
+            .retain(|_, bucket| now.duration_since(bucket.updated) < period);
+    }
+}
+
+/// 带限流的命令总线装饰器
+pub struct RateLimitedCommandBus<B> {
+    inner: B,
+    key: RateLimitKey,
+    limiter: TokenBucketLimiter,
+}
+
+impl<B> RateLimitedCommandBus<B>
+where
+    B: CommandBus,
+{
+    pub fn new(inner: B, key: RateLimitKey, limit: RateLimit) -> Self {
+        Self {
+            inner,
+            key,
+            limiter: TokenBucketLimiter::new(limit),
+        }
+    }
+
+    pub fn inner(&self) -> &B {
+        &self.inner
+    }
+
+    pub fn limiter(&self) -> &TokenBucketLimiter {
+        &self.limiter
+    }
+
+    fn check(&self, ctx: &AppContext) -> Result<(), AppError> {
+        let key = self.key.resolve(ctx).unwrap_or_default();
+        self.limiter.try_acquire(&key).map_err(|retry_after| {
+            let key = if key.is_empty() { "anonymous" } else { &key };
+            AppError::rate_limited(key, retry_after)
+        })
+    }
+}
+
+#[async_trait]
+impl<B> CommandBus for RateLimitedCommandBus<B>
+where
+    B: CommandBus,
+{
+    async fn dispatch<C>(&self, ctx: &AppContext, cmd: C) -> Result<(), AppError>
+    where
+        C: Send + 'static,
+    {
+        self.check(ctx)?;
+        self.inner.dispatch(ctx, cmd).await
+    }
+}
+
+#[async_trait]
+impl<B> EnvelopeCommandBus for RateLimitedCommandBus<B>
+where
+    B: EnvelopeCommandBus,
+{
+    async fn dispatch_envelope(
+        &self,
+        ctx: &AppContext,
+        envelope: CommandEnvelope,
+    ) -> Result<(), AppError> {
+        self.check(ctx)?;
+        self.inner.dispatch_envelope(ctx, envelope).await
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::InMemoryCommandBus;
+    use crate::command_handler::CommandHandler;
+    use ddd_domain::domain_event::EventContext;
+    use ddd_domain::error::ErrorCode;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    struct Ping;
+
+    #[derive(Default)]
+    struct PingHandler {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl CommandHandler<Ping> for PingHandler {
+        async fn handle(&self, _ctx: &AppContext, _cmd: Ping) -> Result<(), AppError> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            Ok(())
+        }
+    }
+
+    fn ctx_for(actor: &str, tenant: &str) -> AppContext {
+        AppContext::new(
+            EventContext::builder()
+                .maybe_actor_id(Some(actor.into()))
+                .maybe_extensions(Some(serde_json::json!({ "tenant": tenant })))
+                .build(),
+        )
+    }
+
+    #[tokio::test]
+    async fn throttles_per_key_and_refills_over_time() {
+        let handler = Arc::new(PingHandler::default());
+        let inner = InMemoryCommandBus::new();
+        inner.register::<Ping, _>(handler.clone()).unwrap();
+        let bus = RateLimitedCommandBus::new(
+            inner,
+            RateLimitKey::Actor,
+            RateLimit::new(2, Duration::from_millis(100)),
+        );
+
+        let alice = ctx_for("alice", "acme");
+        bus.dispatch(&alice, Ping).await.unwrap();
+        bus.dispatch(&alice, Ping).await.unwrap();
+        let err = bus.dispatch(&alice, Ping).await.unwrap_err();
+        assert_eq!(err.code(), "RATE_LIMITED");
+        assert_eq!(err.http_status(), 429);
+        assert!(err.is_retryable());
+        assert!(err.to_string().contains("alice"));
+
+        // 其他主体不受影响；被拒绝的命令不会到达处理器
+        bus.dispatch(&ctx_for("bob", "acme"), Ping).await.unwrap();
+        assert_eq!(handler.calls.load(Ordering::SeqCst), 3);
+
+        // 每 50ms 补充一个令牌
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        bus.dispatch_envelope(&alice, CommandEnvelope::new(Ping))
+            .await
+            .unwrap();
+        assert!(bus.dispatch(&alice, Ping).await.is_err());
+        assert_eq!(bus.limiter().tracked_keys(), 2);
+    }
+
+    #[tokio::test]
+    async fn sweeps_idle_buckets_when_new_keys_arrive() {
+        let limiter = TokenBucketLimiter::new(RateLimit::new(1, Duration::from_millis(20)));
+        for key in ["a", "b", "c"] {
+            limiter.try_acquire(key).unwrap();
+        }
+        assert_eq!(limiter.tracked_keys(), 3);
+
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        limiter.try_acquire("d").unwrap();
+        assert_eq!(limiter.tracked_keys(), 1);
+
+        // 清理掉的键按满桶重建
+        limiter.try_acquire("a").unwrap();
+        assert!(limiter.try_acquire("a").is_err());
+    }
+
+    #[tokio::test]
+    async fn keys_by_tenant_and_shares_anonymous_bucket() {
+        let inner = InMemoryCommandBus::new();
+        inner
+            .register::<Ping, _>(Arc::new(PingHandler::default()))
+            .unwrap();
+        let bus = RateLimitedCommandBus::new(inner, RateLimitKey::Tenant, RateLimit::per_second(1));
+
+        bus.dispatch(&ctx_for("alice", "acme"), Ping).await.unwrap();
+        let err = bus
+            .dispatch(&ctx_for("bob", "acme"), Ping)
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("acme"));
+        bus.dispatch(&ctx_for("carol", "globex"), Ping)
+            .await
+            .unwrap();
+
+        let anonymous = AppContext::default();
+        bus.dispatch(&anonymous, Ping).await.unwrap();
+        let err = bus.dispatch(&anonymous, Ping).await.unwrap_err();
+        assert!(err.to_string().contains("anonymous"));
+    }
+}
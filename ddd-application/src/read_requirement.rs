@@ -0,0 +1,200 @@
+//! 读模型新鲜度要求（Read Requirement）
+//!
+//! 命令处理器依据投影（读模型）做校验时（如「用户名是否已被占用」），投影可能尚未追上最新事件。
+//! `ReadModelGate` 基于投影检查点（`CheckpointStore`）在查询前校验新鲜度，避免静默使用陈旧数据：
+//! - `ReadRequirement::AtLeastVersion(token)`：投影检查点不小于写入后得到的一致性令牌；
+//! - `ReadRequirement::MaxStaleness(duration)`：投影检查点在 `duration` 内推进过。
+//!
+//! 未满足时按配置轮询等待，超时返回 `READ_MODEL_STALE`（`ErrorKind::Conflict`，可重试）。
+//! 注意：`MaxStaleness` 以检查点推进时间衡量，长时间无新事件的投影也会被视为陈旧，
+//! 此类场景应优先使用一致性令牌。
+//!
+use crate::{context::AppContext, error::AppError, query_bus::QueryBus};
+use chrono::Utc;
+use ddd_domain::error::{DomainError, ErrorKind};
+use ddd_domain::eventing::CheckpointStore;
+use ddd_domain::persist::SerializedEvent;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 一致性令牌：写入产生的全局事件位点，读取方据此要求投影至少处理到该位置
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct ConsistencyToken(i64);
+
+impl ConsistencyToken {
+    pub fn new(sequence: i64) -> Self {
+        Self(sequence)
+    }
+
+    pub fn sequence(&self) -> i64 {
+        self.0
+    }
+
+    /// 取一批已持久化事件中最大的全局位点；均未分配位点时为空
+    pub fn from_events(events: &[SerializedEvent]) -> Option<Self> {
+        events
+            .iter()
+            .filter_map(SerializedEvent::sequence_number)
+            .max()
+            .map(Self)
+    }
+}
+
+/// 读模型新鲜度要求
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReadRequirement {
+    /// 不做要求
+    Any,
+    /// 投影检查点不小于令牌位点
+    AtLeastVersion(ConsistencyToken),
+    /// 投影检查点在指定时长内推进过
+    MaxStaleness(Duration),
+}
+
+/// 读模型新鲜度校验
+pub struct ReadModelGate {
+    checkpoints: Arc<dyn CheckpointStore>,
+    timeout: Duration,
+    poll_interval: Duration,
+}
+
+impl ReadModelGate {
+    /// 默认不等待：未满足即返回错误
+    pub fn new(checkpoints: Arc<dyn CheckpointStore>) -> Self {
+        Self {
+            checkpoints,
+            timeout: Duration::ZERO,
+            poll_interval: Duration::from_millis(50),
+        }
+    }
+
+    /// 未满足时最多等待 `timeout`，每隔 `poll_interval` 重新检查
+    pub fn with_wait(mut self, timeout: Duration, poll_interval: Duration) -> Self {
+        self.timeout = timeout;
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// 校验投影满足新鲜度要求
+    pub async fn ensure(
+        &self,
+        projection: &str,
+        requirement: &ReadRequirement,
+    ) -> Result<(), AppError> {
+        let deadline = tokio::time::Instant::now() + self.timeout;
+
+        loop {
+            if self.is_satisfied(projection, requirement).await? {
+                return Ok(());
+            }
+
+            let now = tokio::time::Instant::now();
+            if now >= deadline {
+                return Err(stale_error(projection, requirement));
+            }
+            tokio::time::sleep(self.poll_interval.min(deadline - now)).await;
+        }
+    }
+
+    /// 校验新鲜度后分发查询
+    pub async fn query<B, Q, R>(
+        &self,
+        bus: &B,
+        ctx: &AppContext,
+        projection: &str,
+        requirement: &ReadRequirement,
+        q: Q,
+    ) -> Result<R, AppError>
+    where
+        B: QueryBus + ?Sized,
+        Q: Send + 'static,
+        R: Send + 'static,
+    {
+        self.ensure(projection, requirement).await?;
+        bus.dispatch::<Q, R>(ctx, q).await
+    }
+
+    async fn is_satisfied(
+        &self,
+        projection: &str,
+        requirement: &ReadRequirement,
+    ) -> Result<bool, AppError> {
+        if let ReadRequirement::Any = requirement {
+            return Ok(true);
+        }
+
+        let Some(checkpoint) = self.checkpoints.load(projection).await? else {
+            return Ok(false);
+        };
+
+        Ok(match requirement {
+            ReadRequirement::Any => true,
+            ReadRequirement::AtLeastVersion(token) => checkpoint.sequence >= token.sequence(),
+            ReadRequirement::MaxStaleness(max) => {
+                let max = chrono::Duration::from_std(*max).unwrap_or(chrono::Duration::MAX);
+                Utc::now() - checkpoint.updated_at <= max
+            }
+        })
+    }
+}
+
+fn stale_error(projection: &str, requirement: &ReadRequirement) -> AppError {
+    DomainError::new(
+        ErrorKind::Conflict,
+        format!("read model {projection} does not satisfy {requirement:?}"),
+    )
+    .with_code("READ_MODEL_STALE")
+    .into()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ddd_domain::eventing::InMemoryCheckpointStore;
+
+    #[tokio::test]
+    async fn checks_token_and_staleness_against_checkpoint() {
+        let store = Arc::new(InMemoryCheckpointStore::new());
+        let gate = ReadModelGate::new(store.clone());
+        let token = ConsistencyToken::new(5);
+
+        let err = gate
+            .ensure("users_view", &ReadRequirement::AtLeastVersion(token))
+            .await
+            .unwrap_err();
+        assert_eq!(err.kind(), ErrorKind::Conflict);
+        gate.ensure("users_view", &ReadRequirement::Any)
+            .await
+            .unwrap();
+
+        store.save("users_view", 5).await.unwrap();
+        gate.ensure("users_view", &ReadRequirement::AtLeastVersion(token))
+            .await
+            .unwrap();
+        gate.ensure(
+            "users_view",
+            &ReadRequirement::MaxStaleness(Duration::from_secs(60)),
+        )
+        .await
+        .unwrap();
+
+        // 等待期间投影追上令牌
+        let waiting = ReadModelGate::new(store.clone())
+            .with_wait(Duration::from_secs(2), Duration::from_millis(5));
+        let later = ConsistencyToken::new(6);
+        let catch_up = tokio::spawn({
+            let store = store.clone();
+            async move {
+                tokio::time::sleep(Duration::from_millis(20)).await;
+                store.save("users_view", 6).await.unwrap();
+            }
+        });
+        waiting
+            .ensure("users_view", &ReadRequirement::AtLeastVersion(later))
+            .await
+            .unwrap();
+        catch_up.await.unwrap();
+    }
+}
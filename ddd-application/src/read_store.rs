@@ -0,0 +1,414 @@
+//! 内存读模型存储（Read Store）
+//!
+//! 在确定数据库表结构之前，`InMemoryReadStore<T>` 让投影与查询处理器先在内存中原型化读模型：
+//! - 以主键函数为每条记录取 ID（按 ID 有序存放，默认查询顺序稳定）；
+//! - `with_index` 注册二级索引，`find_by` 与 `ReadQuery::index` 通过索引直接定位记录；
+//! - `query()` 组合过滤、排序与分页（`ListParams`），返回带总数的 `Page`；
+//! - `ReadQuery::scoped` 按 `RowFilter` 只返回执行主体可见的记录（见 `row_security`）；
+//! - 实现 `CrudReadModel`，可直接作为 `CrudService` 的读模型。
+//!
+//! 投影处理器通过 `upsert`/`update`/`remove` 维护记录，查询处理器只读访问。
+//!
+use crate::{
+    context::AppContext,
+    crud_service::{CrudReadModel, ListParams},
+    error::AppError,
+    row_security::{OwnedRow, RowFilter},
+};
+use async_trait::async_trait;
+use std::cmp::Ordering;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::fmt::Display;
+use std::sync::{Arc, RwLock};
+
+type KeyFn<T> = Arc<dyn Fn(&T) -> String + Send + Sync>;
+type IndexFn<T> = Arc<dyn Fn(&T) -> Vec<String> + Send + Sync>;
+type Predicate<T> = Box<dyn Fn(&T) -> bool + Send + Sync>;
+type Comparator<T> = Box<dyn Fn(&T, &T) -> Ordering + Send + Sync>;
+
+/// 分页查询结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// 过滤后（分页前）的记录总数
+    pub total: usize,
+    pub offset: usize,
+    pub limit: usize,
+}
+
+impl<T> Page<T> {
+    /// 是否还有下一页
+    pub fn has_more(&self) -> bool {
+        self.offset + self.items.len() < self.total
+    }
+}
+
+struct Index<T> {
+    keys: IndexFn<T>,
+    entries: HashMap<String, BTreeSet<String>>,
+}
+
+struct State<T> {
+    records: BTreeMap<String, T>,
+    indexes: HashMap<String, Index<T>>,
+}
+
+impl<T> State<T> {
+    fn unindex(&mut self, id: &str, record: &T) {
+        for index in self.indexes.values_mut() {
+            for key in (index.keys)(record) {
+                if let Some(ids) = index.entries.get_mut(&key) {
+                    ids.remove(id);
+                    if ids.is_empty() {
+                        index.entries.remove(&key);
+                    }
+                }
+            }
+        }
+    }
+
+    fn index(&mut self, id: &str, record: &T) {
+        for index in self.indexes.values_mut() {
+            for key in (index.keys)(record) {
+                index.entries.entry(key).or_default().insert(id.to_string());
+            }
+        }
+    }
+}
+
+/// 以主键与二级索引组织的内存读模型存储
+pub struct InMemoryReadStore<T> {
+    key: KeyFn<T>,
+    state: RwLock<State<T>>,
+}
+
+impl<T> InMemoryReadStore<T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    /// `key` 从记录中取主键
+    pub fn new<F>(key: F) -> Self
+    where
+        F: Fn(&T) -> String + Send + Sync + 'static,
+    {
+        Self {
+            key: Arc::new(key),
+            state: RwLock::new(State {
+                records: BTreeMap::new(),
+                indexes: HashMap::new(),
+            }),
+        }
+    }
+
+    /// 注册单值二级索引（返回 `None` 的记录不进入索引）
+    pub fn with_index<F>(self, name: impl Into<String>, key: F) -> Self
+    where
+        F: Fn(&T) -> Option<String> + Send + Sync + 'static,
+    {
+        self.with_multi_index(name, move |record| key(record).into_iter().collect())
+    }
+
+    /// 注册多值二级索引（如标签），一条记录可出现在多个索引键下
+    pub fn with_multi_index<F>(self, name: impl Into<String>, keys: F) -> Self
+    where
+        F: Fn(&T) -> Vec<String> + Send + Sync + 'static,
+    {
+        {
+            let mut state = self.state.write().unwrap();
+            let mut index = Index {
+                keys: Arc::new(keys) as IndexFn<T>,
+                entries: HashMap::new(),
+            };
+            for (id, record) in &state.records {
+                for key in (index.keys)(record) {
+                    index.entries.entry(key).or_default().insert(id.clone());
+                }
+            }
+            state.indexes.insert(name.into(), index);
+        }
+        self
+    }
+
+    /// 写入或替换记录，返回被替换的旧记录
+    pub fn upsert(&self, record: T) -> Option<T> {
+        let id = (self.key)(&record);
+        let mut state = self.state.write().unwrap();
+        let previous = state.records.remove(&id);
+        if let Some(previous) = &previous {
+            state.unindex(&id, previous);
+        }
+        state.index(&id, &record);
+        state.records.insert(id, record);
+        previous
+    }
+
+    /// 原地修改已有记录并重建其索引，记录不存在时返回 `false`
+    ///
+    /// 修改不得改变记录主键。
+    pub fn update<F>(&self, id: &str, f: F) -> bool
+    where
+        F: FnOnce(&mut T),
+    {
+        let mut state = self.state.write().unwrap();
+        let Some(mut record) = state.records.remove(id) else {
+            return false;
+        };
+        state.unindex(id, &record);
+        f(&mut record);
+        debug_assert_eq!((self.key)(&record), id, "update must not change the key");
+        state.index(id, &record);
+        state.records.insert(id.to_string(), record);
+        true
+    }
+
+    pub fn remove(&self, id: &str) -> Option<T> {
+        let mut state = self.state.write().unwrap();
+        let record = state.records.remove(id)?;
+        state.unindex(id, &record);
+        Some(record)
+    }
+
+    pub fn get(&self, id: &str) -> Option<T> {
+        self.state.read().unwrap().records.get(id).cloned()
+    }
+
+    /// 按二级索引取记录（按主键排序）；索引未注册时为空
+    pub fn find_by(&self, index: &str, key: &str) -> Vec<T> {
+        self.query().index(index, key).fetch_all()
+    }
+
+    pub fn len(&self) -> usize {
+        self.state.read().unwrap().records.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    pub fn clear(&self) {
+        let mut state = self.state.write().unwrap();
+        state.records.clear();
+        for index in state.indexes.values_mut() {
+            index.entries.clear();
+        }
+    }
+
+    /// 构造查询
+    pub fn query(&self) -> ReadQuery<'_, T> {
+        ReadQuery {
+            store: self,
+            index: None,
+            filters: Vec::new(),
+            sort: None,
+        }
+    }
+}
+
+/// 读模型查询：索引定位 -> 过滤 -> 排序 -> 分页
+pub struct ReadQuery<'a, T> {
+    store: &'a InMemoryReadStore<T>,
+    index: Option<(String, String)>,
+    filters: Vec<Predicate<T>>,
+    sort: Option<Comparator<T>>,
+}
+
+impl<T> ReadQuery<'_, T>
+where
+    T: Clone + Send + Sync + 'static,
+{
+    /// 仅在二级索引 `index` 的 `key` 下查找
+    pub fn index(mut self, index: impl Into<String>, key: impl Into<String>) -> Self {
+        self.index = Some((index.into(), key.into()));
+        self
+    }
+
+    /// 追加过滤条件（多个条件取交集）
+    pub fn filter<F>(mut self, predicate: F) -> Self
+    where
+        F: Fn(&T) -> bool + Send + Sync + 'static,
+    {
+        self.filters.push(Box::new(predicate));
+        self
+    }
+
+    /// 自定义排序；未指定时按主键排序
+    pub fn sort_by<F>(mut self, compare: F) -> Self
+    where
+        F: Fn(&T, &T) -> Ordering + Send + Sync + 'static,
+    {
+        self.sort = Some(Box::new(compare));
+        self
+    }
+
+    /// 按键排序，`descending` 为真时倒序
+    pub fn sort_by_key<K, F>(self, key: F, descending: bool) -> Self
+    where
+        K: Ord,
+        F: Fn(&T) -> K + Send + Sync + 'static,
+    {
+        self.sort_by(move |a, b| {
+            let ordering = key(a).cmp(&key(b));
+            if descending {
+                ordering.reverse()
+            } else {
+                ordering
+            }
+        })
+    }
+
+    /// 取全部匹配记录
+    pub fn fetch_all(self) -> Vec<T> {
+        let mut items = self.matching();
+        if let Some(compare) = &self.sort {
+            items.sort_by(|a, b| compare(a, b));
+        }
+        items
+    }
+
+    /// 取匹配记录总数
+    pub fn count(self) -> usize {
+        self.matching().len()
+    }
+
+    /// 取一页记录
+    pub fn page(self, params: ListParams) -> Page<T> {
+        let items = self.fetch_all();
+        let total = items.len();
+        Page {
+            items: items
+                .into_iter()
+                .skip(params.offset)
+                .take(params.limit)
+                .collect(),
+            total,
+            offset: params.offset,
+            limit: params.limit,
+        }
+    }
+
+    fn matching(&self) -> Vec<T> {
+        let state = self.store.state.read().unwrap();
+        let keep = |record: &T| self.filters.iter().all(|f| f(record));
+        match &self.index {
+            Some((index, key)) => state
+                .indexes
+                .get(index)
+                .and_then(|index| index.entries.get(key))
+                .into_iter()
+                .flatten()
+                .filter_map(|id| state.records.get(id))
+                .filter(|record| keep(record))
+                .cloned()
+                .collect(),
+            None => state
+                .records
+                .values()
+                .filter(|record| keep(record))
+                .cloned()
+                .collect(),
+        }
+    }
+}
+
+impl<T> ReadQuery<'_, T>
+where
+    T: OwnedRow + Clone + Send + Sync + 'static,
+{
+    /// 按行级过滤条件过滤（`None` 表示不受限，如管理员）
+    pub fn scoped(self, filter: Option<RowFilter>) -> Self {
+        match filter {
+            Some(filter) => self.filter(move |record| filter.permits_row(record)),
+            None => self,
+        }
+    }
+}
+
+#[async_trait]
+impl<Id, T> CrudReadModel<Id> for InMemoryReadStore<T>
+where
+    Id: Display + Sync,
+    T: Clone + Send + Sync + 'static,
+{
+    type Dto = T;
+
+    async fn get(&self, _ctx: &AppContext, id: &Id) -> Result<Option<T>, AppError> {
+        Ok(InMemoryReadStore::get(self, &id.to_string()))
+    }
+
+    async fn list(&self, _ctx: &AppContext, params: ListParams) -> Result<Vec<T>, AppError> {
+        Ok(self.query().page(params).items)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(Debug, Clone, PartialEq)]
+    struct OrderView {
+        id: String,
+        customer: String,
+        status: &'static str,
+        total: u64,
+        tags: Vec<String>,
+    }
+
+    fn order(id: &str, customer: &str, status: &'static str, total: u64) -> OrderView {
+        OrderView {
+            id: id.to_string(),
+            customer: customer.to_string(),
+            status,
+            total,
+            tags: Vec::new(),
+        }
+    }
+
+    fn ids(items: &[OrderView]) -> Vec<&str> {
+        items.iter().map(|o| o.id.as_str()).collect()
+    }
+
+    #[test]
+    fn indexes_filters_sorts_and_paginates() {
+        let store = InMemoryReadStore::new(|o: &OrderView| o.id.clone())
+            .with_index("customer", |o: &OrderView| Some(o.customer.clone()))
+            .with_multi_index("tag", |o: &OrderView| o.tags.clone());
+
+        store.upsert(order("o-3", "alice", "paid", 30));
+        store.upsert(order("o-1", "alice", "pending", 10));
+        store.upsert(order("o-2", "bob", "paid", 20));
+        store.upsert(order("o-4", "alice", "paid", 40));
+
+        assert_eq!(
+            ids(&store.find_by("customer", "alice")),
+            ["o-1", "o-3", "o-4"]
+        );
+        assert!(store.find_by("missing", "alice").is_empty());
+
+        let page = store
+            .query()
+            .index("customer", "alice")
+            .filter(|o| o.status == "paid")
+            .sort_by_key(|o| o.total, true)
+            .page(ListParams::new(0, 1));
+        assert_eq!(ids(&page.items), ["o-4"]);
+        assert_eq!(page.total, 2);
+        assert!(page.has_more());
+
+        // 更新后二级索引随之变化
+        assert!(store.update("o-1", |o| {
+            o.customer = "bob".to_string();
+            o.tags.push("vip".to_string());
+        }));
+        assert_eq!(ids(&store.find_by("customer", "bob")), ["o-1", "o-2"]);
+        assert_eq!(ids(&store.find_by("tag", "vip")), ["o-1"]);
+        assert!(!store.update("o-9", |_| {}));
+
+        store.remove("o-2");
+        assert_eq!(ids(&store.find_by("customer", "bob")), ["o-1"]);
+        assert_eq!(store.query().filter(|o| o.total > 15).count(), 2);
+
+        let last = store.query().page(ListParams::new(2, 5));
+        assert_eq!(ids(&last.items), ["o-4"]);
+        assert!(!last.has_more());
+    }
+}
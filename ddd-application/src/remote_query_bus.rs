@@ -0,0 +1,365 @@
+//! 远程查询联邦分发（需启用 `remote-query` 特性）
+//!
+//! 单体按模块拆分为服务时，查询处理器的调用点无需改动：
+//! - `RemoteQueryBus`：本地已注册的查询交由 `InMemoryQueryBus` 处理，本地未注册、
+//!   但经 `route` 声明了远程端点的查询序列化为 `RemoteQueryEnvelope` 转发；
+//! - `QueryTransport`：传输层（HTTP/gRPC 等）由基础设施实现，框架只约定信封与应答；
+//! - `RemoteQueryPolicy`：单次尝试超时与重试次数/退避，仅对可重试错误与超时重试，
+//!   单次超时不超过调用上下文的剩余时长；
+//! - `RemoteQueryRouter`：服务端按查询名反序列化信封并经本地查询总线执行，
+//!   返回 `RemoteQueryReply`（失败时携带错误码、状态码与可重试性）。
+//!
+//! 远端失败以 `REMOTE_QUERY_FAILED` 返回，原始错误码可经 `downcast_ref::<RemoteQueryFailure>` 取回；
+//! 单次尝试超时为 `REMOTE_QUERY_TIMEOUT`（可重试）。
+//!
+use crate::{
+    context::AppContext, error::AppError, inmemory_query_bus::InMemoryQueryBus, query_bus::QueryBus,
+};
+use async_trait::async_trait;
+use dashmap::DashMap;
+use ddd_domain::domain_event::EventContext;
+use ddd_domain::error::{ErrorCode, ErrorKind};
+use serde::{Deserialize, Serialize, de::DeserializeOwned};
+use serde_json::Value;
+use std::any::{Any, TypeId, type_name};
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+use std::time::Duration;
+
+/// 跨服务传输的查询信封
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RemoteQueryEnvelope {
+    /// 查询名（两端按同名注册）
+    pub query: String,
+    pub payload: Value,
+    /// 调用方的业务语境（链路追踪、执行主体）
+    pub context: EventContext,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub idempotency_key: Option<String>,
+    /// 调用方剩余的时长（毫秒），服务端据此设置截止时间
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_ms: Option<u64>,
+}
+
+/// 远端查询的应答
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(tag = "status", rename_all = "snake_case")]
+pub enum RemoteQueryReply {
+    Ok { result: Value },
+    Error(RemoteQueryFailure),
+}
+
+/// 远端返回的查询失败
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RemoteQueryFailure {
+    pub code: String,
+    pub message: String,
+    pub http_status: u16,
+    pub retryable: bool,
+}
+
+impl RemoteQueryFailure {
+    pub fn from_error<E: ErrorCode>(err: &E) -> Self {
+        Self {
+            code: err.code().to_string(),
+            message: err.to_string(),
+            http_status: err.http_status(),
+            retryable: err.is_retryable(),
+        }
+    }
+}
+
+impl fmt::Display for RemoteQueryFailure {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "remote query failed ({}): {}", self.code, self.message)
+    }
+}
+
+impl std::error::Error for RemoteQueryFailure {}
+
+impl From<RemoteQueryFailure> for AppError {
+    fn from(failure: RemoteQueryFailure) -> Self {
+        AppError::wrap(
+            ErrorKind::Custom {
+                http_status: failure.http_status,
+                code: "REMOTE_QUERY_FAILED",
+                retryable: failure.retryable,
+            },
+            "REMOTE_QUERY_FAILED",
+            failure,
+        )
+    }
+}
+
+/// 查询传输层（HTTP/gRPC 等），由基础设施实现
+///
+/// 连接失败等传输错误以 `AppError` 返回，可重试性由实现决定。
+#[async_trait]
+pub trait QueryTransport: Send + Sync {
+    async fn send(
+        &self,
+        endpoint: &str,
+        envelope: &RemoteQueryEnvelope,
+    ) -> Result<RemoteQueryReply, AppError>;
+}
+
+/// 远程查询的超时与重试策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RemoteQueryPolicy {
+    /// 单次尝试的超时
+    pub timeout: Duration,
+    /// 最大尝试次数（含首次，至少 1）
+    pub max_attempts: u32,
+    /// 重试退避，第 n 次重试前等待 `backoff * n`
+    pub backoff: Duration,
+}
+
+impl Default for RemoteQueryPolicy {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(5),
+            max_attempts: 3,
+            backoff: Duration::from_millis(100),
+        }
+    }
+}
+
+type BoxAnySend = Box<dyn Any + Send>;
+
+type EncodeFn = Arc<dyn Fn(BoxAnySend) -> Result<Value, AppError> + Send + Sync>;
+
+type DecodeFn = Arc<dyn Fn(Value) -> Result<BoxAnySend, AppError> + Send + Sync>;
+
+#[derive(Clone)]
+struct RemoteRoute {
+    query: String,
+    endpoint: String,
+    encode: EncodeFn,
+    decode: DecodeFn,
+}
+
+/// 本地优先、未知查询转发远端的查询总线
+pub struct RemoteQueryBus {
+    local: Arc<InMemoryQueryBus>,
+    transport: Arc<dyn QueryTransport>,
+    routes: DashMap<(TypeId, TypeId), RemoteRoute>,
+    policy: RemoteQueryPolicy,
+}
+
+impl RemoteQueryBus {
+    pub fn new(local: Arc<InMemoryQueryBus>, transport: Arc<dyn QueryTransport>) -> Self {
+        Self {
+            local,
+            transport,
+            routes: DashMap::new(),
+            policy: RemoteQueryPolicy::default(),
+        }
+    }
+
+    pub fn with_policy(mut self, policy: RemoteQueryPolicy) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    /// 声明查询 `Q`（结果 `R`）以查询名 `query` 转发到 `endpoint`
+    pub fn route<Q, R>(
+        &self,
+        query: impl Into<String>,
+        endpoint: impl Into<String>,
+    ) -> Result<(), AppError>
+    where
+        Q: Serialize + Send + 'static,
+        R: DeserializeOwned + Send + 'static,
+    {
+        let query = query.into();
+        let encode: EncodeFn = Arc::new(|boxed| {
+            let q = boxed
+                .downcast::<Q>()
+                .map_err(|_| AppError::type_mismatch(type_name::<Q>(), "Box<dyn Any>"))?;
+            serde_json::to_value(&*q).map_err(|e| {
+                AppError::internal(format!("failed to encode {}: {e}", type_name::<Q>()))
+            })
+        });
+        let decode: DecodeFn = Arc::new(|value| {
+            let r: R = serde_json::from_value(value).map_err(|e| {
+                AppError::internal(format!("failed to decode {}: {e}", type_name::<R>()))
+            })?;
+            Ok(Box::new(r) as BoxAnySend)
+        });
+
+        match self.routes.entry((TypeId::of::<Q>(), TypeId::of::<R>())) {
+            dashmap::mapref::entry::Entry::Occupied(_) => {
+                Err(AppError::handler_already_registered(&query))
+            }
+            dashmap::mapref::entry::Entry::Vacant(e) => {
+                e.insert(RemoteRoute {
+                    query,
+                    endpoint: endpoint.into(),
+                    encode,
+                    decode,
+                });
+                Ok(())
+            }
+        }
+    }
+
+    /// 已声明的远程查询：(查询名, 端点)，按查询名排序
+    pub fn remote_queries(&self) -> Vec<(String, String)> {
+        let mut out: Vec<_> = self
+            .routes
+            .iter()
+            .map(|e| (e.query.clone(), e.endpoint.clone()))
+            .collect();
+        out.sort();
+        out
+    }
+
+    async fn dispatch_remote(
+        &self,
+        ctx: &AppContext,
+        route: &RemoteRoute,
+        payload: Value,
+    ) -> Result<BoxAnySend, AppError> {
+        let mut envelope = RemoteQueryEnvelope {
+            query: route.query.clone(),
+            payload,
+            context: ctx.event_context.clone(),
+            idempotency_key: ctx.idempotency_key.clone(),
+            timeout_ms: None,
+        };
+
+        let max_attempts = self.policy.max_attempts.max(1);
+        let mut attempt = 1;
+        loop {
+            let timeout = ctx
+                .remaining()
+                .map_or(self.policy.timeout, |r| r.min(self.policy.timeout));
+            envelope.timeout_ms = Some(timeout.as_millis() as u64);
+
+            let outcome = match tokio::time::timeout(
+                timeout,
+                self.transport.send(&route.endpoint, &envelope),
+            )
+            .await
+            {
+                Ok(Ok(RemoteQueryReply::Ok { result })) => return (route.decode)(result),
+                Ok(Ok(RemoteQueryReply::Error(failure))) => AppError::from(failure),
+                Ok(Err(err)) => err,
+                Err(_) => AppError::remote_timeout(&route.query, timeout),
+            };
+
+            if attempt >= max_attempts || !outcome.is_retryable() {
+                return Err(outcome);
+            }
+            tokio::time::sleep(self.policy.backoff * attempt).await;
+            attempt += 1;
+        }
+    }
+}
+
+#[async_trait]
+impl QueryBus for RemoteQueryBus {
+    async fn dispatch<Q, R>(&self, ctx: &AppContext, q: Q) -> Result<R, AppError>
+    where
+        Q: Send + 'static,
+        R: Send + 'static,
+    {
+        if self.local.handles::<Q, R>() {
+            return self.local.dispatch::<Q, R>(ctx, q).await;
+        }
+
+        let Some(route) = self
+            .routes
+            .get(&(TypeId::of::<Q>(), TypeId::of::<R>()))
+            .map(|r| r.clone())
+        else {
+            return Err(AppError::handler_not_found(type_name::<Q>()));
+        };
+
+        let payload = (route.encode)(Box::new(q))?;
+        let out = ctx
+            .run(&route.query, self.dispatch_remote(ctx, &route, payload))
+            .await?;
+        out.downcast::<R>()
+            .map(|r| *r)
+            .map_err(|_| AppError::type_mismatch(type_name::<R>(), "Box<dyn Any>"))
+    }
+}
+
+type ServeFuture<'a> = Pin<Box<dyn Future<Output = Result<Value, AppError>> + Send + 'a>>;
+
+type ServeFn = Arc<dyn for<'a> Fn(Value, &'a AppContext) -> ServeFuture<'a> + Send + Sync>;
+
+/// 服务端：按查询名将远程信封分发到本地查询总线
+pub struct RemoteQueryRouter {
+    bus: Arc<InMemoryQueryBus>,
+    routes: DashMap<String, ServeFn>,
+}
+
+impl RemoteQueryRouter {
+    pub fn new(bus: Arc<InMemoryQueryBus>) -> Self {
+        Self {
+            bus,
+            routes: DashMap::new(),
+        }
+    }
+
+    /// 以查询名 `query` 对外提供本地查询 `Q`（结果 `R`）
+    pub fn register<Q, R>(&self, query: impl Into<String>) -> Result<(), AppError>
+    where
+        Q: DeserializeOwned + Send + 'static,
+        R: Serialize + Send + 'static,
+    {
+        let query = query.into();
+        let serve: ServeFn = {
+            let bus = self.bus.clone();
+            let name = query.clone();
+            Arc::new(move |payload, ctx| {
+                let bus = bus.clone();
+                let name = name.clone();
+                Box::pin(async move {
+                    let q: Q = serde_json::from_value(payload).map_err(|e| {
+                        AppError::validation(format!("invalid payload for query {name}: {e}"))
+                    })?;
+                    let r: R = bus.dispatch::<Q, R>(ctx, q).await?;
+                    serde_json::to_value(&r).map_err(|e| {
+                        AppError::internal(format!("failed to encode {}: {e}", type_name::<R>()))
+                    })
+                })
+            })
+        };
+
+        match self.routes.entry(query) {
+            dashmap::mapref::entry::Entry::Occupied(e) => {
+                Err(AppError::handler_already_registered(e.key()))
+            }
+            dashmap::mapref::entry::Entry::Vacant(e) => {
+                e.insert(serve);
+                Ok(())
+            }
+        }
+    }
+
+    /// 执行远程信封，失败转换为 `RemoteQueryReply::Error`
+    pub async fn handle(&self, envelope: RemoteQueryEnvelope) -> RemoteQueryReply {
+        match self.serve(envelope).await {
+            Ok(result) => RemoteQueryReply::Ok { result },
+            Err(err) => RemoteQueryReply::Error(RemoteQueryFailure::from_error(&err)),
+        }
+    }
+
+    async fn serve(&self, envelope: RemoteQueryEnvelope) -> Result<Value, AppError> {
+        let Some(serve) = self.routes.get(&envelope.query).map(|s| s.clone()) else {
+            return Err(AppError::handler_not_found(&envelope.query));
+        };
+
+        let mut ctx = AppContext::new(envelope.context);
+        ctx.idempotency_key = envelope.idempotency_key;
+        if let Some(timeout_ms) = envelope.timeout_ms {
+            ctx = ctx.with_timeout(Duration::from_millis(timeout_ms));
+        }
+        (serve)(envelope.payload, &ctx).await
+    }
+}
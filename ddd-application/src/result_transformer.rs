@@ -0,0 +1,153 @@
+//! 查询结果后处理（Result Transformer）
+//!
+//! 在查询总线返回结果前按 DTO 类型统一做脱敏/遮盖，避免在每个处理器中重复实现：
+//! - `ResultTransformer<R>`：对结果 `R` 的通用后处理钩子，可读取 `AppContext` 中的执行主体角色；
+//! - `FieldMask<R>`：声明式字段遮盖规则（如仅对客服隐藏完整卡号），豁免角色可见原值。
+//!
+//! 通过 `InMemoryQueryBus::register_transformer::<R, _>` 注册，
+//! 对返回 `R`、`Option<R>`、`Vec<R>` 的查询均生效。
+//!
+use crate::context::AppContext;
+
+/// 查询结果后处理器
+pub trait ResultTransformer<R>: Send + Sync {
+    fn transform(&self, ctx: &AppContext, result: &mut R);
+}
+
+impl<R, F> ResultTransformer<R> for F
+where
+    F: Fn(&AppContext, &mut R) + Send + Sync,
+{
+    fn transform(&self, ctx: &AppContext, result: &mut R) {
+        self(ctx, result)
+    }
+}
+
+/// 遮盖方式
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Masking {
+    /// 整体替换为 `***`
+    Redact,
+    /// 仅保留末尾 N 个字符，其余替换为 `*`
+    KeepLast(usize),
+    /// 仅保留开头 N 个字符，其余替换为 `*`
+    KeepFirst(usize),
+    /// 清空
+    Clear,
+}
+
+impl Masking {
+    pub fn apply(&self, value: &str) -> String {
+        let len = value.chars().count();
+        let keep = |skip: usize, take: usize| -> String {
+            value
+                .chars()
+                .enumerate()
+                .map(|(i, c)| if i >= skip && i < skip + take { c } else { '*' })
+                .collect()
+        };
+
+        match *self {
+            Masking::Redact => "***".to_string(),
+            Masking::KeepLast(n) => keep(len.saturating_sub(n), n),
+            Masking::KeepFirst(n) => keep(0, n),
+            Masking::Clear => String::new(),
+        }
+    }
+}
+
+struct MaskRule<R> {
+    field: fn(&mut R) -> Option<&mut String>,
+    masking: Masking,
+    exempt_roles: Vec<String>,
+}
+
+/// 声明式字段遮盖规则
+///
+/// ```rust
+/// use ddd_application::context::AppContext;
+/// use ddd_application::result_transformer::{FieldMask, Masking, ResultTransformer};
+///
+/// struct AccountDto {
+///     account_number: String,
+/// }
+///
+/// let mask = FieldMask::new().mask(
+///     |d: &mut AccountDto| Some(&mut d.account_number),
+///     Masking::KeepLast(4),
+///     ["admin"],
+/// );
+///
+/// let mut dto = AccountDto { account_number: "6222020200112233".into() };
+/// mask.transform(&AppContext::default(), &mut dto);
+/// assert_eq!(dto.account_number, "************2233");
+/// ```
+pub struct FieldMask<R> {
+    rules: Vec<MaskRule<R>>,
+}
+
+impl<R> Default for FieldMask<R> {
+    fn default() -> Self {
+        Self { rules: Vec::new() }
+    }
+}
+
+impl<R> FieldMask<R> {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 遮盖字段；`exempt_roles` 中任一角色的执行主体可见原值
+    ///
+    /// `field` 返回 `None` 表示该字段为空（如 `Option<String>` 未赋值），跳过遮盖。
+    pub fn mask<I, S>(
+        mut self,
+        field: fn(&mut R) -> Option<&mut String>,
+        masking: Masking,
+        exempt_roles: I,
+    ) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.rules.push(MaskRule {
+            field,
+            masking,
+            exempt_roles: exempt_roles.into_iter().map(Into::into).collect(),
+        });
+        self
+    }
+}
+
+impl<R> ResultTransformer<R> for FieldMask<R>
+where
+    R: Send + Sync,
+{
+    fn transform(&self, ctx: &AppContext, result: &mut R) {
+        for rule in &self.rules {
+            if rule.exempt_roles.iter().any(|role| ctx.has_role(role)) {
+                continue;
+            }
+            if let Some(value) = (rule.field)(result) {
+                *value = rule.masking.apply(value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn masking_is_char_aware() {
+        assert_eq!(
+            Masking::KeepLast(4).apply("6222020200112233"),
+            "************2233"
+        );
+        assert_eq!(Masking::KeepFirst(1).apply("张三丰"), "张**");
+        assert_eq!(Masking::KeepLast(8).apply("123"), "123");
+        assert_eq!(Masking::Redact.apply("secret"), "***");
+        assert_eq!(Masking::Clear.apply("secret"), "");
+    }
+}
@@ -0,0 +1,201 @@
+//! 读模型行级安全（Row-Level Security）
+//!
+//! 为基于本库构建的读模型提供可复用的“只能看到自己的数据”约束：
+//! - 投影侧：读模型记录携带 `RowOwnership`（`owner_actor_id`/`tenant_id` 列），
+//!   `OwnedRow::record_ownership` 从事件元数据（执行主体 ID、扩展字段 `tenant_id`）自动补齐，
+//!   已记录的归属不会被后续事件改写（归属于创建者）；
+//! - 查询侧：`RowLevelSecurity` 作为查询过滤器注册到 `InMemoryQueryBus::register_query_filter`，
+//!   在处理器执行前为非管理员执行主体向查询追加 `RowFilter`（查询实现 `RowScoped` 接收），
+//!   管理员角色（`AppContext::has_role`）不受限制；
+//! - 处理器按 `RowFilter` 过滤：内存读模型使用 `ReadQuery::scoped`，SQL 读模型将其字段作为
+//!   `owner_actor_id`/`tenant_id` 条件绑定。
+//!
+use crate::{context::AppContext, error::AppError, query_bus::QueryFilter};
+use ddd_domain::{domain_event::EventContext, persist::SerializedEvent};
+use serde::{Deserialize, Serialize};
+
+/// 业务语境扩展字段中的租户 ID 键
+pub const TENANT_ID_EXTENSION: &str = "tenant_id";
+
+/// 读模型记录的归属列
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct RowOwnership {
+    pub owner_actor_id: Option<String>,
+    pub tenant_id: Option<String>,
+}
+
+impl RowOwnership {
+    /// 从事件元数据读取归属：执行主体 ID 与扩展字段 `tenant_id`
+    pub fn from_event(event: &SerializedEvent) -> Self {
+        Self {
+            owner_actor_id: event.actor_id().map(ToString::to_string),
+            tenant_id: event.context()["extensions"][TENANT_ID_EXTENSION]
+                .as_str()
+                .map(ToString::to_string),
+        }
+    }
+
+    /// 从业务语境读取归属（命令处理器或查询侧使用）
+    pub fn from_context(event_context: &EventContext) -> Self {
+        Self {
+            owner_actor_id: event_context.actor_id().map(ToString::to_string),
+            tenant_id: event_context
+                .extensions()
+                .and_then(|ext| ext.get(TENANT_ID_EXTENSION))
+                .and_then(|tenant| tenant.as_str())
+                .map(ToString::to_string),
+        }
+    }
+
+    /// 以事件元数据补齐尚未记录的列，已有值保持不变
+    pub fn record(&mut self, event: &SerializedEvent) {
+        let RowOwnership {
+            owner_actor_id,
+            tenant_id,
+        } = Self::from_event(event);
+        if self.owner_actor_id.is_none() {
+            self.owner_actor_id = owner_actor_id;
+        }
+        if self.tenant_id.is_none() {
+            self.tenant_id = tenant_id;
+        }
+    }
+}
+
+/// 携带归属列的读模型记录
+pub trait OwnedRow {
+    fn ownership(&self) -> &RowOwnership;
+
+    fn ownership_mut(&mut self) -> &mut RowOwnership;
+
+    /// 投影处理事件时调用，自动记录归属列
+    fn record_ownership(&mut self, event: &SerializedEvent) {
+        self.ownership_mut().record(event);
+    }
+}
+
+/// 行级过滤条件：为 `Some` 的列必须与记录相等
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct RowFilter {
+    pub owner_actor_id: Option<String>,
+    pub tenant_id: Option<String>,
+}
+
+impl RowFilter {
+    /// 记录归属是否满足过滤条件（未记录归属的列视为不匹配）
+    pub fn permits(&self, ownership: &RowOwnership) -> bool {
+        let matches = |expected: &Option<String>, actual: &Option<String>| {
+            expected.is_none() || expected == actual
+        };
+        matches(&self.owner_actor_id, &ownership.owner_actor_id)
+            && matches(&self.tenant_id, &ownership.tenant_id)
+    }
+
+    pub fn permits_row<T: OwnedRow>(&self, row: &T) -> bool {
+        self.permits(row.ownership())
+    }
+}
+
+/// 可接收行级过滤条件的查询
+pub trait RowScoped {
+    fn restrict(&mut self, filter: RowFilter);
+}
+
+/// 行级隔离范围
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum RowScope {
+    /// 仅本人创建的记录
+    Owner,
+    /// 同租户的记录
+    #[default]
+    Tenant,
+    /// 同租户且本人创建的记录
+    OwnerAndTenant,
+}
+
+/// 查询总线的行级安全过滤器
+///
+/// ```rust
+/// use ddd_application::context::AppContext;
+/// use ddd_application::row_security::{RowLevelSecurity, RowScope};
+/// use ddd_domain::domain_event::EventContext;
+///
+/// let rls = RowLevelSecurity::new(RowScope::Owner).with_admin_roles(["admin"]);
+///
+/// let user = AppContext::new(EventContext::builder().actor_id("u-1".into()).build());
+/// let filter = rls.filter_for(&user).unwrap().unwrap();
+/// assert_eq!(filter.owner_actor_id.as_deref(), Some("u-1"));
+///
+/// let admin = AppContext::new(EventContext::builder().actor_type("admin".into()).build());
+/// assert!(rls.filter_for(&admin).unwrap().is_none());
+/// ```
+#[derive(Clone, Debug, Default)]
+pub struct RowLevelSecurity {
+    scope: RowScope,
+    admin_roles: Vec<String>,
+}
+
+impl RowLevelSecurity {
+    pub fn new(scope: RowScope) -> Self {
+        Self {
+            scope,
+            admin_roles: Vec::new(),
+        }
+    }
+
+    /// 具有这些角色的执行主体不受行级过滤
+    pub fn with_admin_roles<I, S>(mut self, roles: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        self.admin_roles.extend(roles.into_iter().map(Into::into));
+        self
+    }
+
+    /// 当前执行主体的过滤条件；管理员返回 `None`，缺少范围所需的执行主体 ID/租户 ID 时返回未授权错误
+    pub fn filter_for(&self, ctx: &AppContext) -> Result<Option<RowFilter>, AppError> {
+        if self.admin_roles.iter().any(|role| ctx.has_role(role)) {
+            return Ok(None);
+        }
+
+        let RowOwnership {
+            owner_actor_id,
+            tenant_id,
+        } = RowOwnership::from_context(&ctx.event_context);
+        let (needs_owner, needs_tenant) = match self.scope {
+            RowScope::Owner => (true, false),
+            RowScope::Tenant => (false, true),
+            RowScope::OwnerAndTenant => (true, true),
+        };
+
+        let require = |value: Option<String>, what: &str| match value {
+            Some(value) => Ok(Some(value)),
+            None => Err(AppError::unauthorized(format!(
+                "row-level security requires {what}"
+            ))),
+        };
+
+        Ok(Some(RowFilter {
+            owner_actor_id: if needs_owner {
+                require(owner_actor_id, "an actor id")?
+            } else {
+                None
+            },
+            tenant_id: if needs_tenant {
+                require(tenant_id, "a tenant id")?
+            } else {
+                None
+            },
+        }))
+    }
+}
+
+impl<Q: RowScoped> QueryFilter<Q> for RowLevelSecurity {
+    fn apply(&self, ctx: &AppContext, query: &mut Q) -> Result<(), AppError> {
+        if let Some(filter) = self.filter_for(ctx)? {
+            query.restrict(filter);
+        }
+        Ok(())
+    }
+}
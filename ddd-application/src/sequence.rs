@@ -0,0 +1,283 @@
+//! 业务序号生成（Sequence Generator）
+//!
+//! 订单号、发票号等需要人类可读、按租户/类型递增的编号，事件 ID 与聚合版本均不适合承担。
+//! `SequenceGenerator` 按 `SequenceKey`（租户 + 序列名）维护独立计数器，
+//! 通过 `AppContext::next_sequence` 在命令处理器中获取；断号策略由 `GapPolicy` 控制：
+//! - `AllowGaps`：取号即消耗，命令失败不回收（高并发下最简单）；
+//! - `ReuseReleased`：命令失败时 `release` 归还号码，后续取号优先复用最小的已归还号码。
+//!
+//! 提供内存实现 `InMemorySequenceGenerator`；启用 `infra-sqlx` 特性后提供 `PgSequenceGenerator`。
+//!
+use crate::error::AppError;
+use async_trait::async_trait;
+use std::collections::{BTreeSet, HashMap};
+use std::fmt;
+use std::sync::Mutex;
+
+/// 序列标识：租户（可选）+ 序列名（通常为业务类型，如 `order`）
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub struct SequenceKey {
+    tenant: Option<String>,
+    name: String,
+}
+
+impl SequenceKey {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            tenant: None,
+            name: name.into(),
+        }
+    }
+
+    pub fn for_tenant(tenant: impl Into<String>, name: impl Into<String>) -> Self {
+        Self {
+            tenant: Some(tenant.into()),
+            name: name.into(),
+        }
+    }
+
+    pub fn tenant(&self) -> Option<&str> {
+        self.tenant.as_deref()
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+}
+
+impl fmt::Display for SequenceKey {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match &self.tenant {
+            Some(tenant) => write!(f, "{tenant}/{}", self.name),
+            None => f.write_str(&self.name),
+        }
+    }
+}
+
+/// 断号策略
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum GapPolicy {
+    /// 允许断号：归还的号码被丢弃
+    #[default]
+    AllowGaps,
+    /// 复用归还的号码，尽量保持编号连续
+    ReuseReleased,
+}
+
+/// 业务序号生成器
+#[async_trait]
+pub trait SequenceGenerator: Send + Sync {
+    /// 获取下一个号码（从 1 开始）
+    async fn next(&self, key: &SequenceKey) -> Result<u64, AppError>;
+
+    /// 归还未使用的号码（如命令执行失败）；`AllowGaps` 策略下忽略
+    async fn release(&self, key: &SequenceKey, value: u64) -> Result<(), AppError>;
+}
+
+#[derive(Default)]
+struct Counter {
+    current: u64,
+    released: BTreeSet<u64>,
+}
+
+/// 基于内存的 SequenceGenerator 实现
+#[derive(Default)]
+pub struct InMemorySequenceGenerator {
+    policy: GapPolicy,
+    counters: Mutex<HashMap<SequenceKey, Counter>>,
+}
+
+impl InMemorySequenceGenerator {
+    pub fn new(policy: GapPolicy) -> Self {
+        Self {
+            policy,
+            counters: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 当前已分配的最大号码
+    pub fn current(&self, key: &SequenceKey) -> u64 {
+        self.counters
+            .lock()
+            .unwrap()
+            .get(key)
+            .map_or(0, |c| c.current)
+    }
+}
+
+#[async_trait]
+impl SequenceGenerator for InMemorySequenceGenerator {
+    async fn next(&self, key: &SequenceKey) -> Result<u64, AppError> {
+        let mut counters = self.counters.lock().unwrap();
+        let counter = counters.entry(key.clone()).or_default();
+
+        if let Some(value) = counter.released.pop_first() {
+            return Ok(value);
+        }
+        counter.current += 1;
+        Ok(counter.current)
+    }
+
+    async fn release(&self, key: &SequenceKey, value: u64) -> Result<(), AppError> {
+        if self.policy == GapPolicy::AllowGaps {
+            return Ok(());
+        }
+
+        let mut counters = self.counters.lock().unwrap();
+        let counter = counters.entry(key.clone()).or_default();
+        if value == 0 || value > counter.current {
+            return Err(AppError::validation(format!(
+                "sequence {key} has not issued number {value}"
+            )));
+        }
+        counter.released.insert(value);
+        Ok(())
+    }
+}
+
+#[cfg(feature = "infra-sqlx")]
+pub use postgres::PgSequenceGenerator;
+
+#[cfg(feature = "infra-sqlx")]
+mod postgres {
+    use super::{GapPolicy, SequenceGenerator, SequenceKey};
+    use crate::error::AppError;
+    use async_trait::async_trait;
+    use ddd_domain::error::DomainError;
+    use sqlx::PgPool;
+
+    /// 基于 Postgres 的 SequenceGenerator 实现
+    ///
+    /// 计数器表 `ddd_sequences` 通过 `INSERT ... ON CONFLICT DO UPDATE` 原子递增；
+    /// `ReuseReleased` 策略下归还的号码存入 `ddd_sequence_released`，取号时以
+    /// `FOR UPDATE SKIP LOCKED` 领取最小号码。未设置租户时以空字符串存储。
+    pub struct PgSequenceGenerator {
+        pool: PgPool,
+        policy: GapPolicy,
+    }
+
+    impl PgSequenceGenerator {
+        pub fn new(pool: PgPool, policy: GapPolicy) -> Self {
+            Self { pool, policy }
+        }
+
+        /// 创建所需的表（幂等）
+        pub async fn migrate(&self) -> Result<(), AppError> {
+            sqlx::query(
+                "CREATE TABLE IF NOT EXISTS ddd_sequences (
+                    tenant TEXT NOT NULL,
+                    name TEXT NOT NULL,
+                    value BIGINT NOT NULL,
+                    PRIMARY KEY (tenant, name)
+                )",
+            )
+            .execute(&self.pool)
+            .await
+            .map_err(DomainError::from)?;
+
+            sqlx::query(
+                "CREATE TABLE IF NOT EXISTS ddd_sequence_released (
+                    tenant TEXT NOT NULL,
+                    name TEXT NOT NULL,
+                    value BIGINT NOT NULL,
+                    PRIMARY KEY (tenant, name, value)
+                )",
+            )
+            .execute(&self.pool)
+            .await
+            .map_err(DomainError::from)?;
+
+            Ok(())
+        }
+    }
+
+    #[async_trait]
+    impl SequenceGenerator for PgSequenceGenerator {
+        async fn next(&self, key: &SequenceKey) -> Result<u64, AppError> {
+            let tenant = key.tenant().unwrap_or_default();
+
+            if self.policy == GapPolicy::ReuseReleased {
+                let reused: Option<i64> = sqlx::query_scalar(
+                    "DELETE FROM ddd_sequence_released
+                     WHERE (tenant, name, value) = (
+                        SELECT tenant, name, value FROM ddd_sequence_released
+                        WHERE tenant = $1 AND name = $2
+                        ORDER BY value
+                        LIMIT 1
+                        FOR UPDATE SKIP LOCKED
+                     )
+                     RETURNING value",
+                )
+                .bind(tenant)
+                .bind(key.name())
+                .fetch_optional(&self.pool)
+                .await
+                .map_err(DomainError::from)?;
+
+                if let Some(value) = reused {
+                    return Ok(value as u64);
+                }
+            }
+
+            let value: i64 = sqlx::query_scalar(
+                "INSERT INTO ddd_sequences (tenant, name, value) VALUES ($1, $2, 1)
+                 ON CONFLICT (tenant, name) DO UPDATE SET value = ddd_sequences.value + 1
+                 RETURNING value",
+            )
+            .bind(tenant)
+            .bind(key.name())
+            .fetch_one(&self.pool)
+            .await
+            .map_err(DomainError::from)?;
+
+            Ok(value as u64)
+        }
+
+        async fn release(&self, key: &SequenceKey, value: u64) -> Result<(), AppError> {
+            if self.policy == GapPolicy::AllowGaps {
+                return Ok(());
+            }
+
+            sqlx::query(
+                "INSERT INTO ddd_sequence_released (tenant, name, value) VALUES ($1, $2, $3)
+                 ON CONFLICT DO NOTHING",
+            )
+            .bind(key.tenant().unwrap_or_default())
+            .bind(key.name())
+            .bind(value as i64)
+            .execute(&self.pool)
+            .await
+            .map_err(DomainError::from)?;
+
+            Ok(())
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn counts_per_tenant_and_reuses_released_numbers() {
+        let generator = InMemorySequenceGenerator::new(GapPolicy::ReuseReleased);
+        let acme = SequenceKey::for_tenant("acme", "order");
+        let globex = SequenceKey::for_tenant("globex", "order");
+
+        assert_eq!(generator.next(&acme).await.unwrap(), 1);
+        assert_eq!(generator.next(&acme).await.unwrap(), 2);
+        assert_eq!(generator.next(&globex).await.unwrap(), 1);
+
+        generator.release(&acme, 1).await.unwrap();
+        assert_eq!(generator.next(&acme).await.unwrap(), 1);
+        assert_eq!(generator.next(&acme).await.unwrap(), 3);
+        assert!(generator.release(&acme, 9).await.is_err());
+
+        let gaps = InMemorySequenceGenerator::new(GapPolicy::AllowGaps);
+        let key = SequenceKey::new("invoice");
+        assert_eq!(gaps.next(&key).await.unwrap(), 1);
+        gaps.release(&key, 1).await.unwrap();
+        assert_eq!(gaps.next(&key).await.unwrap(), 2);
+        assert_eq!(gaps.current(&key), 2);
+    }
+}
@@ -0,0 +1,219 @@
+//! 工作单元（Unit of Work）
+//!
+//! 为命令总线上的多命令事务提供暂存与提交：
+//! - 处理器通过 `UnitOfWorkEventRepository` 保存事件时，若处于工作单元范围内则仅暂存，不落库；
+//! - 暂存期间读取会合并已暂存事件，后续命令能看到前序命令对同一聚合的修改；
+//! - 全部命令成功后 `commit` 按底层仓储分组一次性保存，任一命令失败则 `rollback` 丢弃暂存事件。
+//!
+//! 单个底层仓储内的提交是原子的（一次 `save` 批次）；跨多个底层仓储时不保证原子性。
+//!
+use async_trait::async_trait;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::error::DomainResult;
+use ddd_domain::persist::{AggregateIdLister, EventExclusion, EventRepository, SerializedEvent};
+use std::future::Future;
+use std::sync::{Arc, Mutex};
+
+tokio::task_local! {
+    static CURRENT: UnitOfWork;
+}
+
+/// 暂存事件的提交目标
+#[async_trait]
+trait StagedSink: Send + Sync {
+    async fn commit(&self, events: Vec<SerializedEvent>) -> DomainResult<()>;
+}
+
+#[async_trait]
+impl<E: EventRepository> StagedSink for E {
+    async fn commit(&self, events: Vec<SerializedEvent>) -> DomainResult<()> {
+        self.save(events).await
+    }
+}
+
+/// 单个底层仓储上暂存的事件
+struct Staged {
+    repo: usize,
+    sink: Arc<dyn StagedSink>,
+    events: Vec<SerializedEvent>,
+}
+
+/// 工作单元：收集范围内各仓储暂存的事件，统一提交或丢弃
+#[derive(Clone, Default)]
+pub struct UnitOfWork {
+    staged: Arc<Mutex<Vec<Staged>>>,
+}
+
+impl UnitOfWork {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 在工作单元范围内执行 `fut`，期间经 `UnitOfWorkEventRepository` 保存的事件被暂存
+    pub async fn scope<F: Future>(&self, fut: F) -> F::Output {
+        CURRENT.scope(self.clone(), fut).await
+    }
+
+    /// 当前任务是否处于工作单元范围内
+    pub fn is_active() -> bool {
+        CURRENT.try_with(|_| ()).is_ok()
+    }
+
+    /// 已暂存的事件数
+    pub fn staged_len(&self) -> usize {
+        self.staged
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|s| s.events.len())
+            .sum()
+    }
+
+    /// 提交全部暂存事件，返回已提交的事件 ID（按暂存顺序）
+    ///
+    /// 某个仓储保存失败时返回错误，该仓储及之后仓储的事件被丢弃。
+    pub async fn commit(&self) -> DomainResult<Vec<String>> {
+        let staged = std::mem::take(&mut *self.staged.lock().unwrap());
+
+        let mut committed = Vec::new();
+        for Staged { sink, events, .. } in staged {
+            let ids: Vec<String> = events.iter().map(|e| e.event_id().to_string()).collect();
+            sink.commit(events).await?;
+            committed.extend(ids);
+        }
+        Ok(committed)
+    }
+
+    /// 丢弃全部暂存事件
+    pub fn rollback(&self) {
+        self.staged.lock().unwrap().clear();
+    }
+
+    fn stage(&self, repo: usize, sink: Arc<dyn StagedSink>, events: Vec<SerializedEvent>) {
+        let mut staged = self.staged.lock().unwrap();
+        match staged.iter_mut().find(|s| s.repo == repo) {
+            Some(s) => s.events.extend(events),
+            None => staged.push(Staged { repo, sink, events }),
+        }
+    }
+
+    fn staged_events(
+        &self,
+        repo: usize,
+        aggregate_type: &str,
+        aggregate_id: &str,
+    ) -> Vec<SerializedEvent> {
+        self.staged
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|s| s.repo == repo)
+            .flat_map(|s| s.events.iter())
+            .filter(|e| e.aggregate_type() == aggregate_type && e.aggregate_id() == aggregate_id)
+            .cloned()
+            .collect()
+    }
+}
+
+/// 支持工作单元的事件仓储装饰器
+///
+/// 工作单元范围外的读写直接透传给内部仓储。
+pub struct UnitOfWorkEventRepository<E> {
+    inner: Arc<E>,
+}
+
+impl<E> UnitOfWorkEventRepository<E>
+where
+    E: EventRepository + 'static,
+{
+    pub fn new(inner: Arc<E>) -> Self {
+        Self { inner }
+    }
+
+    pub fn inner(&self) -> &Arc<E> {
+        &self.inner
+    }
+
+    fn key(&self) -> usize {
+        Arc::as_ptr(&self.inner) as *const () as usize
+    }
+
+    fn staged_events<A: Aggregate>(&self, aggregate_id: &A::Id) -> Vec<SerializedEvent> {
+        CURRENT
+            .try_with(|uow| uow.staged_events(self.key(), A::TYPE, &aggregate_id.to_string()))
+            .unwrap_or_default()
+    }
+}
+
+#[async_trait]
+impl<E> EventRepository for UnitOfWorkEventRepository<E>
+where
+    E: EventRepository + 'static,
+{
+    async fn get_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+    ) -> DomainResult<Vec<SerializedEvent>> {
+        let mut events = self.inner.get_events::<A>(aggregate_id).await?;
+        events.extend(self.staged_events::<A>(aggregate_id));
+        Ok(events)
+    }
+
+    async fn get_last_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        last_version: usize,
+    ) -> DomainResult<Vec<SerializedEvent>> {
+        let mut events = self
+            .inner
+            .get_last_events::<A>(aggregate_id, last_version)
+            .await?;
+        events.extend(
+            self.staged_events::<A>(aggregate_id)
+                .into_iter()
+                .filter(|e| e.aggregate_version() > last_version),
+        );
+        Ok(events)
+    }
+
+    async fn save(&self, events: Vec<SerializedEvent>) -> DomainResult<()> {
+        let mut pending = Some(events);
+        let sink: Arc<dyn StagedSink> = self.inner.clone();
+        let _ = CURRENT.try_with(|uow| uow.stage(self.key(), sink, pending.take().unwrap()));
+
+        match pending {
+            Some(events) => self.inner.save(events).await,
+            None => Ok(()),
+        }
+    }
+
+    async fn current_version<A: Aggregate>(&self, aggregate_id: &A::Id) -> DomainResult<usize> {
+        let staged = self.staged_events::<A>(aggregate_id);
+        match staged.last() {
+            Some(e) => Ok(e.aggregate_version()),
+            None => self.inner.current_version::<A>(aggregate_id).await,
+        }
+    }
+
+    async fn mark_excluded(&self, event_id: &str, reason: &str, actor: &str) -> DomainResult<()> {
+        self.inner.mark_excluded(event_id, reason, actor).await
+    }
+
+    async fn exclusions(&self) -> DomainResult<Vec<EventExclusion>> {
+        self.inner.exclusions().await
+    }
+}
+
+#[async_trait]
+impl<E> AggregateIdLister for UnitOfWorkEventRepository<E>
+where
+    E: EventRepository + AggregateIdLister + 'static,
+{
+    async fn list_aggregate_ids<A: Aggregate>(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> DomainResult<Vec<String>> {
+        self.inner.list_aggregate_ids::<A>(after, limit).await
+    }
+}
@@ -0,0 +1,183 @@
+use async_trait::async_trait;
+use ddd_application::InMemoryCommandBus;
+use ddd_application::command_bus::CommandBus;
+use ddd_application::command_handler::CommandHandler;
+use ddd_application::context::AppContext;
+use ddd_application::error::AppError;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::aggregate_root::AggregateRoot;
+use ddd_domain::domain_event::{EventContext, next_event_id};
+use ddd_domain::entity::Entity;
+use ddd_domain::error::{DomainError, DomainResult};
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::persist::{EventRepository, EventSourcedRepo, SerializedEvent};
+use ddd_domain::specification::{AggregateExists, AsyncSpecification};
+use ddd_domain::testing::InMemoryEventRepository;
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Customer {
+    name: String,
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum CustomerEvent {
+    Registered { name: String },
+}
+
+impl Aggregate for Customer {
+    const TYPE: &'static str = "customer";
+    type Command = String;
+    type Event = CustomerEvent;
+    type Error = DomainError;
+
+    fn execute(&self, name: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        Ok(vec![CustomerEvent::Registered {
+            id: next_event_id(),
+            aggregate_version: self.version().next(),
+            name,
+        }])
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        let CustomerEvent::Registered {
+            aggregate_version,
+            name,
+            ..
+        } = event;
+        self.name = name.clone();
+        self.version = *aggregate_version;
+    }
+}
+
+/// 统计存在性查询次数的仓储
+#[derive(Default)]
+struct CountingRepo {
+    inner: InMemoryEventRepository,
+    lookups: AtomicUsize,
+}
+
+#[async_trait]
+impl EventRepository for CountingRepo {
+    async fn get_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+    ) -> DomainResult<Vec<SerializedEvent>> {
+        self.inner.get_events::<A>(aggregate_id).await
+    }
+
+    async fn get_last_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        last_version: usize,
+    ) -> DomainResult<Vec<SerializedEvent>> {
+        self.inner
+            .get_last_events::<A>(aggregate_id, last_version)
+            .await
+    }
+
+    async fn save(&self, events: Vec<SerializedEvent>) -> DomainResult<()> {
+        self.inner.save(events).await
+    }
+
+    async fn exists<A: Aggregate>(&self, aggregate_id: &A::Id) -> DomainResult<bool> {
+        self.lookups.fetch_add(1, Ordering::SeqCst);
+        self.inner.exists::<A>(aggregate_id).await
+    }
+
+    async fn exists_many<A: Aggregate>(&self, aggregate_ids: &[A::Id]) -> DomainResult<Vec<bool>> {
+        self.lookups.fetch_add(1, Ordering::SeqCst);
+        self.inner.exists_many::<A>(aggregate_ids).await
+    }
+}
+
+/// 下单时引用的客户须全部存在
+struct PlaceGroupOrder {
+    customers: Vec<String>,
+}
+
+struct PlaceGroupOrderHandler {
+    customer_exists: AggregateExists<Customer, Arc<CountingRepo>>,
+}
+
+#[async_trait]
+impl CommandHandler<PlaceGroupOrder> for PlaceGroupOrderHandler {
+    async fn handle(&self, ctx: &AppContext, cmd: PlaceGroupOrder) -> Result<(), AppError> {
+        let cache = ctx.spec_cache();
+        if !self
+            .customer_exists
+            .all_exist(&cache, &cmd.customers)
+            .await?
+        {
+            return Err(DomainError::not_found("customer not found").into());
+        }
+        // 后续规则再次检查单个客户：命中缓存，不再访问仓储
+        for customer in &cmd.customers {
+            assert!(cache.evaluate(&self.customer_exists, customer).await?);
+        }
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn batches_existence_checks_and_memoizes_per_command() {
+    let repo = Arc::new(CountingRepo::default());
+    let root = AggregateRoot::<Customer, _>::new(EventSourcedRepo::new(
+        repo.clone(),
+        Arc::new(EventUpcasterChain::default()),
+    ));
+    for id in ["c-1", "c-2", "c-3"] {
+        root.execute(
+            &id.to_string(),
+            vec![id.to_uppercase()],
+            EventContext::default(),
+        )
+        .await
+        .unwrap();
+    }
+
+    let bus = InMemoryCommandBus::new();
+    bus.register::<PlaceGroupOrder, _>(Arc::new(PlaceGroupOrderHandler {
+        customer_exists: AggregateExists::new(repo.clone()),
+    }))
+    .unwrap();
+
+    let ctx = AppContext::default();
+    let customers: Vec<String> = vec!["c-1".into(), "c-2".into(), "c-3".into()];
+    repo.lookups.store(0, Ordering::SeqCst);
+    bus.dispatch(
+        &ctx,
+        PlaceGroupOrder {
+            customers: customers.clone(),
+        },
+    )
+    .await
+    .unwrap();
+    assert_eq!(repo.lookups.load(Ordering::SeqCst), 1);
+
+    // 缓存仅在单次命令内有效：下一条命令重新查询
+    bus.dispatch(&ctx, PlaceGroupOrder { customers })
+        .await
+        .unwrap();
+    assert_eq!(repo.lookups.load(Ordering::SeqCst), 2);
+    assert!(ctx.spec_cache().is_empty());
+
+    let err = bus
+        .dispatch(
+            &ctx,
+            PlaceGroupOrder {
+                customers: vec!["c-1".into(), "c-9".into()],
+            },
+        )
+        .await
+        .unwrap_err();
+    assert!(err.to_string().contains("customer not found"));
+
+    let spec = AggregateExists::<Customer, _>::new(repo.clone());
+    assert!(!spec.is_satisfied_by(&"c-9".to_string()).await.unwrap());
+}
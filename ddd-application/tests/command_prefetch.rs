@@ -0,0 +1,206 @@
+use async_trait::async_trait;
+use ddd_application::InMemoryCommandBus;
+use ddd_application::command_bus::{CommandBus, CommandEnvelope, EnvelopeCommandBus};
+use ddd_application::command_handler::CommandHandler;
+use ddd_application::context::AppContext;
+use ddd_application::error::AppError;
+use ddd_application::prefetch::{Prefetch, PrefetchTarget, PrefetchingCommandBus};
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::domain_event::{EventContext, EventEnvelope};
+use ddd_domain::entity::Entity;
+use ddd_domain::error::{DomainError, ErrorCode};
+use ddd_domain::persist::{AggregateCacheStats, AggregateRepository, CachedAggregateRepo};
+use ddd_domain::value_object::Version;
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Account {
+    balance: i64,
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum AccountEvent {
+    Credited { amount: i64 },
+}
+
+impl Aggregate for Account {
+    const TYPE: &'static str = "account";
+    type Command = ();
+    type Event = AccountEvent;
+    type Error = DomainError;
+
+    fn execute(&self, _command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        Ok(vec![])
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        let AccountEvent::Credited { amount, .. } = event;
+        self.balance += amount;
+    }
+}
+
+/// 慢速存储：记录加载次数与最大并发加载数
+#[derive(Default)]
+struct SlowStore {
+    accounts: Mutex<HashMap<String, Account>>,
+    loads: AtomicUsize,
+    in_flight: AtomicUsize,
+    max_in_flight: AtomicUsize,
+}
+
+#[async_trait]
+impl AggregateRepository<Account> for SlowStore {
+    async fn load(&self, aggregate_id: &String) -> Result<Option<Account>, DomainError> {
+        self.loads.fetch_add(1, Ordering::SeqCst);
+        let now = self.in_flight.fetch_add(1, Ordering::SeqCst) + 1;
+        self.max_in_flight.fetch_max(now, Ordering::SeqCst);
+        tokio::time::sleep(Duration::from_millis(30)).await;
+        self.in_flight.fetch_sub(1, Ordering::SeqCst);
+        Ok(self.accounts.lock().unwrap().get(aggregate_id).cloned())
+    }
+
+    async fn save(
+        &self,
+        aggregate: &Account,
+        _events: Vec<AccountEvent>,
+        _context: EventContext,
+    ) -> Result<Vec<EventEnvelope<Account>>, DomainError> {
+        self.accounts
+            .lock()
+            .unwrap()
+            .insert(aggregate.id().clone(), aggregate.clone());
+        Ok(vec![])
+    }
+}
+
+struct TransferFunds {
+    from: String,
+    to: String,
+}
+
+impl Prefetch for TransferFunds {
+    fn prefetch(&self) -> Vec<PrefetchTarget> {
+        vec![
+            PrefetchTarget::of::<Account>(&self.from),
+            PrefetchTarget::of::<Account>(&self.to),
+            PrefetchTarget::new("ledger", "unregistered"),
+        ]
+    }
+}
+
+struct TransferHandler {
+    repo: Arc<CachedAggregateRepo<Arc<SlowStore>>>,
+}
+
+#[async_trait]
+impl CommandHandler<TransferFunds> for TransferHandler {
+    async fn handle(&self, _ctx: &AppContext, cmd: TransferFunds) -> Result<(), AppError> {
+        for id in [&cmd.from, &cmd.to] {
+            AggregateRepository::<Account>::load(&*self.repo, id)
+                .await?
+                .ok_or_else(|| AppError::aggregate_not_found(Account::TYPE, id))?;
+        }
+        Ok(())
+    }
+}
+
+fn setup() -> (
+    Arc<SlowStore>,
+    Arc<CachedAggregateRepo<Arc<SlowStore>>>,
+    PrefetchingCommandBus<InMemoryCommandBus>,
+) {
+    let store = Arc::new(SlowStore::default());
+    for id in ["a-1", "a-2"] {
+        let account = Account::new(id.to_string(), Version::from(1));
+        store
+            .accounts
+            .lock()
+            .unwrap()
+            .insert(id.to_string(), account);
+    }
+    let cache = Arc::new(CachedAggregateRepo::new(store.clone()));
+
+    let inner = InMemoryCommandBus::new();
+    inner
+        .register::<TransferFunds, _>(Arc::new(TransferHandler {
+            repo: cache.clone(),
+        }))
+        .unwrap();
+    let bus = PrefetchingCommandBus::new(inner)
+        .with_warmer(Arc::new(cache.warmer::<Account>()))
+        .with_prefetch::<TransferFunds>();
+    (store, cache, bus)
+}
+
+fn transfer() -> TransferFunds {
+    TransferFunds {
+        from: "a-1".into(),
+        to: "a-2".into(),
+    }
+}
+
+#[tokio::test]
+async fn warms_declared_aggregates_concurrently_before_handler() {
+    let (store, cache, bus) = setup();
+
+    bus.dispatch(&AppContext::default(), transfer())
+        .await
+        .unwrap();
+
+    assert_eq!(store.loads.load(Ordering::SeqCst), 2);
+    assert_eq!(store.max_in_flight.load(Ordering::SeqCst), 2);
+    assert_eq!(
+        cache.stats(),
+        AggregateCacheStats {
+            hits: 2,
+            misses: 0,
+            entries: 2,
+        }
+    );
+
+    // 已缓存的聚合不再回源
+    bus.dispatch_envelope(&AppContext::default(), CommandEnvelope::new(transfer()))
+        .await
+        .unwrap();
+    assert_eq!(store.loads.load(Ordering::SeqCst), 2);
+    assert_eq!(cache.stats().hits, 4);
+}
+
+#[tokio::test]
+async fn prefetch_is_only_a_hint() {
+    let (store, cache, _) = setup();
+    let inner = InMemoryCommandBus::new();
+    inner
+        .register::<TransferFunds, _>(Arc::new(TransferHandler {
+            repo: cache.clone(),
+        }))
+        .unwrap();
+    // 未登记预取的命令按原路径加载
+    let bus = PrefetchingCommandBus::new(inner).with_warmer(Arc::new(cache.warmer::<Account>()));
+    bus.dispatch(&AppContext::default(), transfer())
+        .await
+        .unwrap();
+    assert_eq!(store.max_in_flight.load(Ordering::SeqCst), 1);
+    assert_eq!(cache.stats().misses, 2);
+
+    // 预热失败（聚合不存在）不影响分发，由处理器返回业务错误
+    let (_, _, bus) = setup();
+    let err = bus
+        .dispatch(
+            &AppContext::default(),
+            TransferFunds {
+                from: "a-1".into(),
+                to: "missing".into(),
+            },
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "AGGREGATE_NOT_FOUND");
+}
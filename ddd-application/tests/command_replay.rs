@@ -0,0 +1,117 @@
+use async_trait::async_trait;
+use ddd_application::InMemoryCommandBus;
+use ddd_application::command_audit::{
+    AuditedCommandBus, CommandOutcome, InMemoryCommandAuditStore,
+};
+use ddd_application::command_bus::CommandBus;
+use ddd_application::command_handler::CommandHandler;
+use ddd_application::command_replay::CommandReplayer;
+use ddd_application::command_router::CommandRouter;
+use ddd_application::context::AppContext;
+use ddd_application::error::AppError;
+use ddd_domain::domain_event::EventContext;
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Deposit {
+    account: String,
+    amount: i64,
+}
+
+/// 账户余额处理器；`max_amount` 模拟重构后 `execute` 中新增的限额规则
+#[derive(Default)]
+struct DepositHandler {
+    balances: Mutex<HashMap<String, i64>>,
+    max_amount: Option<i64>,
+    replayed: Mutex<Vec<bool>>,
+}
+
+#[async_trait]
+impl CommandHandler<Deposit> for DepositHandler {
+    async fn handle(&self, ctx: &AppContext, cmd: Deposit) -> Result<(), AppError> {
+        let replay = ctx
+            .event_context
+            .extensions()
+            .and_then(|ext| ext.get("replay"))
+            .is_some();
+        self.replayed.lock().unwrap().push(replay);
+
+        if cmd.amount <= 0 {
+            return Err(AppError::validation("amount must be positive"));
+        }
+        if self.max_amount.is_some_and(|max| cmd.amount > max) {
+            return Err(AppError::validation("amount exceeds limit"));
+        }
+        *self
+            .balances
+            .lock()
+            .unwrap()
+            .entry(cmd.account)
+            .or_default() += cmd.amount;
+        Ok(())
+    }
+}
+
+fn system(handler: Arc<DepositHandler>) -> InMemoryCommandBus {
+    let bus = InMemoryCommandBus::new();
+    bus.register::<Deposit, _>(handler).unwrap();
+    bus
+}
+
+#[tokio::test]
+async fn replays_recorded_commands_against_a_rebuilt_system() {
+    let store = Arc::new(InMemoryCommandAuditStore::new());
+    let production =
+        AuditedCommandBus::new(system(Arc::new(DepositHandler::default())), store.clone())
+            .with_replayable::<Deposit>("deposit");
+
+    let ctx = AppContext::new(
+        EventContext::builder()
+            .maybe_actor_id(Some("teller-1".into()))
+            .build(),
+    );
+    for amount in [50, -1, 500] {
+        let cmd = Deposit {
+            account: "acc-1".into(),
+            amount,
+        };
+        let _ = production.dispatch(&ctx, cmd).await;
+    }
+
+    let records = store.records();
+    assert_eq!(records.len(), 3);
+    assert_eq!(records[0].replay.as_ref().unwrap().name, "deposit");
+    assert_eq!(records[0].replay.as_ref().unwrap().payload["amount"], 50);
+
+    let router = Arc::new(CommandRouter::new());
+    router.register::<Deposit>("deposit").unwrap();
+    let replayer = CommandReplayer::new(router);
+
+    // 行为不变的重建系统：结果全部一致
+    let sandbox = Arc::new(DepositHandler::default());
+    let report = replayer
+        .replay_store(&system(sandbox.clone()), store.as_ref())
+        .await
+        .unwrap();
+    assert_eq!((report.replayed, report.skipped), (3, 0));
+    assert!(report.is_consistent());
+    assert_eq!(sandbox.balances.lock().unwrap()["acc-1"], 550);
+    assert_eq!(*sandbox.replayed.lock().unwrap(), vec![true; 3]);
+
+    // 重构引入限额后，原本成功的第 3 条命令出现分歧
+    let refactored = Arc::new(DepositHandler {
+        max_amount: Some(100),
+        ..Default::default()
+    });
+    let report = replayer.replay(&system(refactored), &records).await;
+    assert_eq!(report.divergences.len(), 1);
+    let divergence = &report.divergences[0];
+    assert_eq!(divergence.index, 2);
+    assert_eq!(divergence.expected, CommandOutcome::Succeeded);
+    assert!(matches!(
+        &divergence.actual,
+        CommandOutcome::Failed { code, .. } if code == "VALIDATION_ERROR"
+    ));
+}
@@ -0,0 +1,286 @@
+use async_trait::async_trait;
+use ddd_application::InMemoryCommandBus;
+use ddd_application::command_handler::CommandHandler;
+use ddd_application::context::AppContext;
+use ddd_application::crud_service::{CrudReadModel, CrudService, IntoCommand, ListParams};
+use ddd_application::error::AppError;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::aggregate_root::AggregateRoot;
+use ddd_domain::domain_event::next_event_id;
+use ddd_domain::entity::Entity;
+use ddd_domain::error::{DomainError, ErrorCode};
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::persist::EventSourcedRepo;
+use ddd_domain::testing::InMemoryEventRepository;
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Product {
+    name: String,
+    price: i64,
+}
+
+#[derive(Debug)]
+enum ProductCommand {
+    Create { name: String, price: i64 },
+    Reprice { price: i64 },
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum ProductEvent {
+    Created { name: String, price: i64 },
+    Repriced { price: i64 },
+}
+
+impl Aggregate for Product {
+    const TYPE: &'static str = "product";
+    type Command = ProductCommand;
+    type Event = ProductEvent;
+    type Error = DomainError;
+
+    fn execute(&self, command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        let id = next_event_id();
+        let aggregate_version = self.version().next();
+
+        match command {
+            ProductCommand::Create { name, price } => Ok(vec![ProductEvent::Created {
+                id,
+                aggregate_version,
+                name,
+                price,
+            }]),
+            ProductCommand::Reprice { .. } if self.version().is_new() => {
+                Err(DomainError::not_found("product not found"))
+            }
+            ProductCommand::Reprice { price } => Ok(vec![ProductEvent::Repriced {
+                id,
+                aggregate_version,
+                price,
+            }]),
+        }
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        match event {
+            ProductEvent::Created {
+                aggregate_version,
+                name,
+                price,
+                ..
+            } => {
+                self.name = name.clone();
+                self.price = *price;
+                self.version = *aggregate_version;
+            }
+            ProductEvent::Repriced {
+                aggregate_version,
+                price,
+                ..
+            } => {
+                self.price = *price;
+                self.version = *aggregate_version;
+            }
+        }
+    }
+}
+
+// 类型化 DTO
+struct CreateProduct {
+    name: String,
+    price: i64,
+}
+
+struct RepriceProduct {
+    price: i64,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct ProductDto {
+    id: String,
+    name: String,
+    price: i64,
+}
+
+// 命令
+struct ProductCmd {
+    id: String,
+    command: ProductCommand,
+}
+
+impl IntoCommand<String> for CreateProduct {
+    type Command = ProductCmd;
+
+    fn into_command(self, id: &String) -> ProductCmd {
+        ProductCmd {
+            id: id.clone(),
+            command: ProductCommand::Create {
+                name: self.name,
+                price: self.price,
+            },
+        }
+    }
+}
+
+impl IntoCommand<String> for RepriceProduct {
+    type Command = ProductCmd;
+
+    fn into_command(self, id: &String) -> ProductCmd {
+        ProductCmd {
+            id: id.clone(),
+            command: ProductCommand::Reprice { price: self.price },
+        }
+    }
+}
+
+/// 由处理器在命令成功后同步维护的读模型（代替异步投影）
+#[derive(Default)]
+struct ProductReadModel {
+    rows: Mutex<BTreeMap<String, ProductDto>>,
+}
+
+#[async_trait]
+impl CrudReadModel<String> for ProductReadModel {
+    type Dto = ProductDto;
+
+    async fn get(&self, _ctx: &AppContext, id: &String) -> Result<Option<ProductDto>, AppError> {
+        Ok(self.rows.lock().unwrap().get(id).cloned())
+    }
+
+    async fn list(
+        &self,
+        _ctx: &AppContext,
+        params: ListParams,
+    ) -> Result<Vec<ProductDto>, AppError> {
+        Ok(self
+            .rows
+            .lock()
+            .unwrap()
+            .values()
+            .skip(params.offset)
+            .take(params.limit)
+            .cloned()
+            .collect())
+    }
+}
+
+struct ProductHandler {
+    root: AggregateRoot<Product, EventSourcedRepo<InMemoryEventRepository>>,
+    read_model: Arc<ProductReadModel>,
+}
+
+#[async_trait]
+impl CommandHandler<ProductCmd> for ProductHandler {
+    async fn handle(&self, ctx: &AppContext, cmd: ProductCmd) -> Result<(), AppError> {
+        self.root
+            .execute(&cmd.id, vec![cmd.command], ctx.event_context.clone())
+            .await?;
+        let product = self.root.load(&cmd.id).await?.expect("product exists");
+        self.read_model.rows.lock().unwrap().insert(
+            cmd.id.clone(),
+            ProductDto {
+                id: cmd.id,
+                name: product.name,
+                price: product.price,
+            },
+        );
+        Ok(())
+    }
+}
+
+type ProductService = CrudService<Product, InMemoryCommandBus, ProductReadModel>;
+
+fn setup() -> ProductService {
+    let read_model = Arc::new(ProductReadModel::default());
+    let handler = Arc::new(ProductHandler {
+        root: AggregateRoot::new(EventSourcedRepo::new(
+            Arc::new(InMemoryEventRepository::new()),
+            Arc::new(EventUpcasterChain::default()),
+        )),
+        read_model: read_model.clone(),
+    });
+    let bus = InMemoryCommandBus::new();
+    bus.register::<ProductCmd, _>(handler).unwrap();
+
+    let seq = AtomicU64::new(0);
+    CrudService::new(Arc::new(bus), read_model, move || {
+        format!("p-{}", seq.fetch_add(1, Ordering::SeqCst) + 1)
+    })
+}
+
+#[tokio::test]
+async fn creates_updates_and_reads_through_the_service() {
+    let service = setup();
+    let ctx = AppContext::default();
+
+    let first = service
+        .create(
+            &ctx,
+            CreateProduct {
+                name: "pen".into(),
+                price: 3,
+            },
+        )
+        .await
+        .unwrap();
+    let second = service
+        .create(
+            &ctx,
+            CreateProduct {
+                name: "ink".into(),
+                price: 8,
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!((first.as_str(), second.as_str()), ("p-1", "p-2"));
+
+    service
+        .update(&ctx, &first, RepriceProduct { price: 5 })
+        .await
+        .unwrap();
+    assert_eq!(
+        service.get_required(&ctx, &first).await.unwrap(),
+        ProductDto {
+            id: "p-1".into(),
+            name: "pen".into(),
+            price: 5,
+        }
+    );
+
+    let page = service.list(&ctx, ListParams::new(1, 10)).await.unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].name, "ink");
+    assert_eq!(
+        service
+            .list(&ctx, ListParams::default())
+            .await
+            .unwrap()
+            .len(),
+        2
+    );
+}
+
+#[tokio::test]
+async fn surfaces_missing_aggregates_and_command_errors() {
+    let service = setup();
+    let ctx = AppContext::default();
+    let missing = "p-9".to_string();
+
+    assert!(service.get(&ctx, &missing).await.unwrap().is_none());
+    let err = service.get_required(&ctx, &missing).await.unwrap_err();
+    assert_eq!(err.code(), "AGGREGATE_NOT_FOUND");
+    assert_eq!(err.to_string(), "product not found: p-9");
+
+    let err = service
+        .update(&ctx, &missing, RepriceProduct { price: 1 })
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), DomainError::not_found("").code());
+    assert!(service.get(&ctx, &missing).await.unwrap().is_none());
+}
@@ -0,0 +1,216 @@
+use async_trait::async_trait;
+use ddd_application::InMemoryCommandBus;
+use ddd_application::command_bus::{CommandBus, CommandEnvelope, EnvelopeCommandBus};
+use ddd_application::command_handler::CommandHandler;
+use ddd_application::context::AppContext;
+use ddd_application::error::AppError;
+use ddd_application::unit_of_work::UnitOfWorkEventRepository;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::aggregate_root::AggregateRoot;
+use ddd_domain::domain_event::next_event_id;
+use ddd_domain::entity::Entity;
+use ddd_domain::error::{DomainError, ErrorCode};
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::persist::{EventRepository, EventSourcedRepo};
+use ddd_domain::testing::InMemoryEventRepository;
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Account {
+    balance: i64,
+}
+
+#[derive(Debug)]
+enum AccountCommand {
+    Deposit(i64),
+    Withdraw(i64),
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum AccountEvent {
+    Deposited { amount: i64 },
+    Withdrawn { amount: i64 },
+}
+
+impl Aggregate for Account {
+    const TYPE: &'static str = "account";
+    type Command = AccountCommand;
+    type Event = AccountEvent;
+    type Error = DomainError;
+
+    fn execute(&self, command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        let id = next_event_id();
+        let aggregate_version = self.version().next();
+
+        match command {
+            AccountCommand::Deposit(amount) => Ok(vec![AccountEvent::Deposited {
+                id,
+                aggregate_version,
+                amount,
+            }]),
+            AccountCommand::Withdraw(amount) if amount > self.balance => {
+                Err(DomainError::invalid_state("insufficient balance"))
+            }
+            AccountCommand::Withdraw(amount) => Ok(vec![AccountEvent::Withdrawn {
+                id,
+                aggregate_version,
+                amount,
+            }]),
+        }
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        match event {
+            AccountEvent::Deposited {
+                aggregate_version,
+                amount,
+                ..
+            } => {
+                self.balance += amount;
+                self.version = *aggregate_version;
+            }
+            AccountEvent::Withdrawn {
+                aggregate_version,
+                amount,
+                ..
+            } => {
+                self.balance -= amount;
+                self.version = *aggregate_version;
+            }
+        }
+    }
+}
+
+struct Deposit {
+    account: String,
+    amount: i64,
+}
+
+struct Withdraw {
+    account: String,
+    amount: i64,
+}
+
+type AccountRepo = EventSourcedRepo<UnitOfWorkEventRepository<InMemoryEventRepository>>;
+
+struct AccountHandler {
+    root: AggregateRoot<Account, AccountRepo>,
+}
+
+#[async_trait]
+impl CommandHandler<Deposit> for AccountHandler {
+    async fn handle(&self, ctx: &AppContext, cmd: Deposit) -> Result<(), AppError> {
+        self.root
+            .execute(
+                &cmd.account,
+                vec![AccountCommand::Deposit(cmd.amount)],
+                ctx.event_context.clone(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl CommandHandler<Withdraw> for AccountHandler {
+    async fn handle(&self, ctx: &AppContext, cmd: Withdraw) -> Result<(), AppError> {
+        self.root
+            .execute(
+                &cmd.account,
+                vec![AccountCommand::Withdraw(cmd.amount)],
+                ctx.event_context.clone(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+fn setup() -> (InMemoryCommandBus, Arc<InMemoryEventRepository>) {
+    let store = Arc::new(InMemoryEventRepository::new());
+    let repo = UnitOfWorkEventRepository::new(store.clone());
+    let handler = Arc::new(AccountHandler {
+        root: AggregateRoot::new(EventSourcedRepo::new(
+            Arc::new(repo),
+            Arc::new(EventUpcasterChain::default()),
+        )),
+    });
+
+    let bus = InMemoryCommandBus::new();
+    bus.register::<Deposit, _>(handler.clone()).unwrap();
+    bus.register::<Withdraw, _>(handler).unwrap();
+    (bus, store)
+}
+
+fn deposit(account: &str, amount: i64) -> CommandEnvelope {
+    CommandEnvelope::new(Deposit {
+        account: account.into(),
+        amount,
+    })
+}
+
+fn withdraw(account: &str, amount: i64) -> CommandEnvelope {
+    CommandEnvelope::new(Withdraw {
+        account: account.into(),
+        amount,
+    })
+}
+
+#[tokio::test]
+async fn commits_all_commands_together() {
+    let (bus, store) = setup();
+    let ctx = AppContext::default();
+
+    // 同一工作单元内，后续命令能看到前序命令暂存的事件
+    let ids = bus
+        .dispatch_all(
+            &ctx,
+            vec![deposit("a", 100), deposit("b", 20), withdraw("a", 30)],
+        )
+        .await
+        .unwrap();
+    assert_eq!(ids.len(), 3);
+
+    let a = store.get_events::<Account>(&"a".to_string()).await.unwrap();
+    assert_eq!(a.len(), 2);
+    assert_eq!(a[1].aggregate_version(), 2);
+    assert_eq!(ids[2], a[1].event_id());
+    assert!(store.exists::<Account>(&"b".to_string()).await.unwrap());
+}
+
+#[tokio::test]
+async fn discards_staged_events_when_a_later_command_fails() {
+    let (bus, store) = setup();
+    let ctx = AppContext::default();
+
+    let err = bus
+        .dispatch_all(&ctx, vec![deposit("a", 100), withdraw("b", 50)])
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), DomainError::invalid_state("").code());
+    assert!(!store.exists::<Account>(&"a".to_string()).await.unwrap());
+
+    // 未注册的命令同样使整体失败
+    struct Unknown;
+    let err = bus
+        .dispatch_all(&ctx, vec![deposit("a", 100), CommandEnvelope::new(Unknown)])
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "HANDLER_NOT_FOUND");
+    assert!(!store.exists::<Account>(&"a".to_string()).await.unwrap());
+
+    // 工作单元范围外正常透传
+    bus.dispatch(
+        &ctx,
+        Deposit {
+            account: "a".into(),
+            amount: 10,
+        },
+    )
+    .await
+    .unwrap();
+    assert!(store.exists::<Account>(&"a".to_string()).await.unwrap());
+}
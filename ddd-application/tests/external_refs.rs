@@ -0,0 +1,82 @@
+use ddd_application::context::AppContext;
+use ddd_application::external_ref::{EXTERNAL_REF_TAKEN, ExternalRef, InMemoryExternalRefStore};
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::error::{DomainError, ErrorCode};
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Customer {
+    name: String,
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum CustomerEvent {
+    Registered { name: String },
+}
+
+impl Aggregate for Customer {
+    const TYPE: &'static str = "customer";
+    type Command = ();
+    type Event = CustomerEvent;
+    type Error = DomainError;
+
+    fn execute(&self, _command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        Ok(vec![])
+    }
+
+    fn apply(&mut self, _event: &Self::Event) {}
+}
+
+#[tokio::test]
+async fn acl_translator_resolves_or_links_external_ids() {
+    let ctx = AppContext::default().with_external_refs(Arc::new(InMemoryExternalRefStore::new()));
+    let refs = ctx.external_refs::<Customer>().unwrap();
+    let stripe = ExternalRef::new("stripe", "cus_1");
+
+    // 首次收到外部客户：建立映射，翻译器据此创建聚合
+    let (id, created) = refs
+        .resolve_or_link(&stripe, || "c-1".to_string())
+        .await
+        .unwrap();
+    assert_eq!((id.as_str(), created), ("c-1", true));
+
+    // 重复收到：返回已有聚合，不再新建
+    let (id, created) = refs
+        .resolve_or_link(&stripe, || "c-2".to_string())
+        .await
+        .unwrap();
+    assert_eq!((id.as_str(), created), ("c-1", false));
+
+    // 命令处理器内按外部引用查找
+    assert_eq!(refs.require(&stripe).await.unwrap(), "c-1");
+    let missing = refs
+        .require(&ExternalRef::new("stripe", "cus_9"))
+        .await
+        .unwrap_err();
+    assert_eq!(missing.code(), "AGGREGATE_NOT_FOUND");
+
+    // 同一聚合在同一外部系统中只能有一个外部 ID
+    let err = refs
+        .link(&ExternalRef::new("stripe", "cus_2"), &"c-1".to_string())
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), EXTERNAL_REF_TAKEN);
+    assert_eq!(err.http_status(), 409);
+    assert_eq!(
+        refs.references(&"c-1".to_string()).await.unwrap(),
+        vec![stripe]
+    );
+}
+
+#[test]
+fn missing_store_is_reported() {
+    let err = AppContext::default()
+        .external_refs::<Customer>()
+        .err()
+        .unwrap();
+    assert_eq!(err.code(), "INTERNAL_ERROR");
+}
@@ -0,0 +1,250 @@
+#![cfg(feature = "remote-query")]
+use async_trait::async_trait;
+use ddd_application::InMemoryQueryBus;
+use ddd_application::context::AppContext;
+use ddd_application::error::AppError;
+use ddd_application::query_bus::QueryBus;
+use ddd_application::query_handler::QueryHandler;
+use ddd_application::remote_query_bus::{
+    QueryTransport, RemoteQueryBus, RemoteQueryEnvelope, RemoteQueryFailure, RemoteQueryPolicy,
+    RemoteQueryReply, RemoteQueryRouter,
+};
+use ddd_domain::domain_event::EventContext;
+use ddd_domain::error::ErrorCode;
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU32, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct OrderSummary {
+    order_id: String,
+}
+
+#[derive(Debug, PartialEq, Serialize, Deserialize)]
+struct OrderSummaryDto {
+    order_id: String,
+    total: i64,
+    requested_by: Option<String>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct Ping;
+
+struct OrderSummaryHandler;
+
+#[async_trait]
+impl QueryHandler<OrderSummary, OrderSummaryDto> for OrderSummaryHandler {
+    async fn handle(&self, ctx: &AppContext, q: OrderSummary) -> Result<OrderSummaryDto, AppError> {
+        if q.order_id == "missing" {
+            return Err(AppError::aggregate_not_found("order", &q.order_id));
+        }
+        Ok(OrderSummaryDto {
+            order_id: q.order_id,
+            total: 42,
+            requested_by: ctx.event_context.actor_id().map(ToString::to_string),
+        })
+    }
+}
+
+struct PingHandler;
+
+#[async_trait]
+impl QueryHandler<Ping, String> for PingHandler {
+    async fn handle(&self, _ctx: &AppContext, _q: Ping) -> Result<String, AppError> {
+        Ok("local".to_string())
+    }
+}
+
+/// 进程内回环传输：经 JSON 往返后交给远端服务的路由器
+struct Loopback {
+    router: RemoteQueryRouter,
+    endpoints: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl QueryTransport for Loopback {
+    async fn send(
+        &self,
+        endpoint: &str,
+        envelope: &RemoteQueryEnvelope,
+    ) -> Result<RemoteQueryReply, AppError> {
+        self.endpoints.lock().unwrap().push(endpoint.to_string());
+        let wire = serde_json::to_string(envelope).unwrap();
+        let reply = self
+            .router
+            .handle(serde_json::from_str(&wire).unwrap())
+            .await;
+        let wire = serde_json::to_string(&reply).unwrap();
+        Ok(serde_json::from_str(&wire).unwrap())
+    }
+}
+
+fn orders_service() -> RemoteQueryRouter {
+    let bus = Arc::new(InMemoryQueryBus::new());
+    bus.register::<OrderSummary, OrderSummaryDto, _>(Arc::new(OrderSummaryHandler))
+        .unwrap();
+    let router = RemoteQueryRouter::new(bus);
+    router
+        .register::<OrderSummary, OrderSummaryDto>("orders.summary")
+        .unwrap();
+    router
+}
+
+fn caller_ctx() -> AppContext {
+    AppContext::new(
+        EventContext::builder()
+            .maybe_actor_id(Some("u-1".into()))
+            .build(),
+    )
+}
+
+#[tokio::test]
+async fn forwards_unknown_queries_and_keeps_local_ones_local() {
+    let local = Arc::new(InMemoryQueryBus::new());
+    local
+        .register::<Ping, String, _>(Arc::new(PingHandler))
+        .unwrap();
+    let transport = Arc::new(Loopback {
+        router: orders_service(),
+        endpoints: Mutex::new(Vec::new()),
+    });
+    let bus = RemoteQueryBus::new(local, transport.clone());
+    bus.route::<OrderSummary, OrderSummaryDto>("orders.summary", "http://orders/queries")
+        .unwrap();
+    assert!(
+        bus.route::<OrderSummary, OrderSummaryDto>("orders.summary", "http://other")
+            .is_err()
+    );
+
+    let ctx = caller_ctx();
+    let dto: OrderSummaryDto = bus
+        .dispatch(
+            &ctx,
+            OrderSummary {
+                order_id: "o-1".into(),
+            },
+        )
+        .await
+        .unwrap();
+    assert_eq!(
+        dto,
+        OrderSummaryDto {
+            order_id: "o-1".into(),
+            total: 42,
+            requested_by: Some("u-1".into()),
+        }
+    );
+
+    let pong: String = bus.dispatch(&ctx, Ping).await.unwrap();
+    assert_eq!(pong, "local");
+    assert_eq!(
+        transport.endpoints.lock().unwrap().as_slice(),
+        &["http://orders/queries".to_string()]
+    );
+
+    // 既无本地处理器也无远程路由
+    let err = bus.dispatch::<Ping, u64>(&ctx, Ping).await.unwrap_err();
+    assert_eq!(err.code(), "HANDLER_NOT_FOUND");
+}
+
+#[tokio::test]
+async fn remote_failures_keep_original_code_and_are_not_retried() {
+    let transport = Arc::new(Loopback {
+        router: orders_service(),
+        endpoints: Mutex::new(Vec::new()),
+    });
+    let bus = RemoteQueryBus::new(Arc::new(InMemoryQueryBus::new()), transport.clone());
+    bus.route::<OrderSummary, OrderSummaryDto>("orders.summary", "orders")
+        .unwrap();
+
+    let err = bus
+        .dispatch::<_, OrderSummaryDto>(
+            &caller_ctx(),
+            OrderSummary {
+                order_id: "missing".into(),
+            },
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "REMOTE_QUERY_FAILED");
+    assert_eq!(err.http_status(), 404);
+    let failure = err.downcast_ref::<RemoteQueryFailure>().unwrap();
+    assert_eq!(failure.code, "AGGREGATE_NOT_FOUND");
+    assert_eq!(transport.endpoints.lock().unwrap().len(), 1);
+}
+
+/// 前 `failures` 次尝试超时或返回可重试错误
+struct Unreliable {
+    attempts: AtomicU32,
+    failures: u32,
+    hang: bool,
+}
+
+#[async_trait]
+impl QueryTransport for Unreliable {
+    async fn send(
+        &self,
+        _endpoint: &str,
+        envelope: &RemoteQueryEnvelope,
+    ) -> Result<RemoteQueryReply, AppError> {
+        let attempt = self.attempts.fetch_add(1, Ordering::SeqCst) + 1;
+        assert!(envelope.timeout_ms.is_some());
+        if attempt <= self.failures {
+            if self.hang {
+                tokio::time::sleep(Duration::from_secs(60)).await;
+            }
+            return Ok(RemoteQueryReply::Error(RemoteQueryFailure {
+                code: "UNAVAILABLE".into(),
+                message: "service unavailable".into(),
+                http_status: 503,
+                retryable: true,
+            }));
+        }
+        Ok(RemoteQueryReply::Ok {
+            result: serde_json::json!("pong"),
+        })
+    }
+}
+
+fn policy(max_attempts: u32) -> RemoteQueryPolicy {
+    RemoteQueryPolicy {
+        timeout: Duration::from_millis(20),
+        max_attempts,
+        backoff: Duration::from_millis(1),
+    }
+}
+
+#[tokio::test]
+async fn retries_retryable_failures_and_timeouts_within_policy() {
+    for hang in [false, true] {
+        let transport = Arc::new(Unreliable {
+            attempts: AtomicU32::new(0),
+            failures: 2,
+            hang,
+        });
+        let bus = RemoteQueryBus::new(Arc::new(InMemoryQueryBus::new()), transport.clone())
+            .with_policy(policy(3));
+        bus.route::<Ping, String>("ping", "edge").unwrap();
+
+        let pong: String = bus.dispatch(&AppContext::default(), Ping).await.unwrap();
+        assert_eq!(pong, "pong");
+        assert_eq!(transport.attempts.load(Ordering::SeqCst), 3);
+    }
+
+    let transport = Arc::new(Unreliable {
+        attempts: AtomicU32::new(0),
+        failures: 5,
+        hang: true,
+    });
+    let bus = RemoteQueryBus::new(Arc::new(InMemoryQueryBus::new()), transport.clone())
+        .with_policy(policy(2));
+    bus.route::<Ping, String>("ping", "edge").unwrap();
+
+    let err = bus
+        .dispatch::<_, String>(&AppContext::default(), Ping)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "REMOTE_QUERY_TIMEOUT");
+    assert_eq!(transport.attempts.load(Ordering::SeqCst), 2);
+}
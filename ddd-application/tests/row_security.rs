@@ -0,0 +1,170 @@
+use async_trait::async_trait;
+use chrono::Utc;
+use ddd_application::InMemoryQueryBus;
+use ddd_application::context::AppContext;
+use ddd_application::error::AppError;
+use ddd_application::query_bus::QueryBus;
+use ddd_application::query_handler::QueryHandler;
+use ddd_application::read_store::InMemoryReadStore;
+use ddd_application::row_security::{
+    OwnedRow, RowFilter, RowLevelSecurity, RowOwnership, RowScope, RowScoped,
+};
+use ddd_domain::domain_event::EventContext;
+use ddd_domain::error::ErrorCode;
+use ddd_domain::persist::SerializedEvent;
+use serde_json::json;
+use std::sync::Arc;
+
+#[derive(Debug, Clone)]
+struct DocumentRow {
+    id: String,
+    title: String,
+    ownership: RowOwnership,
+}
+
+impl OwnedRow for DocumentRow {
+    fn ownership(&self) -> &RowOwnership {
+        &self.ownership
+    }
+
+    fn ownership_mut(&mut self) -> &mut RowOwnership {
+        &mut self.ownership
+    }
+}
+
+fn event(document_id: &str, title: &str, actor_id: &str, tenant_id: &str) -> SerializedEvent {
+    SerializedEvent::builder()
+        .event_id(format!("{document_id}-{actor_id}"))
+        .event_type("DocumentSaved".to_string())
+        .event_version(1)
+        .aggregate_id(document_id.to_string())
+        .aggregate_type("document".to_string())
+        .aggregate_version(1)
+        .actor_id(actor_id.to_string())
+        .occurred_at(Utc::now())
+        .payload(json!({ "title": title }))
+        .context(json!({ "extensions": { "tenant_id": tenant_id } }))
+        .build()
+}
+
+/// 投影：首次保存时记录归属，后续保存只更新标题
+fn project(store: &InMemoryReadStore<DocumentRow>, event: &SerializedEvent) {
+    let title = event.payload()["title"].as_str().unwrap().to_string();
+    let mut row = store.get(event.aggregate_id()).unwrap_or(DocumentRow {
+        id: event.aggregate_id().to_string(),
+        title: String::new(),
+        ownership: RowOwnership::default(),
+    });
+    row.title = title;
+    row.record_ownership(event);
+    store.upsert(row);
+}
+
+#[derive(Default)]
+struct ListDocuments {
+    filter: Option<RowFilter>,
+}
+
+impl RowScoped for ListDocuments {
+    fn restrict(&mut self, filter: RowFilter) {
+        self.filter = Some(filter);
+    }
+}
+
+struct ListDocumentsHandler {
+    store: Arc<InMemoryReadStore<DocumentRow>>,
+}
+
+#[async_trait]
+impl QueryHandler<ListDocuments, Vec<String>> for ListDocumentsHandler {
+    async fn handle(&self, _ctx: &AppContext, q: ListDocuments) -> Result<Vec<String>, AppError> {
+        Ok(self
+            .store
+            .query()
+            .scoped(q.filter)
+            .fetch_all()
+            .into_iter()
+            .map(|row| row.id)
+            .collect())
+    }
+}
+
+fn ctx(actor_type: &str, actor_id: &str, tenant_id: Option<&str>) -> AppContext {
+    AppContext::new(
+        EventContext::builder()
+            .actor_type(actor_type.into())
+            .actor_id(actor_id.into())
+            .maybe_extensions(tenant_id.map(|t| json!({ "tenant_id": t })))
+            .build(),
+    )
+}
+
+#[tokio::test]
+async fn non_admin_actors_only_see_their_own_rows() {
+    let store = Arc::new(InMemoryReadStore::new(|row: &DocumentRow| row.id.clone()));
+    project(&store, &event("d-1", "draft", "alice", "acme"));
+    project(&store, &event("d-1", "final", "bob", "acme"));
+    project(&store, &event("d-2", "notes", "bob", "acme"));
+    project(&store, &event("d-3", "memo", "carol", "globex"));
+
+    // 其他执行主体的后续事件不改写归属
+    let d1 = store.get("d-1").unwrap();
+    assert_eq!(d1.title, "final");
+    assert_eq!(d1.ownership.owner_actor_id.as_deref(), Some("alice"));
+    assert_eq!(d1.ownership.tenant_id.as_deref(), Some("acme"));
+
+    let bus = InMemoryQueryBus::new();
+    bus.register::<ListDocuments, Vec<String>, _>(Arc::new(ListDocumentsHandler {
+        store: store.clone(),
+    }))
+    .unwrap();
+    bus.register_query_filter::<ListDocuments, _>(Arc::new(
+        RowLevelSecurity::new(RowScope::OwnerAndTenant).with_admin_roles(["admin"]),
+    ));
+
+    let list = |ctx: AppContext| {
+        let bus = &bus;
+        async move {
+            bus.dispatch::<ListDocuments, Vec<String>>(&ctx, ListDocuments::default())
+                .await
+        }
+    };
+
+    assert_eq!(
+        list(ctx("user", "bob", Some("acme"))).await.unwrap(),
+        ["d-2"]
+    );
+    assert_eq!(
+        list(ctx("user", "alice", Some("acme"))).await.unwrap(),
+        ["d-1"]
+    );
+    assert!(
+        list(ctx("user", "carol", Some("acme")))
+            .await
+            .unwrap()
+            .is_empty()
+    );
+    assert_eq!(
+        list(ctx("admin", "root", None)).await.unwrap(),
+        ["d-1", "d-2", "d-3"]
+    );
+
+    // 缺少范围所需的租户时拒绝查询
+    let err = list(ctx("user", "bob", None)).await.unwrap_err();
+    assert_eq!(err.code(), "UNAUTHORIZED");
+}
+
+#[test]
+fn tenant_scope_ignores_owner() {
+    let filter = RowLevelSecurity::new(RowScope::Tenant)
+        .filter_for(&ctx("user", "bob", Some("acme")))
+        .unwrap()
+        .unwrap();
+    let row = |owner: &str, tenant: &str| RowOwnership {
+        owner_actor_id: Some(owner.into()),
+        tenant_id: Some(tenant.into()),
+    };
+    assert!(filter.permits(&row("alice", "acme")));
+    assert!(!filter.permits(&row("bob", "globex")));
+    assert!(!filter.permits(&RowOwnership::default()));
+}
@@ -633,10 +633,12 @@ impl SnapshotRepository for InMemorySnapshotRepository {
         }
     }
 
-    async fn save<A: Aggregate>(&self, aggregate: &A) -> DomainResult<()> {
-        let snapshot = SerializedSnapshot::from_aggregate(aggregate)?;
+    async fn save_serialized(&self, snapshot: SerializedSnapshot) -> DomainResult<()> {
         let mut store = self.snapshots.lock().unwrap();
-        let key = (A::TYPE.to_string(), aggregate.id().to_string());
+        let key = (
+            snapshot.aggregate_type().to_string(),
+            snapshot.aggregate_id().to_string(),
+        );
         let entry = store.entry(key).or_default();
         entry.push(snapshot);
         entry.sort_by_key(|s| s.aggregate_version());
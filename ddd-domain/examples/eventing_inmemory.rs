@@ -6,7 +6,7 @@ use ddd_domain::domain_event::EventContext;
 use ddd_domain::error::DomainResult;
 use ddd_domain::eventing::{
     EventDeliverer, EventEngine, EventEngineConfig, EventHandler, EventReclaimer, HandledEventType,
-    InMemoryEventBus,
+    HandlerContext, InMemoryEventBus,
 };
 use ddd_domain::persist::SerializedEvent;
 use std::{
@@ -122,7 +122,7 @@ struct PrintHandler {
 
 #[async_trait::async_trait]
 impl EventHandler for PrintHandler {
-    async fn handle(&self, event: &SerializedEvent) -> anyhow::Result<()> {
+    async fn handle(&self, event: &SerializedEvent, _ctx: &HandlerContext) -> anyhow::Result<()> {
         if let Some(bad) = self.fail_on
             && event.event_type() == bad
         {
@@ -359,11 +359,13 @@ impl SnapshotRepository for InMemorySnapshotRepository {
     }
 
     /// 保存快照
-    async fn save<A: Aggregate>(&self, aggregate: &A) -> DomainResult<()> {
-        let snapshot = SerializedSnapshot::from_aggregate(aggregate)?;
+    async fn save_serialized(&self, snapshot: SerializedSnapshot) -> DomainResult<()> {
         let mut snapshots = self.snapshots.lock().unwrap();
 
-        let key = (A::TYPE.to_string(), aggregate.id().to_string());
+        let key = (
+            snapshot.aggregate_type().to_string(),
+            snapshot.aggregate_id().to_string(),
+        );
         let entry = snapshots.entry(key).or_default();
 
         // 保持版本排序
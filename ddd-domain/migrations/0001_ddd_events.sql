@@ -0,0 +1,26 @@
+-- 事件存储（PgEventRepository）
+-- 位点由 sequence_number 全局递增分配；同一聚合的版本唯一，保证乐观并发控制
+
+CREATE TABLE IF NOT EXISTS ddd_events (
+    sequence_number BIGSERIAL PRIMARY KEY,
+    event_id TEXT NOT NULL UNIQUE,
+    event_type TEXT NOT NULL,
+    aggregate_type TEXT NOT NULL,
+    aggregate_id TEXT NOT NULL,
+    aggregate_version BIGINT NOT NULL,
+    occurred_at TIMESTAMPTZ NOT NULL,
+    body JSONB NOT NULL,
+    UNIQUE (aggregate_type, aggregate_id, aggregate_version)
+);
+
+CREATE INDEX IF NOT EXISTS ddd_events_occurred_at_idx ON ddd_events (occurred_at);
+
+-- 事件排除记录（读取跳过、事件本身保留供审计）
+CREATE TABLE IF NOT EXISTS ddd_event_exclusions (
+    event_id TEXT PRIMARY KEY REFERENCES ddd_events (event_id),
+    aggregate_type TEXT NOT NULL,
+    aggregate_id TEXT NOT NULL,
+    reason TEXT NOT NULL,
+    actor TEXT NOT NULL,
+    excluded_at TIMESTAMPTZ NOT NULL DEFAULT now()
+);
@@ -2,13 +2,17 @@
 //!
 //! 约束一个聚合的核心行为：
 //! - `execute` 将命令转换为事件（不改变状态）；
-//! - `apply` 将事件投影到状态（改变状态）；
+//! - `apply` 将事件投影到状态（改变状态），`apply_serialized` 为重建时按需反序列化的可选快速路径；
+//! - `invariants` 声明应用事件后必须成立的业务规则，由 `AggregateRoot::execute` 在保存前校验；
 //! - 通过 `Entity` 约束聚合具备标识与版本。
 //!
 use crate::domain_event::DomainEvent;
 use crate::entity::Entity;
+use crate::error::{DomainError, ErrorKind};
+use crate::persist::SerializedEvent;
 use serde::{Serialize, de::DeserializeOwned};
 use std::error::Error;
+use std::fmt;
 
 /// 聚合根接口
 pub trait Aggregate: Entity + Default + Serialize + DeserializeOwned + Send + Sync {
@@ -26,8 +30,99 @@ pub trait Aggregate: Entity + Default + Serialize + DeserializeOwned + Send + Sy
 
     /// 应用事件，更新聚合状态
     fn apply(&mut self, event: &Self::Event);
+
+    /// 重建时直接从持久化事件应用状态的快速路径（可选）
+    ///
+    /// 只反序列化所需字段（见 `SerializedEvent::payload_fields`），避免为忽略的大字段分配内存；
+    /// 返回 `false` 时回退为完整反序列化后调用 `apply`。可通过 `#[apply_serialized]` 宏生成。
+    fn apply_serialized(&mut self, event: &SerializedEvent) -> serde_json::Result<bool> {
+        let _ = event;
+        Ok(false)
+    }
+
+    /// 校验聚合不变量，存在违反时返回错误（默认总是满足）
+    ///
+    /// `AggregateRoot::execute` 在应用命令产生的事件后、保存前调用；返回错误时命令整体回滚，
+    /// 错误原样返回给调用方。`InvariantViolations::into_result` 将逐条声明的违反项汇总为
+    /// `ErrorKind::InvalidState`（错误码 `INVARIANT_VIOLATED`）的 `DomainError`，
+    /// 违反明细可经 `DomainError::downcast_ref::<InvariantViolations>()` 取回。
+    fn invariants(&self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+}
+
+/// 不变量违反时的错误码
+pub const INVARIANT_VIOLATED: &str = "INVARIANT_VIOLATED";
+
+/// 被违反的不变量：规则名 + 说明
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvariantViolation {
+    pub rule: &'static str,
+    pub message: String,
+}
+
+impl InvariantViolation {
+    pub fn new(rule: &'static str, message: impl Into<String>) -> Self {
+        Self {
+            rule,
+            message: message.into(),
+        }
+    }
+
+    /// `holds` 为假时返回违反项，便于在 `invariants` 中逐条声明
+    pub fn check(holds: bool, rule: &'static str, message: impl Into<String>) -> Option<Self> {
+        (!holds).then(|| Self::new(rule, message))
+    }
+}
+
+impl fmt::Display for InvariantViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.rule, self.message)
+    }
 }
 
+/// 一次命令执行后被违反的全部不变量
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct InvariantViolations {
+    pub aggregate_type: &'static str,
+    pub violations: Vec<InvariantViolation>,
+}
+
+impl InvariantViolations {
+    pub fn new(
+        aggregate_type: &'static str,
+        violations: impl IntoIterator<Item = InvariantViolation>,
+    ) -> Self {
+        Self {
+            aggregate_type,
+            violations: violations.into_iter().collect(),
+        }
+    }
+
+    /// 无违反时返回 `Ok`，否则返回 `INVARIANT_VIOLATED` 错误
+    pub fn into_result(self) -> Result<(), DomainError> {
+        if self.violations.is_empty() {
+            return Ok(());
+        }
+        Err(DomainError::custom(ErrorKind::InvalidState, self).with_code(INVARIANT_VIOLATED))
+    }
+}
+
+impl fmt::Display for InvariantViolations {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} invariants violated: ", self.aggregate_type)?;
+        for (i, violation) in self.violations.iter().enumerate() {
+            if i > 0 {
+                f.write_str("; ")?;
+            }
+            write!(f, "{violation}")?;
+        }
+        Ok(())
+    }
+}
+
+impl Error for InvariantViolations {}
+
 #[cfg(test)]
 mod tests {
     use super::Aggregate;
@@ -0,0 +1,102 @@
+//! 按聚合 ID 的进程内锁（AggregateLocks）
+//!
+//! 同一聚合 ID 上的并发 `AggregateRoot::execute` 会加载到相同版本，随后在保存时因乐观并发控制冲突。
+//! `AggregateRoot::with_locking(LockMode::PerAggregate)` 在“加载 → 执行 → 保存”期间持有该聚合 ID
+//! 的异步锁，使同一聚合的命令在进程内依次执行；不同聚合互不阻塞。
+//!
+//! 与 `AggregateMailbox` 不同，锁不创建后台任务也不排队缓冲，命令仍在调用方任务中执行；
+//! 锁只在单进程内生效，多实例部署时仍依赖仓储的乐观并发控制兜底。
+//!
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tokio::sync::{Mutex as AsyncMutex, OwnedMutexGuard};
+
+/// `AggregateRoot` 的加锁方式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum LockMode {
+    /// 不加锁，并发命令由仓储的乐观并发控制裁决
+    #[default]
+    None,
+    /// 按聚合 ID 串行执行命令
+    PerAggregate,
+}
+
+/// 表中的锁及其持有者与等待者计数
+struct LockEntry {
+    lock: Arc<AsyncMutex<()>>,
+    users: usize,
+}
+
+type Locks = Arc<Mutex<HashMap<String, LockEntry>>>;
+
+/// 按聚合 ID 分配的异步锁；无人持有或等待的锁在释放时回收
+#[derive(Default)]
+pub struct AggregateLocks {
+    locks: Locks,
+}
+
+impl AggregateLocks {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 获取聚合 ID 的锁，守卫释放前同一 ID 的其他调用方等待
+    ///
+    /// 等待期间被取消（如超时）同样释放对表项的占用，不会遗留无人使用的锁。
+    pub async fn acquire(&self, aggregate_id: &str) -> AggregateLockGuard {
+        let (lock, user) = {
+            let mut locks = self.locks.lock().unwrap();
+            let entry = locks
+                .entry(aggregate_id.to_string())
+                .or_insert_with(|| LockEntry {
+                    lock: Arc::default(),
+                    users: 0,
+                });
+            entry.users += 1;
+            let user = LockUser {
+                aggregate_id: aggregate_id.to_string(),
+                locks: self.locks.clone(),
+            };
+            (entry.lock.clone(), user)
+        };
+
+        AggregateLockGuard {
+            _guard: lock.lock_owned().await,
+            _user: user,
+        }
+    }
+
+    /// 当前被持有或等待中的聚合数
+    pub fn len(&self) -> usize {
+        self.locks.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// 对表项的一次占用（持有或等待），释放时在表锁内递减计数，归零即回收
+struct LockUser {
+    aggregate_id: String,
+    locks: Locks,
+}
+
+impl Drop for LockUser {
+    fn drop(&mut self) {
+        let mut locks = self.locks.lock().unwrap();
+        if let Some(entry) = locks.get_mut(&self.aggregate_id) {
+            entry.users -= 1;
+            if entry.users == 0 {
+                locks.remove(&self.aggregate_id);
+            }
+        }
+    }
+}
+
+/// 聚合锁守卫
+pub struct AggregateLockGuard {
+    // 字段按声明顺序析构：先释放锁，再归还表项占用
+    _guard: OwnedMutexGuard<()>,
+    _user: LockUser,
+}
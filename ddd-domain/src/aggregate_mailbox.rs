@@ -0,0 +1,250 @@
+//! 按聚合串行执行命令的邮箱（AggregateMailbox）
+//!
+//! 热点聚合上的并发命令在乐观并发控制下会反复冲突重试。`AggregateMailbox` 借鉴 Actor 模型，
+//! 将同一聚合 ID 的命令路由到专属任务的内存队列中依次执行，消除同进程内的版本冲突：
+//! - 每个聚合一个邮箱（有界队列，`MailboxConfig::capacity`），队列满时 `execute` 等待（背压）；
+//! - 邮箱空闲超过 `MailboxConfig::idle_timeout` 后自动回收，下一条命令到达时重新创建；
+//! - 命令仍经 `AggregateRoot` 执行，聚合与仓储 API 不变；不同聚合的命令并发执行。
+//!
+//! 命令执行 panic 时调用方收到内部错误，邮箱随之停止，其表项被移除，下一条命令重新创建邮箱。
+//!
+//! 邮箱只在单进程内串行化；多实例部署时仍依赖仓储的乐观并发控制兜底。
+//!
+use crate::{
+    aggregate::Aggregate,
+    aggregate_root::AggregateRoot,
+    domain_event::{EventContext, EventEnvelope},
+    error::DomainError,
+    eventing::panic_guard::panic_message,
+    persist::AggregateRepository,
+};
+use futures_util::FutureExt;
+use std::collections::HashMap;
+use std::panic::AssertUnwindSafe;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::{mpsc, oneshot};
+
+/// 邮箱参数
+#[derive(Clone, Copy, Debug)]
+pub struct MailboxConfig {
+    /// 单个聚合邮箱的队列容量
+    pub capacity: usize,
+    /// 邮箱空闲多久后回收
+    pub idle_timeout: Duration,
+}
+
+impl Default for MailboxConfig {
+    fn default() -> Self {
+        Self {
+            capacity: 64,
+            idle_timeout: Duration::from_secs(30),
+        }
+    }
+}
+
+type Reply<A> = oneshot::Sender<Result<Vec<EventEnvelope<A>>, <A as Aggregate>::Error>>;
+
+struct Job<A: Aggregate> {
+    commands: Vec<A::Command>,
+    context: EventContext,
+    reply: Reply<A>,
+}
+
+struct Mailbox<A: Aggregate> {
+    generation: u64,
+    sender: mpsc::Sender<Job<A>>,
+}
+
+type Mailboxes<A> = Arc<Mutex<HashMap<String, Mailbox<A>>>>;
+
+/// 按聚合 ID 串行执行命令的执行器
+pub struct AggregateMailbox<A, R>
+where
+    A: Aggregate,
+    R: AggregateRepository<A>,
+{
+    root: Arc<AggregateRoot<A, R>>,
+    config: MailboxConfig,
+    mailboxes: Mailboxes<A>,
+    generation: AtomicU64,
+}
+
+impl<A, R> AggregateMailbox<A, R>
+where
+    A: Aggregate + 'static,
+    A::Command: Send + 'static,
+    A::Error: From<DomainError>,
+    R: AggregateRepository<A> + 'static,
+{
+    pub fn new(root: AggregateRoot<A, R>) -> Self {
+        Self::with_config(root, MailboxConfig::default())
+    }
+
+    pub fn with_config(root: AggregateRoot<A, R>, config: MailboxConfig) -> Self {
+        Self {
+            root: Arc::new(root),
+            config,
+            mailboxes: Arc::new(Mutex::new(HashMap::new())),
+            generation: AtomicU64::new(0),
+        }
+    }
+
+    pub fn root(&self) -> &AggregateRoot<A, R> {
+        &self.root
+    }
+
+    /// 当前存活的邮箱数（即近期有命令的聚合数）
+    pub fn active_mailboxes(&self) -> usize {
+        self.mailboxes.lock().unwrap().len()
+    }
+
+    /// 将命令投递到聚合的邮箱，等待其按到达顺序执行完成
+    ///
+    /// 返回值与 `AggregateRoot::execute` 一致。
+    pub async fn execute(
+        &self,
+        aggregate_id: &A::Id,
+        commands: Vec<A::Command>,
+        context: EventContext,
+    ) -> Result<Vec<EventEnvelope<A>>, A::Error> {
+        let (reply, receiver) = oneshot::channel();
+        let mut job = Job {
+            commands,
+            context,
+            reply,
+        };
+
+        // 邮箱可能恰在投递前被回收或已停止，此时移除失效表项、取回命令并投递到新建的邮箱
+        loop {
+            let (generation, sender) = self.sender(aggregate_id);
+            match sender.send(job).await {
+                Ok(()) => break,
+                Err(mpsc::error::SendError(returned)) => {
+                    Self::remove(&self.mailboxes, &aggregate_id.to_string(), generation);
+                    job = returned;
+                }
+            }
+        }
+
+        receiver.await.map_err(|_| {
+            A::Error::from(DomainError::internal(format!(
+                "aggregate mailbox for {}:{aggregate_id} stopped before replying",
+                A::TYPE
+            )))
+        })?
+    }
+
+    fn sender(&self, aggregate_id: &A::Id) -> (u64, mpsc::Sender<Job<A>>) {
+        let key = aggregate_id.to_string();
+        let mut mailboxes = self.mailboxes.lock().unwrap();
+        if let Some(mailbox) = mailboxes.get(&key) {
+            return (mailbox.generation, mailbox.sender.clone());
+        }
+
+        let generation = self.generation.fetch_add(1, Ordering::Relaxed);
+        let (sender, receiver) = mpsc::channel(self.config.capacity.max(1));
+        mailboxes.insert(
+            key.clone(),
+            Mailbox {
+                generation,
+                sender: sender.clone(),
+            },
+        );
+        tokio::spawn(Self::run(
+            self.root.clone(),
+            self.mailboxes.clone(),
+            aggregate_id.clone(),
+            key,
+            generation,
+            receiver,
+            self.config.idle_timeout,
+        ));
+        (generation, sender)
+    }
+
+    /// 移除指定代次的邮箱表项（已被新邮箱替换时不动）
+    fn remove(mailboxes: &Mailboxes<A>, key: &str, generation: u64) {
+        let mut mailboxes = mailboxes.lock().unwrap();
+        if mailboxes
+            .get(key)
+            .is_some_and(|mailbox| mailbox.generation == generation)
+        {
+            mailboxes.remove(key);
+        }
+    }
+
+    async fn run(
+        root: Arc<AggregateRoot<A, R>>,
+        mailboxes: Mailboxes<A>,
+        aggregate_id: A::Id,
+        key: String,
+        generation: u64,
+        mut receiver: mpsc::Receiver<Job<A>>,
+        idle_timeout: Duration,
+    ) {
+        loop {
+            match tokio::time::timeout(idle_timeout, receiver.recv()).await {
+                Ok(Some(job)) => {
+                    if !Self::process(&root, &aggregate_id, job).await {
+                        Self::remove(&mailboxes, &key, generation);
+                        return;
+                    }
+                }
+                Ok(None) => break,
+                Err(_) if Self::evict(&mailboxes, &key, generation, &mut receiver) => break,
+                Err(_) => {}
+            }
+        }
+
+        while let Some(job) = receiver.recv().await {
+            if !Self::process(&root, &aggregate_id, job).await {
+                return;
+            }
+        }
+    }
+
+    /// 空闲超时后回收邮箱；持锁检查队列，避免与并发取得发送端的投递交错
+    fn evict(
+        mailboxes: &Mailboxes<A>,
+        key: &str,
+        generation: u64,
+        receiver: &mut mpsc::Receiver<Job<A>>,
+    ) -> bool {
+        let mut mailboxes = mailboxes.lock().unwrap();
+        if !receiver.is_empty() {
+            return false;
+        }
+        if mailboxes
+            .get(key)
+            .is_some_and(|mailbox| mailbox.generation == generation)
+        {
+            mailboxes.remove(key);
+        }
+        // 关闭后新的投递会失败并改投新邮箱；关闭前已入队的命令仍会处理完毕
+        receiver.close();
+        true
+    }
+
+    /// 执行一条命令并回复调用方；命令执行 panic 时回复内部错误并返回 `false`（邮箱随之停止）
+    async fn process(root: &AggregateRoot<A, R>, aggregate_id: &A::Id, job: Job<A>) -> bool {
+        let execution = root.execute(aggregate_id, job.commands, job.context);
+        match AssertUnwindSafe(execution).catch_unwind().await {
+            Ok(result) => {
+                let _ = job.reply.send(result);
+                true
+            }
+            Err(payload) => {
+                let _ = job
+                    .reply
+                    .send(Err(A::Error::from(DomainError::internal(format!(
+                        "aggregate mailbox for {}:{aggregate_id} panicked: {}",
+                        A::TYPE,
+                        panic_message(payload.as_ref())
+                    )))));
+                false
+            }
+        }
+    }
+}
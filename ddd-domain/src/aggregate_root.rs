@@ -2,14 +2,40 @@
 //!
 //! 封装从“加载聚合 → 执行命令 → 应用事件 → 持久化事件”的标准流程，
 //! 以仓储实现（`AggregateRepository`）为依赖，便于在应用层直接调用。
+//! 启用 `eventing` 特性时可经 `with_locking(LockMode::PerAggregate)` 在进程内串行化同一聚合的命令。
 //!
+#[cfg(feature = "eventing")]
+use crate::aggregate_lock::{AggregateLocks, LockMode};
 use crate::{
     aggregate::Aggregate,
-    domain_event::{EventContext, EventEnvelope},
+    domain_event::{DomainEvent, EventContext, EventEnvelope, StateTransfer},
+    error::{ErrorCode, ErrorKind},
     persist::AggregateRepository,
     value_object::Version,
 };
+use chrono::Utc;
 use std::marker::PhantomData;
+#[cfg(feature = "eventing")]
+use std::sync::Arc;
+
+/// `execute_with_retry` 的重试参数
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct RetryOptions {
+    /// 最大执行次数（含首次），至少执行一次
+    pub max_attempts: u32,
+}
+
+impl RetryOptions {
+    pub fn new(max_attempts: u32) -> Self {
+        Self { max_attempts }
+    }
+}
+
+impl Default for RetryOptions {
+    fn default() -> Self {
+        Self { max_attempts: 3 }
+    }
+}
 
 /// 面向应用层的聚合根编排器。
 ///
@@ -21,6 +47,9 @@ where
     R: AggregateRepository<A>,
 {
     repo: R,
+    state_transfer: StateTransfer,
+    #[cfg(feature = "eventing")]
+    locks: Option<Arc<AggregateLocks>>,
     _marker: PhantomData<A>,
 }
 
@@ -33,46 +62,131 @@ where
     pub fn new(repo: R) -> Self {
         Self {
             repo,
+            state_transfer: StateTransfer::default(),
+            #[cfg(feature = "eventing")]
+            locks: None,
             _marker: PhantomData,
         }
     }
 
+    /// 启用事件携带状态传递：按事件类型在事件信封上附带应用后的聚合状态
+    pub fn with_state_transfer(mut self, state_transfer: StateTransfer) -> Self {
+        self.state_transfer = state_transfer;
+        self
+    }
+
+    /// 设置同一聚合 ID 上并发命令的加锁方式
+    #[cfg(feature = "eventing")]
+    pub fn with_locking(mut self, mode: LockMode) -> Self {
+        self.locks = match mode {
+            LockMode::None => None,
+            LockMode::PerAggregate => Some(Arc::new(AggregateLocks::new())),
+        };
+        self
+    }
+
     /// 执行聚合命令：
     /// 1. 若未持久化则创建新聚合；
-    /// 2. 执行命令得到新事件；
-    /// 3. 应用事件到聚合状态；
-    /// 4. 调用仓储持久化并返回事件信封。
+    /// 2. 依次执行命令得到新事件，每条命令都作用于已应用前序事件的内存聚合；
+    /// 3. 应用事件到聚合状态（启用 `StateTransfer` 时记录应用后的状态快照）；
+    /// 4. 校验聚合不变量（`Aggregate::invariants`），违反时不保存并返回其错误；
+    /// 5. 调用仓储持久化并返回事件信封。
+    ///
+    /// 全部命令产生的事件经一次仓储保存提交；任一命令失败时整体返回错误，不保存任何事件。
+    ///
+    /// 上下文未记录命令接收时间时以调用时刻补记，用于端到端延迟统计；
+    /// 启用按聚合加锁时，加载到保存的全过程持有该聚合 ID 的锁。
     pub async fn execute(
         &self,
         aggregate_id: &A::Id,
         commands: Vec<A::Command>,
-        context: EventContext,
+        mut context: EventContext,
     ) -> Result<Vec<EventEnvelope<A>>, A::Error> {
+        context.stamp_command_received(Utc::now());
+
+        #[cfg(feature = "eventing")]
+        let _guard = match &self.locks {
+            Some(locks) => Some(locks.acquire(&aggregate_id.to_string()).await),
+            None => None,
+        };
+
         // 如果不存在则创建新的聚合实例
         let mut aggregate = self
             .load(aggregate_id)
             .await?
             .unwrap_or_else(|| A::new(aggregate_id.clone(), Version::new()));
 
-        // 执行命令，获取事件
+        // 执行命令，获取事件（及应用后的状态快照）
+        let mut snapshots = Vec::new();
         let events = commands.into_iter().try_fold(Vec::new(), |mut acc, cmd| {
             let mut events = aggregate.execute(cmd)?;
 
             for event in &events {
                 aggregate.apply(event);
+
+                if !self.state_transfer.is_empty() {
+                    snapshots.push(self.state_transfer.snapshot(event.event_type(), &aggregate));
+                }
             }
 
             acc.append(&mut events);
 
-            Ok(acc)
+            Ok::<_, A::Error>(acc)
         })?;
 
         if events.is_empty() {
             return Ok(vec![]);
         }
 
-        // 保存聚合状态和未提交的事件
-        self.repo.save(&aggregate, events, context).await
+        aggregate.invariants()?;
+
+        if snapshots.iter().all(Option::is_none) {
+            // 保存聚合状态和未提交的事件
+            return self.repo.save(&aggregate, events, context).await;
+        }
+
+        let envelopes = events
+            .into_iter()
+            .zip(snapshots)
+            .map(|(event, snapshot)| {
+                let envelope = EventEnvelope::new(aggregate.id(), event, context.clone());
+                match snapshot {
+                    Some(state) => envelope.with_state_snapshot(state),
+                    None => envelope,
+                }
+            })
+            .collect();
+
+        self.repo.save_envelopes(&aggregate, envelopes).await
+    }
+
+    /// 执行聚合命令，遇到版本冲突时重新加载聚合并重跑命令，至多执行 `max_attempts` 次
+    ///
+    /// 只重试乐观并发控制的版本冲突（`ErrorKind::Conflict` 且错误码为 `CONFLICT`），
+    /// 其他错误（包括带自定义错误码的冲突，如唯一键占用）直接返回；次数耗尽时返回最后一次的冲突。
+    pub async fn execute_with_retry(
+        &self,
+        aggregate_id: &A::Id,
+        commands: Vec<A::Command>,
+        context: EventContext,
+        options: RetryOptions,
+    ) -> Result<Vec<EventEnvelope<A>>, A::Error>
+    where
+        A::Command: Clone,
+        A::Error: ErrorCode,
+    {
+        let mut attempt = 1;
+        loop {
+            match self
+                .execute(aggregate_id, commands.clone(), context.clone())
+                .await
+            {
+                Err(err) if attempt < options.max_attempts && is_version_conflict(&err) => {
+                    attempt += 1;
+                }
+                result => return result,
+            }
+        }
     }
 
     /// 加载聚合实例
@@ -80,3 +194,7 @@ where
         self.repo.load(aggregate_id).await
     }
 }
+
+fn is_version_conflict(err: &impl ErrorCode) -> bool {
+    err.kind() == ErrorKind::Conflict && err.code() == ErrorKind::Conflict.default_code()
+}
@@ -0,0 +1,424 @@
+//! 限界上下文描述（Bounded Context）
+//!
+//! 以 `BoundedContext` 声明一个上下文拥有的聚合、对外发布的事件类型，
+//! 以及订阅事件的处理器、Saga 与策略；多个上下文组成 `ContextMap` 后在启动时校验装配：
+//! - 组件订阅的事件类型必须存在于事件类型登记表（全部上下文聚合的 `DomainEvent::DESCRIPTORS`
+//!   与显式声明的事件类型）中，避免拼写错误导致处理器静默不触发；
+//! - 同一聚合类型不能被多个上下文声明；
+//! - 配置 `EventTaxonomy` 后，声明的事件类型须符合命名规范与版本约束。
+//!
+use crate::aggregate::Aggregate;
+use crate::domain_event::{DomainEvent, EventDescriptor, EventTaxonomy};
+use crate::error::{DomainError, DomainResult};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+
+/// 订阅事件的组件类别
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ComponentKind {
+    Handler,
+    Saga,
+    Policy,
+}
+
+/// 订阅事件的组件声明
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ComponentBinding {
+    pub kind: ComponentKind,
+    pub name: String,
+    /// 订阅的事件类型；为空表示订阅全部事件（不参与校验）
+    pub event_types: Vec<String>,
+}
+
+/// 聚合声明
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AggregateBinding {
+    pub aggregate_type: String,
+    pub event_types: Vec<String>,
+    pub descriptors: &'static [EventDescriptor],
+}
+
+/// 限界上下文描述
+#[derive(Debug, Clone)]
+pub struct BoundedContext {
+    name: String,
+    aggregates: Vec<AggregateBinding>,
+    event_types: Vec<String>,
+    components: Vec<ComponentBinding>,
+}
+
+impl BoundedContext {
+    pub fn new(name: impl Into<String>) -> Self {
+        Self {
+            name: name.into(),
+            aggregates: Vec::new(),
+            event_types: Vec::new(),
+            components: Vec::new(),
+        }
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// 声明上下文拥有的聚合，其事件类型来自 `DomainEvent::DESCRIPTORS`
+    pub fn aggregate<A: Aggregate>(mut self) -> Self {
+        self.aggregates.push(AggregateBinding {
+            aggregate_type: A::TYPE.to_string(),
+            event_types: A::Event::DESCRIPTORS
+                .iter()
+                .map(|d| d.event_type.to_string())
+                .collect(),
+            descriptors: A::Event::DESCRIPTORS,
+        });
+        self
+    }
+
+    /// 声明不属于聚合的事件类型（如集成事件、生命周期事件）
+    pub fn event_type(mut self, event_type: impl Into<String>) -> Self {
+        self.event_types.push(event_type.into());
+        self
+    }
+
+    pub fn handler<I, T>(self, name: impl Into<String>, event_types: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.component(ComponentKind::Handler, name, event_types)
+    }
+
+    pub fn saga<I, T>(self, name: impl Into<String>, event_types: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.component(ComponentKind::Saga, name, event_types)
+    }
+
+    pub fn policy<I, T>(self, name: impl Into<String>, event_types: I) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.component(ComponentKind::Policy, name, event_types)
+    }
+
+    /// 按处理器自身的名称与订阅类型声明
+    #[cfg(feature = "eventing")]
+    pub fn event_handler(self, handler: &dyn crate::eventing::EventHandler) -> Self {
+        use crate::eventing::HandledEventType;
+
+        let event_types = match handler.handled_event_type() {
+            HandledEventType::All => Vec::new(),
+            HandledEventType::One(t) => vec![t],
+            HandledEventType::Many(ts) => ts,
+        };
+        self.component(ComponentKind::Handler, handler.handler_name(), event_types)
+    }
+
+    pub fn aggregates(&self) -> &[AggregateBinding] {
+        &self.aggregates
+    }
+
+    pub fn components(&self) -> &[ComponentBinding] {
+        &self.components
+    }
+
+    /// 单独校验本上下文（订阅的事件只能来自本上下文）
+    pub fn validate(&self) -> DomainResult<()> {
+        ContextMap::new().context(self.clone()).validate()
+    }
+
+    fn component<I, T>(
+        mut self,
+        kind: ComponentKind,
+        name: impl Into<String>,
+        event_types: I,
+    ) -> Self
+    where
+        I: IntoIterator<Item = T>,
+        T: Into<String>,
+    {
+        self.components.push(ComponentBinding {
+            kind,
+            name: name.into(),
+            event_types: event_types.into_iter().map(Into::into).collect(),
+        });
+        self
+    }
+
+    fn declared_event_types(&self) -> impl Iterator<Item = &str> {
+        self.aggregates
+            .iter()
+            .flat_map(|a| a.event_types.iter())
+            .chain(self.event_types.iter())
+            .map(String::as_str)
+    }
+}
+
+/// 装配问题
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum WiringIssue {
+    /// 组件订阅了登记表中不存在的事件类型
+    UnknownEventType {
+        context: String,
+        component: String,
+        event_type: String,
+    },
+    /// 同一聚合类型被多个上下文声明
+    DuplicateAggregate {
+        aggregate_type: String,
+        contexts: Vec<String>,
+    },
+    /// 事件类型不符合命名规范或版本约束
+    TaxonomyViolation {
+        context: String,
+        event_type: String,
+        reason: String,
+    },
+}
+
+impl fmt::Display for WiringIssue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WiringIssue::UnknownEventType {
+                context,
+                component,
+                event_type,
+            } => write!(
+                f,
+                "{context}/{component} subscribes to unknown event type {event_type}"
+            ),
+            WiringIssue::DuplicateAggregate {
+                aggregate_type,
+                contexts,
+            } => write!(
+                f,
+                "aggregate type {aggregate_type} is claimed by contexts {}",
+                contexts.join(", ")
+            ),
+            WiringIssue::TaxonomyViolation {
+                context,
+                event_type,
+                reason,
+            } => write!(f, "{context} declares event type {event_type}: {reason}"),
+        }
+    }
+}
+
+/// 上下文映射：汇总多个限界上下文并校验装配
+#[derive(Debug, Clone, Default)]
+pub struct ContextMap {
+    contexts: Vec<BoundedContext>,
+    taxonomy: Option<EventTaxonomy>,
+}
+
+impl ContextMap {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn context(mut self, context: BoundedContext) -> Self {
+        self.contexts.push(context);
+        self
+    }
+
+    pub fn contexts(&self) -> &[BoundedContext] {
+        &self.contexts
+    }
+
+    /// 启用事件命名规范与版本约束校验
+    pub fn with_taxonomy(mut self, taxonomy: EventTaxonomy) -> Self {
+        self.taxonomy = Some(taxonomy);
+        self
+    }
+
+    /// 事件类型登记表：全部上下文声明的事件类型
+    pub fn event_types(&self) -> BTreeSet<&str> {
+        self.contexts
+            .iter()
+            .flat_map(BoundedContext::declared_event_types)
+            .collect()
+    }
+
+    /// 全部装配问题：先列聚合归属冲突，再按上下文声明顺序列出未知事件类型，最后列出命名规范违规
+    pub fn issues(&self) -> Vec<WiringIssue> {
+        let mut issues = Vec::new();
+
+        let mut owners: BTreeMap<&str, Vec<String>> = BTreeMap::new();
+        for context in &self.contexts {
+            for aggregate in &context.aggregates {
+                let owners = owners.entry(aggregate.aggregate_type.as_str()).or_default();
+                if !owners.contains(&context.name) {
+                    owners.push(context.name.clone());
+                }
+            }
+        }
+        issues.extend(
+            owners
+                .into_iter()
+                .filter(|(_, contexts)| contexts.len() > 1)
+                .map(
+                    |(aggregate_type, contexts)| WiringIssue::DuplicateAggregate {
+                        aggregate_type: aggregate_type.to_string(),
+                        contexts,
+                    },
+                ),
+        );
+
+        let registry = self.event_types();
+        for context in &self.contexts {
+            for component in &context.components {
+                for event_type in &component.event_types {
+                    if !registry.contains(event_type.as_str()) {
+                        issues.push(WiringIssue::UnknownEventType {
+                            context: context.name.clone(),
+                            component: component.name.clone(),
+                            event_type: event_type.clone(),
+                        });
+                    }
+                }
+            }
+        }
+
+        if let Some(taxonomy) = &self.taxonomy {
+            for context in &self.contexts {
+                let declared = context
+                    .aggregates
+                    .iter()
+                    .flat_map(|a| a.descriptors.iter())
+                    .map(|d| (d.event_type, Some(d.event_version)))
+                    .chain(context.event_types.iter().map(|t| (t.as_str(), None)));
+                for (event_type, event_version) in declared {
+                    issues.extend(taxonomy.check(event_type, event_version).into_iter().map(
+                        |reason| WiringIssue::TaxonomyViolation {
+                            context: context.name.clone(),
+                            event_type: event_type.to_string(),
+                            reason,
+                        },
+                    ));
+                }
+            }
+        }
+        issues
+    }
+
+    /// 校验装配；存在问题时返回 `CONTEXT_WIRING_ERROR`，消息列出全部问题
+    pub fn validate(&self) -> DomainResult<()> {
+        let issues = self.issues();
+        if issues.is_empty() {
+            return Ok(());
+        }
+
+        let message = issues
+            .iter()
+            .map(ToString::to_string)
+            .collect::<Vec<_>>()
+            .join("; ");
+        Err(
+            DomainError::invalid_state(format!("bounded context wiring: {message}"))
+                .with_code("CONTEXT_WIRING_ERROR"),
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ddd_macros::{domain_event, entity};
+    use serde::{Deserialize, Serialize};
+
+    #[entity]
+    #[derive(Debug, Clone, Default, Serialize, Deserialize)]
+    struct Order {
+        shipped: bool,
+    }
+
+    #[domain_event(version = 1)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    enum OrderEvent {
+        #[event(event_type = "order.placed")]
+        Placed {},
+        #[event(event_type = "order.shipped")]
+        Shipped {},
+    }
+
+    impl Aggregate for Order {
+        const TYPE: &'static str = "order";
+        type Command = ();
+        type Event = OrderEvent;
+        type Error = DomainError;
+
+        fn execute(&self, _command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+            Ok(vec![])
+        }
+
+        fn apply(&mut self, _event: &Self::Event) {}
+    }
+
+    #[test]
+    fn validates_event_types_and_aggregate_ownership() {
+        let sales = BoundedContext::new("sales")
+            .aggregate::<Order>()
+            .event_type("order.created")
+            .policy("notify_customer", ["order.placed", "order.created"]);
+        let shipping = BoundedContext::new("shipping")
+            .saga("fulfillment", ["order.placed", "order.shiped"])
+            .handler("audit", Vec::<String>::new());
+        assert!(sales.validate().is_ok());
+
+        // 跨上下文订阅在上下文映射中可解析
+        let map = ContextMap::new().context(sales.clone()).context(shipping);
+        assert_eq!(
+            map.issues(),
+            vec![WiringIssue::UnknownEventType {
+                context: "shipping".into(),
+                component: "fulfillment".into(),
+                event_type: "order.shiped".into(),
+            }]
+        );
+        assert!(map.event_types().contains("order.shipped"));
+
+        let billing = BoundedContext::new("billing").aggregate::<Order>();
+        let err = ContextMap::new()
+            .context(sales)
+            .context(billing)
+            .validate()
+            .unwrap_err();
+        assert!(err.matches(
+            crate::error::ErrorKind::InvalidState,
+            "CONTEXT_WIRING_ERROR"
+        ));
+        assert!(err.to_string().contains("sales, billing"));
+    }
+
+    #[test]
+    fn reports_taxonomy_violations_at_startup() {
+        let sales = BoundedContext::new("sales")
+            .aggregate::<Order>()
+            .event_type("OrderArchived");
+        assert!(sales.validate().is_ok());
+
+        let map = ContextMap::new()
+            .context(sales)
+            .with_taxonomy(EventTaxonomy::dotted().max_version(1));
+        assert_eq!(
+            map.issues(),
+            vec![WiringIssue::TaxonomyViolation {
+                context: "sales".into(),
+                event_type: "OrderArchived".into(),
+                reason: "expected at least 2 dot-separated segments".into(),
+            }, WiringIssue::TaxonomyViolation {
+                context: "sales".into(),
+                event_type: "OrderArchived".into(),
+                reason: "segment \"OrderArchived\" must be lowercase snake_case starting with a letter".into(),
+            }]
+        );
+        let err = map.validate().unwrap_err();
+        assert!(
+            err.to_string()
+                .contains("sales declares event type OrderArchived")
+        );
+    }
+}
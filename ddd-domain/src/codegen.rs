@@ -0,0 +1,589 @@
+//! 事件 Schema 优先（schema-first）代码生成
+//!
+//! 事件契约以版本化的 JSON Schema 先行发布时，由 `EventCodegen` 从 Schema 目录生成
+//! `#[domain_event]` 事件枚举与相邻版本间的 `#[upcaster]` 上抬骨架，使 Rust 类型与已发布契约保持同步。
+//! 典型用法是在 `build.rs` 中调用 `write_to` 生成到 `OUT_DIR` 后 `include!`，
+//! 或将生成结果提交到仓库并在测试中以 `verify` 校验未与 Schema 脱节。
+//!
+//! Schema 文件格式（单个对象或对象数组，每项描述一个事件类型的一个版本）：
+//!
+//! ```json
+//! {
+//!   "event_type": "OrderEvent.Placed",
+//!   "event_version": 2,
+//!   "description": "订单已提交",
+//!   "schema": {
+//!     "type": "object",
+//!     "required": ["amount", "currency"],
+//!     "properties": {
+//!       "amount": { "type": "integer", "description": "金额（分）" },
+//!       "currency": { "type": "string" },
+//!       "note": { "type": ["string", "null"] }
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! - 枚举名与变体名默认取 `event_type` 最后一个 `.` 的前后两段，可用 `enum`/`variant` 显式指定；
+//!   事件类型不是 `枚举.变体` 形式时生成 `#[event(event_type = "...")]`；
+//! - 变体字段取该事件类型最新版本的 `properties`（按字段名排序），`id`/`aggregate_version` 由宏补齐而忽略；
+//!   类型映射：`integer` → `i64`、`number` → `f64`、`boolean` → `bool`、`string` → `String`、
+//!   `array` → `Vec<items>`，其余为 `serde_json::Value`；未列入 `required` 或可为 `null` 的字段为 `Option`；
+//! - 同一事件类型的版本须从最早版本起连续，每对相邻版本生成一个原地修改负载的上抬骨架：
+//!   新增的必填字段以类型默认值补齐、移除的字段被删除，类型变化的字段留下 `TODO` 注释待手工补充；
+//!   每个枚举另生成 `<枚举蛇形名>_upcasters()` 返回全部上抬器。
+//!
+//! Schema 不合法时返回 `EVENT_SCHEMA_INVALID`；`verify` 发现生成结果与文件不一致时返回 `EVENT_CODEGEN_STALE`。
+//!
+use crate::error::{DomainError, DomainResult};
+use serde::{Deserialize, Serialize};
+use serde_json::{Map, Value, json};
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// 单个事件类型某一版本的 Schema 定义
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventSchemaDef {
+    pub event_type: String,
+    pub event_version: usize,
+    /// 生成的枚举名；省略时取 `event_type` 最后一个 `.` 之前的部分
+    #[serde(default, rename = "enum", skip_serializing_if = "Option::is_none")]
+    pub enum_name: Option<String>,
+    /// 生成的变体名；省略时取 `event_type` 最后一个 `.` 之后的部分
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub variant: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// 变体字段的 JSON Schema（`type: object`）
+    pub schema: Value,
+}
+
+impl EventSchemaDef {
+    fn rust_names(&self) -> DomainResult<(String, String)> {
+        let (enum_part, variant_part) = self.event_type.rsplit_once('.').unwrap_or(("", ""));
+        let enum_name = self.enum_name.as_deref().unwrap_or(enum_part);
+        let variant = self.variant.as_deref().unwrap_or(variant_part);
+        for name in [enum_name, variant] {
+            if !is_type_ident(name) {
+                return Err(invalid(format!(
+                    "{} v{}: `{name}` is not a valid enum/variant name; set `enum`/`variant` explicitly",
+                    self.event_type, self.event_version
+                )));
+            }
+        }
+        Ok((enum_name.to_string(), variant.to_string()))
+    }
+
+    fn fields(&self) -> DomainResult<BTreeMap<String, FieldDef>> {
+        let at = || format!("{} v{}", self.event_type, self.event_version);
+        if self.schema.get("type").is_some_and(|t| *t != "object") {
+            return Err(invalid(format!("{}: schema type must be `object`", at())));
+        }
+        let required: Vec<&str> = self
+            .schema
+            .get("required")
+            .and_then(Value::as_array)
+            .map(|items| items.iter().filter_map(Value::as_str).collect())
+            .unwrap_or_default();
+        let empty = Map::new();
+        let properties = self
+            .schema
+            .get("properties")
+            .and_then(Value::as_object)
+            .unwrap_or(&empty);
+
+        let mut fields = BTreeMap::new();
+        for (name, schema) in properties {
+            if name == "id" || name == "aggregate_version" {
+                continue;
+            }
+            if !is_field_ident(name) {
+                return Err(invalid(format!(
+                    "{}: property `{name}` is not a valid Rust field name",
+                    at()
+                )));
+            }
+            let (base, nullable) = FieldType::of(schema);
+            fields.insert(
+                name.clone(),
+                FieldDef {
+                    base,
+                    optional: nullable || !required.contains(&name.as_str()),
+                    description: schema
+                        .get("description")
+                        .and_then(Value::as_str)
+                        .map(ToString::to_string),
+                },
+            );
+        }
+        Ok(fields)
+    }
+}
+
+/// 字段的基础类型（不含可选性）
+#[derive(Debug, Clone, PartialEq)]
+enum FieldType {
+    Integer,
+    Number,
+    Boolean,
+    String,
+    Array(Box<FieldType>),
+    Json,
+}
+
+impl FieldType {
+    /// 返回基础类型与是否可为 `null`
+    fn of(schema: &Value) -> (Self, bool) {
+        let (name, nullable) = match schema.get("type") {
+            Some(Value::String(t)) => (Some(t.as_str()), false),
+            Some(Value::Array(types)) => {
+                let names: Vec<&str> = types.iter().filter_map(Value::as_str).collect();
+                let non_null: Vec<&str> = names.iter().copied().filter(|t| *t != "null").collect();
+                let name = if non_null.len() == 1 {
+                    Some(non_null[0])
+                } else {
+                    None
+                };
+                (name, non_null.len() < names.len())
+            }
+            _ => (None, false),
+        };
+        let base = match name {
+            Some("integer") => Self::Integer,
+            Some("number") => Self::Number,
+            Some("boolean") => Self::Boolean,
+            Some("string") => Self::String,
+            Some("array") => Self::Array(Box::new(
+                schema
+                    .get("items")
+                    .map(|items| Self::of(items).0)
+                    .unwrap_or(Self::Json),
+            )),
+            _ => Self::Json,
+        };
+        (base, nullable)
+    }
+
+    fn rust(&self) -> String {
+        match self {
+            Self::Integer => "i64".to_string(),
+            Self::Number => "f64".to_string(),
+            Self::Boolean => "bool".to_string(),
+            Self::String => "String".to_string(),
+            Self::Array(item) => format!("Vec<{}>", item.rust()),
+            Self::Json => "::serde_json::Value".to_string(),
+        }
+    }
+
+    /// 上抬骨架中补齐新增必填字段所用的默认值
+    fn default_value(&self) -> Value {
+        match self {
+            Self::Integer => json!(0),
+            Self::Number => json!(0.0),
+            Self::Boolean => json!(false),
+            Self::String => json!(""),
+            Self::Array(_) => json!([]),
+            Self::Json => Value::Null,
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq)]
+struct FieldDef {
+    base: FieldType,
+    optional: bool,
+    description: Option<String>,
+}
+
+impl FieldDef {
+    fn rust(&self) -> String {
+        if self.optional {
+            format!("Option<{}>", self.base.rust())
+        } else {
+            self.base.rust()
+        }
+    }
+}
+
+/// 单个事件类型的全部版本（按版本升序）
+struct EventHistory<'a> {
+    variant: String,
+    versions: Vec<&'a EventSchemaDef>,
+}
+
+/// 从事件 Schema 生成事件枚举与上抬骨架
+#[derive(Debug, Clone, Default)]
+pub struct EventCodegen {
+    schemas: Vec<EventSchemaDef>,
+}
+
+impl EventCodegen {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn schema(mut self, schema: EventSchemaDef) -> Self {
+        self.schemas.push(schema);
+        self
+    }
+
+    /// 从 JSON 文本加载 Schema 定义（单个对象或数组）
+    pub fn load_json(mut self, json: &str) -> DomainResult<Self> {
+        let value: Value = serde_json::from_str(json)?;
+        match value {
+            Value::Array(items) => {
+                for item in items {
+                    self.schemas.push(serde_json::from_value(item)?);
+                }
+            }
+            item => self.schemas.push(serde_json::from_value(item)?),
+        }
+        Ok(self)
+    }
+
+    /// 加载目录下全部 `*.json` Schema 文件（按文件名排序）
+    pub fn load_dir(mut self, dir: impl AsRef<Path>) -> DomainResult<Self> {
+        let dir = dir.as_ref();
+        let read_err = |e: std::io::Error| {
+            DomainError::invalid_state(format!(
+                "cannot read event schemas from {}: {e}",
+                dir.display()
+            ))
+        };
+        let mut paths: Vec<_> = std::fs::read_dir(dir)
+            .map_err(read_err)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+        for path in paths {
+            let json = std::fs::read_to_string(&path).map_err(read_err)?;
+            self = self.load_json(&json)?;
+        }
+        Ok(self)
+    }
+
+    pub fn schemas(&self) -> &[EventSchemaDef] {
+        &self.schemas
+    }
+
+    /// 生成 Rust 源码（枚举按名称排序，变体按名称排序）
+    pub fn generate(&self) -> DomainResult<String> {
+        let enums = self.group()?;
+        let mut out = String::from(
+            "// @generated 由 `ddd_domain::codegen` 根据事件 JSON Schema 生成，请勿手工修改。\n",
+        );
+        for (enum_name, histories) in &enums {
+            out.push('\n');
+            render_enum(&mut out, enum_name, histories)?;
+            for history in histories.values() {
+                for pair in history.versions.windows(2) {
+                    out.push('\n');
+                    render_upcaster(&mut out, enum_name, &history.variant, pair[0], pair[1])?;
+                }
+            }
+            out.push('\n');
+            render_upcaster_list(&mut out, enum_name, histories);
+        }
+        Ok(out)
+    }
+
+    /// 生成并写入文件；内容未变化时不写入（避免触发无谓的重新编译），返回是否写入
+    pub fn write_to(&self, path: impl AsRef<Path>) -> DomainResult<bool> {
+        let path = path.as_ref();
+        let code = self.generate()?;
+        if std::fs::read_to_string(path).is_ok_and(|existing| existing == code) {
+            return Ok(false);
+        }
+        std::fs::write(path, code).map_err(|e| {
+            DomainError::invalid_state(format!("cannot write {}: {e}", path.display()))
+        })?;
+        Ok(true)
+    }
+
+    /// 校验已提交的生成文件与当前 Schema 一致
+    pub fn verify(&self, path: impl AsRef<Path>) -> DomainResult<()> {
+        let path = path.as_ref();
+        let code = self.generate()?;
+        let existing = std::fs::read_to_string(path).unwrap_or_default();
+        if existing != code {
+            return Err(DomainError::invalid_state(format!(
+                "{} is out of date with the event schemas; regenerate it with EventCodegen::write_to",
+                path.display()
+            ))
+            .with_code("EVENT_CODEGEN_STALE"));
+        }
+        Ok(())
+    }
+
+    /// 按枚举 -> 事件类型分组并校验版本连续、名称唯一
+    fn group(&self) -> DomainResult<BTreeMap<String, BTreeMap<String, EventHistory<'_>>>> {
+        let mut enums: BTreeMap<String, BTreeMap<String, EventHistory<'_>>> = BTreeMap::new();
+        for def in &self.schemas {
+            if def.event_version == 0 {
+                return Err(invalid(format!(
+                    "{}: event_version must start at 1",
+                    def.event_type
+                )));
+            }
+            let (enum_name, variant) = def.rust_names()?;
+            let histories = enums.entry(enum_name.clone()).or_default();
+            if let Some(other) = histories
+                .values()
+                .find(|h| h.variant == variant && h.versions[0].event_type != def.event_type)
+            {
+                return Err(invalid(format!(
+                    "{} and {} both map to {enum_name}::{variant}",
+                    other.versions[0].event_type, def.event_type
+                )));
+            }
+            histories
+                .entry(variant.clone())
+                .or_insert_with(|| EventHistory {
+                    variant,
+                    versions: Vec::new(),
+                })
+                .versions
+                .push(def);
+        }
+
+        for history in enums.values_mut().flat_map(BTreeMap::values_mut) {
+            history.versions.sort_by_key(|def| def.event_version);
+            for pair in history.versions.windows(2) {
+                if pair[1].event_version != pair[0].event_version + 1 {
+                    return Err(invalid(format!(
+                        "{}: versions must be consecutive, found v{} followed by v{}",
+                        pair[0].event_type, pair[0].event_version, pair[1].event_version
+                    )));
+                }
+            }
+        }
+        Ok(enums)
+    }
+}
+
+fn render_enum(
+    out: &mut String,
+    enum_name: &str,
+    histories: &BTreeMap<String, EventHistory<'_>>,
+) -> DomainResult<()> {
+    let _ = writeln!(out, "#[::ddd_macros::domain_event]");
+    let _ = writeln!(
+        out,
+        "#[derive(Debug, Clone, PartialEq, ::serde::Serialize, ::serde::Deserialize)]"
+    );
+    let _ = writeln!(out, "pub enum {enum_name} {{");
+    for history in histories.values() {
+        let latest = history.versions[history.versions.len() - 1];
+        if let Some(description) = &latest.description {
+            let _ = writeln!(out, "    /// {description}");
+        }
+        let mut event_args = Vec::new();
+        if latest.event_type != format!("{enum_name}.{}", history.variant) {
+            event_args.push(format!("event_type = {:?}", latest.event_type));
+        }
+        if latest.event_version != 1 {
+            event_args.push(format!("event_version = {}", latest.event_version));
+        }
+        if !event_args.is_empty() {
+            let _ = writeln!(out, "    #[event({})]", event_args.join(", "));
+        }
+
+        let fields = latest.fields()?;
+        if fields.is_empty() {
+            let _ = writeln!(out, "    {},", history.variant);
+            continue;
+        }
+        let _ = writeln!(out, "    {} {{", history.variant);
+        for (name, field) in &fields {
+            if let Some(description) = &field.description {
+                let _ = writeln!(out, "        /// {description}");
+            }
+            let _ = writeln!(out, "        {}: {},", field_ident(name), field.rust());
+        }
+        let _ = writeln!(out, "    }},");
+    }
+    let _ = writeln!(out, "}}");
+    Ok(())
+}
+
+fn render_upcaster(
+    out: &mut String,
+    enum_name: &str,
+    variant: &str,
+    from: &EventSchemaDef,
+    to: &EventSchemaDef,
+) -> DomainResult<()> {
+    let (old, new) = (from.fields()?, to.fields()?);
+    let mut steps = Vec::new();
+    for (name, field) in &new {
+        match old.get(name) {
+            None if !field.optional => steps.push(format!(
+                "    // 新增字段 `{name}`：以类型默认值补齐\n    fields.insert({name:?}.to_string(), ::serde_json::json!({}));",
+                field.base.default_value()
+            )),
+            Some(previous) if previous.base != field.base => steps.push(format!(
+                "    // TODO: 字段 `{name}` 类型由 `{}` 变更为 `{}`，需补充转换",
+                previous.base.rust(),
+                field.base.rust()
+            )),
+            _ => {}
+        }
+    }
+    for name in old.keys().filter(|name| !new.contains_key(*name)) {
+        steps.push(format!(
+            "    // 移除字段 `{name}`\n    fields.remove({name:?});"
+        ));
+    }
+
+    let fn_name = upcaster_fn_name(enum_name, variant, from, to);
+    let _ = writeln!(
+        out,
+        "/// `{}` v{} -> v{} 的上抬骨架（由 Schema 差异生成，按需补充迁移逻辑）",
+        to.event_type, from.event_version, to.event_version
+    );
+    let _ = writeln!(
+        out,
+        "#[::ddd_macros::upcaster(event_type = {:?}, from = {}, to = {}, variant = {variant:?})]",
+        to.event_type, from.event_version, to.event_version
+    );
+    let _ = writeln!(
+        out,
+        "pub fn {fn_name}(payload: &mut ::serde_json::Value) {{"
+    );
+    if steps.iter().any(|step| step.contains("fields.")) {
+        let _ = writeln!(
+            out,
+            "    let Some(fields) = payload.as_object_mut() else {{\n        return;\n    }};"
+        );
+    } else {
+        let _ = writeln!(out, "    let _ = payload;");
+    }
+    for step in steps {
+        let _ = writeln!(out, "{step}");
+    }
+    let _ = writeln!(out, "}}");
+    Ok(())
+}
+
+fn render_upcaster_list(
+    out: &mut String,
+    enum_name: &str,
+    histories: &BTreeMap<String, EventHistory<'_>>,
+) {
+    let structs: Vec<String> = histories
+        .values()
+        .flat_map(|history| {
+            history.versions.windows(2).map(|pair| {
+                upper_camel(&upcaster_fn_name(
+                    enum_name,
+                    &history.variant,
+                    pair[0],
+                    pair[1],
+                ))
+            })
+        })
+        .collect();
+    let _ = writeln!(out, "/// `{enum_name}` 的全部生成上抬器");
+    let _ = writeln!(
+        out,
+        "pub fn {}_upcasters() -> Vec<::std::sync::Arc<dyn ::ddd_domain::event_upcaster::EventUpcaster>> {{",
+        snake_case(enum_name)
+    );
+    if structs.is_empty() {
+        let _ = writeln!(out, "    Vec::new()");
+    } else {
+        let _ = writeln!(out, "    vec![");
+        for name in structs {
+            let _ = writeln!(out, "        ::std::sync::Arc::new({name}),");
+        }
+        let _ = writeln!(out, "    ]");
+    }
+    let _ = writeln!(out, "}}");
+}
+
+const KEYWORDS: &[&str] = &[
+    "as", "async", "await", "break", "const", "continue", "dyn", "else", "enum", "extern", "false",
+    "fn", "for", "if", "impl", "in", "let", "loop", "match", "mod", "move", "mut", "pub", "ref",
+    "return", "static", "struct", "trait", "true", "type", "unsafe", "use", "where", "while",
+    "abstract", "become", "box", "do", "final", "gen", "macro", "override", "priv", "try",
+    "typeof", "unsized", "virtual", "yield",
+];
+
+fn is_ident(name: &str) -> bool {
+    let mut chars = name.chars();
+    chars
+        .next()
+        .is_some_and(|c| c.is_ascii_alphabetic() || c == '_')
+        && chars.all(|c| c.is_ascii_alphanumeric() || c == '_')
+        && name != "_"
+}
+
+fn is_type_ident(name: &str) -> bool {
+    is_ident(name) && name.starts_with(|c: char| c.is_ascii_uppercase())
+}
+
+fn is_field_ident(name: &str) -> bool {
+    is_ident(name) && !matches!(name, "self" | "super" | "crate" | "Self")
+}
+
+/// 关键字字段以原始标识符生成（serde 序列化名不含 `r#`）
+fn field_ident(name: &str) -> String {
+    if KEYWORDS.contains(&name) {
+        format!("r#{name}")
+    } else {
+        name.to_string()
+    }
+}
+
+fn upcaster_fn_name(
+    enum_name: &str,
+    variant: &str,
+    from: &EventSchemaDef,
+    to: &EventSchemaDef,
+) -> String {
+    format!(
+        "{}_{}_v{}_to_v{}",
+        snake_case(enum_name),
+        snake_case(variant),
+        from.event_version,
+        to.event_version
+    )
+}
+
+/// 与 `#[upcaster]` 由函数名得到结构体名的规则一致
+fn upper_camel(name: &str) -> String {
+    name.split('_')
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            let mut chars = s.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+fn snake_case(name: &str) -> String {
+    let mut out = String::with_capacity(name.len() + 4);
+    let chars: Vec<char> = name.chars().collect();
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_ascii_uppercase() {
+            let prev_lower = i > 0 && !chars[i - 1].is_ascii_uppercase() && chars[i - 1] != '_';
+            let next_lower = chars.get(i + 1).is_some_and(|n| n.is_ascii_lowercase());
+            if i > 0 && (prev_lower || (next_lower && chars[i - 1] != '_')) {
+                out.push('_');
+            }
+            out.push(c.to_ascii_lowercase());
+        } else {
+            out.push(*c);
+        }
+    }
+    out
+}
+
+fn invalid(msg: String) -> DomainError {
+    DomainError::invalid_value(msg).with_code("EVENT_SCHEMA_INVALID")
+}
@@ -0,0 +1,581 @@
+//! 运行配置加载（config）
+//!
+//! 将事件引擎、处理器熔断、快照策略与总线适配器参数从 TOML 文件与环境变量反序列化为
+//! 可直接使用的配置结构，部署时无需重新编译即可调整间隔与并发：
+//! - `ConfigLoader` 按追加顺序合并多层来源（TOML 字符串/文件、环境变量），后者覆盖前者；
+//! - 环境变量以 `<前缀>__<节>__<键>` 命名（如 `DDD__ENGINE__HANDLER_CONCURRENCY=16`），
+//!   值按 TOML 字面量解析（数字、布尔），无法解析时视为字符串；
+//! - 未知字段、类型错误与取值越界均在加载时报告，取值校验一次性列出全部违规字段。
+//!
+//! ```toml
+//! [engine]
+//! deliver_interval_ms = 500
+//! handler_concurrency = 16
+//! compression = { threshold_bytes = 4096, level = 6 }
+//! log_level = "batches"
+//! max_deliveries = 10
+//! max_catch_up_window_ms = 3600000
+//! quarantine_after_panics = 3
+//!
+//! [engine.retry]
+//! max_attempts = 3
+//! backoff_ms = 200
+//! jitter_percent = 20
+//!
+//! [snapshot]
+//! every = 50
+//!
+//! [bus]
+//! capacity = 4096
+//! ```
+//!
+use crate::eventing::{
+    CircuitBreakerConfig, EngineLogLevel, EngineTuning, EventEngineConfig, PayloadCompression,
+    RetryPolicy,
+};
+use crate::persist::SnapshotPolicy;
+use serde::Deserialize;
+use std::fmt;
+use std::path::Path;
+use std::time::Duration;
+use toml::{Table, Value};
+
+/// 环境变量键的层级分隔符
+const ENV_SEPARATOR: &str = "__";
+
+/// 完整运行配置，各节缺省时取默认值
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct DddConfig {
+    pub engine: EngineSettings,
+    pub circuit_breaker: CircuitBreakerSettings,
+    pub snapshot: SnapshotSettings,
+    pub bus: BusSettings,
+}
+
+/// 事件引擎参数（对应 `EventEngineConfig`）
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct EngineSettings {
+    /// 引擎名称，传递给处理器上下文
+    pub name: String,
+    /// Outbox -> Bus 的推送间隔（毫秒）
+    pub deliver_interval_ms: u64,
+    /// 补偿投递的间隔（毫秒）
+    pub reclaim_interval_ms: u64,
+    /// 单事件的处理并发
+    pub handler_concurrency: usize,
+    /// 负载压缩，缺省不压缩
+    pub compression: Option<CompressionSettings>,
+    /// 结构化日志详细程度：`off` | `errors` | `batches` | `events`
+    pub log_level: EngineLogLevel,
+    /// 处理器失败的原地重试，缺省不重试
+    pub retry: RetrySettings,
+    /// 累计投递次数上限，超过后转入死信队列，缺省不限制
+    pub max_deliveries: Option<u32>,
+    /// 热启动追赶窗口（毫秒），缺省追赶全部停机期间的事件
+    pub max_catch_up_window_ms: Option<u64>,
+    /// 处理器连续 panic 多少次后隔离，缺省不隔离
+    pub quarantine_after_panics: Option<u32>,
+}
+
+impl Default for EngineSettings {
+    fn default() -> Self {
+        let defaults = EventEngineConfig::default();
+        let tuning = EngineTuning::default();
+        Self {
+            name: "default".to_string(),
+            deliver_interval_ms: defaults.deliver_interval.as_millis() as u64,
+            reclaim_interval_ms: defaults.reclaim_interval.as_millis() as u64,
+            handler_concurrency: defaults.handler_concurrency,
+            compression: None,
+            log_level: tuning.log_level(),
+            retry: RetrySettings::default(),
+            max_deliveries: tuning.max_deliveries(),
+            max_catch_up_window_ms: tuning.max_catch_up_window().map(|w| w.as_millis() as u64),
+            quarantine_after_panics: tuning.quarantine_after_panics(),
+        }
+    }
+}
+
+/// 负载压缩参数（对应 `PayloadCompression`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CompressionSettings {
+    /// 负载字节数超过该阈值才压缩
+    pub threshold_bytes: usize,
+    /// 压缩级别（0-9）
+    pub level: u32,
+}
+
+impl Default for CompressionSettings {
+    fn default() -> Self {
+        let defaults = PayloadCompression::default();
+        Self {
+            threshold_bytes: defaults.threshold,
+            level: defaults.level,
+        }
+    }
+}
+
+/// 处理器重试参数（对应 `RetryPolicy`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetrySettings {
+    /// 最大尝试次数（含首次）
+    pub max_attempts: u32,
+    /// 首次重试前的退避（毫秒）
+    pub backoff_ms: u64,
+    /// 退避上限（毫秒）
+    pub max_backoff_ms: u64,
+    /// 抖动百分比（0-100）
+    pub jitter_percent: u8,
+}
+
+impl Default for RetrySettings {
+    fn default() -> Self {
+        let defaults = RetryPolicy::default();
+        Self {
+            max_attempts: defaults.max_attempts,
+            backoff_ms: defaults.backoff.as_millis() as u64,
+            max_backoff_ms: defaults.max_backoff.as_millis() as u64,
+            jitter_percent: (defaults.jitter * 100.0) as u8,
+        }
+    }
+}
+
+/// 处理器熔断参数（对应 `CircuitBreakerConfig`）
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct CircuitBreakerSettings {
+    /// 连续失败多少次后打开
+    pub failure_threshold: u32,
+    /// 打开后多久进入半开状态（毫秒）
+    pub open_duration_ms: u64,
+}
+
+impl Default for CircuitBreakerSettings {
+    fn default() -> Self {
+        let defaults = CircuitBreakerConfig::default();
+        Self {
+            failure_threshold: defaults.failure_threshold,
+            open_duration_ms: defaults.open_duration.as_millis() as u64,
+        }
+    }
+}
+
+/// 快照策略参数（对应 `SnapshotPolicy`）
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct SnapshotSettings {
+    /// 每隔多少个版本生成快照，缺省不生成
+    pub every: Option<usize>,
+}
+
+/// 总线适配器参数
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BusSettings {
+    /// 内存总线的广播容量
+    pub capacity: usize,
+}
+
+impl Default for BusSettings {
+    fn default() -> Self {
+        Self { capacity: 1024 }
+    }
+}
+
+impl DddConfig {
+    /// 从单个 TOML 字符串加载并校验
+    pub fn from_toml_str(input: &str) -> Result<Self, ConfigError> {
+        ConfigLoader::new().with_toml(input).load()
+    }
+
+    /// 校验取值范围，一次性返回全部违规字段
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let mut violations = Vec::new();
+        let mut check = |ok: bool, field: &str, message: &str| {
+            if !ok {
+                violations.push(ConfigViolation::new(field, message));
+            }
+        };
+
+        check(
+            self.engine.deliver_interval_ms > 0,
+            "engine.deliver_interval_ms",
+            "must be greater than 0",
+        );
+        check(
+            self.engine.reclaim_interval_ms > 0,
+            "engine.reclaim_interval_ms",
+            "must be greater than 0",
+        );
+        check(
+            self.engine.handler_concurrency > 0,
+            "engine.handler_concurrency",
+            "must be at least 1",
+        );
+        if let Some(compression) = &self.engine.compression {
+            check(
+                compression.level <= 9,
+                "engine.compression.level",
+                "must be between 0 and 9",
+            );
+        }
+        check(
+            self.engine.retry.max_attempts > 0,
+            "engine.retry.max_attempts",
+            "must be at least 1",
+        );
+        check(
+            self.engine.retry.jitter_percent <= 100,
+            "engine.retry.jitter_percent",
+            "must be between 0 and 100",
+        );
+        check(
+            self.engine.max_deliveries != Some(0),
+            "engine.max_deliveries",
+            "must be at least 1",
+        );
+        check(
+            self.circuit_breaker.failure_threshold > 0,
+            "circuit_breaker.failure_threshold",
+            "must be at least 1",
+        );
+        check(
+            self.snapshot.every != Some(0),
+            "snapshot.every",
+            "must be at least 1 (omit to disable snapshots)",
+        );
+        check(self.bus.capacity > 0, "bus.capacity", "must be at least 1");
+
+        if violations.is_empty() {
+            Ok(())
+        } else {
+            Err(ConfigError::Invalid(violations))
+        }
+    }
+
+    pub fn engine_config(&self) -> EventEngineConfig {
+        EventEngineConfig {
+            deliver_interval: Duration::from_millis(self.engine.deliver_interval_ms),
+            reclaim_interval: Duration::from_millis(self.engine.reclaim_interval_ms),
+            handler_concurrency: self.engine.handler_concurrency,
+        }
+    }
+
+    /// 引擎可选行为（重试、死信、压缩、日志等），经 `EventEngine::builder().tuning(..)` 使用
+    pub fn engine_tuning(&self) -> EngineTuning {
+        let mut tuning = EngineTuning::default()
+            .with_log_level(self.engine.log_level)
+            .with_retry(RetryPolicy {
+                max_attempts: self.engine.retry.max_attempts,
+                backoff: Duration::from_millis(self.engine.retry.backoff_ms),
+                max_backoff: Duration::from_millis(self.engine.retry.max_backoff_ms),
+                jitter: f64::from(self.engine.retry.jitter_percent) / 100.0,
+            });
+        if let Some(c) = self.engine.compression {
+            tuning = tuning.with_compression(PayloadCompression {
+                threshold: c.threshold_bytes,
+                level: c.level,
+            });
+        }
+        if let Some(max) = self.engine.max_deliveries {
+            tuning = tuning.with_max_deliveries(max);
+        }
+        if let Some(window) = self.engine.max_catch_up_window_ms {
+            tuning = tuning.with_max_catch_up_window(Duration::from_millis(window));
+        }
+        if let Some(panics) = self.engine.quarantine_after_panics {
+            tuning = tuning.with_quarantine_after_panics(panics);
+        }
+        tuning
+    }
+
+    pub fn circuit_breaker_config(&self) -> CircuitBreakerConfig {
+        CircuitBreakerConfig {
+            failure_threshold: self.circuit_breaker.failure_threshold,
+            open_duration: Duration::from_millis(self.circuit_breaker.open_duration_ms),
+        }
+    }
+
+    pub fn snapshot_policy(&self) -> SnapshotPolicy {
+        match self.snapshot.every {
+            Some(interval) => SnapshotPolicy::Every(interval),
+            None => SnapshotPolicy::Never,
+        }
+    }
+}
+
+/// 多层配置加载器：按追加顺序合并，后追加的来源覆盖先前来源的同名键
+#[derive(Debug, Default)]
+pub struct ConfigLoader {
+    layers: Vec<Layer>,
+}
+
+#[derive(Debug)]
+enum Layer {
+    Toml { origin: String, input: String },
+    File(String),
+    Vars(Vec<(String, String)>),
+}
+
+impl ConfigLoader {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 追加 TOML 字符串
+    pub fn with_toml(mut self, input: impl Into<String>) -> Self {
+        self.layers.push(Layer::Toml {
+            origin: "inline TOML".to_string(),
+            input: input.into(),
+        });
+        self
+    }
+
+    /// 追加 TOML 文件（加载时读取）
+    pub fn with_file(mut self, path: impl AsRef<Path>) -> Self {
+        self.layers
+            .push(Layer::File(path.as_ref().display().to_string()));
+        self
+    }
+
+    /// 追加当前进程中以 `prefix__` 开头的环境变量
+    pub fn with_env(self, prefix: &str) -> Self {
+        self.with_vars(prefix, std::env::vars())
+    }
+
+    /// 追加给定的键值对（按环境变量规则解析，便于测试与自定义来源）
+    pub fn with_vars(
+        mut self,
+        prefix: &str,
+        vars: impl IntoIterator<Item = (String, String)>,
+    ) -> Self {
+        let prefix = format!("{}{ENV_SEPARATOR}", prefix.to_ascii_uppercase());
+        let vars = vars
+            .into_iter()
+            .filter_map(|(key, value)| {
+                key.to_ascii_uppercase()
+                    .strip_prefix(&prefix)
+                    .map(|path| (path.to_ascii_lowercase(), value))
+            })
+            .collect();
+        self.layers.push(Layer::Vars(vars));
+        self
+    }
+
+    /// 合并全部来源、反序列化并校验
+    pub fn load(self) -> Result<DddConfig, ConfigError> {
+        let mut merged = Table::new();
+        for layer in self.layers {
+            match layer {
+                Layer::Toml { origin, input } => merge(&mut merged, parse_table(&origin, &input)?),
+                Layer::File(path) => {
+                    let input = std::fs::read_to_string(&path).map_err(|e| ConfigError::Io {
+                        path: path.clone(),
+                        message: e.to_string(),
+                    })?;
+                    merge(&mut merged, parse_table(&path, &input)?);
+                }
+                Layer::Vars(vars) => {
+                    for (path, raw) in vars {
+                        set_path(&mut merged, &path, env_value(&raw))?;
+                    }
+                }
+            }
+        }
+
+        let config =
+            DddConfig::deserialize(Value::Table(merged)).map_err(|e| ConfigError::Parse {
+                origin: "merged configuration".to_string(),
+                message: e.to_string(),
+            })?;
+        config.validate()?;
+        Ok(config)
+    }
+}
+
+fn parse_table(origin: &str, input: &str) -> Result<Table, ConfigError> {
+    input.parse::<Table>().map_err(|e| ConfigError::Parse {
+        origin: origin.to_string(),
+        message: e.to_string(),
+    })
+}
+
+/// 递归合并：同名子表逐键合并，其余值整体覆盖
+fn merge(base: &mut Table, overlay: Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(Value::Table(existing)), Value::Table(table)) => merge(existing, table),
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// 按 `a__b__c` 路径写入值，途经的非表值视为冲突
+fn set_path(table: &mut Table, path: &str, value: Value) -> Result<(), ConfigError> {
+    let keys: Vec<&str> = path.split(ENV_SEPARATOR).collect();
+    let (last, parents) = keys.split_last().expect("split yields at least one key");
+    let mut current = table;
+    for key in parents {
+        let entry = current
+            .entry(key.to_string())
+            .or_insert_with(|| Value::Table(Table::new()));
+        current = match entry {
+            Value::Table(next) => next,
+            _ => {
+                return Err(ConfigError::Parse {
+                    origin: "environment".to_string(),
+                    message: format!("`{}` is not a table", keys.join(".")),
+                });
+            }
+        };
+    }
+    current.insert(last.to_string(), value);
+    Ok(())
+}
+
+fn env_value(raw: &str) -> Value {
+    format!("value = {raw}")
+        .parse::<Table>()
+        .ok()
+        .and_then(|mut t| t.remove("value"))
+        .unwrap_or_else(|| Value::String(raw.to_string()))
+}
+
+/// 单个字段的取值违规
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ConfigViolation {
+    /// 字段路径（如 `engine.handler_concurrency`）
+    pub field: String,
+    pub message: String,
+}
+
+impl ConfigViolation {
+    pub fn new(field: impl Into<String>, message: impl Into<String>) -> Self {
+        Self {
+            field: field.into(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for ConfigViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.field, self.message)
+    }
+}
+
+/// 配置加载错误
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error("failed to read config file {path}: {message}")]
+    Io { path: String, message: String },
+    #[error("invalid config in {origin}: {message}")]
+    Parse { origin: String, message: String },
+    #[error("invalid config: {}", join_violations(.0))]
+    Invalid(Vec<ConfigViolation>),
+}
+
+impl ConfigError {
+    /// 取值校验失败的字段（其他错误为空）
+    pub fn violations(&self) -> &[ConfigViolation] {
+        match self {
+            ConfigError::Invalid(violations) => violations,
+            _ => &[],
+        }
+    }
+}
+
+fn join_violations(violations: &[ConfigViolation]) -> String {
+    violations
+        .iter()
+        .map(ToString::to_string)
+        .collect::<Vec<_>>()
+        .join("; ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn loads_layers_and_reports_all_violations() {
+        let config = ConfigLoader::new()
+            .with_toml(
+                r#"
+                [engine]
+                deliver_interval_ms = 500
+                handler_concurrency = 4
+                compression = { threshold_bytes = 2048 }
+                retry = { max_attempts = 3, jitter_percent = 25 }
+                max_deliveries = 10
+                max_catch_up_window_ms = 60000
+                quarantine_after_panics = 3
+
+                [snapshot]
+                every = 50
+                "#,
+            )
+            .with_vars(
+                "ddd",
+                [
+                    (
+                        "DDD__ENGINE__HANDLER_CONCURRENCY".to_string(),
+                        "16".to_string(),
+                    ),
+                    ("DDD__ENGINE__NAME".to_string(), "orders".to_string()),
+                    ("DDD__ENGINE__LOG_LEVEL".to_string(), "events".to_string()),
+                    ("OTHER__BUS__CAPACITY".to_string(), "1".to_string()),
+                ],
+            )
+            .load()
+            .unwrap();
+
+        assert_eq!(config.engine.name, "orders");
+        let engine = config.engine_config();
+        assert_eq!(engine.deliver_interval, Duration::from_millis(500));
+        assert_eq!(engine.reclaim_interval, Duration::from_secs(60));
+        assert_eq!(engine.handler_concurrency, 16);
+        let tuning = config.engine_tuning();
+        assert_eq!(tuning.log_level(), EngineLogLevel::Events);
+        assert_eq!(
+            tuning.compression().map(|c| (c.threshold, c.level)),
+            Some((2048, 6))
+        );
+        assert_eq!(tuning.retry().max_attempts, 3);
+        assert_eq!(tuning.retry().backoff, Duration::from_millis(100));
+        assert_eq!(tuning.retry().jitter, 0.25);
+        assert_eq!(tuning.max_deliveries(), Some(10));
+        assert_eq!(tuning.max_catch_up_window(), Some(Duration::from_secs(60)));
+        assert_eq!(tuning.quarantine_after_panics(), Some(3));
+        assert_eq!(config.snapshot_policy(), SnapshotPolicy::Every(50));
+        assert_eq!(config.bus.capacity, 1024);
+        assert_eq!(config.circuit_breaker_config().failure_threshold, 5);
+
+        let err =
+            DddConfig::from_toml_str("[engine]\nhandler_concurrency = 0\n[snapshot]\nevery = 0\n")
+                .unwrap_err();
+        let fields: Vec<&str> = err.violations().iter().map(|v| v.field.as_str()).collect();
+        assert_eq!(fields, ["engine.handler_concurrency", "snapshot.every"]);
+
+        let err = DddConfig::from_toml_str("[engine]\nhandler_concurency = 8\n").unwrap_err();
+        assert!(err.to_string().contains("handler_concurency"));
+
+        let err = ConfigLoader::new()
+            .with_vars(
+                "DDD",
+                [(
+                    "DDD__ENGINE__DELIVER_INTERVAL_MS".to_string(),
+                    "soon".to_string(),
+                )],
+            )
+            .load()
+            .unwrap_err();
+        assert!(matches!(err, ConfigError::Parse { .. }));
+    }
+}
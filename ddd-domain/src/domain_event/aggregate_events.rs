@@ -5,7 +5,21 @@ use std::vec::IntoIter;
 
 use crate::aggregate::Aggregate;
 
+use super::DomainEvent;
 use super::event_envelope::EventEnvelope;
+use super::field_changed::{FieldChanges, TrackChanges};
+
+/// 单个事件带来的状态变更
+#[derive(Debug, Clone, PartialEq)]
+pub struct ChangeRecord {
+    pub event_id: String,
+    pub event_type: String,
+    pub occurred_at: DateTime<Utc>,
+    /// 触发事件的主体 ID
+    pub actor_id: Option<String>,
+    /// 应用该事件前后发生变化的字段
+    pub changes: FieldChanges,
+}
 
 /// 聚合事件集合，按时间顺序排列，便于获取创建/修改者与时间等信息
 pub struct AggregateEvents<A>
@@ -66,6 +80,30 @@ where
     pub fn iter(&self) -> Iter<'_, EventEnvelope<A>> {
         self.events.iter()
     }
+
+    /// 从初始状态依次应用事件，记录每个事件前后的字段变更（回答“改了什么”）
+    ///
+    /// 事件集合须从聚合的第一个事件开始；未改变任何被跟踪字段的事件，其 `changes` 为空。
+    pub fn change_history(&self) -> Vec<ChangeRecord>
+    where
+        A: TrackChanges + Clone,
+    {
+        let mut state = A::default();
+        self.events
+            .iter()
+            .map(|envelope| {
+                let before = state.clone();
+                state.apply(&envelope.payload);
+                ChangeRecord {
+                    event_id: envelope.payload.event_id().to_string(),
+                    event_type: envelope.payload.event_type().to_string(),
+                    occurred_at: *envelope.metadata.occurred_at(),
+                    actor_id: envelope.context.actor_id().map(ToString::to_string),
+                    changes: before.changes(&state),
+                }
+            })
+            .collect()
+    }
 }
 
 impl<A> IntoIterator for AggregateEvents<A>
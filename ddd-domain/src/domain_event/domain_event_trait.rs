@@ -2,12 +2,23 @@ use serde::Serialize;
 use serde::de::DeserializeOwned;
 use std::fmt;
 
+use super::{EventDescriptor, PayloadFormat};
+use crate::persist::PiiField;
 use crate::value_object::Version;
 
 /// 领域事件载荷需要满足的通用能力边界
 pub trait DomainEvent:
     Clone + PartialEq + fmt::Debug + Serialize + DeserializeOwned + Send + Sync + 'static
 {
+    /// 全部变体的静态元信息（`#[domain_event]` 自动生成）
+    const DESCRIPTORS: &'static [EventDescriptor] = &[];
+
+    /// 载荷存储格式（`#[domain_event(payload = "event_type")]` 设置）
+    const PAYLOAD_FORMAT: PayloadFormat = PayloadFormat::VariantTagged;
+
+    /// 字段上 `#[pii(...)]` 声明的个人数据字段（`#[domain_event]` 自动生成）
+    const PII_FIELDS: &'static [PiiField] = &[];
+
     /// 事件唯一标识
     fn event_id(&self) -> &str;
 
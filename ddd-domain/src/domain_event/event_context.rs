@@ -1,4 +1,6 @@
+use crate::persist::SerializedEvent;
 use bon::Builder;
+use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 
 /// 业务上下文信息
@@ -14,6 +16,13 @@ pub struct EventContext {
     actor_type: Option<String>,
     /// 触发事件的主体ID
     actor_id: Option<String>,
+    /// 因果链深度：由事件触发的命令（策略/Saga）每经过一跳加 1，缺省为 0
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    causation_depth: Option<u32>,
+    /// 命令被接收的时间（由命令总线或调用方设置），写入事件元数据用于端到端延迟统计；
+    /// 不随上下文序列化，由 `SerializedEvent::command_received_at` 承载
+    #[serde(skip)]
+    command_received_at: Option<DateTime<Utc>>,
 
     #[serde(skip_serializing_if = "Option::is_none")]
     extensions: Option<serde_json::Value>,
@@ -40,6 +49,19 @@ impl EventContext {
         self.actor_id.as_deref()
     }
 
+    pub fn causation_depth(&self) -> u32 {
+        self.causation_depth.unwrap_or(0)
+    }
+
+    pub fn command_received_at(&self) -> Option<DateTime<Utc>> {
+        self.command_received_at
+    }
+
+    /// 设置命令接收时间（已设置时保留原值，以最早的接收时刻为准）
+    pub fn stamp_command_received(&mut self, at: DateTime<Utc>) {
+        self.command_received_at.get_or_insert(at);
+    }
+
     pub fn extensions(&self) -> Option<&serde_json::Value> {
         self.extensions.as_ref()
     }
@@ -48,4 +70,26 @@ impl EventContext {
         self.actor_type = Some(actor_type.into());
         self.actor_id = Some(actor_id.into());
     }
+
+    /// 由事件触发后续命令时使用的上下文
+    ///
+    /// 继承关联 ID（缺失时以该事件 ID 作为关联 ID）与执行主体，
+    /// 因果 ID 指向该事件，因果链深度在该事件基础上加 1。
+    pub fn caused_by(event: &SerializedEvent) -> Self {
+        Self {
+            correlation_id: Some(
+                event
+                    .correlation_id()
+                    .unwrap_or(event.event_id())
+                    .to_string(),
+            ),
+            causation_id: Some(event.event_id().to_string()),
+            duration_ms: None,
+            actor_type: event.actor_type().map(ToString::to_string),
+            actor_id: event.actor_id().map(ToString::to_string),
+            causation_depth: Some(event.causation_depth() + 1),
+            command_received_at: None,
+            extensions: None,
+        }
+    }
 }
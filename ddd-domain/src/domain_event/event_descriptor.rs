@@ -0,0 +1,24 @@
+use serde::Serialize;
+
+/// 事件静态元信息：事件类型与载荷版本
+///
+/// 由 `#[domain_event]` 为每个变体生成关联常量（如 `BankEvent::OPENED`），
+/// 并汇总到 `DomainEvent::DESCRIPTORS`，无需构造事件实例即可引用，
+/// 用于处理器订阅、Schema 导出与上抬器覆盖检查等场景，替代字符串字面量。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+pub struct EventDescriptor {
+    /// 枚举变体名
+    pub variant: &'static str,
+    pub event_type: &'static str,
+    pub event_version: usize,
+}
+
+impl EventDescriptor {
+    /// 按事件类型查找描述
+    pub fn find(
+        descriptors: &'static [EventDescriptor],
+        event_type: &str,
+    ) -> Option<&'static EventDescriptor> {
+        descriptors.iter().find(|d| d.event_type == event_type)
+    }
+}
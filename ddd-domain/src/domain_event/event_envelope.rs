@@ -1,5 +1,6 @@
 use crate::aggregate::Aggregate;
-use chrono::Utc;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
 
 use super::event_context::EventContext;
 use super::metadata::Metadata;
@@ -24,6 +25,7 @@ where
             .aggregate_id(aggregate_id.to_string())
             .aggregate_type(A::TYPE.to_string())
             .occurred_at(Utc::now())
+            .maybe_command_received_at(context.command_received_at())
             .build();
 
         Self {
@@ -32,4 +34,36 @@ where
             context,
         }
     }
+
+    /// 以指定的发生时间构造信封（用于导入历史数据），元数据标记为回填
+    pub fn new_with(
+        aggregate_id: &A::Id,
+        payload: A::Event,
+        context: EventContext,
+        occurred_at: DateTime<Utc>,
+    ) -> Self {
+        let metadata = Metadata::builder()
+            .aggregate_id(aggregate_id.to_string())
+            .aggregate_type(A::TYPE.to_string())
+            .occurred_at(occurred_at)
+            .backfilled(true)
+            .build();
+
+        Self {
+            metadata,
+            payload,
+            context,
+        }
+    }
+
+    /// 附带聚合状态快照（记录在元数据中）
+    pub fn with_state_snapshot(mut self, state_snapshot: Value) -> Self {
+        self.metadata.set_state_snapshot(state_snapshot);
+        self
+    }
+
+    /// 应用该事件后的聚合状态（或选定字段），见 `StateTransfer`
+    pub fn state_snapshot(&self) -> Option<&Value> {
+        self.metadata.state_snapshot()
+    }
 }
@@ -0,0 +1,109 @@
+//! 事件 ID 生成器
+//!
+//! 聚合在 `execute` 中通过 `next_event_id()` 为新事件生成 ID，
+//! 默认使用随机 UUID v4；测试中可替换为确定性生成器（见 `testing::DeterministicEventIdGenerator`），
+//! 使录制的事件流、黄金文件与断言中的事件 ID 在多次运行间保持稳定。
+//!
+//! 生成器按以下优先级解析：
+//! 1. 当前线程通过 `scoped_event_id_generator` 安装的生成器（守卫析构后恢复）；
+//! 2. 通过 `set_event_id_generator` 设置的全局生成器；
+//! 3. 默认的 `UuidEventIdGenerator`。
+//!
+use std::cell::RefCell;
+use std::sync::{Arc, OnceLock, RwLock};
+
+/// 事件 ID 生成器
+pub trait EventIdGenerator: Send + Sync {
+    fn next_id(&self) -> String;
+}
+
+/// 默认生成器：随机 UUID v4
+#[derive(Clone, Copy, Debug, Default)]
+pub struct UuidEventIdGenerator;
+
+impl EventIdGenerator for UuidEventIdGenerator {
+    fn next_id(&self) -> String {
+        uuid::Uuid::new_v4().to_string()
+    }
+}
+
+static GLOBAL: OnceLock<RwLock<Arc<dyn EventIdGenerator>>> = OnceLock::new();
+
+thread_local! {
+    static SCOPED: RefCell<Option<Arc<dyn EventIdGenerator>>> = const { RefCell::new(None) };
+}
+
+fn global() -> &'static RwLock<Arc<dyn EventIdGenerator>> {
+    GLOBAL.get_or_init(|| RwLock::new(Arc::new(UuidEventIdGenerator)))
+}
+
+/// 生成下一个事件 ID
+pub fn next_event_id() -> String {
+    if let Some(id) = SCOPED.with(|s| s.borrow().as_ref().map(|g| g.next_id())) {
+        return id;
+    }
+
+    let generator = Arc::clone(&global().read().unwrap());
+    generator.next_id()
+}
+
+/// 设置进程级全局生成器
+pub fn set_event_id_generator(generator: Arc<dyn EventIdGenerator>) {
+    *global().write().unwrap() = generator;
+}
+
+/// 在当前线程安装生成器，返回的守卫析构时恢复之前的生成器
+///
+/// 仅对当前线程生效，适用于 `#[tokio::test]` 默认的单线程运行时；
+/// 多线程运行时下任务可能迁移到其他线程，应改用 `set_event_id_generator`。
+pub fn scoped_event_id_generator(generator: Arc<dyn EventIdGenerator>) -> EventIdGeneratorGuard {
+    let previous = SCOPED.with(|s| s.borrow_mut().replace(generator));
+    EventIdGeneratorGuard { previous }
+}
+
+/// `scoped_event_id_generator` 返回的守卫
+#[must_use = "the generator is uninstalled when the guard is dropped"]
+pub struct EventIdGeneratorGuard {
+    previous: Option<Arc<dyn EventIdGenerator>>,
+}
+
+impl Drop for EventIdGeneratorGuard {
+    fn drop(&mut self) {
+        let previous = self.previous.take();
+        SCOPED.with(|s| *s.borrow_mut() = previous);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct Counting(AtomicUsize);
+
+    impl EventIdGenerator for Counting {
+        fn next_id(&self) -> String {
+            format!("evt-{}", self.0.fetch_add(1, Ordering::Relaxed))
+        }
+    }
+
+    #[test]
+    fn scoped_generator_is_restored_on_drop() {
+        assert!(uuid::Uuid::parse_str(&next_event_id()).is_ok());
+
+        {
+            let _outer = scoped_event_id_generator(Arc::new(Counting::default()));
+            assert_eq!(next_event_id(), "evt-0");
+
+            {
+                let _inner = scoped_event_id_generator(Arc::new(Counting(AtomicUsize::new(100))));
+                assert_eq!(next_event_id(), "evt-100");
+            }
+
+            assert_eq!(next_event_id(), "evt-1");
+        }
+
+        assert!(uuid::Uuid::parse_str(&next_event_id()).is_ok());
+    }
+}
@@ -1,4 +1,5 @@
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 /// 字段变更封装，包含旧值与新值
 #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
@@ -29,3 +30,24 @@ where
         self.old != self.new
     }
 }
+
+impl FieldChanged<Value> {
+    /// 以 JSON 表示记录字段的新旧值（无法序列化时记为 `null`）
+    pub fn json<T: Serialize>(old: &T, new: &T) -> Self {
+        Self::new(
+            serde_json::to_value(old).unwrap_or(Value::Null),
+            serde_json::to_value(new).unwrap_or(Value::Null),
+        )
+    }
+}
+
+/// 按字段名记录的变更列表
+pub type FieldChanges = Vec<(String, FieldChanged<Value>)>;
+
+/// 比较同一类型的两个实例，得出发生变化的字段
+///
+/// 通常经 `#[derive(TrackChanges)]` 生成：逐字段以 `PartialEq` 比较，跳过 `id`/`version`
+/// 与标注 `#[track(skip)]` 的字段，按声明顺序返回变化字段的新旧值。
+pub trait TrackChanges {
+    fn changes(&self, new: &Self) -> FieldChanges;
+}
@@ -1,6 +1,7 @@
 use bon::Builder;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use serde_json::Value;
 
 /// 元数据
 #[derive(Builder, Default, Debug, Clone, Serialize, Deserialize)]
@@ -8,6 +9,16 @@ pub struct Metadata {
     aggregate_id: String,
     aggregate_type: String,
     occurred_at: DateTime<Utc>,
+    /// 是否为回填事件（导入历史数据时以原始时间构造，`occurred_at` 非写入时刻）
+    #[builder(default)]
+    #[serde(default)]
+    backfilled: bool,
+    /// 触发该事件的命令被接收的时间，用于统计命令到投影更新的端到端延迟
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    command_received_at: Option<DateTime<Utc>>,
+    /// 应用该事件后的聚合状态（或选定字段），见 `StateTransfer`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    state_snapshot: Option<Value>,
 }
 
 impl Metadata {
@@ -22,4 +33,20 @@ impl Metadata {
     pub fn occurred_at(&self) -> &DateTime<Utc> {
         &self.occurred_at
     }
+
+    pub fn is_backfilled(&self) -> bool {
+        self.backfilled
+    }
+
+    pub fn command_received_at(&self) -> Option<&DateTime<Utc>> {
+        self.command_received_at.as_ref()
+    }
+
+    pub fn state_snapshot(&self) -> Option<&Value> {
+        self.state_snapshot.as_ref()
+    }
+
+    pub(crate) fn set_state_snapshot(&mut self, state_snapshot: Value) {
+        self.state_snapshot = Some(state_snapshot);
+    }
 }
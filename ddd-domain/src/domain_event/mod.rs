@@ -1,18 +1,35 @@
 //! 领域事件（Domain Event）与事件集合
 //!
-//! 定义事件载荷需要实现的最小接口（`DomainEvent`），以及将事件与元数据/上下文
-//! 封装后的 `EventEnvelope` 与辅助集合类型 `AggregateEvents`。
+//! 定义事件载荷需要实现的最小接口（`DomainEvent`）与静态元信息（`EventDescriptor`），以及将事件与元数据/上下文
+//! 封装后的 `EventEnvelope` 与辅助集合类型 `AggregateEvents`；
+//! 以及事件携带状态传递（`StateTransfer`）的配置、事件 ID 生成（`next_event_id`）
+//! 与载荷存储格式（`PayloadFormat`，可按 `event_type` 存储以解耦 Rust 变体名）；
+//! `EventTaxonomy` 校验事件命名规范与版本约束；`TrackChanges`（可 `#[derive]`）比较前后状态得出字段变更，
+//! `AggregateEvents::change_history` 据此生成逐事件的变更记录。
 
 mod aggregate_events;
 mod domain_event_trait;
 mod event_context;
+mod event_descriptor;
 mod event_envelope;
+mod event_id;
 mod field_changed;
 mod metadata;
+mod payload_format;
+mod state_transfer;
+mod taxonomy;
 
-pub use aggregate_events::AggregateEvents;
+pub use aggregate_events::{AggregateEvents, ChangeRecord};
 pub use domain_event_trait::DomainEvent;
 pub use event_context::EventContext;
+pub use event_descriptor::EventDescriptor;
 pub use event_envelope::EventEnvelope;
-pub use field_changed::FieldChanged;
+pub use event_id::{
+    EventIdGenerator, EventIdGeneratorGuard, UuidEventIdGenerator, next_event_id,
+    scoped_event_id_generator, set_event_id_generator,
+};
+pub use field_changed::{FieldChanged, FieldChanges, TrackChanges};
 pub use metadata::Metadata;
+pub use payload_format::PayloadFormat;
+pub use state_transfer::{StateSelection, StateTransfer};
+pub use taxonomy::EventTaxonomy;
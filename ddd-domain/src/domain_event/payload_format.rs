@@ -0,0 +1,155 @@
+use serde::de::Error as _;
+use serde_json::{Map, Value};
+
+use super::{DomainEvent, EventDescriptor};
+
+/// 事件载荷的存储格式
+///
+/// - `VariantTagged`（默认）：serde 默认的外部标签，载荷内嵌枚举变体名，如 `{"Deposited": {...}}`；
+/// - `EventType`：只存储变体字段，读取时按 `event_type` 查找当前变体名还原，
+///   重命名 Rust 变体不影响已存储的事件（`event_type` 保持不变即可）。
+///
+/// `EventType` 格式兼容读取 `VariantTagged` 格式写入的历史载荷。
+/// 通过 `#[domain_event(payload = "event_type")]` 启用。
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum PayloadFormat {
+    #[default]
+    VariantTagged,
+    EventType,
+}
+
+impl PayloadFormat {
+    /// 按格式编码事件载荷
+    pub fn encode<E: DomainEvent>(self, event: &E) -> serde_json::Result<Value> {
+        let value = serde_json::to_value(event)?;
+        match (self, value) {
+            (PayloadFormat::EventType, Value::Object(tagged)) if tagged.len() == 1 => Ok(tagged
+                .into_iter()
+                .next()
+                .map(|(_, v)| v)
+                .unwrap_or_default()),
+            (_, value) => Ok(value),
+        }
+    }
+
+    /// 按格式解码事件载荷
+    pub fn decode<E: DomainEvent>(
+        self,
+        event_type: &str,
+        payload: &Value,
+    ) -> serde_json::Result<E> {
+        if self == PayloadFormat::VariantTagged {
+            return serde_json::from_value(payload.clone());
+        }
+
+        let descriptor = EventDescriptor::find(E::DESCRIPTORS, event_type)
+            .ok_or_else(|| serde_json::Error::custom(format!("unknown event type {event_type}")))?;
+
+        // 兼容以变体名为标签写入的历史载荷
+        if let Value::Object(fields) = payload
+            && fields.len() == 1
+            && fields.get(descriptor.variant).is_some_and(Value::is_object)
+        {
+            return serde_json::from_value(payload.clone());
+        }
+
+        let mut tagged = Map::new();
+        tagged.insert(descriptor.variant.to_string(), payload.clone());
+        serde_json::from_value(Value::Object(tagged))
+    }
+
+    /// 定位载荷中变体字段所在的对象（不复制载荷），用于按需反序列化部分字段
+    pub fn fields<'a, E: DomainEvent>(
+        self,
+        event_type: &str,
+        payload: &'a Value,
+    ) -> serde_json::Result<&'a Value> {
+        let descriptor = EventDescriptor::find(E::DESCRIPTORS, event_type)
+            .ok_or_else(|| serde_json::Error::custom(format!("unknown event type {event_type}")))?;
+
+        if self == PayloadFormat::VariantTagged {
+            return payload.get(descriptor.variant).ok_or_else(|| {
+                serde_json::Error::custom(format!("payload has no variant {}", descriptor.variant))
+            });
+        }
+
+        // 兼容以变体名为标签写入的历史载荷
+        match payload {
+            Value::Object(fields) if fields.len() == 1 => match fields.get(descriptor.variant) {
+                Some(tagged) if tagged.is_object() => Ok(tagged),
+                _ => Ok(payload),
+            },
+            _ => Ok(payload),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::value_object::Version;
+    use ddd_macros::domain_event;
+    use serde::{Deserialize, Serialize};
+    use serde_json::json;
+
+    #[domain_event(version = 1, payload = "event_type")]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    enum Before {
+        #[event(event_type = "account.deposited")]
+        Deposited { amount: i64 },
+    }
+
+    #[domain_event(version = 1, payload = "event_type")]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    enum After {
+        #[event(event_type = "account.deposited")]
+        FundsDeposited { amount: i64 },
+    }
+
+    #[test]
+    fn payload_keyed_by_event_type_survives_variant_rename() {
+        assert_eq!(Before::PAYLOAD_FORMAT, PayloadFormat::EventType);
+        let event = Before::Deposited {
+            id: "e-1".into(),
+            aggregate_version: Version::from_value(1),
+            amount: 10,
+        };
+
+        let payload = Before::PAYLOAD_FORMAT.encode(&event).unwrap();
+        assert_eq!(payload["amount"], 10);
+        assert!(payload.get("Deposited").is_none());
+
+        let renamed: After = After::PAYLOAD_FORMAT
+            .decode("account.deposited", &payload)
+            .unwrap();
+        assert!(matches!(renamed, After::FundsDeposited { amount: 10, .. }));
+
+        // 历史载荷（变体名标签）仍可读取
+        let legacy = PayloadFormat::VariantTagged.encode(&event).unwrap();
+        let decoded: Before = Before::PAYLOAD_FORMAT
+            .decode("account.deposited", &legacy)
+            .unwrap();
+        assert_eq!(decoded, event);
+
+        let err = After::PAYLOAD_FORMAT
+            .decode::<After>("account.withdrawn", &json!({}))
+            .unwrap_err();
+        assert!(err.to_string().contains("unknown event type"));
+    }
+
+    #[test]
+    fn locates_variant_fields_without_copying() {
+        let current = json!({ "amount": 10 });
+        let legacy = json!({ "Deposited": { "amount": 10 } });
+        let fields = |format: PayloadFormat, payload| {
+            format
+                .fields::<Before>("account.deposited", payload)
+                .map(|v| v["amount"].clone())
+        };
+
+        assert_eq!(fields(PayloadFormat::EventType, &current).unwrap(), 10);
+        assert_eq!(fields(PayloadFormat::EventType, &legacy).unwrap(), 10);
+        assert_eq!(fields(PayloadFormat::VariantTagged, &legacy).unwrap(), 10);
+        assert!(fields(PayloadFormat::VariantTagged, &current).is_err());
+    }
+}
@@ -0,0 +1,76 @@
+use crate::aggregate::Aggregate;
+use serde_json::{Map, Value};
+use std::collections::HashMap;
+
+/// 状态快照的选取方式
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum StateSelection {
+    /// 应用事件后的完整聚合状态
+    Full,
+    /// 仅选取聚合状态中的顶层字段
+    Fields(Vec<String>),
+}
+
+/// 事件携带状态传递（Event-Carried State Transfer）配置
+///
+/// 按事件类型声明是否在事件上附带应用该事件后的聚合状态（“胖事件”），
+/// 使下游消费者无需回调源服务即可获得所需状态。由 `AggregateRoot` 在执行命令时填充。
+#[derive(Debug, Clone, Default)]
+pub struct StateTransfer {
+    rules: HashMap<String, StateSelection>,
+}
+
+impl StateTransfer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 该事件类型附带完整聚合状态
+    pub fn full(mut self, event_type: impl Into<String>) -> Self {
+        self.rules.insert(event_type.into(), StateSelection::Full);
+        self
+    }
+
+    /// 该事件类型仅附带指定的顶层字段
+    pub fn fields<I, S>(mut self, event_type: impl Into<String>, fields: I) -> Self
+    where
+        I: IntoIterator<Item = S>,
+        S: Into<String>,
+    {
+        let fields = fields.into_iter().map(Into::into).collect();
+        self.rules
+            .insert(event_type.into(), StateSelection::Fields(fields));
+        self
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.rules.is_empty()
+    }
+
+    pub fn selection(&self, event_type: &str) -> Option<&StateSelection> {
+        self.rules.get(event_type)
+    }
+
+    /// 按事件类型生成聚合状态快照；未启用或序列化失败时返回 `None`
+    pub fn snapshot<A>(&self, event_type: &str, aggregate: &A) -> Option<Value>
+    where
+        A: Aggregate,
+    {
+        let selection = self.selection(event_type)?;
+        let state = serde_json::to_value(aggregate).ok()?;
+
+        match selection {
+            StateSelection::Full => Some(state),
+            StateSelection::Fields(fields) => {
+                let Value::Object(mut all) = state else {
+                    return Some(state);
+                };
+                let picked: Map<String, Value> = fields
+                    .iter()
+                    .filter_map(|f| all.remove_entry(f.as_str()))
+                    .collect();
+                Some(Value::Object(picked))
+            }
+        }
+    }
+}
@@ -0,0 +1,189 @@
+use super::EventDescriptor;
+use crate::error::{DomainError, DomainResult};
+use std::fmt;
+use std::sync::Arc;
+
+type NameRule = Arc<dyn Fn(&str) -> bool + Send + Sync>;
+
+/// 事件命名规范与版本约束
+///
+/// 内置规范（`dotted`）：事件类型由 `.` 分隔的若干段组成，每段以小写字母开头，
+/// 仅含小写字母、数字与下划线（等价于 `^[a-z][a-z0-9_]*(\.[a-z][a-z0-9_]*)+$`）。
+/// 可追加自定义规则与版本上限；`validate` 汇总全部违规，便于在启动时一次性报告。
+#[derive(Clone)]
+pub struct EventTaxonomy {
+    segments: Option<(usize, usize)>,
+    rules: Vec<(String, NameRule)>,
+    max_version: Option<usize>,
+}
+
+impl Default for EventTaxonomy {
+    fn default() -> Self {
+        Self::dotted()
+    }
+}
+
+impl fmt::Debug for EventTaxonomy {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("EventTaxonomy")
+            .field("segments", &self.segments)
+            .field(
+                "rules",
+                &self.rules.iter().map(|(name, _)| name).collect::<Vec<_>>(),
+            )
+            .field("max_version", &self.max_version)
+            .finish()
+    }
+}
+
+impl EventTaxonomy {
+    /// 不限制名称格式，仅要求版本不小于 1
+    pub fn permissive() -> Self {
+        Self {
+            segments: None,
+            rules: Vec::new(),
+            max_version: None,
+        }
+    }
+
+    /// `<segment>.<segment>[...]` 小写蛇形分段命名（至少两段）
+    pub fn dotted() -> Self {
+        Self {
+            segments: Some((2, usize::MAX)),
+            ..Self::permissive()
+        }
+    }
+
+    /// 限定分段数量范围（如 `segments(2, 2)` 要求恰为 `aggregate.action`）
+    pub fn segments(mut self, min: usize, max: usize) -> Self {
+        self.segments = Some((min.max(1), max.max(min)));
+        self
+    }
+
+    /// 追加自定义名称规则，`description` 用于违规报告
+    pub fn rule<F>(mut self, description: impl Into<String>, rule: F) -> Self
+    where
+        F: Fn(&str) -> bool + Send + Sync + 'static,
+    {
+        self.rules.push((description.into(), Arc::new(rule)));
+        self
+    }
+
+    /// 事件版本上限（超过即视为版本膨胀，应考虑合并上抬链）
+    pub fn max_version(mut self, max: usize) -> Self {
+        self.max_version = Some(max);
+        self
+    }
+
+    /// 检查单个事件类型（及版本），返回违规原因
+    pub fn check(&self, event_type: &str, event_version: Option<usize>) -> Vec<String> {
+        let mut reasons = Vec::new();
+
+        if let Some((min, max)) = self.segments {
+            let segments: Vec<&str> = event_type.split('.').collect();
+            if segments.len() < min || segments.len() > max {
+                reasons.push(match (min, max) {
+                    (min, usize::MAX) => format!("expected at least {min} dot-separated segments"),
+                    (min, max) if min == max => format!("expected {min} dot-separated segments"),
+                    (min, max) => format!("expected {min} to {max} dot-separated segments"),
+                });
+            }
+            if let Some(bad) = segments.iter().find(|s| !is_snake_segment(s)) {
+                reasons.push(format!(
+                    "segment {bad:?} must be lowercase snake_case starting with a letter"
+                ));
+            }
+        }
+
+        for (description, rule) in &self.rules {
+            if !rule(event_type) {
+                reasons.push(format!("violates rule: {description}"));
+            }
+        }
+
+        match (event_version, self.max_version) {
+            (Some(0), _) => reasons.push("event version must be at least 1".to_string()),
+            (Some(version), Some(max)) if version > max => {
+                reasons.push(format!("event version {version} exceeds maximum {max}"))
+            }
+            _ => {}
+        }
+        reasons
+    }
+
+    /// 校验一组事件描述；存在违规时返回 `EVENT_TAXONOMY_VIOLATION`，消息列出全部违规
+    pub fn validate(&self, descriptors: &[EventDescriptor]) -> DomainResult<()> {
+        let violations: Vec<String> = descriptors
+            .iter()
+            .flat_map(|d| {
+                self.check(d.event_type, Some(d.event_version))
+                    .into_iter()
+                    .map(move |reason| format!("{}@v{}: {reason}", d.event_type, d.event_version))
+            })
+            .collect();
+
+        if violations.is_empty() {
+            return Ok(());
+        }
+        Err(
+            DomainError::invalid_state(format!("event taxonomy: {}", violations.join("; ")))
+                .with_code("EVENT_TAXONOMY_VIOLATION"),
+        )
+    }
+}
+
+fn is_snake_segment(segment: &str) -> bool {
+    let mut chars = segment.chars();
+    chars.next().is_some_and(|c| c.is_ascii_lowercase())
+        && chars.all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::ErrorKind;
+
+    #[test]
+    fn reports_naming_and_version_violations() {
+        let taxonomy = EventTaxonomy::dotted()
+            .segments(2, 2)
+            .rule("must not end with _event", |t| !t.ends_with("_event"))
+            .max_version(5);
+
+        assert!(taxonomy.check("order.placed", Some(1)).is_empty());
+        assert_eq!(taxonomy.check("OrderPlaced", Some(1)).len(), 2);
+        assert_eq!(
+            taxonomy.check("order.line.added", None),
+            vec!["expected 2 dot-separated segments"]
+        );
+        assert_eq!(
+            taxonomy.check("order.placed_event", Some(7)),
+            vec![
+                "violates rule: must not end with _event",
+                "event version 7 exceeds maximum 5"
+            ]
+        );
+
+        let descriptors = [
+            EventDescriptor {
+                variant: "Placed",
+                event_type: "order.placed",
+                event_version: 1,
+            },
+            EventDescriptor {
+                variant: "Shipped",
+                event_type: "order.Shipped",
+                event_version: 0,
+            },
+        ];
+        let err = taxonomy.validate(&descriptors).unwrap_err();
+        assert!(err.matches(ErrorKind::InvalidState, "EVENT_TAXONOMY_VIOLATION"));
+        assert!(err.to_string().contains("order.Shipped@v0"));
+        assert!(err.to_string().contains("at least 1"));
+        assert!(
+            EventTaxonomy::permissive()
+                .validate(&descriptors[..1])
+                .is_ok()
+        );
+    }
+}
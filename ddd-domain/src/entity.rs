@@ -4,6 +4,7 @@
 //!
 use std::{fmt::Display, str::FromStr};
 
+use crate::persist::PiiField;
 use crate::value_object::Version;
 
 /// 具备唯一标识与版本的实体抽象
@@ -11,6 +12,9 @@ pub trait Entity: Send + Sync {
     /// 实体标识类型，要求可解析、可显示与可克隆
     type Id: FromStr + Clone + Display + Send + Sync;
 
+    /// 字段上 `#[pii(...)]` 声明的个人数据字段（`#[entity]` 自动生成），指针相对序列化后的实体状态
+    const PII_FIELDS: &'static [PiiField] = &[];
+
     /// 使用给定标识创建实体（聚合）
     fn new(aggregate_id: Self::Id, version: Version) -> Self;
 
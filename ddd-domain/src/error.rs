@@ -176,6 +176,16 @@ use std::fmt;
 ///
 /// let conflict = ErrorKind::Conflict;
 /// assert!(conflict.is_retryable());
+///
+/// // 应用自定义分类
+/// const RATE_LIMITED: ErrorKind = ErrorKind::Custom {
+///     http_status: 429,
+///     code: "RATE_LIMITED",
+///     retryable: true,
+/// };
+/// assert_eq!(RATE_LIMITED.http_status(), 429);
+/// assert_eq!(RATE_LIMITED.default_code(), "RATE_LIMITED");
+/// assert!(RATE_LIMITED.is_retryable());
 /// ```
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
 #[non_exhaustive]
@@ -194,6 +204,14 @@ pub enum ErrorKind {
     Unauthorized,
     /// 内部错误（数据库、序列化等基础设施错误）
     Internal,
+    /// 应用自定义分类（如 `RateLimited` 429、`PreconditionFailed` 412）
+    ///
+    /// 建议以常量声明后复用；`code` 同时作为默认错误码。
+    Custom {
+        http_status: u16,
+        code: &'static str,
+        retryable: bool,
+    },
 }
 
 impl ErrorKind {
@@ -208,6 +226,7 @@ impl ErrorKind {
     /// | Conflict        | 409         |
     /// | InvalidState    | 422         |
     /// | Internal        | 500         |
+    /// | Custom          | 自定义      |
     #[must_use]
     pub const fn http_status(self) -> u16 {
         match self {
@@ -217,6 +236,7 @@ impl ErrorKind {
             Self::Conflict => 409,
             Self::InvalidState => 422,
             Self::Internal => 500,
+            Self::Custom { http_status, .. } => http_status,
         }
     }
 
@@ -233,15 +253,27 @@ impl ErrorKind {
             Self::Conflict => "CONFLICT",
             Self::Unauthorized => "UNAUTHORIZED",
             Self::Internal => "INTERNAL_ERROR",
+            Self::Custom { code, .. } => code,
         }
     }
 
     /// 是否可重试
     ///
-    /// 目前只有 [`ErrorKind::Conflict`] 返回 `true`，表示乐观锁冲突可以重试。
+    /// 内置分类中只有 [`ErrorKind::Conflict`] 返回 `true`，表示乐观锁冲突可以重试；
+    /// [`ErrorKind::Custom`] 由其 `retryable` 决定。
     #[must_use]
     pub const fn is_retryable(self) -> bool {
-        matches!(self, Self::Conflict)
+        match self {
+            Self::Conflict => true,
+            Self::Custom { retryable, .. } => retryable,
+            _ => false,
+        }
+    }
+
+    /// 是否为应用自定义分类
+    #[must_use]
+    pub const fn is_custom(self) -> bool {
+        matches!(self, Self::Custom { .. })
     }
 
     /// 获取用户友好的错误消息
@@ -257,6 +289,7 @@ impl ErrorKind {
             Self::Conflict => "a version conflict occurred, please retry",
             Self::Unauthorized => "access denied",
             Self::Internal => "an internal error occurred",
+            Self::Custom { .. } => "the request could not be completed",
         }
     }
 }
@@ -887,6 +920,30 @@ mod tests {
         assert_eq!(err.kind(), ErrorKind::NotFound);
     }
 
+    // 测试应用自定义分类
+    #[test]
+    fn test_custom_error_kind() {
+        const PRECONDITION_FAILED: ErrorKind = ErrorKind::Custom {
+            http_status: 412,
+            code: "PRECONDITION_FAILED",
+            retryable: false,
+        };
+
+        let err = DomainError::new(PRECONDITION_FAILED, "etag mismatch");
+        assert_eq!(err.kind(), PRECONDITION_FAILED);
+        assert_eq!(err.code(), "PRECONDITION_FAILED");
+        assert_eq!(err.http_status(), 412);
+        assert!(!err.is_retryable());
+        assert!(err.kind().is_custom());
+        assert!(err.matches(PRECONDITION_FAILED, "PRECONDITION_FAILED"));
+        assert!(!ErrorKind::Internal.is_custom());
+
+        // 细化错误码不改变分类
+        let err = DomainError::from(PRECONDITION_FAILED).with_code("STALE_ETAG");
+        assert_eq!(err.code(), "STALE_ETAG");
+        assert_eq!(err.http_status(), 412);
+    }
+
     // 测试用户自定义错误实现 ErrorCode
     #[test]
     fn test_user_custom_error() {
@@ -5,6 +5,7 @@
 //! 并在稳定后返回。
 //!
 use crate::{error::DomainResult as Result, persist::SerializedEvent};
+use serde_json::Value;
 use std::sync::Arc;
 
 /// 事件版本升级器（Upcaster）
@@ -47,6 +48,40 @@ impl Default for EventUpcasterChain {
 }
 
 impl EventUpcasterChain {
+    pub fn len(&self) -> usize {
+        self.stages.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.stages.is_empty()
+    }
+
+    /// 将 `event_type` 的最旧版本（1）上抬到 `current_version` 需经过的步数
+    ///
+    /// 按版本累计适用的升级器数量，用于估算重放成本；不追踪改名后的事件类型。
+    pub fn depth(&self, event_type: &str, current_version: usize) -> usize {
+        (1..current_version)
+            .map(|version| {
+                self.stages
+                    .iter()
+                    .filter(|stage| stage.applies(event_type, version))
+                    .count()
+            })
+            .sum()
+    }
+
+    /// 将 `event_type` 上抬到 `current_version` 时缺少升级器的旧版本（启动自检使用）
+    pub fn uncovered_versions(&self, event_type: &str, current_version: usize) -> Vec<usize> {
+        (1..current_version)
+            .filter(|version| {
+                !self
+                    .stages
+                    .iter()
+                    .any(|stage| stage.applies(event_type, *version))
+            })
+            .collect()
+    }
+
     /// 对一批事件进行升级，直到不再有升级发生
     pub fn upcast_all(&self, mut events: Vec<SerializedEvent>) -> Result<Vec<SerializedEvent>> {
         loop {
@@ -128,6 +163,135 @@ impl Extend<Arc<dyn EventUpcaster>> for EventUpcasterChain {
     }
 }
 
+/// 断言上抬器将输入事件转换为期望结果（用于编写上抬器测试）
+///
+/// - `input`：事件 JSON 片段（如 `event_type`/`event_version`/`payload`），未给出的字段使用占位默认值；
+/// - `expected`：单个事件片段，或片段数组（`Many`，空数组表示 `Drop`），未给出的字段沿用输入事件。
+///
+/// ```rust
+/// use ddd_domain::assert_upcasts_to;
+/// use ddd_domain::error::DomainResult;
+/// use ddd_domain::event_upcaster::{EventUpcaster, EventUpcasterResult};
+/// use ddd_domain::persist::SerializedEvent;
+/// use serde_json::json;
+///
+/// struct AddCurrency;
+/// impl EventUpcaster for AddCurrency {
+///     fn applies(&self, event_type: &str, event_version: usize) -> bool {
+///         event_type == "paid" && event_version == 1
+///     }
+///     fn upcast(&self, event: SerializedEvent) -> DomainResult<EventUpcasterResult> {
+///         let mut payload = event.payload().clone();
+///         payload["currency"] = json!("CNY");
+///         let next = SerializedEvent::builder()
+///             .event_id(event.event_id().to_string())
+///             .event_type(event.event_type().to_string())
+///             .event_version(2)
+///             .aggregate_id(event.aggregate_id().to_string())
+///             .aggregate_type(event.aggregate_type().to_string())
+///             .aggregate_version(event.aggregate_version())
+///             .occurred_at(event.occurred_at())
+///             .payload(payload)
+///             .context(event.context().clone())
+///             .build();
+///         Ok(EventUpcasterResult::One(next))
+///     }
+/// }
+///
+/// assert_upcasts_to!(
+///     AddCurrency,
+///     json!({"event_type": "paid", "event_version": 1, "payload": {"amount": 1}}),
+///     json!({"event_version": 2, "payload": {"amount": 1, "currency": "CNY"}})
+/// );
+/// ```
+#[macro_export]
+macro_rules! assert_upcasts_to {
+    ($upcaster:expr, $input:expr, $expected:expr $(,)?) => {
+        $crate::event_upcaster::__assert_upcasts_to(&$upcaster, $input, $expected)
+    };
+}
+
+#[doc(hidden)]
+pub fn __assert_upcasts_to<U>(upcaster: &U, input: Value, expected: Value)
+where
+    U: EventUpcaster + ?Sized,
+{
+    let input = merge_event_json(placeholder_event_json(), input);
+    let event: SerializedEvent =
+        serde_json::from_value(input.clone()).expect("input is not a valid SerializedEvent");
+
+    assert!(
+        upcaster.applies(event.event_type(), event.event_version()),
+        "upcaster does not apply to {} v{}",
+        event.event_type(),
+        event.event_version()
+    );
+
+    let actual = match upcaster.upcast(event).expect("upcast failed") {
+        EventUpcasterResult::One(e) => vec![e],
+        EventUpcasterResult::Many(v) => v,
+        EventUpcasterResult::Drop => vec![],
+    };
+
+    let expected: Vec<SerializedEvent> = match expected {
+        Value::Array(items) => items,
+        single => vec![single],
+    }
+    .into_iter()
+    .map(|e| {
+        serde_json::from_value(merge_event_json(input.clone(), e))
+            .expect("expected is not a valid SerializedEvent")
+    })
+    .collect();
+
+    assert_eq!(
+        actual.len(),
+        expected.len(),
+        "upcaster produced {} events, expected {}",
+        actual.len(),
+        expected.len()
+    );
+
+    for (i, (actual, expected)) in actual.iter().zip(&expected).enumerate() {
+        let diff = expected.diff(actual);
+        assert!(
+            diff.is_empty(),
+            "upcasted event #{i} differs from expected:\n{}",
+            diff.iter()
+                .map(|(path, c)| format!("  {path}: expected {}, got {}", c.old, c.new))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+}
+
+fn placeholder_event_json() -> Value {
+    serde_json::json!({
+        "event_id": "event-1",
+        "event_type": "",
+        "event_version": 1,
+        "sequence_number": null,
+        "aggregate_id": "aggregate-1",
+        "aggregate_type": "aggregate",
+        "aggregate_version": 1,
+        "correlation_id": null,
+        "causation_id": null,
+        "actor_type": null,
+        "actor_id": null,
+        "occurred_at": "1970-01-01T00:00:00Z",
+        "payload": {},
+        "context": {},
+    })
+}
+
+/// 以 `patch` 的顶层字段覆盖 `base`
+fn merge_event_json(mut base: Value, patch: Value) -> Value {
+    if let (Some(base), Value::Object(patch)) = (base.as_object_mut(), patch) {
+        base.extend(patch);
+    }
+    base
+}
+
 #[cfg(test)]
 mod tests {
     use super::{EventUpcaster, EventUpcasterChain, EventUpcasterResult};
@@ -314,4 +478,88 @@ mod tests {
         assert_eq!(err.kind(), ErrorKind::Internal);
         assert_eq!(err.code(), "UPCAST_FAILED");
     }
+
+    struct RenameAmount; // v1 {amount_yuan} -> v2 {amount, currency}
+    impl EventUpcaster for RenameAmount {
+        fn applies(&self, event_type: &str, event_version: usize) -> bool {
+            event_type == "order.paid" && event_version == 1
+        }
+        fn upcast(&self, event: SerializedEvent) -> DomainResult<EventUpcasterResult> {
+            let amount = event.payload()["amount_yuan"].as_i64().unwrap_or_default();
+            let next = SerializedEvent::builder()
+                .event_id(event.event_id().to_string())
+                .event_type(event.event_type().to_string())
+                .event_version(2)
+                .aggregate_id(event.aggregate_id().to_string())
+                .aggregate_type(event.aggregate_type().to_string())
+                .aggregate_version(event.aggregate_version())
+                .occurred_at(event.occurred_at())
+                .payload(serde_json::json!({"amount": amount * 100, "currency": "CNY"}))
+                .context(event.context().clone())
+                .build();
+            Ok(EventUpcasterResult::One(next))
+        }
+    }
+
+    #[test]
+    fn diff_reports_changed_paths() {
+        let v1 = mk_event("order.paid", 1, serde_json::json!({"amount_yuan": 5}));
+        let mut chain: EventUpcasterChain = EventUpcasterChain::default();
+        chain.extend([Arc::new(RenameAmount) as Arc<dyn EventUpcaster>]);
+        let v2 = chain.upcast_all(vec![v1.clone()]).unwrap().remove(0);
+
+        let paths: Vec<String> = v1.diff(&v2).into_iter().map(|(p, _)| p).collect();
+        assert_eq!(
+            paths,
+            vec![
+                "/actor_id",
+                "/actor_type",
+                "/causation_id",
+                "/correlation_id",
+                "/event_version",
+                "/payload/amount",
+                "/payload/amount_yuan",
+                "/payload/currency",
+            ]
+        );
+        assert!(v2.diff(&v2).is_empty());
+    }
+
+    #[test]
+    fn assert_upcasts_to_compares_against_input() {
+        crate::assert_upcasts_to!(
+            RenameAmount,
+            serde_json::json!({
+                "event_type": "order.paid",
+                "event_version": 1,
+                "payload": {"amount_yuan": 5}
+            }),
+            serde_json::json!({
+                "event_version": 2,
+                "payload": {"amount": 500, "currency": "CNY"}
+            })
+        );
+        crate::assert_upcasts_to!(
+            DropMeta,
+            serde_json::json!({"event_type": "order.meta"}),
+            serde_json::json!([])
+        );
+    }
+
+    #[test]
+    #[should_panic(expected = "/payload/currency")]
+    fn assert_upcasts_to_reports_diff() {
+        crate::assert_upcasts_to!(
+            RenameAmount,
+            serde_json::json!({
+                "event_type": "order.paid",
+                "event_version": 1,
+                "payload": {"amount_yuan": 5}
+            }),
+            serde_json::json!({
+                "event_version": 2,
+                "payload": {"amount": 500, "currency": "USD"}
+            })
+        );
+    }
 }
@@ -3,10 +3,108 @@
 //! 定义事件发布与订阅的统一抽象，支持批量发布与 'static 生命周期事件流，
 //! 以便在异步运行时（如 tokio::spawn）中消费。
 //!
-use crate::{error::DomainResult as Result, persist::SerializedEvent};
+//! `subscribe_with` 支持指定订阅起点（`SubscribeOptions`）：具备回放能力的适配器
+//! （Kafka、JetStream、Redis Streams 等）按位点或时间戳从历史位置开始投递，
+//! 消费者重建读模型时无需单独的追赶（catch-up）逻辑。
+//! 分区后端不存在跨分区的全局位点，按分区记录的检查点以 `SubscribeFrom::Positions` 恢复订阅。
+//!
+//! 适配器经 `ordering` 声明订阅流的顺序保证（`OrderingGuarantee`），处理器经
+//! `EventHandler::required_ordering` 声明所需的保证，引擎启动时校验两者。
+//!
+use crate::{
+    error::{DomainError, DomainResult as Result},
+    persist::{EventPosition, SerializedEvent},
+};
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
 use futures_core::stream::BoxStream;
 
+/// 订阅流的事件顺序保证，由弱到强排列
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum OrderingGuarantee {
+    /// 不保证顺序（默认）
+    #[default]
+    Unordered,
+    /// 同一分区键（聚合 ID）的事件按发布顺序到达，如按聚合分区的 Kafka 主题
+    PerKey,
+    /// 全部事件按发布顺序到达
+    Global,
+}
+
+impl OrderingGuarantee {
+    /// 当前保证是否满足 `required`
+    pub fn satisfies(self, required: OrderingGuarantee) -> bool {
+        self >= required
+    }
+}
+
+/// 订阅起点
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub enum SubscribeFrom {
+    /// 仅接收订阅之后发布的事件（默认）
+    #[default]
+    Latest,
+    /// 从全局位点（含）开始
+    Sequence(u64),
+    /// 从发生时间（含）开始
+    Timestamp(DateTime<Utc>),
+    /// 按分区从各自位置（含）开始；未列出的分区从头开始
+    Positions(Vec<EventPosition>),
+}
+
+impl SubscribeFrom {
+    /// 位于全局位置 `position` 的事件是否在起点之后（含起点）
+    ///
+    /// `Positions` 优先使用事件自身的位置（`SerializedEvent::position`），
+    /// 缺失时视为分区 `0` 中的 `position`。
+    pub fn includes(&self, position: u64, event: &SerializedEvent) -> bool {
+        match self {
+            SubscribeFrom::Latest => false,
+            SubscribeFrom::Sequence(from) => position >= *from,
+            SubscribeFrom::Timestamp(from) => event.occurred_at() >= *from,
+            SubscribeFrom::Positions(from) => {
+                let at = event
+                    .position()
+                    .unwrap_or_else(|| EventPosition::global(position as i64));
+                from.iter()
+                    .find(|p| p.partition == at.partition)
+                    .is_none_or(|p| at.reached(p))
+            }
+        }
+    }
+}
+
+/// 订阅选项
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct SubscribeOptions {
+    pub from: SubscribeFrom,
+}
+
+impl SubscribeOptions {
+    pub fn latest() -> Self {
+        Self::default()
+    }
+
+    pub fn from_sequence(sequence: u64) -> Self {
+        Self {
+            from: SubscribeFrom::Sequence(sequence),
+        }
+    }
+
+    pub fn from_timestamp(at: DateTime<Utc>) -> Self {
+        Self {
+            from: SubscribeFrom::Timestamp(at),
+        }
+    }
+
+    /// 从各分区的位置（含）恢复订阅
+    pub fn from_positions(positions: impl IntoIterator<Item = EventPosition>) -> Self {
+        Self {
+            from: SubscribeFrom::Positions(positions.into_iter().collect()),
+        }
+    }
+}
+
 /// 事件总线：负责分发事件与订阅事件流
 #[async_trait]
 pub trait EventBus: Send + Sync {
@@ -19,6 +117,34 @@ pub trait EventBus: Send + Sync {
         Ok(())
     }
 
+    /// 订阅流的顺序保证；默认不保证顺序，能保证顺序的适配器应覆盖
+    fn ordering(&self) -> OrderingGuarantee {
+        OrderingGuarantee::Unordered
+    }
+
     /// 返回一个 'static 生命周期的事件流，便于在 tokio::spawn 中使用
     async fn subscribe(&self) -> BoxStream<'static, Result<SerializedEvent>>;
+
+    /// 按选项订阅：先投递起点之后的历史事件，再接续新事件
+    ///
+    /// 默认实现仅支持 `SubscribeFrom::Latest`；具备回放能力的适配器应覆盖该方法。
+    async fn subscribe_with(
+        &self,
+        options: SubscribeOptions,
+    ) -> Result<BoxStream<'static, Result<SerializedEvent>>> {
+        match options.from {
+            SubscribeFrom::Latest => Ok(self.subscribe().await),
+            from => Err(DomainError::event_bus(format!(
+                "replay from {from:?} is not supported by this event bus"
+            ))),
+        }
+    }
+}
+
+/// 回放数据源：按全局顺序（旧 → 新）返回起点之后的历史事件
+///
+/// 供不具备原生回放能力的总线（如 `InMemoryEventBus`）借助事件存储近似实现回放。
+#[async_trait]
+pub trait ReplaySource: Send + Sync {
+    async fn replay(&self, from: SubscribeFrom) -> Result<Vec<SerializedEvent>>;
 }
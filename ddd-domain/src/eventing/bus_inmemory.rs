@@ -3,16 +3,21 @@
 //! 基于 `tokio::sync::broadcast` 实现的轻量事件总线，满足 `EventBus` 协议：
 //! - `publish`：克隆并广播事件；
 //! - `subscribe`：返回 `'static` 生命周期事件流，便于在 `tokio::spawn` 中使用；
+//! - `subscribe_with`：配置回放数据源（`with_replay`）后，先投递事件存储中起点之后的历史事件，
+//!   再接续广播的新事件（按事件 ID 去重），近似具备回放能力的消息中间件；
 //! - 典型用途：测试环境、示例与本地开发。
 //!
 //! 注意：该实现具备“至少一次”投递语义，若无订阅者时发送将被忽略。
+//! 单个广播通道按发布顺序投递，订阅流具备全局顺序（`OrderingGuarantee::Global`）。
 
 use crate::error::{DomainError, DomainResult as Result};
-use crate::eventing::EventBus;
+use crate::eventing::{EventBus, OrderingGuarantee, ReplaySource, SubscribeFrom, SubscribeOptions};
 use crate::persist::SerializedEvent;
 use async_trait::async_trait;
 use futures_core::stream::BoxStream;
-use futures_util::StreamExt;
+use futures_util::{StreamExt, stream};
+use std::collections::HashSet;
+use std::sync::Arc;
 use tokio::sync::broadcast;
 use tokio_stream::wrappers::BroadcastStream;
 
@@ -20,13 +25,27 @@ use tokio_stream::wrappers::BroadcastStream;
 #[derive(Clone)]
 pub struct InMemoryEventBus {
     tx: broadcast::Sender<SerializedEvent>,
+    replay: Option<Arc<dyn ReplaySource>>,
 }
 
 impl InMemoryEventBus {
     /// 创建一个内存总线，`capacity` 为广播缓冲区容量
     pub fn new(capacity: usize) -> Self {
         let (tx, _rx) = broadcast::channel(capacity);
-        Self { tx }
+        Self { tx, replay: None }
+    }
+
+    /// 设置回放数据源，使 `subscribe_with` 支持从历史位置订阅
+    pub fn with_replay(mut self, replay: Arc<dyn ReplaySource>) -> Self {
+        self.replay = Some(replay);
+        self
+    }
+
+    fn live(&self) -> BoxStream<'static, Result<SerializedEvent>> {
+        let rx = self.tx.subscribe();
+        let stream =
+            BroadcastStream::new(rx).map(|r| r.map_err(|e| DomainError::event_bus(e.to_string())));
+        Box::pin(stream)
     }
 }
 
@@ -38,10 +57,38 @@ impl EventBus for InMemoryEventBus {
         Ok(())
     }
 
+    fn ordering(&self) -> OrderingGuarantee {
+        OrderingGuarantee::Global
+    }
+
     async fn subscribe(&self) -> BoxStream<'static, Result<SerializedEvent>> {
-        let rx = self.tx.subscribe();
-        let stream =
-            BroadcastStream::new(rx).map(|r| r.map_err(|e| DomainError::event_bus(e.to_string())));
-        Box::pin(stream)
+        self.live()
+    }
+
+    async fn subscribe_with(
+        &self,
+        options: SubscribeOptions,
+    ) -> Result<BoxStream<'static, Result<SerializedEvent>>> {
+        if options.from == SubscribeFrom::Latest {
+            return Ok(self.live());
+        }
+        let Some(replay) = &self.replay else {
+            return Err(DomainError::event_bus(
+                "in-memory event bus has no replay source configured",
+            ));
+        };
+
+        // 先订阅再读取历史，避免两者之间发布的事件丢失；重叠部分按事件 ID 去重
+        let live = self.live();
+        let history = replay.replay(options.from).await?;
+        let seen: HashSet<String> = history.iter().map(|e| e.event_id().to_string()).collect();
+
+        let live = live.filter(move |r| {
+            let duplicate = matches!(r, Ok(e) if seen.contains(e.event_id()));
+            std::future::ready(!duplicate)
+        });
+        Ok(Box::pin(
+            stream::iter(history.into_iter().map(Ok)).chain(live),
+        ))
     }
 }
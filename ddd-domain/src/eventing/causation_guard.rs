@@ -0,0 +1,180 @@
+//! 因果链深度保护（CausationGuardHandler）
+//!
+//! 策略/Saga 以「事件 → 命令 → 事件」的方式串联，配置不当时可能形成无限循环。
+//! 处理器通过 `EventContext::caused_by` 为后续命令派生上下文，因果链深度随之逐跳加 1；
+//! 本装饰器在事件深度达到上限时拒绝处理并返回错误（原因以 `causation_depth_exceeded` 开头），
+//! 引擎将其视为不可重试的失败：不做原地重试，配置死信存储时首次命中即转入死信队列
+//! （未配置时以该原因转交回收器），同时累加同名指标，避免失控的反馈环拖垮生产环境。
+//!
+use super::{EventHandler, HandledEventType, HandlerContext, OrderingGuarantee};
+use crate::persist::SerializedEvent;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// 超出因果链深度时交给回收器的失败原因前缀（同时作为指标名）
+pub const CAUSATION_DEPTH_EXCEEDED_REASON: &str = "causation_depth_exceeded";
+
+/// 因果链深度超限错误
+#[derive(Debug, thiserror::Error)]
+#[error(
+    "causation_depth_exceeded: event {event_id} ({event_type}) has causation depth {depth}, max is {max_depth}; possible event/command feedback loop"
+)]
+pub struct CausationDepthExceeded {
+    pub event_id: String,
+    pub event_type: String,
+    pub depth: u32,
+    pub max_depth: u32,
+}
+
+/// `EventHandler` 的因果链深度保护装饰器
+pub struct CausationGuardHandler {
+    inner: Arc<dyn EventHandler>,
+    max_depth: u32,
+}
+
+impl CausationGuardHandler {
+    /// `max_depth`：允许处理的最大事件深度（深度大于等于该值的事件被拒绝）
+    pub fn new(inner: Arc<dyn EventHandler>, max_depth: u32) -> Self {
+        Self { inner, max_depth }
+    }
+
+    pub fn max_depth(&self) -> u32 {
+        self.max_depth
+    }
+}
+
+#[async_trait]
+impl EventHandler for CausationGuardHandler {
+    fn handler_name(&self) -> &str {
+        self.inner.handler_name()
+    }
+
+    fn handled_event_type(&self) -> HandledEventType {
+        self.inner.handled_event_type()
+    }
+
+    fn required_ordering(&self) -> OrderingGuarantee {
+        self.inner.required_ordering()
+    }
+
+    async fn handle(&self, event: &SerializedEvent, ctx: &HandlerContext) -> anyhow::Result<()> {
+        let depth = event.causation_depth();
+        if depth >= self.max_depth {
+            ctx.metrics().increment(CAUSATION_DEPTH_EXCEEDED_REASON, 1);
+            return Err(CausationDepthExceeded {
+                event_id: event.event_id().to_string(),
+                event_type: event.event_type().to_string(),
+                depth,
+                max_depth: self.max_depth,
+            }
+            .into());
+        }
+
+        self.inner.handle(event, ctx).await
+    }
+
+    fn circuit_status(&self) -> Option<super::CircuitStatus> {
+        self.inner.circuit_status()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain_event::EventContext;
+    use crate::eventing::HandlerMetrics;
+    use chrono::Utc;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct Counting {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl EventHandler for Counting {
+        fn handler_name(&self) -> &str {
+            "policy"
+        }
+
+        fn handled_event_type(&self) -> HandledEventType {
+            HandledEventType::All
+        }
+
+        async fn handle(
+            &self,
+            _event: &SerializedEvent,
+            _ctx: &HandlerContext,
+        ) -> anyhow::Result<()> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct Recorded(Mutex<Vec<String>>);
+
+    impl HandlerMetrics for Recorded {
+        fn increment(&self, name: &str, _value: u64) {
+            self.0.lock().unwrap().push(name.to_string());
+        }
+
+        fn observe(&self, _name: &str, _value: f64) {}
+    }
+
+    fn mk_event(id: &str, context: &EventContext) -> SerializedEvent {
+        SerializedEvent::builder()
+            .event_id(id.to_string())
+            .event_type("Demo".to_string())
+            .event_version(1)
+            .aggregate_id("a-1".to_string())
+            .aggregate_type("demo".to_string())
+            .aggregate_version(1)
+            .maybe_correlation_id(context.correlation_id().map(ToString::to_string))
+            .occurred_at(Utc::now())
+            .payload(serde_json::json!({}))
+            .context(serde_json::to_value(context).unwrap())
+            .build()
+    }
+
+    #[tokio::test]
+    async fn rejects_events_beyond_max_depth() {
+        let inner = Arc::new(Counting::default());
+        let guard = CausationGuardHandler::new(inner.clone(), 2);
+        let metrics = Arc::new(Recorded::default());
+        let ctx = HandlerContext::builder().metrics(metrics.clone()).build();
+
+        // 模拟策略循环：每次处理都以 caused_by 派生下一跳
+        let root = mk_event("e-0", &EventContext::default());
+        assert_eq!(root.causation_depth(), 0);
+        let first = mk_event("e-1", &EventContext::caused_by(&root));
+        let second = mk_event("e-2", &EventContext::caused_by(&first));
+        assert_eq!(second.causation_depth(), 2);
+        assert_eq!(
+            EventContext::caused_by(&first).correlation_id(),
+            Some("e-0")
+        );
+
+        guard.handle(&root, &ctx).await.unwrap();
+        guard.handle(&first, &ctx).await.unwrap();
+        let err = guard.handle(&second, &ctx).await.unwrap_err();
+
+        assert!(err.to_string().starts_with(CAUSATION_DEPTH_EXCEEDED_REASON));
+        let exceeded = err.downcast_ref::<CausationDepthExceeded>().unwrap();
+        assert_eq!((exceeded.depth, exceeded.max_depth), (2, 2));
+        assert_eq!(inner.calls.load(Ordering::Relaxed), 2);
+        assert_eq!(
+            *metrics.0.lock().unwrap(),
+            vec![CAUSATION_DEPTH_EXCEEDED_REASON.to_string()]
+        );
+    }
+
+    #[test]
+    fn oversized_depth_saturates_instead_of_wrapping() {
+        let mut event = serde_json::to_value(mk_event("e-1", &EventContext::default())).unwrap();
+        event["context"]["causation_depth"] = serde_json::json!(u64::from(u32::MAX) + 2);
+        let event: SerializedEvent = serde_json::from_value(event).unwrap();
+        assert_eq!(event.causation_depth(), u32::MAX);
+    }
+}
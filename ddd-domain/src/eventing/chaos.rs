@@ -0,0 +1,196 @@
+//! 事件引擎故障注入（混沌测试）
+//!
+//! 在上线前主动制造生产环境中迟早会出现的异常，验证处理器与投影的幂等性与乱序容忍度。
+//! 启用 `chaos` 特性并以 `EventEngine::builder().chaos(Arc<EngineChaos>)` 接入后，引擎在以下注入点按 `ChaosConfig` 的概率注入故障：
+//! - 处理器失败：分发前直接判定失败，事件以 `chaos_injected_failure` 原因转交回收器（经补偿重投）；
+//! - 发布延迟：投递/补偿 worker 发布前随机等待 `0..=max_publish_delay`；
+//! - 重复投递：订阅到的事件再分发一次给全部匹配的处理器（含批量处理器）；
+//! - 批次乱序：投递/补偿 worker 发布前打乱一批事件的顺序。
+//!
+//! 随机数由 `seed` 决定，相同种子按相同调用顺序产生相同的注入决策；`set_enabled` 可在运行期开关，
+//! `stats` 给出各注入点的累计注入次数。未启用特性时注入点为空操作。
+//!
+#[cfg(feature = "chaos")]
+pub use enabled::{ChaosConfig, ChaosStats, EngineChaos};
+
+use crate::persist::SerializedEvent;
+
+/// 注入处理器失败时交给回收器的失败原因
+pub const CHAOS_FAILURE_REASON: &str = "chaos_injected_failure";
+
+/// 引擎内部的注入点；未配置或未启用 `chaos` 特性时不注入
+#[derive(Clone, Default)]
+pub(crate) struct ChaosHooks {
+    #[cfg(feature = "chaos")]
+    chaos: Option<std::sync::Arc<EngineChaos>>,
+}
+
+impl ChaosHooks {
+    #[cfg(feature = "chaos")]
+    pub(crate) fn new(chaos: std::sync::Arc<EngineChaos>) -> Self {
+        Self { chaos: Some(chaos) }
+    }
+
+    /// 本次处理是否注入失败
+    pub(crate) fn fail_handler(&self) -> bool {
+        #[cfg(feature = "chaos")]
+        if let Some(chaos) = &self.chaos {
+            return chaos.fail_handler();
+        }
+        false
+    }
+
+    /// 订阅到的事件分发的次数（注入重复投递时为 2）
+    pub(crate) fn delivery_copies(&self) -> usize {
+        #[cfg(feature = "chaos")]
+        if let Some(chaos) = &self.chaos {
+            return chaos.delivery_copies();
+        }
+        1
+    }
+
+    /// 发布前的注入：随机延迟与打乱批次顺序
+    pub(crate) async fn before_publish(&self, events: &mut [SerializedEvent]) {
+        #[cfg(feature = "chaos")]
+        if let Some(chaos) = &self.chaos {
+            chaos.before_publish(events).await;
+        }
+        let _ = events;
+    }
+}
+
+#[cfg(feature = "chaos")]
+mod enabled {
+    use crate::persist::SerializedEvent;
+    use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+    use std::time::Duration;
+
+    /// 混沌测试配置，各概率取值 `0.0..=1.0`（超出范围按边界处理）
+    #[derive(Clone, Copy, Debug, Default)]
+    pub struct ChaosConfig {
+        /// 随机数种子
+        pub seed: u64,
+        /// 每次处理调用（含批量处理）注入失败的概率
+        pub handler_failure_rate: f64,
+        /// 每批发布前注入延迟的概率
+        pub publish_delay_rate: f64,
+        /// 注入延迟的上限
+        pub max_publish_delay: Duration,
+        /// 每个订阅到的事件被重复分发的概率
+        pub duplicate_rate: f64,
+        /// 每批发布前打乱顺序的概率
+        pub reorder_rate: f64,
+    }
+
+    /// 各注入点的累计注入次数
+    #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+    pub struct ChaosStats {
+        pub handler_failures: u64,
+        pub delayed_publishes: u64,
+        pub duplicated_deliveries: u64,
+        pub reordered_batches: u64,
+    }
+
+    /// 引擎故障注入器，可在多个引擎间共享并在测试中读取统计
+    #[derive(Debug)]
+    pub struct EngineChaos {
+        config: ChaosConfig,
+        enabled: AtomicBool,
+        state: AtomicU64,
+        handler_failures: AtomicU64,
+        delayed_publishes: AtomicU64,
+        duplicated_deliveries: AtomicU64,
+        reordered_batches: AtomicU64,
+    }
+
+    impl EngineChaos {
+        pub fn new(config: ChaosConfig) -> Self {
+            Self {
+                config,
+                enabled: AtomicBool::new(true),
+                state: AtomicU64::new(config.seed),
+                handler_failures: AtomicU64::new(0),
+                delayed_publishes: AtomicU64::new(0),
+                duplicated_deliveries: AtomicU64::new(0),
+                reordered_batches: AtomicU64::new(0),
+            }
+        }
+
+        pub fn config(&self) -> &ChaosConfig {
+            &self.config
+        }
+
+        /// 运行期开关注入（例如预热完成后再开启）
+        pub fn set_enabled(&self, enabled: bool) {
+            self.enabled.store(enabled, Ordering::SeqCst);
+        }
+
+        pub fn is_enabled(&self) -> bool {
+            self.enabled.load(Ordering::SeqCst)
+        }
+
+        pub fn stats(&self) -> ChaosStats {
+            ChaosStats {
+                handler_failures: self.handler_failures.load(Ordering::Relaxed),
+                delayed_publishes: self.delayed_publishes.load(Ordering::Relaxed),
+                duplicated_deliveries: self.duplicated_deliveries.load(Ordering::Relaxed),
+                reordered_batches: self.reordered_batches.load(Ordering::Relaxed),
+            }
+        }
+
+        pub(crate) fn fail_handler(&self) -> bool {
+            self.inject(self.config.handler_failure_rate, &self.handler_failures)
+        }
+
+        pub(crate) fn delivery_copies(&self) -> usize {
+            if self.inject(self.config.duplicate_rate, &self.duplicated_deliveries) {
+                2
+            } else {
+                1
+            }
+        }
+
+        pub(crate) async fn before_publish(&self, events: &mut [SerializedEvent]) {
+            if events.is_empty() {
+                return;
+            }
+            if events.len() > 1 && self.inject(self.config.reorder_rate, &self.reordered_batches) {
+                // Fisher–Yates
+                for i in (1..events.len()).rev() {
+                    let j = (self.next() % (i as u64 + 1)) as usize;
+                    events.swap(i, j);
+                }
+            }
+            if !self.config.max_publish_delay.is_zero()
+                && self.inject(self.config.publish_delay_rate, &self.delayed_publishes)
+            {
+                let max = self.config.max_publish_delay.as_millis().max(1) as u64;
+                tokio::time::sleep(Duration::from_millis(self.next() % (max + 1))).await;
+            }
+        }
+
+        /// 按概率决定是否注入，注入时累计计数
+        fn inject(&self, rate: f64, counter: &AtomicU64) -> bool {
+            if rate <= 0.0 || !self.is_enabled() {
+                return false;
+            }
+            let sample = (self.next() >> 11) as f64 / (1u64 << 53) as f64;
+            let hit = sample < rate;
+            if hit {
+                counter.fetch_add(1, Ordering::Relaxed);
+            }
+            hit
+        }
+
+        /// SplitMix64
+        fn next(&self) -> u64 {
+            let mut z = self
+                .state
+                .fetch_add(0x9E37_79B9_7F4A_7C15, Ordering::Relaxed)
+                .wrapping_add(0x9E37_79B9_7F4A_7C15);
+            z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+            z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+            z ^ (z >> 31)
+        }
+    }
+}
@@ -0,0 +1,263 @@
+//! 处理器熔断（CircuitBreakerHandler）
+//!
+//! 处理器依赖的下游不可用时，重试会放大故障。熔断装饰器在连续失败达到阈值后打开，
+//! 打开期间直接拒绝事件（失败原因为 `circuit_open`，由引擎转交回收器），
+//! 冷却期结束后进入半开状态，放行一次试探调用：成功则关闭，失败则重新打开。
+//!
+use super::{EventHandler, HandledEventType, HandlerContext, OrderingGuarantee};
+use crate::persist::SerializedEvent;
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 熔断打开时交给回收器的失败原因
+pub const CIRCUIT_OPEN_REASON: &str = "circuit_open";
+
+/// 熔断配置
+#[derive(Clone, Copy, Debug)]
+pub struct CircuitBreakerConfig {
+    /// 连续失败多少次后打开
+    pub failure_threshold: u32,
+    /// 打开后多久进入半开状态
+    pub open_duration: Duration,
+}
+
+impl Default for CircuitBreakerConfig {
+    fn default() -> Self {
+        Self {
+            failure_threshold: 5,
+            open_duration: Duration::from_secs(30),
+        }
+    }
+}
+
+/// 熔断状态
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CircuitState {
+    Closed,
+    Open,
+    HalfOpen,
+}
+
+/// 熔断状态快照（用于 `EngineStatus`）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct CircuitStatus {
+    pub state: CircuitState,
+    /// 当前连续失败次数
+    pub consecutive_failures: u32,
+}
+
+/// 熔断打开时返回的错误，`Display` 即 `circuit_open`
+#[derive(Debug, thiserror::Error)]
+#[error("circuit_open")]
+pub struct CircuitOpenError;
+
+#[derive(Debug)]
+struct Breaker {
+    state: CircuitState,
+    consecutive_failures: u32,
+    opened_at: Option<Instant>,
+    trial_in_flight: bool,
+}
+
+/// `EventHandler` 的熔断装饰器
+pub struct CircuitBreakerHandler {
+    inner: Arc<dyn EventHandler>,
+    config: CircuitBreakerConfig,
+    breaker: Mutex<Breaker>,
+}
+
+impl CircuitBreakerHandler {
+    pub fn new(inner: Arc<dyn EventHandler>, config: CircuitBreakerConfig) -> Self {
+        Self {
+            inner,
+            config,
+            breaker: Mutex::new(Breaker {
+                state: CircuitState::Closed,
+                consecutive_failures: 0,
+                opened_at: None,
+                trial_in_flight: false,
+            }),
+        }
+    }
+
+    pub fn status(&self) -> CircuitStatus {
+        let mut breaker = self.breaker.lock().unwrap();
+        self.refresh(&mut breaker);
+
+        CircuitStatus {
+            state: breaker.state,
+            consecutive_failures: breaker.consecutive_failures,
+        }
+    }
+
+    /// 冷却期结束时由打开转为半开
+    fn refresh(&self, breaker: &mut Breaker) {
+        if breaker.state == CircuitState::Open
+            && breaker
+                .opened_at
+                .is_some_and(|at| at.elapsed() >= self.config.open_duration)
+        {
+            breaker.state = CircuitState::HalfOpen;
+            breaker.trial_in_flight = false;
+        }
+    }
+
+    /// 是否放行本次调用
+    fn try_acquire(&self) -> bool {
+        let mut breaker = self.breaker.lock().unwrap();
+        self.refresh(&mut breaker);
+
+        match breaker.state {
+            CircuitState::Closed => true,
+            CircuitState::Open => false,
+            CircuitState::HalfOpen if breaker.trial_in_flight => false,
+            CircuitState::HalfOpen => {
+                breaker.trial_in_flight = true;
+                true
+            }
+        }
+    }
+
+    fn record(&self, success: bool) {
+        let mut breaker = self.breaker.lock().unwrap();
+        breaker.trial_in_flight = false;
+
+        if success {
+            breaker.state = CircuitState::Closed;
+            breaker.consecutive_failures = 0;
+            breaker.opened_at = None;
+            return;
+        }
+
+        breaker.consecutive_failures += 1;
+        if breaker.state == CircuitState::HalfOpen
+            || breaker.consecutive_failures >= self.config.failure_threshold
+        {
+            breaker.state = CircuitState::Open;
+            breaker.opened_at = Some(Instant::now());
+        }
+    }
+}
+
+#[async_trait]
+impl EventHandler for CircuitBreakerHandler {
+    fn handler_name(&self) -> &str {
+        self.inner.handler_name()
+    }
+
+    fn handled_event_type(&self) -> HandledEventType {
+        self.inner.handled_event_type()
+    }
+
+    fn required_ordering(&self) -> OrderingGuarantee {
+        self.inner.required_ordering()
+    }
+
+    async fn handle(&self, event: &SerializedEvent, ctx: &HandlerContext) -> anyhow::Result<()> {
+        if !self.try_acquire() {
+            return Err(CircuitOpenError.into());
+        }
+
+        let result = self.inner.handle(event, ctx).await;
+        self.record(result.is_ok());
+        result
+    }
+
+    fn circuit_status(&self) -> Option<CircuitStatus> {
+        Some(self.status())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct Flaky {
+        failing: AtomicBool,
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl EventHandler for Flaky {
+        fn handler_name(&self) -> &str {
+            "flaky"
+        }
+
+        fn handled_event_type(&self) -> HandledEventType {
+            HandledEventType::All
+        }
+
+        async fn handle(
+            &self,
+            _event: &SerializedEvent,
+            _ctx: &HandlerContext,
+        ) -> anyhow::Result<()> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            if self.failing.load(Ordering::Relaxed) {
+                anyhow::bail!("downstream unavailable");
+            }
+            Ok(())
+        }
+    }
+
+    fn mk_event() -> SerializedEvent {
+        SerializedEvent::builder()
+            .event_id("e-1".to_string())
+            .event_type("Demo".to_string())
+            .event_version(1)
+            .aggregate_id("a-1".to_string())
+            .aggregate_type("demo".to_string())
+            .aggregate_version(1)
+            .occurred_at(Utc::now())
+            .payload(serde_json::json!({}))
+            .context(serde_json::json!({}))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn opens_after_threshold_and_recovers_via_half_open() {
+        let inner = Arc::new(Flaky::default());
+        inner.failing.store(true, Ordering::Relaxed);
+        let breaker = CircuitBreakerHandler::new(
+            inner.clone(),
+            CircuitBreakerConfig {
+                failure_threshold: 2,
+                open_duration: Duration::from_millis(50),
+            },
+        );
+        let ev = mk_event();
+        let ctx = HandlerContext::default();
+
+        assert!(breaker.handle(&ev, &ctx).await.is_err());
+        assert!(breaker.handle(&ev, &ctx).await.is_err());
+        assert_eq!(breaker.status().state, CircuitState::Open);
+
+        // 打开期间不再调用下游
+        let err = breaker.handle(&ev, &ctx).await.unwrap_err();
+        assert_eq!(err.to_string(), CIRCUIT_OPEN_REASON);
+        assert_eq!(inner.calls.load(Ordering::Relaxed), 2);
+
+        // 半开试探失败：重新打开
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(breaker.status().state, CircuitState::HalfOpen);
+        assert!(breaker.handle(&ev, &ctx).await.is_err());
+        assert_eq!(inner.calls.load(Ordering::Relaxed), 3);
+        assert_eq!(breaker.status().state, CircuitState::Open);
+
+        // 半开试探成功：关闭
+        inner.failing.store(false, Ordering::Relaxed);
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert!(breaker.handle(&ev, &ctx).await.is_ok());
+        assert_eq!(
+            breaker.status(),
+            CircuitStatus {
+                state: CircuitState::Closed,
+                consecutive_failures: 0,
+            }
+        );
+    }
+}
@@ -0,0 +1,337 @@
+//! 多总线桥接（CompositeEventBus）
+//!
+//! 一个引擎实例同时面向多个传输目标（如全部事件进 Kafka 供分析，仅 `order.*` 进 NATS 供履约）：
+//! - 每个目标（`BusTarget`）带独立过滤条件，只接收匹配的事件；
+//! - 目标失败互不影响：`Required` 目标失败时整体返回错误，引擎据此标记失败并交由回收器重试；
+//!   `BestEffort` 目标失败仅计入统计，不影响标记；
+//! - 重试时跳过已成功接收该事件的目标，避免部分成功的事件在其他目标上重复发布；
+//!   部分成功的记录有保留时长（默认 24 小时）与数量上限（默认 10000，超出时淘汰最早的记录），
+//!   放弃重试的事件可经 `forget` 立即清除；
+//! - 订阅经由指定目标（默认第一个）进行。
+//!
+use crate::error::{DomainError, DomainResult as Result};
+use crate::eventing::{EventBus, HandledEventType, OrderingGuarantee, SubscribeOptions};
+use crate::persist::SerializedEvent;
+use async_trait::async_trait;
+use futures_core::stream::BoxStream;
+use futures_util::{StreamExt, stream};
+use std::collections::{BTreeSet, HashMap};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 部分成功记录的默认保留时长
+const DEFAULT_PARTIAL_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+/// 部分成功记录的默认数量上限
+const DEFAULT_PARTIAL_CAPACITY: usize = 10_000;
+
+type EventFilter = Arc<dyn Fn(&SerializedEvent) -> bool + Send + Sync>;
+
+/// 目标失败时的处理方式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum FailurePolicy {
+    /// 失败使本次发布返回错误（事件被标记失败并重试），默认
+    #[default]
+    Required,
+    /// 失败仅计入统计，不影响发布结果
+    BestEffort,
+}
+
+/// 组合总线中的单个目标
+pub struct BusTarget {
+    name: String,
+    bus: Arc<dyn EventBus>,
+    filter: Option<EventFilter>,
+    policy: FailurePolicy,
+    published: AtomicU64,
+    failed: AtomicU64,
+}
+
+impl BusTarget {
+    /// 默认接收全部事件，失败策略为 `Required`
+    pub fn new(name: impl Into<String>, bus: Arc<dyn EventBus>) -> Self {
+        Self {
+            name: name.into(),
+            bus,
+            filter: None,
+            policy: FailurePolicy::Required,
+            published: AtomicU64::new(0),
+            failed: AtomicU64::new(0),
+        }
+    }
+
+    /// 自定义过滤条件
+    pub fn filter<F>(mut self, filter: F) -> Self
+    where
+        F: Fn(&SerializedEvent) -> bool + Send + Sync + 'static,
+    {
+        self.filter = Some(Arc::new(filter));
+        self
+    }
+
+    /// 仅接收给定事件类型
+    pub fn event_types(self, types: impl Into<HandledEventType>) -> Self {
+        let types = types.into();
+        self.filter(move |event| types.matches(event.event_type()))
+    }
+
+    /// 仅接收事件类型以 `prefix` 开头的事件（如 `order.`）
+    pub fn prefix(self, prefix: impl Into<String>) -> Self {
+        let prefix = prefix.into();
+        self.filter(move |event| event.event_type().starts_with(&prefix))
+    }
+
+    /// 失败不影响发布结果
+    pub fn best_effort(mut self) -> Self {
+        self.policy = FailurePolicy::BestEffort;
+        self
+    }
+
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    pub fn policy(&self) -> FailurePolicy {
+        self.policy
+    }
+
+    fn accepts(&self, event: &SerializedEvent) -> bool {
+        self.filter.as_ref().is_none_or(|f| f(event))
+    }
+
+    fn stats(&self) -> BusTargetStats {
+        BusTargetStats {
+            name: self.name.clone(),
+            policy: self.policy,
+            published: self.published.load(Ordering::Relaxed),
+            failed: self.failed.load(Ordering::Relaxed),
+        }
+    }
+}
+
+/// 单个目标的发布统计（按事件计数）
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct BusTargetStats {
+    pub name: String,
+    pub policy: FailurePolicy,
+    pub published: u64,
+    pub failed: u64,
+}
+
+/// 存在 Required 目标失败的事件：已成功接收的目标下标与首次记录时间
+struct Partial {
+    delivered: BTreeSet<usize>,
+    recorded_at: Instant,
+}
+
+/// 按目标过滤并独立处理失败的组合事件总线
+pub struct CompositeEventBus {
+    targets: Vec<BusTarget>,
+    subscription: Option<usize>,
+    partial: Mutex<HashMap<String, Partial>>,
+    partial_ttl: Duration,
+    partial_capacity: usize,
+}
+
+impl Default for CompositeEventBus {
+    fn default() -> Self {
+        Self {
+            targets: Vec::new(),
+            subscription: None,
+            partial: Mutex::new(HashMap::new()),
+            partial_ttl: DEFAULT_PARTIAL_TTL,
+            partial_capacity: DEFAULT_PARTIAL_CAPACITY,
+        }
+    }
+}
+
+impl CompositeEventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 部分成功记录的保留时长（默认 24 小时），过期后重试会重新发往全部目标
+    pub fn partial_ttl(mut self, ttl: Duration) -> Self {
+        self.partial_ttl = ttl;
+        self
+    }
+
+    /// 部分成功记录的数量上限（默认 10000），超出时淘汰最早的记录
+    pub fn partial_capacity(mut self, capacity: usize) -> Self {
+        self.partial_capacity = capacity.max(1);
+        self
+    }
+
+    pub fn target(mut self, target: BusTarget) -> Self {
+        self.targets.push(target);
+        self
+    }
+
+    /// 指定订阅所经由的目标（默认第一个目标；名称未登记时保持不变）
+    pub fn subscribe_via(mut self, name: &str) -> Self {
+        if let Some(idx) = self.targets.iter().position(|t| t.name == name) {
+            self.subscription = Some(idx);
+        }
+        self
+    }
+
+    pub fn stats(&self) -> Vec<BusTargetStats> {
+        self.targets.iter().map(BusTarget::stats).collect()
+    }
+
+    /// 部分目标已成功、等待重试的事件数
+    pub fn partially_published(&self) -> usize {
+        self.partial.lock().unwrap().len()
+    }
+
+    /// 清除事件的部分成功记录（如回收器放弃重试），返回是否存在记录
+    pub fn forget(&self, event_id: &str) -> bool {
+        self.partial.lock().unwrap().remove(event_id).is_some()
+    }
+
+    /// 清除过期记录，并在达到上限时淘汰最早的记录，为新记录腾出位置
+    fn make_room(&self, partial: &mut HashMap<String, Partial>, now: Instant) {
+        partial.retain(|_, p| now.duration_since(p.recorded_at) < self.partial_ttl);
+        while partial.len() >= self.partial_capacity {
+            let Some(oldest) = partial
+                .iter()
+                .min_by_key(|(_, p)| p.recorded_at)
+                .map(|(id, _)| id.clone())
+            else {
+                break;
+            };
+            partial.remove(&oldest);
+        }
+    }
+
+    fn subscription_target(&self) -> Option<&BusTarget> {
+        self.targets.get(self.subscription.unwrap_or(0))
+    }
+}
+
+#[async_trait]
+impl EventBus for CompositeEventBus {
+    async fn publish(&self, event: &SerializedEvent) -> Result<()> {
+        self.publish_batch(std::slice::from_ref(event)).await
+    }
+
+    async fn publish_batch(&self, events: &[SerializedEvent]) -> Result<()> {
+        let delivered: HashMap<String, BTreeSet<usize>> = {
+            let partial = self.partial.lock().unwrap();
+            let now = Instant::now();
+            events
+                .iter()
+                .filter_map(|e| {
+                    partial
+                        .get(e.event_id())
+                        .filter(|p| now.duration_since(p.recorded_at) < self.partial_ttl)
+                        .map(|p| (e.event_id().to_string(), p.delivered.clone()))
+                })
+                .collect()
+        };
+
+        let mut succeeded: HashMap<String, BTreeSet<usize>> = HashMap::new();
+        let mut failed_events: BTreeSet<String> = BTreeSet::new();
+        let mut errors = Vec::new();
+
+        for (idx, target) in self.targets.iter().enumerate() {
+            let outgoing: Vec<SerializedEvent> = events
+                .iter()
+                .filter(|e| target.accepts(e))
+                .filter(|e| {
+                    !delivered
+                        .get(e.event_id())
+                        .is_some_and(|d| d.contains(&idx))
+                })
+                .cloned()
+                .collect();
+            if outgoing.is_empty() {
+                continue;
+            }
+
+            match target.bus.publish_batch(&outgoing).await {
+                Ok(()) => {
+                    target
+                        .published
+                        .fetch_add(outgoing.len() as u64, Ordering::Relaxed);
+                    for event in &outgoing {
+                        succeeded
+                            .entry(event.event_id().to_string())
+                            .or_default()
+                            .insert(idx);
+                    }
+                }
+                Err(err) => {
+                    target
+                        .failed
+                        .fetch_add(outgoing.len() as u64, Ordering::Relaxed);
+                    if target.policy == FailurePolicy::Required {
+                        failed_events.extend(outgoing.iter().map(|e| e.event_id().to_string()));
+                        errors.push(format!("{}: {err}", target.name));
+                    }
+                }
+            }
+        }
+
+        {
+            let mut partial = self.partial.lock().unwrap();
+            let now = Instant::now();
+            for event in events {
+                let id = event.event_id();
+                let mut delivered = delivered.get(id).cloned().unwrap_or_default();
+                delivered.extend(succeeded.remove(id).unwrap_or_default());
+                // 全部成功，或没有任何目标成功（无需跳过）时不保留记录
+                if !failed_events.contains(id) || delivered.is_empty() {
+                    partial.remove(id);
+                    continue;
+                }
+                match partial.get_mut(id) {
+                    Some(entry) if now.duration_since(entry.recorded_at) < self.partial_ttl => {
+                        entry.delivered = delivered;
+                    }
+                    _ => {
+                        self.make_room(&mut partial, now);
+                        partial.insert(
+                            id.to_string(),
+                            Partial {
+                                delivered,
+                                recorded_at: now,
+                            },
+                        );
+                    }
+                }
+            }
+        }
+
+        if errors.is_empty() {
+            return Ok(());
+        }
+        Err(DomainError::event_bus(format!(
+            "composite publish failed on {}",
+            errors.join("; ")
+        )))
+    }
+
+    /// 订阅经由单个目标，顺序保证即该目标总线的保证
+    fn ordering(&self) -> OrderingGuarantee {
+        self.subscription_target()
+            .map_or(OrderingGuarantee::Unordered, |target| target.bus.ordering())
+    }
+
+    async fn subscribe(&self) -> BoxStream<'static, Result<SerializedEvent>> {
+        match self.subscription_target() {
+            Some(target) => target.bus.subscribe().await,
+            None => stream::empty().boxed(),
+        }
+    }
+
+    async fn subscribe_with(
+        &self,
+        options: SubscribeOptions,
+    ) -> Result<BoxStream<'static, Result<SerializedEvent>>> {
+        match self.subscription_target() {
+            Some(target) => target.bus.subscribe_with(options).await,
+            None => Err(DomainError::event_bus("composite event bus has no targets")),
+        }
+    }
+}
@@ -0,0 +1,154 @@
+//! 事件负载压缩（PayloadCompression）
+//!
+//! 发布到总线前，对序列化后超过阈值的事件负载进行 gzip 压缩并以 base64 字符串承载，
+//! 通过 `SerializedEvent::content_encoding` 标记编码方式；引擎在分发给处理器前
+//! 根据该标记透明解压，处理器始终看到原始 JSON 负载。
+//!
+use crate::{
+    error::{DomainError, DomainResult as Result, ErrorKind},
+    persist::SerializedEvent,
+};
+use base64::{Engine as _, engine::general_purpose::STANDARD};
+use flate2::{Compression, read::GzDecoder, write::GzEncoder};
+use serde_json::Value;
+use std::io::{Read, Write};
+
+/// gzip + base64 编码标记
+pub const GZIP_ENCODING: &str = "gzip";
+
+/// 负载压缩配置
+#[derive(Clone, Copy, Debug)]
+pub struct PayloadCompression {
+    /// 负载（序列化 JSON）字节数超过该阈值才压缩
+    pub threshold: usize,
+    /// 压缩级别（0-9）
+    pub level: u32,
+}
+
+impl Default for PayloadCompression {
+    fn default() -> Self {
+        Self {
+            threshold: 8 * 1024,
+            level: 6,
+        }
+    }
+}
+
+impl PayloadCompression {
+    /// 压缩单个事件；未超过阈值或已编码时返回 `None`
+    pub fn compress(&self, event: &SerializedEvent) -> Result<Option<SerializedEvent>> {
+        if event.content_encoding().is_some() {
+            return Ok(None);
+        }
+
+        let raw = serde_json::to_vec(event.payload())?;
+        if raw.len() <= self.threshold {
+            return Ok(None);
+        }
+
+        let mut encoder = GzEncoder::new(Vec::new(), Compression::new(self.level));
+        encoder.write_all(&raw).map_err(compression_error)?;
+        let compressed = encoder.finish().map_err(compression_error)?;
+
+        Ok(Some(event.clone().with_encoded_payload(
+            Value::String(STANDARD.encode(compressed)),
+            Some(GZIP_ENCODING.to_string()),
+        )))
+    }
+
+    /// 批量压缩；单条失败时保留原事件
+    pub fn compress_batch(&self, events: &[SerializedEvent]) -> Vec<SerializedEvent> {
+        events
+            .iter()
+            .map(|e| match self.compress(e) {
+                Ok(Some(compressed)) => compressed,
+                _ => e.clone(),
+            })
+            .collect()
+    }
+}
+
+/// 按 `content_encoding` 标记解压事件；未编码时返回 `None`
+pub fn decompress(event: &SerializedEvent) -> Result<Option<SerializedEvent>> {
+    let Some(encoding) = event.content_encoding() else {
+        return Ok(None);
+    };
+
+    if encoding != GZIP_ENCODING {
+        return Err(DomainError::new(
+            ErrorKind::Internal,
+            format!("unsupported content encoding: {encoding}"),
+        )
+        .with_code("PAYLOAD_COMPRESSION_ERROR"));
+    }
+
+    let encoded = event.payload().as_str().ok_or_else(|| {
+        DomainError::new(ErrorKind::Internal, "compressed payload must be a string")
+            .with_code("PAYLOAD_COMPRESSION_ERROR")
+    })?;
+
+    let compressed = STANDARD.decode(encoded).map_err(compression_error)?;
+    let mut raw = Vec::new();
+    GzDecoder::new(compressed.as_slice())
+        .read_to_end(&mut raw)
+        .map_err(compression_error)?;
+
+    let payload: Value = serde_json::from_slice(&raw)?;
+
+    Ok(Some(event.clone().with_encoded_payload(payload, None)))
+}
+
+fn compression_error<E>(err: E) -> DomainError
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    DomainError::custom(ErrorKind::Internal, err).with_code("PAYLOAD_COMPRESSION_ERROR")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+
+    fn mk_event(payload: Value) -> SerializedEvent {
+        SerializedEvent::builder()
+            .event_id("e-1".to_string())
+            .event_type("doc.uploaded".to_string())
+            .event_version(1)
+            .aggregate_id("d-1".to_string())
+            .aggregate_type("document".to_string())
+            .aggregate_version(1)
+            .occurred_at(Utc::now())
+            .payload(payload)
+            .context(serde_json::json!({}))
+            .build()
+    }
+
+    #[test]
+    fn small_payload_is_left_untouched() {
+        let c = PayloadCompression::default();
+        let ev = mk_event(serde_json::json!({"title": "tiny"}));
+        assert!(c.compress(&ev).unwrap().is_none());
+        assert!(decompress(&ev).unwrap().is_none());
+    }
+
+    #[test]
+    fn large_payload_roundtrips() {
+        let c = PayloadCompression {
+            threshold: 64,
+            ..Default::default()
+        };
+        let body = "lorem ipsum ".repeat(200);
+        let ev = mk_event(serde_json::json!({"body": body}));
+
+        let compressed = c.compress(&ev).unwrap().expect("should compress");
+        assert_eq!(compressed.content_encoding(), Some(GZIP_ENCODING));
+        assert!(compressed.payload().is_string());
+        assert!(c.compress(&compressed).unwrap().is_none());
+
+        let restored = decompress(&compressed).unwrap().expect("should decompress");
+        assert_eq!(restored.content_encoding(), None);
+        assert_eq!(restored.payload(), ev.payload());
+        assert_eq!(restored.event_id(), ev.event_id());
+    }
+}
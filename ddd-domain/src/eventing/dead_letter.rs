@@ -0,0 +1,96 @@
+//! 死信队列（DeadLetterStore）
+//!
+//! 处理器持续失败的事件若无终态，会经回收器无限循环重投。配置
+//! `EngineTuning::with_max_deliveries` 与 `EventEngine::builder().dead_letters(...)` 后：
+//! - 处理器对同一事件的累计投递次数（含原地重试与回收重投）达到上限仍失败时，
+//!   事件连同处理器名、失败原因与投递次数写入 `DeadLetterStore`，并经
+//!   `EventReclaimer::mark_dead_lettered` 通知回收器不再重投；
+//! - `EngineHandle::dead_letters` 列出死信，`replay_dead_letter` 取出死信并重新交给原处理器，
+//!   重放再次失败时按常规流程重试、回收，耗尽后再次进入死信队列。
+//!
+//! 投递次数由引擎在进程内计数，引擎重启后从 1 重新计数。
+//!
+use crate::{error::DomainResult as Result, persist::SerializedEvent};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::sync::Mutex;
+
+/// 进入死信队列的事件
+#[derive(Clone, Debug)]
+pub struct DeadLetter {
+    pub event: SerializedEvent,
+    /// 失败的处理器
+    pub handler_name: String,
+    /// 最后一次失败的原因
+    pub reason: String,
+    /// 累计投递次数
+    pub attempts: u32,
+    /// 首次投递时间
+    pub first_attempted_at: DateTime<Utc>,
+    /// 进入死信队列的时间
+    pub dead_lettered_at: DateTime<Utc>,
+}
+
+/// 死信存储
+#[async_trait]
+pub trait DeadLetterStore: Send + Sync {
+    /// 写入死信；同一 (处理器, 事件) 已存在时覆盖
+    async fn push(&self, letter: DeadLetter) -> Result<()>;
+
+    /// 按进入时间列出死信，`handler_name` 为空时列出全部处理器的死信
+    async fn list(&self, handler_name: Option<&str>) -> Result<Vec<DeadLetter>>;
+
+    /// 取出（移除）指定处理器的死信，不存在时返回空
+    async fn take(&self, handler_name: &str, event_id: &str) -> Result<Option<DeadLetter>>;
+}
+
+/// 基于内存的 DeadLetterStore 实现
+#[derive(Default)]
+pub struct InMemoryDeadLetterStore {
+    letters: Mutex<Vec<DeadLetter>>,
+}
+
+impl InMemoryDeadLetterStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn len(&self) -> usize {
+        self.letters.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+#[async_trait]
+impl DeadLetterStore for InMemoryDeadLetterStore {
+    async fn push(&self, letter: DeadLetter) -> Result<()> {
+        let mut letters = self.letters.lock().unwrap();
+        letters.retain(|l| {
+            l.handler_name != letter.handler_name || l.event.event_id() != letter.event.event_id()
+        });
+        letters.push(letter);
+        Ok(())
+    }
+
+    async fn list(&self, handler_name: Option<&str>) -> Result<Vec<DeadLetter>> {
+        Ok(self
+            .letters
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|l| handler_name.is_none_or(|name| l.handler_name == name))
+            .cloned()
+            .collect())
+    }
+
+    async fn take(&self, handler_name: &str, event_id: &str) -> Result<Option<DeadLetter>> {
+        let mut letters = self.letters.lock().unwrap();
+        let position = letters
+            .iter()
+            .position(|l| l.handler_name == handler_name && l.event.event_id() == event_id);
+        Ok(position.map(|i| letters.remove(i)))
+    }
+}
@@ -1,10 +1,21 @@
 //! 事件中继（EventDeliverer）
 //!
 //! 负责从本地存储（如 Outbox 表）批量取出待投递事件，并在发布后
-//! 标记成功或失败，便于进行重试与审计。
+//! 标记成功或失败，便于进行重试与审计；`EventOutbox` 负责写入端，
+//! 供引擎将处理器派生的事件写入同一 Outbox。
 //!
-use crate::{error::DomainResult as Result, persist::SerializedEvent};
+//! 事务性 Outbox：
+//! - `OutboxRepository`：存储侧契约，`save` 在同一事务内写入事件流并将事件入队，
+//!   另提供待投递查询与发布/失败标记；
+//! - `TransactionalOutboxDeliverer`：将 `OutboxRepository` 适配为引擎使用的 `EventDeliverer`
+//!   与 `EventOutbox`，聚合事件提交与入队投递因此具备原子性。
+//!
+use crate::{
+    error::DomainResult as Result,
+    persist::{EventRepository, SerializedEvent},
+};
 use async_trait::async_trait;
+use std::sync::Arc;
 
 /// 事件中继：从本地存储/Outbox 拉取待发送的事件
 #[async_trait]
@@ -18,3 +29,96 @@ pub trait EventDeliverer: Send + Sync {
     /// 将事件标记为投递失败（可用于增加 attempts、设置 next_retry_at 等）
     async fn mark_failed(&self, events: &[&SerializedEvent], reason: &str) -> Result<()>;
 }
+
+/// Outbox 写入端：引擎将处理器经 `HandlerContext::emit` 产出的派生事件写入 Outbox，
+/// 再由 `EventDeliverer` 拉取投递
+#[async_trait]
+pub trait EventOutbox: Send + Sync {
+    /// 写入一批待投递事件（应原子写入，失败时整批视为未写入）
+    async fn enqueue(&self, events: &[SerializedEvent]) -> Result<()>;
+}
+
+/// 事务性 Outbox 存储契约
+///
+/// 实现者的 `EventRepository::save`（及 `save_with_expected_version`）须在同一事务内
+/// 写入事件流并将同一批事件入队：要么都写入，要么都不写入。
+#[async_trait]
+pub trait OutboxRepository: EventRepository {
+    /// 仅入队、不写入事件流（如处理器派生事件），应原子写入
+    async fn enqueue(&self, events: &[SerializedEvent]) -> Result<()>;
+
+    /// 按入队顺序拉取至多 `limit` 条尚未发布的事件（含此前标记失败、等待重试的事件）
+    async fn fetch_pending(&self, limit: usize) -> Result<Vec<SerializedEvent>>;
+
+    /// 标记事件已发布，此后不再被拉取
+    async fn mark_published(&self, event_ids: &[&str]) -> Result<()>;
+
+    /// 记录发布失败（如累计尝试次数、设置下次重试时间），事件保持待投递
+    async fn mark_failed(&self, event_ids: &[&str], reason: &str) -> Result<()>;
+}
+
+/// 基于 `OutboxRepository` 的事件中继与 Outbox 写入端
+pub struct TransactionalOutboxDeliverer<R> {
+    repo: Arc<R>,
+    batch_size: usize,
+}
+
+impl<R> TransactionalOutboxDeliverer<R>
+where
+    R: OutboxRepository,
+{
+    pub fn new(repo: Arc<R>) -> Self {
+        Self {
+            repo,
+            batch_size: 100,
+        }
+    }
+
+    /// 每次拉取的最大事件数（默认 100）
+    pub fn with_batch_size(mut self, batch_size: usize) -> Self {
+        self.batch_size = batch_size.max(1);
+        self
+    }
+
+    pub fn repository(&self) -> &Arc<R> {
+        &self.repo
+    }
+}
+
+fn event_ids<'a>(events: &[&'a SerializedEvent]) -> Vec<&'a str> {
+    events.iter().map(|e| e.event_id()).collect()
+}
+
+#[async_trait]
+impl<R> EventDeliverer for TransactionalOutboxDeliverer<R>
+where
+    R: OutboxRepository,
+{
+    async fn fetch_events(&self) -> Result<Vec<SerializedEvent>> {
+        self.repo.fetch_pending(self.batch_size).await
+    }
+
+    async fn mark_delivered(&self, events: &[&SerializedEvent]) -> Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        self.repo.mark_published(&event_ids(events)).await
+    }
+
+    async fn mark_failed(&self, events: &[&SerializedEvent], reason: &str) -> Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        self.repo.mark_failed(&event_ids(events), reason).await
+    }
+}
+
+#[async_trait]
+impl<R> EventOutbox for TransactionalOutboxDeliverer<R>
+where
+    R: OutboxRepository,
+{
+    async fn enqueue(&self, events: &[SerializedEvent]) -> Result<()> {
+        self.repo.enqueue(events).await
+    }
+}
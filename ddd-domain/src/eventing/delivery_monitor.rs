@@ -0,0 +1,438 @@
+//! 投递诊断：重复与缺口检测（DeliveryMonitor）
+//!
+//! 基础设施变更（换总线、调整 Outbox/收件箱去重）后，需要确认“至少一次”投递链路与去重仍然有效。
+//! `DeliveryMonitor` 按处理器组在滑动窗口内记录已成功处理的事件 ID：
+//! - 重复：同一组内窗口中已处理过的事件再次处理成功，计入 `delivery_duplicates.<组>` 指标并输出告警日志；
+//! - 缺口：按事件所在分区的位点（`sequence_number`）检测跳号，缺失数量计入 `delivery_gaps.<组>` 指标，
+//!   迟到事件补齐后从未闭合缺口中移除；仅对观察完整事件流的组有意义（订阅全部事件类型）。
+//!
+//! `DeliveryMonitorHandler` 作为 `EventHandler` 装饰器按处理器名分组接入，仅在内层处理成功后记录，
+//! 失败后的重投不计为重复；也可直接调用 `DeliveryMonitor::observe` 接入自定义处理流程。
+//! 诊断不影响处理结果，`report` 给出各组的累计统计与未闭合的缺口。
+//!
+use super::engine_log::LOG_TARGET;
+use super::{
+    CircuitStatus, EventHandler, HandledEventType, HandlerContext, HandlerMetrics, NoopMetrics,
+    OrderingGuarantee,
+};
+use crate::persist::SerializedEvent;
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashSet, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// 重复处理计数指标名前缀（完整名称为 `delivery_duplicates.<组>`）
+pub const DELIVERY_DUPLICATES_METRIC: &str = "delivery_duplicates";
+
+/// 位点缺口计数指标名前缀（完整名称为 `delivery_gaps.<组>`）
+pub const DELIVERY_GAPS_METRIC: &str = "delivery_gaps";
+
+/// 监控配置
+#[derive(Clone, Copy, Debug)]
+pub struct DeliveryMonitorConfig {
+    /// 每组保留的最近事件 ID 数（滑动窗口），超出后最早的 ID 不再参与重复检测
+    pub window: usize,
+    /// 每组保留的未闭合缺口数，超出后丢弃最早的缺口
+    pub max_open_gaps: usize,
+}
+
+impl Default for DeliveryMonitorConfig {
+    fn default() -> Self {
+        Self {
+            window: 10_000,
+            max_open_gaps: 1_000,
+        }
+    }
+}
+
+/// 分区内缺失的位点区间（闭区间）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct SequenceGap {
+    pub partition: u32,
+    pub from: i64,
+    pub to: i64,
+}
+
+/// 单组的诊断结果
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct DeliveryReport {
+    pub group: String,
+    /// 已记录的事件数（含重复）
+    pub observed: u64,
+    /// 重复处理次数
+    pub duplicates: u64,
+    /// 检测到的缺失位点累计数（含之后补齐的）
+    pub missing: u64,
+    /// 尚未补齐的缺口
+    pub open_gaps: Vec<SequenceGap>,
+}
+
+/// 单次记录的结果
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Observation {
+    pub duplicate: bool,
+    /// 本次发现的新缺口
+    pub gap: Option<SequenceGap>,
+}
+
+#[derive(Default)]
+struct GroupState {
+    seen: HashSet<String>,
+    order: VecDeque<String>,
+    /// 分区 -> 已见的最大位点
+    last_sequence: BTreeMap<u32, i64>,
+    open_gaps: VecDeque<SequenceGap>,
+    observed: u64,
+    duplicates: u64,
+    missing: u64,
+}
+
+impl GroupState {
+    fn remember(&mut self, event_id: &str, window: usize) -> bool {
+        if self.seen.contains(event_id) {
+            return true;
+        }
+        self.seen.insert(event_id.to_string());
+        self.order.push_back(event_id.to_string());
+        while self.order.len() > window {
+            if let Some(oldest) = self.order.pop_front() {
+                self.seen.remove(&oldest);
+            }
+        }
+        false
+    }
+
+    fn track_sequence(
+        &mut self,
+        partition: u32,
+        sequence: i64,
+        max_gaps: usize,
+    ) -> Option<SequenceGap> {
+        let Some(&last) = self.last_sequence.get(&partition) else {
+            self.last_sequence.insert(partition, sequence);
+            return None;
+        };
+        if sequence > last + 1 {
+            let gap = SequenceGap {
+                partition,
+                from: last + 1,
+                to: sequence - 1,
+            };
+            self.last_sequence.insert(partition, sequence);
+            self.missing += (gap.to - gap.from + 1) as u64;
+            self.open_gaps.push_back(gap);
+            while self.open_gaps.len() > max_gaps {
+                self.open_gaps.pop_front();
+            }
+            return Some(gap);
+        }
+        if sequence == last + 1 {
+            self.last_sequence.insert(partition, sequence);
+        } else {
+            self.fill(partition, sequence);
+        }
+        None
+    }
+
+    /// 迟到事件补齐缺口：拆分或移除所在区间
+    fn fill(&mut self, partition: u32, sequence: i64) {
+        let Some(index) = self
+            .open_gaps
+            .iter()
+            .position(|g| g.partition == partition && (g.from..=g.to).contains(&sequence))
+        else {
+            return;
+        };
+        let gap = self.open_gaps.remove(index).unwrap();
+        if gap.from < sequence {
+            self.open_gaps.push_back(SequenceGap {
+                to: sequence - 1,
+                ..gap
+            });
+        }
+        if sequence < gap.to {
+            self.open_gaps.push_back(SequenceGap {
+                from: sequence + 1,
+                ..gap
+            });
+        }
+    }
+}
+
+/// 按处理器组记录已处理事件，检测重复与位点缺口
+pub struct DeliveryMonitor {
+    config: DeliveryMonitorConfig,
+    groups: Mutex<BTreeMap<String, GroupState>>,
+    metrics: Arc<dyn HandlerMetrics>,
+}
+
+impl Default for DeliveryMonitor {
+    fn default() -> Self {
+        Self::new(DeliveryMonitorConfig::default())
+    }
+}
+
+impl DeliveryMonitor {
+    pub fn new(config: DeliveryMonitorConfig) -> Self {
+        Self {
+            config,
+            groups: Mutex::new(BTreeMap::new()),
+            metrics: Arc::new(NoopMetrics),
+        }
+    }
+
+    /// 写入 `delivery_duplicates.<组>`/`delivery_gaps.<组>` 计数的指标后端
+    pub fn with_metrics(mut self, metrics: Arc<dyn HandlerMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// 记录组内一次成功处理；`track_gaps` 为真时按位点检测缺口
+    pub fn observe(&self, group: &str, event: &SerializedEvent, track_gaps: bool) -> Observation {
+        let observation = {
+            let mut groups = self.groups.lock().unwrap();
+            let state = groups.entry(group.to_string()).or_default();
+            state.observed += 1;
+            let duplicate = state.remember(event.event_id(), self.config.window.max(1));
+            if duplicate {
+                state.duplicates += 1;
+            }
+            let gap = match (track_gaps, duplicate, event.sequence_number()) {
+                (true, false, Some(sequence)) => {
+                    state.track_sequence(event.partition(), sequence, self.config.max_open_gaps)
+                }
+                _ => None,
+            };
+            Observation { duplicate, gap }
+        };
+
+        if observation.duplicate {
+            self.metrics
+                .increment(&format!("{DELIVERY_DUPLICATES_METRIC}.{group}"), 1);
+            tracing::warn!(
+                target: LOG_TARGET,
+                group,
+                event_id = event.event_id(),
+                event_type = event.event_type(),
+                aggregate_id = event.aggregate_id(),
+                "duplicate event handled"
+            );
+        }
+        if let Some(gap) = observation.gap {
+            self.metrics.increment(
+                &format!("{DELIVERY_GAPS_METRIC}.{group}"),
+                (gap.to - gap.from + 1) as u64,
+            );
+            tracing::warn!(
+                target: LOG_TARGET,
+                group,
+                partition = gap.partition,
+                from = gap.from,
+                to = gap.to,
+                event_id = event.event_id(),
+                "event sequence gap detected"
+            );
+        }
+        observation
+    }
+
+    /// 单组的诊断结果；未记录过的组返回 `None`
+    pub fn report(&self, group: &str) -> Option<DeliveryReport> {
+        self.groups
+            .lock()
+            .unwrap()
+            .get(group)
+            .map(|state| to_report(group, state))
+    }
+
+    /// 全部组的诊断结果（按组名排序）
+    pub fn reports(&self) -> Vec<DeliveryReport> {
+        self.groups
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|(group, state)| to_report(group, state))
+            .collect()
+    }
+
+    /// 清空组的记录（如基础设施切换完成、开始新一轮验证）
+    pub fn reset(&self, group: &str) {
+        self.groups.lock().unwrap().remove(group);
+    }
+}
+
+fn to_report(group: &str, state: &GroupState) -> DeliveryReport {
+    let mut open_gaps: Vec<SequenceGap> = state.open_gaps.iter().copied().collect();
+    open_gaps.sort_by_key(|g| (g.partition, g.from));
+    DeliveryReport {
+        group: group.to_string(),
+        observed: state.observed,
+        duplicates: state.duplicates,
+        missing: state.missing,
+        open_gaps,
+    }
+}
+
+/// 以处理器名为组接入 `DeliveryMonitor` 的 `EventHandler` 装饰器
+///
+/// 仅当内层处理器订阅全部事件类型时检测位点缺口（按类型过滤的订阅本身就会跳号）。
+pub struct DeliveryMonitorHandler {
+    inner: Arc<dyn EventHandler>,
+    monitor: Arc<DeliveryMonitor>,
+}
+
+impl DeliveryMonitorHandler {
+    pub fn new(inner: Arc<dyn EventHandler>, monitor: Arc<DeliveryMonitor>) -> Self {
+        Self { inner, monitor }
+    }
+
+    pub fn monitor(&self) -> &Arc<DeliveryMonitor> {
+        &self.monitor
+    }
+}
+
+#[async_trait]
+impl EventHandler for DeliveryMonitorHandler {
+    fn handler_name(&self) -> &str {
+        self.inner.handler_name()
+    }
+
+    fn handled_event_type(&self) -> HandledEventType {
+        self.inner.handled_event_type()
+    }
+
+    fn required_ordering(&self) -> OrderingGuarantee {
+        self.inner.required_ordering()
+    }
+
+    async fn handle(&self, event: &SerializedEvent, ctx: &HandlerContext) -> anyhow::Result<()> {
+        self.inner.handle(event, ctx).await?;
+        let track_gaps = matches!(self.inner.handled_event_type(), HandledEventType::All);
+        self.monitor
+            .observe(self.inner.handler_name(), event, track_gaps);
+        Ok(())
+    }
+
+    fn circuit_status(&self) -> Option<CircuitStatus> {
+        self.inner.circuit_status()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    #[derive(Default)]
+    struct Recorded(Mutex<Vec<(String, u64)>>);
+
+    impl HandlerMetrics for Recorded {
+        fn increment(&self, name: &str, value: u64) {
+            self.0.lock().unwrap().push((name.to_string(), value));
+        }
+
+        fn observe(&self, _name: &str, _value: f64) {}
+    }
+
+    /// 首次处理 `e-3` 失败，之后成功
+    #[derive(Default)]
+    struct Projection {
+        failed_once: AtomicBool,
+    }
+
+    #[async_trait]
+    impl EventHandler for Projection {
+        fn handler_name(&self) -> &str {
+            "orders_view"
+        }
+
+        fn handled_event_type(&self) -> HandledEventType {
+            HandledEventType::All
+        }
+
+        async fn handle(
+            &self,
+            event: &SerializedEvent,
+            _ctx: &HandlerContext,
+        ) -> anyhow::Result<()> {
+            if event.event_id() == "e-3" && !self.failed_once.swap(true, Ordering::SeqCst) {
+                anyhow::bail!("transient failure");
+            }
+            Ok(())
+        }
+    }
+
+    fn mk_event(sequence: i64) -> SerializedEvent {
+        SerializedEvent::builder()
+            .event_id(format!("e-{sequence}"))
+            .sequence_number(sequence)
+            .event_type("OrderPlaced".to_string())
+            .event_version(1)
+            .aggregate_id("o-1".to_string())
+            .aggregate_type("order".to_string())
+            .aggregate_version(1)
+            .occurred_at(Utc::now())
+            .payload(serde_json::json!({}))
+            .context(serde_json::json!({}))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn reports_duplicates_and_gaps_per_group() {
+        let metrics = Arc::new(Recorded::default());
+        let monitor = Arc::new(DeliveryMonitor::default().with_metrics(metrics.clone()));
+        let handler = DeliveryMonitorHandler::new(Arc::new(Projection::default()), monitor.clone());
+        let ctx = HandlerContext::default();
+
+        // 1, 2, 3（失败后重投成功）, 2（重复）, 7（缺 4..=6）, 5（迟到补齐）
+        for sequence in [1, 2, 3, 3, 2, 7, 5] {
+            let _ = handler.handle(&mk_event(sequence), &ctx).await;
+        }
+
+        let report = monitor.report("orders_view").unwrap();
+        assert_eq!(report.observed, 6);
+        assert_eq!(report.duplicates, 1);
+        assert_eq!(report.missing, 3);
+        assert_eq!(
+            report.open_gaps,
+            [
+                SequenceGap {
+                    partition: 0,
+                    from: 4,
+                    to: 4
+                },
+                SequenceGap {
+                    partition: 0,
+                    from: 6,
+                    to: 6
+                },
+            ]
+        );
+        assert_eq!(
+            metrics.0.lock().unwrap().as_slice(),
+            &[
+                ("delivery_duplicates.orders_view".to_string(), 1),
+                ("delivery_gaps.orders_view".to_string(), 3),
+            ]
+        );
+    }
+
+    #[test]
+    fn window_bounds_duplicate_detection() {
+        let monitor = DeliveryMonitor::new(DeliveryMonitorConfig {
+            window: 2,
+            ..Default::default()
+        });
+        for sequence in [1, 2, 3] {
+            monitor.observe("audit", &mk_event(sequence), false);
+        }
+        // e-1 已滑出窗口，e-3 仍在窗口内
+        assert!(!monitor.observe("audit", &mk_event(1), false).duplicate);
+        assert!(monitor.observe("audit", &mk_event(3), false).duplicate);
+        // 未检测缺口的组不记录位点
+        assert!(monitor.report("audit").unwrap().open_gaps.is_empty());
+
+        monitor.reset("audit");
+        assert!(monitor.reports().is_empty());
+    }
+}
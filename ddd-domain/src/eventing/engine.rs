@@ -2,22 +2,57 @@
 //!
 //! 统一编排“投递 → 订阅 → 分发处理”的长驻任务：
 //! - 周期从中继与回收器拉取事件并发布至总线；
-//! - 订阅总线事件流，按处理器匹配分发并发执行；
-//! - 失败标记与补偿重放；
-//! - 提供关闭与等待的 `EngineHandle`。
+//! - 订阅总线事件流（可按 `SubscribeOptions` 从历史位置开始），按处理器匹配分发并发执行；
+//! - 批量处理器（`BatchEventHandler`）各由独立 worker 按大小/时间窗口累积事件并整批交付，关闭时交付剩余事件；
+//! - 分发前后执行 `HandlerMiddleware` 链（解密、租户作用域、日志等横切逻辑）；
+//! - 处理成功后将处理器派生的事件以源事件为因写入 Outbox（`EventOutbox`），写入失败时源事件转交回收器重试；
+//! - 处理器 panic 被捕获并以 `panic` 原因转交回收器，连续 panic 的处理器可按配置隔离（`panic_guard`）；
+//! - 失败标记与补偿重放；累计投递次数超过上限仍失败的事件转入死信队列（`DeadLetterStore`），可列出并重放；
+//! - 配置热启动（`WarmStart`）时，启动后先追赶停机期间未分发的事件，再开始投递，进度见 `EngineStatus::warm_start`；
+//! - 生命周期各环节输出结构化日志（`engine_log`，详细程度见 `EngineTuning::with_log_level`）；
+//! - 启动与运行期注册时校验总线的顺序保证满足处理器要求（`check_ordering`，`try_start` 以错误返回）；
+//! - 提供关闭、等待、按组件暂停/恢复、运行期注册/注销处理器与调整订阅（`HandlerSubscription`）的 `EngineHandle`。
 //!
-use super::handler::HandledEventType;
-use super::{EventBus, EventDeliverer, EventHandler, EventReclaimer};
+use super::causation_guard::CausationDepthExceeded;
+#[cfg(feature = "chaos")]
+use super::chaos::EngineChaos;
+use super::chaos::{CHAOS_FAILURE_REASON, ChaosHooks};
+use super::circuit_breaker::CircuitStatus;
+use super::compression::{self, PayloadCompression};
+use super::dead_letter::{DeadLetter, DeadLetterStore};
+use super::engine_log::{EngineLog, EngineLogLevel};
+use super::handler::{BatchEventHandler, HandledEventType, HandlerSubscription};
+use super::handler_context::{Clock, HandlerContext, HandlerMetrics, NoopMetrics, SystemClock};
+use super::middleware::{self, HandlerMiddleware};
+use super::panic_guard::{
+    HANDLER_PANIC_REASON, HANDLER_PANICS_METRIC, HandlerPanicked, PanicTracker, catch_panic,
+};
+use super::pause::{EngineComponent, HANDLER_PAUSED_REASON, PauseControl};
+use super::retry::RetryPolicy;
+use super::warm_start::{WarmStart, WarmStartStatus, WarmStartTracker};
+use super::{
+    EventBus, EventDeliverer, EventHandler, EventOutbox, EventReclaimer, OrderingGuarantee,
+    ReplaySource, SubscribeFrom, SubscribeOptions,
+};
+use crate::error::{DomainError, DomainResult};
 use crate::persist::SerializedEvent;
 use async_trait::async_trait;
 use bon::Builder;
+use chrono::{DateTime, Utc};
 use futures_util::{StreamExt, stream};
-use std::{collections::HashMap, sync::Arc, time::Duration};
+use std::{
+    collections::{HashMap, HashSet},
+    sync::{Arc, Mutex, RwLock},
+    time::Duration,
+};
+use tokio::sync::mpsc;
 use tokio::task::JoinHandle;
 use tokio::time::{self, MissedTickBehavior};
 use tokio_util::sync::CancellationToken;
 
 // 导入由 bon::Builder 生成的 typestate 模块与状态转换别名
+#[cfg(feature = "chaos")]
+use self::event_engine_builder::SetChaos;
 use self::event_engine_builder::{IsUnset, SetRegistry, State as BuilderState};
 
 /// EventEngine：
@@ -28,10 +63,46 @@ pub struct EventEngine {
     event_bus: Arc<dyn EventBus>,
     event_deliverer: Arc<dyn EventDeliverer>,
     event_reclaimer: Arc<dyn EventReclaimer>,
+    /// 处理器派生事件的写入端；未配置时产出派生事件的处理视为失败
+    event_outbox: Option<Arc<dyn EventOutbox>>,
+    /// 死信存储；未配置时超过 `max_deliveries` 的事件仍转交回收器
+    dead_letters: Option<Arc<dyn DeadLetterStore>>,
+    /// 热启动；未配置时停机期间未分发的事件仅由回收器补偿
+    warm_start: Option<WarmStart>,
     #[builder(setters(vis = "pub(crate)"))]
     registry: HandlerRegistry,
     #[builder(default)]
     config: EventEngineConfig,
+    /// 重试、死信、压缩、日志等可选行为
+    #[builder(default)]
+    tuning: EngineTuning,
+    /// 引擎名称，传递给处理器上下文
+    #[builder(into, default = "default".to_string())]
+    name: String,
+    #[builder(default = Arc::new(SystemClock))]
+    clock: Arc<dyn Clock>,
+    #[builder(default = Arc::new(NoopMetrics))]
+    metrics: Arc<dyn HandlerMetrics>,
+    /// 处理器中间件，按顺序包裹每次处理调用
+    #[builder(default)]
+    middlewares: Vec<Arc<dyn HandlerMiddleware>>,
+    /// 订阅起点；从历史位置订阅可直接重建读模型，总线不支持回放时退化为仅订阅新事件
+    #[builder(default)]
+    subscribe_options: SubscribeOptions,
+    /// 批量处理器（启动时固定，不参与运行期注册/注销）
+    #[builder(default)]
+    batch_handlers: Vec<Arc<dyn BatchEventHandler>>,
+    /// 故障注入点（见 `chaos` 模块），未配置时不注入
+    #[builder(default, setters(name = chaos_hooks, vis = "pub(crate)"))]
+    chaos: ChaosHooks,
+    #[builder(skip)]
+    deliveries: Arc<DeliveryTracker>,
+    #[builder(skip)]
+    pauses: Arc<PauseControl>,
+    #[builder(skip)]
+    warm_start_progress: Arc<WarmStartTracker>,
+    #[builder(skip)]
+    panics: Arc<PanicTracker>,
 }
 
 impl<S: BuilderState> EventEngineBuilder<S> {
@@ -44,25 +115,655 @@ impl<S: BuilderState> EventEngineBuilder<S> {
     {
         self.registry(HandlerRegistry::new(handlers))
     }
+
+    /// 接入故障注入器（混沌测试），注入点与概率见 `EngineChaos`
+    #[cfg(feature = "chaos")]
+    pub fn chaos(self, chaos: Arc<EngineChaos>) -> EventEngineBuilder<SetChaos<S>>
+    where
+        <S as BuilderState>::Chaos: IsUnset,
+    {
+        self.chaos_hooks(ChaosHooks::new(chaos))
+    }
 }
 
 impl EventEngine {
+    /// 引擎状态快照：各处理器的熔断/暂停状态、生效的订阅、已暂停的组件与热启动进度
+    pub fn status(&self) -> EngineStatus {
+        let registry = self.registry.load();
+        let handlers = registry
+            .handlers
+            .iter()
+            .map(|h| {
+                let overridden = registry.overrides.get(h.handler_name());
+                HandlerStatus {
+                    name: h.handler_name().to_string(),
+                    circuit: h.circuit_status(),
+                    paused: self.pauses.is_handler_paused(h.handler_name()),
+                    event_types: overridden
+                        .map_or_else(|| h.handled_event_type(), |s| s.event_types().clone()),
+                    payload_filter: overridden
+                        .and_then(|s| s.filter_description())
+                        .map(ToString::to_string),
+                    subscription_overridden: overridden.is_some(),
+                }
+            })
+            .chain(self.batch_handlers.iter().map(|h| HandlerStatus {
+                name: h.handler_name().to_string(),
+                circuit: None,
+                paused: self.pauses.is_handler_paused(h.handler_name()),
+                event_types: h.handled_event_type(),
+                payload_filter: None,
+                subscription_overridden: false,
+            }))
+            .collect();
+
+        EngineStatus {
+            handlers,
+            paused: self.pauses.paused(),
+            warm_start: self.warm_start_progress.status(),
+        }
+    }
+
+    /// 为一次处理调用构造上下文，并累计该处理器对此事件的投递次数
+    fn handler_context(&self, handler_name: &str, event: &SerializedEvent) -> HandlerContext {
+        let (attempt, first_seen_at) =
+            self.deliveries
+                .begin(handler_name, event.event_id(), self.clock.now());
+
+        HandlerContext::builder()
+            .engine_name(self.name.clone())
+            .attempt(attempt)
+            .first_seen_at(first_seen_at)
+            .clock(Arc::clone(&self.clock))
+            .metrics(Arc::clone(&self.metrics))
+            .build()
+    }
+
+    /// 将事件交给单个处理器（经中间件链），失败时按 `RetryPolicy` 原地重试，
+    /// 仍失败或处理器已暂停时转交回收器
+    async fn dispatch(&self, handler: &dyn EventHandler, event: &SerializedEvent) {
+        let name = handler.handler_name();
+        if self.pauses.is_handler_paused(name) {
+            self.reclaim_failed(name, &[event], HANDLER_PAUSED_REASON)
+                .await;
+            return;
+        }
+        if self.chaos.fail_handler() {
+            self.reclaim_failed(name, &[event], CHAOS_FAILURE_REASON)
+                .await;
+            return;
+        }
+
+        let retry = self.tuning.retry;
+        let mut attempt = 1;
+        loop {
+            self.log().dispatched(name, event);
+            match self.handle_once(handler, event).await {
+                Ok(()) => {
+                    self.deliveries.finish(name, event.event_id());
+                    self.panics.reset(name);
+                    self.log().handled(name, &[event]);
+                    return;
+                }
+                Err(ref err) if let Some(panicked) = err.downcast_ref::<HandlerPanicked>() => {
+                    self.handler_panicked(name, &[event], panicked).await;
+                    return;
+                }
+                Err(err) if err.downcast_ref::<CausationDepthExceeded>().is_some() => {
+                    self.handler_rejected(name, &[event], &err.to_string())
+                        .await;
+                    return;
+                }
+                Err(err)
+                    if retry.should_retry(attempt) && !self.deliveries_exhausted(name, event) =>
+                {
+                    let delay = retry.delay(attempt, uuid::Uuid::new_v4().as_u64_pair().0);
+                    self.log()
+                        .handler_retrying(name, event, attempt, delay, &err);
+                    time::sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(err) => {
+                    self.handler_failed(name, &[event], &err.to_string()).await;
+                    return;
+                }
+            }
+        }
+    }
+
+    /// 单次处理调用（经中间件链），成功后写入派生事件
+    async fn handle_once(
+        &self,
+        handler: &dyn EventHandler,
+        event: &SerializedEvent,
+    ) -> anyhow::Result<()> {
+        let ctx = self.handler_context(handler.handler_name(), event);
+        let outputs = ctx.clone();
+        catch_panic(middleware::run(
+            &self.middlewares,
+            handler,
+            event.clone(),
+            ctx,
+        ))
+        .await?;
+        self.write_emitted(Some(event), outputs.take_emitted())
+            .await
+    }
+
+    fn log(&self) -> EngineLog<'_> {
+        EngineLog::new(self.tuning.log_level, &self.name)
+    }
+
+    /// 处理失败的事件转交回收器
+    async fn reclaim_failed(&self, handler_name: &str, events: &[&SerializedEvent], reason: &str) {
+        let log = self.log();
+        log.handler_failed(handler_name, events, &reason);
+        if let Err(err) = self
+            .event_reclaimer
+            .mark_handler_failed(handler_name, events, reason)
+            .await
+        {
+            log.mark_failed("reclaim", "handler_failed", events, &err);
+        }
+    }
+
+    /// 处理器 panic：计入指标，连续次数达到 `quarantine_after_panics` 时隔离处理器，
+    /// 事件以 `panic` 原因按失败处理（不原地重试）
+    async fn handler_panicked(
+        &self,
+        handler_name: &str,
+        events: &[&SerializedEvent],
+        panicked: &HandlerPanicked,
+    ) {
+        self.metrics
+            .increment(&format!("{HANDLER_PANICS_METRIC}.{handler_name}"), 1);
+        let consecutive = self.panics.record(handler_name);
+        let log = self.log();
+        log.handler_panicked(handler_name, events, &panicked.0, consecutive);
+        if self
+            .tuning
+            .quarantine_after_panics
+            .is_some_and(|max| consecutive >= max)
+        {
+            self.panics.reset(handler_name);
+            if self.pauses.pause(EngineComponent::handler(handler_name)) {
+                log.handler_quarantined(handler_name, consecutive);
+            }
+        }
+        self.handler_failed(handler_name, events, HANDLER_PANIC_REASON)
+            .await;
+    }
+
+    /// 处理器对事件的累计投递次数是否已达 `max_deliveries`（仅在配置了死信存储时生效）
+    fn deliveries_exhausted(&self, handler_name: &str, event: &SerializedEvent) -> bool {
+        match (self.tuning.max_deliveries, &self.dead_letters) {
+            (Some(max), Some(_)) => self
+                .deliveries
+                .current(handler_name, event.event_id())
+                .is_some_and(|(attempts, _)| attempts >= max),
+            _ => false,
+        }
+    }
+
+    /// 处理失败：累计投递次数达到 `max_deliveries` 且配置了死信存储时转入死信队列，否则转交回收器
+    ///
+    /// 批量处理器按批内首个事件计数，整批转入死信队列。
+    async fn handler_failed(&self, handler_name: &str, events: &[&SerializedEvent], reason: &str) {
+        let Some(first) = events.first() else {
+            return;
+        };
+        if !self.deliveries_exhausted(handler_name, first) {
+            return self.reclaim_failed(handler_name, events, reason).await;
+        }
+        self.dead_letter(handler_name, events, reason).await;
+    }
+
+    /// 不可重试的失败（如因果链深度超限）：配置了死信存储时直接转入死信队列，否则转交回收器
+    async fn handler_rejected(
+        &self,
+        handler_name: &str,
+        events: &[&SerializedEvent],
+        reason: &str,
+    ) {
+        self.dead_letter(handler_name, events, reason).await;
+    }
+
+    /// 将事件转入死信队列；未配置死信存储时转交回收器
+    async fn dead_letter(&self, handler_name: &str, events: &[&SerializedEvent], reason: &str) {
+        let Some(first) = events.first() else {
+            return;
+        };
+        let Some(store) = &self.dead_letters else {
+            return self.reclaim_failed(handler_name, events, reason).await;
+        };
+        let (attempts, first_attempted_at) = self
+            .deliveries
+            .current(handler_name, first.event_id())
+            .unwrap_or((1, self.clock.now()));
+
+        let log = self.log();
+        let now = self.clock.now();
+        let mut dead = Vec::with_capacity(events.len());
+        for event in events {
+            let letter = DeadLetter {
+                event: (*event).clone(),
+                handler_name: handler_name.to_string(),
+                reason: reason.to_string(),
+                attempts,
+                first_attempted_at,
+                dead_lettered_at: now,
+            };
+            match store.push(letter).await {
+                Ok(()) => dead.push(*event),
+                // 写入死信失败时仍交给回收器，避免事件丢失
+                Err(err) => {
+                    log.mark_failed("dead_letter", "dead_lettered", &[event], &err);
+                    self.reclaim_failed(handler_name, &[event], reason).await;
+                }
+            }
+        }
+        self.deliveries.finish(handler_name, first.event_id());
+        if dead.is_empty() {
+            return;
+        }
+
+        log.dead_lettered(handler_name, &dead, attempts, &reason);
+        if let Err(err) = self
+            .event_reclaimer
+            .mark_dead_lettered(handler_name, &dead, reason)
+            .await
+        {
+            log.mark_failed("reclaim", "dead_lettered", &dead, &err);
+        }
+    }
+
+    /// 取出死信并重新交给原处理器，返回死信是否存在；处理器未注册时返回错误并保留死信
+    async fn replay_dead_letter(&self, handler_name: &str, event_id: &str) -> DomainResult<bool> {
+        let Some(store) = &self.dead_letters else {
+            return Ok(false);
+        };
+        let handler = self
+            .registry
+            .load()
+            .handlers
+            .iter()
+            .find(|h| h.handler_name() == handler_name)
+            .cloned();
+        let batch_handler = self
+            .batch_handlers
+            .iter()
+            .find(|h| h.handler_name() == handler_name);
+        if handler.is_none() && batch_handler.is_none() {
+            return Err(DomainError::invalid_state(format!(
+                "event handler {handler_name} is not registered"
+            )));
+        }
+
+        let Some(letter) = store.take(handler_name, event_id).await? else {
+            return Ok(false);
+        };
+        match (handler, batch_handler) {
+            (Some(handler), _) => self.dispatch(handler.as_ref(), &letter.event).await,
+            (None, Some(handler)) => {
+                self.dispatch_batch(handler.as_ref(), std::slice::from_ref(&letter.event))
+                    .await
+            }
+            (None, None) => unreachable!(),
+        }
+        Ok(true)
+    }
+
+    /// 将派生事件写入 Outbox；单事件处理器产出的事件以 `parent` 为因补齐因果元数据，
+    /// 批量处理器产出的事件无唯一源事件，原样写入
+    async fn write_emitted(
+        &self,
+        parent: Option<&SerializedEvent>,
+        emitted: Vec<SerializedEvent>,
+    ) -> anyhow::Result<()> {
+        if emitted.is_empty() {
+            return Ok(());
+        }
+        let Some(outbox) = &self.event_outbox else {
+            anyhow::bail!("handler emitted events but no event outbox is configured");
+        };
+
+        let events: Vec<SerializedEvent> = match parent {
+            Some(parent) => emitted.into_iter().map(|e| e.caused_by(parent)).collect(),
+            None => emitted,
+        };
+        outbox.enqueue(&events).await?;
+        Ok(())
+    }
+
+    /// 将一批事件交给批量处理器，失败或处理器已暂停时整批转交回收器
+    async fn dispatch_batch(&self, handler: &dyn BatchEventHandler, events: &[SerializedEvent]) {
+        let Some(first) = events.first() else {
+            return;
+        };
+        let name = handler.handler_name();
+        let refs: Vec<&SerializedEvent> = events.iter().collect();
+        if self.pauses.is_handler_paused(name) {
+            self.reclaim_failed(name, &refs, HANDLER_PAUSED_REASON)
+                .await;
+            return;
+        }
+        if self.chaos.fail_handler() {
+            self.reclaim_failed(name, &refs, CHAOS_FAILURE_REASON).await;
+            return;
+        }
+
+        let ctx = self.handler_context(name, first);
+        let result = match catch_panic(handler.handle_batch(events, &ctx)).await {
+            Ok(()) => self.write_emitted(None, ctx.take_emitted()).await,
+            Err(err) => Err(err),
+        };
+        match result {
+            Ok(()) => {
+                self.deliveries.finish(name, first.event_id());
+                self.panics.reset(name);
+                self.log().handled(name, &refs);
+            }
+            Err(err) => match err.downcast_ref::<HandlerPanicked>() {
+                Some(panicked) => self.handler_panicked(name, &refs, panicked).await,
+                None => self.handler_failed(name, &refs, &err.to_string()).await,
+            },
+        }
+    }
+
+    /// 批量处理器 worker：按到达顺序累积事件，满批或时间窗口到期时交付；
+    /// 关闭时交付已累积与通道中剩余的事件
+    async fn batch_worker(
+        self: Arc<Self>,
+        handler: Arc<dyn BatchEventHandler>,
+        mut rx: mpsc::Receiver<SerializedEvent>,
+        token: CancellationToken,
+    ) {
+        let config = handler.batch_config();
+        let max = config.max_batch_size.max(1);
+        let mut batch = Vec::with_capacity(max);
+
+        loop {
+            let first = tokio::select! {
+                _ = token.cancelled() => break,
+                event = rx.recv() => event,
+            };
+            let Some(first) = first else { break };
+            batch.push(first);
+
+            let window = time::sleep(config.max_wait);
+            tokio::pin!(window);
+            while batch.len() < max {
+                tokio::select! {
+                    event = rx.recv() => match event {
+                        Some(event) => batch.push(event),
+                        None => break,
+                    },
+                    _ = &mut window => break,
+                    _ = token.cancelled() => break,
+                }
+            }
+            self.dispatch_batch(handler.as_ref(), &std::mem::take(&mut batch))
+                .await;
+        }
+
+        while let Ok(event) = rx.try_recv() {
+            batch.push(event);
+            if batch.len() >= max {
+                self.dispatch_batch(handler.as_ref(), &std::mem::take(&mut batch))
+                    .await;
+            }
+        }
+        self.dispatch_batch(handler.as_ref(), &batch).await;
+    }
+
+    /// 校验总线的顺序保证满足全部处理器（含批量处理器）的要求，不满足时列出全部处理器
+    pub fn check_ordering(&self) -> DomainResult<()> {
+        let registry = self.registry.load();
+        let required = registry
+            .handlers
+            .iter()
+            .map(|h| (h.handler_name(), h.required_ordering()))
+            .chain(
+                self.batch_handlers
+                    .iter()
+                    .map(|h| (h.handler_name(), h.required_ordering())),
+            );
+        self.ensure_ordering(required)
+    }
+
+    fn ensure_ordering<'a>(
+        &self,
+        required: impl IntoIterator<Item = (&'a str, OrderingGuarantee)>,
+    ) -> DomainResult<()> {
+        let provided = self.event_bus.ordering();
+        let unsatisfied: Vec<String> = required
+            .into_iter()
+            .filter(|(_, ordering)| !provided.satisfies(*ordering))
+            .map(|(name, ordering)| format!("{name} requires {ordering:?}"))
+            .collect();
+        if unsatisfied.is_empty() {
+            return Ok(());
+        }
+        Err(DomainError::invalid_state(format!(
+            "event bus only guarantees {provided:?} ordering: {}",
+            unsatisfied.join(", ")
+        ))
+        .with_code("ORDERING_UNSATISFIED"))
+    }
+
+    /// 运行期注册处理器；名称已存在或总线不满足其顺序要求时返回错误
+    fn register_handler(&self, handler: Arc<dyn EventHandler>) -> DomainResult<()> {
+        self.ensure_ordering([(handler.handler_name(), handler.required_ordering())])?;
+        self.registry.register(handler)
+    }
+
+    /// 先以回放数据源追赶历史事件，再注册处理器，返回追赶处理的事件数
+    ///
+    /// 追赶期间新发布的事件在注册后再补一轮；与实时投递重叠的事件可能被处理两次，
+    /// 处理器应保持幂等（如经 `ProjectionRunner` 按位点去重）。
+    async fn register_handler_with_catch_up(
+        &self,
+        handler: Arc<dyn EventHandler>,
+        replay: &dyn ReplaySource,
+        from: SubscribeFrom,
+    ) -> DomainResult<usize> {
+        if self.registry.contains(handler.handler_name()) {
+            return Err(duplicate_handler(handler.handler_name()));
+        }
+        self.ensure_ordering([(handler.handler_name(), handler.required_ordering())])?;
+
+        let mut seen = HashSet::new();
+        let mut handled = self
+            .catch_up(handler.as_ref(), replay, from.clone(), &mut seen)
+            .await?;
+        self.register_handler(Arc::clone(&handler))?;
+        handled += self
+            .catch_up(handler.as_ref(), replay, from, &mut seen)
+            .await?;
+        Ok(handled)
+    }
+
+    async fn catch_up(
+        &self,
+        handler: &dyn EventHandler,
+        replay: &dyn ReplaySource,
+        from: SubscribeFrom,
+        seen: &mut HashSet<String>,
+    ) -> DomainResult<usize> {
+        let mut handled = 0;
+        for event in replay.replay(from).await? {
+            if !seen.insert(event.event_id().to_string())
+                || !handler.handled_event_type().matches(event.event_type())
+            {
+                continue;
+            }
+            self.dispatch(handler, &event).await;
+            handled += 1;
+        }
+        Ok(handled)
+    }
+
+    /// 热启动：追赶停机期间未分发的事件，返回追赶过的事件 ID（随后经总线到达时跳过）
+    async fn warm_start_catch_up(
+        self: &Arc<Self>,
+        batch_sinks: &[(HandledEventType, mpsc::Sender<SerializedEvent>)],
+    ) -> HashSet<String> {
+        let mut replayed = HashSet::new();
+        let Some(warm_start) = &self.warm_start else {
+            return replayed;
+        };
+        let log = self.log();
+        let from = match warm_start.load(&self.name).await {
+            Ok(Some(from)) => from,
+            Ok(None) => return replayed,
+            Err(err) => {
+                log.warm_start_failed(&err);
+                return replayed;
+            }
+        };
+        self.warm_start_progress.resume(from);
+
+        let events = match warm_start
+            .replay()
+            .replay(SubscribeFrom::Sequence(from.saturating_add(1).max(0) as u64))
+            .await
+        {
+            Ok(events) => events,
+            Err(err) => {
+                log.warm_start_failed(&err);
+                return replayed;
+            }
+        };
+        let cutoff = self.tuning.max_catch_up_window.map(|window| {
+            self.clock.now() - chrono::Duration::from_std(window).unwrap_or(chrono::Duration::MAX)
+        });
+        let (events, skipped): (Vec<_>, Vec<_>) = events
+            .into_iter()
+            .filter(|e| e.sequence_number().is_some_and(|s| s > from))
+            .partition(|e| cutoff.is_none_or(|cutoff| e.occurred_at() >= cutoff));
+
+        let status = WarmStartStatus {
+            from_sequence: from,
+            head_sequence: events
+                .iter()
+                .chain(&skipped)
+                .filter_map(|e| e.sequence_number())
+                .max()
+                .unwrap_or(from),
+            total: events.len(),
+            replayed: 0,
+            skipped: skipped.len(),
+            completed: false,
+        };
+        log.warm_start_began(&status);
+        self.warm_start_progress.begin(status);
+
+        for event in &events {
+            self.dispatch_all(event, batch_sinks).await;
+            self.record_delivered(event).await;
+            replayed.insert(event.event_id().to_string());
+            self.warm_start_progress.update(|s| s.replayed += 1);
+        }
+        self.warm_start_progress.update(|s| s.completed = true);
+        if let Some(status) = self.warm_start_progress.status() {
+            log.warm_start_completed(&status);
+        }
+        replayed
+    }
+
+    /// 配置热启动时推进已分发位点的检查点
+    async fn record_delivered(&self, event: &SerializedEvent) {
+        let (Some(warm_start), Some(sequence)) = (&self.warm_start, event.sequence_number()) else {
+            return;
+        };
+        if self.warm_start_progress.advance(sequence)
+            && let Err(err) = warm_start.save(&self.name, sequence).await
+        {
+            self.log().warm_start_failed(&err);
+        }
+    }
+
+    /// 将事件交给匹配的批量处理器 worker 与处理器（同一事件的处理器间按 `handler_concurrency` 并发）
+    async fn dispatch_all(
+        self: &Arc<Self>,
+        event: &SerializedEvent,
+        batch_sinks: &[(HandledEventType, mpsc::Sender<SerializedEvent>)],
+    ) {
+        for (types, tx) in batch_sinks {
+            if types.matches(event.event_type()) {
+                let _ = tx.send(event.clone()).await;
+            }
+        }
+        // 每个事件读取一次当前注册表，运行期注册/注销对后续事件生效
+        let merged = self.registry.load().matching(event);
+        if merged.is_empty() {
+            return;
+        }
+        let engine = self.clone();
+        let event = event.clone();
+        stream::iter(merged)
+            .for_each_concurrent(Some(self.config.handler_concurrency), move |h| {
+                let engine = engine.clone();
+                let ev = event.clone();
+                async move { engine.dispatch(h.as_ref(), &ev).await }
+            })
+            .await;
+    }
+
     /// 启动事件引擎，返回可用于关闭/等待的句柄
     ///
-    /// 启动顺序：先启动 subscribe worker 并等待其完成订阅，
+    /// 启动顺序：先启动 subscribe worker 并等待其完成订阅（及热启动追赶），
     /// 然后再启动 deliver/reclaim worker，避免事件丢失。
+    ///
+    /// 总线的顺序保证不满足已注册处理器的要求时（见 `check_ordering`），不启动任何 worker，
+    /// 记录错误日志并返回已停止的句柄（`EngineHandle::is_stopped`）；需要处理该错误时使用 `try_start`。
     pub fn start(self: Arc<Self>) -> EngineHandle {
+        match Arc::clone(&self).try_start() {
+            Ok(handle) => handle,
+            Err(e) => {
+                self.log().start_refused(&e);
+                let token = CancellationToken::new();
+                token.cancel();
+                EngineHandle {
+                    token,
+                    tasks: Vec::new(),
+                    pauses: self.pauses.clone(),
+                    engine: self,
+                }
+            }
+        }
+    }
+
+    /// 同 `start`，总线的顺序保证不满足已注册处理器的要求时返回 `ORDERING_UNSATISFIED`
+    /// 错误而不启动任何 worker
+    pub fn try_start(self: Arc<Self>) -> DomainResult<EngineHandle> {
+        self.check_ordering()?;
+
         let token = CancellationToken::new();
         let mut tasks: Vec<JoinHandle<()>> = Vec::with_capacity(3);
 
         // 使用 oneshot channel 同步订阅完成
         let (subscribe_ready_tx, subscribe_ready_rx) = tokio::sync::oneshot::channel::<()>();
 
+        // 0. 每个批量处理器一个 worker，订阅循环经通道按序投递匹配的事件
+        let mut batch_sinks = Vec::with_capacity(self.batch_handlers.len());
+        for handler in &self.batch_handlers {
+            let (tx, rx) = mpsc::channel(handler.batch_config().max_batch_size.max(1));
+            batch_sinks.push((handler.handled_event_type(), tx));
+            tasks.push(tokio::spawn(Self::batch_worker(
+                self.clone(),
+                Arc::clone(handler),
+                rx,
+                token.clone(),
+            )));
+        }
+
         // 1. 先启动 subscribe worker（长循环），等待订阅完成后再启动其他 worker
         tasks.push(tokio::spawn(Self::subscribe_loop_with_ready_signal(
             self.clone(),
             token.clone(),
             subscribe_ready_tx,
+            batch_sinks,
         )));
 
         // 2. 启动 deliver worker（周期任务），等待订阅完成
@@ -71,23 +772,38 @@ impl EventEngine {
             let deliverer = self.event_deliverer.clone();
             let marker = DelivererMarker::new(deliverer.clone());
             let interval = self.config.deliver_interval;
+            let compression = self.tuning.compression;
+            let chaos = self.chaos.clone();
+            let engine = self.clone();
 
             tasks.push(Self::spawn_periodic_after_ready(
                 token.clone(),
                 interval,
                 subscribe_ready_rx,
+                (self.pauses.clone(), EngineComponent::Deliver),
                 move || {
                     let bus = bus.clone();
                     let deliverer = deliverer.clone();
                     let marker = marker.clone();
+                    let chaos = chaos.clone();
+                    let engine = engine.clone();
                     async move {
+                        let log = engine.log();
                         match deliverer.fetch_events().await {
                             Ok(events) => {
-                                Self::publish_and_mark(&bus, &marker, events).await;
-                            }
-                            Err(_) => {
-                                // 拉取事件失败，稍后重试
+                                log.fetched(marker.source(), events.len());
+                                Self::publish_and_mark(
+                                    &bus,
+                                    &marker,
+                                    compression,
+                                    &chaos,
+                                    log,
+                                    events,
+                                )
+                                .await;
                             }
+                            // 拉取事件失败，稍后重试
+                            Err(err) => log.fetch_failed(marker.source(), &err),
                         }
                     }
                 },
@@ -100,25 +816,55 @@ impl EventEngine {
             let reclaimer = self.event_reclaimer.clone();
             let marker = ReclaimerMarker::new(reclaimer.clone());
             let interval = self.config.reclaim_interval;
+            let compression = self.tuning.compression;
+            let chaos = self.chaos.clone();
+            let engine = self.clone();
 
-            tasks.push(Self::spawn_periodic(token.clone(), interval, move || {
-                let bus = bus.clone();
-                let reclaimer = reclaimer.clone();
-                let marker = marker.clone();
-                async move {
-                    if let Ok(events) = reclaimer.fetch_events().await {
-                        Self::publish_and_mark(&bus, &marker, events).await;
+            let pause = (self.pauses.clone(), EngineComponent::Reclaim);
+            tasks.push(Self::spawn_periodic(
+                token.clone(),
+                interval,
+                pause,
+                move || {
+                    let bus = bus.clone();
+                    let reclaimer = reclaimer.clone();
+                    let marker = marker.clone();
+                    let chaos = chaos.clone();
+                    let engine = engine.clone();
+                    async move {
+                        let log = engine.log();
+                        match reclaimer.fetch_events().await {
+                            Ok(events) => {
+                                log.fetched(marker.source(), events.len());
+                                Self::publish_and_mark(
+                                    &bus,
+                                    &marker,
+                                    compression,
+                                    &chaos,
+                                    log,
+                                    events,
+                                )
+                                .await;
+                            }
+                            Err(err) => log.fetch_failed(marker.source(), &err),
+                        }
                     }
-                }
-            }));
+                },
+            ));
         }
 
-        EngineHandle { token, tasks }
+        Ok(EngineHandle {
+            token,
+            tasks,
+            pauses: self.pauses.clone(),
+            engine: self,
+        })
     }
 
     fn spawn_periodic<F, Fut>(
         token: CancellationToken,
         interval: Duration,
+        (pauses, component): (Arc<PauseControl>, EngineComponent),
         mut f: F,
     ) -> JoinHandle<()>
     where
@@ -132,7 +878,11 @@ impl EventEngine {
             loop {
                 tokio::select! {
                     _ = token.cancelled() => break,
-                    _ = ticker.tick() => f().await,
+                    _ = ticker.tick() => {
+                        if !pauses.is_paused(&component) {
+                            f().await;
+                        }
+                    }
                 }
             }
         })
@@ -143,6 +893,7 @@ impl EventEngine {
         token: CancellationToken,
         interval: Duration,
         ready_rx: tokio::sync::oneshot::Receiver<()>,
+        (pauses, component): (Arc<PauseControl>, EngineComponent),
         mut f: F,
     ) -> JoinHandle<()>
     where
@@ -167,35 +918,55 @@ impl EventEngine {
             loop {
                 tokio::select! {
                     _ = token.cancelled() => break,
-                    _ = ticker.tick() => f().await,
+                    _ = ticker.tick() => {
+                        if !pauses.is_paused(&component) {
+                            f().await;
+                        }
+                    }
                 }
             }
         })
     }
 
+    /// 发布事件并标记结果；配置了压缩时发布压缩后的副本，标记仍基于原事件
     async fn publish_and_mark(
         bus: &Arc<dyn EventBus>,
         marker: &impl EventBatchMarker,
-        events: Vec<SerializedEvent>,
+        compression: Option<PayloadCompression>,
+        chaos: &ChaosHooks,
+        log: EngineLog<'_>,
+        mut events: Vec<SerializedEvent>,
     ) {
         if events.is_empty() {
             return;
         }
+        chaos.before_publish(&mut events).await;
+
+        let compressed;
+        let outgoing: &[SerializedEvent] = match compression {
+            Some(c) => {
+                compressed = c.compress_batch(&events);
+                &compressed
+            }
+            None => &events,
+        };
 
-        match bus.publish_batch(&events).await {
+        match bus.publish_batch(outgoing).await {
             Ok(()) => {
                 let refs: Vec<&SerializedEvent> = events.iter().collect();
-                marker.mark_success(&refs).await;
+                log.published(marker.source(), &refs);
+                Self::mark(marker, log, &refs, None).await;
             }
             Err(_batch_err) => {
-                for ev in &events {
-                    match bus.publish(ev).await {
+                for (ev, out) in events.iter().zip(outgoing) {
+                    match bus.publish(out).await {
                         Ok(()) => {
-                            marker.mark_success(&[ev]).await;
+                            log.published(marker.source(), &[ev]);
+                            Self::mark(marker, log, &[ev], None).await;
                         }
                         Err(e) => {
-                            let reason = e.to_string();
-                            marker.mark_failure(&[ev], &reason).await;
+                            log.publish_failed(marker.source(), ev, &e);
+                            Self::mark(marker, log, &[ev], Some(&e.to_string())).await;
                         }
                     }
                 }
@@ -203,23 +974,59 @@ impl EventEngine {
         }
     }
 
+    /// 标记发布结果（`failure` 为失败原因），标记写入失败时记录日志
+    async fn mark(
+        marker: &impl EventBatchMarker,
+        log: EngineLog<'_>,
+        events: &[&SerializedEvent],
+        failure: Option<&str>,
+    ) {
+        let (status, result) = match failure {
+            None => (marker.success_status(), marker.mark_success(events).await),
+            Some(reason) => ("failed", marker.mark_failure(events, reason).await),
+        };
+        match result {
+            Ok(()) => log.marked(marker.source(), status, events.len()),
+            Err(err) => log.mark_failed(marker.source(), status, events, &err),
+        }
+    }
+
     /// 带 ready 信号的订阅循环
     ///
-    /// 在完成订阅后发送 ready 信号，通知 deliver worker 可以开始投递事件
+    /// 在完成订阅与热启动追赶后发送 ready 信号，通知 deliver worker 可以开始投递事件
     async fn subscribe_loop_with_ready_signal(
         self: Arc<Self>,
         token: CancellationToken,
         ready_tx: tokio::sync::oneshot::Sender<()>,
+        batch_sinks: Vec<(HandledEventType, mpsc::Sender<SerializedEvent>)>,
     ) {
-        let mut stream = self.event_bus.subscribe().await;
-        let registry = self.registry.clone();
-        let concurrency = self.config.handler_concurrency;
+        let mut stream = match self
+            .event_bus
+            .subscribe_with(self.subscribe_options.clone())
+            .await
+        {
+            Ok(stream) => stream,
+            Err(err) => Box::pin(
+                stream::once(async move { Err(err) }).chain(self.event_bus.subscribe().await),
+            ),
+        };
         let reclaimer = self.event_reclaimer.clone();
+        let engine = self.clone();
 
-        // 订阅完成，发送 ready 信号
+        // 订阅完成后先追赶停机期间的事件，再发送 ready 信号
+        let mut replayed = engine.warm_start_catch_up(&batch_sinks).await;
         let _ = ready_tx.send(());
 
         loop {
+            if engine.pauses.is_paused(&EngineComponent::Subscribe)
+                && !engine
+                    .pauses
+                    .wait_resumed(&EngineComponent::Subscribe, &token)
+                    .await
+            {
+                break;
+            }
+
             tokio::select! {
                 _ = token.cancelled() => {
                     break;
@@ -227,30 +1034,35 @@ impl EventEngine {
                 maybe_event = stream.next() => {
                     match maybe_event {
                         Some(Ok(event)) => {
-                            let merged = registry.matching(event.event_type());
-                            if merged.is_empty() { continue; }
-                            let tasks = merged.into_iter();
-                            let reclaimer_for_stream = reclaimer.clone();
-
-                            stream::iter(tasks)
-                                .for_each_concurrent(Some(concurrency), move |h| {
-                                    let ev = event.clone();
-                                    let reclaimer = reclaimer_for_stream.clone();
-                                    async move {
-                                        if let Err(err) = h.handle(&ev).await {
-                                            let _ = reclaimer
-                                                .mark_handler_failed(h.handler_name(), &[&ev], &err.to_string())
-                                                .await;
-                                        }
+                            // 按 content_encoding 标记透明解压，处理器始终看到原始负载
+                            let event = match compression::decompress(&event) {
+                                Ok(Some(decoded)) => decoded,
+                                Ok(None) => event,
+                                Err(err) => {
+                                    let log = engine.log();
+                                    log.decode_failed(&event, &err);
+                                    if let Err(err) = reclaimer.mark_failed(&[&event], &err.to_string()).await {
+                                        log.mark_failed("subscribe", "failed", &[&event], &err);
                                     }
-                                })
-                                .await;
+                                    continue;
+                                }
+                            };
+                            // 热启动已追赶的事件不再分发
+                            if !replayed.is_empty() && replayed.remove(event.event_id()) {
+                                continue;
+                            }
+                            // 注入重复投递时同一事件整体再分发一次
+                            for _ in 0..engine.chaos.delivery_copies() {
+                                engine.dispatch_all(&event, &batch_sinks).await;
+                            }
+                            engine.record_delivered(&event).await;
                         }
                         None => {
                             break;
                         }
-                        Some(Err(_)) => {
+                        Some(Err(err)) => {
                             // 事件流错误，继续处理下一个
+                            engine.log().stream_error(&err);
                         }
                     }
                 }
@@ -264,19 +1076,124 @@ impl EventEngine {
 // 若已设置 `registry`，编译器会报错提示重复设置。
 // 正确的做法是：链式调用一次 `event_handlers(...)` 即可。
 
+/// 可热替换的处理器注册表：分发时读取当前快照，注册/注销与订阅调整以写时复制整体替换，
+/// 已取得快照的分发不受影响
 #[derive(Clone, Default)]
 pub(crate) struct HandlerRegistry {
-    by_type: HashMap<String, Vec<Arc<dyn EventHandler>>>,
-    all: Vec<Arc<dyn EventHandler>>,
+    current: Arc<RwLock<Arc<HandlerSet>>>,
 }
 
 impl HandlerRegistry {
     fn new(handlers: Vec<Arc<dyn EventHandler>>) -> Self {
+        Self {
+            current: Arc::new(RwLock::new(Arc::new(HandlerSet::new(
+                handlers,
+                HashMap::new(),
+            )))),
+        }
+    }
+
+    fn load(&self) -> Arc<HandlerSet> {
+        Arc::clone(&self.current.read().unwrap())
+    }
+
+    fn contains(&self, handler_name: &str) -> bool {
+        self.load()
+            .handlers
+            .iter()
+            .any(|h| h.handler_name() == handler_name)
+    }
+
+    fn register(&self, handler: Arc<dyn EventHandler>) -> DomainResult<()> {
+        let mut current = self.current.write().unwrap();
+        if current
+            .handlers
+            .iter()
+            .any(|h| h.handler_name() == handler.handler_name())
+        {
+            return Err(duplicate_handler(handler.handler_name()));
+        }
+
+        let mut handlers = current.handlers.clone();
+        handlers.push(handler);
+        *current = Arc::new(HandlerSet::new(handlers, current.overrides.clone()));
+        Ok(())
+    }
+
+    fn deregister(&self, handler_name: &str) -> bool {
+        let mut current = self.current.write().unwrap();
+        let handlers: Vec<_> = current
+            .handlers
+            .iter()
+            .filter(|h| h.handler_name() != handler_name)
+            .cloned()
+            .collect();
+        if handlers.len() == current.handlers.len() {
+            return false;
+        }
+
+        let mut overrides = current.overrides.clone();
+        overrides.remove(handler_name);
+        *current = Arc::new(HandlerSet::new(handlers, overrides));
+        true
+    }
+
+    /// 覆盖（`Some`）或恢复（`None`）处理器的订阅，返回此前生效的覆盖；处理器未注册时返回错误
+    fn set_subscription(
+        &self,
+        handler_name: &str,
+        subscription: Option<HandlerSubscription>,
+    ) -> DomainResult<Option<HandlerSubscription>> {
+        let mut current = self.current.write().unwrap();
+        if !current
+            .handlers
+            .iter()
+            .any(|h| h.handler_name() == handler_name)
+        {
+            return Err(DomainError::invalid_state(format!(
+                "event handler {handler_name} is not registered"
+            )));
+        }
+
+        let mut overrides = current.overrides.clone();
+        let previous = match subscription {
+            Some(subscription) => overrides.insert(handler_name.to_string(), subscription),
+            None => overrides.remove(handler_name),
+        };
+        *current = Arc::new(HandlerSet::new(current.handlers.clone(), overrides));
+        Ok(previous)
+    }
+}
+
+fn duplicate_handler(handler_name: &str) -> DomainError {
+    DomainError::invalid_state(format!(
+        "event handler {handler_name} is already registered"
+    ))
+}
+
+#[derive(Default)]
+struct HandlerSet {
+    handlers: Vec<Arc<dyn EventHandler>>,
+    /// 运行期覆盖的订阅（按处理器名）
+    overrides: HashMap<String, HandlerSubscription>,
+    by_type: HashMap<String, Vec<Arc<dyn EventHandler>>>,
+    all: Vec<Arc<dyn EventHandler>>,
+}
+
+impl HandlerSet {
+    fn new(
+        handlers: Vec<Arc<dyn EventHandler>>,
+        overrides: HashMap<String, HandlerSubscription>,
+    ) -> Self {
         let mut by_type: HashMap<String, Vec<Arc<dyn EventHandler>>> = HashMap::new();
         let mut all: Vec<Arc<dyn EventHandler>> = Vec::new();
 
-        for h in handlers {
-            match h.handled_event_type() {
+        for h in handlers.iter().cloned() {
+            let event_types = match overrides.get(h.handler_name()) {
+                Some(subscription) => subscription.event_types().clone(),
+                None => h.handled_event_type(),
+            };
+            match event_types {
                 HandledEventType::All => all.push(h),
                 HandledEventType::One(t) => {
                     by_type.entry(t).or_default().push(h);
@@ -289,23 +1206,37 @@ impl HandlerRegistry {
             }
         }
 
-        Self { by_type, all }
+        Self {
+            handlers,
+            overrides,
+            by_type,
+            all,
+        }
     }
 
-    fn matching(&self, event_type: &str) -> Vec<Arc<dyn EventHandler>> {
+    fn matching(&self, event: &SerializedEvent) -> Vec<Arc<dyn EventHandler>> {
         let mut merged: Vec<Arc<dyn EventHandler>> = Vec::new();
-        if let Some(list) = self.by_type.get(event_type) {
+        if let Some(list) = self.by_type.get(event.event_type()) {
             merged.extend(list.iter().cloned());
         }
         merged.extend(self.all.iter().cloned());
+        merged.retain(|h| {
+            self.overrides
+                .get(h.handler_name())
+                .is_none_or(|subscription| subscription.matches(event))
+        });
         merged
     }
 }
 
 #[async_trait]
 trait EventBatchMarker: Send + Sync {
-    async fn mark_success(&self, events: &[&SerializedEvent]);
-    async fn mark_failure(&self, events: &[&SerializedEvent], reason: &str);
+    /// 日志中的来源（`deliver`/`reclaim`）
+    fn source(&self) -> &'static str;
+    /// 成功标记的状态名
+    fn success_status(&self) -> &'static str;
+    async fn mark_success(&self, events: &[&SerializedEvent]) -> DomainResult<()>;
+    async fn mark_failure(&self, events: &[&SerializedEvent], reason: &str) -> DomainResult<()>;
 }
 
 #[derive(Clone)]
@@ -321,12 +1252,20 @@ impl DelivererMarker {
 
 #[async_trait]
 impl EventBatchMarker for DelivererMarker {
-    async fn mark_success(&self, events: &[&SerializedEvent]) {
-        let _ = self.inner.mark_delivered(events).await;
+    fn source(&self) -> &'static str {
+        "deliver"
+    }
+
+    fn success_status(&self) -> &'static str {
+        "delivered"
+    }
+
+    async fn mark_success(&self, events: &[&SerializedEvent]) -> DomainResult<()> {
+        self.inner.mark_delivered(events).await
     }
 
-    async fn mark_failure(&self, events: &[&SerializedEvent], reason: &str) {
-        let _ = self.inner.mark_failed(events, reason).await;
+    async fn mark_failure(&self, events: &[&SerializedEvent], reason: &str) -> DomainResult<()> {
+        self.inner.mark_failed(events, reason).await
     }
 }
 
@@ -343,40 +1282,248 @@ impl ReclaimerMarker {
 
 #[async_trait]
 impl EventBatchMarker for ReclaimerMarker {
-    async fn mark_success(&self, events: &[&SerializedEvent]) {
-        let _ = self.inner.mark_reclaimed(events).await;
+    fn source(&self) -> &'static str {
+        "reclaim"
+    }
+
+    fn success_status(&self) -> &'static str {
+        "reclaimed"
+    }
+
+    async fn mark_success(&self, events: &[&SerializedEvent]) -> DomainResult<()> {
+        self.inner.mark_reclaimed(events).await
     }
 
-    async fn mark_failure(&self, events: &[&SerializedEvent], reason: &str) {
-        let _ = self.inner.mark_failed(events, reason).await;
+    async fn mark_failure(&self, events: &[&SerializedEvent], reason: &str) -> DomainResult<()> {
+        self.inner.mark_failed(events, reason).await
     }
 }
 
-/// 事件引擎配置
+/// 投递记录在最近一次投递后保留的时长：失败转交回收器的事件在此期间重投时继续累计次数，
+/// 此后未再投递（回收器放弃、处理器已暂停/注销或订阅不再匹配）的记录被清理
+const DELIVERY_TRACKING_TTL: Duration = Duration::from_secs(24 * 60 * 60);
+
+/// 单个 (处理器, 事件) 的投递记录
 #[derive(Clone, Copy, Debug)]
-pub struct EventEngineConfig {
-    /// Outbox -> Bus 的推送间隔
-    pub deliver_interval: Duration,
-    /// 补偿投递的间隔
-    pub reclaim_interval: Duration,
-    /// 单事件的处理并发（同一事件广播给多个 handler）
-    pub handler_concurrency: usize,
+struct Delivery {
+    attempts: u32,
+    first_seen_at: DateTime<Utc>,
+    last_seen_at: DateTime<Utc>,
 }
 
-impl Default for EventEngineConfig {
+/// (处理器名, 事件 ID) -> 投递记录
+type Deliveries = HashMap<(String, String), Delivery>;
+
+/// 按 (处理器, 事件 ID) 记录投递次数与首次投递时间
+///
+/// 处理成功或转入死信后移除；失败转交回收器的记录保留以累计回收重投的次数，
+/// 超过 `DELIVERY_TRACKING_TTL` 未再投递时在后续投递中顺带清理（至多每个 TTL 扫描一次），
+/// 注销处理器时移除其全部记录。
+/// 仅在进程内有效：引擎重启后投递次数从 1 重新计数。
+struct DeliveryTracker {
+    inner: Mutex<Deliveries>,
+    ttl: chrono::Duration,
+    pruned_at: Mutex<Option<DateTime<Utc>>>,
+}
+
+impl Default for DeliveryTracker {
     fn default() -> Self {
+        Self::new(DELIVERY_TRACKING_TTL)
+    }
+}
+
+impl DeliveryTracker {
+    fn new(ttl: Duration) -> Self {
         Self {
-            deliver_interval: Duration::from_secs(10),
-            reclaim_interval: Duration::from_secs(60),
-            handler_concurrency: 8,
+            inner: Mutex::default(),
+            ttl: chrono::Duration::from_std(ttl).unwrap_or(chrono::Duration::MAX),
+            pruned_at: Mutex::default(),
         }
     }
-}
 
-/// 引擎运行句柄：用于优雅关闭与等待任务结束
-pub struct EngineHandle {
+    fn begin(
+        &self,
+        handler_name: &str,
+        event_id: &str,
+        now: DateTime<Utc>,
+    ) -> (u32, DateTime<Utc>) {
+        let mut inner = self.inner.lock().unwrap();
+        self.prune(&mut inner, now);
+        let entry = inner
+            .entry((handler_name.to_string(), event_id.to_string()))
+            .or_insert(Delivery {
+                attempts: 0,
+                first_seen_at: now,
+                last_seen_at: now,
+            });
+        entry.attempts += 1;
+        entry.last_seen_at = now;
+        (entry.attempts, entry.first_seen_at)
+    }
+
+    /// 清理超过 TTL 未再投递的记录
+    fn prune(&self, inner: &mut Deliveries, now: DateTime<Utc>) {
+        let mut pruned_at = self.pruned_at.lock().unwrap();
+        if pruned_at.is_some_and(|at| now - at < self.ttl) {
+            return;
+        }
+        *pruned_at = Some(now);
+        inner.retain(|_, delivery| now - delivery.last_seen_at < self.ttl);
+    }
+
+    fn current(&self, handler_name: &str, event_id: &str) -> Option<(u32, DateTime<Utc>)> {
+        self.inner
+            .lock()
+            .unwrap()
+            .get(&(handler_name.to_string(), event_id.to_string()))
+            .map(|delivery| (delivery.attempts, delivery.first_seen_at))
+    }
+
+    fn finish(&self, handler_name: &str, event_id: &str) {
+        self.inner
+            .lock()
+            .unwrap()
+            .remove(&(handler_name.to_string(), event_id.to_string()));
+    }
+
+    /// 移除处理器的全部记录
+    fn forget_handler(&self, handler_name: &str) {
+        self.inner
+            .lock()
+            .unwrap()
+            .retain(|(name, _), _| name != handler_name);
+    }
+}
+
+/// 事件引擎配置
+#[derive(Clone, Copy, Debug)]
+pub struct EventEngineConfig {
+    /// Outbox -> Bus 的推送间隔
+    pub deliver_interval: Duration,
+    /// 补偿投递的间隔
+    pub reclaim_interval: Duration,
+    /// 单事件的处理并发（同一事件广播给多个 handler）
+    pub handler_concurrency: usize,
+}
+
+impl Default for EventEngineConfig {
+    fn default() -> Self {
+        Self {
+            deliver_interval: Duration::from_secs(10),
+            reclaim_interval: Duration::from_secs(60),
+            handler_concurrency: 8,
+        }
+    }
+}
+
+/// 事件引擎的可选行为，经 `EventEngine::builder().tuning(..)` 设置
+///
+/// 字段私有，经 `with_*` 设置、经同名访问器读取；新增选项不会改变 `EventEngineConfig`。
+#[derive(Clone, Copy, Debug, Default)]
+pub struct EngineTuning {
+    compression: Option<PayloadCompression>,
+    log_level: EngineLogLevel,
+    retry: RetryPolicy,
+    max_deliveries: Option<u32>,
+    max_catch_up_window: Option<Duration>,
+    quarantine_after_panics: Option<u32>,
+}
+
+impl EngineTuning {
+    /// 发布到总线前的负载压缩（默认不压缩；订阅侧总是按标记解压）
+    pub fn with_compression(mut self, compression: PayloadCompression) -> Self {
+        self.compression = Some(compression);
+        self
+    }
+
+    /// 引擎结构化日志的详细程度（target `ddd::eventing`）
+    pub fn with_log_level(mut self, log_level: EngineLogLevel) -> Self {
+        self.log_level = log_level;
+        self
+    }
+
+    /// 处理器失败后转交回收器前的原地重试（默认不重试）
+    pub fn with_retry(mut self, retry: RetryPolicy) -> Self {
+        self.retry = retry;
+        self
+    }
+
+    /// 处理器对同一事件的累计投递次数上限（含原地重试与回收重投），达到后仍失败的事件
+    /// 转入死信队列（需配置 `dead_letters`）；默认不限制
+    pub fn with_max_deliveries(mut self, max_deliveries: u32) -> Self {
+        self.max_deliveries = Some(max_deliveries);
+        self
+    }
+
+    /// 热启动追赶窗口：发生时间早于该窗口的停机期间事件不追赶，留给回收器；默认追赶全部
+    pub fn with_max_catch_up_window(mut self, window: Duration) -> Self {
+        self.max_catch_up_window = Some(window);
+        self
+    }
+
+    /// 处理器连续 panic 达到该次数后被隔离（暂停），需经 `EngineHandle::resume` 恢复；默认不隔离
+    pub fn with_quarantine_after_panics(mut self, panics: u32) -> Self {
+        self.quarantine_after_panics = Some(panics);
+        self
+    }
+
+    pub fn compression(&self) -> Option<PayloadCompression> {
+        self.compression
+    }
+
+    pub fn log_level(&self) -> EngineLogLevel {
+        self.log_level
+    }
+
+    pub fn retry(&self) -> RetryPolicy {
+        self.retry
+    }
+
+    pub fn max_deliveries(&self) -> Option<u32> {
+        self.max_deliveries
+    }
+
+    pub fn max_catch_up_window(&self) -> Option<Duration> {
+        self.max_catch_up_window
+    }
+
+    pub fn quarantine_after_panics(&self) -> Option<u32> {
+        self.quarantine_after_panics
+    }
+}
+
+/// 引擎状态快照
+#[derive(Clone, Debug, Default)]
+pub struct EngineStatus {
+    pub handlers: Vec<HandlerStatus>,
+    /// 已暂停的组件（含处理器）
+    pub paused: Vec<EngineComponent>,
+    /// 热启动追赶进度（未配置热启动或无需追赶时为空）
+    pub warm_start: Option<WarmStartStatus>,
+}
+
+/// 单个处理器的状态
+#[derive(Clone, Debug)]
+pub struct HandlerStatus {
+    pub name: String,
+    /// 熔断状态（未包装熔断装饰器时为空）
+    pub circuit: Option<CircuitStatus>,
+    /// 是否已暂停
+    pub paused: bool,
+    /// 生效的订阅事件类型（运行期覆盖优先于处理器声明）
+    pub event_types: HandledEventType,
+    /// 生效的负载过滤描述
+    pub payload_filter: Option<String>,
+    /// 订阅是否经 `EngineHandle::update_subscription` 覆盖
+    pub subscription_overridden: bool,
+}
+
+/// 引擎运行句柄：用于优雅关闭、等待任务结束与按组件暂停/恢复
+pub struct EngineHandle {
     token: CancellationToken,
     tasks: Vec<JoinHandle<()>>,
+    pauses: Arc<PauseControl>,
+    engine: Arc<EventEngine>,
 }
 
 impl EngineHandle {
@@ -384,6 +1531,93 @@ impl EngineHandle {
         self.token.cancel();
     }
 
+    /// 是否已停止（已调用 `shutdown`，或 `EventEngine::start` 拒绝启动）
+    pub fn is_stopped(&self) -> bool {
+        self.token.is_cancelled()
+    }
+
+    /// 暂停组件（如批量导入期间暂停投递、暂停异常的投影处理器），返回此前是否在运行
+    pub fn pause(&self, component: EngineComponent) -> bool {
+        self.pauses.pause(component)
+    }
+
+    /// 恢复组件，返回此前是否已暂停
+    pub fn resume(&self, component: EngineComponent) -> bool {
+        self.pauses.resume(&component)
+    }
+
+    pub fn is_paused(&self, component: &EngineComponent) -> bool {
+        self.pauses.is_paused(component)
+    }
+
+    /// 向运行中的引擎注册处理器（如新增投影），无需重启；名称已存在时返回错误
+    pub fn register_handler(&self, handler: Arc<dyn EventHandler>) -> DomainResult<()> {
+        self.engine.register_handler(handler)
+    }
+
+    /// 注册处理器，并先从 `replay` 追赶 `from` 之后的历史事件，返回追赶处理的事件数
+    ///
+    /// 追赶失败的事件与实时分发一样转交回收器；与实时投递重叠的事件可能被处理两次。
+    pub async fn register_handler_from(
+        &self,
+        handler: Arc<dyn EventHandler>,
+        replay: &dyn ReplaySource,
+        from: SubscribeFrom,
+    ) -> DomainResult<usize> {
+        self.engine
+            .register_handler_with_catch_up(handler, replay, from)
+            .await
+    }
+
+    /// 注销处理器，返回是否存在；已开始的处理调用不受影响
+    pub fn deregister(&self, handler_name: &str) -> bool {
+        let removed = self.engine.registry.deregister(handler_name);
+        if removed {
+            self.engine.deliveries.forget_handler(handler_name);
+        }
+        removed
+    }
+
+    /// 覆盖运行中处理器的订阅（事件类型与负载过滤整体原子替换），返回此前的覆盖
+    ///
+    /// 对后续分发的事件生效；处理器未注册时返回错误。批量处理器的订阅在启动时固定，不可覆盖。
+    pub fn update_subscription(
+        &self,
+        handler_name: &str,
+        subscription: HandlerSubscription,
+    ) -> DomainResult<Option<HandlerSubscription>> {
+        self.engine
+            .registry
+            .set_subscription(handler_name, Some(subscription))
+    }
+
+    /// 撤销订阅覆盖，恢复处理器声明的事件类型，返回被撤销的覆盖
+    pub fn reset_subscription(
+        &self,
+        handler_name: &str,
+    ) -> DomainResult<Option<HandlerSubscription>> {
+        self.engine.registry.set_subscription(handler_name, None)
+    }
+
+    /// 列出死信，`handler_name` 为空时列出全部处理器的死信；未配置死信存储时为空
+    pub async fn dead_letters(&self, handler_name: Option<&str>) -> DomainResult<Vec<DeadLetter>> {
+        match &self.engine.dead_letters {
+            Some(store) => store.list(handler_name).await,
+            None => Ok(Vec::new()),
+        }
+    }
+
+    /// 取出死信并重新交给原处理器，返回死信是否存在
+    ///
+    /// 重放按常规流程处理：失败时重试、转交回收器，投递次数耗尽后再次进入死信队列。
+    pub async fn replay_dead_letter(
+        &self,
+        handler_name: &str,
+        event_id: &str,
+    ) -> DomainResult<bool> {
+        self.engine.replay_dead_letter(handler_name, event_id).await
+    }
+
     pub async fn join(mut self) {
         let tasks = std::mem::take(&mut self.tasks);
 
@@ -404,6 +1638,8 @@ mod tests {
     use super::*;
     use crate::domain_event::EventContext;
     use crate::error::{DomainError, DomainResult};
+    use crate::eventing::InMemoryDeadLetterStore;
+    use crate::eventing::engine_log::LOG_TARGET;
     use async_trait::async_trait;
     use chrono::Utc;
     use futures_core::stream::BoxStream;
@@ -451,6 +1687,14 @@ mod tests {
         }
     }
 
+    #[async_trait]
+    impl EventOutbox for Outbox {
+        async fn enqueue(&self, events: &[SerializedEvent]) -> DomainResult<()> {
+            self.inner.lock().unwrap().extend_from_slice(events);
+            Ok(())
+        }
+    }
+
     #[derive(Clone, Default)]
     struct SpyDeliverer {
         outbox: Outbox,
@@ -481,6 +1725,7 @@ mod tests {
         handler_failed: Arc<AtomicUsize>,
         reclaimed: Arc<AtomicUsize>,
         stored: Arc<Mutex<Vec<SerializedEvent>>>,
+        reasons: Arc<Mutex<Vec<String>>>,
     }
     #[async_trait]
     impl EventReclaimer for SpyReclaimer {
@@ -502,10 +1747,11 @@ mod tests {
             &self,
             _handler_name: &str,
             events: &[&SerializedEvent],
-            _reason: &str,
+            reason: &str,
         ) -> DomainResult<()> {
             self.handler_failed
                 .fetch_add(events.len(), Ordering::Relaxed);
+            self.reasons.lock().unwrap().push(reason.to_string());
             for e in events {
                 self.stored.lock().unwrap().push((*e).clone());
             }
@@ -522,7 +1768,11 @@ mod tests {
     }
     #[async_trait]
     impl EventHandler for SpyHandler {
-        async fn handle(&self, event: &SerializedEvent) -> anyhow::Result<()> {
+        async fn handle(
+            &self,
+            event: &SerializedEvent,
+            _ctx: &HandlerContext,
+        ) -> anyhow::Result<()> {
             if let Some(bad) = self.fail_on
                 && event.event_type() == bad
             {
@@ -628,4 +1878,1321 @@ mod tests {
         // 至少一个处理器成功消费
         assert!(*ok.handled.lock().unwrap() >= 2);
     }
+
+    #[tokio::test]
+    async fn status_reports_circuit_state_per_handler() {
+        use crate::eventing::circuit_breaker::{
+            CircuitBreakerConfig, CircuitBreakerHandler, CircuitState,
+        };
+
+        let plain = Arc::new(SpyHandler {
+            name: "plain",
+            types: HandledEventType::All,
+            fail_on: None,
+            handled: Arc::new(Mutex::new(0)),
+        });
+        let guarded = Arc::new(CircuitBreakerHandler::new(
+            Arc::new(SpyHandler {
+                name: "guarded",
+                types: HandledEventType::All,
+                fail_on: Some("Boom"),
+                handled: Arc::new(Mutex::new(0)),
+            }),
+            CircuitBreakerConfig {
+                failure_threshold: 1,
+                ..Default::default()
+            },
+        ));
+
+        let engine = EventEngine::builder()
+            .event_bus(Arc::new(InMemoryBus::new(8)))
+            .event_deliverer(Arc::new(SpyDeliverer::default()))
+            .event_reclaimer(Arc::new(SpyReclaimer::default()))
+            .event_handlers(vec![plain, guarded.clone()])
+            .build();
+
+        let _ = guarded
+            .handle(&mk_event("e1", "Boom"), &HandlerContext::default())
+            .await;
+
+        let status = engine.status();
+        assert_eq!(status.handlers.len(), 2);
+        assert_eq!(status.handlers[0].name, "plain");
+        assert!(status.handlers[0].circuit.is_none());
+        assert_eq!(status.handlers[1].name, "guarded");
+        assert_eq!(
+            status.handlers[1].circuit.map(|c| c.state),
+            Some(CircuitState::Open)
+        );
+    }
+
+    /// 记录每个批次的事件 ID，批次含 `FailMe` 时失败
+    #[derive(Clone, Default)]
+    struct BatchSpy {
+        batches: Arc<Mutex<Vec<Vec<String>>>>,
+    }
+
+    #[async_trait]
+    impl BatchEventHandler for BatchSpy {
+        fn handler_name(&self) -> &str {
+            "batch"
+        }
+
+        fn handled_event_type(&self) -> HandledEventType {
+            HandledEventType::Many(vec!["Ok".into(), "FailMe".into()])
+        }
+
+        fn batch_config(&self) -> crate::eventing::BatchConfig {
+            crate::eventing::BatchConfig {
+                max_batch_size: 3,
+                max_wait: Duration::from_millis(50),
+            }
+        }
+
+        async fn handle_batch(
+            &self,
+            events: &[SerializedEvent],
+            _ctx: &HandlerContext,
+        ) -> anyhow::Result<()> {
+            self.batches
+                .lock()
+                .unwrap()
+                .push(events.iter().map(|e| e.event_id().to_string()).collect());
+            if events.iter().any(|e| e.event_type() == "FailMe") {
+                anyhow::bail!("batch rejected");
+            }
+            Ok(())
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn batch_handlers_receive_ordered_batches_by_size_and_window() {
+        let outbox = Outbox::default();
+        let deliverer = Arc::new(SpyDeliverer {
+            outbox: outbox.clone(),
+            ..Default::default()
+        });
+        let reclaimer = Arc::new(SpyReclaimer::default());
+        let batch = Arc::new(BatchSpy::default());
+        let single = Arc::new(SpyHandler {
+            name: "single",
+            types: HandledEventType::All,
+            fail_on: None,
+            handled: Arc::new(Mutex::new(0)),
+        });
+
+        let engine = Arc::new(
+            EventEngine::builder()
+                .event_bus(Arc::new(InMemoryBus::new(64)))
+                .event_deliverer(deliverer)
+                .event_reclaimer(reclaimer.clone())
+                .event_handlers(vec![single.clone()])
+                .batch_handlers(vec![batch.clone()])
+                .config(EventEngineConfig {
+                    deliver_interval: Duration::from_millis(20),
+                    reclaim_interval: Duration::from_secs(60),
+                    ..Default::default()
+                })
+                .build(),
+        );
+        assert_eq!(engine.status().handlers[1].name, "batch");
+
+        for i in 1..=7 {
+            outbox.push(mk_event(&format!("e{i}"), "Ok"));
+        }
+        outbox.push(mk_event("skip", "Other"));
+        let handle = Arc::clone(&engine).start();
+        let _ = tokio::time::timeout(Duration::from_secs(2), async {
+            while batch.batches.lock().unwrap().len() < 3 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await;
+
+        // 失败的批次整批转交回收器
+        outbox.push(mk_event("e8", "FailMe"));
+        let _ = tokio::time::timeout(Duration::from_secs(2), async {
+            while *single.handled.lock().unwrap() < 9 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await;
+        handle.shutdown();
+        handle.join().await;
+
+        let batches = batch.batches.lock().unwrap().clone();
+        assert_eq!(
+            batches,
+            vec![
+                vec!["e1", "e2", "e3"],
+                vec!["e4", "e5", "e6"],
+                vec!["e7"],
+                vec!["e8"],
+            ]
+        );
+        assert_eq!(reclaimer.handler_failed.load(Ordering::Relaxed), 1);
+    }
+
+    /// 首次投递失败、重投成功，记录每次收到的上下文
+    #[derive(Clone, Default)]
+    struct RedeliveryHandler {
+        seen: Arc<Mutex<Vec<HandlerContext>>>,
+    }
+
+    #[async_trait]
+    impl EventHandler for RedeliveryHandler {
+        async fn handle(
+            &self,
+            _event: &SerializedEvent,
+            ctx: &HandlerContext,
+        ) -> anyhow::Result<()> {
+            self.seen.lock().unwrap().push(ctx.clone());
+            if !ctx.is_redelivery() {
+                anyhow::bail!("first attempt fails");
+            }
+            Ok(())
+        }
+
+        fn handled_event_type(&self) -> HandledEventType {
+            HandledEventType::All
+        }
+
+        fn handler_name(&self) -> &str {
+            "redelivery"
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn handler_context_counts_redeliveries() {
+        let outbox = Outbox::default();
+        let deliverer = Arc::new(SpyDeliverer {
+            outbox: outbox.clone(),
+            ..Default::default()
+        });
+        let handler = Arc::new(RedeliveryHandler::default());
+
+        let engine = Arc::new(
+            EventEngine::builder()
+                .event_bus(Arc::new(InMemoryBus::new(64)))
+                .event_deliverer(deliverer)
+                .event_reclaimer(Arc::new(SpyReclaimer::default()))
+                .event_handlers(vec![handler.clone()])
+                .name("orders")
+                .config(EventEngineConfig {
+                    deliver_interval: Duration::from_millis(20),
+                    reclaim_interval: Duration::from_millis(50),
+                    ..Default::default()
+                })
+                .build(),
+        );
+
+        outbox.push(mk_event("e1", "Ok"));
+        let handle = Arc::clone(&engine).start();
+        let _ = tokio::time::timeout(Duration::from_secs(2), async {
+            while handler.seen.lock().unwrap().len() < 2 {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await;
+        handle.shutdown();
+        handle.join().await;
+
+        let seen = handler.seen.lock().unwrap().clone();
+        assert_eq!(seen.len(), 2);
+        assert_eq!((seen[0].attempt(), seen[1].attempt()), (1, 2));
+        assert!(seen.iter().all(|ctx| ctx.engine_name() == "orders"));
+        assert_eq!(seen[0].first_seen_at(), seen[1].first_seen_at());
+        // 处理成功后清除投递记录
+        assert!(engine.deliveries.inner.lock().unwrap().is_empty());
+    }
+
+    #[test]
+    fn abandoned_delivery_records_expire() {
+        let tracker = DeliveryTracker::new(Duration::from_secs(60));
+        let t0 = Utc::now();
+        let at = |secs| t0 + chrono::Duration::seconds(secs);
+
+        // 失败后转交回收器的记录保留，重投时继续累计
+        assert_eq!(tracker.begin("audit", "e1", t0), (1, t0));
+        assert_eq!(tracker.begin("audit", "e2", at(10)), (1, at(10)));
+        assert_eq!(tracker.begin("audit", "e1", at(50)), (2, t0));
+
+        // e2 超过 TTL 未再投递（如回收器放弃或处理器已暂停）
+        tracker.begin("orders", "e3", at(80));
+        assert!(tracker.current("audit", "e2").is_none());
+        assert_eq!(tracker.current("audit", "e1"), Some((2, t0)));
+
+        tracker.forget_handler("audit");
+        assert!(tracker.current("audit", "e1").is_none());
+        assert_eq!(tracker.inner.lock().unwrap().len(), 1);
+    }
+
+    /// 前 `failures` 次尝试失败，记录每次尝试的序号
+    struct FlakyHandler {
+        failures: u32,
+        attempts: Mutex<Vec<u32>>,
+    }
+
+    #[async_trait]
+    impl EventHandler for FlakyHandler {
+        async fn handle(
+            &self,
+            _event: &SerializedEvent,
+            ctx: &HandlerContext,
+        ) -> anyhow::Result<()> {
+            self.attempts.lock().unwrap().push(ctx.attempt());
+            if ctx.attempt() <= self.failures {
+                anyhow::bail!("attempt {} fails", ctx.attempt());
+            }
+            Ok(())
+        }
+
+        fn handled_event_type(&self) -> HandledEventType {
+            HandledEventType::All
+        }
+
+        fn handler_name(&self) -> &str {
+            "flaky"
+        }
+    }
+
+    async fn run_with_retry(failures: u32, max_attempts: u32) -> (Vec<u32>, usize) {
+        let outbox = Outbox::default();
+        let reclaimer = Arc::new(SpyReclaimer::default());
+        let handler = Arc::new(FlakyHandler {
+            failures,
+            attempts: Mutex::new(Vec::new()),
+        });
+        let engine = Arc::new(
+            EventEngine::builder()
+                .event_bus(Arc::new(InMemoryBus::new(64)))
+                .event_deliverer(Arc::new(SpyDeliverer {
+                    outbox: outbox.clone(),
+                    ..Default::default()
+                }))
+                .event_reclaimer(reclaimer.clone())
+                .event_handlers(vec![handler.clone()])
+                .config(EventEngineConfig {
+                    deliver_interval: Duration::from_millis(20),
+                    reclaim_interval: Duration::from_secs(60),
+                    ..Default::default()
+                })
+                .tuning(EngineTuning::default().with_retry(RetryPolicy {
+                    max_attempts,
+                    backoff: Duration::from_millis(5),
+                    max_backoff: Duration::from_millis(20),
+                    jitter: 0.5,
+                }))
+                .build(),
+        );
+
+        outbox.push(mk_event("e1", "Ok"));
+        let handle = Arc::clone(&engine).start();
+        let _ = tokio::time::timeout(Duration::from_secs(2), async {
+            loop {
+                let attempts = handler.attempts.lock().unwrap().len() as u32;
+                if attempts > failures || reclaimer.handler_failed.load(Ordering::Relaxed) > 0 {
+                    break;
+                }
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await;
+        handle.shutdown();
+        handle.join().await;
+
+        let attempts = handler.attempts.lock().unwrap().clone();
+        (attempts, reclaimer.handler_failed.load(Ordering::Relaxed))
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn retries_failing_handlers_before_reclaiming() {
+        // 重试内恢复：不转交回收器，每次重试都是新的投递
+        let (attempts, reclaimed) = run_with_retry(2, 3).await;
+        assert_eq!(attempts, [1, 2, 3]);
+        assert_eq!(reclaimed, 0);
+
+        // 重试耗尽：转交回收器
+        let (attempts, reclaimed) = run_with_retry(5, 2).await;
+        assert_eq!(attempts, [1, 2]);
+        assert_eq!(reclaimed, 1);
+    }
+
+    /// 处理 `Boom` 事件时 panic
+    struct PanickingHandler {
+        handled: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl EventHandler for PanickingHandler {
+        async fn handle(
+            &self,
+            event: &SerializedEvent,
+            _ctx: &HandlerContext,
+        ) -> anyhow::Result<()> {
+            if event.event_type() == "Boom" {
+                panic!("unexpected payload in {}", event.event_id());
+            }
+            self.handled.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+
+        fn handled_event_type(&self) -> HandledEventType {
+            HandledEventType::All
+        }
+
+        fn handler_name(&self) -> &str {
+            "panicky"
+        }
+    }
+
+    #[derive(Default)]
+    struct RecordedMetrics(Mutex<Vec<String>>);
+
+    impl HandlerMetrics for RecordedMetrics {
+        fn increment(&self, name: &str, _value: u64) {
+            self.0.lock().unwrap().push(name.to_string());
+        }
+
+        fn observe(&self, _name: &str, _value: f64) {}
+    }
+
+    #[tokio::test]
+    async fn contains_handler_panics_and_quarantines_repeat_offenders() {
+        let reclaimer = Arc::new(SpyReclaimer::default());
+        let metrics = Arc::new(RecordedMetrics::default());
+        let handler = PanickingHandler {
+            handled: AtomicUsize::new(0),
+        };
+        let engine = EventEngine::builder()
+            .event_bus(Arc::new(InMemoryBus::new(8)))
+            .event_deliverer(Arc::new(SpyDeliverer::default()))
+            .event_reclaimer(reclaimer.clone())
+            .event_handlers(vec![])
+            .metrics(metrics.clone())
+            .tuning(
+                EngineTuning::default()
+                    .with_retry(RetryPolicy {
+                        max_attempts: 3,
+                        ..Default::default()
+                    })
+                    .with_quarantine_after_panics(2),
+            )
+            .build();
+
+        // panic 不原地重试，以 `panic` 原因转交回收器；成功处理清零连续计数
+        engine.dispatch(&handler, &mk_event("e1", "Boom")).await;
+        engine.dispatch(&handler, &mk_event("e2", "Ok")).await;
+        engine.dispatch(&handler, &mk_event("e3", "Boom")).await;
+        assert!(!engine.pauses.is_handler_paused("panicky"));
+
+        // 连续第二次 panic：隔离处理器，后续事件不再交给它
+        engine.dispatch(&handler, &mk_event("e4", "Boom")).await;
+        assert!(engine.pauses.is_handler_paused("panicky"));
+        engine.dispatch(&handler, &mk_event("e5", "Ok")).await;
+
+        assert_eq!(handler.handled.load(Ordering::Relaxed), 1);
+        assert_eq!(
+            *reclaimer.reasons.lock().unwrap(),
+            [
+                HANDLER_PANIC_REASON,
+                HANDLER_PANIC_REASON,
+                HANDLER_PANIC_REASON,
+                HANDLER_PAUSED_REASON
+            ]
+        );
+        assert_eq!(*metrics.0.lock().unwrap(), ["handler_panics.panicky"; 3]);
+    }
+
+    /// 健康前持续失败，记录每次尝试的序号
+    #[derive(Default)]
+    struct RecoveringHandler {
+        healthy: std::sync::atomic::AtomicBool,
+        attempts: Mutex<Vec<u32>>,
+    }
+
+    #[async_trait]
+    impl EventHandler for RecoveringHandler {
+        async fn handle(
+            &self,
+            _event: &SerializedEvent,
+            ctx: &HandlerContext,
+        ) -> anyhow::Result<()> {
+            self.attempts.lock().unwrap().push(ctx.attempt());
+            if !self.healthy.load(Ordering::Relaxed) {
+                anyhow::bail!("downstream unavailable");
+            }
+            Ok(())
+        }
+
+        fn handled_event_type(&self) -> HandledEventType {
+            HandledEventType::All
+        }
+
+        fn handler_name(&self) -> &str {
+            "recovering"
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn dead_letters_events_exceeding_delivery_budget_and_replays_them() {
+        let outbox = Outbox::default();
+        let reclaimer = Arc::new(SpyReclaimer::default());
+        let dead_letters = Arc::new(InMemoryDeadLetterStore::new());
+        let handler = Arc::new(RecoveringHandler::default());
+        let engine = Arc::new(
+            EventEngine::builder()
+                .event_bus(Arc::new(InMemoryBus::new(64)))
+                .event_deliverer(Arc::new(SpyDeliverer {
+                    outbox: outbox.clone(),
+                    ..Default::default()
+                }))
+                .event_reclaimer(reclaimer.clone())
+                .dead_letters(dead_letters.clone())
+                .event_handlers(vec![handler.clone()])
+                .config(EventEngineConfig {
+                    deliver_interval: Duration::from_millis(10),
+                    reclaim_interval: Duration::from_millis(20),
+                    ..Default::default()
+                })
+                .tuning(
+                    EngineTuning::default()
+                        .with_retry(RetryPolicy {
+                            max_attempts: 2,
+                            backoff: Duration::from_millis(1),
+                            ..Default::default()
+                        })
+                        .with_max_deliveries(3),
+                )
+                .build(),
+        );
+
+        outbox.push(mk_event("e1", "Ok"));
+        let handle = Arc::clone(&engine).start();
+        let _ = tokio::time::timeout(Duration::from_secs(2), async {
+            while dead_letters.is_empty() {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await;
+        tokio::time::sleep(Duration::from_millis(60)).await;
+
+        // 首次投递与一次重试后转交回收器，回收重投的第 3 次投递耗尽预算，不再原地重试
+        assert_eq!(*handler.attempts.lock().unwrap(), [1, 2, 3]);
+        assert_eq!(reclaimer.handler_failed.load(Ordering::Relaxed), 1);
+        assert_eq!(reclaimer.reclaimed.load(Ordering::Relaxed), 2);
+        let letters = handle.dead_letters(Some("recovering")).await.unwrap();
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].event.event_id(), "e1");
+        assert_eq!(letters[0].attempts, 3);
+        assert_eq!(letters[0].reason, "downstream unavailable");
+        assert!(engine.deliveries.inner.lock().unwrap().is_empty());
+
+        // 下游恢复后重放：取出死信并交给原处理器
+        handler.healthy.store(true, Ordering::Relaxed);
+        assert!(handle.replay_dead_letter("recovering", "e1").await.unwrap());
+        assert_eq!(*handler.attempts.lock().unwrap(), [1, 2, 3, 1]);
+        assert!(dead_letters.is_empty());
+        assert!(!handle.replay_dead_letter("recovering", "e1").await.unwrap());
+        assert!(handle.replay_dead_letter("missing", "e1").await.is_err());
+
+        handle.shutdown();
+        handle.join().await;
+    }
+
+    #[tokio::test]
+    async fn causation_depth_violations_are_dead_lettered_without_retry() {
+        use crate::domain_event::EventContext;
+        use crate::eventing::CausationGuardHandler;
+
+        let reclaimer = Arc::new(SpyReclaimer::default());
+        let dead_letters = Arc::new(InMemoryDeadLetterStore::new());
+        let inner = Arc::new(RecoveringHandler::default());
+        let guard = CausationGuardHandler::new(inner.clone(), 1);
+        let engine = EventEngine::builder()
+            .event_bus(Arc::new(InMemoryBus::new(8)))
+            .event_deliverer(Arc::new(SpyDeliverer::default()))
+            .event_reclaimer(reclaimer.clone())
+            .dead_letters(dead_letters.clone())
+            .event_handlers(vec![])
+            .tuning(EngineTuning::default().with_retry(RetryPolicy {
+                max_attempts: 3,
+                backoff: Duration::from_millis(1),
+                ..Default::default()
+            }))
+            .build();
+
+        let parent = mk_event("e0", "Ok");
+        let looping = SerializedEvent::builder()
+            .event_id("e1".to_string())
+            .event_type("Ok".to_string())
+            .event_version(1)
+            .aggregate_id("a-1".to_string())
+            .aggregate_type("demo".to_string())
+            .aggregate_version(1)
+            .occurred_at(Utc::now())
+            .payload(serde_json::json!({}))
+            .context(serde_json::to_value(EventContext::caused_by(&parent)).unwrap())
+            .build();
+
+        // 未达到 max_deliveries 也不原地重试，首次命中即转入死信
+        engine.dispatch(&guard, &looping).await;
+        assert!(inner.attempts.lock().unwrap().is_empty());
+        assert_eq!(reclaimer.handler_failed.load(Ordering::Relaxed), 0);
+        let letters = dead_letters.list(Some("recovering")).await.unwrap();
+        assert_eq!(letters.len(), 1);
+        assert_eq!(letters[0].attempts, 1);
+        assert!(letters[0].reason.starts_with("causation_depth_exceeded"));
+        assert!(engine.deliveries.inner.lock().unwrap().is_empty());
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn warm_start_replays_missed_events_before_delivering() {
+        use crate::eventing::{CheckpointStore, InMemoryCheckpointStore, WarmStart};
+        use crate::persist::EventPosition;
+
+        let at = |id: &str, sequence: i64, age_minutes: i64| {
+            let mut event = serde_json::to_value(mk_event(id, "Ok")).unwrap();
+            event["occurred_at"] =
+                serde_json::json!(Utc::now() - chrono::Duration::minutes(age_minutes));
+            serde_json::from_value::<SerializedEvent>(event)
+                .unwrap()
+                .with_position(EventPosition::global(sequence))
+        };
+        // 停机前已分发到位点 2；位点 3 超出追赶窗口
+        let history = History(vec![
+            at("e2", 2, 5),
+            at("e3", 3, 120),
+            at("e4", 4, 5),
+            at("e5", 5, 1),
+        ]);
+        let checkpoints = Arc::new(InMemoryCheckpointStore::new());
+        checkpoints.save("warm_start.default", 2).await.unwrap();
+
+        let bus = Arc::new(InMemoryBus::new(256));
+        let outbox = Outbox::default();
+        let deliverer = Arc::new(SpyDeliverer {
+            outbox: outbox.clone(),
+            ..Default::default()
+        });
+        let handler = Arc::new(SpyHandler {
+            name: "spy",
+            types: HandledEventType::One("Ok".into()),
+            fail_on: None,
+            handled: Arc::new(Mutex::new(0)),
+        });
+        // 追赶完成前不投递：Outbox 中停机前未投递的 e5 与追赶重叠，只处理一次
+        outbox.push(at("e5", 5, 1));
+        let engine = Arc::new(
+            EventEngine::builder()
+                .event_bus(bus)
+                .event_deliverer(deliverer.clone())
+                .event_reclaimer(Arc::new(SpyReclaimer::default()))
+                .event_handlers(vec![handler.clone()])
+                .warm_start(WarmStart::new(Arc::new(history), checkpoints.clone()))
+                .config(EventEngineConfig {
+                    deliver_interval: Duration::from_millis(10),
+                    ..Default::default()
+                })
+                .tuning(EngineTuning::default().with_max_catch_up_window(Duration::from_secs(3600)))
+                .build(),
+        );
+        let handle = engine.clone().start();
+
+        outbox.push(at("e6", 6, 0));
+        tokio::time::timeout(Duration::from_secs(2), async {
+            while deliverer.delivered.load(Ordering::Relaxed) < 2 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await
+        .unwrap();
+        tokio::time::sleep(Duration::from_millis(30)).await;
+
+        assert_eq!(*handler.handled.lock().unwrap(), 3);
+        assert_eq!(
+            engine.status().warm_start,
+            Some(WarmStartStatus {
+                from_sequence: 2,
+                head_sequence: 5,
+                total: 2,
+                replayed: 2,
+                skipped: 1,
+                completed: true,
+            })
+        );
+        let checkpoint = checkpoints.load("warm_start.default").await.unwrap();
+        assert_eq!(checkpoint.map(|c| c.sequence), Some(6));
+
+        handle.shutdown();
+        handle.join().await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn pause_and_resume_components_independently() {
+        let outbox = Outbox::default();
+        let deliverer = Arc::new(SpyDeliverer {
+            outbox: outbox.clone(),
+            ..Default::default()
+        });
+        let reclaimer = Arc::new(SpyReclaimer::default());
+        let spy = |name| {
+            Arc::new(SpyHandler {
+                name,
+                types: HandledEventType::All,
+                fail_on: None,
+                handled: Arc::new(Mutex::new(0)),
+            })
+        };
+        let live = spy("live");
+        let projection = spy("projection");
+
+        let engine = Arc::new(
+            EventEngine::builder()
+                .event_bus(Arc::new(InMemoryBus::new(64)))
+                .event_deliverer(deliverer.clone())
+                .event_reclaimer(reclaimer.clone())
+                .event_handlers(vec![live.clone(), projection.clone()])
+                .config(EventEngineConfig {
+                    deliver_interval: Duration::from_millis(10),
+                    reclaim_interval: Duration::from_millis(20),
+                    ..Default::default()
+                })
+                .build(),
+        );
+        let handle = Arc::clone(&engine).start();
+
+        let wait_until = |cond: Box<dyn Fn() -> bool + Send>| async move {
+            let _ = tokio::time::timeout(Duration::from_secs(2), async {
+                while !cond() {
+                    tokio::time::sleep(Duration::from_millis(5)).await;
+                }
+            })
+            .await;
+        };
+
+        // 暂停投递：事件留在 outbox
+        assert!(handle.pause(EngineComponent::Deliver));
+        outbox.push(mk_event("e1", "Ok"));
+        tokio::time::sleep(Duration::from_millis(60)).await;
+        assert_eq!(deliverer.delivered.load(Ordering::Relaxed), 0);
+        assert_eq!(engine.status().paused, vec![EngineComponent::Deliver]);
+
+        // 暂停单个处理器、恢复投递：其他处理器照常消费，暂停的处理器事件转交回收器
+        handle.pause(EngineComponent::handler("projection"));
+        handle.pause(EngineComponent::Reclaim);
+        assert!(handle.resume(EngineComponent::Deliver));
+        let live_handled = live.handled.clone();
+        wait_until(Box::new(move || *live_handled.lock().unwrap() == 1)).await;
+        let failed = reclaimer.handler_failed.clone();
+        wait_until(Box::new(move || failed.load(Ordering::Relaxed) == 1)).await;
+        assert_eq!(*projection.handled.lock().unwrap(), 0);
+
+        let status = engine.status();
+        assert!(status.handlers[1].paused);
+        assert!(!status.handlers[0].paused);
+
+        // 恢复处理器与补偿：经补偿重投后补齐
+        handle.resume(EngineComponent::handler("projection"));
+        handle.resume(EngineComponent::Reclaim);
+        let projection_handled = projection.handled.clone();
+        wait_until(Box::new(move || *projection_handled.lock().unwrap() == 1)).await;
+        assert_eq!(*projection.handled.lock().unwrap(), 1);
+        assert!(engine.status().paused.is_empty());
+
+        // 暂停订阅后关闭引擎不会阻塞
+        handle.pause(EngineComponent::Subscribe);
+        handle.shutdown();
+        handle.join().await;
+    }
+
+    /// 租户作用域中间件：写入注解、改写事件副本，并跳过内部事件
+    struct TenantScope {
+        log: Arc<Mutex<Vec<String>>>,
+    }
+
+    #[async_trait]
+    impl HandlerMiddleware for TenantScope {
+        async fn before(
+            &self,
+            handler: &str,
+            event: &mut SerializedEvent,
+            ctx: &mut HandlerContext,
+        ) -> anyhow::Result<middleware::Flow> {
+            if event.event_type() == "Internal" {
+                return Ok(middleware::Flow::ShortCircuit);
+            }
+            if event.event_type() == "Forbidden" {
+                anyhow::bail!("tenant access denied");
+            }
+            ctx.annotate("tenant", "acme");
+            *event = event
+                .clone()
+                .upcasted(serde_json::json!({"scoped": true}), 1);
+            self.log.lock().unwrap().push(format!("before:{handler}"));
+            Ok(middleware::Flow::Continue)
+        }
+
+        async fn after(
+            &self,
+            handler: &str,
+            event: &SerializedEvent,
+            _ctx: &HandlerContext,
+            result: &anyhow::Result<()>,
+        ) {
+            self.log.lock().unwrap().push(format!(
+                "after:{handler}:{}:{}",
+                event.event_id(),
+                result.is_ok()
+            ));
+        }
+    }
+
+    #[derive(Clone, Default)]
+    struct ScopedHandler {
+        seen: Arc<Mutex<Vec<serde_json::Value>>>,
+    }
+
+    #[async_trait]
+    impl EventHandler for ScopedHandler {
+        async fn handle(
+            &self,
+            event: &SerializedEvent,
+            ctx: &HandlerContext,
+        ) -> anyhow::Result<()> {
+            self.seen.lock().unwrap().push(serde_json::json!({
+                "payload": event.payload(),
+                "tenant": ctx.annotation("tenant"),
+            }));
+            Ok(())
+        }
+
+        fn handled_event_type(&self) -> HandledEventType {
+            HandledEventType::All
+        }
+
+        fn handler_name(&self) -> &str {
+            "scoped"
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn middlewares_wrap_handler_calls() {
+        let outbox = Outbox::default();
+        let deliverer = Arc::new(SpyDeliverer {
+            outbox: outbox.clone(),
+            ..Default::default()
+        });
+        let reclaimer = Arc::new(SpyReclaimer::default());
+        let handler = Arc::new(ScopedHandler::default());
+        let log = Arc::new(Mutex::new(Vec::new()));
+
+        let engine = Arc::new(
+            EventEngine::builder()
+                .event_bus(Arc::new(InMemoryBus::new(64)))
+                .event_deliverer(deliverer)
+                .event_reclaimer(reclaimer.clone())
+                .event_handlers(vec![handler.clone()])
+                .middlewares(vec![Arc::new(TenantScope { log: log.clone() })])
+                .config(EventEngineConfig {
+                    deliver_interval: Duration::from_millis(10),
+                    reclaim_interval: Duration::from_secs(60),
+                    ..Default::default()
+                })
+                .build(),
+        );
+        let handle = Arc::clone(&engine).start();
+
+        outbox.push(mk_event("e1", "Ok"));
+        outbox.push(mk_event("e2", "Internal"));
+        outbox.push(mk_event("e3", "Forbidden"));
+        let _ = tokio::time::timeout(Duration::from_secs(2), async {
+            while log.lock().unwrap().len() < 4 {
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await;
+        handle.shutdown();
+        handle.join().await;
+
+        let seen = handler.seen.lock().unwrap().clone();
+        assert_eq!(
+            seen,
+            vec![serde_json::json!({"payload": {"scoped": true}, "tenant": "acme"})]
+        );
+        assert_eq!(
+            *log.lock().unwrap(),
+            vec![
+                "before:scoped",
+                "after:scoped:e1:true",
+                "after:scoped:e2:true",
+                "after:scoped:e3:false",
+            ]
+        );
+        // 中间件拒绝的事件以原始形态转交回收器
+        let stored = reclaimer.stored.lock().unwrap().clone();
+        assert_eq!(stored.len(), 1);
+        assert_eq!(stored[0].payload(), &serde_json::json!({"id": "e3"}));
+    }
+
+    struct History(Vec<SerializedEvent>);
+
+    #[async_trait]
+    impl ReplaySource for History {
+        async fn replay(&self, _from: SubscribeFrom) -> DomainResult<Vec<SerializedEvent>> {
+            Ok(self.0.clone())
+        }
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn registers_and_deregisters_handlers_at_runtime() {
+        let bus = Arc::new(InMemoryBus::new(256));
+        let outbox = Outbox::default();
+        let deliverer = Arc::new(SpyDeliverer {
+            outbox: outbox.clone(),
+            ..Default::default()
+        });
+        let spy = |name| {
+            Arc::new(SpyHandler {
+                name,
+                types: HandledEventType::One("Ok".into()),
+                fail_on: None,
+                handled: Arc::new(Mutex::new(0)),
+            })
+        };
+        let initial = spy("initial");
+        let added = spy("added");
+
+        let engine = Arc::new(
+            EventEngine::builder()
+                .event_bus(bus)
+                .event_deliverer(deliverer.clone())
+                .event_reclaimer(Arc::new(SpyReclaimer::default()))
+                .event_handlers(vec![initial.clone()])
+                .config(EventEngineConfig {
+                    deliver_interval: Duration::from_millis(10),
+                    ..Default::default()
+                })
+                .build(),
+        );
+        let handle = engine.clone().start();
+
+        // 新处理器先追赶历史事件（仅匹配类型），再接收实时事件
+        let history = History(vec![mk_event("h1", "Ok"), mk_event("h2", "Other")]);
+        let caught_up = handle
+            .register_handler_from(added.clone(), &history, SubscribeFrom::Sequence(1))
+            .await
+            .unwrap();
+        assert_eq!(caught_up, 1);
+        assert!(handle.register_handler(spy("added")).is_err());
+        assert_eq!(engine.status().handlers.len(), 2);
+
+        let delivered = |n| {
+            let deliverer = deliverer.clone();
+            async move {
+                tokio::time::timeout(Duration::from_secs(2), async {
+                    while deliverer.delivered.load(Ordering::Relaxed) < n {
+                        tokio::time::sleep(Duration::from_millis(5)).await;
+                    }
+                })
+                .await
+                .unwrap();
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        };
+
+        outbox.push(mk_event("e1", "Ok"));
+        delivered(1).await;
+        assert_eq!(*initial.handled.lock().unwrap(), 1);
+        assert_eq!(*added.handled.lock().unwrap(), 2);
+
+        assert!(handle.deregister("initial"));
+        assert!(!handle.deregister("initial"));
+        outbox.push(mk_event("e2", "Ok"));
+        delivered(2).await;
+        assert_eq!(*initial.handled.lock().unwrap(), 1);
+        assert_eq!(*added.handled.lock().unwrap(), 3);
+
+        handle.shutdown();
+        handle.join().await;
+    }
+
+    #[tokio::test(flavor = "multi_thread")]
+    async fn updates_handler_subscriptions_at_runtime() {
+        let bus = Arc::new(InMemoryBus::new(256));
+        let outbox = Outbox::default();
+        let deliverer = Arc::new(SpyDeliverer {
+            outbox: outbox.clone(),
+            ..Default::default()
+        });
+        let audit = Arc::new(SpyHandler {
+            name: "audit",
+            types: HandledEventType::One("Ok".into()),
+            fail_on: None,
+            handled: Arc::new(Mutex::new(0)),
+        });
+
+        let engine = Arc::new(
+            EventEngine::builder()
+                .event_bus(bus)
+                .event_deliverer(deliverer.clone())
+                .event_reclaimer(Arc::new(SpyReclaimer::default()))
+                .event_handlers(vec![audit.clone()])
+                .config(EventEngineConfig {
+                    deliver_interval: Duration::from_millis(10),
+                    ..Default::default()
+                })
+                .build(),
+        );
+        let handle = engine.clone().start();
+        let deliver = |events: Vec<SerializedEvent>, total: usize| {
+            let deliverer = deliverer.clone();
+            let outbox = outbox.clone();
+            async move {
+                for event in events {
+                    outbox.push(event);
+                }
+                tokio::time::timeout(Duration::from_secs(2), async {
+                    while deliverer.delivered.load(Ordering::Relaxed) < total {
+                        tokio::time::sleep(Duration::from_millis(5)).await;
+                    }
+                })
+                .await
+                .unwrap();
+                tokio::time::sleep(Duration::from_millis(20)).await;
+            }
+        };
+
+        deliver(vec![mk_event("e1", "Ok"), mk_event("e2", "Other")], 2).await;
+        assert_eq!(*audit.handled.lock().unwrap(), 1);
+
+        // 事故期间放宽为全部事件类型，并按负载过滤
+        let previous = handle
+            .update_subscription(
+                "audit",
+                HandlerSubscription::new(HandledEventType::All)
+                    .with_filter("incident events", |e| e.event_id().starts_with("inc")),
+            )
+            .unwrap();
+        assert!(previous.is_none());
+        let status = engine.status();
+        assert_eq!(status.handlers[0].event_types, HandledEventType::All);
+        assert_eq!(
+            status.handlers[0].payload_filter.as_deref(),
+            Some("incident events")
+        );
+        assert!(status.handlers[0].subscription_overridden);
+
+        deliver(
+            vec![
+                mk_event("inc-1", "Other"),
+                mk_event("inc-2", "Ok"),
+                mk_event("e3", "Ok"),
+            ],
+            5,
+        )
+        .await;
+        assert_eq!(*audit.handled.lock().unwrap(), 3);
+
+        // 恢复声明的订阅
+        let reset = handle.reset_subscription("audit").unwrap();
+        assert_eq!(
+            reset.and_then(|s| s.filter_description().map(ToString::to_string)),
+            Some("incident events".to_string())
+        );
+        assert!(!engine.status().handlers[0].subscription_overridden);
+        deliver(vec![mk_event("inc-3", "Other"), mk_event("e4", "Ok")], 7).await;
+        assert_eq!(*audit.handled.lock().unwrap(), 4);
+
+        assert!(
+            handle
+                .update_subscription("missing", HandlerSubscription::new(HandledEventType::All))
+                .is_err()
+        );
+
+        handle.shutdown();
+        handle.join().await;
+    }
+
+    /// 将 `OrderPlaced` 翻译为 `InvoiceRequested` 的处理器
+    struct Translator;
+    #[async_trait]
+    impl EventHandler for Translator {
+        async fn handle(
+            &self,
+            event: &SerializedEvent,
+            ctx: &HandlerContext,
+        ) -> anyhow::Result<()> {
+            ctx.emit(mk_event(
+                &format!("{}-invoice", event.event_id()),
+                "InvoiceRequested",
+            ));
+            Ok(())
+        }
+        fn handled_event_type(&self) -> HandledEventType {
+            HandledEventType::One("OrderPlaced".into())
+        }
+        fn handler_name(&self) -> &str {
+            "translator"
+        }
+    }
+
+    #[tokio::test]
+    async fn emitted_events_are_written_to_outbox_with_causation() {
+        let outbox = Outbox::default();
+        let reclaimer = Arc::new(SpyReclaimer::default());
+        let engine = EventEngine::builder()
+            .event_bus(Arc::new(InMemoryBus::new(8)))
+            .event_deliverer(Arc::new(SpyDeliverer::default()))
+            .event_reclaimer(reclaimer.clone())
+            .event_outbox(Arc::new(outbox.clone()))
+            .event_handlers(vec![])
+            .build();
+
+        let source = mk_event("e1", "OrderPlaced");
+        engine.dispatch(&Translator, &source).await;
+
+        let written = outbox.drain();
+        assert_eq!(written.len(), 1);
+        let derived = &written[0];
+        assert_eq!(derived.event_id(), "e1-invoice");
+        assert_eq!(derived.correlation_id(), Some("cor-e1"));
+        assert_eq!(derived.causation_id(), Some("e1"));
+        assert_eq!(derived.context()["causation_id"], "e1");
+        assert_eq!(derived.causation_depth(), 1);
+        assert_eq!(derived.actor_id(), Some("u-1"));
+        assert_eq!(reclaimer.handler_failed.load(Ordering::Relaxed), 0);
+
+        // 未配置 Outbox：派生事件无处写入，源事件转交回收器
+        let engine = EventEngine::builder()
+            .event_bus(Arc::new(InMemoryBus::new(8)))
+            .event_deliverer(Arc::new(SpyDeliverer::default()))
+            .event_reclaimer(reclaimer.clone())
+            .event_handlers(vec![])
+            .build();
+        engine.dispatch(&Translator, &source).await;
+        assert_eq!(reclaimer.handler_failed.load(Ordering::Relaxed), 1);
+    }
+
+    #[tokio::test]
+    async fn refuses_handlers_requiring_stronger_ordering_than_bus() {
+        use crate::error::ErrorCode;
+        use crate::eventing::{InMemoryCheckpointStore, InMemoryEventBus, ProjectionRunner};
+
+        let projection = |name: &'static str| -> Arc<dyn EventHandler> {
+            let spy = Arc::new(SpyHandler {
+                name,
+                types: HandledEventType::All,
+                fail_on: None,
+                handled: Arc::new(Mutex::new(0)),
+            });
+            Arc::new(
+                ProjectionRunner::new(spy, Arc::new(InMemoryCheckpointStore::new()))
+                    .with_required_ordering(OrderingGuarantee::PerKey),
+            )
+        };
+        let engine = |bus: Arc<dyn EventBus>, handlers| {
+            Arc::new(
+                EventEngine::builder()
+                    .event_bus(bus)
+                    .event_deliverer(Arc::new(SpyDeliverer::default()))
+                    .event_reclaimer(Arc::new(SpyReclaimer::default()))
+                    .event_handlers(handlers)
+                    .build(),
+            )
+        };
+
+        // 测试总线未声明顺序保证（Unordered）
+        let unordered = engine(Arc::new(InMemoryBus::new(8)), vec![projection("orders")]);
+        let err = unordered.check_ordering().unwrap_err();
+        assert_eq!(err.code(), "ORDERING_UNSATISFIED");
+        assert!(err.to_string().contains("orders requires PerKey"));
+
+        // 启动时返回错误而不是 panic
+        let err = unordered.clone().try_start().err().unwrap();
+        assert_eq!(err.code(), "ORDERING_UNSATISFIED");
+        assert!(err.to_string().contains("orders requires PerKey"));
+
+        // `start` 不 panic，返回已停止的句柄
+        let handle = unordered.clone().start();
+        assert!(handle.is_stopped());
+        handle.join().await;
+
+        // 运行期注册同样校验
+        let handle = engine(Arc::new(InMemoryBus::new(8)), vec![]).start();
+        let err = handle.register_handler(projection("late")).unwrap_err();
+        assert_eq!(err.code(), "ORDERING_UNSATISFIED");
+        handle.shutdown();
+        handle.join().await;
+
+        let ordered = engine(
+            Arc::new(InMemoryEventBus::new(8)),
+            vec![projection("orders")],
+        );
+        assert!(ordered.check_ordering().is_ok());
+    }
+
+    /// (target, 字段)
+    type LogRecord = (String, HashMap<String, String>);
+
+    /// 收集日志事件的 `tracing` 订阅者
+    #[derive(Clone, Default)]
+    struct LogCapture {
+        records: Arc<Mutex<Vec<LogRecord>>>,
+    }
+
+    impl LogCapture {
+        fn find(&self, message: &str, field: &str, value: &str) -> Option<HashMap<String, String>> {
+            self.records
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(_, fields)| fields)
+                .find(|f| {
+                    f.get("message").map(String::as_str) == Some(message)
+                        && f.get(field).map(String::as_str) == Some(value)
+                })
+                .cloned()
+        }
+    }
+
+    struct FieldMap<'a>(&'a mut HashMap<String, String>);
+
+    impl tracing::field::Visit for FieldMap<'_> {
+        fn record_str(&mut self, field: &tracing::field::Field, value: &str) {
+            self.0.insert(field.name().to_string(), value.to_string());
+        }
+        fn record_debug(&mut self, field: &tracing::field::Field, value: &dyn std::fmt::Debug) {
+            self.0
+                .insert(field.name().to_string(), format!("{value:?}"));
+        }
+    }
+
+    impl tracing::Subscriber for LogCapture {
+        fn enabled(&self, _metadata: &tracing::Metadata<'_>) -> bool {
+            true
+        }
+        fn new_span(&self, _span: &tracing::span::Attributes<'_>) -> tracing::span::Id {
+            tracing::span::Id::from_u64(1)
+        }
+        fn record(&self, _span: &tracing::span::Id, _values: &tracing::span::Record<'_>) {}
+        fn record_follows_from(&self, _span: &tracing::span::Id, _follows: &tracing::span::Id) {}
+        fn event(&self, event: &tracing::Event<'_>) {
+            let mut fields = HashMap::new();
+            event.record(&mut FieldMap(&mut fields));
+            self.records
+                .lock()
+                .unwrap()
+                .push((event.metadata().target().to_string(), fields));
+        }
+        fn enter(&self, _span: &tracing::span::Id) {}
+        fn exit(&self, _span: &tracing::span::Id) {}
+    }
+
+    async fn run_logged(log_level: EngineLogLevel) -> LogCapture {
+        let capture = LogCapture::default();
+        let _guard = tracing::subscriber::set_default(capture.clone());
+
+        let outbox = Outbox::default();
+        let deliverer = Arc::new(SpyDeliverer {
+            outbox: outbox.clone(),
+            ..Default::default()
+        });
+        let reclaimer = Arc::new(SpyReclaimer::default());
+        let handler = |name, fail_on| {
+            Arc::new(SpyHandler {
+                name,
+                types: HandledEventType::All,
+                fail_on,
+                handled: Arc::new(Mutex::new(0)),
+            }) as Arc<dyn EventHandler>
+        };
+        let engine = Arc::new(
+            EventEngine::builder()
+                .event_bus(Arc::new(InMemoryBus::new(16)))
+                .event_deliverer(deliverer.clone())
+                .event_reclaimer(reclaimer.clone())
+                .event_handlers(vec![handler("ok", None), handler("fail", Some("FailMe"))])
+                .name("orders")
+                .config(EventEngineConfig {
+                    deliver_interval: Duration::from_millis(20),
+                    reclaim_interval: Duration::from_secs(60),
+                    ..Default::default()
+                })
+                .tuning(EngineTuning::default().with_log_level(log_level))
+                .build(),
+        );
+        outbox.push(mk_event("e1", "Ok"));
+        outbox.push(mk_event("e2", "FailMe"));
+
+        let handle = engine.start();
+        let _ = tokio::time::timeout(Duration::from_secs(2), async {
+            while reclaimer.handler_failed.load(Ordering::Relaxed) == 0
+                || deliverer.delivered.load(Ordering::Relaxed) < 2
+            {
+                tokio::time::sleep(Duration::from_millis(10)).await;
+            }
+        })
+        .await;
+        handle.shutdown();
+        handle.join().await;
+        capture
+    }
+
+    #[tokio::test]
+    async fn logs_event_lifecycle_breadcrumbs() {
+        let capture = run_logged(EngineLogLevel::Events).await;
+
+        let fetched = capture.find("events fetched", "source", "deliver").unwrap();
+        assert_eq!(fetched["count"], "2");
+        assert_eq!(fetched["engine"], "orders");
+        let published = capture.find("event published", "event_id", "e1").unwrap();
+        assert_eq!(published["aggregate_id"], "agg-1");
+        assert_eq!(
+            capture
+                .find("events marked", "status", "delivered")
+                .unwrap()["count"],
+            "2"
+        );
+        assert_eq!(
+            capture.find("event dispatched", "event_id", "e2").unwrap()["event_type"],
+            "FailMe"
+        );
+        assert_eq!(
+            capture.find("event handled", "event_id", "e1").unwrap()["handler"],
+            "ok"
+        );
+        let failed = capture
+            .find("event handler failed", "handler", "fail")
+            .unwrap();
+        assert_eq!(failed["event_id"], "e2");
+        assert!(failed["reason"].contains("simulated handler failure"));
+        assert!(
+            capture
+                .records
+                .lock()
+                .unwrap()
+                .iter()
+                .all(|(target, _)| target == LOG_TARGET)
+        );
+    }
+
+    #[tokio::test]
+    async fn log_level_limits_verbosity() {
+        let capture = run_logged(EngineLogLevel::Errors).await;
+        assert!(
+            capture
+                .find("event handler failed", "event_id", "e2")
+                .is_some()
+        );
+        assert!(capture.find("event published", "event_id", "e1").is_none());
+        assert!(
+            capture
+                .find("events fetched", "source", "deliver")
+                .is_none()
+        );
+
+        let capture = run_logged(EngineLogLevel::Off).await;
+        assert!(capture.records.lock().unwrap().is_empty());
+    }
 }
@@ -0,0 +1,345 @@
+//! 事件引擎结构化日志
+//!
+//! 引擎在事件生命周期的各环节以 `tracing` 输出结构化日志（target 为 `ddd::eventing`），
+//! 字段统一为 `engine`、`source`（`deliver`/`reclaim`）、`handler`、`event_id`、`event_type`、
+//! `aggregate_type`、`aggregate_id`、`reason`，便于按事件 ID 串联“拉取 → 发布 → 标记 → 分发 → 处理”的轨迹：
+//! - `Errors`（默认）：拉取/发布/标记失败、处理器失败（含原地重试与转入死信）、事件流与解压错误；
+//! - `Batches`：另输出每批拉取、发布与标记的数量，以及热启动追赶的起止；
+//! - `Events`：另输出每个事件的发布、分发与处理成功。
+//!
+//! 详细程度经 `EngineTuning::with_log_level` 配置，`Off` 关闭全部引擎日志；
+//! 最终是否输出仍由应用安装的 `tracing` 订阅者过滤。
+//!
+use super::warm_start::WarmStartStatus;
+use crate::persist::SerializedEvent;
+use serde::Deserialize;
+use std::fmt::Display;
+use std::time::Duration;
+
+/// 引擎日志的 target
+pub const LOG_TARGET: &str = "ddd::eventing";
+
+/// 引擎日志详细程度（逐级包含）
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EngineLogLevel {
+    /// 不输出引擎日志
+    Off,
+    /// 仅失败
+    #[default]
+    Errors,
+    /// 失败与批次摘要
+    Batches,
+    /// 失败、批次摘要与逐事件轨迹
+    Events,
+}
+
+/// 绑定引擎名称与详细程度的日志器
+#[derive(Clone, Copy)]
+pub(crate) struct EngineLog<'a> {
+    level: EngineLogLevel,
+    engine: &'a str,
+}
+
+impl<'a> EngineLog<'a> {
+    pub(crate) fn new(level: EngineLogLevel, engine: &'a str) -> Self {
+        Self { level, engine }
+    }
+
+    fn enabled(&self, level: EngineLogLevel) -> bool {
+        self.level >= level
+    }
+
+    /// 启动被拒绝（如顺序保证不满足），引擎未启动任何 worker
+    pub(crate) fn start_refused(&self, err: &dyn Display) {
+        if self.enabled(EngineLogLevel::Errors) {
+            tracing::error!(target: LOG_TARGET, engine = self.engine, reason = %err, "engine start refused");
+        }
+    }
+
+    pub(crate) fn fetched(&self, source: &str, count: usize) {
+        if count > 0 && self.enabled(EngineLogLevel::Batches) {
+            tracing::debug!(target: LOG_TARGET, engine = self.engine, source, count, "events fetched");
+        }
+    }
+
+    pub(crate) fn fetch_failed(&self, source: &str, err: &dyn Display) {
+        if self.enabled(EngineLogLevel::Errors) {
+            tracing::warn!(target: LOG_TARGET, engine = self.engine, source, reason = %err, "fetch events failed");
+        }
+    }
+
+    pub(crate) fn published(&self, source: &str, events: &[&SerializedEvent]) {
+        if self.enabled(EngineLogLevel::Batches) {
+            tracing::debug!(target: LOG_TARGET, engine = self.engine, source, count = events.len(), "events published");
+        }
+        if self.enabled(EngineLogLevel::Events) {
+            for event in events {
+                tracing::debug!(
+                    target: LOG_TARGET,
+                    engine = self.engine,
+                    source,
+                    event_id = event.event_id(),
+                    event_type = event.event_type(),
+                    aggregate_type = event.aggregate_type(),
+                    aggregate_id = event.aggregate_id(),
+                    "event published"
+                );
+            }
+        }
+    }
+
+    pub(crate) fn publish_failed(&self, source: &str, event: &SerializedEvent, err: &dyn Display) {
+        if self.enabled(EngineLogLevel::Errors) {
+            tracing::warn!(
+                target: LOG_TARGET,
+                engine = self.engine,
+                source,
+                event_id = event.event_id(),
+                event_type = event.event_type(),
+                aggregate_type = event.aggregate_type(),
+                aggregate_id = event.aggregate_id(),
+                reason = %err,
+                "publish event failed"
+            );
+        }
+    }
+
+    /// 标记成功；`status` 为标记结果（如 `delivered`、`reclaimed`、`failed`）
+    pub(crate) fn marked(&self, source: &str, status: &str, count: usize) {
+        if self.enabled(EngineLogLevel::Batches) {
+            tracing::debug!(target: LOG_TARGET, engine = self.engine, source, status, count, "events marked");
+        }
+    }
+
+    /// 标记写入失败：事件状态未能落盘，可能被重复投递或滞留
+    pub(crate) fn mark_failed(
+        &self,
+        source: &str,
+        status: &str,
+        events: &[&SerializedEvent],
+        err: &dyn Display,
+    ) {
+        if !self.enabled(EngineLogLevel::Errors) {
+            return;
+        }
+        for event in events {
+            tracing::error!(
+                target: LOG_TARGET,
+                engine = self.engine,
+                source,
+                status,
+                event_id = event.event_id(),
+                event_type = event.event_type(),
+                aggregate_type = event.aggregate_type(),
+                aggregate_id = event.aggregate_id(),
+                reason = %err,
+                "mark events failed"
+            );
+        }
+    }
+
+    pub(crate) fn dispatched(&self, handler: &str, event: &SerializedEvent) {
+        if self.enabled(EngineLogLevel::Events) {
+            tracing::debug!(
+                target: LOG_TARGET,
+                engine = self.engine,
+                handler,
+                event_id = event.event_id(),
+                event_type = event.event_type(),
+                aggregate_type = event.aggregate_type(),
+                aggregate_id = event.aggregate_id(),
+                "event dispatched"
+            );
+        }
+    }
+
+    pub(crate) fn handled(&self, handler: &str, events: &[&SerializedEvent]) {
+        if !self.enabled(EngineLogLevel::Events) {
+            return;
+        }
+        for event in events {
+            tracing::debug!(
+                target: LOG_TARGET,
+                engine = self.engine,
+                handler,
+                event_id = event.event_id(),
+                event_type = event.event_type(),
+                aggregate_type = event.aggregate_type(),
+                aggregate_id = event.aggregate_id(),
+                "event handled"
+            );
+        }
+    }
+
+    /// 处理失败（含暂停、注入失败），事件转交回收器
+    pub(crate) fn handler_failed(
+        &self,
+        handler: &str,
+        events: &[&SerializedEvent],
+        reason: &dyn Display,
+    ) {
+        if !self.enabled(EngineLogLevel::Errors) {
+            return;
+        }
+        for event in events {
+            tracing::warn!(
+                target: LOG_TARGET,
+                engine = self.engine,
+                handler,
+                event_id = event.event_id(),
+                event_type = event.event_type(),
+                aggregate_type = event.aggregate_type(),
+                aggregate_id = event.aggregate_id(),
+                reason = %reason,
+                "event handler failed"
+            );
+        }
+    }
+
+    /// 处理器 panic，事件随后转交回收器
+    pub(crate) fn handler_panicked(
+        &self,
+        handler: &str,
+        events: &[&SerializedEvent],
+        message: &str,
+        consecutive: u32,
+    ) {
+        if !self.enabled(EngineLogLevel::Errors) {
+            return;
+        }
+        for event in events {
+            tracing::error!(
+                target: LOG_TARGET,
+                engine = self.engine,
+                handler,
+                event_id = event.event_id(),
+                event_type = event.event_type(),
+                aggregate_type = event.aggregate_type(),
+                aggregate_id = event.aggregate_id(),
+                consecutive,
+                panic = message,
+                "event handler panicked"
+            );
+        }
+    }
+
+    /// 处理器连续 panic 达到阈值，已隔离（暂停）
+    pub(crate) fn handler_quarantined(&self, handler: &str, panics: u32) {
+        if self.enabled(EngineLogLevel::Errors) {
+            tracing::error!(
+                target: LOG_TARGET,
+                engine = self.engine,
+                handler,
+                panics,
+                "event handler quarantined after repeated panics"
+            );
+        }
+    }
+
+    /// 处理失败，按重试策略退避后原地重试
+    pub(crate) fn handler_retrying(
+        &self,
+        handler: &str,
+        event: &SerializedEvent,
+        attempt: u32,
+        delay: Duration,
+        reason: &dyn Display,
+    ) {
+        if self.enabled(EngineLogLevel::Errors) {
+            tracing::warn!(
+                target: LOG_TARGET,
+                engine = self.engine,
+                handler,
+                event_id = event.event_id(),
+                event_type = event.event_type(),
+                aggregate_type = event.aggregate_type(),
+                aggregate_id = event.aggregate_id(),
+                attempt,
+                delay_ms = delay.as_millis() as u64,
+                reason = %reason,
+                "event handler failed, retrying"
+            );
+        }
+    }
+
+    /// 投递次数耗尽，事件转入死信队列
+    pub(crate) fn dead_lettered(
+        &self,
+        handler: &str,
+        events: &[&SerializedEvent],
+        attempts: u32,
+        reason: &dyn Display,
+    ) {
+        if !self.enabled(EngineLogLevel::Errors) {
+            return;
+        }
+        for event in events {
+            tracing::error!(
+                target: LOG_TARGET,
+                engine = self.engine,
+                handler,
+                event_id = event.event_id(),
+                event_type = event.event_type(),
+                aggregate_type = event.aggregate_type(),
+                aggregate_id = event.aggregate_id(),
+                attempts,
+                reason = %reason,
+                "event dead-lettered"
+            );
+        }
+    }
+
+    pub(crate) fn warm_start_began(&self, status: &WarmStartStatus) {
+        if self.enabled(EngineLogLevel::Batches) {
+            tracing::info!(
+                target: LOG_TARGET,
+                engine = self.engine,
+                from_sequence = status.from_sequence,
+                head_sequence = status.head_sequence,
+                total = status.total,
+                skipped = status.skipped,
+                "warm start catch-up began"
+            );
+        }
+    }
+
+    pub(crate) fn warm_start_completed(&self, status: &WarmStartStatus) {
+        if self.enabled(EngineLogLevel::Batches) {
+            tracing::info!(
+                target: LOG_TARGET,
+                engine = self.engine,
+                replayed = status.replayed,
+                skipped = status.skipped,
+                "warm start catch-up completed"
+            );
+        }
+    }
+
+    pub(crate) fn warm_start_failed(&self, err: &dyn Display) {
+        if self.enabled(EngineLogLevel::Errors) {
+            tracing::warn!(target: LOG_TARGET, engine = self.engine, reason = %err, "warm start failed");
+        }
+    }
+
+    pub(crate) fn decode_failed(&self, event: &SerializedEvent, err: &dyn Display) {
+        if self.enabled(EngineLogLevel::Errors) {
+            tracing::warn!(
+                target: LOG_TARGET,
+                engine = self.engine,
+                event_id = event.event_id(),
+                event_type = event.event_type(),
+                aggregate_type = event.aggregate_type(),
+                aggregate_id = event.aggregate_id(),
+                reason = %err,
+                "decode event payload failed"
+            );
+        }
+    }
+
+    pub(crate) fn stream_error(&self, err: &dyn Display) {
+        if self.enabled(EngineLogLevel::Errors) {
+            tracing::warn!(target: LOG_TARGET, engine = self.engine, reason = %err, "event stream error");
+        }
+    }
+}
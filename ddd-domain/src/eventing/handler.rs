@@ -1,17 +1,108 @@
 //! 事件处理器（EventHandler）
 //!
-//! 定义消费某类/多类/全部事件的处理逻辑与元信息（名称、订阅类型）。
+//! 定义消费某类/多类/全部事件的处理逻辑与元信息（名称、订阅类型），
+//! 以及按批次消费事件的 `BatchEventHandler`（如在单个数据库事务中应用一批投影更新）。
+//! `HandlerSubscription` 描述运行期覆盖的订阅（事件类型 + 负载过滤），经 `EngineHandle::update_subscription` 生效。
 //!
+use super::bus::OrderingGuarantee;
+use super::circuit_breaker::CircuitStatus;
+use super::handler_context::HandlerContext;
+use crate::domain_event::EventDescriptor;
 use crate::persist::SerializedEvent;
 use async_trait::async_trait;
+use std::fmt;
+use std::sync::Arc;
+use std::time::Duration;
 
-#[derive(Clone, Debug)]
+#[derive(Clone, Debug, PartialEq, Eq)]
 pub enum HandledEventType {
     One(String),
     Many(Vec<String>),
     All,
 }
 
+impl HandledEventType {
+    /// 按事件描述订阅（如 `HandledEventType::of(&[OrderEvent::CREATED, OrderEvent::PAID])`）
+    pub fn of(descriptors: &[EventDescriptor]) -> Self {
+        HandledEventType::Many(
+            descriptors
+                .iter()
+                .map(|d| d.event_type.to_string())
+                .collect(),
+        )
+    }
+
+    /// 是否处理给定类型的事件
+    pub fn matches(&self, event_type: &str) -> bool {
+        match self {
+            HandledEventType::All => true,
+            HandledEventType::One(t) => t == event_type,
+            HandledEventType::Many(ts) => ts.iter().any(|t| t == event_type),
+        }
+    }
+}
+
+impl From<EventDescriptor> for HandledEventType {
+    fn from(descriptor: EventDescriptor) -> Self {
+        HandledEventType::One(descriptor.event_type.to_string())
+    }
+}
+
+type PayloadFilter = Arc<dyn Fn(&SerializedEvent) -> bool + Send + Sync>;
+
+/// 运行期订阅配置：覆盖处理器声明的事件类型，并可附加负载过滤
+///
+/// 如事故期间将审计处理器临时放宽为 `HandledEventType::All`，或只处理某租户的事件。
+#[derive(Clone)]
+pub struct HandlerSubscription {
+    event_types: HandledEventType,
+    filter: Option<(String, PayloadFilter)>,
+}
+
+impl HandlerSubscription {
+    pub fn new(event_types: HandledEventType) -> Self {
+        Self {
+            event_types,
+            filter: None,
+        }
+    }
+
+    /// 附加负载过滤：仅处理 `filter` 返回真的事件；`description` 用于状态展示
+    pub fn with_filter<F>(mut self, description: impl Into<String>, filter: F) -> Self
+    where
+        F: Fn(&SerializedEvent) -> bool + Send + Sync + 'static,
+    {
+        self.filter = Some((description.into(), Arc::new(filter)));
+        self
+    }
+
+    pub fn event_types(&self) -> &HandledEventType {
+        &self.event_types
+    }
+
+    /// 负载过滤的描述
+    pub fn filter_description(&self) -> Option<&str> {
+        self.filter
+            .as_ref()
+            .map(|(description, _)| description.as_str())
+    }
+
+    /// 事件是否同时满足事件类型与负载过滤
+    pub fn matches(&self, event: &SerializedEvent) -> bool {
+        self.event_types.matches(event.event_type())
+            && self.filter.as_ref().is_none_or(|(_, filter)| filter(event))
+    }
+}
+
+impl fmt::Debug for HandlerSubscription {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("HandlerSubscription")
+            .field("event_types", &self.event_types)
+            .field("filter", &self.filter_description())
+            .finish()
+    }
+}
+
 /// 事件处理器：处理某一类型的事件
 #[async_trait]
 pub trait EventHandler: Send + Sync {
@@ -19,6 +110,54 @@ pub trait EventHandler: Send + Sync {
     fn handler_name(&self) -> &str;
     /// 返回该处理器支持的事件类型
     fn handled_event_type(&self) -> HandledEventType;
-    /// 处理事件
-    async fn handle(&self, event: &SerializedEvent) -> anyhow::Result<()>;
+    /// 处理事件；`ctx` 携带投递元信息（第几次投递等）与作用域服务
+    async fn handle(&self, event: &SerializedEvent, ctx: &HandlerContext) -> anyhow::Result<()>;
+    /// 所需的事件顺序保证（如依赖事件顺序的投影），引擎启动时校验总线能否满足
+    fn required_ordering(&self) -> OrderingGuarantee {
+        OrderingGuarantee::Unordered
+    }
+    /// 熔断状态（仅熔断装饰器返回，用于 `EngineStatus`）
+    fn circuit_status(&self) -> Option<CircuitStatus> {
+        None
+    }
+}
+
+/// 批次累积配置：达到 `max_batch_size` 或自批次首个事件起经过 `max_wait` 即交付
+#[derive(Clone, Copy, Debug)]
+pub struct BatchConfig {
+    pub max_batch_size: usize,
+    pub max_wait: Duration,
+}
+
+impl Default for BatchConfig {
+    fn default() -> Self {
+        Self {
+            max_batch_size: 100,
+            max_wait: Duration::from_millis(500),
+        }
+    }
+}
+
+/// 批量事件处理器：引擎按订阅顺序累积匹配的事件，整批交付
+///
+/// 批次内事件保持到达顺序（同一聚合的事件顺序不变）；处理失败时整批转交回收器。
+/// 批量处理器不经过 `HandlerMiddleware` 链。
+#[async_trait]
+pub trait BatchEventHandler: Send + Sync {
+    fn handler_name(&self) -> &str;
+    fn handled_event_type(&self) -> HandledEventType;
+    /// 批次大小与时间窗口
+    fn batch_config(&self) -> BatchConfig {
+        BatchConfig::default()
+    }
+    /// 所需的事件顺序保证
+    fn required_ordering(&self) -> OrderingGuarantee {
+        OrderingGuarantee::Unordered
+    }
+    /// 处理一批事件；`ctx` 的投递次数按批次首个事件计算
+    async fn handle_batch(
+        &self,
+        events: &[SerializedEvent],
+        ctx: &HandlerContext,
+    ) -> anyhow::Result<()>;
 }
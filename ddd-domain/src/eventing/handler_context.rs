@@ -0,0 +1,140 @@
+//! 处理器上下文（HandlerContext）
+//!
+//! 由引擎在分发事件时构造并传入 `EventHandler::handle`，携带：
+//! - 投递元信息：第几次投递、首次投递时间、分区、引擎名称；
+//! - 作用域服务：时钟（`Clock`）与指标（`HandlerMetrics`），便于测试替换而无需全局状态；
+//! - 注解：由 `HandlerMiddleware` 写入的键值（如租户、解密密钥版本），供处理器读取；
+//! - 派生事件：处理器经 `emit` 产出的后续事件，处理成功后由引擎补齐因果元数据并写入 Outbox。
+//!
+use crate::persist::SerializedEvent;
+use bon::Builder;
+use chrono::{DateTime, Utc};
+use serde_json::Value;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// 时钟
+pub trait Clock: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// 系统时钟
+#[derive(Clone, Copy, Debug, Default)]
+pub struct SystemClock;
+
+impl Clock for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// 处理器指标
+pub trait HandlerMetrics: Send + Sync {
+    /// 计数器累加
+    fn increment(&self, name: &str, value: u64);
+    /// 记录观测值（耗时、大小等）
+    fn observe(&self, name: &str, value: f64);
+}
+
+/// 丢弃全部指标
+#[derive(Clone, Copy, Debug, Default)]
+pub struct NoopMetrics;
+
+impl HandlerMetrics for NoopMetrics {
+    fn increment(&self, _name: &str, _value: u64) {}
+
+    fn observe(&self, _name: &str, _value: f64) {}
+}
+
+/// 单次处理调用的上下文
+#[derive(Builder, Clone)]
+pub struct HandlerContext {
+    /// 引擎名称
+    #[builder(into, default = "default".to_string())]
+    engine_name: String,
+    /// 该处理器第几次收到此事件（从 1 开始）
+    #[builder(default = 1)]
+    attempt: u32,
+    /// 该处理器首次收到此事件的时间
+    #[builder(default = Utc::now())]
+    first_seen_at: DateTime<Utc>,
+    /// 事件所在分区（总线不分区时为空）
+    partition: Option<String>,
+    #[builder(default = Arc::new(SystemClock))]
+    clock: Arc<dyn Clock>,
+    #[builder(default = Arc::new(NoopMetrics))]
+    metrics: Arc<dyn HandlerMetrics>,
+    /// 中间件写入的注解
+    #[builder(default)]
+    annotations: HashMap<String, Value>,
+    /// 处理器派生的后续事件（克隆的上下文共享同一收集器）
+    #[builder(skip)]
+    emitted: Arc<Mutex<Vec<SerializedEvent>>>,
+}
+
+impl Default for HandlerContext {
+    fn default() -> Self {
+        Self::builder().build()
+    }
+}
+
+impl HandlerContext {
+    pub fn engine_name(&self) -> &str {
+        &self.engine_name
+    }
+
+    pub fn attempt(&self) -> u32 {
+        self.attempt
+    }
+
+    /// 是否为重复投递（`attempt > 1`）
+    pub fn is_redelivery(&self) -> bool {
+        self.attempt > 1
+    }
+
+    pub fn first_seen_at(&self) -> DateTime<Utc> {
+        self.first_seen_at
+    }
+
+    pub fn partition(&self) -> Option<&str> {
+        self.partition.as_deref()
+    }
+
+    pub fn clock(&self) -> &dyn Clock {
+        self.clock.as_ref()
+    }
+
+    pub fn metrics(&self) -> &dyn HandlerMetrics {
+        self.metrics.as_ref()
+    }
+
+    /// 写入注解（同名覆盖）
+    pub fn annotate(&mut self, key: impl Into<String>, value: impl Into<Value>) {
+        self.annotations.insert(key.into(), value.into());
+    }
+
+    pub fn annotation(&self, key: &str) -> Option<&Value> {
+        self.annotations.get(key)
+    }
+
+    /// 产出派生事件：处理成功后由引擎以当前事件为因写入 Outbox，处理失败时丢弃
+    ///
+    /// 事件可能随重新投递再次产出，派生事件 ID 应由源事件确定性生成，以便下游去重。
+    pub fn emit(&self, event: SerializedEvent) {
+        self.emitted.lock().unwrap().push(event);
+    }
+
+    /// 批量产出派生事件
+    pub fn emit_all(&self, events: impl IntoIterator<Item = SerializedEvent>) {
+        self.emitted.lock().unwrap().extend(events);
+    }
+
+    /// 已产出、尚未写入的派生事件
+    pub fn emitted(&self) -> Vec<SerializedEvent> {
+        self.emitted.lock().unwrap().clone()
+    }
+
+    pub(crate) fn take_emitted(&self) -> Vec<SerializedEvent> {
+        std::mem::take(&mut *self.emitted.lock().unwrap())
+    }
+}
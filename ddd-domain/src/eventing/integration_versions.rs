@@ -0,0 +1,286 @@
+//! 集成事件版本协商与弃用告警（IntegrationEventCatalog）
+//!
+//! 对外发布的集成事件以 `(事件类型, schema_version)` 标识契约版本（即 `SerializedEvent::event_version`）。
+//! 平台团队在 `IntegrationEventCatalog` 中登记各版本，并为计划下线的版本声明 `deprecated_after`：
+//! - 版本协商：`negotiate` 在发布方登记的版本与消费方接受的版本中选出最高的共同版本，
+//!   未弃用的版本优先于已弃用的版本；
+//! - 弃用告警：`DeprecatedVersionHandler` 作为 `EventHandler` 装饰器接入，处理器消费已过
+//!   `deprecated_after` 的版本时计入 `deprecated_event_versions.<事件类型>` 指标并输出告警日志，
+//!   便于找出仍依赖旧契约的消费方；告警不影响处理结果。
+//!
+//! 未登记的事件类型与版本不参与告警。
+//!
+use super::engine_log::LOG_TARGET;
+use super::{CircuitStatus, EventHandler, HandledEventType, HandlerContext, OrderingGuarantee};
+use crate::persist::SerializedEvent;
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// 消费已弃用版本的计数指标名前缀（完整名称为 `deprecated_event_versions.<事件类型>`）
+pub const DEPRECATED_EVENT_VERSIONS_METRIC: &str = "deprecated_event_versions";
+
+/// 集成事件的一个契约版本
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct IntegrationEventVersion {
+    pub event_type: String,
+    pub schema_version: usize,
+    /// 该时间之后视为弃用，为空表示未计划弃用
+    pub deprecated_after: Option<DateTime<Utc>>,
+}
+
+impl IntegrationEventVersion {
+    /// 在 `now` 时是否已弃用
+    pub fn is_deprecated_at(&self, now: DateTime<Utc>) -> bool {
+        self.deprecated_after.is_some_and(|after| now > after)
+    }
+}
+
+/// 集成事件版本登记表
+#[derive(Clone, Debug, Default)]
+pub struct IntegrationEventCatalog {
+    versions: BTreeMap<(String, usize), IntegrationEventVersion>,
+}
+
+impl IntegrationEventCatalog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 登记版本
+    pub fn version(self, event_type: impl Into<String>, schema_version: usize) -> Self {
+        self.register(event_type.into(), schema_version, None)
+    }
+
+    /// 登记计划弃用的版本，`deprecated_after` 之后消费该版本会触发告警
+    pub fn deprecated_version(
+        self,
+        event_type: impl Into<String>,
+        schema_version: usize,
+        deprecated_after: DateTime<Utc>,
+    ) -> Self {
+        self.register(event_type.into(), schema_version, Some(deprecated_after))
+    }
+
+    fn register(
+        mut self,
+        event_type: String,
+        schema_version: usize,
+        deprecated_after: Option<DateTime<Utc>>,
+    ) -> Self {
+        self.versions.insert(
+            (event_type.clone(), schema_version),
+            IntegrationEventVersion {
+                event_type,
+                schema_version,
+                deprecated_after,
+            },
+        );
+        self
+    }
+
+    pub fn get(&self, event_type: &str, schema_version: usize) -> Option<&IntegrationEventVersion> {
+        self.versions.get(&(event_type.to_string(), schema_version))
+    }
+
+    /// 事件类型登记的全部版本（按版本升序）
+    pub fn versions(&self, event_type: &str) -> Vec<&IntegrationEventVersion> {
+        self.versions
+            .values()
+            .filter(|v| v.event_type == event_type)
+            .collect()
+    }
+
+    /// 事件所属的契约版本（未登记时为空）
+    pub fn lookup(&self, event: &SerializedEvent) -> Option<&IntegrationEventVersion> {
+        self.get(event.event_type(), event.event_version())
+    }
+
+    /// 从消费方接受的版本中选出最高的共同版本，`now` 时未弃用的版本优先
+    pub fn negotiate(
+        &self,
+        event_type: &str,
+        accepted: &[usize],
+        now: DateTime<Utc>,
+    ) -> Option<&IntegrationEventVersion> {
+        self.versions(event_type)
+            .into_iter()
+            .filter(|v| accepted.contains(&v.schema_version))
+            .max_by_key(|v| (!v.is_deprecated_at(now), v.schema_version))
+    }
+}
+
+/// 消费已弃用版本时告警的处理器装饰器
+pub struct DeprecatedVersionHandler {
+    inner: Arc<dyn EventHandler>,
+    catalog: Arc<IntegrationEventCatalog>,
+}
+
+impl DeprecatedVersionHandler {
+    pub fn new(inner: Arc<dyn EventHandler>, catalog: Arc<IntegrationEventCatalog>) -> Self {
+        Self { inner, catalog }
+    }
+
+    fn warn_if_deprecated(&self, event: &SerializedEvent, ctx: &HandlerContext) {
+        let Some(version) = self.catalog.lookup(event) else {
+            return;
+        };
+        if !version.is_deprecated_at(ctx.clock().now()) {
+            return;
+        }
+
+        ctx.metrics().increment(
+            &format!("{DEPRECATED_EVENT_VERSIONS_METRIC}.{}", event.event_type()),
+            1,
+        );
+        tracing::warn!(
+            target: LOG_TARGET,
+            handler = self.inner.handler_name(),
+            event_id = event.event_id(),
+            event_type = event.event_type(),
+            schema_version = version.schema_version,
+            deprecated_after = ?version.deprecated_after,
+            "deprecated integration event version consumed"
+        );
+    }
+}
+
+#[async_trait]
+impl EventHandler for DeprecatedVersionHandler {
+    fn handler_name(&self) -> &str {
+        self.inner.handler_name()
+    }
+
+    fn handled_event_type(&self) -> HandledEventType {
+        self.inner.handled_event_type()
+    }
+
+    fn required_ordering(&self) -> OrderingGuarantee {
+        self.inner.required_ordering()
+    }
+
+    async fn handle(&self, event: &SerializedEvent, ctx: &HandlerContext) -> anyhow::Result<()> {
+        self.warn_if_deprecated(event, ctx);
+        self.inner.handle(event, ctx).await
+    }
+
+    fn circuit_status(&self) -> Option<CircuitStatus> {
+        self.inner.circuit_status()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::eventing::{Clock, HandlerMetrics};
+    use chrono::TimeZone;
+    use std::sync::Mutex;
+
+    #[derive(Default)]
+    struct Recorded(Mutex<Vec<(String, u64)>>);
+
+    impl HandlerMetrics for Recorded {
+        fn increment(&self, name: &str, value: u64) {
+            self.0.lock().unwrap().push((name.to_string(), value));
+        }
+
+        fn observe(&self, _name: &str, _value: f64) {}
+    }
+
+    struct FixedClock(DateTime<Utc>);
+
+    impl Clock for FixedClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.0
+        }
+    }
+
+    #[derive(Default)]
+    struct Consumer(Mutex<usize>);
+
+    #[async_trait]
+    impl EventHandler for Consumer {
+        fn handler_name(&self) -> &str {
+            "billing"
+        }
+
+        fn handled_event_type(&self) -> HandledEventType {
+            HandledEventType::One("order.placed".into())
+        }
+
+        async fn handle(
+            &self,
+            _event: &SerializedEvent,
+            _ctx: &HandlerContext,
+        ) -> anyhow::Result<()> {
+            *self.0.lock().unwrap() += 1;
+            Ok(())
+        }
+    }
+
+    fn at(day: u32) -> DateTime<Utc> {
+        Utc.with_ymd_and_hms(2026, 3, day, 0, 0, 0).unwrap()
+    }
+
+    fn catalog() -> IntegrationEventCatalog {
+        IntegrationEventCatalog::new()
+            .deprecated_version("order.placed", 1, at(10))
+            .version("order.placed", 2)
+            .deprecated_version("order.placed", 3, at(20))
+    }
+
+    fn event(version: usize) -> SerializedEvent {
+        SerializedEvent::builder()
+            .event_id(format!("e-{version}"))
+            .event_type("order.placed".to_string())
+            .event_version(version)
+            .aggregate_id("o-1".to_string())
+            .aggregate_type("order".to_string())
+            .aggregate_version(1)
+            .occurred_at(at(1))
+            .payload(serde_json::json!({}))
+            .context(serde_json::json!({}))
+            .build()
+    }
+
+    #[test]
+    fn negotiates_highest_common_version_preferring_supported_ones() {
+        let catalog = catalog();
+        let pick = |accepted: &[usize], now| {
+            catalog
+                .negotiate("order.placed", accepted, now)
+                .map(|v| v.schema_version)
+        };
+
+        assert_eq!(pick(&[1, 2, 3], at(15)), Some(3));
+        assert_eq!(pick(&[1, 2, 3], at(25)), Some(2));
+        assert_eq!(pick(&[1], at(25)), Some(1));
+        assert_eq!(pick(&[4], at(1)), None);
+        assert_eq!(catalog.versions("order.placed").len(), 3);
+    }
+
+    #[tokio::test]
+    async fn warns_when_consuming_deprecated_versions() {
+        let consumer = Arc::new(Consumer::default());
+        let handler = DeprecatedVersionHandler::new(consumer.clone(), Arc::new(catalog()));
+        let metrics = Arc::new(Recorded::default());
+        let ctx = |now| {
+            HandlerContext::builder()
+                .clock(Arc::new(FixedClock(now)))
+                .metrics(metrics.clone())
+                .build()
+        };
+
+        handler.handle(&event(1), &ctx(at(5))).await.unwrap();
+        handler.handle(&event(2), &ctx(at(15))).await.unwrap();
+        handler.handle(&event(1), &ctx(at(15))).await.unwrap();
+        handler.handle(&event(7), &ctx(at(15))).await.unwrap();
+
+        assert_eq!(*consumer.0.lock().unwrap(), 4);
+        assert_eq!(
+            *metrics.0.lock().unwrap(),
+            [("deprecated_event_versions.order.placed".to_string(), 1)]
+        );
+    }
+}
@@ -0,0 +1,86 @@
+//! 处理器中间件（HandlerMiddleware）
+//!
+//! 位于总线与处理器之间的通用横切逻辑（解密、解压、租户作用域、日志等），
+//! 由 `EventEngine` 在每次分发时按注册顺序执行，避免在每个处理器中重复实现：
+//! - `before`：可修改事件副本与上下文（如写入注解），或返回 `Flow::ShortCircuit` 跳过处理器；
+//!   返回错误视为处理失败，事件以该原因转交回收器；
+//! - `after`：按注册逆序执行，可观察处理结果（短路时不调用处理器，结果为 `Ok`）。
+//!
+//! 中间件修改的是事件副本，失败标记与补偿始终使用总线上的原始事件。
+//!
+use super::{EventHandler, HandlerContext};
+use crate::persist::SerializedEvent;
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// `before` 的执行结果
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum Flow {
+    /// 继续执行后续中间件与处理器
+    Continue,
+    /// 跳过后续中间件与处理器，视为处理成功
+    ShortCircuit,
+}
+
+/// 处理器中间件
+#[async_trait]
+pub trait HandlerMiddleware: Send + Sync {
+    /// 处理前调用；`handler` 为目标处理器名称
+    async fn before(
+        &self,
+        handler: &str,
+        event: &mut SerializedEvent,
+        ctx: &mut HandlerContext,
+    ) -> anyhow::Result<Flow> {
+        let _ = (handler, event, ctx);
+        Ok(Flow::Continue)
+    }
+
+    /// 处理后调用（仅对 `before` 已执行的中间件）
+    async fn after(
+        &self,
+        handler: &str,
+        event: &SerializedEvent,
+        ctx: &HandlerContext,
+        result: &anyhow::Result<()>,
+    ) {
+        let _ = (handler, event, ctx, result);
+    }
+}
+
+/// 依次执行中间件与处理器
+pub(crate) async fn run(
+    middlewares: &[Arc<dyn HandlerMiddleware>],
+    handler: &dyn EventHandler,
+    mut event: SerializedEvent,
+    mut ctx: HandlerContext,
+) -> anyhow::Result<()> {
+    let name = handler.handler_name();
+    let mut entered = 0;
+    let mut result = Ok(());
+    let mut short_circuit = false;
+
+    for middleware in middlewares {
+        entered += 1;
+        match middleware.before(name, &mut event, &mut ctx).await {
+            Ok(Flow::Continue) => {}
+            Ok(Flow::ShortCircuit) => {
+                short_circuit = true;
+                break;
+            }
+            Err(err) => {
+                result = Err(err);
+                break;
+            }
+        }
+    }
+
+    if result.is_ok() && !short_circuit {
+        result = handler.handle(&event, &ctx).await;
+    }
+
+    for middleware in middlewares[..entered].iter().rev() {
+        middleware.after(name, &event, &ctx, &result).await;
+    }
+    result
+}
@@ -1,24 +1,102 @@
 //! 事件子系统（eventing）
 //!
 //! 提供事件发布/订阅与处理的基础抽象与运行时：
-//! - `EventBus`：统一发布/订阅接口；
-//! - `EventDeliverer`：从本地存储（如 Outbox）批量取出待投递事件；
+//! - `EventBus`：统一发布/订阅接口，`subscribe_with` 可按全局位点/时间戳从历史位置订阅（`SubscribeOptions`），
+//!   `ordering` 声明订阅流的顺序保证（`OrderingGuarantee`），引擎启动时按处理器的要求校验；
+//! - `CompositeEventBus`：按目标过滤并桥接多个总线，目标间失败互不影响（`Required`/`BestEffort`），
+//!   重试时跳过已成功的目标；
+//! - `EventDeliverer`：从本地存储（如 Outbox）批量取出待投递事件；`EventOutbox` 写入处理器派生的事件；
+//!   `OutboxRepository` 约定事件提交与入队在同一事务内完成，由 `TransactionalOutboxDeliverer` 适配给引擎；
 //! - `EventReclaimer`：对失败/超时/漏投递事件进行补偿；
-//! - `EventHandler`：对外部事件进行消费处理；
-//! - `EventEngine`：编排投递、订阅与调度处理，并发执行、失败标记与补偿。
+//! - `WarmStart`：引擎启动时从回放数据源追赶停机期间未分发的事件（按 `max_catch_up_window` 限定），
+//!   再进入常规运行，进度见 `EngineStatus::warm_start`；
+//! - `DeadLetterStore`：累计投递次数超过 `EngineTuning::with_max_deliveries` 仍失败的事件转入死信队列，
+//!   记录处理器、失败原因与投递次数，经 `EngineHandle::dead_letters`/`replay_dead_letter` 查看与重放；
+//! - `EventHandler`：对外部事件进行消费处理，`HandlerContext` 携带投递元信息与作用域服务；
+//!   `BatchEventHandler` 按批次大小/时间窗口累积事件后整批处理；处理器可经 `HandlerContext::emit`
+//!   产出派生事件，由引擎补齐因果元数据后写入 Outbox；
+//! - `EventEngine`：编排投递、订阅与调度处理，并发执行、失败标记与补偿；处理器失败先按
+//!   `EngineTuning::with_retry`（`RetryPolicy`：最大尝试次数、指数退避与抖动）原地重试，仍失败才转交回收器；
+//!   `EngineHandle::pause`/`resume` 可独立暂停投递、补偿、订阅 worker 或单个处理器（`EngineComponent`），
+//!   `register_handler`/`deregister` 在运行期增删处理器（可先追赶历史事件），`update_subscription` 原子替换
+//!   处理器的订阅事件类型与负载过滤（`HandlerSubscription`），生效配置见 `EngineStatus`；
+//!   处理器 panic 被捕获并以 `panic` 原因转交回收器（`panic_guard`），连续 panic 的处理器可被自动隔离；
+//! - `HandlerMiddleware`：总线与处理器之间的中间件链，可修改事件副本、短路或为上下文写入注解；
+//! - 引擎结构化日志（`engine_log`）：以 `tracing`（target `ddd::eventing`）记录拉取、发布、标记、分发与处理结果，
+//!   携带事件 ID/类型/聚合 ID，按 `EngineLogLevel` 调整详细程度；
+//! - `PayloadCompression`：发布前按阈值压缩负载，分发前透明解压；
+//! - `CircuitBreakerHandler`：处理器熔断，打开期间事件以 `circuit_open` 原因转交回收器；
+//! - `EngineChaos`（需启用 `chaos` 特性）：在引擎内按概率注入处理器失败、发布延迟、重复投递与批次乱序，
+//!   上线前验证处理器/投影的幂等性与乱序容忍度；
+//! - `CausationGuardHandler`：因果链深度保护，超限事件不原地重试，配置死信存储时直接转入死信队列（原因 `causation_depth_exceeded`）；
+//! - `DeliveryMonitor`：投递诊断，按处理器组在滑动窗口内检测重复处理与位点缺口（指标 + 告警日志），
+//!   验证基础设施变更后“至少一次”投递与去重仍然有效；`DeliveryMonitorHandler` 以装饰器接入；
+//! - `IntegrationEventCatalog`：集成事件契约版本登记（`schema_version`/`deprecated_after`）与版本协商，
+//!   `DeprecatedVersionHandler` 在处理器消费已弃用版本时计入指标并告警；
+//! - `ProjectionRunner`：投影检查点推进（`CheckpointStore`）与滞后监控（指标 + 阈值告警回调）；
+//! - `TransactionalProjection`：读模型与检查点在同一事务内更新的投影契约，由
+//!   `TransactionalProjectionRunner` 适配为处理器，崩溃后既不重复应用也不丢失更新。
 //!
 //! 该模块仅定义协议与引擎，不绑定具体传输实现，可对接任意消息系统或内存实现。
 //!
 pub mod bus;
 pub mod bus_inmemory;
+pub mod causation_guard;
+pub mod chaos;
+pub mod circuit_breaker;
+pub mod composite_bus;
+pub mod compression;
+pub mod dead_letter;
 pub mod deliverer;
+pub mod delivery_monitor;
 pub mod engine;
+pub mod engine_log;
 pub mod handler;
+pub mod handler_context;
+pub mod integration_versions;
+pub mod middleware;
+pub mod panic_guard;
+pub mod pause;
+pub mod projection;
 pub mod reclaimer;
+pub mod retry;
+pub mod transactional_projection;
+pub mod warm_start;
 
-pub use bus::EventBus;
+pub use bus::{EventBus, OrderingGuarantee, ReplaySource, SubscribeFrom, SubscribeOptions};
 pub use bus_inmemory::InMemoryEventBus;
-pub use deliverer::EventDeliverer;
-pub use engine::{EngineHandle, EventEngine, EventEngineConfig};
-pub use handler::{EventHandler, HandledEventType};
+pub use causation_guard::{CausationDepthExceeded, CausationGuardHandler};
+pub use chaos::CHAOS_FAILURE_REASON;
+#[cfg(feature = "chaos")]
+pub use chaos::{ChaosConfig, ChaosStats, EngineChaos};
+pub use circuit_breaker::{
+    CircuitBreakerConfig, CircuitBreakerHandler, CircuitState, CircuitStatus,
+};
+pub use composite_bus::{BusTarget, BusTargetStats, CompositeEventBus, FailurePolicy};
+pub use compression::PayloadCompression;
+pub use dead_letter::{DeadLetter, DeadLetterStore, InMemoryDeadLetterStore};
+pub use deliverer::{EventDeliverer, EventOutbox, OutboxRepository, TransactionalOutboxDeliverer};
+pub use delivery_monitor::{
+    DELIVERY_DUPLICATES_METRIC, DELIVERY_GAPS_METRIC, DeliveryMonitor, DeliveryMonitorConfig,
+    DeliveryMonitorHandler, DeliveryReport, Observation, SequenceGap,
+};
+pub use engine::{
+    EngineHandle, EngineStatus, EngineTuning, EventEngine, EventEngineConfig, HandlerStatus,
+};
+pub use engine_log::{EngineLogLevel, LOG_TARGET};
+pub use handler::{
+    BatchConfig, BatchEventHandler, EventHandler, HandledEventType, HandlerSubscription,
+};
+pub use handler_context::{Clock, HandlerContext, HandlerMetrics, NoopMetrics, SystemClock};
+pub use integration_versions::{
+    DEPRECATED_EVENT_VERSIONS_METRIC, DeprecatedVersionHandler, IntegrationEventCatalog,
+    IntegrationEventVersion,
+};
+pub use middleware::{Flow, HandlerMiddleware};
+pub use panic_guard::{HANDLER_PANIC_REASON, HANDLER_PANICS_METRIC};
+pub use pause::EngineComponent;
+pub use projection::{CheckpointStore, InMemoryCheckpointStore, ProjectionLag, ProjectionRunner};
 pub use reclaimer::EventReclaimer;
+pub use retry::RetryPolicy;
+pub use transactional_projection::{TransactionalProjection, TransactionalProjectionRunner};
+pub use warm_start::{WARM_START_CHECKPOINT_PREFIX, WarmStart, WarmStartStatus};
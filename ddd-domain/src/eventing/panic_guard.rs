@@ -0,0 +1,98 @@
+//! 处理器 panic 隔离
+//!
+//! 处理器（含中间件链）在处理中 panic 时，引擎捕获 panic 而不终止订阅 worker：
+//! - 事件以 `panic` 原因转交回收器（达到 `max_deliveries` 时转入死信队列），不做原地重试；
+//! - 计入 `handler_panics.<处理器名>` 指标并输出错误日志（含 panic 消息）；
+//! - 配置 `EngineTuning::with_quarantine_after_panics` 后，处理器连续 panic 达到该次数即被隔离
+//!   （以 `EngineComponent::Handler` 暂停，后续事件以 `handler_paused` 原因转交回收器），
+//!   修复后经 `EngineHandle::resume` 恢复；处理成功会清零连续计数。
+//!
+//! 仅在 `panic = "unwind"`（默认）时生效。
+//!
+use futures_util::FutureExt;
+use std::any::Any;
+use std::collections::HashMap;
+use std::fmt;
+use std::future::Future;
+use std::panic::AssertUnwindSafe;
+use std::sync::Mutex;
+
+/// 处理器 panic 时交给回收器的失败原因
+pub const HANDLER_PANIC_REASON: &str = "panic";
+
+/// 处理器 panic 次数指标前缀（完整名称为 `handler_panics.<处理器名>`）
+pub const HANDLER_PANICS_METRIC: &str = "handler_panics";
+
+/// 处理器 panic，携带 panic 消息
+#[derive(Debug)]
+pub(crate) struct HandlerPanicked(pub(crate) String);
+
+impl fmt::Display for HandlerPanicked {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "handler panicked: {}", self.0)
+    }
+}
+
+impl std::error::Error for HandlerPanicked {}
+
+/// 执行处理调用，panic 转换为 `HandlerPanicked` 错误
+pub(crate) async fn catch_panic<F>(fut: F) -> anyhow::Result<()>
+where
+    F: Future<Output = anyhow::Result<()>>,
+{
+    match AssertUnwindSafe(fut).catch_unwind().await {
+        Ok(result) => result,
+        Err(payload) => Err(HandlerPanicked(panic_message(payload.as_ref())).into()),
+    }
+}
+
+pub(crate) fn panic_message(payload: &(dyn Any + Send)) -> String {
+    payload
+        .downcast_ref::<&str>()
+        .map(|s| s.to_string())
+        .or_else(|| payload.downcast_ref::<String>().cloned())
+        .unwrap_or_else(|| "non-string panic payload".to_string())
+}
+
+/// 各处理器的连续 panic 次数
+#[derive(Default)]
+pub(crate) struct PanicTracker {
+    counts: Mutex<HashMap<String, u32>>,
+}
+
+impl PanicTracker {
+    /// 记录一次 panic，返回连续次数
+    pub(crate) fn record(&self, handler_name: &str) -> u32 {
+        let mut counts = self.counts.lock().unwrap();
+        let count = counts.entry(handler_name.to_string()).or_default();
+        *count += 1;
+        *count
+    }
+
+    pub(crate) fn reset(&self, handler_name: &str) {
+        self.counts.lock().unwrap().remove(handler_name);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn converts_panics_into_errors() {
+        let err = catch_panic(async { panic!("boom {}", 1) })
+            .await
+            .unwrap_err();
+        let panicked = err.downcast_ref::<HandlerPanicked>().unwrap();
+        assert_eq!(panicked.0, "boom 1");
+
+        assert!(catch_panic(async { Ok(()) }).await.is_ok());
+        assert!(
+            catch_panic(async { anyhow::bail!("plain") })
+                .await
+                .unwrap_err()
+                .downcast_ref::<HandlerPanicked>()
+                .is_none()
+        );
+    }
+}
@@ -0,0 +1,95 @@
+//! 引擎组件暂停/恢复
+//!
+//! `EngineHandle::pause` / `resume` 可独立控制投递、补偿、订阅三个 worker 以及单个处理器：
+//! - 投递/补偿 worker 暂停期间跳过周期拉取；
+//! - 订阅 worker 暂停期间不再从总线拉取事件（由总线侧缓冲）；
+//! - 处理器暂停期间收到的事件以 `handler_paused` 原因转交回收器，恢复后经补偿重投。
+//!
+use std::collections::BTreeSet;
+use std::sync::Mutex;
+use tokio::sync::Notify;
+use tokio_util::sync::CancellationToken;
+
+/// 处理器暂停期间交给回收器的失败原因
+pub const HANDLER_PAUSED_REASON: &str = "handler_paused";
+
+/// 可暂停的引擎组件
+#[derive(Clone, Debug, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum EngineComponent {
+    /// 投递 worker（Deliverer → Bus）
+    Deliver,
+    /// 补偿 worker（Reclaimer → Bus）
+    Reclaim,
+    /// 订阅 worker（Bus → Handler）
+    Subscribe,
+    /// 指定名称的处理器
+    Handler(String),
+}
+
+impl EngineComponent {
+    pub fn handler(name: impl Into<String>) -> Self {
+        Self::Handler(name.into())
+    }
+}
+
+/// 暂停状态，由引擎与 `EngineHandle` 共享
+#[derive(Default)]
+pub(crate) struct PauseControl {
+    paused: Mutex<BTreeSet<EngineComponent>>,
+    resumed: Notify,
+}
+
+impl PauseControl {
+    /// 暂停组件，返回此前是否处于运行状态
+    pub(crate) fn pause(&self, component: EngineComponent) -> bool {
+        self.paused.lock().unwrap().insert(component)
+    }
+
+    /// 恢复组件，返回此前是否处于暂停状态
+    pub(crate) fn resume(&self, component: &EngineComponent) -> bool {
+        let removed = self.paused.lock().unwrap().remove(component);
+        if removed {
+            self.resumed.notify_waiters();
+        }
+        removed
+    }
+
+    pub(crate) fn is_paused(&self, component: &EngineComponent) -> bool {
+        self.paused.lock().unwrap().contains(component)
+    }
+
+    pub(crate) fn is_handler_paused(&self, name: &str) -> bool {
+        self.paused
+            .lock()
+            .unwrap()
+            .iter()
+            .any(|c| matches!(c, EngineComponent::Handler(n) if n == name))
+    }
+
+    pub(crate) fn paused(&self) -> Vec<EngineComponent> {
+        self.paused.lock().unwrap().iter().cloned().collect()
+    }
+
+    /// 等待组件恢复；被取消时返回 `false`
+    pub(crate) async fn wait_resumed(
+        &self,
+        component: &EngineComponent,
+        token: &CancellationToken,
+    ) -> bool {
+        loop {
+            let notified = self.resumed.notified();
+            tokio::pin!(notified);
+            // 先登记等待再检查状态，避免检查与等待之间的恢复通知丢失
+            notified.as_mut().enable();
+
+            if !self.is_paused(component) {
+                return true;
+            }
+
+            tokio::select! {
+                _ = token.cancelled() => return false,
+                _ = notified => {}
+            }
+        }
+    }
+}
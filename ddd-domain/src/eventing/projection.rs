@@ -0,0 +1,593 @@
+//! 读模型投影检查点与滞后监控（Projection）
+//!
+//! - `CheckpointStore`：按投影名称记录已处理的事件位点；分区后端按分区分别记录（`EventPosition`）；
+//! - `ProjectionRunner`：`EventHandler` 装饰器，处理成功后推进事件所在分区的检查点，跳过同一分区内
+//!   位点不大于检查点的重复事件；`resume_from` 按各分区检查点生成订阅恢复选项；
+//! - 滞后（lag）= 最新全局位点 − 检查点。`ProjectionRunner::report_lag` 以事件存储的最新位点
+//!   计算滞后，写入 `projection_lag.<投影名>` 观测指标，超过阈值时触发告警回调，
+//!   便于在投影落后时及时告警，而不是等用户发现报表数据陈旧。
+//! - 未知事件：`with_unknown_event_policy` 声明投影认识的事件类型，其余事件按 `UnknownEventPolicy`
+//!   失败（不推进检查点，交由回收器重试）、跳过或跳过并记录（计入 `unknown_events.<投影名>` 指标）。
+//! - 端到端延迟：事件处理成功后，以命令接收时间（`SerializedEvent::command_received_at`）
+//!   到处理完成的间隔写入 `end_to_end_latency.<事件类型>` 观测指标（毫秒），回填事件不计入；
+//! - 顺序要求：检查点去重假设事件按位点顺序到达，依赖顺序的投影以 `with_required_ordering`
+//!   声明所需的 `OrderingGuarantee`，引擎启动时拒绝在不满足的总线上运行。
+//!
+use super::{
+    EventHandler, HandledEventType, HandlerContext, HandlerMetrics, NoopMetrics, OrderingGuarantee,
+    SubscribeOptions,
+};
+use crate::domain_event::EventDescriptor;
+use crate::error::DomainResult as Result;
+use crate::persist::{EventPosition, SerializedEvent, UnknownEventPolicy};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// 投影滞后观测指标名前缀（完整名称为 `projection_lag.<投影名>`）
+pub const PROJECTION_LAG_METRIC: &str = "projection_lag";
+
+/// 投影未知事件计数指标名前缀（完整名称为 `unknown_events.<投影名>`）
+pub const UNKNOWN_EVENTS_METRIC: &str = "unknown_events";
+
+/// 端到端延迟观测指标名前缀（完整名称为 `end_to_end_latency.<事件类型>`，单位毫秒）
+pub const END_TO_END_LATENCY_METRIC: &str = "end_to_end_latency";
+
+/// 投影滞后
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct ProjectionLag {
+    pub projection: String,
+    /// 事件存储的最新全局位点
+    pub head: i64,
+    /// 投影检查点（尚未处理任何事件时为空）
+    pub checkpoint: Option<i64>,
+    /// 落后的事件数
+    pub lag: u64,
+}
+
+impl ProjectionLag {
+    pub fn new(projection: impl Into<String>, head: i64, checkpoint: Option<i64>) -> Self {
+        let lag = head.saturating_sub(checkpoint.unwrap_or(0)).max(0) as u64;
+        Self {
+            projection: projection.into(),
+            head,
+            checkpoint,
+            lag,
+        }
+    }
+}
+
+/// 投影检查点
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Checkpoint {
+    /// 已处理的最大全局位点
+    pub sequence: i64,
+    /// 检查点最近一次推进的时间
+    pub updated_at: DateTime<Utc>,
+}
+
+/// 投影检查点存储
+#[async_trait]
+pub trait CheckpointStore: Send + Sync {
+    /// 读取投影的检查点
+    async fn load(&self, projection: &str) -> Result<Option<Checkpoint>>;
+
+    /// 保存投影的检查点（推进时间取当前时间）
+    async fn save(&self, projection: &str, sequence: i64) -> Result<()>;
+
+    /// 计算投影相对最新全局位点 `head` 的滞后
+    async fn lag(&self, projection: &str, head: i64) -> Result<ProjectionLag> {
+        let checkpoint = self.load(projection).await?;
+        Ok(ProjectionLag::new(
+            projection,
+            head,
+            checkpoint.map(|c| c.sequence),
+        ))
+    }
+
+    /// 读取投影在 `partition` 分区的检查点位置
+    ///
+    /// 默认实现将分区检查点映射为名为 `<投影名>@<分区>` 的检查点（分区 `0` 即投影名本身，
+    /// 与单分区检查点兼容），存储后端可覆盖为按 `(投影, 分区)` 存储。
+    async fn load_position(
+        &self,
+        projection: &str,
+        partition: u32,
+    ) -> Result<Option<EventPosition>> {
+        let checkpoint = self.load(&partition_key(projection, partition)).await?;
+        Ok(checkpoint.map(|c| EventPosition::new(partition, c.sequence)))
+    }
+
+    /// 保存投影在事件所在分区的检查点位置
+    async fn save_position(&self, projection: &str, position: EventPosition) -> Result<()> {
+        self.save(
+            &partition_key(projection, position.partition),
+            position.sequence,
+        )
+        .await
+    }
+
+    /// 投影在各分区的检查点位置（按分区排序）
+    ///
+    /// 默认实现仅返回分区 `0`；支持分区的存储应覆盖以列出全部分区。
+    async fn positions(&self, projection: &str) -> Result<Vec<EventPosition>> {
+        Ok(self
+            .load_position(projection, 0)
+            .await?
+            .into_iter()
+            .collect())
+    }
+}
+
+fn partition_key(projection: &str, partition: u32) -> String {
+    match partition {
+        0 => projection.to_string(),
+        partition => format!("{projection}@{partition}"),
+    }
+}
+
+/// 基于内存的 CheckpointStore 实现
+#[derive(Default)]
+pub struct InMemoryCheckpointStore {
+    checkpoints: Mutex<HashMap<String, Checkpoint>>,
+}
+
+impl InMemoryCheckpointStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl CheckpointStore for InMemoryCheckpointStore {
+    async fn load(&self, projection: &str) -> Result<Option<Checkpoint>> {
+        Ok(self.checkpoints.lock().unwrap().get(projection).copied())
+    }
+
+    async fn save(&self, projection: &str, sequence: i64) -> Result<()> {
+        self.checkpoints.lock().unwrap().insert(
+            projection.to_string(),
+            Checkpoint {
+                sequence,
+                updated_at: Utc::now(),
+            },
+        );
+        Ok(())
+    }
+
+    async fn positions(&self, projection: &str) -> Result<Vec<EventPosition>> {
+        let checkpoints = self.checkpoints.lock().unwrap();
+        let mut positions: Vec<EventPosition> = checkpoints
+            .iter()
+            .filter_map(|(key, checkpoint)| {
+                let partition = match key.strip_prefix(projection)? {
+                    "" => 0,
+                    suffix => suffix.strip_prefix('@')?.parse().ok()?,
+                };
+                Some(EventPosition::new(partition, checkpoint.sequence))
+            })
+            .collect();
+        positions.sort_by_key(|p| p.partition);
+        Ok(positions)
+    }
+}
+
+type LagAlert = Arc<dyn Fn(&ProjectionLag) + Send + Sync>;
+
+/// 投影运行器：以处理器名称作为投影名维护检查点，并监控滞后
+///
+/// 未携带 `sequence_number` 的事件（尚未由存储层分配位点）直接交给内部处理器，不推进检查点；
+/// 检查点按事件所在分区（`SerializedEvent::partition`）分别推进。
+pub struct ProjectionRunner {
+    inner: Arc<dyn EventHandler>,
+    checkpoints: Arc<dyn CheckpointStore>,
+    metrics: Arc<dyn HandlerMetrics>,
+    alert: Option<(u64, LagAlert)>,
+    unknown_events: Option<(HandledEventType, UnknownEventPolicy)>,
+    ordering: OrderingGuarantee,
+}
+
+impl ProjectionRunner {
+    pub fn new(inner: Arc<dyn EventHandler>, checkpoints: Arc<dyn CheckpointStore>) -> Self {
+        Self {
+            inner,
+            checkpoints,
+            metrics: Arc::new(NoopMetrics),
+            alert: None,
+            unknown_events: None,
+            ordering: OrderingGuarantee::Unordered,
+        }
+    }
+
+    /// 滞后指标的输出目标
+    pub fn with_metrics(mut self, metrics: Arc<dyn HandlerMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// 滞后超过 `threshold` 时，每次 `report_lag` 都会调用 `on_alert`
+    pub fn with_lag_alert<F>(mut self, threshold: u64, on_alert: F) -> Self
+    where
+        F: Fn(&ProjectionLag) + Send + Sync + 'static,
+    {
+        self.alert = Some((threshold, Arc::new(on_alert)));
+        self
+    }
+
+    /// 声明投影认识的事件类型（通常为 `Event::DESCRIPTORS`），其余事件按 `policy` 处理
+    pub fn with_unknown_event_policy(
+        mut self,
+        known: &[EventDescriptor],
+        policy: UnknownEventPolicy,
+    ) -> Self {
+        self.unknown_events = Some((HandledEventType::of(known), policy));
+        self
+    }
+
+    /// 声明投影所需的事件顺序保证（与内部处理器的要求取较强者）
+    pub fn with_required_ordering(mut self, ordering: OrderingGuarantee) -> Self {
+        self.ordering = ordering;
+        self
+    }
+
+    pub fn projection(&self) -> &str {
+        self.inner.handler_name()
+    }
+
+    /// 当前检查点位点
+    pub async fn checkpoint(&self) -> Result<Option<i64>> {
+        let checkpoint = self.checkpoints.load(self.projection()).await?;
+        Ok(checkpoint.map(|c| c.sequence))
+    }
+
+    /// 各分区的检查点位置
+    pub async fn positions(&self) -> Result<Vec<EventPosition>> {
+        self.checkpoints.positions(self.projection()).await
+    }
+
+    /// 从各分区检查点之后恢复订阅的选项（无检查点时从头开始）
+    pub async fn resume_from(&self) -> Result<SubscribeOptions> {
+        let positions = self.positions().await?;
+        Ok(SubscribeOptions::from_positions(
+            positions.into_iter().map(EventPosition::next),
+        ))
+    }
+
+    /// 以最新全局位点 `head` 计算滞后，记录指标并按阈值触发告警
+    pub async fn report_lag(&self, head: i64) -> Result<ProjectionLag> {
+        let lag = self.checkpoints.lag(self.projection(), head).await?;
+
+        self.metrics.observe(
+            &format!("{PROJECTION_LAG_METRIC}.{}", lag.projection),
+            lag.lag as f64,
+        );
+        if let Some((threshold, on_alert)) = &self.alert
+            && lag.lag > *threshold
+        {
+            on_alert(&lag);
+        }
+        Ok(lag)
+    }
+
+    /// 将已知事件交给内部处理器；未知事件按策略失败或跳过（跳过的事件同样推进检查点）
+    async fn handle_known(
+        &self,
+        event: &SerializedEvent,
+        ctx: &HandlerContext,
+    ) -> anyhow::Result<()> {
+        let policy = match &self.unknown_events {
+            Some((known, policy)) if !known.matches(event.event_type()) => policy,
+            _ => return self.inner.handle(event, ctx).await,
+        };
+
+        match policy {
+            UnknownEventPolicy::Fail => anyhow::bail!(
+                "unknown event type {} for projection {}",
+                event.event_type(),
+                self.projection()
+            ),
+            UnknownEventPolicy::Skip => {}
+            UnknownEventPolicy::Collect(log) => {
+                log.record(event);
+                self.metrics
+                    .increment(&format!("{UNKNOWN_EVENTS_METRIC}.{}", self.projection()), 1);
+            }
+        }
+        Ok(())
+    }
+
+    /// 记录命令接收到投影更新完成的端到端延迟；回填事件与未记录接收时间的事件不计入
+    fn observe_latency(&self, event: &SerializedEvent, ctx: &HandlerContext) {
+        let Some(received_at) = event.command_received_at() else {
+            return;
+        };
+        if event.is_backfilled() {
+            return;
+        }
+        let latency = (ctx.clock().now() - received_at).num_milliseconds().max(0);
+        self.metrics.observe(
+            &format!("{END_TO_END_LATENCY_METRIC}.{}", event.event_type()),
+            latency as f64,
+        );
+    }
+}
+
+#[async_trait]
+impl EventHandler for ProjectionRunner {
+    fn handler_name(&self) -> &str {
+        self.inner.handler_name()
+    }
+
+    fn handled_event_type(&self) -> HandledEventType {
+        self.inner.handled_event_type()
+    }
+
+    fn required_ordering(&self) -> OrderingGuarantee {
+        self.ordering.max(self.inner.required_ordering())
+    }
+
+    async fn handle(&self, event: &SerializedEvent, ctx: &HandlerContext) -> anyhow::Result<()> {
+        let position = event.position();
+        if let Some(position) = position
+            && self
+                .checkpoints
+                .load_position(self.projection(), position.partition)
+                .await?
+                .is_some_and(|checkpoint| checkpoint.reached(&position))
+        {
+            return Ok(());
+        }
+
+        self.handle_known(event, ctx).await?;
+        self.observe_latency(event, ctx);
+        if let Some(position) = position {
+            self.checkpoints
+                .save_position(self.projection(), position)
+                .await?;
+        }
+        Ok(())
+    }
+
+    fn circuit_status(&self) -> Option<super::CircuitStatus> {
+        self.inner.circuit_status()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+
+    #[derive(Default)]
+    struct Counting {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl EventHandler for Counting {
+        fn handler_name(&self) -> &str {
+            "orders_view"
+        }
+
+        fn handled_event_type(&self) -> HandledEventType {
+            HandledEventType::All
+        }
+
+        async fn handle(
+            &self,
+            _event: &SerializedEvent,
+            _ctx: &HandlerContext,
+        ) -> anyhow::Result<()> {
+            self.calls.fetch_add(1, Ordering::Relaxed);
+            Ok(())
+        }
+    }
+
+    #[derive(Default)]
+    struct Observed(Mutex<Vec<(String, f64)>>);
+
+    impl HandlerMetrics for Observed {
+        fn increment(&self, name: &str, value: u64) {
+            self.0
+                .lock()
+                .unwrap()
+                .push((name.to_string(), value as f64));
+        }
+
+        fn observe(&self, name: &str, value: f64) {
+            self.0.lock().unwrap().push((name.to_string(), value));
+        }
+    }
+
+    fn mk_event(sequence: i64) -> SerializedEvent {
+        SerializedEvent::builder()
+            .event_id(format!("e-{sequence}"))
+            .event_type("Demo".to_string())
+            .event_version(1)
+            .sequence_number(sequence)
+            .aggregate_id("a-1".to_string())
+            .aggregate_type("demo".to_string())
+            .aggregate_version(sequence as usize)
+            .occurred_at(Utc::now())
+            .payload(serde_json::json!({}))
+            .context(serde_json::json!({}))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn advances_checkpoint_and_alerts_on_lag() {
+        let inner = Arc::new(Counting::default());
+        let store = Arc::new(InMemoryCheckpointStore::new());
+        let metrics = Arc::new(Observed::default());
+        let alerts = Arc::new(Mutex::new(Vec::new()));
+        let sink = alerts.clone();
+        let runner = ProjectionRunner::new(inner.clone(), store.clone())
+            .with_metrics(metrics.clone())
+            .with_lag_alert(5, move |lag| sink.lock().unwrap().push(lag.clone()));
+        let ctx = HandlerContext::default();
+
+        assert_eq!(runner.report_lag(3).await.unwrap().lag, 3);
+
+        for sequence in [1, 2, 3, 2] {
+            runner.handle(&mk_event(sequence), &ctx).await.unwrap();
+        }
+        // 重复投递的位点 2 被跳过
+        assert_eq!(inner.calls.load(Ordering::Relaxed), 3);
+        assert_eq!(runner.checkpoint().await.unwrap(), Some(3));
+
+        let lag = runner.report_lag(10).await.unwrap();
+        assert_eq!(lag, ProjectionLag::new("orders_view", 10, Some(3)));
+        assert_eq!(lag.lag, 7);
+        assert_eq!(alerts.lock().unwrap().as_slice(), &[lag]);
+        assert_eq!(
+            metrics.0.lock().unwrap().last(),
+            Some(&("projection_lag.orders_view".to_string(), 7.0))
+        );
+
+        assert_eq!(runner.report_lag(8).await.unwrap().lag, 5);
+        assert_eq!(alerts.lock().unwrap().len(), 1);
+    }
+
+    struct FixedClock(DateTime<Utc>);
+
+    impl crate::eventing::Clock for FixedClock {
+        fn now(&self) -> DateTime<Utc> {
+            self.0
+        }
+    }
+
+    #[tokio::test]
+    async fn observes_end_to_end_latency_per_event_type() {
+        let received_at = Utc::now();
+        let metrics = Arc::new(Observed::default());
+        let runner = ProjectionRunner::new(
+            Arc::new(Counting::default()),
+            Arc::new(InMemoryCheckpointStore::new()),
+        )
+        .with_metrics(metrics.clone());
+        let ctx = HandlerContext::builder()
+            .clock(Arc::new(FixedClock(
+                received_at + chrono::Duration::milliseconds(250),
+            )))
+            .build();
+
+        let stamped = |sequence: i64| {
+            SerializedEvent::builder()
+                .event_id(format!("e-{sequence}"))
+                .event_type("Demo".to_string())
+                .event_version(1)
+                .sequence_number(sequence)
+                .aggregate_id("a-1".to_string())
+                .aggregate_type("demo".to_string())
+                .aggregate_version(sequence as usize)
+                .occurred_at(received_at)
+                .command_received_at(received_at)
+                .payload(serde_json::json!({}))
+                .context(serde_json::json!({}))
+        };
+        runner.handle(&stamped(1).build(), &ctx).await.unwrap();
+        // 回填事件与未记录接收时间的事件不计入
+        runner
+            .handle(&stamped(2).backfilled(true).build(), &ctx)
+            .await
+            .unwrap();
+        runner.handle(&mk_event(3), &ctx).await.unwrap();
+        // 重复投递被检查点跳过，不重复计入
+        runner.handle(&stamped(1).build(), &ctx).await.unwrap();
+
+        assert_eq!(
+            metrics.0.lock().unwrap().as_slice(),
+            &[("end_to_end_latency.Demo".to_string(), 250.0)]
+        );
+    }
+
+    #[tokio::test]
+    async fn tracks_checkpoints_per_partition_and_resumes_from_them() {
+        let inner = Arc::new(Counting::default());
+        let store = Arc::new(InMemoryCheckpointStore::new());
+        let runner = ProjectionRunner::new(inner.clone(), store.clone());
+        let ctx = HandlerContext::default();
+
+        // 分区 1 的位点 3 不会让分区 2 的位点 2 被视为重复
+        for (partition, sequence) in [(1, 3), (2, 2), (1, 2), (2, 5), (0, 1)] {
+            let event = mk_event(sequence).with_position(EventPosition::new(partition, sequence));
+            runner.handle(&event, &ctx).await.unwrap();
+        }
+        assert_eq!(inner.calls.load(Ordering::Relaxed), 4);
+        assert_eq!(
+            runner.positions().await.unwrap(),
+            vec![
+                EventPosition::new(0, 1),
+                EventPosition::new(1, 3),
+                EventPosition::new(2, 5),
+            ]
+        );
+        assert_eq!(runner.checkpoint().await.unwrap(), Some(1));
+
+        let resume = runner.resume_from().await.unwrap();
+        assert_eq!(
+            resume,
+            SubscribeOptions::from_positions([
+                EventPosition::new(0, 2),
+                EventPosition::new(1, 4),
+                EventPosition::new(2, 6),
+            ])
+        );
+        let event = |p, s| mk_event(s).with_position(EventPosition::new(p, s));
+        assert!(!resume.from.includes(0, &event(1, 3)));
+        assert!(resume.from.includes(0, &event(1, 4)));
+        assert!(resume.from.includes(0, &event(7, 1)));
+    }
+
+    #[tokio::test]
+    async fn handles_unknown_events_by_policy() {
+        const KNOWN: &[EventDescriptor] = &[EventDescriptor {
+            variant: "Demo",
+            event_type: "Demo",
+            event_version: 1,
+        }];
+        let unknown = |sequence: i64| {
+            SerializedEvent::builder()
+                .event_id(format!("n-{sequence}"))
+                .event_type("Newer".to_string())
+                .event_version(1)
+                .sequence_number(sequence)
+                .aggregate_id("a-1".to_string())
+                .aggregate_type("demo".to_string())
+                .aggregate_version(sequence as usize)
+                .occurred_at(Utc::now())
+                .payload(serde_json::json!({}))
+                .context(serde_json::json!({}))
+                .build()
+        };
+        let ctx = HandlerContext::default();
+
+        let inner = Arc::new(Counting::default());
+        let failing =
+            ProjectionRunner::new(inner.clone(), Arc::new(InMemoryCheckpointStore::new()))
+                .with_unknown_event_policy(KNOWN, UnknownEventPolicy::Fail);
+        assert!(failing.handle(&unknown(1), &ctx).await.is_err());
+        assert_eq!(failing.checkpoint().await.unwrap(), None);
+
+        let metrics = Arc::new(Observed::default());
+        let policy = UnknownEventPolicy::collect(10);
+        let log = policy.log().unwrap().clone();
+        let collecting =
+            ProjectionRunner::new(inner.clone(), Arc::new(InMemoryCheckpointStore::new()))
+                .with_metrics(metrics.clone())
+                .with_unknown_event_policy(KNOWN, policy);
+        for event in [mk_event(1), unknown(2), unknown(2), mk_event(3)] {
+            collecting.handle(&event, &ctx).await.unwrap();
+        }
+        // 未知事件不交给内部处理器，但推进检查点；重复投递不重复记录
+        assert_eq!(inner.calls.load(Ordering::Relaxed), 2);
+        assert_eq!(collecting.checkpoint().await.unwrap(), Some(3));
+        assert_eq!(log.total(), 1);
+        assert_eq!(log.recent()[0].event_type(), "Newer");
+        assert_eq!(
+            metrics.0.lock().unwrap().as_slice(),
+            &[("unknown_events.orders_view".to_string(), 1.0)]
+        );
+    }
+}
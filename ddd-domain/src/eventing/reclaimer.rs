@@ -1,7 +1,7 @@
 //! 事件回收器（EventReclaimer）
 //!
 //! 负责拉取失败/超时/漏投递事件进行补偿，并细化到处理器粒度的失败标记，
-//! 以便区分具体 handler 的异常。
+//! 以便区分具体 handler 的异常；超出投递上限转入死信队列的事件经 `mark_dead_lettered` 退出重投。
 //!
 use crate::{error::DomainResult as Result, persist::SerializedEvent};
 use async_trait::async_trait;
@@ -25,4 +25,17 @@ pub trait EventReclaimer: Send + Sync {
         events: &[&SerializedEvent],
         reason: &str,
     ) -> Result<()>;
+
+    /// 事件已转入死信队列，此后不应再为该处理器重投
+    ///
+    /// 默认实现视为补偿完成（`mark_reclaimed`）；按处理器粒度记录失败的回收器可覆盖为仅移除该处理器的记录。
+    async fn mark_dead_lettered(
+        &self,
+        handler_name: &str,
+        events: &[&SerializedEvent],
+        reason: &str,
+    ) -> Result<()> {
+        let _ = (handler_name, reason);
+        self.mark_reclaimed(events).await
+    }
 }
@@ -0,0 +1,93 @@
+//! 处理器失败的进程内重试（RetryPolicy）
+//!
+//! 处理器失败时，引擎先按 `RetryPolicy` 在原地重试，仍失败才转交回收器：
+//! - 退避按指数增长：第 n 次重试前等待 `backoff * 2^(n-1)`，不超过 `max_backoff`；
+//! - 抖动（`jitter`，0.0–1.0）在退避上随机扣减至多该比例，避免同时失败的处理器同步重试；
+//! - 每次重试都是一次新的投递，`HandlerContext::attempt` 随之递增，派生事件只在成功的那次写入。
+//!
+//! 默认 `max_attempts = 1`（不重试），与未配置重试时的行为一致。
+//!
+use std::time::Duration;
+
+/// 处理器失败的重试策略
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RetryPolicy {
+    /// 最大尝试次数（含首次，至少 1）
+    pub max_attempts: u32,
+    /// 首次重试前的退避
+    pub backoff: Duration,
+    /// 退避上限
+    pub max_backoff: Duration,
+    /// 抖动比例（0.0–1.0），退避随机扣减至多该比例
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: 1,
+            backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_secs(10),
+            jitter: 0.0,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// 第 `attempt` 次尝试失败后是否还可重试
+    pub fn should_retry(&self, attempt: u32) -> bool {
+        attempt < self.max_attempts.max(1)
+    }
+
+    /// 第 `retry` 次重试（从 1 开始）前的退避；`random` 为均匀分布的随机数，用于抖动
+    pub fn delay(&self, retry: u32, random: u64) -> Duration {
+        let exponent = retry.saturating_sub(1).min(31);
+        let delay = self
+            .backoff
+            .saturating_mul(1u32 << exponent)
+            .min(self.max_backoff);
+
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        if jitter == 0.0 {
+            return delay;
+        }
+        let fraction = (random >> 11) as f64 / (1u64 << 53) as f64;
+        delay.mul_f64(1.0 - jitter * fraction)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn backs_off_exponentially_up_to_the_cap() {
+        let policy = RetryPolicy {
+            max_attempts: 5,
+            backoff: Duration::from_millis(100),
+            max_backoff: Duration::from_millis(500),
+            jitter: 0.0,
+        };
+        let delays: Vec<_> = (1..=4).map(|n| policy.delay(n, 0)).collect();
+        assert_eq!(
+            delays,
+            [100, 200, 400, 500].map(Duration::from_millis).to_vec()
+        );
+        assert!(policy.should_retry(4));
+        assert!(!policy.should_retry(5));
+        assert!(!RetryPolicy::default().should_retry(1));
+    }
+
+    #[test]
+    fn jitter_shortens_delay_by_at_most_the_ratio() {
+        let policy = RetryPolicy {
+            max_attempts: 3,
+            backoff: Duration::from_millis(1000),
+            max_backoff: Duration::from_secs(10),
+            jitter: 0.5,
+        };
+        assert_eq!(policy.delay(1, 0), Duration::from_millis(1000));
+        let shortest = policy.delay(1, u64::MAX);
+        assert!(shortest >= Duration::from_millis(500) && shortest < Duration::from_millis(501));
+    }
+}
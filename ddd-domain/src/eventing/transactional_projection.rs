@@ -0,0 +1,298 @@
+//! 事务性投影（TransactionalProjection）
+//!
+//! `ProjectionRunner` 在处理成功后单独保存检查点：进程在更新读模型与保存检查点之间崩溃时，
+//! 事件会被重复应用（double-apply）；若先保存检查点再更新读模型，则可能丢失更新（lost update）。
+//! 读模型与检查点位于同一数据库时，应在同一事务内完成两者：
+//! - `TransactionalProjection`：存储侧契约，`begin` 开启事务，`load_position`/`save_position` 在事务内
+//!   读写检查点（SQL 后端可用 `SELECT ... FOR UPDATE` 串行化同一投影的并发处理），`apply` 在事务内
+//!   更新读模型，`commit` 提交；未提交的事务丢弃时应回滚（如 sqlx 的 `Transaction` 在 drop 时回滚）；
+//! - `TransactionalProjectionRunner`：将契约适配为 `EventHandler`，每个事件在一个事务内完成
+//!   “检查点去重 → 应用 → 推进检查点 → 提交”，任一步失败整体回滚，由回收器重投。
+//!
+//! 未携带 `sequence_number` 的事件无法去重，仍在事务内应用但不推进检查点。
+//!
+use super::{EventHandler, HandledEventType, HandlerContext, OrderingGuarantee, SubscribeOptions};
+use crate::persist::{EventPosition, SerializedEvent};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// 读模型与检查点共享同一事务的投影
+#[async_trait]
+pub trait TransactionalProjection: Send + Sync {
+    /// 事务句柄（如 `sqlx::Transaction<'static, Postgres>`）
+    type Tx: Send;
+
+    /// 投影名，用作处理器名与检查点的键
+    fn projection_name(&self) -> &str;
+
+    /// 投影关心的事件类型
+    fn handled_event_type(&self) -> HandledEventType {
+        HandledEventType::All
+    }
+
+    /// 开启事务
+    async fn begin(&self) -> anyhow::Result<Self::Tx>;
+
+    /// 在事务内读取 `partition` 分区的检查点
+    async fn load_position(
+        &self,
+        tx: &mut Self::Tx,
+        partition: u32,
+    ) -> anyhow::Result<Option<EventPosition>>;
+
+    /// 在事务内更新读模型
+    async fn apply(&self, tx: &mut Self::Tx, event: &SerializedEvent) -> anyhow::Result<()>;
+
+    /// 在事务内推进事件所在分区的检查点
+    async fn save_position(&self, tx: &mut Self::Tx, position: EventPosition)
+    -> anyhow::Result<()>;
+
+    /// 提交事务
+    async fn commit(&self, tx: Self::Tx) -> anyhow::Result<()>;
+
+    /// 放弃事务；默认直接丢弃句柄，由实现在 drop 时回滚
+    async fn rollback(&self, tx: Self::Tx) -> anyhow::Result<()> {
+        drop(tx);
+        Ok(())
+    }
+
+    /// 各分区已提交的检查点位置（按分区排序），用于恢复订阅
+    async fn positions(&self) -> anyhow::Result<Vec<EventPosition>>;
+}
+
+/// 将 `TransactionalProjection` 适配为 `EventHandler`
+///
+/// 与 `ProjectionRunner` 相同，检查点去重假设事件按位点顺序到达，依赖顺序的投影以
+/// `with_required_ordering` 声明所需的 `OrderingGuarantee`。
+pub struct TransactionalProjectionRunner<P> {
+    projection: Arc<P>,
+    ordering: OrderingGuarantee,
+}
+
+impl<P> TransactionalProjectionRunner<P>
+where
+    P: TransactionalProjection,
+{
+    pub fn new(projection: Arc<P>) -> Self {
+        Self {
+            projection,
+            ordering: OrderingGuarantee::Unordered,
+        }
+    }
+
+    /// 声明投影所需的事件顺序保证
+    pub fn with_required_ordering(mut self, ordering: OrderingGuarantee) -> Self {
+        self.ordering = ordering;
+        self
+    }
+
+    pub fn projection(&self) -> &Arc<P> {
+        &self.projection
+    }
+
+    /// 从各分区已提交的检查点之后恢复订阅的选项（无检查点时从头开始）
+    pub async fn resume_from(&self) -> anyhow::Result<SubscribeOptions> {
+        let positions = self.projection.positions().await?;
+        Ok(SubscribeOptions::from_positions(
+            positions.into_iter().map(EventPosition::next),
+        ))
+    }
+
+    /// 在已开启的事务内完成去重、应用与检查点推进；返回 `false` 表示事件已处理过
+    async fn apply_in(&self, tx: &mut P::Tx, event: &SerializedEvent) -> anyhow::Result<bool> {
+        let position = event.position();
+        if let Some(position) = position
+            && self
+                .projection
+                .load_position(tx, position.partition)
+                .await?
+                .is_some_and(|checkpoint| checkpoint.reached(&position))
+        {
+            return Ok(false);
+        }
+
+        self.projection.apply(tx, event).await?;
+        if let Some(position) = position {
+            self.projection.save_position(tx, position).await?;
+        }
+        Ok(true)
+    }
+}
+
+#[async_trait]
+impl<P> EventHandler for TransactionalProjectionRunner<P>
+where
+    P: TransactionalProjection + 'static,
+{
+    fn handler_name(&self) -> &str {
+        self.projection.projection_name()
+    }
+
+    fn handled_event_type(&self) -> HandledEventType {
+        self.projection.handled_event_type()
+    }
+
+    fn required_ordering(&self) -> OrderingGuarantee {
+        self.ordering
+    }
+
+    async fn handle(&self, event: &SerializedEvent, _ctx: &HandlerContext) -> anyhow::Result<()> {
+        let mut tx = self.projection.begin().await?;
+        match self.apply_in(&mut tx, event).await {
+            Ok(true) => self.projection.commit(tx).await,
+            Ok(false) => self.projection.rollback(tx).await,
+            Err(err) => {
+                self.projection.rollback(tx).await?;
+                Err(err)
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use std::collections::BTreeMap;
+    use std::sync::Mutex;
+    use std::sync::atomic::{AtomicBool, Ordering};
+
+    /// 模拟数据库：读模型（按订单计数）与检查点
+    #[derive(Clone, Default, Debug, PartialEq)]
+    struct Db {
+        totals: BTreeMap<String, i64>,
+        checkpoints: BTreeMap<u32, i64>,
+    }
+
+    /// 事务在副本上修改，提交时整体替换
+    #[derive(Default)]
+    struct OrderTotals {
+        db: Mutex<Db>,
+        fail_commit: AtomicBool,
+    }
+
+    #[async_trait]
+    impl TransactionalProjection for OrderTotals {
+        type Tx = Db;
+
+        fn projection_name(&self) -> &str {
+            "order_totals"
+        }
+
+        async fn begin(&self) -> anyhow::Result<Db> {
+            Ok(self.db.lock().unwrap().clone())
+        }
+
+        async fn load_position(
+            &self,
+            tx: &mut Db,
+            partition: u32,
+        ) -> anyhow::Result<Option<EventPosition>> {
+            Ok(tx
+                .checkpoints
+                .get(&partition)
+                .map(|sequence| EventPosition::new(partition, *sequence)))
+        }
+
+        async fn apply(&self, tx: &mut Db, event: &SerializedEvent) -> anyhow::Result<()> {
+            let amount = event.payload()["amount"]
+                .as_i64()
+                .ok_or_else(|| anyhow::anyhow!("missing amount"))?;
+            *tx.totals
+                .entry(event.aggregate_id().to_string())
+                .or_default() += amount;
+            Ok(())
+        }
+
+        async fn save_position(&self, tx: &mut Db, position: EventPosition) -> anyhow::Result<()> {
+            tx.checkpoints.insert(position.partition, position.sequence);
+            Ok(())
+        }
+
+        async fn commit(&self, tx: Db) -> anyhow::Result<()> {
+            if self.fail_commit.swap(false, Ordering::SeqCst) {
+                anyhow::bail!("connection lost before commit");
+            }
+            *self.db.lock().unwrap() = tx;
+            Ok(())
+        }
+
+        async fn positions(&self) -> anyhow::Result<Vec<EventPosition>> {
+            Ok(self
+                .db
+                .lock()
+                .unwrap()
+                .checkpoints
+                .iter()
+                .map(|(partition, sequence)| EventPosition::new(*partition, *sequence))
+                .collect())
+        }
+    }
+
+    fn event(sequence: Option<i64>, payload: serde_json::Value) -> SerializedEvent {
+        SerializedEvent::builder()
+            .event_id(format!("e-{sequence:?}"))
+            .event_type("order.paid".to_string())
+            .event_version(1)
+            .maybe_sequence_number(sequence)
+            .aggregate_id("o-1".to_string())
+            .aggregate_type("order".to_string())
+            .aggregate_version(1)
+            .occurred_at(Utc::now())
+            .payload(payload)
+            .context(serde_json::json!({}))
+            .build()
+    }
+
+    #[tokio::test]
+    async fn applies_each_event_exactly_once_across_failures() {
+        let projection = Arc::new(OrderTotals::default());
+        let runner = TransactionalProjectionRunner::new(projection.clone());
+        let ctx = HandlerContext::default();
+        let paid = |sequence| event(Some(sequence), serde_json::json!({ "amount": 10 }));
+
+        runner.handle(&paid(1), &ctx).await.unwrap();
+
+        // 提交前崩溃：读模型与检查点都未写入，重投后只应用一次
+        projection.fail_commit.store(true, Ordering::SeqCst);
+        assert!(runner.handle(&paid(2), &ctx).await.is_err());
+        assert_eq!(projection.db.lock().unwrap().totals["o-1"], 10);
+        runner.handle(&paid(2), &ctx).await.unwrap();
+
+        // 已提交事件的重复投递被跳过
+        runner.handle(&paid(2), &ctx).await.unwrap();
+        runner.handle(&paid(1), &ctx).await.unwrap();
+
+        // 应用失败时检查点不推进
+        let malformed = event(Some(3), serde_json::json!({}));
+        assert!(runner.handle(&malformed, &ctx).await.is_err());
+
+        let db = projection.db.lock().unwrap().clone();
+        assert_eq!(db.totals["o-1"], 20);
+        assert_eq!(db.checkpoints, BTreeMap::from([(0, 2)]));
+        assert_eq!(
+            runner.resume_from().await.unwrap(),
+            SubscribeOptions::from_positions([EventPosition::new(0, 3)])
+        );
+    }
+
+    #[tokio::test]
+    async fn events_without_sequence_are_applied_without_checkpoint() {
+        let projection = Arc::new(OrderTotals::default());
+        let runner = TransactionalProjectionRunner::new(projection.clone());
+
+        runner
+            .handle(
+                &event(None, serde_json::json!({ "amount": 5 })),
+                &HandlerContext::default(),
+            )
+            .await
+            .unwrap();
+
+        let db = projection.db.lock().unwrap().clone();
+        assert_eq!(db.totals["o-1"], 5);
+        assert!(db.checkpoints.is_empty());
+        assert_eq!(runner.handler_name(), "order_totals");
+        assert_eq!(runner.required_ordering(), OrderingGuarantee::Unordered);
+    }
+}
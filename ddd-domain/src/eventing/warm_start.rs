@@ -0,0 +1,110 @@
+//! 引擎热启动（WarmStart）
+//!
+//! 引擎停机期间写入存储、却未经总线分发的事件，原本只能等待回收器周期性补偿。配置
+//! `EventEngine::builder().warm_start(...)` 后：
+//! - 引擎分发每个携带全局位点（`sequence_number`）的事件后，将已分发的最大位点写入
+//!   `CheckpointStore`（名称为 `warm_start.<引擎名>`）；
+//! - 启动时完成订阅后、开始投递前，从回放数据源读取检查点之后直到当前最新位点的事件，
+//!   依次分发给处理器，再进入常规运行；发生时间早于 `EngineTuning::with_max_catch_up_window`
+//!   的事件不追赶（计入 `skipped`），仍交由回收器补偿；
+//! - 追赶进度经 `EngineStatus::warm_start` 暴露；追赶过的事件随后经总线再次到达时跳过。
+//!
+//! 首次启动（尚无检查点）不追赶历史，仅开始记录检查点。
+//!
+use super::{CheckpointStore, ReplaySource};
+use crate::error::DomainResult as Result;
+use std::sync::{Arc, Mutex};
+
+/// 热启动检查点名称前缀（完整名称为 `warm_start.<引擎名>`）
+pub const WARM_START_CHECKPOINT_PREFIX: &str = "warm_start";
+
+/// 热启动配置：追赶的回放数据源与已分发位点的检查点存储
+#[derive(Clone)]
+pub struct WarmStart {
+    replay: Arc<dyn ReplaySource>,
+    checkpoints: Arc<dyn CheckpointStore>,
+}
+
+impl WarmStart {
+    pub fn new(replay: Arc<dyn ReplaySource>, checkpoints: Arc<dyn CheckpointStore>) -> Self {
+        Self {
+            replay,
+            checkpoints,
+        }
+    }
+
+    pub(crate) fn replay(&self) -> &dyn ReplaySource {
+        self.replay.as_ref()
+    }
+
+    /// 引擎上次分发到的全局位点
+    pub(crate) async fn load(&self, engine: &str) -> Result<Option<i64>> {
+        let checkpoint = self.checkpoints.load(&checkpoint_name(engine)).await?;
+        Ok(checkpoint.map(|c| c.sequence))
+    }
+
+    pub(crate) async fn save(&self, engine: &str, sequence: i64) -> Result<()> {
+        self.checkpoints
+            .save(&checkpoint_name(engine), sequence)
+            .await
+    }
+}
+
+fn checkpoint_name(engine: &str) -> String {
+    format!("{WARM_START_CHECKPOINT_PREFIX}.{engine}")
+}
+
+/// 热启动追赶进度
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct WarmStartStatus {
+    /// 停机前已分发的最大全局位点
+    pub from_sequence: i64,
+    /// 启动时存储的最新全局位点
+    pub head_sequence: i64,
+    /// 窗口内需要追赶的事件数
+    pub total: usize,
+    /// 已追赶的事件数
+    pub replayed: usize,
+    /// 超出追赶窗口、留给回收器的事件数
+    pub skipped: usize,
+    /// 追赶是否已结束
+    pub completed: bool,
+}
+
+/// 引擎内的追赶进度与已记录位点
+#[derive(Default)]
+pub(crate) struct WarmStartTracker {
+    status: Mutex<Option<WarmStartStatus>>,
+    delivered: Mutex<Option<i64>>,
+}
+
+impl WarmStartTracker {
+    pub(crate) fn status(&self) -> Option<WarmStartStatus> {
+        self.status.lock().unwrap().clone()
+    }
+
+    pub(crate) fn update(&self, f: impl FnOnce(&mut WarmStartStatus)) {
+        if let Some(status) = self.status.lock().unwrap().as_mut() {
+            f(status);
+        }
+    }
+
+    pub(crate) fn begin(&self, status: WarmStartStatus) {
+        *self.status.lock().unwrap() = Some(status);
+    }
+
+    /// 以检查点作为已记录位点，之后只推进不回退
+    pub(crate) fn resume(&self, sequence: i64) {
+        *self.delivered.lock().unwrap() = Some(sequence);
+    }
+
+    /// 位点超过已记录的最大值时推进并返回 `true`
+    pub(crate) fn advance(&self, sequence: i64) -> bool {
+        let mut delivered = self.delivered.lock().unwrap();
+        if delivered.is_some_and(|d| d >= sequence) {
+            return false;
+        }
+        *delivered = Some(sequence);
+        true
+    }
+}
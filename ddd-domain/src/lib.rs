@@ -1,14 +1,21 @@
 //! DDD 领域层基础库（ddd-domain）
 //!
 //! 提供以 DDD 为中心的通用抽象与构件，用于在应用中实现：
-//! - 聚合（`aggregate`）与实体（`entity`）建模
+//! - 聚合（`aggregate`）与实体（`entity`）建模，热点聚合的命令串行邮箱（`aggregate_mailbox`）
+//!   与按聚合 ID 的进程内锁（`aggregate_lock`）
 //! - 领域事件（`domain_event`）与事件上抬（`event_upcaster`）
 //! - 基于事件溯源与快照的仓储（`persist`）
 //! - 事件系统（`eventing`）：总线、投递/回收器、引擎与处理器
 //! - 规约（`specification`）与值对象（`value_object`）等通用模式
+//! - 限界上下文描述与启动时装配校验（`bounded_context`）
+//! - 从 TOML/环境变量加载运行配置（`config`，需启用 `config` 特性）
+//! - 从版本化的事件 JSON Schema 生成事件枚举与上抬骨架（`codegen`，需启用 `codegen` 特性）
+//! - 测试工具（`testing`，需启用 `testing` 特性）
 //!
 //! 本 crate 尽量保持与存储与传输实现解耦，仅定义领域层接口与最小必要的错误类型，
 //! 以便在不同基础设施（例如 Postgres、消息中间件等）上进行适配实现。
+//! 关闭默认特性（`eventing`）后不依赖 tokio，核心建模与事件上抬可编译到 `wasm32-unknown-unknown`，
+//! 在浏览器或边缘函数中运行同一套领域逻辑。
 //!
 //! 典型用法：
 //! 1. 定义聚合、命令与事件，实现在 `Aggregate` 上的 `execute/apply`；
@@ -17,7 +24,16 @@
 //! 4. 通过 `AggregateRoot` 编排一条完整的命令到事件持久化的流程。
 //!
 pub mod aggregate;
+#[cfg(feature = "eventing")]
+pub mod aggregate_lock;
+#[cfg(feature = "eventing")]
+pub mod aggregate_mailbox;
 pub mod aggregate_root;
+pub mod bounded_context;
+#[cfg(feature = "codegen")]
+pub mod codegen;
+#[cfg(feature = "config")]
+pub mod config;
 pub mod domain_event;
 pub mod domain_service;
 pub mod entity;
@@ -27,6 +43,8 @@ pub mod event_upcaster;
 pub mod eventing;
 pub mod persist;
 pub mod specification;
+#[cfg(feature = "testing")]
+pub mod testing;
 pub mod value_object;
 
 // 允许在本 crate 内部通过 ::ddd_domain 进行自引用，
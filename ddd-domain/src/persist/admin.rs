@@ -0,0 +1,185 @@
+//! 事件更正（break-glass 管理接口）
+//!
+//! 事件溯源中已写入的事件不可修改，更正须追加一条引用原事件的更正事件：
+//! - `EventAdmin::supersede` 校验原事件存在于聚合流中，要求执行主体与原因，
+//!   以聚合自身的更正事件（由聚合 `apply` 处理）追加到流尾；
+//! - 更正事件的因果 ID 指向原事件，上下文扩展 `correction` 记录被更正的事件 ID 与原因，
+//!   下游可经 `supersedes` 识别；
+//! - 每次更正写入审计记录（`CorrectionAuditStore`），事件写入成功但审计失败时返回审计错误，
+//!   避免静默丢失审计。
+//!
+//! 只追加、不就地修改，取代直接修改存储中行的“数据库手术”。
+//!
+use crate::{
+    aggregate::Aggregate,
+    domain_event::{DomainEvent, EventContext, EventEnvelope},
+    error::{DomainError, DomainResult as Result},
+    persist::{EventRepository, SerializedEvent, serialize_events},
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+/// 更正事件的执行主体类型
+pub const CORRECTION_ACTOR_TYPE: &str = "operator";
+
+/// 一次事件更正的审计记录
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CorrectionRecord {
+    /// 追加的更正事件 ID
+    pub correction_event_id: String,
+    pub correction_event_type: String,
+    /// 被更正的原事件 ID
+    pub supersedes_event_id: String,
+    pub aggregate_type: String,
+    pub aggregate_id: String,
+    pub actor: String,
+    pub reason: String,
+    pub corrected_at: DateTime<Utc>,
+}
+
+/// 更正审计存储
+#[async_trait]
+pub trait CorrectionAuditStore: Send + Sync {
+    async fn record(&self, record: CorrectionRecord) -> Result<()>;
+
+    /// 按记录顺序读取全部审计记录
+    async fn list(&self) -> Result<Vec<CorrectionRecord>>;
+}
+
+/// 基于内存的 CorrectionAuditStore 实现
+#[derive(Clone, Default)]
+pub struct InMemoryCorrectionAuditStore {
+    inner: Arc<Mutex<Vec<CorrectionRecord>>>,
+}
+
+impl InMemoryCorrectionAuditStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn records(&self) -> Vec<CorrectionRecord> {
+        self.inner.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl CorrectionAuditStore for InMemoryCorrectionAuditStore {
+    async fn record(&self, record: CorrectionRecord) -> Result<()> {
+        self.inner.lock().unwrap().push(record);
+        Ok(())
+    }
+
+    async fn list(&self) -> Result<Vec<CorrectionRecord>> {
+        Ok(self.records())
+    }
+}
+
+/// 更正事件所更正的原事件 ID；非更正事件返回 `None`
+pub fn supersedes(event: &SerializedEvent) -> Option<&str> {
+    event
+        .context()
+        .pointer("/extensions/correction/supersedes")
+        .and_then(|v| v.as_str())
+}
+
+/// 事件更正管理接口
+pub struct EventAdmin<R> {
+    events: R,
+    audit: Arc<dyn CorrectionAuditStore>,
+}
+
+impl<R> EventAdmin<R>
+where
+    R: EventRepository,
+{
+    pub fn new(events: R, audit: Arc<dyn CorrectionAuditStore>) -> Self {
+        Self { events, audit }
+    }
+
+    /// 追加更正事件以取代聚合流中的 `original_event_id`
+    ///
+    /// `correction` 须为聚合的下一个版本；原事件不存在（或已被排除）返回 `NotFound`，
+    /// 执行主体或原因为空返回 `InvalidValue`，版本不连续返回 `Conflict`。
+    pub async fn supersede<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        original_event_id: &str,
+        correction: A::Event,
+        actor: &str,
+        reason: &str,
+    ) -> Result<CorrectionRecord> {
+        if actor.trim().is_empty() {
+            return Err(
+                DomainError::invalid_value("event correction requires an actor")
+                    .with_code("CORRECTION_ACTOR_REQUIRED"),
+            );
+        }
+        if reason.trim().is_empty() {
+            return Err(
+                DomainError::invalid_value("event correction requires a reason")
+                    .with_code("CORRECTION_REASON_REQUIRED"),
+            );
+        }
+
+        let stream = self.events.get_events::<A>(aggregate_id).await?;
+        let original = stream
+            .iter()
+            .find(|e| e.event_id() == original_event_id)
+            .ok_or_else(|| {
+                DomainError::not_found(format!(
+                    "event {original_event_id} not found in {}/{aggregate_id}",
+                    A::TYPE
+                ))
+            })?;
+
+        let expected = self.events.current_version::<A>(aggregate_id).await? + 1;
+        let version = correction.aggregate_version().value();
+        if version != expected {
+            return Err(DomainError::conflict(expected, version));
+        }
+
+        let mut context = EventContext::builder()
+            .correlation_id(
+                original
+                    .correlation_id()
+                    .unwrap_or(original.event_id())
+                    .to_string(),
+            )
+            .causation_id(original.event_id().to_string())
+            .extensions(serde_json::json!({
+                "correction": { "supersedes": original.event_id(), "reason": reason }
+            }))
+            .build();
+        context.set_actor(CORRECTION_ACTOR_TYPE, actor);
+
+        let envelope = EventEnvelope::<A>::new(aggregate_id, correction, context);
+        let serialized = serialize_events(std::slice::from_ref(&envelope))?;
+        let record = CorrectionRecord {
+            correction_event_id: envelope.payload.event_id().to_string(),
+            correction_event_type: envelope.payload.event_type().to_string(),
+            supersedes_event_id: original.event_id().to_string(),
+            aggregate_type: A::TYPE.to_string(),
+            aggregate_id: aggregate_id.to_string(),
+            actor: actor.to_string(),
+            reason: reason.to_string(),
+            corrected_at: Utc::now(),
+        };
+
+        self.events.save(serialized).await?;
+        self.audit.record(record.clone()).await?;
+        Ok(record)
+    }
+
+    /// 原事件的全部更正记录（按记录顺序）
+    pub async fn corrections_of(&self, event_id: &str) -> Result<Vec<CorrectionRecord>> {
+        Ok(self
+            .audit
+            .list()
+            .await?
+            .into_iter()
+            .filter(|r| r.supersedes_event_id == event_id)
+            .collect())
+    }
+}
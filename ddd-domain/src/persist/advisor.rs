@@ -0,0 +1,261 @@
+//! 聚合事件流分析建议（advisor）
+//!
+//! 根据事件流统计（日均事件数、负载大小、并发冲突、距上次快照的事件数等）
+//! 标记超过阈值的聚合，并给出结构化建议（提高快照频率、考虑拆分聚合），
+//! 结果可直接序列化供运维看板展示。
+//!
+use crate::persist::SerializedEvent;
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+
+/// 单个聚合事件流的统计
+#[derive(Debug, Clone, Default, PartialEq, Serialize)]
+pub struct StreamStats {
+    pub aggregate_type: String,
+    pub aggregate_id: String,
+    /// 事件总数
+    pub event_count: usize,
+    /// 日均事件数（按首个事件至统计时刻计算，不足一天按一天计）
+    pub events_per_day: f64,
+    /// 单个事件负载的最大字节数
+    pub max_payload_bytes: usize,
+    /// 统计周期内的乐观锁冲突次数（由调用方提供）
+    pub conflicts: u64,
+    /// 最近快照之后的事件数（无快照时为事件总数）
+    pub events_since_snapshot: usize,
+}
+
+impl StreamStats {
+    /// 从事件流计算统计；`conflicts` 与快照信息需另行填充
+    pub fn from_events(events: &[SerializedEvent], now: DateTime<Utc>) -> Option<Self> {
+        let first = events.first()?;
+
+        let days = (now - first.occurred_at()).num_seconds() as f64 / 86_400.0;
+        let max_payload_bytes = events
+            .iter()
+            .map(|e| e.payload().to_string().len())
+            .max()
+            .unwrap_or_default();
+
+        Some(Self {
+            aggregate_type: first.aggregate_type().to_string(),
+            aggregate_id: first.aggregate_id().to_string(),
+            event_count: events.len(),
+            events_per_day: events.len() as f64 / days.max(1.0),
+            max_payload_bytes,
+            conflicts: 0,
+            events_since_snapshot: events.len(),
+        })
+    }
+
+    pub fn with_conflicts(mut self, conflicts: u64) -> Self {
+        self.conflicts = conflicts;
+        self
+    }
+
+    /// 以最近快照版本更新 `events_since_snapshot`
+    pub fn with_snapshot_version(mut self, snapshot_version: usize) -> Self {
+        self.events_since_snapshot = self.event_count.saturating_sub(snapshot_version);
+        self
+    }
+}
+
+/// 判定阈值
+#[derive(Debug, Clone, Copy, PartialEq, Serialize)]
+pub struct AdvisorThresholds {
+    pub max_events_since_snapshot: usize,
+    pub max_event_count: usize,
+    pub max_events_per_day: f64,
+    pub max_payload_bytes: usize,
+    pub max_conflicts: u64,
+}
+
+impl Default for AdvisorThresholds {
+    fn default() -> Self {
+        Self {
+            max_events_since_snapshot: 200,
+            max_event_count: 10_000,
+            max_events_per_day: 1_000.0,
+            max_payload_bytes: 64 * 1024,
+            max_conflicts: 50,
+        }
+    }
+}
+
+/// 建议类型
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Recommendation {
+    /// 提高快照频率，缩短重建时的重放长度
+    IncreaseSnapshotFrequency,
+    /// 考虑拆分聚合（事件过多/过热/过大或冲突频繁）
+    ConsiderSplitting,
+}
+
+/// 触发建议的指标
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AdvisorMetric {
+    EventsSinceSnapshot,
+    EventCount,
+    EventsPerDay,
+    PayloadBytes,
+    Conflicts,
+}
+
+/// 单条建议
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Advice {
+    pub aggregate_type: String,
+    pub aggregate_id: String,
+    pub recommendation: Recommendation,
+    pub metric: AdvisorMetric,
+    pub observed: f64,
+    pub threshold: f64,
+}
+
+/// 事件流分析器
+#[derive(Debug, Clone, Copy, Default)]
+pub struct StreamAdvisor {
+    thresholds: AdvisorThresholds,
+}
+
+impl StreamAdvisor {
+    pub fn new(thresholds: AdvisorThresholds) -> Self {
+        Self { thresholds }
+    }
+
+    /// 分析一批事件流统计，返回超出阈值的建议（按输入顺序）
+    pub fn analyze<'a, I>(&self, stats: I) -> Vec<Advice>
+    where
+        I: IntoIterator<Item = &'a StreamStats>,
+    {
+        let t = &self.thresholds;
+        let mut out = Vec::new();
+
+        for s in stats {
+            let checks = [
+                (
+                    Recommendation::IncreaseSnapshotFrequency,
+                    AdvisorMetric::EventsSinceSnapshot,
+                    s.events_since_snapshot as f64,
+                    t.max_events_since_snapshot as f64,
+                ),
+                (
+                    Recommendation::ConsiderSplitting,
+                    AdvisorMetric::EventCount,
+                    s.event_count as f64,
+                    t.max_event_count as f64,
+                ),
+                (
+                    Recommendation::ConsiderSplitting,
+                    AdvisorMetric::EventsPerDay,
+                    s.events_per_day,
+                    t.max_events_per_day,
+                ),
+                (
+                    Recommendation::ConsiderSplitting,
+                    AdvisorMetric::PayloadBytes,
+                    s.max_payload_bytes as f64,
+                    t.max_payload_bytes as f64,
+                ),
+                (
+                    Recommendation::ConsiderSplitting,
+                    AdvisorMetric::Conflicts,
+                    s.conflicts as f64,
+                    t.max_conflicts as f64,
+                ),
+            ];
+
+            for (recommendation, metric, observed, threshold) in checks {
+                if observed > threshold {
+                    out.push(Advice {
+                        aggregate_type: s.aggregate_type.clone(),
+                        aggregate_id: s.aggregate_id.clone(),
+                        recommendation,
+                        metric,
+                        observed,
+                        threshold,
+                    });
+                }
+            }
+        }
+
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    fn mk_event(version: usize, occurred_at: DateTime<Utc>) -> SerializedEvent {
+        SerializedEvent::builder()
+            .event_id(format!("e-{version}"))
+            .event_type("order.line_added".to_string())
+            .event_version(1)
+            .aggregate_id("o-1".to_string())
+            .aggregate_type("order".to_string())
+            .aggregate_version(version)
+            .occurred_at(occurred_at)
+            .payload(serde_json::json!({"sku": "x".repeat(version)}))
+            .context(serde_json::json!({}))
+            .build()
+    }
+
+    #[test]
+    fn stats_are_computed_from_stream() {
+        let now = Utc::now();
+        let events: Vec<_> = (1..=40)
+            .map(|v| mk_event(v, now - Duration::days(4)))
+            .collect();
+
+        let stats = StreamStats::from_events(&events, now)
+            .unwrap()
+            .with_snapshot_version(30)
+            .with_conflicts(3);
+
+        assert_eq!(stats.aggregate_type, "order");
+        assert_eq!(stats.event_count, 40);
+        assert!((stats.events_per_day - 10.0).abs() < 0.1);
+        assert_eq!(stats.max_payload_bytes, r#"{"sku":""}"#.len() + 40);
+        assert_eq!(stats.events_since_snapshot, 10);
+        assert!(StreamStats::from_events(&[], now).is_none());
+    }
+
+    #[test]
+    fn flags_only_streams_over_thresholds() {
+        let advisor = StreamAdvisor::new(AdvisorThresholds {
+            max_events_since_snapshot: 100,
+            max_conflicts: 5,
+            ..Default::default()
+        });
+        let healthy = StreamStats {
+            aggregate_type: "order".into(),
+            aggregate_id: "o-1".into(),
+            event_count: 50,
+            events_since_snapshot: 50,
+            ..Default::default()
+        };
+        let hot = StreamStats {
+            aggregate_id: "o-2".into(),
+            events_since_snapshot: 150,
+            conflicts: 9,
+            ..healthy.clone()
+        };
+
+        let advice = advisor.analyze([&healthy, &hot]);
+        assert_eq!(advice.len(), 2);
+        assert!(advice.iter().all(|a| a.aggregate_id == "o-2"));
+        assert_eq!(
+            advice[0].recommendation,
+            Recommendation::IncreaseSnapshotFrequency
+        );
+        assert_eq!(advice[1].metric, AdvisorMetric::Conflicts);
+        assert_eq!(
+            serde_json::to_value(&advice[1]).unwrap()["recommendation"],
+            "consider_splitting"
+        );
+    }
+}
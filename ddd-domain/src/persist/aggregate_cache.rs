@@ -0,0 +1,238 @@
+//! 聚合状态缓存
+//!
+//! `CachedAggregateRepo` 作为 `AggregateRepository` 的装饰器，按 (聚合类型, 聚合 ID) 缓存重建后的聚合状态
+//! （以 JSON 保存，无需聚合实现 `Clone`）：
+//! - `load` 命中缓存时不访问底层仓储；未命中时加载并写入缓存；
+//! - `save` 成功后以保存后的状态更新缓存，失败（如并发冲突）时淘汰该条目，下次加载回源；
+//! - 超出容量时按写入顺序淘汰最早的条目。
+//!
+//! 缓存假定同一聚合的写入经由本进程（或由命令邮箱串行化）；其他进程写入后缓存状态可能落后，
+//! 此时保存会因版本冲突失败并淘汰条目，重试即回源。
+//!
+//! `AggregateWarmer` 供命令总线在处理器执行前预热缓存（见 `ddd_application::prefetch`），
+//! `CachedAggregateRepo::warmer::<A>()` 为某个聚合类型提供实现。
+//!
+use crate::{
+    aggregate::Aggregate,
+    domain_event::{EventContext, EventEnvelope},
+    error::{DomainError, DomainResult},
+    persist::AggregateRepository,
+};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::{HashMap, VecDeque};
+use std::marker::PhantomData;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+type CacheKey = (&'static str, String);
+
+#[derive(Default)]
+struct Entries {
+    states: HashMap<CacheKey, Value>,
+    order: VecDeque<CacheKey>,
+}
+
+/// 缓存统计
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct AggregateCacheStats {
+    pub hits: u64,
+    pub misses: u64,
+    pub entries: usize,
+}
+
+/// 带状态缓存的聚合仓储装饰器
+pub struct CachedAggregateRepo<R> {
+    inner: R,
+    capacity: usize,
+    entries: Mutex<Entries>,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+impl<R> CachedAggregateRepo<R> {
+    /// 默认容量 10,000 个聚合
+    pub fn new(inner: R) -> Self {
+        Self {
+            inner,
+            capacity: 10_000,
+            entries: Mutex::new(Entries::default()),
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn with_capacity(mut self, capacity: usize) -> Self {
+        self.capacity = capacity.max(1);
+        self
+    }
+
+    pub fn inner(&self) -> &R {
+        &self.inner
+    }
+
+    pub fn stats(&self) -> AggregateCacheStats {
+        AggregateCacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+            entries: self.entries.lock().unwrap().states.len(),
+        }
+    }
+
+    /// 聚合是否已缓存
+    pub fn contains<A: Aggregate>(&self, aggregate_id: &A::Id) -> bool {
+        self.entries
+            .lock()
+            .unwrap()
+            .states
+            .contains_key(&(A::TYPE, aggregate_id.to_string()))
+    }
+
+    /// 淘汰单个聚合的缓存
+    pub fn invalidate<A: Aggregate>(&self, aggregate_id: &A::Id) {
+        let key = (A::TYPE, aggregate_id.to_string());
+        let mut entries = self.entries.lock().unwrap();
+        if entries.states.remove(&key).is_some() {
+            entries.order.retain(|k| *k != key);
+        }
+    }
+
+    /// 预热单个聚合：已缓存时跳过，否则从底层仓储加载并写入缓存；返回是否发生加载
+    pub async fn warm<A>(&self, aggregate_id: &A::Id) -> Result<bool, A::Error>
+    where
+        A: Aggregate,
+        R: AggregateRepository<A>,
+    {
+        if self.contains::<A>(aggregate_id) {
+            return Ok(false);
+        }
+        if let Some(aggregate) = self.inner.load(aggregate_id).await? {
+            self.store(&aggregate);
+        }
+        Ok(true)
+    }
+
+    /// 为聚合类型 `A` 提供缓存预热器
+    pub fn warmer<A>(self: &Arc<Self>) -> CacheWarmer<A, R> {
+        CacheWarmer {
+            cache: Arc::clone(self),
+            _marker: PhantomData,
+        }
+    }
+
+    fn cached<A: Aggregate>(&self, aggregate_id: &A::Id) -> Option<A> {
+        let key = (A::TYPE, aggregate_id.to_string());
+        let state = self.entries.lock().unwrap().states.get(&key).cloned()?;
+        match serde_json::from_value(state) {
+            Ok(aggregate) => Some(aggregate),
+            Err(_) => {
+                self.invalidate::<A>(aggregate_id);
+                None
+            }
+        }
+    }
+
+    fn after_save<A: Aggregate>(&self, aggregate: &A, saved: bool) {
+        if saved {
+            self.store(aggregate);
+        } else {
+            self.invalidate::<A>(aggregate.id());
+        }
+    }
+
+    fn store<A: Aggregate>(&self, aggregate: &A) {
+        let Ok(state) = serde_json::to_value(aggregate) else {
+            self.invalidate::<A>(aggregate.id());
+            return;
+        };
+        let key = (A::TYPE, aggregate.id().to_string());
+        let mut entries = self.entries.lock().unwrap();
+        if entries.states.insert(key.clone(), state).is_none() {
+            entries.order.push_back(key);
+        }
+        while entries.states.len() > self.capacity {
+            let Some(oldest) = entries.order.pop_front() else {
+                break;
+            };
+            entries.states.remove(&oldest);
+        }
+    }
+}
+
+#[async_trait]
+impl<A, R> AggregateRepository<A> for CachedAggregateRepo<R>
+where
+    A: Aggregate,
+    R: AggregateRepository<A>,
+{
+    async fn load(&self, aggregate_id: &A::Id) -> Result<Option<A>, A::Error> {
+        if let Some(aggregate) = self.cached::<A>(aggregate_id) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(Some(aggregate));
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+        let aggregate = self.inner.load(aggregate_id).await?;
+        if let Some(aggregate) = &aggregate {
+            self.store(aggregate);
+        }
+        Ok(aggregate)
+    }
+
+    async fn save(
+        &self,
+        aggregate: &A,
+        events: Vec<A::Event>,
+        context: EventContext,
+    ) -> Result<Vec<EventEnvelope<A>>, A::Error> {
+        let saved = self.inner.save(aggregate, events, context).await;
+        self.after_save(aggregate, saved.is_ok());
+        saved
+    }
+
+    async fn save_envelopes(
+        &self,
+        aggregate: &A,
+        envelopes: Vec<EventEnvelope<A>>,
+    ) -> Result<Vec<EventEnvelope<A>>, A::Error> {
+        let saved = self.inner.save_envelopes(aggregate, envelopes).await;
+        self.after_save(aggregate, saved.is_ok());
+        saved
+    }
+}
+
+/// 按聚合类型预热聚合缓存
+#[async_trait]
+pub trait AggregateWarmer: Send + Sync {
+    /// 负责的聚合类型（`Aggregate::TYPE`）
+    fn aggregate_type(&self) -> &'static str;
+
+    async fn warm(&self, aggregate_id: &str) -> DomainResult<()>;
+}
+
+/// `CachedAggregateRepo` 针对聚合类型 `A` 的预热器
+pub struct CacheWarmer<A, R> {
+    cache: Arc<CachedAggregateRepo<R>>,
+    _marker: PhantomData<fn() -> A>,
+}
+
+#[async_trait]
+impl<A, R> AggregateWarmer for CacheWarmer<A, R>
+where
+    A: Aggregate,
+    R: AggregateRepository<A>,
+{
+    fn aggregate_type(&self) -> &'static str {
+        A::TYPE
+    }
+
+    async fn warm(&self, aggregate_id: &str) -> DomainResult<()> {
+        let id = aggregate_id.parse::<A::Id>().map_err(|_| {
+            DomainError::invalid_value(format!("invalid {} id: {aggregate_id}", A::TYPE))
+        })?;
+        self.cache
+            .warm::<A>(&id)
+            .await
+            .map_err(|e| DomainError::internal(e.to_string()))?;
+        Ok(())
+    }
+}
@@ -0,0 +1,368 @@
+//! 聚合二级索引（业务键 → 聚合 ID）
+//!
+//! 按自然键（如邮箱）定位聚合时，无需为此单独维护读模型：
+//! - `AggregateIndexes` 声明从事件中提取业务键的函数（如见到 `user.registered` 时取 `email`），
+//!   以及释放键的事件（如 `user.deleted`）；
+//! - `IndexedEventRepo` 装饰事件仓储，保存事件前按声明更新 `AggregateIndexStore`，
+//!   键已属于其他聚合时整批拒绝并返回 `ErrorKind::Conflict`（`INDEX_KEY_TAKEN`）；
+//!   事件保存失败时撤销本批次的索引变更；
+//! - 同一聚合在同一索引下只保留一个键，分配新键时释放旧键（如修改邮箱）。
+//!
+use crate::{
+    aggregate::Aggregate,
+    error::{DomainError, DomainResult as Result},
+    persist::{AggregateIdLister, EventExclusion, EventRepository, SerializedEvent},
+};
+use async_trait::async_trait;
+use serde_json::Value;
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// 索引存储：按 (聚合类型, 索引名, 业务键) 记录聚合 ID
+#[async_trait]
+pub trait AggregateIndexStore: Send + Sync {
+    async fn lookup(&self, aggregate_type: &str, index: &str, key: &str) -> Result<Option<String>>;
+
+    /// 将业务键指向聚合，返回该聚合在此索引下被替换的旧键
+    ///
+    /// 键已属于其他聚合时返回 `ErrorKind::Conflict`（`INDEX_KEY_TAKEN`）。
+    async fn assign(
+        &self,
+        aggregate_type: &str,
+        index: &str,
+        key: &str,
+        aggregate_id: &str,
+    ) -> Result<Option<String>>;
+
+    /// 释放聚合在此索引下的键，返回被释放的键
+    async fn release(
+        &self,
+        aggregate_type: &str,
+        index: &str,
+        aggregate_id: &str,
+    ) -> Result<Option<String>>;
+}
+
+#[async_trait]
+impl<T> AggregateIndexStore for Arc<T>
+where
+    T: AggregateIndexStore + ?Sized,
+{
+    async fn lookup(&self, aggregate_type: &str, index: &str, key: &str) -> Result<Option<String>> {
+        (**self).lookup(aggregate_type, index, key).await
+    }
+
+    async fn assign(
+        &self,
+        aggregate_type: &str,
+        index: &str,
+        key: &str,
+        aggregate_id: &str,
+    ) -> Result<Option<String>> {
+        (**self)
+            .assign(aggregate_type, index, key, aggregate_id)
+            .await
+    }
+
+    async fn release(
+        &self,
+        aggregate_type: &str,
+        index: &str,
+        aggregate_id: &str,
+    ) -> Result<Option<String>> {
+        (**self).release(aggregate_type, index, aggregate_id).await
+    }
+}
+
+#[async_trait]
+pub trait AggregateIndexStoreExt: AggregateIndexStore {
+    /// 按业务键查找聚合 ID
+    async fn find<A: Aggregate>(&self, index: &str, key: &str) -> Result<Option<A::Id>> {
+        let Some(id) = self.lookup(A::TYPE, index, key).await? else {
+            return Ok(None);
+        };
+        A::Id::from_str(&id).map(Some).map_err(|_| {
+            DomainError::invalid_value(format!("invalid {} id in index {index}: {id}", A::TYPE))
+        })
+    }
+}
+
+#[async_trait]
+impl<T> AggregateIndexStoreExt for T where T: AggregateIndexStore + ?Sized {}
+
+type KeyExtractor = Arc<dyn Fn(&SerializedEvent) -> Option<String> + Send + Sync>;
+
+#[derive(Clone)]
+enum IndexAction {
+    Assign(KeyExtractor),
+    Release,
+}
+
+#[derive(Clone)]
+struct IndexRule {
+    index: String,
+    action: IndexAction,
+}
+
+/// 索引声明：事件类型 → 索引更新
+#[derive(Clone, Default)]
+pub struct AggregateIndexes {
+    rules: HashMap<String, Vec<IndexRule>>,
+}
+
+impl AggregateIndexes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 见到 `event_type` 时以 `extract` 提取的业务键更新索引；返回 `None` 表示不变更
+    pub fn index<F>(
+        mut self,
+        index: impl Into<String>,
+        event_type: impl Into<String>,
+        extract: F,
+    ) -> Self
+    where
+        F: Fn(&SerializedEvent) -> Option<String> + Send + Sync + 'static,
+    {
+        self.rules
+            .entry(event_type.into())
+            .or_default()
+            .push(IndexRule {
+                index: index.into(),
+                action: IndexAction::Assign(Arc::new(extract)),
+            });
+        self
+    }
+
+    /// 以事件载荷中的字段（相对载荷的 JSON Pointer，值为字符串或数字）作为业务键
+    pub fn field(
+        self,
+        index: impl Into<String>,
+        event_type: impl Into<String>,
+        pointer: impl Into<String>,
+    ) -> Self {
+        let pointer = pointer.into();
+        self.index(index, event_type, move |event| {
+            match event.payload().pointer(&pointer)? {
+                Value::String(s) => Some(s.clone()),
+                Value::Number(n) => Some(n.to_string()),
+                _ => None,
+            }
+        })
+    }
+
+    /// 见到 `event_type` 时释放聚合在该索引下的键
+    pub fn release_on(mut self, index: impl Into<String>, event_type: impl Into<String>) -> Self {
+        self.rules
+            .entry(event_type.into())
+            .or_default()
+            .push(IndexRule {
+                index: index.into(),
+                action: IndexAction::Release,
+            });
+        self
+    }
+
+    fn rules_for(&self, event_type: &str) -> &[IndexRule] {
+        self.rules.get(event_type).map(Vec::as_slice).unwrap_or(&[])
+    }
+}
+
+/// 已执行的索引变更，用于事件保存失败时撤销
+enum Applied {
+    Assigned {
+        aggregate_type: String,
+        index: String,
+        aggregate_id: String,
+        previous: Option<String>,
+    },
+    Released {
+        aggregate_type: String,
+        index: String,
+        aggregate_id: String,
+        previous: Option<String>,
+    },
+}
+
+/// 按声明自动维护二级索引的 `EventRepository` 装饰器
+pub struct IndexedEventRepo<E, I> {
+    inner: Arc<E>,
+    store: Arc<I>,
+    indexes: AggregateIndexes,
+}
+
+impl<E, I> IndexedEventRepo<E, I>
+where
+    E: EventRepository,
+    I: AggregateIndexStore,
+{
+    pub fn new(inner: Arc<E>, store: Arc<I>, indexes: AggregateIndexes) -> Self {
+        Self {
+            inner,
+            store,
+            indexes,
+        }
+    }
+
+    pub fn store(&self) -> &Arc<I> {
+        &self.store
+    }
+
+    async fn apply(&self, events: &[SerializedEvent]) -> Result<Vec<Applied>> {
+        let mut applied = Vec::new();
+
+        for event in events {
+            let (aggregate_type, aggregate_id) = (event.aggregate_type(), event.aggregate_id());
+            for rule in self.indexes.rules_for(event.event_type()) {
+                let result = match &rule.action {
+                    IndexAction::Assign(extract) => match extract(event) {
+                        Some(key) => self
+                            .store
+                            .assign(aggregate_type, &rule.index, &key, aggregate_id)
+                            .await
+                            .map(|previous| {
+                                Some(Applied::Assigned {
+                                    aggregate_type: aggregate_type.to_string(),
+                                    index: rule.index.clone(),
+                                    aggregate_id: aggregate_id.to_string(),
+                                    previous,
+                                })
+                            }),
+                        None => Ok(None),
+                    },
+                    IndexAction::Release => self
+                        .store
+                        .release(aggregate_type, &rule.index, aggregate_id)
+                        .await
+                        .map(|previous| {
+                            Some(Applied::Released {
+                                aggregate_type: aggregate_type.to_string(),
+                                index: rule.index.clone(),
+                                aggregate_id: aggregate_id.to_string(),
+                                previous,
+                            })
+                        }),
+                };
+
+                match result {
+                    Ok(change) => applied.extend(change),
+                    Err(err) => {
+                        self.undo(applied).await;
+                        return Err(err);
+                    }
+                }
+            }
+        }
+
+        Ok(applied)
+    }
+
+    /// 逆序撤销索引变更（尽力而为）
+    async fn undo(&self, applied: Vec<Applied>) {
+        for change in applied.into_iter().rev() {
+            match change {
+                Applied::Assigned {
+                    aggregate_type,
+                    index,
+                    aggregate_id,
+                    previous,
+                } => {
+                    let _ = self
+                        .store
+                        .release(&aggregate_type, &index, &aggregate_id)
+                        .await;
+                    if let Some(key) = previous {
+                        let _ = self
+                            .store
+                            .assign(&aggregate_type, &index, &key, &aggregate_id)
+                            .await;
+                    }
+                }
+                Applied::Released {
+                    aggregate_type,
+                    index,
+                    aggregate_id,
+                    previous: Some(key),
+                } => {
+                    let _ = self
+                        .store
+                        .assign(&aggregate_type, &index, &key, &aggregate_id)
+                        .await;
+                }
+                Applied::Released { previous: None, .. } => {}
+            }
+        }
+    }
+}
+
+#[async_trait]
+impl<E, I> EventRepository for IndexedEventRepo<E, I>
+where
+    E: EventRepository,
+    I: AggregateIndexStore,
+{
+    async fn get_events<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<Vec<SerializedEvent>> {
+        self.inner.get_events::<A>(aggregate_id).await
+    }
+
+    async fn get_last_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        last_version: usize,
+    ) -> Result<Vec<SerializedEvent>> {
+        self.inner
+            .get_last_events::<A>(aggregate_id, last_version)
+            .await
+    }
+
+    async fn save(&self, events: Vec<SerializedEvent>) -> Result<()> {
+        let applied = self.apply(&events).await?;
+
+        if let Err(err) = self.inner.save(events).await {
+            self.undo(applied).await;
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    async fn ping(&self) -> Result<()> {
+        self.inner.ping().await
+    }
+
+    async fn current_version<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<usize> {
+        self.inner.current_version::<A>(aggregate_id).await
+    }
+
+    async fn exists<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<bool> {
+        self.inner.exists::<A>(aggregate_id).await
+    }
+
+    async fn exists_many<A: Aggregate>(&self, aggregate_ids: &[A::Id]) -> Result<Vec<bool>> {
+        self.inner.exists_many::<A>(aggregate_ids).await
+    }
+
+    async fn mark_excluded(&self, event_id: &str, reason: &str, actor: &str) -> Result<()> {
+        self.inner.mark_excluded(event_id, reason, actor).await
+    }
+
+    async fn exclusions(&self) -> Result<Vec<EventExclusion>> {
+        self.inner.exclusions().await
+    }
+}
+
+#[async_trait]
+impl<E, I> AggregateIdLister for IndexedEventRepo<E, I>
+where
+    E: EventRepository + AggregateIdLister,
+    I: AggregateIndexStore,
+{
+    async fn list_aggregate_ids<A: Aggregate>(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>> {
+        self.inner.list_aggregate_ids::<A>(after, limit).await
+    }
+}
@@ -0,0 +1,34 @@
+//! 按类型列举聚合 ID（AggregateIdLister）
+//!
+//! `EventRepository` 只能按已知的聚合 ID 读写事件；批量维护（`MaintenanceRunner`）需要遍历某类型的
+//! 全部聚合。支持列举的存储后端实现该能力，仓储装饰器在内层支持时同样实现并转发。
+//!
+use crate::{aggregate::Aggregate, error::DomainResult as Result};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// 按 ID 升序分页列举某类型的全部聚合 ID
+#[async_trait]
+pub trait AggregateIdLister: Send + Sync {
+    /// 返回 `after` 之后的至多 `limit` 个聚合 ID；以上一页最后一个 ID 作为下一页的 `after`，
+    /// 返回空表示已遍历完
+    async fn list_aggregate_ids<A: Aggregate>(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>>;
+}
+
+#[async_trait]
+impl<T> AggregateIdLister for Arc<T>
+where
+    T: AggregateIdLister + ?Sized,
+{
+    async fn list_aggregate_ids<A: Aggregate>(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>> {
+        (**self).list_aggregate_ids::<A>(after, limit).await
+    }
+}
@@ -3,16 +3,24 @@
 //! 基于事件溯源（Event Store）与快照（Snapshot）的通用聚合仓储实现，
 //! 通过事件上抬链在重建过程中完成旧事件兼容。
 //!
+use crate::domain_event::DomainEvent;
 use crate::error::DomainError;
-use crate::persist::SnapshotRepositoryWithPolicy;
+#[cfg(feature = "eventing")]
+use crate::persist::{BackgroundSnapshotter, SerializedSnapshot};
+use crate::persist::{LifecycleEventKind, LifecycleEvents, SnapshotRepositoryWithPolicy};
 use crate::{
     aggregate::Aggregate,
     domain_event::{EventContext, EventEnvelope},
     event_upcaster::EventUpcasterChain,
-    persist::{EventRepository, SnapshotRepository, deserialize_events, serialize_events},
+    persist::{
+        ConflictResolver, EventRepository, RejectConflicts, ReplicaClock, SnapshotRepository,
+        UnknownEventPolicy, apply_events_with, resolve_divergence, serialize_events,
+    },
     value_object::Version,
 };
 use async_trait::async_trait;
+#[cfg(feature = "eventing")]
+use futures_util::future::BoxFuture;
 use std::sync::Arc;
 
 #[async_trait]
@@ -28,6 +36,23 @@ where
         events: Vec<A::Event>,
         context: EventContext,
     ) -> Result<Vec<EventEnvelope<A>>, A::Error>;
+
+    /// 保存已构造的事件信封（可携带 `state_snapshot` 等附件）
+    ///
+    /// 默认实现退化为 `save`，信封附件会被丢弃；需要持久化附件的仓储应覆盖该方法。
+    async fn save_envelopes(
+        &self,
+        aggregate: &A,
+        envelopes: Vec<EventEnvelope<A>>,
+    ) -> Result<Vec<EventEnvelope<A>>, A::Error> {
+        let context = envelopes
+            .first()
+            .map(|e| e.context.clone())
+            .unwrap_or_default();
+        let events = envelopes.into_iter().map(|e| e.payload).collect();
+
+        self.save(aggregate, events, context).await
+    }
 }
 
 #[async_trait]
@@ -48,14 +73,30 @@ where
     ) -> Result<Vec<EventEnvelope<A>>, A::Error> {
         (**self).save(aggregate, events, context).await
     }
+
+    async fn save_envelopes(
+        &self,
+        aggregate: &A,
+        envelopes: Vec<EventEnvelope<A>>,
+    ) -> Result<Vec<EventEnvelope<A>>, A::Error> {
+        (**self).save_envelopes(aggregate, envelopes).await
+    }
 }
 
 /// 基于事件存储的通用聚合仓储实现。
-/// - 使用 `EventRepository` 读取/保存事件
+/// - 使用 `EventRepository` 读取/保存事件，保存时以加载时的版本调用 `save_with_expected_version`，
+///   期间有其他写入则返回 `Conflict`
 /// - 在重建聚合时通过 `EventUpcasterChain` 对事件进行上抬
+/// - 配置生命周期事件后，首个事件持久化时产生 `<type>.created`
+/// - 重建时遇到未知事件类型按 `UnknownEventPolicy` 处理（默认失败）
+/// - 重建时检测多活副本的分叉历史，按 `ConflictResolver` 处理（默认拒绝）
 pub struct EventSourcedRepo<E> {
     event_repo: Arc<E>,
     upcaster_chain: Arc<EventUpcasterChain>,
+    lifecycle: Option<LifecycleEvents>,
+    unknown_events: UnknownEventPolicy,
+    replica_clock: Option<Arc<ReplicaClock>>,
+    conflict_resolver: Arc<dyn ConflictResolver>,
 }
 
 impl<E> EventSourcedRepo<E>
@@ -66,9 +107,37 @@ where
         Self {
             event_repo,
             upcaster_chain,
+            lifecycle: None,
+            unknown_events: UnknownEventPolicy::default(),
+            replica_clock: None,
+            conflict_resolver: Arc::new(RejectConflicts),
         }
     }
 
+    /// 启用生命周期事件
+    pub fn with_lifecycle_events(mut self, lifecycle: LifecycleEvents) -> Self {
+        self.lifecycle = Some(lifecycle);
+        self
+    }
+
+    /// 重建时未知事件类型的处理策略
+    pub fn with_unknown_event_policy(mut self, policy: UnknownEventPolicy) -> Self {
+        self.unknown_events = policy;
+        self
+    }
+
+    /// 多活部署：写入的事件标记本副本与 Lamport 时钟，重放时观察已存储事件的时钟
+    pub fn with_replica_clock(mut self, clock: Arc<ReplicaClock>) -> Self {
+        self.replica_clock = Some(clock);
+        self
+    }
+
+    /// 重建时检测到分叉历史的处理策略
+    pub fn with_conflict_resolver(mut self, resolver: Arc<dyn ConflictResolver>) -> Self {
+        self.conflict_resolver = resolver;
+        self
+    }
+
     pub async fn replay<A>(&self, mut aggregate: A) -> Result<Option<A>, DomainError>
     where
         A: Aggregate,
@@ -86,12 +155,20 @@ where
             return Ok(Some(aggregate));
         }
 
-        let envelopes = deserialize_events::<A>(&self.upcaster_chain, serialized)?;
-
-        for env in envelopes {
-            aggregate.apply(&env.payload);
+        let serialized = resolve_divergence(serialized, self.conflict_resolver.as_ref())?;
+        if let Some(clock) = &self.replica_clock
+            && let Some(lamport) = serialized.iter().filter_map(|e| e.lamport()).max()
+        {
+            clock.observe(lamport);
         }
 
+        apply_events_with(
+            &mut aggregate,
+            &self.upcaster_chain,
+            serialized,
+            &self.unknown_events,
+        )?;
+
         Ok(Some(aggregate))
     }
 }
@@ -123,17 +200,50 @@ where
             .map(|e| EventEnvelope::new(aggregate.id(), e, context.clone()))
             .collect();
 
+        self.save_envelopes(aggregate, envelopes).await
+    }
+
+    async fn save_envelopes(
+        &self,
+        aggregate: &A,
+        envelopes: Vec<EventEnvelope<A>>,
+    ) -> Result<Vec<EventEnvelope<A>>, A::Error> {
         if envelopes.is_empty() {
             return Ok(envelopes);
         }
 
-        let serialized = serialize_events(&envelopes).map_err(A::Error::from)?;
+        let mut serialized = serialize_events(&envelopes).map_err(A::Error::from)?;
+        if let Some(clock) = &self.replica_clock {
+            serialized = clock.stamp(serialized);
+        }
 
+        // 加载时的版本：首个新事件版本的前一版
+        let expected_version = envelopes[0]
+            .payload
+            .aggregate_version()
+            .value()
+            .saturating_sub(1);
         self.event_repo
-            .save(serialized)
+            .save_with_expected_version::<A>(aggregate.id(), serialized, expected_version)
             .await
             .map_err(A::Error::from)?;
 
+        if let Some(lifecycle) = &self.lifecycle {
+            let first = &envelopes[0];
+            if first.payload.aggregate_version().value() == 1 {
+                lifecycle
+                    .emit_best_effort(
+                        LifecycleEventKind::Created,
+                        A::TYPE,
+                        &aggregate.id().to_string(),
+                        1,
+                        &first.context,
+                        serde_json::json!({ "event_type": first.payload.event_type() }),
+                    )
+                    .await;
+            }
+        }
+
         Ok(envelopes)
     }
 }
@@ -141,6 +251,8 @@ where
 /// 基于事件存储 + 快照 的通用聚合仓储实现。
 /// - 优先使用 `SnapshotRepository` 恢复最近快照
 /// - 然后加载快照版本之后的增量事件并上抬（Upcast）重放
+/// - 配置生命周期事件后，额外在快照落盘时产生 `<type>.snapshot_taken`
+/// - 默认在保存路径中同步写快照；`with_background_snapshots` 启用后台快照，避免增加命令延迟
 pub struct SnapshotPolicyRepo<E, S>
 where
     E: EventRepository,
@@ -149,6 +261,23 @@ where
     event_repo: Arc<E>,
     snapshot_repo: Arc<SnapshotRepositoryWithPolicy<S>>,
     upcaster_chain: Arc<EventUpcasterChain>,
+    lifecycle: Option<LifecycleEvents>,
+    unknown_events: UnknownEventPolicy,
+    replica_clock: Option<Arc<ReplicaClock>>,
+    conflict_resolver: Arc<dyn ConflictResolver>,
+    #[cfg(feature = "eventing")]
+    background: Option<BackgroundSnapshots>,
+}
+
+/// 后台快照队列与类型擦除后的快照写入器（在 `with_background_snapshots` 中绑定快照仓储）
+#[cfg(feature = "eventing")]
+struct BackgroundSnapshots {
+    snapshotter: Arc<BackgroundSnapshotter>,
+    write: Arc<
+        dyn Fn(SerializedSnapshot) -> BoxFuture<'static, crate::error::DomainResult<()>>
+            + Send
+            + Sync,
+    >,
 }
 
 impl<E, S> SnapshotPolicyRepo<E, S>
@@ -165,6 +294,60 @@ where
             event_repo,
             snapshot_repo,
             upcaster_chain,
+            lifecycle: None,
+            unknown_events: UnknownEventPolicy::default(),
+            replica_clock: None,
+            conflict_resolver: Arc::new(RejectConflicts),
+            #[cfg(feature = "eventing")]
+            background: None,
+        }
+    }
+
+    /// 启用生命周期事件
+    pub fn with_lifecycle_events(mut self, lifecycle: LifecycleEvents) -> Self {
+        self.lifecycle = Some(lifecycle);
+        self
+    }
+
+    /// 重放增量事件时未知事件类型的处理策略
+    pub fn with_unknown_event_policy(mut self, policy: UnknownEventPolicy) -> Self {
+        self.unknown_events = policy;
+        self
+    }
+
+    /// 多活部署的副本时钟，见 `EventSourcedRepo::with_replica_clock`
+    pub fn with_replica_clock(mut self, clock: Arc<ReplicaClock>) -> Self {
+        self.replica_clock = Some(clock);
+        self
+    }
+
+    /// 重放增量事件时检测到分叉历史的处理策略
+    pub fn with_conflict_resolver(mut self, resolver: Arc<dyn ConflictResolver>) -> Self {
+        self.conflict_resolver = resolver;
+        self
+    }
+
+    /// 后台快照队列（未启用时为空）
+    #[cfg(feature = "eventing")]
+    pub fn background_snapshots(&self) -> Option<&Arc<BackgroundSnapshotter>> {
+        self.background.as_ref().map(|b| &b.snapshotter)
+    }
+
+    fn event_sourced_repo(&self) -> EventSourcedRepo<E> {
+        let repo = EventSourcedRepo::new(
+            Arc::clone(&self.event_repo),
+            Arc::clone(&self.upcaster_chain),
+        )
+        .with_unknown_event_policy(self.unknown_events.clone())
+        .with_conflict_resolver(Arc::clone(&self.conflict_resolver));
+        let repo = match &self.replica_clock {
+            Some(clock) => repo.with_replica_clock(Arc::clone(clock)),
+            None => repo,
+        };
+
+        match &self.lifecycle {
+            Some(lifecycle) => repo.with_lifecycle_events(lifecycle.clone()),
+            None => repo,
         }
     }
 }
@@ -178,10 +361,7 @@ where
     A::Error: From<DomainError> + Send + Sync,
 {
     async fn load(&self, aggregate_id: &A::Id) -> Result<Option<A>, A::Error> {
-        let event_sourced_repo = EventSourcedRepo::new(
-            Arc::clone(&self.event_repo),
-            Arc::clone(&self.upcaster_chain),
-        );
+        let event_sourced_repo = self.event_sourced_repo();
 
         if let Some(snapshot) = self
             .snapshot_repo
@@ -209,18 +389,124 @@ where
         events: Vec<A::Event>,
         context: EventContext,
     ) -> Result<Vec<EventEnvelope<A>>, A::Error> {
-        let event_sourced_repo = EventSourcedRepo::new(
-            Arc::clone(&self.event_repo),
-            Arc::clone(&self.upcaster_chain),
-        );
+        let envelopes: Vec<EventEnvelope<A>> = events
+            .into_iter()
+            .map(|e| EventEnvelope::new(aggregate.id(), e, context.clone()))
+            .collect();
 
-        let envelopes = event_sourced_repo.save(aggregate, events, context).await?;
+        self.save_envelopes(aggregate, envelopes).await
+    }
+
+    async fn save_envelopes(
+        &self,
+        aggregate: &A,
+        envelopes: Vec<EventEnvelope<A>>,
+    ) -> Result<Vec<EventEnvelope<A>>, A::Error> {
+        let envelopes = self
+            .event_sourced_repo()
+            .save_envelopes(aggregate, envelopes)
+            .await?;
+
+        #[cfg(feature = "eventing")]
+        if let Some(background) = &self.background {
+            self.enqueue_snapshot(background, aggregate, envelopes.last())
+                .map_err(A::Error::from)?;
+            return Ok(envelopes);
+        }
 
         self.snapshot_repo
             .save(aggregate)
             .await
             .map_err(A::Error::from)?;
 
+        let version = aggregate.version().value();
+        if let (Some(lifecycle), Some(last)) = (&self.lifecycle, envelopes.last())
+            && self.snapshot_repo.should_snapshot::<A>(version)
+        {
+            lifecycle
+                .emit_best_effort(
+                    LifecycleEventKind::SnapshotTaken,
+                    A::TYPE,
+                    &aggregate.id().to_string(),
+                    version,
+                    &last.context,
+                    serde_json::json!({ "snapshot_version": version }),
+                )
+                .await;
+        }
+
         Ok(envelopes)
     }
 }
+
+#[cfg(feature = "eventing")]
+impl<E, S> SnapshotPolicyRepo<E, S>
+where
+    E: EventRepository,
+    S: SnapshotRepository + 'static,
+{
+    /// 启用后台快照：满足策略的快照请求交由 `snapshotter` 异步落盘
+    ///
+    /// 同一 `snapshotter` 可在多个仓储间共享，以统一约束队列容量。
+    pub fn with_background_snapshots(mut self, snapshotter: Arc<BackgroundSnapshotter>) -> Self {
+        let snapshot_repo = Arc::clone(&self.snapshot_repo);
+        self.background = Some(BackgroundSnapshots {
+            snapshotter,
+            write: Arc::new(move |snapshot| {
+                let snapshot_repo = Arc::clone(&snapshot_repo);
+                Box::pin(async move { snapshot_repo.save_encoded(snapshot).await })
+            }),
+        });
+        self
+    }
+}
+
+#[cfg(feature = "eventing")]
+impl<E, S> SnapshotPolicyRepo<E, S>
+where
+    E: EventRepository,
+    S: SnapshotRepository,
+{
+    /// 捕获聚合当前状态并提交后台快照任务；不满足策略时不入队
+    fn enqueue_snapshot<A: Aggregate>(
+        &self,
+        background: &BackgroundSnapshots,
+        aggregate: &A,
+        last: Option<&EventEnvelope<A>>,
+    ) -> Result<(), DomainError> {
+        let version = aggregate.version().value();
+        if !self.snapshot_repo.should_snapshot::<A>(version) {
+            return Ok(());
+        }
+
+        let snapshot = SerializedSnapshot::from_aggregate(aggregate)?;
+        let (aggregate_type, aggregate_id) = (A::TYPE, aggregate.id().to_string());
+        let write = (background.write)(snapshot);
+        let lifecycle = self.lifecycle.clone();
+        let context = last.map(|e| e.context.clone());
+        let id = aggregate_id.clone();
+
+        background.snapshotter.enqueue(
+            aggregate_type,
+            &aggregate_id,
+            Box::pin(async move {
+                write.await?;
+
+                if let (Some(lifecycle), Some(context)) = (lifecycle, context) {
+                    lifecycle
+                        .emit_best_effort(
+                            LifecycleEventKind::SnapshotTaken,
+                            aggregate_type,
+                            &id,
+                            version,
+                            &context,
+                            serde_json::json!({ "snapshot_version": version }),
+                        )
+                        .await;
+                }
+                Ok(())
+            }),
+        );
+        Ok(())
+    }
+}
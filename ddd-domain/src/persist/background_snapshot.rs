@@ -0,0 +1,139 @@
+//! 后台快照（BackgroundSnapshotter）
+//!
+//! 在保存路径中同步写快照会增加命令延迟。`SnapshotPolicyRepo::with_background_snapshots`
+//! 启用后台模式：保存事件后仅将快照请求入队，由后台任务异步落盘（同步模式仍为默认）：
+//! - 队列有界：待处理的聚合数达到容量时丢弃新请求（快照只是加速手段，下次满足策略时会重新生成）；
+//! - 按聚合合并：同一聚合尚未落盘的请求被最新状态覆盖，只写最新的快照；
+//! - 落盘失败只计入统计，不影响已完成的命令；
+//! - `flush` 等待队列清空，便于在停机或测试时确保快照已落盘。
+//!
+use crate::error::DomainResult as Result;
+use futures_util::future::BoxFuture;
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+use tokio::sync::Notify;
+
+/// 后台快照统计
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct BackgroundSnapshotStats {
+    /// 入队的请求数（含被合并的请求）
+    pub enqueued: u64,
+    /// 被同一聚合更新请求覆盖的请求数
+    pub coalesced: u64,
+    /// 队列已满而丢弃的请求数
+    pub dropped: u64,
+    /// 成功落盘的快照数
+    pub completed: u64,
+    /// 落盘失败的快照数
+    pub failed: u64,
+}
+
+type Key = (String, String);
+
+#[derive(Default)]
+struct State {
+    order: VecDeque<Key>,
+    jobs: HashMap<Key, BoxFuture<'static, Result<()>>>,
+    draining: bool,
+    stats: BackgroundSnapshotStats,
+}
+
+/// 有界、按聚合合并的后台快照队列
+pub struct BackgroundSnapshotter {
+    capacity: usize,
+    state: Arc<Mutex<State>>,
+    idle: Arc<Notify>,
+}
+
+impl BackgroundSnapshotter {
+    /// `capacity` 为最多同时等待落盘的聚合数（至少为 1）
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            state: Arc::new(Mutex::new(State::default())),
+            idle: Arc::new(Notify::new()),
+        }
+    }
+
+    pub fn stats(&self) -> BackgroundSnapshotStats {
+        self.state.lock().unwrap().stats
+    }
+
+    /// 尚未落盘的聚合数
+    pub fn pending_len(&self) -> usize {
+        self.state.lock().unwrap().jobs.len()
+    }
+
+    /// 提交聚合的快照任务；同一聚合已在队列中时以新任务替换，返回是否被接收
+    pub fn enqueue(
+        &self,
+        aggregate_type: &str,
+        aggregate_id: &str,
+        job: BoxFuture<'static, Result<()>>,
+    ) -> bool {
+        let key = (aggregate_type.to_string(), aggregate_id.to_string());
+        let spawn = {
+            let mut state = self.state.lock().unwrap();
+            state.stats.enqueued += 1;
+
+            if let Some(pending) = state.jobs.get_mut(&key) {
+                *pending = job;
+                state.stats.coalesced += 1;
+                return true;
+            }
+            if state.jobs.len() >= self.capacity {
+                state.stats.dropped += 1;
+                return false;
+            }
+
+            state.order.push_back(key.clone());
+            state.jobs.insert(key, job);
+            !std::mem::replace(&mut state.draining, true)
+        };
+
+        if spawn {
+            let state = Arc::clone(&self.state);
+            let idle = Arc::clone(&self.idle);
+            tokio::spawn(Self::drain(state, idle));
+        }
+        true
+    }
+
+    /// 等待队列中的快照全部处理完毕
+    pub async fn flush(&self) {
+        loop {
+            let notified = self.idle.notified();
+            tokio::pin!(notified);
+            notified.as_mut().enable();
+
+            if !self.state.lock().unwrap().draining {
+                return;
+            }
+            notified.await;
+        }
+    }
+
+    async fn drain(state: Arc<Mutex<State>>, idle: Arc<Notify>) {
+        loop {
+            let job = {
+                let mut s = state.lock().unwrap();
+                let next = s.order.pop_front().and_then(|key| s.jobs.remove(&key));
+                if next.is_none() {
+                    s.draining = false;
+                }
+                next
+            };
+            let Some(job) = job else {
+                idle.notify_waiters();
+                return;
+            };
+
+            let result = job.await;
+            let mut s = state.lock().unwrap();
+            match result {
+                Ok(()) => s.stats.completed += 1,
+                Err(_) => s.stats.failed += 1,
+            }
+        }
+    }
+}
@@ -0,0 +1,248 @@
+//! 缓冲批量写入（BufferedOutboxWriter）
+//!
+//! 高吞吐写入场景下，逐条命令写入事件/Outbox 的开销主要在每次插入的往返与提交。
+//! `BufferedOutboxWriter` 作为 `EventRepository` 的装饰器，在一个很短的时间窗口内
+//! 将多个命令的事件合并为一次 `save`：
+//! - 达到 `max_batch_size` 或等待超过 `max_latency` 时落盘；
+//! - 事务提交等需要确定落盘的时机可调用 `flush` 显式刷新；
+//! - 合并写入失败时按调用方拆分逐个重试，使乐观锁冲突等错误只影响对应命令；
+//! - `WriteAck` 控制 `save` 的返回时机：等待落盘（默认）或仅入队（吞吐优先，错误由 `flush` 报告）。
+//!
+use crate::{
+    aggregate::Aggregate,
+    error::{DomainError, DomainResult as Result},
+    persist::{AggregateIdLister, EventExclusion, EventRepository, SerializedEvent},
+};
+use async_trait::async_trait;
+use std::mem;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::sync::oneshot;
+
+/// `save` 的确认方式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum WriteAck {
+    /// 等待所在批次落盘后返回（默认）
+    #[default]
+    Flushed,
+    /// 入队即返回；落盘错误在下一次 `flush` 时返回
+    Buffered,
+}
+
+/// 缓冲写入配置
+#[derive(Clone, Copy, Debug)]
+pub struct BufferedOutboxConfig {
+    /// 单批最多事件数，达到即落盘
+    pub max_batch_size: usize,
+    /// 批次首个事件入队后最长等待时间
+    pub max_latency: Duration,
+    pub ack: WriteAck,
+}
+
+impl Default for BufferedOutboxConfig {
+    fn default() -> Self {
+        Self {
+            max_batch_size: 256,
+            max_latency: Duration::from_millis(5),
+            ack: WriteAck::Flushed,
+        }
+    }
+}
+
+/// 单次 `save` 调用在批次中的片段
+struct Waiter {
+    len: usize,
+    reply: Option<oneshot::Sender<Result<()>>>,
+}
+
+#[derive(Default)]
+struct Pending {
+    events: Vec<SerializedEvent>,
+    waiters: Vec<Waiter>,
+    /// `WriteAck::Buffered` 模式下未报告的落盘错误
+    errors: Vec<String>,
+}
+
+/// `EventRepository` 的缓冲批量写入装饰器
+pub struct BufferedOutboxWriter<E> {
+    inner: Arc<E>,
+    config: BufferedOutboxConfig,
+    pending: Arc<Mutex<Pending>>,
+}
+
+impl<E> BufferedOutboxWriter<E>
+where
+    E: EventRepository + 'static,
+{
+    pub fn new(inner: Arc<E>, config: BufferedOutboxConfig) -> Self {
+        Self {
+            inner,
+            config,
+            pending: Arc::new(Mutex::new(Pending::default())),
+        }
+    }
+
+    /// 当前缓冲中尚未落盘的事件数
+    pub fn pending_len(&self) -> usize {
+        self.pending.lock().unwrap().events.len()
+    }
+
+    /// 立即落盘缓冲中的事件（如在事务提交时调用）
+    ///
+    /// `WriteAck::Buffered` 模式下同时返回此前累积的落盘错误。
+    pub async fn flush(&self) -> Result<()> {
+        Self::flush_pending(&self.inner, &self.pending).await;
+
+        let errors = mem::take(&mut self.pending.lock().unwrap().errors);
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(DomainError::internal(format!(
+                "buffered outbox write failed: {}",
+                errors.join("; ")
+            )))
+        }
+    }
+
+    async fn flush_pending(inner: &Arc<E>, pending: &Arc<Mutex<Pending>>) {
+        let (events, waiters) = {
+            let mut p = pending.lock().unwrap();
+            (mem::take(&mut p.events), mem::take(&mut p.waiters))
+        };
+        if events.is_empty() {
+            return;
+        }
+
+        let results = match inner.save(events.clone()).await {
+            Ok(()) => waiters.iter().map(|_| Ok(())).collect(),
+            Err(e) if waiters.len() == 1 => vec![Err(e)],
+            Err(_) => {
+                // 合并批次失败：按调用方拆分重试，定位出错的命令
+                let mut results = Vec::with_capacity(waiters.len());
+                let mut rest = events.into_iter();
+                for w in &waiters {
+                    let chunk: Vec<_> = rest.by_ref().take(w.len).collect();
+                    results.push(inner.save(chunk).await);
+                }
+                results
+            }
+        };
+
+        let mut unreported = Vec::new();
+        for (mut w, result) in waiters.into_iter().zip(results) {
+            match w.reply.take() {
+                Some(reply) => {
+                    let _ = reply.send(result);
+                }
+                None => {
+                    if let Err(e) = result {
+                        unreported.push(e.to_string());
+                    }
+                }
+            }
+        }
+
+        if !unreported.is_empty() {
+            pending.lock().unwrap().errors.extend(unreported);
+        }
+    }
+}
+
+#[async_trait]
+impl<E> EventRepository for BufferedOutboxWriter<E>
+where
+    E: EventRepository + 'static,
+{
+    async fn get_events<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<Vec<SerializedEvent>> {
+        self.inner.get_events::<A>(aggregate_id).await
+    }
+
+    async fn get_last_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        last_version: usize,
+    ) -> Result<Vec<SerializedEvent>> {
+        self.inner
+            .get_last_events::<A>(aggregate_id, last_version)
+            .await
+    }
+
+    async fn save(&self, events: Vec<SerializedEvent>) -> Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+
+        let (tx, rx) = oneshot::channel();
+        let reply = (self.config.ack == WriteAck::Flushed).then_some(tx);
+
+        let (first_in_batch, full) = {
+            let mut p = self.pending.lock().unwrap();
+            let first_in_batch = p.events.is_empty();
+            p.waiters.push(Waiter {
+                len: events.len(),
+                reply,
+            });
+            p.events.extend(events);
+            (first_in_batch, p.events.len() >= self.config.max_batch_size)
+        };
+
+        if full {
+            Self::flush_pending(&self.inner, &self.pending).await;
+        } else if first_in_batch {
+            let inner = Arc::clone(&self.inner);
+            let pending = Arc::clone(&self.pending);
+            let latency = self.config.max_latency;
+            tokio::spawn(async move {
+                tokio::time::sleep(latency).await;
+                Self::flush_pending(&inner, &pending).await;
+            });
+        }
+
+        match self.config.ack {
+            WriteAck::Flushed => rx
+                .await
+                .map_err(|_| DomainError::internal("buffered outbox writer dropped the batch"))?,
+            WriteAck::Buffered => Ok(()),
+        }
+    }
+
+    async fn ping(&self) -> Result<()> {
+        self.inner.ping().await
+    }
+
+    async fn current_version<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<usize> {
+        self.inner.current_version::<A>(aggregate_id).await
+    }
+
+    async fn exists<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<bool> {
+        self.inner.exists::<A>(aggregate_id).await
+    }
+
+    async fn exists_many<A: Aggregate>(&self, aggregate_ids: &[A::Id]) -> Result<Vec<bool>> {
+        self.inner.exists_many::<A>(aggregate_ids).await
+    }
+
+    /// 先落盘缓冲中的事件，保证待排除的事件已写入
+    async fn mark_excluded(&self, event_id: &str, reason: &str, actor: &str) -> Result<()> {
+        self.flush().await?;
+        self.inner.mark_excluded(event_id, reason, actor).await
+    }
+
+    async fn exclusions(&self) -> Result<Vec<EventExclusion>> {
+        self.inner.exclusions().await
+    }
+}
+
+#[async_trait]
+impl<E> AggregateIdLister for BufferedOutboxWriter<E>
+where
+    E: EventRepository + AggregateIdLister + 'static,
+{
+    async fn list_aggregate_ids<A: Aggregate>(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>> {
+        self.inner.list_aggregate_ids::<A>(after, limit).await
+    }
+}
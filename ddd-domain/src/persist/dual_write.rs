@@ -0,0 +1,285 @@
+//! 双写迁移事件仓储（DualWriteRepo）
+//!
+//! 在两个事件存储之间迁移时，事件同时写入旧库与新库（影子写），读取来源可配置，
+//! 并记录两侧的分歧（影子写失败、读取结果不一致），验证完成后执行切换（cutover）。
+//! 作为装饰器注入 `EventSourcedRepo` / `SnapshotPolicyRepo`，迁移期间应用代码无需改动。
+//!
+use crate::{
+    aggregate::Aggregate,
+    error::DomainResult as Result,
+    persist::{AggregateIdLister, EventExclusion, EventRepository, SerializedEvent},
+};
+use async_trait::async_trait;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, AtomicU64, Ordering};
+
+/// 读取来源，同时也是写入时必须成功的一侧（主写）
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum ReadSource {
+    Old,
+    New,
+}
+
+/// 双写分歧统计
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DualWriteStats {
+    /// 影子写（非主写一侧）失败次数
+    pub shadow_write_failures: u64,
+    /// 对比读取次数
+    pub compared_reads: u64,
+    /// 对比读取中两侧结果不一致的次数
+    pub read_mismatches: u64,
+}
+
+#[derive(Default)]
+struct Counters {
+    shadow_write_failures: AtomicU64,
+    compared_reads: AtomicU64,
+    read_mismatches: AtomicU64,
+}
+
+/// `EventRepository` 的双写迁移装饰器
+///
+/// - `O`：旧事件仓储
+/// - `N`：新事件仓储
+///
+/// 写入时先写读取来源一侧（失败即返回错误），再影子写另一侧（失败仅计入统计）；
+/// 开启 `compare_reads` 后每次读取同时查询两侧并比较事件 ID/版本序列。
+pub struct DualWriteRepo<O, N> {
+    old: Arc<O>,
+    new: Arc<N>,
+    read_new: AtomicBool,
+    compare_reads: bool,
+    counters: Counters,
+}
+
+impl<O, N> DualWriteRepo<O, N>
+where
+    O: EventRepository,
+    N: EventRepository,
+{
+    /// 默认从旧库读取
+    pub fn new(old: Arc<O>, new: Arc<N>) -> Self {
+        Self {
+            old,
+            new,
+            read_new: AtomicBool::new(false),
+            compare_reads: false,
+            counters: Counters::default(),
+        }
+    }
+
+    pub fn with_read_source(self, source: ReadSource) -> Self {
+        self.set_read_source(source);
+        self
+    }
+
+    /// 读取时同时查询另一侧并比较结果
+    pub fn with_compare_reads(mut self, compare_reads: bool) -> Self {
+        self.compare_reads = compare_reads;
+        self
+    }
+
+    pub fn read_source(&self) -> ReadSource {
+        if self.read_new.load(Ordering::Acquire) {
+            ReadSource::New
+        } else {
+            ReadSource::Old
+        }
+    }
+
+    pub fn set_read_source(&self, source: ReadSource) {
+        self.read_new
+            .store(source == ReadSource::New, Ordering::Release);
+    }
+
+    /// 切换到新库：此后从新库读取、以新库为主写，旧库继续影子写以便回退
+    pub fn cutover(&self) {
+        self.set_read_source(ReadSource::New);
+    }
+
+    pub fn stats(&self) -> DualWriteStats {
+        DualWriteStats {
+            shadow_write_failures: self.counters.shadow_write_failures.load(Ordering::Relaxed),
+            compared_reads: self.counters.compared_reads.load(Ordering::Relaxed),
+            read_mismatches: self.counters.read_mismatches.load(Ordering::Relaxed),
+        }
+    }
+
+    fn compare(&self, primary: &[SerializedEvent], shadow: Result<Vec<SerializedEvent>>) {
+        self.counters.compared_reads.fetch_add(1, Ordering::Relaxed);
+
+        let same = shadow.is_ok_and(|shadow| {
+            shadow.len() == primary.len()
+                && shadow.iter().zip(primary).all(|(a, b)| {
+                    a.event_id() == b.event_id() && a.aggregate_version() == b.aggregate_version()
+                })
+        });
+        if !same {
+            self.counters
+                .read_mismatches
+                .fetch_add(1, Ordering::Relaxed);
+        }
+    }
+}
+
+#[async_trait]
+impl<O, N> EventRepository for DualWriteRepo<O, N>
+where
+    O: EventRepository,
+    N: EventRepository,
+{
+    async fn get_events<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<Vec<SerializedEvent>> {
+        let (events, shadow) = match self.read_source() {
+            ReadSource::Old => {
+                let events = self.old.get_events::<A>(aggregate_id).await?;
+                let shadow = match self.compare_reads {
+                    true => Some(self.new.get_events::<A>(aggregate_id).await),
+                    false => None,
+                };
+                (events, shadow)
+            }
+            ReadSource::New => {
+                let events = self.new.get_events::<A>(aggregate_id).await?;
+                let shadow = match self.compare_reads {
+                    true => Some(self.old.get_events::<A>(aggregate_id).await),
+                    false => None,
+                };
+                (events, shadow)
+            }
+        };
+
+        if let Some(shadow) = shadow {
+            self.compare(&events, shadow);
+        }
+        Ok(events)
+    }
+
+    async fn get_last_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        last_version: usize,
+    ) -> Result<Vec<SerializedEvent>> {
+        let (events, shadow) = match self.read_source() {
+            ReadSource::Old => {
+                let events = self
+                    .old
+                    .get_last_events::<A>(aggregate_id, last_version)
+                    .await?;
+                let shadow = match self.compare_reads {
+                    true => Some(
+                        self.new
+                            .get_last_events::<A>(aggregate_id, last_version)
+                            .await,
+                    ),
+                    false => None,
+                };
+                (events, shadow)
+            }
+            ReadSource::New => {
+                let events = self
+                    .new
+                    .get_last_events::<A>(aggregate_id, last_version)
+                    .await?;
+                let shadow = match self.compare_reads {
+                    true => Some(
+                        self.old
+                            .get_last_events::<A>(aggregate_id, last_version)
+                            .await,
+                    ),
+                    false => None,
+                };
+                (events, shadow)
+            }
+        };
+
+        if let Some(shadow) = shadow {
+            self.compare(&events, shadow);
+        }
+        Ok(events)
+    }
+
+    async fn save(&self, events: Vec<SerializedEvent>) -> Result<()> {
+        let shadow = match self.read_source() {
+            ReadSource::Old => {
+                self.old.save(events.clone()).await?;
+                self.new.save(events).await
+            }
+            ReadSource::New => {
+                self.new.save(events.clone()).await?;
+                self.old.save(events).await
+            }
+        };
+
+        if shadow.is_err() {
+            self.counters
+                .shadow_write_failures
+                .fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    async fn ping(&self) -> Result<()> {
+        self.old.ping().await?;
+        self.new.ping().await
+    }
+
+    async fn current_version<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<usize> {
+        match self.read_source() {
+            ReadSource::Old => self.old.current_version::<A>(aggregate_id).await,
+            ReadSource::New => self.new.current_version::<A>(aggregate_id).await,
+        }
+    }
+
+    async fn exists_many<A: Aggregate>(&self, aggregate_ids: &[A::Id]) -> Result<Vec<bool>> {
+        match self.read_source() {
+            ReadSource::Old => self.old.exists_many::<A>(aggregate_ids).await,
+            ReadSource::New => self.new.exists_many::<A>(aggregate_ids).await,
+        }
+    }
+
+    async fn mark_excluded(&self, event_id: &str, reason: &str, actor: &str) -> Result<()> {
+        let shadow = match self.read_source() {
+            ReadSource::Old => {
+                self.old.mark_excluded(event_id, reason, actor).await?;
+                self.new.mark_excluded(event_id, reason, actor).await
+            }
+            ReadSource::New => {
+                self.new.mark_excluded(event_id, reason, actor).await?;
+                self.old.mark_excluded(event_id, reason, actor).await
+            }
+        };
+
+        if shadow.is_err() {
+            self.counters
+                .shadow_write_failures
+                .fetch_add(1, Ordering::Relaxed);
+        }
+        Ok(())
+    }
+
+    async fn exclusions(&self) -> Result<Vec<EventExclusion>> {
+        match self.read_source() {
+            ReadSource::Old => self.old.exclusions().await,
+            ReadSource::New => self.new.exclusions().await,
+        }
+    }
+}
+
+#[async_trait]
+impl<O, N> AggregateIdLister for DualWriteRepo<O, N>
+where
+    O: EventRepository + AggregateIdLister,
+    N: EventRepository + AggregateIdLister,
+{
+    async fn list_aggregate_ids<A: Aggregate>(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>> {
+        match self.read_source() {
+            ReadSource::Old => self.old.list_aggregate_ids::<A>(after, limit).await,
+            ReadSource::New => self.new.list_aggregate_ids::<A>(after, limit).await,
+        }
+    }
+}
@@ -0,0 +1,133 @@
+//! 历史事件导入（回填）
+//!
+//! 从旧系统迁移历史数据时，事件须保留原始发生时间，而常规的 `EventEnvelope::new` 记录写入时刻。
+//! `EventImporter` 以 `EventEnvelope::new_with` 构造信封，元数据标记为回填
+//! （`Metadata::is_backfilled`/`SerializedEvent::is_backfilled`），写入前校验：
+//! - 聚合版本自流的当前版本起连续；
+//! - 发生时间不晚于当前时间（允许 `with_max_clock_skew` 设置的时钟偏差）；
+//! - 发生时间在流内不递减（与流中已有的最后一个事件比较）。
+//!
+//! 下游处理器可据回填标记跳过通知类副作用，仅更新读模型。
+//!
+use crate::{
+    aggregate::Aggregate,
+    domain_event::{DomainEvent, EventContext, EventEnvelope},
+    error::{DomainError, DomainResult as Result},
+    persist::{EventRepository, serialize_events},
+};
+use chrono::{DateTime, Duration, Utc};
+use std::sync::Arc;
+
+/// 待导入的历史事件
+#[derive(Debug, Clone)]
+pub struct BackfillEvent<E> {
+    pub payload: E,
+    /// 事件在源系统中的原始发生时间
+    pub occurred_at: DateTime<Utc>,
+    pub context: EventContext,
+}
+
+impl<E> BackfillEvent<E> {
+    pub fn new(payload: E, occurred_at: DateTime<Utc>) -> Self {
+        Self {
+            payload,
+            occurred_at,
+            context: EventContext::default(),
+        }
+    }
+
+    pub fn with_context(mut self, context: EventContext) -> Self {
+        self.context = context;
+        self
+    }
+}
+
+/// 历史事件导入器
+pub struct EventImporter<R> {
+    repo: Arc<R>,
+    max_clock_skew: Duration,
+}
+
+impl<R> EventImporter<R>
+where
+    R: EventRepository,
+{
+    pub fn new(repo: Arc<R>) -> Self {
+        Self {
+            repo,
+            max_clock_skew: Duration::zero(),
+        }
+    }
+
+    /// 允许发生时间超前当前时间的最大偏差（源系统与本机时钟不一致时使用）
+    pub fn with_max_clock_skew(mut self, skew: Duration) -> Self {
+        self.max_clock_skew = skew;
+        self
+    }
+
+    /// 校验并写入一个聚合的历史事件，返回写入的事件数
+    ///
+    /// 任一事件校验失败时整批拒绝，不写入任何事件。
+    pub async fn import<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        events: Vec<BackfillEvent<A::Event>>,
+    ) -> Result<usize> {
+        if events.is_empty() {
+            return Ok(0);
+        }
+
+        let mut expected_version = self.repo.current_version::<A>(aggregate_id).await?;
+        let mut last_occurred_at = self
+            .repo
+            .get_events::<A>(aggregate_id)
+            .await?
+            .last()
+            .map(|e| e.occurred_at());
+        let latest_allowed = Utc::now() + self.max_clock_skew;
+
+        let mut envelopes = Vec::with_capacity(events.len());
+        for event in events {
+            expected_version += 1;
+            let version = event.payload.aggregate_version().value();
+            if version != expected_version {
+                return Err(rejected(format!(
+                    "{} {aggregate_id}: expected version {expected_version}, got {version}",
+                    A::TYPE
+                )));
+            }
+            if event.occurred_at > latest_allowed {
+                return Err(rejected(format!(
+                    "{} {aggregate_id} v{version}: occurred_at {} is in the future",
+                    A::TYPE,
+                    event.occurred_at
+                )));
+            }
+            if let Some(previous) = last_occurred_at
+                && event.occurred_at < previous
+            {
+                return Err(rejected(format!(
+                    "{} {aggregate_id} v{version}: occurred_at {} is earlier than previous event at {previous}",
+                    A::TYPE,
+                    event.occurred_at
+                )));
+            }
+            last_occurred_at = Some(event.occurred_at);
+
+            envelopes.push(EventEnvelope::<A>::new_with(
+                aggregate_id,
+                event.payload,
+                event.context,
+                event.occurred_at,
+            ));
+        }
+
+        let count = envelopes.len();
+        self.repo.save(serialize_events(&envelopes)?).await?;
+        Ok(count)
+    }
+}
+
+fn rejected(msg: String) -> DomainError {
+    DomainError::invalid_value(msg).with_code("BACKFILL_REJECTED")
+}
@@ -1,18 +1,36 @@
 //! 事件仓储协议
 //!
-//! 定义按聚合读取全部或增量事件与批量保存的接口；
+//! 定义按聚合读取全部或增量事件与批量保存的接口，以及存在性/当前版本的快速查询；
+//! `save_with_expected_version` 以加载时的版本做乐观并发校验，版本不符返回 `Conflict`；
 //! 并提供扩展方法将读取结果与上抬链组合为 `AggregateEvents`。
 //!
+//! 误写入且依法可从重放中排除的事件（非财务数据）通过 `mark_excluded` 软删除：
+//! 读取路径跳过该事件，事件本身与排除记录（原因、操作人、时间）保留以供审计，
+//! 避免手工修改存储中的行。
+//!
 use crate::{
     aggregate::Aggregate,
     domain_event::AggregateEvents,
-    error::DomainResult as Result,
+    error::{DomainError, DomainResult as Result},
     event_upcaster::EventUpcasterChain,
     persist::{SerializedEvent, deserialize_events},
 };
 use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use serde::{Deserialize, Serialize};
 use std::sync::Arc;
 
+/// 事件排除记录（审计用）
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct EventExclusion {
+    pub event_id: String,
+    pub aggregate_type: String,
+    pub aggregate_id: String,
+    pub reason: String,
+    pub actor: String,
+    pub excluded_at: DateTime<Utc>,
+}
+
 #[async_trait]
 pub trait EventRepository: Send + Sync {
     async fn get_events<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<Vec<SerializedEvent>>;
@@ -24,6 +42,98 @@ pub trait EventRepository: Send + Sync {
     ) -> Result<Vec<SerializedEvent>>;
 
     async fn save(&self, events: Vec<SerializedEvent>) -> Result<()>;
+
+    /// 以乐观并发保存单个聚合的事件：仅当聚合当前版本等于 `expected_version`
+    /// （加载聚合时的版本）时写入，否则返回 `Conflict`
+    ///
+    /// 事件须属于该聚合且版本从 `expected_version + 1` 连续递增。默认实现先查询 `current_version`
+    /// 再调用 `save`，两步之间不具备原子性；存储后端应覆盖为原子操作（如事务内校验或版本唯一约束）。
+    async fn save_with_expected_version<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        events: Vec<SerializedEvent>,
+        expected_version: usize,
+    ) -> Result<()> {
+        check_expected_versions::<A>(aggregate_id, &events, expected_version)?;
+        let current = self.current_version::<A>(aggregate_id).await?;
+        if current != expected_version {
+            return Err(DomainError::conflict(expected_version, current));
+        }
+        self.save(events).await
+    }
+
+    /// 探测存储是否可用（启动自检使用）；默认视为可用，存储后端应覆盖为轻量查询
+    async fn ping(&self) -> Result<()> {
+        Ok(())
+    }
+
+    /// 聚合当前版本（最后一个事件的版本），不存在时为 0
+    ///
+    /// 默认实现加载全部事件；存储后端应覆盖为只查询最大版本。
+    async fn current_version<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<usize> {
+        let events = self.get_events::<A>(aggregate_id).await?;
+        Ok(events.last().map_or(0, SerializedEvent::aggregate_version))
+    }
+
+    /// 聚合是否存在（至少有一个事件）
+    async fn exists<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<bool> {
+        Ok(self.current_version::<A>(aggregate_id).await? > 0)
+    }
+
+    /// 批量查询聚合是否存在，结果与 `aggregate_ids` 一一对应
+    ///
+    /// 默认实现逐个调用 `exists`；存储后端应覆盖为单次查询（如 `WHERE aggregate_id = ANY($1)`）。
+    async fn exists_many<A: Aggregate>(&self, aggregate_ids: &[A::Id]) -> Result<Vec<bool>> {
+        let mut found = Vec::with_capacity(aggregate_ids.len());
+        for aggregate_id in aggregate_ids {
+            found.push(self.exists::<A>(aggregate_id).await?);
+        }
+        Ok(found)
+    }
+
+    /// 将事件标记为排除：此后 `get_events`/`get_last_events` 跳过该事件，事件本身保留
+    ///
+    /// 排除不改变流的版本，支持排除的后端须让 `current_version` 仍计入被排除的事件；
+    /// 默认实现返回错误，由支持排除的后端覆盖。
+    async fn mark_excluded(&self, event_id: &str, reason: &str, actor: &str) -> Result<()> {
+        let _ = (event_id, reason, actor);
+        Err(
+            DomainError::invalid_state("event exclusion is not supported by this event repository")
+                .with_code("EXCLUSION_UNSUPPORTED"),
+        )
+    }
+
+    /// 全部排除记录，按排除时间排序
+    async fn exclusions(&self) -> Result<Vec<EventExclusion>> {
+        Ok(Vec::new())
+    }
+}
+
+/// 校验事件属于 `aggregate_id` 且版本从 `expected_version + 1` 连续递增
+///
+/// 供 `save_with_expected_version` 的实现在写入前调用。
+pub fn check_expected_versions<A: Aggregate>(
+    aggregate_id: &A::Id,
+    events: &[SerializedEvent],
+    expected_version: usize,
+) -> Result<()> {
+    let aggregate_id = aggregate_id.to_string();
+    for (offset, event) in events.iter().enumerate() {
+        if event.aggregate_type() != A::TYPE || event.aggregate_id() != aggregate_id {
+            return Err(DomainError::invalid_value(format!(
+                "event {} belongs to {}/{}, expected {}/{aggregate_id}",
+                event.event_id(),
+                event.aggregate_type(),
+                event.aggregate_id(),
+                A::TYPE
+            )));
+        }
+        let expected = expected_version + offset + 1;
+        if event.aggregate_version() != expected {
+            return Err(DomainError::conflict(expected, event.aggregate_version()));
+        }
+    }
+    Ok(())
 }
 
 #[async_trait]
@@ -62,6 +172,41 @@ where
     async fn save(&self, events: Vec<SerializedEvent>) -> Result<()> {
         (**self).save(events).await
     }
+
+    async fn save_with_expected_version<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        events: Vec<SerializedEvent>,
+        expected_version: usize,
+    ) -> Result<()> {
+        (**self)
+            .save_with_expected_version::<A>(aggregate_id, events, expected_version)
+            .await
+    }
+
+    async fn ping(&self) -> Result<()> {
+        (**self).ping().await
+    }
+
+    async fn current_version<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<usize> {
+        (**self).current_version::<A>(aggregate_id).await
+    }
+
+    async fn exists<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<bool> {
+        (**self).exists::<A>(aggregate_id).await
+    }
+
+    async fn exists_many<A: Aggregate>(&self, aggregate_ids: &[A::Id]) -> Result<Vec<bool>> {
+        (**self).exists_many::<A>(aggregate_ids).await
+    }
+
+    async fn mark_excluded(&self, event_id: &str, reason: &str, actor: &str) -> Result<()> {
+        (**self).mark_excluded(event_id, reason, actor).await
+    }
+
+    async fn exclusions(&self) -> Result<Vec<EventExclusion>> {
+        (**self).exclusions().await
+    }
 }
 
 #[async_trait]
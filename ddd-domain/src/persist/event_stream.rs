@@ -0,0 +1,61 @@
+//! 全局事件流读取（EventStreamReader）
+//!
+//! `EventRepository` 只能按聚合读取事件，重建读模型或追赶订阅需要跨聚合按全局顺序读取整个日志：
+//! - `read_page` 由存储后端实现，按全局位点升序返回起点（含）之后的一页事件，跳过已排除的事件；
+//! - `stream_all` 在其上逐页拉取，以流的形式产出事件，内存占用与日志总量无关；
+//!   下一页从上一页最后一个事件的位点之后开始。
+//!
+//! 返回的事件须携带全局位点（`sequence_number`），否则流在该页之后结束。
+//!
+use crate::{error::DomainResult as Result, persist::SerializedEvent};
+use async_trait::async_trait;
+use futures_core::stream::BoxStream;
+use futures_util::stream::{self, StreamExt};
+use std::sync::Arc;
+
+/// `stream_all` 每页读取的事件数
+pub const EVENT_STREAM_PAGE_SIZE: usize = 500;
+
+/// 按全局顺序读取全部聚合的事件
+#[async_trait]
+pub trait EventStreamReader: Send + Sync {
+    /// 按全局位点升序读取 `from_sequence`（含）之后至多 `limit` 条事件
+    async fn read_page(&self, from_sequence: u64, limit: usize) -> Result<Vec<SerializedEvent>>;
+
+    /// 从 `from_sequence`（含）开始按全局顺序读取直到当前末尾的事件流
+    fn stream_all(&self, from_sequence: u64) -> BoxStream<'_, Result<SerializedEvent>> {
+        stream::unfold(Some(from_sequence), move |next| async move {
+            let from = next?;
+            match self.read_page(from, EVENT_STREAM_PAGE_SIZE).await {
+                Ok(page) if page.is_empty() => None,
+                Ok(page) => {
+                    let next = match page.last().and_then(SerializedEvent::sequence_number) {
+                        Some(last) if page.len() == EVENT_STREAM_PAGE_SIZE => {
+                            Some(last.max(0) as u64 + 1)
+                        }
+                        _ => None,
+                    };
+                    Some((stream::iter(page.into_iter().map(Ok)).left_stream(), next))
+                }
+                // 读取失败时产出错误并结束流
+                Err(err) => Some((stream::once(async { Err(err) }).right_stream(), None)),
+            }
+        })
+        .flatten()
+        .boxed()
+    }
+}
+
+#[async_trait]
+impl<T> EventStreamReader for Arc<T>
+where
+    T: EventStreamReader + ?Sized,
+{
+    async fn read_page(&self, from_sequence: u64, limit: usize) -> Result<Vec<SerializedEvent>> {
+        (**self).read_page(from_sequence, limit).await
+    }
+
+    fn stream_all(&self, from_sequence: u64) -> BoxStream<'_, Result<SerializedEvent>> {
+        (**self).stream_all(from_sequence)
+    }
+}
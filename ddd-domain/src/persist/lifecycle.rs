@@ -0,0 +1,176 @@
+//! 聚合生命周期事件（框架事件）
+//!
+//! 由仓储按配置自动产生标准化的框架事件，写入独立的系统事件流（`LifecycleEventSink`），
+//! 供运维工具感知聚合生命周期，而无需为每个领域编写处理器：
+//! - `<type>.created`：聚合的首个事件持久化后；
+//! - `<type>.snapshot_taken`：快照按策略落盘后；
+//! - `<type>.archived`：由归档流程调用 `LifecycleEvents::archived` 产生。
+//!
+//! 生命周期事件不属于领域事件流，写入失败不影响领域事件的提交结果；
+//! 仓储自动产生的事件写入失败时以 `warn` 记录日志（target `ddd::eventing`，需启用 `eventing` 特性）。
+//!
+use crate::{
+    aggregate::Aggregate, domain_event::EventContext, domain_event::next_event_id,
+    error::DomainResult as Result, persist::SerializedEvent,
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use serde_json::{Value, json};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// 生命周期事件种类
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum LifecycleEventKind {
+    Created,
+    SnapshotTaken,
+    Archived,
+}
+
+impl LifecycleEventKind {
+    /// 事件类型后缀，完整事件类型为 `<aggregate_type>.<suffix>`
+    pub fn suffix(&self) -> &'static str {
+        match self {
+            LifecycleEventKind::Created => "created",
+            LifecycleEventKind::SnapshotTaken => "snapshot_taken",
+            LifecycleEventKind::Archived => "archived",
+        }
+    }
+}
+
+/// 系统事件流写入端
+#[async_trait]
+pub trait LifecycleEventSink: Send + Sync {
+    async fn append(&self, events: Vec<SerializedEvent>) -> Result<()>;
+}
+
+/// 生命周期事件配置：写入端与启用的事件种类（默认全部启用）
+#[derive(Clone)]
+pub struct LifecycleEvents {
+    sink: Arc<dyn LifecycleEventSink>,
+    disabled: HashSet<LifecycleEventKind>,
+}
+
+impl LifecycleEvents {
+    pub fn new(sink: Arc<dyn LifecycleEventSink>) -> Self {
+        Self {
+            sink,
+            disabled: HashSet::new(),
+        }
+    }
+
+    /// 关闭某类生命周期事件
+    pub fn disable(mut self, kind: LifecycleEventKind) -> Self {
+        self.disabled.insert(kind);
+        self
+    }
+
+    pub fn is_enabled(&self, kind: LifecycleEventKind) -> bool {
+        !self.disabled.contains(&kind)
+    }
+
+    /// 写入一条生命周期事件；该种类未启用时忽略
+    pub async fn emit<A: Aggregate>(
+        &self,
+        kind: LifecycleEventKind,
+        aggregate_id: &A::Id,
+        aggregate_version: usize,
+        context: &EventContext,
+        payload: Value,
+    ) -> Result<()> {
+        self.emit_for(
+            kind,
+            A::TYPE,
+            &aggregate_id.to_string(),
+            aggregate_version,
+            context,
+            payload,
+        )
+        .await
+    }
+
+    /// `emit` 的非泛型形式，供不持有聚合类型的后台任务使用
+    pub(crate) async fn emit_for(
+        &self,
+        kind: LifecycleEventKind,
+        aggregate_type: &str,
+        aggregate_id: &str,
+        aggregate_version: usize,
+        context: &EventContext,
+        payload: Value,
+    ) -> Result<()> {
+        if !self.is_enabled(kind) {
+            return Ok(());
+        }
+
+        let event = SerializedEvent::builder()
+            .event_id(next_event_id())
+            .event_type(format!("{aggregate_type}.{}", kind.suffix()))
+            .event_version(1)
+            .aggregate_id(aggregate_id.to_string())
+            .aggregate_type(aggregate_type.to_string())
+            .aggregate_version(aggregate_version)
+            .maybe_correlation_id(context.correlation_id().map(ToString::to_string))
+            .maybe_causation_id(context.causation_id().map(ToString::to_string))
+            .maybe_actor_type(context.actor_type().map(ToString::to_string))
+            .maybe_actor_id(context.actor_id().map(ToString::to_string))
+            .occurred_at(Utc::now())
+            .payload(payload)
+            .context(serde_json::to_value(context)?)
+            .build();
+
+        self.sink.append(vec![event]).await
+    }
+
+    /// 尽力写入生命周期事件：失败不返回给调用方，仅记录日志
+    pub(crate) async fn emit_best_effort(
+        &self,
+        kind: LifecycleEventKind,
+        aggregate_type: &str,
+        aggregate_id: &str,
+        aggregate_version: usize,
+        context: &EventContext,
+        payload: Value,
+    ) {
+        let result = self
+            .emit_for(
+                kind,
+                aggregate_type,
+                aggregate_id,
+                aggregate_version,
+                context,
+                payload,
+            )
+            .await;
+        if let Err(err) = result {
+            #[cfg(feature = "eventing")]
+            tracing::warn!(
+                target: crate::eventing::LOG_TARGET,
+                aggregate_type,
+                aggregate_id,
+                aggregate_version,
+                kind = kind.suffix(),
+                reason = %err,
+                "emit lifecycle event failed"
+            );
+            #[cfg(not(feature = "eventing"))]
+            let _ = err;
+        }
+    }
+
+    /// 聚合已归档
+    pub async fn archived<A: Aggregate>(
+        &self,
+        aggregate: &A,
+        context: &EventContext,
+    ) -> Result<()> {
+        self.emit::<A>(
+            LifecycleEventKind::Archived,
+            aggregate.id(),
+            aggregate.version().value(),
+            context,
+            json!({}),
+        )
+        .await
+    }
+}
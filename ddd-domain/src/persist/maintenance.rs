@@ -0,0 +1,288 @@
+//! 批量聚合维护（MaintenanceRunner）
+//!
+//! 批量迁移（如重新计算派生字段）需要遍历某类型的全部聚合，逐个经正常仓储加载、
+//! 可选执行迁移命令并保存。`MaintenanceRunner` 将这一流程标准化，替代一次性脚本：
+//! - 按 `AggregateIdLister::list_aggregate_ids` 以 ID 升序分批遍历聚合（事件存储需实现该能力）；
+//! - 每个聚合经 `AggregateRepository` 加载（上抬、快照等与正常读路径一致），迁移函数返回命令时
+//!   执行并保存产生的事件，返回 `None` 表示该聚合无需迁移；
+//! - 批内按 `concurrency` 并发处理，`rate_per_second` 限制每秒开始处理的聚合数；
+//! - 每批完成后将最后一个 ID 写入 `MaintenanceCheckpointStore`，中断后以同一任务名重新运行
+//!   从检查点之后继续；单个聚合失败只计入汇总，不中断任务；
+//! - 每批完成时回调进度（`MaintenanceProgress`），结束时返回汇总（`MaintenanceSummary`）。
+//!
+use crate::{
+    aggregate::Aggregate,
+    domain_event::EventContext,
+    error::{DomainError, DomainResult as Result},
+    persist::{AggregateIdLister, AggregateRepository},
+};
+use async_trait::async_trait;
+use futures_util::stream::{self, StreamExt};
+use std::collections::HashMap;
+use std::str::FromStr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::time::Instant;
+
+/// 维护任务检查点存储：按任务名记录已处理到的聚合 ID
+#[async_trait]
+pub trait MaintenanceCheckpointStore: Send + Sync {
+    async fn load(&self, job: &str) -> Result<Option<String>>;
+
+    async fn save(&self, job: &str, last_id: &str) -> Result<()>;
+}
+
+/// 基于内存的 MaintenanceCheckpointStore 实现
+#[derive(Default)]
+pub struct InMemoryMaintenanceCheckpointStore {
+    checkpoints: Mutex<HashMap<String, String>>,
+}
+
+impl InMemoryMaintenanceCheckpointStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl MaintenanceCheckpointStore for InMemoryMaintenanceCheckpointStore {
+    async fn load(&self, job: &str) -> Result<Option<String>> {
+        Ok(self.checkpoints.lock().unwrap().get(job).cloned())
+    }
+
+    async fn save(&self, job: &str, last_id: &str) -> Result<()> {
+        self.checkpoints
+            .lock()
+            .unwrap()
+            .insert(job.to_string(), last_id.to_string());
+        Ok(())
+    }
+}
+
+/// 维护任务配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MaintenanceConfig {
+    /// 每批列举的聚合数（至少 1），每批完成后推进检查点
+    pub batch_size: usize,
+    /// 同时处理的聚合数（至少 1）
+    pub concurrency: usize,
+    /// 每秒最多开始处理的聚合数，`None` 表示不限速
+    pub rate_per_second: Option<u32>,
+}
+
+impl Default for MaintenanceConfig {
+    fn default() -> Self {
+        Self {
+            batch_size: 100,
+            concurrency: 4,
+            rate_per_second: None,
+        }
+    }
+}
+
+/// 单个聚合的处理失败
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaintenanceFailure {
+    pub aggregate_id: String,
+    pub error: String,
+}
+
+/// 维护任务汇总
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MaintenanceSummary {
+    /// 处理的聚合数（含失败）
+    pub processed: usize,
+    /// 执行迁移命令并保存了事件的聚合数
+    pub migrated: usize,
+    /// 迁移函数返回 `None` 或命令未产生事件的聚合数
+    pub unchanged: usize,
+    /// 加载时已不存在的聚合数
+    pub missing: usize,
+    /// 处理失败的聚合
+    pub failures: Vec<MaintenanceFailure>,
+}
+
+impl MaintenanceSummary {
+    fn record(&mut self, aggregate_id: String, outcome: Result<Outcome>) {
+        self.processed += 1;
+        match outcome {
+            Ok(Outcome::Migrated) => self.migrated += 1,
+            Ok(Outcome::Unchanged) => self.unchanged += 1,
+            Ok(Outcome::Missing) => self.missing += 1,
+            Err(err) => self.failures.push(MaintenanceFailure {
+                aggregate_id,
+                error: err.to_string(),
+            }),
+        }
+    }
+}
+
+/// 每批完成后的进度
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct MaintenanceProgress {
+    pub job: String,
+    /// 已推进到的检查点（本批最后一个聚合 ID）
+    pub checkpoint: String,
+    /// 本次运行截至当前的汇总
+    pub summary: MaintenanceSummary,
+}
+
+enum Outcome {
+    Migrated,
+    Unchanged,
+    Missing,
+}
+
+type ProgressCallback = Arc<dyn Fn(&MaintenanceProgress) + Send + Sync>;
+
+/// 遍历某类型全部聚合，批量执行迁移命令
+pub struct MaintenanceRunner<E, R> {
+    job: String,
+    events: E,
+    repo: R,
+    config: MaintenanceConfig,
+    context: EventContext,
+    checkpoints: Option<Arc<dyn MaintenanceCheckpointStore>>,
+    on_progress: Option<ProgressCallback>,
+}
+
+impl<E, R> MaintenanceRunner<E, R>
+where
+    E: AggregateIdLister,
+{
+    /// `job` 为任务名，用作检查点的键；`events` 用于列举聚合 ID，`repo` 用于加载与保存
+    pub fn new(job: impl Into<String>, events: E, repo: R) -> Self {
+        Self {
+            job: job.into(),
+            events,
+            repo,
+            config: MaintenanceConfig::default(),
+            context: EventContext::default(),
+            checkpoints: None,
+            on_progress: None,
+        }
+    }
+
+    pub fn with_config(mut self, config: MaintenanceConfig) -> Self {
+        self.config = config;
+        self
+    }
+
+    /// 迁移命令产生的事件使用的上下文（如标记执行主体）
+    pub fn with_context(mut self, context: EventContext) -> Self {
+        self.context = context;
+        self
+    }
+
+    /// 启用可恢复的检查点
+    pub fn with_checkpoints(mut self, checkpoints: Arc<dyn MaintenanceCheckpointStore>) -> Self {
+        self.checkpoints = Some(checkpoints);
+        self
+    }
+
+    /// 每批完成后调用 `on_progress`
+    pub fn with_progress<F>(mut self, on_progress: F) -> Self
+    where
+        F: Fn(&MaintenanceProgress) + Send + Sync + 'static,
+    {
+        self.on_progress = Some(Arc::new(on_progress));
+        self
+    }
+
+    /// 从检查点（若有）之后遍历 `A` 的全部聚合，对 `migrate` 返回的命令执行并保存
+    pub async fn run<A, F>(&self, migrate: F) -> Result<MaintenanceSummary>
+    where
+        A: Aggregate,
+        R: AggregateRepository<A>,
+        F: Fn(&A) -> Option<A::Command> + Send + Sync,
+    {
+        let batch_size = self.config.batch_size.max(1);
+        let concurrency = self.config.concurrency.max(1);
+        let interval = self
+            .config
+            .rate_per_second
+            .map(|rate| Duration::from_secs(1) / rate.max(1));
+        let next_slot = Mutex::new(Instant::now());
+
+        let mut after = match &self.checkpoints {
+            Some(checkpoints) => checkpoints.load(&self.job).await?,
+            None => None,
+        };
+        let mut summary = MaintenanceSummary::default();
+
+        loop {
+            let ids = self
+                .events
+                .list_aggregate_ids::<A>(after.as_deref(), batch_size)
+                .await?;
+            let Some(last) = ids.last().cloned() else {
+                break;
+            };
+
+            let outcomes: Vec<_> = stream::iter(ids)
+                .map(|id| async {
+                    if let Some(interval) = interval {
+                        let slot = {
+                            let mut next = next_slot.lock().unwrap();
+                            let slot = (*next).max(Instant::now());
+                            *next = slot + interval;
+                            slot
+                        };
+                        tokio::time::sleep_until(slot).await;
+                    }
+                    let outcome = self.migrate_one::<A, F>(&id, &migrate).await;
+                    (id, outcome)
+                })
+                .buffer_unordered(concurrency)
+                .collect()
+                .await;
+            for (id, outcome) in outcomes {
+                summary.record(id, outcome);
+            }
+
+            if let Some(checkpoints) = &self.checkpoints {
+                checkpoints.save(&self.job, &last).await?;
+            }
+            if let Some(on_progress) = &self.on_progress {
+                on_progress(&MaintenanceProgress {
+                    job: self.job.clone(),
+                    checkpoint: last.clone(),
+                    summary: summary.clone(),
+                });
+            }
+            after = Some(last);
+        }
+
+        Ok(summary)
+    }
+
+    async fn migrate_one<A, F>(&self, id: &str, migrate: &F) -> Result<Outcome>
+    where
+        A: Aggregate,
+        R: AggregateRepository<A>,
+        F: Fn(&A) -> Option<A::Command> + Send + Sync,
+    {
+        let aggregate_id = A::Id::from_str(id)
+            .map_err(|_| DomainError::invalid_value(format!("invalid {} id: {id}", A::TYPE)))?;
+        let Some(aggregate) = self.repo.load(&aggregate_id).await.map_err(internal)? else {
+            return Ok(Outcome::Missing);
+        };
+        let Some(command) = migrate(&aggregate) else {
+            return Ok(Outcome::Unchanged);
+        };
+
+        let events = aggregate.execute(command).map_err(internal)?;
+        if events.is_empty() {
+            return Ok(Outcome::Unchanged);
+        }
+        self.repo
+            .save(&aggregate, events, self.context.clone())
+            .await
+            .map_err(internal)?;
+        Ok(Outcome::Migrated)
+    }
+}
+
+fn internal(err: impl std::fmt::Display) -> DomainError {
+    DomainError::internal(err.to_string())
+}
@@ -1,21 +1,143 @@
 //! 持久化与事件溯源（persist）
 //!
 //! 定义事件仓储、快照仓储及其通用组合实现，支持：
-//! - 事件持久化与按聚合查询（`EventRepository`）；
-//! - 快照读写与策略（`SnapshotRepository`/`SnapshotPolicy`）；
-//! - 事件上抬（Upcast）与反序列化（`deserialize_events`）；
-//! - 纯事件或事件+快照的聚合仓储实现（`EventSourcedRepo`、`SnapshotPolicyRepo`）。
+//! - 事件位置（`EventPosition`）：`(分区, 分区内位点)`，仅分区内有序，供检查点与订阅恢复使用；
+//! - 事件持久化与按聚合查询（`EventRepository`），误写事件的排除标记（`mark_excluded`，读取跳过、保留审计）；
+//! - 全局事件流读取（`EventStreamReader`，需启用 `eventing`）：跨聚合按全局位点分页读取，`stream_all` 以流产出，
+//!   供读模型重建与追赶订阅使用；
+//! - 事件更正（`admin::EventAdmin`）：追加引用原事件的更正事件，要求执行主体与原因并写入审计记录；
+//! - 快照读写与策略（`SnapshotRepository`/`SnapshotPolicy`，可替换的 `SnapshotStrategy` 如按上抬成本缩短间隔的
+//!   `UpcastCostStrategy`），冷热分层（`TieredSnapshotRepository`）；
+//! - 快照负载编解码（`SnapshotCodec`：`JsonCodec`、`ZstdJsonCodec`/`MessagePackCodec` 需启用对应特性），
+//!   经 `SnapshotRepositoryWithPolicy::with_codec` 编码落盘，`SerializedSnapshot::to_aggregate` 透明解码；
+//! - 快照分代回收（`SnapshotGc`）：按归档水位（`EventArchive`）与保留配置（`SnapshotRetention`）
+//!   删除被更新快照与已归档事件共同取代的历史快照；
+//! - 历史事件回填（`EventImporter`）：保留原始发生时间（`EventEnvelope::new_with`），元数据标记为回填，写入前校验版本连续与时间顺序；
+//! - 快照 NDJSON 导出/导入（`export_snapshots_ndjson`/`import_snapshots_ndjson`，含完整性校验和）与批量写入（`SnapshotRepository::bulk_load`）；
+//! - 聚合二级索引（`IndexedEventRepo`/`AggregateIndexStore`）：按声明从事件提取业务键，唯一性冲突返回 `Conflict`；
+//! - 事件上抬（Upcast）与反序列化（`deserialize_events`），未知事件类型按 `UnknownEventPolicy` 失败/跳过/收集；
+//!   重建时经 `apply_events_with` 直接应用，聚合可通过 `apply_serialized` 按需反序列化字段；
+//! - 个人数据字段策略（`PiiRegistry`）：加密/令牌化/到期擦除，可由类型定义上的 `#[pii(...)]` 字段注解登记（`register_event`/`register_state`）；
+//! - 数据主体访问报告（`SubjectAccessReporter`）：按执行主体与登记的主体标识字段提取事件并生成擦除计划；
+//! - 纯事件或事件+快照的聚合仓储实现（`EventSourcedRepo`、`SnapshotPolicyRepo`）；
+//! - 后台快照（`BackgroundSnapshotter`）：有界队列、按聚合合并，快照落盘移出保存路径；
+//! - 聚合状态缓存装饰器（`CachedAggregateRepo`），可由命令总线按预取提示预热（`AggregateWarmer`）；
+//! - 读写分离装饰器（`ReadWriteSplitRepo`），副本落后时回退主库；
+//! - 多活副本冲突检测（`ReplicaClock`/`ConflictResolver`）：事件标记来源副本与 Lamport 时钟，重放时检测分叉历史并按策略拒绝或合并；
+//! - 双写迁移装饰器（`DualWriteRepo`）：新旧存储影子写、可配置读取来源、记录分歧并支持切换；
+//! - 缓冲批量写入装饰器（`BufferedOutboxWriter`），跨命令合并写入以提升吞吐；
+//! - 按租户的存储配额装饰器（`QuotaRepository`）：计量事件/快照字节数，超额按 `QuotaPolicy` 拒绝或告警；
+//! - 聚合生命周期事件（`LifecycleEvents`）：created/snapshot_taken/archived 写入独立系统事件流；
+//! - 事件模式漂移检测（`SchemaDriftDetector`）：抽样已存储事件，按当前类型与上抬链校验反序列化；
+//! - 批量聚合维护（`MaintenanceRunner`，需启用 `eventing`）：按 `AggregateIdLister::list_aggregate_ids` 分批遍历聚合执行迁移命令，
+//!   支持并发上限、限速、可恢复检查点与进度回调；
+//! - 事件流分析建议（`advisor`）：按阈值标记需提高快照频率或考虑拆分的聚合；
+//! - Postgres 事件仓储（`PgEventRepository`，需启用 `infra-sqlx`）：仅追加事件表、全局位点与版本唯一约束，
+//!   建表语句见 `EVENT_STORE_MIGRATION`。
 //!
-//! 该模块聚焦协议与装配逻辑，具体存储后端（如 Postgres）由上层提供实现并注入。
+//! 该模块聚焦协议与装配逻辑，其他存储后端由上层提供实现并注入。
 //!
+pub mod admin;
+pub mod advisor;
+mod aggregate_cache;
+mod aggregate_index;
+mod aggregate_lister;
 mod aggregate_repository;
+#[cfg(feature = "eventing")]
+mod background_snapshot;
+#[cfg(feature = "eventing")]
+mod buffered_outbox;
+mod dual_write;
+mod event_import;
 mod event_repository;
+#[cfg(feature = "eventing")]
+mod event_stream;
+mod lifecycle;
+#[cfg(feature = "eventing")]
+mod maintenance;
+#[cfg(feature = "infra-sqlx")]
+mod pg_event_repository;
+mod pii;
+mod position;
+mod read_write_split;
+mod replica_conflict;
+mod schema_drift;
 mod serialized_event;
 mod serialized_snapshot;
+mod snapshot_codec;
+mod snapshot_gc;
+mod snapshot_history;
 mod snapshot_repository;
+mod snapshot_transfer;
+mod storage_quota;
+mod subject_access;
+mod tiered_snapshot;
+mod unknown_event;
 
+pub use aggregate_cache::{AggregateCacheStats, AggregateWarmer, CacheWarmer, CachedAggregateRepo};
+pub use aggregate_index::{
+    AggregateIndexStore, AggregateIndexStoreExt, AggregateIndexes, IndexedEventRepo,
+};
+pub use aggregate_lister::AggregateIdLister;
 pub use aggregate_repository::{AggregateRepository, EventSourcedRepo, SnapshotPolicyRepo};
-pub use event_repository::{EventRepository, EventRepositoryExt};
-pub use serialized_event::{SerializedEvent, deserialize_events, serialize_events};
+#[cfg(feature = "eventing")]
+pub use background_snapshot::{BackgroundSnapshotStats, BackgroundSnapshotter};
+#[cfg(feature = "eventing")]
+pub use buffered_outbox::{BufferedOutboxConfig, BufferedOutboxWriter, WriteAck};
+pub use dual_write::{DualWriteRepo, DualWriteStats, ReadSource};
+pub use event_import::{BackfillEvent, EventImporter};
+pub use event_repository::{
+    EventExclusion, EventRepository, EventRepositoryExt, check_expected_versions,
+};
+#[cfg(feature = "eventing")]
+pub use event_stream::{EVENT_STREAM_PAGE_SIZE, EventStreamReader};
+pub use lifecycle::{LifecycleEventKind, LifecycleEventSink, LifecycleEvents};
+#[cfg(feature = "eventing")]
+pub use maintenance::{
+    InMemoryMaintenanceCheckpointStore, MaintenanceCheckpointStore, MaintenanceConfig,
+    MaintenanceFailure, MaintenanceProgress, MaintenanceRunner, MaintenanceSummary,
+};
+#[cfg(feature = "infra-sqlx")]
+pub use pg_event_repository::{EVENT_STORE_MIGRATION, PgEventRepository};
+pub use pii::{PiiCipher, PiiField, PiiPolicy, PiiRegistry, PiiRule};
+pub use position::EventPosition;
+pub use read_write_split::ReadWriteSplitRepo;
+pub use replica_conflict::{
+    ConflictResolution, ConflictResolver, DivergentHistory, LastWriterWins, RejectConflicts,
+    ReplicaBranch, ReplicaClock, detect_divergence, resolve_divergence,
+};
+pub use schema_drift::{DriftGroup, SchemaDriftDetector, SchemaDriftReport};
+pub use serialized_event::{
+    SerializedEvent, apply_events_with, deserialize_events, deserialize_events_with,
+    serialize_events,
+};
 pub use serialized_snapshot::SerializedSnapshot;
-pub use snapshot_repository::{SnapshotPolicy, SnapshotRepository, SnapshotRepositoryWithPolicy};
+#[cfg(feature = "snapshot-msgpack")]
+pub use snapshot_codec::MessagePackCodec;
+#[cfg(feature = "snapshot-zstd")]
+pub use snapshot_codec::ZstdJsonCodec;
+pub use snapshot_codec::{
+    JSON_SNAPSHOT_ENCODING, JsonCodec, MSGPACK_SNAPSHOT_ENCODING, SNAPSHOT_CODEC_ERROR,
+    SnapshotCodec, ZSTD_JSON_SNAPSHOT_ENCODING, builtin_snapshot_codec,
+};
+pub use snapshot_gc::{
+    EventArchive, InMemoryEventArchive, SnapshotGc, SnapshotGcReport, SnapshotRetention,
+};
+pub use snapshot_history::SnapshotHistory;
+pub use snapshot_repository::{
+    SnapshotDecision, SnapshotPolicy, SnapshotRepository, SnapshotRepositoryWithPolicy,
+    SnapshotStrategy, UpcastCostStrategy,
+};
+pub use snapshot_transfer::{
+    SnapshotExportSummary, export_snapshots_ndjson, import_snapshots_ndjson,
+};
+pub use storage_quota::{
+    QuotaEnforcement, QuotaPolicy, QuotaRepository, QuotaWarning, StorageKind, StorageQuota,
+    StorageUsage,
+};
+pub use subject_access::{
+    RedactionAction, RedactionStep, SubjectAccessReport, SubjectAccessReporter, SubjectEvent,
+    SubjectMatch,
+};
+pub use tiered_snapshot::TieredSnapshotRepository;
+pub use unknown_event::{UnknownEventLog, UnknownEventPolicy};
@@ -0,0 +1,364 @@
+//! Postgres 事件仓储（需启用 `infra-sqlx` 特性）
+//!
+//! `PgEventRepository` 以仅追加的 `ddd_events` 表实现 `EventRepository`：
+//! - 位点：`sequence_number`（`BIGSERIAL`）在写入时全局递增分配，读取时回填到 `SerializedEvent`（分区 `0`）；
+//! - 乐观并发：保存前在事务内校验各聚合版本从“当前版本 + 1”连续递增，并由
+//!   `(aggregate_type, aggregate_id, aggregate_version)` 唯一约束兜底并发写入，冲突返回 `Conflict`；
+//! - 事件排除：`ddd_event_exclusions` 记录排除原因与操作人，读取与回放跳过被排除的事件；
+//! - 回放：启用 `eventing` 时实现 `ReplaySource`，按位点或时间戳从历史位置读取。
+//!
+//! 表结构见 `EVENT_STORE_MIGRATION`（即 `migrations/0001_ddd_events.sql`），
+//! 可由 `migrate` 幂等创建，或复制到应用自己的迁移目录中。
+//!
+#[cfg(feature = "eventing")]
+use crate::eventing::{ReplaySource, SubscribeFrom};
+#[cfg(feature = "eventing")]
+use crate::persist::EventStreamReader;
+use crate::{
+    aggregate::Aggregate,
+    error::{DomainError, DomainResult as Result, ErrorKind},
+    persist::{
+        AggregateIdLister, EventExclusion, EventPosition, EventRepository, SerializedEvent,
+        check_expected_versions,
+    },
+};
+use async_trait::async_trait;
+use chrono::{DateTime, Utc};
+use sqlx::types::Json;
+use sqlx::{PgPool, Postgres, Transaction};
+use std::collections::HashMap;
+
+/// 事件存储的建表语句（幂等）
+pub const EVENT_STORE_MIGRATION: &str = include_str!("../../migrations/0001_ddd_events.sql");
+
+/// 基于 Postgres 的事件仓储
+#[derive(Clone)]
+pub struct PgEventRepository {
+    pool: PgPool,
+}
+
+impl PgEventRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+
+    pub fn pool(&self) -> &PgPool {
+        &self.pool
+    }
+
+    /// 创建事件表与排除记录表（幂等）
+    pub async fn migrate(&self) -> Result<()> {
+        sqlx::raw_sql(EVENT_STORE_MIGRATION)
+            .execute(&self.pool)
+            .await?;
+        Ok(())
+    }
+
+    async fn load(
+        &self,
+        aggregate_type: &str,
+        aggregate_id: &str,
+        after_version: usize,
+    ) -> Result<Vec<SerializedEvent>> {
+        let rows: Vec<(i64, Json<SerializedEvent>)> = sqlx::query_as(
+            "SELECT e.sequence_number, e.body FROM ddd_events e
+             WHERE e.aggregate_type = $1 AND e.aggregate_id = $2 AND e.aggregate_version > $3
+               AND NOT EXISTS (SELECT 1 FROM ddd_event_exclusions x WHERE x.event_id = e.event_id)
+             ORDER BY e.aggregate_version",
+        )
+        .bind(aggregate_type)
+        .bind(aggregate_id)
+        .bind(after_version as i64)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(into_event).collect())
+    }
+
+    /// 校验批次内各聚合版本从存储中的当前版本连续递增
+    async fn check_versions(
+        tx: &mut Transaction<'_, Postgres>,
+        events: &[SerializedEvent],
+    ) -> Result<()> {
+        let mut next: HashMap<(&str, &str), usize> = HashMap::new();
+        for event in events {
+            let key = (event.aggregate_type(), event.aggregate_id());
+            let expected = match next.get(&key) {
+                Some(expected) => *expected,
+                None => {
+                    let current: i64 = sqlx::query_scalar(
+                        "SELECT COALESCE(MAX(aggregate_version), 0) FROM ddd_events
+                         WHERE aggregate_type = $1 AND aggregate_id = $2",
+                    )
+                    .bind(key.0)
+                    .bind(key.1)
+                    .fetch_one(&mut **tx)
+                    .await?;
+                    current as usize + 1
+                }
+            };
+            if event.aggregate_version() != expected {
+                return Err(DomainError::conflict(expected, event.aggregate_version()));
+            }
+            next.insert(key, expected + 1);
+        }
+        Ok(())
+    }
+
+    async fn insert(tx: &mut Transaction<'_, Postgres>, events: &[SerializedEvent]) -> Result<()> {
+        for event in events {
+            sqlx::query(
+                "INSERT INTO ddd_events
+                    (event_id, event_type, aggregate_type, aggregate_id, aggregate_version, occurred_at, body)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            )
+            .bind(event.event_id())
+            .bind(event.event_type())
+            .bind(event.aggregate_type())
+            .bind(event.aggregate_id())
+            .bind(event.aggregate_version() as i64)
+            .bind(event.occurred_at())
+            .bind(Json(event))
+            .execute(&mut **tx)
+            .await
+            .map_err(|err| unique_violation(err, event))?;
+        }
+        Ok(())
+    }
+}
+
+fn into_event((sequence, Json(event)): (i64, Json<SerializedEvent>)) -> SerializedEvent {
+    event.with_position(EventPosition::global(sequence))
+}
+
+/// 并发写入撞上唯一约束：版本已被其他写入占用，或事件 ID 重复
+fn unique_violation(err: sqlx::Error, event: &SerializedEvent) -> DomainError {
+    match &err {
+        sqlx::Error::Database(db) if db.is_unique_violation() => DomainError::new(
+            ErrorKind::Conflict,
+            format!(
+                "version conflict: {}/{} version {} or event {} already stored",
+                event.aggregate_type(),
+                event.aggregate_id(),
+                event.aggregate_version(),
+                event.event_id()
+            ),
+        ),
+        _ => DomainError::from(err),
+    }
+}
+
+#[async_trait]
+impl EventRepository for PgEventRepository {
+    async fn get_events<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<Vec<SerializedEvent>> {
+        self.load(A::TYPE, &aggregate_id.to_string(), 0).await
+    }
+
+    async fn get_last_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        last_version: usize,
+    ) -> Result<Vec<SerializedEvent>> {
+        self.load(A::TYPE, &aggregate_id.to_string(), last_version)
+            .await
+    }
+
+    /// 整批在同一事务内写入，任一事件冲突则整体回滚
+    async fn save(&self, events: Vec<SerializedEvent>) -> Result<()> {
+        if events.is_empty() {
+            return Ok(());
+        }
+        let mut tx = self.pool.begin().await?;
+        Self::check_versions(&mut tx, &events).await?;
+        Self::insert(&mut tx, &events).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    /// 事务内比对当前版本，并发写入由版本唯一约束兜底
+    async fn save_with_expected_version<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        events: Vec<SerializedEvent>,
+        expected_version: usize,
+    ) -> Result<()> {
+        check_expected_versions::<A>(aggregate_id, &events, expected_version)?;
+        if events.is_empty() {
+            return Ok(());
+        }
+        let mut tx = self.pool.begin().await?;
+        Self::check_versions(&mut tx, &events).await?;
+        Self::insert(&mut tx, &events).await?;
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn ping(&self) -> Result<()> {
+        sqlx::query("SELECT 1").execute(&self.pool).await?;
+        Ok(())
+    }
+
+    async fn current_version<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<usize> {
+        let version: i64 = sqlx::query_scalar(
+            "SELECT COALESCE(MAX(aggregate_version), 0) FROM ddd_events
+             WHERE aggregate_type = $1 AND aggregate_id = $2",
+        )
+        .bind(A::TYPE)
+        .bind(aggregate_id.to_string())
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(version as usize)
+    }
+
+    async fn exists_many<A: Aggregate>(&self, aggregate_ids: &[A::Id]) -> Result<Vec<bool>> {
+        let ids: Vec<String> = aggregate_ids.iter().map(ToString::to_string).collect();
+        let found: Vec<String> = sqlx::query_scalar(
+            "SELECT DISTINCT aggregate_id FROM ddd_events
+             WHERE aggregate_type = $1 AND aggregate_id = ANY($2)",
+        )
+        .bind(A::TYPE)
+        .bind(&ids)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ids.iter().map(|id| found.contains(id)).collect())
+    }
+
+    async fn mark_excluded(&self, event_id: &str, reason: &str, actor: &str) -> Result<()> {
+        let mut tx = self.pool.begin().await?;
+        let row: Option<(String, String, i64, i64)> = sqlx::query_as(
+            "SELECT e.aggregate_type, e.aggregate_id, e.aggregate_version,
+                    (SELECT MAX(h.aggregate_version) FROM ddd_events h
+                     WHERE h.aggregate_type = e.aggregate_type AND h.aggregate_id = e.aggregate_id)
+             FROM ddd_events e WHERE e.event_id = $1",
+        )
+        .bind(event_id)
+        .fetch_optional(&mut *tx)
+        .await?;
+        let (aggregate_type, aggregate_id, version, head) =
+            row.ok_or_else(|| DomainError::not_found(format!("event {event_id} not found")))?;
+
+        if version == head {
+            return Err(DomainError::invalid_state(format!(
+                "event {event_id} is the latest in its stream; append a correcting event first"
+            ))
+            .with_code("EXCLUSION_OF_HEAD"));
+        }
+
+        let inserted = sqlx::query(
+            "INSERT INTO ddd_event_exclusions (event_id, aggregate_type, aggregate_id, reason, actor)
+             VALUES ($1, $2, $3, $4, $5)
+             ON CONFLICT (event_id) DO NOTHING",
+        )
+        .bind(event_id)
+        .bind(&aggregate_type)
+        .bind(&aggregate_id)
+        .bind(reason)
+        .bind(actor)
+        .execute(&mut *tx)
+        .await?;
+        if inserted.rows_affected() == 0 {
+            return Err(DomainError::invalid_state(format!(
+                "event {event_id} is already excluded"
+            ))
+            .with_code("EVENT_ALREADY_EXCLUDED"));
+        }
+        tx.commit().await?;
+        Ok(())
+    }
+
+    async fn exclusions(&self) -> Result<Vec<EventExclusion>> {
+        let rows: Vec<(String, String, String, String, String, DateTime<Utc>)> = sqlx::query_as(
+            "SELECT event_id, aggregate_type, aggregate_id, reason, actor, excluded_at
+             FROM ddd_event_exclusions ORDER BY excluded_at",
+        )
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(
+                |(event_id, aggregate_type, aggregate_id, reason, actor, excluded_at)| {
+                    EventExclusion {
+                        event_id,
+                        aggregate_type,
+                        aggregate_id,
+                        reason,
+                        actor,
+                        excluded_at,
+                    }
+                },
+            )
+            .collect())
+    }
+}
+
+#[async_trait]
+impl AggregateIdLister for PgEventRepository {
+    async fn list_aggregate_ids<A: Aggregate>(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>> {
+        let ids = sqlx::query_scalar(
+            "SELECT DISTINCT aggregate_id FROM ddd_events
+             WHERE aggregate_type = $1 AND ($2::TEXT IS NULL OR aggregate_id > $2)
+             ORDER BY aggregate_id LIMIT $3",
+        )
+        .bind(A::TYPE)
+        .bind(after)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(ids)
+    }
+}
+
+/// 按全局位点分页读取未排除的事件
+#[cfg(feature = "eventing")]
+#[async_trait]
+impl EventStreamReader for PgEventRepository {
+    async fn read_page(&self, from_sequence: u64, limit: usize) -> Result<Vec<SerializedEvent>> {
+        let rows: Vec<(i64, Json<SerializedEvent>)> = sqlx::query_as(
+            "SELECT e.sequence_number, e.body FROM ddd_events e
+             WHERE e.sequence_number >= $1
+               AND NOT EXISTS (SELECT 1 FROM ddd_event_exclusions x WHERE x.event_id = e.event_id)
+             ORDER BY e.sequence_number
+             LIMIT $2",
+        )
+        .bind(from_sequence as i64)
+        .bind(limit as i64)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows.into_iter().map(into_event).collect())
+    }
+}
+
+/// 按全局位点顺序回放未排除的事件；`Sequence`/`Timestamp` 起点下推到查询条件
+#[cfg(feature = "eventing")]
+#[async_trait]
+impl ReplaySource for PgEventRepository {
+    async fn replay(&self, from: SubscribeFrom) -> Result<Vec<SerializedEvent>> {
+        let (min_sequence, min_occurred_at) = match &from {
+            SubscribeFrom::Latest => return Ok(Vec::new()),
+            SubscribeFrom::Sequence(sequence) => (*sequence as i64, None),
+            SubscribeFrom::Timestamp(at) => (0, Some(*at)),
+            SubscribeFrom::Positions(_) => (0, None),
+        };
+        let rows: Vec<(i64, Json<SerializedEvent>)> = sqlx::query_as(
+            "SELECT e.sequence_number, e.body FROM ddd_events e
+             WHERE e.sequence_number >= $1 AND ($2::timestamptz IS NULL OR e.occurred_at >= $2)
+               AND NOT EXISTS (SELECT 1 FROM ddd_event_exclusions x WHERE x.event_id = e.event_id)
+             ORDER BY e.sequence_number",
+        )
+        .bind(min_sequence)
+        .bind(min_occurred_at)
+        .fetch_all(&self.pool)
+        .await?;
+        Ok(rows
+            .into_iter()
+            .map(into_event)
+            .filter(|event| {
+                let sequence = event.sequence_number().unwrap_or_default();
+                from.includes(sequence as u64, event)
+            })
+            .collect())
+    }
+}
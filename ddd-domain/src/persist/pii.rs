@@ -0,0 +1,415 @@
+//! 个人数据（PII）字段策略
+//!
+//! 以声明方式登记 `(event_type, JSON Pointer) → PiiPolicy`，在序列化路径统一执行：
+//! - `Encrypt`：写入前加密，读取时解密（`PiiCipher` 提供具体算法）；
+//! - `Tokenize`：写入前替换为令牌（不可逆，由外部令牌库维护映射）；
+//! - `EraseAfter`：事件发生超过保留期后擦除为 `null`（由维护任务调用 `redact_expired`）。
+//!
+//! 需要被擦除的字段在事件载荷类型中应为 `Option<T>` 或带 `#[serde(default)]`，
+//! 以便擦除后仍可反序列化。
+//!
+//! 分类也可直接写在类型定义上：`#[domain_event]`/`#[entity]` 的字段属性
+//! `#[pii(category = "email", policy = "tokenize")]`（或 `erase_after_days = N`，缺省为加密）
+//! 生成 `DomainEvent::PII_FIELDS`/`Entity::PII_FIELDS`，由 `register_event`/`register_state`
+//! 登记到本表；实体字段的规则作用于聚合快照（`protect_snapshot`/`reveal_snapshot`）。
+//!
+use crate::{
+    aggregate::Aggregate,
+    domain_event::{DomainEvent, EventEnvelope},
+    error::{DomainError, DomainResult as Result},
+    event_upcaster::EventUpcasterChain,
+    persist::{SerializedEvent, SerializedSnapshot, deserialize_events, serialize_events},
+};
+use chrono::{DateTime, Duration, Utc};
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// 字段级个人数据策略
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PiiPolicy {
+    /// 加密存储，读取时解密
+    Encrypt,
+    /// 令牌化存储（不可逆）
+    Tokenize,
+    /// 超过保留期后擦除
+    EraseAfter(Duration),
+}
+
+impl PiiPolicy {
+    /// 超过 `days` 天后擦除
+    pub const fn erase_after_days(days: i64) -> Self {
+        Self::EraseAfter(Duration::days(days))
+    }
+}
+
+/// 类型定义上声明的个人数据字段（由 `#[pii(...)]` 字段属性生成）
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct PiiField {
+    /// 事件类型；实体（聚合状态）字段为 `None`
+    pub event_type: Option<&'static str>,
+    /// 字段位置（JSON Pointer，相对事件载荷或聚合状态）
+    pub pointer: &'static str,
+    /// 数据分类（如 `email`、`phone`）
+    pub category: &'static str,
+    pub policy: PiiPolicy,
+}
+
+/// 单条字段规则
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PiiRule {
+    /// 字段位置（RFC 6901 JSON Pointer，相对事件载荷）
+    pub pointer: String,
+    pub policy: PiiPolicy,
+    /// 数据分类（经 `#[pii(category = ...)]` 声明时存在）
+    pub category: Option<String>,
+}
+
+/// 加密/令牌化钩子，由基础设施层提供实现（如 KMS、Vault）
+pub trait PiiCipher: Send + Sync {
+    fn encrypt(&self, value: &Value) -> Result<Value>;
+
+    fn decrypt(&self, value: &Value) -> Result<Value>;
+
+    fn tokenize(&self, value: &Value) -> Result<Value>;
+}
+
+/// PII 策略登记表：按事件类型维护字段规则
+#[derive(Debug, Clone, Default)]
+pub struct PiiRegistry {
+    rules: HashMap<String, Vec<PiiRule>>,
+    state_rules: HashMap<String, Vec<PiiRule>>,
+}
+
+impl PiiRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 登记字段策略（同一字段重复登记时以后者为准）
+    pub fn register(
+        mut self,
+        event_type: impl Into<String>,
+        pointer: impl Into<String>,
+        policy: PiiPolicy,
+    ) -> Self {
+        upsert(
+            self.rules.entry(event_type.into()).or_default(),
+            PiiRule {
+                pointer: pointer.into(),
+                policy,
+                category: None,
+            },
+        );
+        self
+    }
+
+    /// 登记事件类型上 `#[pii(...)]` 声明的全部字段
+    pub fn register_event<E: DomainEvent>(mut self) -> Self {
+        for field in E::PII_FIELDS {
+            if let Some(event_type) = field.event_type {
+                upsert(
+                    self.rules.entry(event_type.to_string()).or_default(),
+                    field.into(),
+                );
+            }
+        }
+        self
+    }
+
+    /// 登记聚合状态上 `#[pii(...)]` 声明的字段（作用于该聚合类型的快照）
+    pub fn register_state<A: Aggregate>(mut self) -> Self {
+        let rules = self.state_rules.entry(A::TYPE.to_string()).or_default();
+        for field in A::PII_FIELDS {
+            upsert(rules, field.into());
+        }
+        self
+    }
+
+    /// 获取事件类型的全部规则
+    pub fn rules_for(&self, event_type: &str) -> &[PiiRule] {
+        self.rules.get(event_type).map(Vec::as_slice).unwrap_or(&[])
+    }
+
+    /// 获取聚合类型快照的全部规则
+    pub fn state_rules_for(&self, aggregate_type: &str) -> &[PiiRule] {
+        self.state_rules
+            .get(aggregate_type)
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+
+    /// 已登记的事件类型
+    pub fn event_types(&self) -> impl Iterator<Item = &str> {
+        self.rules.keys().map(String::as_str)
+    }
+
+    /// 写入前保护：加密/令牌化登记字段
+    pub fn protect(
+        &self,
+        event: SerializedEvent,
+        cipher: &dyn PiiCipher,
+    ) -> Result<SerializedEvent> {
+        self.transform(event, |rule, value| match rule.policy {
+            PiiPolicy::Encrypt => cipher.encrypt(value).map(Some),
+            PiiPolicy::Tokenize => cipher.tokenize(value).map(Some),
+            PiiPolicy::EraseAfter(_) => Ok(None),
+        })
+    }
+
+    /// 读取后还原：解密加密字段（令牌化字段保持令牌）
+    pub fn reveal(
+        &self,
+        event: SerializedEvent,
+        cipher: &dyn PiiCipher,
+    ) -> Result<SerializedEvent> {
+        self.transform(event, |rule, value| match rule.policy {
+            PiiPolicy::Encrypt if !value.is_null() => cipher.decrypt(value).map(Some),
+            _ => Ok(None),
+        })
+    }
+
+    /// 擦除已超过保留期的字段；无字段变化时返回 `None`
+    ///
+    /// 供维护任务遍历事件并回写存储使用。
+    pub fn redact_expired(
+        &self,
+        event: &SerializedEvent,
+        now: DateTime<Utc>,
+    ) -> Result<Option<SerializedEvent>> {
+        let age = now - event.occurred_at();
+        let mut changed = false;
+
+        let redacted = self.transform(event.clone(), |rule, value| match rule.policy {
+            PiiPolicy::EraseAfter(retention) if age >= retention && !value.is_null() => {
+                changed = true;
+                Ok(Some(Value::Null))
+            }
+            _ => Ok(None),
+        })?;
+
+        Ok(changed.then_some(redacted))
+    }
+
+    /// 快照写入前保护：加密/令牌化聚合状态中登记的字段
+    pub fn protect_snapshot(
+        &self,
+        snapshot: SerializedSnapshot,
+        cipher: &dyn PiiCipher,
+    ) -> Result<SerializedSnapshot> {
+        self.transform_snapshot(snapshot, |rule, value| match rule.policy {
+            PiiPolicy::Encrypt => cipher.encrypt(value).map(Some),
+            PiiPolicy::Tokenize => cipher.tokenize(value).map(Some),
+            PiiPolicy::EraseAfter(_) => Ok(None),
+        })
+    }
+
+    /// 快照读取后还原：解密加密字段
+    pub fn reveal_snapshot(
+        &self,
+        snapshot: SerializedSnapshot,
+        cipher: &dyn PiiCipher,
+    ) -> Result<SerializedSnapshot> {
+        self.transform_snapshot(snapshot, |rule, value| match rule.policy {
+            PiiPolicy::Encrypt if !value.is_null() => cipher.decrypt(value).map(Some),
+            _ => Ok(None),
+        })
+    }
+
+    /// 序列化事件信封并执行保护策略
+    pub fn serialize_events<A>(
+        &self,
+        cipher: &dyn PiiCipher,
+        events: &[EventEnvelope<A>],
+    ) -> Result<Vec<SerializedEvent>>
+    where
+        A: Aggregate,
+    {
+        serialize_events(events)?
+            .into_iter()
+            .map(|e| self.protect(e, cipher))
+            .collect()
+    }
+
+    /// 还原受保护字段后上抬并反序列化
+    pub fn deserialize_events<A>(
+        &self,
+        cipher: &dyn PiiCipher,
+        upcaster_chain: &EventUpcasterChain,
+        events: Vec<SerializedEvent>,
+    ) -> Result<Vec<EventEnvelope<A>>>
+    where
+        A: Aggregate,
+    {
+        let events = events
+            .into_iter()
+            .map(|e| self.reveal(e, cipher))
+            .collect::<Result<Vec<_>>>()?;
+
+        deserialize_events::<A>(upcaster_chain, events)
+    }
+
+    /// 对事件载荷中登记的字段逐一应用变换；`f` 返回 `None` 表示保持原值
+    fn transform<F>(&self, event: SerializedEvent, f: F) -> Result<SerializedEvent>
+    where
+        F: FnMut(&PiiRule, &Value) -> Result<Option<Value>>,
+    {
+        let rules = self.rules_for(event.event_type());
+        if rules.is_empty() {
+            return Ok(event);
+        }
+
+        let mut payload = event.payload().clone();
+        apply_rules(rules, &mut payload, f)?;
+
+        let encoding = event.content_encoding().map(ToString::to_string);
+        Ok(event.with_encoded_payload(payload, encoding))
+    }
+
+    fn transform_snapshot<F>(
+        &self,
+        snapshot: SerializedSnapshot,
+        f: F,
+    ) -> Result<SerializedSnapshot>
+    where
+        F: FnMut(&PiiRule, &Value) -> Result<Option<Value>>,
+    {
+        let rules = self.state_rules_for(snapshot.aggregate_type());
+        if rules.is_empty() {
+            return Ok(snapshot);
+        }
+
+        let mut payload = snapshot.payload().clone();
+        apply_rules(rules, &mut payload, f)?;
+
+        Ok(SerializedSnapshot::builder()
+            .aggregate_id(snapshot.aggregate_id().to_string())
+            .aggregate_type(snapshot.aggregate_type().to_string())
+            .aggregate_version(snapshot.aggregate_version())
+            .payload(payload)
+            .build())
+    }
+}
+
+impl From<&PiiField> for PiiRule {
+    fn from(field: &PiiField) -> Self {
+        Self {
+            pointer: field.pointer.to_string(),
+            policy: field.policy,
+            category: Some(field.category.to_string()),
+        }
+    }
+}
+
+/// 同一字段重复登记时以后者为准
+fn upsert(rules: &mut Vec<PiiRule>, rule: PiiRule) {
+    rules.retain(|r| r.pointer != rule.pointer);
+    rules.push(rule);
+}
+
+fn apply_rules<F>(rules: &[PiiRule], payload: &mut Value, mut f: F) -> Result<()>
+where
+    F: FnMut(&PiiRule, &Value) -> Result<Option<Value>>,
+{
+    for rule in rules {
+        let Some(slot) = payload.pointer_mut(&rule.pointer) else {
+            continue;
+        };
+        if let Some(next) = f(rule, slot).map_err(|e| pii_error(&rule.pointer, e))? {
+            *slot = next;
+        }
+    }
+    Ok(())
+}
+
+fn pii_error(pointer: &str, err: DomainError) -> DomainError {
+    DomainError::internal(format!("pii policy failed at {pointer}: {err}"))
+        .with_code("PII_POLICY_ERROR")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct ReverseCipher;
+
+    impl PiiCipher for ReverseCipher {
+        fn encrypt(&self, value: &Value) -> Result<Value> {
+            let s = value.as_str().unwrap_or_default();
+            Ok(Value::String(format!(
+                "enc:{}",
+                s.chars().rev().collect::<String>()
+            )))
+        }
+
+        fn decrypt(&self, value: &Value) -> Result<Value> {
+            let s = value
+                .as_str()
+                .and_then(|s| s.strip_prefix("enc:"))
+                .ok_or_else(|| DomainError::invalid_value("not encrypted"))?;
+            Ok(Value::String(s.chars().rev().collect()))
+        }
+
+        fn tokenize(&self, _value: &Value) -> Result<Value> {
+            Ok(Value::String("tok_1".into()))
+        }
+    }
+
+    fn mk_event(occurred_at: DateTime<Utc>) -> SerializedEvent {
+        SerializedEvent::builder()
+            .event_id("e-1".to_string())
+            .event_type("user.registered".to_string())
+            .event_version(1)
+            .aggregate_id("u-1".to_string())
+            .aggregate_type("user".to_string())
+            .aggregate_version(1)
+            .occurred_at(occurred_at)
+            .payload(serde_json::json!({
+                "Registered": {"email": "a@b.c", "phone": "123", "ip": "10.0.0.1", "name": "x"}
+            }))
+            .context(serde_json::json!({}))
+            .build()
+    }
+
+    fn registry() -> PiiRegistry {
+        PiiRegistry::new()
+            .register("user.registered", "/Registered/email", PiiPolicy::Encrypt)
+            .register("user.registered", "/Registered/phone", PiiPolicy::Tokenize)
+            .register(
+                "user.registered",
+                "/Registered/ip",
+                PiiPolicy::EraseAfter(Duration::days(30)),
+            )
+    }
+
+    #[test]
+    fn protect_and_reveal_roundtrip() {
+        let registry = registry();
+        let ev = mk_event(Utc::now());
+
+        let protected = registry.protect(ev, &ReverseCipher).unwrap();
+        let p = &protected.payload()["Registered"];
+        assert_eq!(p["email"], "enc:c.b@a");
+        assert_eq!(p["phone"], "tok_1");
+        assert_eq!(p["ip"], "10.0.0.1");
+        assert_eq!(p["name"], "x");
+
+        let revealed = registry.reveal(protected, &ReverseCipher).unwrap();
+        let p = &revealed.payload()["Registered"];
+        assert_eq!(p["email"], "a@b.c");
+        assert_eq!(p["phone"], "tok_1");
+    }
+
+    #[test]
+    fn redact_only_after_retention() {
+        let registry = registry();
+        let now = Utc::now();
+
+        let fresh = mk_event(now - Duration::days(1));
+        assert!(registry.redact_expired(&fresh, now).unwrap().is_none());
+
+        let old = mk_event(now - Duration::days(31));
+        let redacted = registry.redact_expired(&old, now).unwrap().unwrap();
+        assert!(redacted.payload()["Registered"]["ip"].is_null());
+        assert!(registry.redact_expired(&redacted, now).unwrap().is_none());
+    }
+}
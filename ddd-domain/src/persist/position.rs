@@ -0,0 +1,68 @@
+use serde::{Deserialize, Serialize};
+use std::cmp::Ordering;
+use std::fmt;
+
+/// 事件在分区存储中的位置：`(分区, 分区内位点)`
+///
+/// 分片/分区后端（Kafka 分区、按租户分片的事件表等）只保证分区内有序，
+/// 不存在跨分区单调递增的全局位点；因此仅同一分区内的位置可比较，
+/// 跨分区比较返回 `None`。单分区存储统一使用分区 `0`，与原有的全局 `sequence_number` 等价。
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+pub struct EventPosition {
+    pub partition: u32,
+    pub sequence: i64,
+}
+
+impl EventPosition {
+    pub fn new(partition: u32, sequence: i64) -> Self {
+        Self {
+            partition,
+            sequence,
+        }
+    }
+
+    /// 单分区存储中的位置（分区 `0`）
+    pub fn global(sequence: i64) -> Self {
+        Self::new(0, sequence)
+    }
+
+    /// 同一分区内的下一个位置（用于从检查点之后恢复订阅）
+    pub fn next(self) -> Self {
+        Self::new(self.partition, self.sequence + 1)
+    }
+
+    /// 是否位于同一分区且不早于 `other`
+    pub fn reached(&self, other: &EventPosition) -> bool {
+        matches!(
+            self.partial_cmp(other),
+            Some(Ordering::Greater | Ordering::Equal)
+        )
+    }
+}
+
+impl PartialOrd for EventPosition {
+    fn partial_cmp(&self, other: &Self) -> Option<Ordering> {
+        (self.partition == other.partition).then(|| self.sequence.cmp(&other.sequence))
+    }
+}
+
+impl fmt::Display for EventPosition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}", self.partition, self.sequence)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn orders_only_within_a_partition() {
+        let a = EventPosition::new(1, 5);
+        assert!(a < EventPosition::new(1, 6));
+        assert!(a.next().reached(&EventPosition::new(1, 6)));
+        assert_eq!(a.partial_cmp(&EventPosition::new(2, 1)), None);
+        assert!(!a.reached(&EventPosition::new(2, 1)));
+        assert_eq!(EventPosition::global(3).to_string(), "0:3");
+    }
+}
@@ -0,0 +1,213 @@
+//! 读写分离事件仓储（ReadWriteSplitRepo）
+//!
+//! 将事件写入主库（primary），读取优先走只读副本（replica）；
+//! 当副本中该聚合的最大版本落后于期望版本时（复制延迟），回退到主库读取，
+//! 避免基于过期事件流重建聚合。
+//!
+//! 写入记录只保留复制延迟窗口（`with_replication_lag`，默认 30 秒）内的条目，访问时清理过期条目；
+//! 且只反映经由同一装饰器实例的写入：多实例部署时，其他实例写入的事件仍可能从落后的副本读取。
+//!
+use crate::{
+    aggregate::Aggregate,
+    error::DomainResult as Result,
+    persist::{AggregateIdLister, EventExclusion, EventRepository, SerializedEvent},
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+/// 默认复制延迟窗口
+const DEFAULT_REPLICATION_LAG: Duration = Duration::from_secs(30);
+
+/// `EventRepository` 的读写分离装饰器
+///
+/// - `R`：只读副本仓储
+/// - `W`：主库仓储
+///
+/// 期望版本来源：
+/// - 经由本装饰器写入的事件（记录每个聚合已写入的最大版本，超过复制延迟窗口后过期）；
+/// - `get_last_events` 的 `last_version` 参数（调用方已知的版本，如快照版本）。
+///
+/// 可作为 `EventSourcedRepo` / `SnapshotPolicyRepo` 的事件仓储直接注入。
+pub struct ReadWriteSplitRepo<R, W> {
+    replica: Arc<R>,
+    primary: Arc<W>,
+    replication_lag: Duration,
+    written: Mutex<HashMap<(String, String), (usize, Instant)>>,
+}
+
+impl<R, W> ReadWriteSplitRepo<R, W>
+where
+    R: EventRepository,
+    W: EventRepository,
+{
+    pub fn new(replica: Arc<R>, primary: Arc<W>) -> Self {
+        Self {
+            replica,
+            primary,
+            replication_lag: DEFAULT_REPLICATION_LAG,
+            written: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 副本复制延迟的上限：写入记录超过该时长后视为副本已追平并被清理
+    pub fn with_replication_lag(mut self, replication_lag: Duration) -> Self {
+        self.replication_lag = replication_lag;
+        self
+    }
+
+    /// 经由本装饰器写入的、该聚合的最大版本（复制延迟窗口内）
+    pub fn written_version<A: Aggregate>(&self, aggregate_id: &A::Id) -> Option<usize> {
+        let key = (A::TYPE.to_string(), aggregate_id.to_string());
+        let mut written = self.written.lock().unwrap();
+        match written.get(&key) {
+            Some((version, at)) if at.elapsed() < self.replication_lag => Some(*version),
+            Some(_) => {
+                written.remove(&key);
+                None
+            }
+            None => None,
+        }
+    }
+
+    /// 当前保留的写入记录数
+    pub fn tracked_aggregates(&self) -> usize {
+        self.written.lock().unwrap().len()
+    }
+
+    fn is_stale(events: &[SerializedEvent], known_version: usize, expected: usize) -> bool {
+        let max_version = events
+            .iter()
+            .map(SerializedEvent::aggregate_version)
+            .max()
+            .unwrap_or(known_version);
+
+        max_version < expected
+    }
+
+    fn record_written(&self, versions: Vec<((String, String), usize)>) {
+        let mut written = self.written.lock().unwrap();
+        let now = Instant::now();
+        written.retain(|_, (_, at)| now.duration_since(*at) < self.replication_lag);
+
+        for (key, version) in versions {
+            let current = written.entry(key).or_insert((version, now));
+            *current = (current.0.max(version), now);
+        }
+    }
+}
+
+#[async_trait]
+impl<R, W> EventRepository for ReadWriteSplitRepo<R, W>
+where
+    R: EventRepository,
+    W: EventRepository,
+{
+    async fn get_events<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<Vec<SerializedEvent>> {
+        let events = self.replica.get_events::<A>(aggregate_id).await?;
+
+        match self.written_version::<A>(aggregate_id) {
+            Some(expected) if Self::is_stale(&events, 0, expected) => {
+                self.primary.get_events::<A>(aggregate_id).await
+            }
+            _ => Ok(events),
+        }
+    }
+
+    async fn get_last_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        last_version: usize,
+    ) -> Result<Vec<SerializedEvent>> {
+        let events = self
+            .replica
+            .get_last_events::<A>(aggregate_id, last_version)
+            .await?;
+
+        let expected = self
+            .written_version::<A>(aggregate_id)
+            .unwrap_or_default()
+            .max(last_version);
+
+        if Self::is_stale(&events, last_version, expected) {
+            return self
+                .primary
+                .get_last_events::<A>(aggregate_id, last_version)
+                .await;
+        }
+
+        Ok(events)
+    }
+
+    async fn save(&self, events: Vec<SerializedEvent>) -> Result<()> {
+        let versions = events
+            .iter()
+            .map(|e| {
+                let key = (e.aggregate_type().to_string(), e.aggregate_id().to_string());
+                (key, e.aggregate_version())
+            })
+            .collect();
+
+        self.primary.save(events).await?;
+        self.record_written(versions);
+        Ok(())
+    }
+
+    /// 版本校验以主库为准，避免副本落后导致误判
+    async fn save_with_expected_version<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        events: Vec<SerializedEvent>,
+        expected_version: usize,
+    ) -> Result<()> {
+        let written = expected_version + events.len();
+        self.primary
+            .save_with_expected_version::<A>(aggregate_id, events, expected_version)
+            .await?;
+        self.record_written(vec![(
+            (A::TYPE.to_string(), aggregate_id.to_string()),
+            written,
+        )]);
+        Ok(())
+    }
+
+    async fn ping(&self) -> Result<()> {
+        self.primary.ping().await?;
+        self.replica.ping().await
+    }
+
+    async fn current_version<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<usize> {
+        let version = self.replica.current_version::<A>(aggregate_id).await?;
+
+        match self.written_version::<A>(aggregate_id) {
+            Some(expected) if version < expected => {
+                self.primary.current_version::<A>(aggregate_id).await
+            }
+            _ => Ok(version),
+        }
+    }
+
+    async fn mark_excluded(&self, event_id: &str, reason: &str, actor: &str) -> Result<()> {
+        self.primary.mark_excluded(event_id, reason, actor).await
+    }
+
+    async fn exclusions(&self) -> Result<Vec<EventExclusion>> {
+        self.primary.exclusions().await
+    }
+}
+
+#[async_trait]
+impl<R, W> AggregateIdLister for ReadWriteSplitRepo<R, W>
+where
+    R: EventRepository,
+    W: EventRepository + AggregateIdLister,
+{
+    async fn list_aggregate_ids<A: Aggregate>(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>> {
+        self.primary.list_aggregate_ids::<A>(after, limit).await
+    }
+}
@@ -0,0 +1,228 @@
+//! 多写者冲突检测（Active-Active 复制）
+//!
+//! 多活部署中，各副本可能同时为同一聚合写入事件，复制合并后同一聚合版本出现多个事件，
+//! 直接重放会把两段互不知情的历史叠加到聚合上。本模块在重放时检测并交由可替换的策略处理：
+//! - `ReplicaClock`：为本副本写入的事件标记来源副本与 Lamport 时钟（`SerializedEvent::origin_replica`/`lamport`），
+//!   重放时观察已存储事件的时钟，保证因果在后的写入时钟更大；
+//! - `detect_divergence`：找到首个出现多个事件的版本（分叉点），将其后的事件按来源副本分组为分支；
+//! - `ConflictResolver`：决定采用哪个分支或给出自定义合并后的事件；默认 `RejectConflicts` 返回
+//!   `REPLICA_CONFLICT` 错误（至少检测出来，而非静默损坏），`LastWriterWins` 采用分支末端时钟最大者。
+//!
+//! 仅能检测重放范围内的分叉：从快照恢复时，分叉点早于快照版本的冲突不可见。
+//!
+use crate::{
+    error::{DomainError, DomainResult},
+    persist::SerializedEvent,
+};
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// 本副本的 Lamport 时钟
+#[derive(Debug)]
+pub struct ReplicaClock {
+    replica_id: String,
+    counter: AtomicU64,
+}
+
+impl ReplicaClock {
+    pub fn new(replica_id: impl Into<String>) -> Self {
+        Self {
+            replica_id: replica_id.into(),
+            counter: AtomicU64::new(0),
+        }
+    }
+
+    pub fn replica_id(&self) -> &str {
+        &self.replica_id
+    }
+
+    /// 当前时钟值
+    pub fn current(&self) -> u64 {
+        self.counter.load(Ordering::SeqCst)
+    }
+
+    /// 观察到其他事件的时钟：本地时钟至少推进到该值
+    pub fn observe(&self, lamport: u64) {
+        self.counter.fetch_max(lamport, Ordering::SeqCst);
+    }
+
+    /// 为待写入的事件逐个递增时钟并标记来源副本
+    pub fn stamp(&self, events: Vec<SerializedEvent>) -> Vec<SerializedEvent> {
+        events
+            .into_iter()
+            .map(|event| {
+                let lamport = self.counter.fetch_add(1, Ordering::SeqCst) + 1;
+                event.with_replica(self.replica_id.clone(), lamport)
+            })
+            .collect()
+    }
+}
+
+/// 分叉点之后某个来源副本写入的事件
+#[derive(Debug, Clone)]
+pub struct ReplicaBranch {
+    /// 来源副本；未标记来源的事件归入 `None`
+    pub origin_replica: Option<String>,
+    pub events: Vec<SerializedEvent>,
+}
+
+impl ReplicaBranch {
+    /// 分支末端事件的 Lamport 时钟
+    pub fn tip_lamport(&self) -> Option<u64> {
+        self.events.last().and_then(SerializedEvent::lamport)
+    }
+}
+
+/// 检测到的分叉历史
+#[derive(Debug, Clone)]
+pub struct DivergentHistory {
+    pub aggregate_type: String,
+    pub aggregate_id: String,
+    /// 首个出现多个事件的聚合版本
+    pub fork_version: usize,
+    /// 分叉点之后按来源副本分组的事件（按首次出现顺序）
+    pub branches: Vec<ReplicaBranch>,
+}
+
+impl DivergentHistory {
+    fn describe(&self) -> String {
+        let replicas: Vec<&str> = self
+            .branches
+            .iter()
+            .map(|b| b.origin_replica.as_deref().unwrap_or("<unknown>"))
+            .collect();
+        format!(
+            "{} {} diverged at version {} between replicas {}",
+            self.aggregate_type,
+            self.aggregate_id,
+            self.fork_version,
+            replicas.join(", ")
+        )
+    }
+}
+
+/// 冲突处理结果
+#[derive(Debug, Clone)]
+pub enum ConflictResolution {
+    /// 采用指定下标的分支，其余分支的事件不参与重放
+    Branch(usize),
+    /// 采用自定义合并后的事件，须从分叉版本起版本连续
+    Events(Vec<SerializedEvent>),
+    /// 无法处理，重放失败
+    Reject,
+}
+
+/// 分叉历史的处理策略
+pub trait ConflictResolver: Send + Sync {
+    fn resolve(&self, history: &DivergentHistory) -> ConflictResolution;
+}
+
+/// 拒绝任何分叉（默认）
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RejectConflicts;
+
+impl ConflictResolver for RejectConflicts {
+    fn resolve(&self, _history: &DivergentHistory) -> ConflictResolution {
+        ConflictResolution::Reject
+    }
+}
+
+/// 采用末端 Lamport 时钟最大的分支，时钟相同时按副本名较大者
+#[derive(Debug, Clone, Copy, Default)]
+pub struct LastWriterWins;
+
+impl ConflictResolver for LastWriterWins {
+    fn resolve(&self, history: &DivergentHistory) -> ConflictResolution {
+        history
+            .branches
+            .iter()
+            .enumerate()
+            .max_by(|(_, a), (_, b)| {
+                (a.tip_lamport(), &a.origin_replica).cmp(&(b.tip_lamport(), &b.origin_replica))
+            })
+            .map_or(ConflictResolution::Reject, |(index, _)| {
+                ConflictResolution::Branch(index)
+            })
+    }
+}
+
+/// 检测单个聚合事件流中的分叉：同一版本出现多个不同事件（事件 ID 不同）时返回分叉历史
+pub fn detect_divergence(events: &[SerializedEvent]) -> Option<DivergentHistory> {
+    let mut seen: BTreeMap<usize, &str> = BTreeMap::new();
+    let fork_version = events.iter().find_map(|e| {
+        let first_id = *seen.entry(e.aggregate_version()).or_insert(e.event_id());
+        (first_id != e.event_id()).then_some(e.aggregate_version())
+    })?;
+    let first = events.first()?;
+
+    let mut branches: Vec<ReplicaBranch> = Vec::new();
+    for event in events
+        .iter()
+        .filter(|e| e.aggregate_version() >= fork_version)
+    {
+        let origin = event.origin_replica();
+        match branches
+            .iter_mut()
+            .find(|b| b.origin_replica.as_deref() == origin)
+        {
+            Some(branch) => branch.events.push(event.clone()),
+            None => branches.push(ReplicaBranch {
+                origin_replica: origin.map(ToString::to_string),
+                events: vec![event.clone()],
+            }),
+        }
+    }
+
+    Some(DivergentHistory {
+        aggregate_type: first.aggregate_type().to_string(),
+        aggregate_id: first.aggregate_id().to_string(),
+        fork_version,
+        branches,
+    })
+}
+
+/// 检测分叉并按 `resolver` 处理，返回可安全重放的事件（无分叉时原样返回）
+pub fn resolve_divergence(
+    events: Vec<SerializedEvent>,
+    resolver: &dyn ConflictResolver,
+) -> DomainResult<Vec<SerializedEvent>> {
+    let Some(history) = detect_divergence(&events) else {
+        return Ok(events);
+    };
+
+    let suffix = match resolver.resolve(&history) {
+        ConflictResolution::Branch(index) => match history.branches.get(index) {
+            Some(branch) => branch.events.clone(),
+            None => {
+                return Err(conflict(format!(
+                    "{}: resolver chose missing branch {index}",
+                    history.describe()
+                )));
+            }
+        },
+        ConflictResolution::Events(events) => events,
+        ConflictResolution::Reject => return Err(conflict(history.describe())),
+    };
+
+    let consecutive = suffix
+        .iter()
+        .enumerate()
+        .all(|(i, e)| e.aggregate_version() == history.fork_version + i);
+    if !consecutive {
+        return Err(conflict(format!(
+            "{}: resolved events are not consecutive from the fork version",
+            history.describe()
+        )));
+    }
+
+    let mut resolved: Vec<SerializedEvent> = events
+        .into_iter()
+        .filter(|e| e.aggregate_version() < history.fork_version)
+        .collect();
+    resolved.extend(suffix);
+    Ok(resolved)
+}
+
+fn conflict(msg: String) -> DomainError {
+    DomainError::invalid_state(msg).with_code("REPLICA_CONFLICT")
+}
@@ -0,0 +1,167 @@
+//! 事件模式漂移检测（Schema Drift）
+//!
+//! 部署前从全局事件流中按 (聚合类型, 事件类型, 事件版本) 抽取最近的若干事件，
+//! 使用当前代码中的事件类型与上抬链逐一尝试反序列化，并按类型/版本汇总失败。
+//! 适合在 CI 或预发环境运行，提前发现代码与已存储事件之间的不兼容。
+//!
+use crate::{
+    aggregate::Aggregate, domain_event::EventEnvelope, event_upcaster::EventUpcasterChain,
+    persist::SerializedEvent,
+};
+use std::collections::{BTreeMap, BTreeSet, HashMap, VecDeque};
+use std::sync::Arc;
+
+type CheckFn =
+    Box<dyn Fn(&EventUpcasterChain, &SerializedEvent) -> Result<(), String> + Send + Sync>;
+
+/// 单个事件类型/版本的漂移情况（仅包含存在失败的分组）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DriftGroup {
+    pub aggregate_type: String,
+    pub event_type: String,
+    pub event_version: usize,
+    /// 抽样事件数
+    pub sampled: usize,
+    /// 失败事件 ID 与错误信息
+    pub failures: Vec<(String, String)>,
+}
+
+/// 漂移检测报告
+#[derive(Debug, Clone, Default)]
+pub struct SchemaDriftReport {
+    /// 实际校验的事件数
+    pub sampled: usize,
+    /// 存在失败的分组，按 (聚合类型, 事件类型, 版本) 排序
+    pub groups: Vec<DriftGroup>,
+    /// 未注册聚合、因而未校验的聚合类型
+    pub unchecked_aggregate_types: Vec<String>,
+}
+
+impl SchemaDriftReport {
+    pub fn is_clean(&self) -> bool {
+        self.groups.is_empty()
+    }
+
+    /// 断言无漂移，否则 panic 并列出全部失败分组
+    pub fn assert_clean(&self) {
+        assert!(
+            self.is_clean(),
+            "schema drift detected:\n{}",
+            self.groups
+                .iter()
+                .map(|g| format!(
+                    "{}/{} v{}: {} of {} sampled events failed, e.g. {}",
+                    g.aggregate_type,
+                    g.event_type,
+                    g.event_version,
+                    g.failures.len(),
+                    g.sampled,
+                    g.failures[0].1
+                ))
+                .collect::<Vec<_>>()
+                .join("\n")
+        );
+    }
+}
+
+/// 事件模式漂移检测器
+///
+/// 通过 `aggregate::<A>()` 登记需要校验的聚合；输入事件应按全局顺序（旧 → 新）排列，
+/// 每个分组保留最近的 `sample_size` 个事件参与校验。
+pub struct SchemaDriftDetector {
+    upcaster_chain: Arc<EventUpcasterChain>,
+    sample_size: usize,
+    checks: HashMap<&'static str, CheckFn>,
+}
+
+impl SchemaDriftDetector {
+    pub fn new(upcaster_chain: Arc<EventUpcasterChain>) -> Self {
+        Self {
+            upcaster_chain,
+            sample_size: 20,
+            checks: HashMap::new(),
+        }
+    }
+
+    /// 每个事件类型/版本抽样的事件数（默认 20）
+    pub fn sample_size(mut self, sample_size: usize) -> Self {
+        self.sample_size = sample_size.max(1);
+        self
+    }
+
+    /// 登记聚合，按其当前事件类型校验 `A::TYPE` 下的事件
+    pub fn aggregate<A: Aggregate + 'static>(mut self) -> Self {
+        self.checks.insert(A::TYPE, Box::new(check::<A>));
+        self
+    }
+
+    pub fn detect<I>(&self, events: I) -> SchemaDriftReport
+    where
+        I: IntoIterator<Item = SerializedEvent>,
+    {
+        let mut samples: BTreeMap<(String, String, usize), VecDeque<SerializedEvent>> =
+            BTreeMap::new();
+        let mut unchecked = BTreeSet::new();
+
+        for event in events {
+            if !self.checks.contains_key(event.aggregate_type()) {
+                unchecked.insert(event.aggregate_type().to_string());
+                continue;
+            }
+
+            let key = (
+                event.aggregate_type().to_string(),
+                event.event_type().to_string(),
+                event.event_version(),
+            );
+            let sample = samples.entry(key).or_default();
+            if sample.len() == self.sample_size {
+                sample.pop_front();
+            }
+            sample.push_back(event);
+        }
+
+        let mut report = SchemaDriftReport {
+            unchecked_aggregate_types: unchecked.into_iter().collect(),
+            ..Default::default()
+        };
+
+        for ((aggregate_type, event_type, event_version), sample) in samples {
+            let check = &self.checks[aggregate_type.as_str()];
+            report.sampled += sample.len();
+
+            let failures: Vec<(String, String)> = sample
+                .iter()
+                .filter_map(|e| {
+                    check(&self.upcaster_chain, e)
+                        .err()
+                        .map(|err| (e.event_id().to_string(), err))
+                })
+                .collect();
+
+            if !failures.is_empty() {
+                report.groups.push(DriftGroup {
+                    aggregate_type,
+                    event_type,
+                    event_version,
+                    sampled: sample.len(),
+                    failures,
+                });
+            }
+        }
+
+        report
+    }
+}
+
+/// 上抬并反序列化单个事件
+fn check<A: Aggregate>(chain: &EventUpcasterChain, event: &SerializedEvent) -> Result<(), String> {
+    let upcasted = chain
+        .upcast_all(vec![event.clone()])
+        .map_err(|e| e.to_string())?;
+
+    for e in &upcasted {
+        EventEnvelope::<A>::try_from(e).map_err(|e| e.to_string())?;
+    }
+    Ok(())
+}
@@ -5,14 +5,18 @@
 //!
 use crate::{
     aggregate::Aggregate,
-    domain_event::{DomainEvent, EventContext, EventEnvelope, Metadata},
+    domain_event::{
+        DomainEvent, EventContext, EventDescriptor, EventEnvelope, FieldChanged, Metadata,
+    },
     error::{DomainError, DomainResult},
     event_upcaster::EventUpcasterChain,
+    persist::{EventPosition, UnknownEventPolicy},
 };
 use bon::Builder;
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use std::collections::BTreeSet;
 
 #[derive(Debug, Clone, Builder, Serialize, Deserialize)]
 pub struct SerializedEvent {
@@ -22,8 +26,11 @@ pub struct SerializedEvent {
     event_type: String,
     /// 事件版本，用于事件版本控制和升级
     event_version: usize,
-    /// 全局事件位点，由存储层在持久化后赋值
+    /// 分区内事件位点（单分区存储即全局位点），由存储层在持久化后赋值
     sequence_number: Option<i64>,
+    /// 事件所在分区，由分区存储在持久化后赋值；为空表示单分区（分区 `0`）
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    partition: Option<u32>,
     /// 聚合 ID，标识事件所属的聚合根实例
     aggregate_id: String,
     /// 聚合类型，用于区分不同的聚合根
@@ -40,10 +47,29 @@ pub struct SerializedEvent {
     actor_id: Option<String>,
     /// 事件发生时间
     occurred_at: DateTime<Utc>,
+    /// 是否为回填事件（`occurred_at` 为导入的原始时间，而非写入时刻）
+    #[builder(default)]
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    backfilled: bool,
+    /// 触发事件的命令被接收的时间，随事件经 Outbox/总线传递到投影，用于端到端延迟统计
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    command_received_at: Option<DateTime<Utc>>,
     /// 事件负载，存储事件的具体数据
     payload: Value,
+    /// 负载编码方式（如 `gzip`），为空表示原始 JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    content_encoding: Option<String>,
     /// 业务上下文信息（冗余存储，便于查询）
     context: Value,
+    /// 应用该事件后的聚合状态快照（事件携带状态传递），未启用时为空
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    state_snapshot: Option<Value>,
+    /// 写入该事件的副本（多活部署），见 `ReplicaClock`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    origin_replica: Option<String>,
+    /// 写入副本的 Lamport 时钟
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    lamport: Option<u64>,
 }
 
 impl SerializedEvent {
@@ -63,6 +89,24 @@ impl SerializedEvent {
         self.sequence_number
     }
 
+    /// 事件所在分区（单分区存储为 `0`）
+    pub fn partition(&self) -> u32 {
+        self.partition.unwrap_or(0)
+    }
+
+    /// 事件位置 `(分区, 位点)`；尚未由存储层分配位点时为 `None`
+    pub fn position(&self) -> Option<EventPosition> {
+        self.sequence_number
+            .map(|sequence| EventPosition::new(self.partition(), sequence))
+    }
+
+    /// 设置事件位置（供存储层在持久化后回填）
+    pub fn with_position(mut self, position: EventPosition) -> Self {
+        self.partition = Some(position.partition);
+        self.sequence_number = Some(position.sequence);
+        self
+    }
+
     pub fn aggregate_id(&self) -> &str {
         &self.aggregate_id
     }
@@ -95,13 +139,125 @@ impl SerializedEvent {
         self.occurred_at
     }
 
+    pub fn is_backfilled(&self) -> bool {
+        self.backfilled
+    }
+
+    pub fn command_received_at(&self) -> Option<DateTime<Utc>> {
+        self.command_received_at
+    }
+
     pub fn payload(&self) -> &Value {
         &self.payload
     }
 
+    /// 按 `E` 的载荷格式只反序列化所需字段，`T` 可借用载荷中的字符串
+    pub fn payload_fields<'a, E, T>(&'a self) -> serde_json::Result<T>
+    where
+        E: DomainEvent,
+        T: Deserialize<'a>,
+    {
+        let fields = E::PAYLOAD_FORMAT.fields::<E>(&self.event_type, &self.payload)?;
+        T::deserialize(fields)
+    }
+
     pub fn context(&self) -> &Value {
         &self.context
     }
+
+    /// 因果链深度（见 `EventContext::causation_depth`），缺失时为 0
+    pub fn causation_depth(&self) -> u32 {
+        self.context
+            .get("causation_depth")
+            .and_then(Value::as_u64)
+            .map_or(0, |d| u32::try_from(d).unwrap_or(u32::MAX))
+    }
+
+    pub fn content_encoding(&self) -> Option<&str> {
+        self.content_encoding.as_deref()
+    }
+
+    pub fn state_snapshot(&self) -> Option<&Value> {
+        self.state_snapshot.as_ref()
+    }
+
+    pub fn origin_replica(&self) -> Option<&str> {
+        self.origin_replica.as_deref()
+    }
+
+    pub fn lamport(&self) -> Option<u64> {
+        self.lamport
+    }
+
+    /// 标记来源副本与 Lamport 时钟
+    pub fn with_replica(mut self, origin_replica: impl Into<String>, lamport: u64) -> Self {
+        self.origin_replica = Some(origin_replica.into());
+        self.lamport = Some(lamport);
+        self
+    }
+
+    /// 逐字段比较两个事件（含负载），返回变化字段的 JSON Pointer 路径与新旧值
+    ///
+    /// 缺失字段视为 `null`；路径按字典序稳定输出，如 `/payload/amount`、`/event_version`。
+    pub fn diff(&self, other: &SerializedEvent) -> Vec<(String, FieldChanged<Value>)> {
+        let old = serde_json::to_value(self).unwrap_or_default();
+        let new = serde_json::to_value(other).unwrap_or_default();
+
+        let mut changes = Vec::new();
+        diff_values(String::new(), &old, &new, &mut changes);
+        changes
+    }
+
+    /// 替换负载及其编码标记（用于传输层的压缩/解压）
+    pub fn with_encoded_payload(
+        mut self,
+        payload: Value,
+        content_encoding: Option<String>,
+    ) -> Self {
+        self.payload = payload;
+        self.content_encoding = content_encoding;
+        self
+    }
+
+    /// 以 `parent` 为因补齐因果元数据（见 `EventContext::caused_by`）：继承关联 ID、
+    /// 因果 ID 指向 `parent`、因果链深度加一；已设置执行主体时保留，否则继承 `parent` 的主体。
+    /// 上下文中的其余字段（如扩展字段）原样保留，位点清空待存储重新赋值。
+    pub fn caused_by(mut self, parent: &SerializedEvent) -> Self {
+        let derived = EventContext::caused_by(parent);
+        self.correlation_id = derived.correlation_id().map(ToString::to_string);
+        self.causation_id = derived.causation_id().map(ToString::to_string);
+        let keep_actor = self.actor_type.is_some() || self.actor_id.is_some();
+        if !keep_actor {
+            self.actor_type = derived.actor_type().map(ToString::to_string);
+            self.actor_id = derived.actor_id().map(ToString::to_string);
+        }
+
+        let mut context = match std::mem::take(&mut self.context) {
+            Value::Object(map) => map,
+            _ => serde_json::Map::new(),
+        };
+        if let Ok(Value::Object(fields)) = serde_json::to_value(&derived) {
+            for (key, value) in fields {
+                let actor_field = key == "actor_type" || key == "actor_id";
+                if value.is_null() || (keep_actor && actor_field) {
+                    continue;
+                }
+                context.insert(key, value);
+            }
+        }
+        self.context = Value::Object(context);
+        self.sequence_number = None;
+        self.partition = None;
+        self
+    }
+
+    /// 替换负载并升级事件版本，其余信封字段（ID、聚合信息、上下文、发生时间等）原样保留（用于上抬器）
+    pub fn upcasted(mut self, payload: Value, event_version: usize) -> Self {
+        self.payload = payload;
+        self.content_encoding = None;
+        self.event_version = event_version;
+        self
+    }
 }
 
 impl<A> TryFrom<&EventEnvelope<A>> for SerializedEvent
@@ -116,6 +272,7 @@ where
             event_type: envelope.payload.event_type().to_string(),
             event_version: envelope.payload.event_version(),
             sequence_number: None,
+            partition: None,
             aggregate_id: envelope.metadata.aggregate_id().to_string(),
             aggregate_type: envelope.metadata.aggregate_type().to_string(),
             aggregate_version: envelope.payload.aggregate_version().value(),
@@ -124,8 +281,14 @@ where
             actor_type: envelope.context.actor_type().map(|s| s.to_string()),
             actor_id: envelope.context.actor_id().map(|s| s.to_string()),
             occurred_at: *envelope.metadata.occurred_at(),
-            payload: serde_json::to_value(&envelope.payload)?,
+            backfilled: envelope.metadata.is_backfilled(),
+            command_received_at: envelope.metadata.command_received_at().copied(),
+            payload: A::Event::PAYLOAD_FORMAT.encode(&envelope.payload)?,
+            content_encoding: None,
             context: serde_json::to_value(&envelope.context)?,
+            state_snapshot: envelope.metadata.state_snapshot().cloned(),
+            origin_replica: None,
+            lamport: None,
         })
     }
 }
@@ -141,9 +304,13 @@ where
             .aggregate_id(value.aggregate_id.clone())
             .aggregate_type(value.aggregate_type.clone())
             .occurred_at(value.occurred_at)
+            .backfilled(value.backfilled)
+            .maybe_command_received_at(value.command_received_at)
+            .maybe_state_snapshot(value.state_snapshot.clone())
             .build();
 
-        let payload: A::Event = serde_json::from_value(value.payload.clone())?;
+        let payload: A::Event =
+            A::Event::PAYLOAD_FORMAT.decode(&value.event_type, &value.payload)?;
 
         let context: EventContext = serde_json::from_value(value.context.clone())?;
 
@@ -155,6 +322,40 @@ where
     }
 }
 
+fn diff_values(
+    path: String,
+    old: &Value,
+    new: &Value,
+    changes: &mut Vec<(String, FieldChanged<Value>)>,
+) {
+    match (old, new) {
+        (Value::Object(a), Value::Object(b)) => {
+            let keys: BTreeSet<&String> = a.keys().chain(b.keys()).collect();
+            for key in keys {
+                let escaped = key.replace('~', "~0").replace('/', "~1");
+                diff_values(
+                    format!("{path}/{escaped}"),
+                    a.get(key).unwrap_or(&Value::Null),
+                    b.get(key).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        (Value::Array(a), Value::Array(b)) => {
+            for i in 0..a.len().max(b.len()) {
+                diff_values(
+                    format!("{path}/{i}"),
+                    a.get(i).unwrap_or(&Value::Null),
+                    b.get(i).unwrap_or(&Value::Null),
+                    changes,
+                );
+            }
+        }
+        _ if old != new => changes.push((path, FieldChanged::new(old.clone(), new.clone()))),
+        _ => {}
+    }
+}
+
 pub fn serialize_events<A>(events: &[EventEnvelope<A>]) -> DomainResult<Vec<SerializedEvent>>
 where
     A: Aggregate,
@@ -171,6 +372,18 @@ pub fn deserialize_events<A>(
     upcaster_chain: &EventUpcasterChain,
     events: Vec<SerializedEvent>,
 ) -> DomainResult<Vec<EventEnvelope<A>>>
+where
+    A: Aggregate,
+{
+    deserialize_events_with(upcaster_chain, events, &UnknownEventPolicy::Fail)
+}
+
+/// 上抬并反序列化事件，上抬后仍未知的事件类型按 `policy` 处理
+pub fn deserialize_events_with<A>(
+    upcaster_chain: &EventUpcasterChain,
+    events: Vec<SerializedEvent>,
+    policy: &UnknownEventPolicy,
+) -> DomainResult<Vec<EventEnvelope<A>>>
 where
     A: Aggregate,
 {
@@ -178,9 +391,56 @@ where
 
     let events = events
         .iter()
+        .filter(|event| accepts::<A>(policy, event))
         .map(EventEnvelope::try_from)
         .collect::<Result<Vec<_>, _>>()
         .map_err(DomainError::from)?;
 
     Ok(events)
 }
+
+/// 上抬事件并依次应用到聚合，不构造 `EventEnvelope`
+///
+/// 优先走 `Aggregate::apply_serialized` 快速路径，未处理的事件完整反序列化后调用 `apply`；
+/// 上抬后仍未知的事件类型按 `policy` 处理。
+pub fn apply_events_with<A>(
+    aggregate: &mut A,
+    upcaster_chain: &EventUpcasterChain,
+    events: Vec<SerializedEvent>,
+    policy: &UnknownEventPolicy,
+) -> DomainResult<()>
+where
+    A: Aggregate,
+{
+    let events = upcaster_chain.upcast_all(events)?;
+
+    for event in events.iter().filter(|event| accepts::<A>(policy, event)) {
+        if !aggregate.apply_serialized(event)? {
+            let payload: A::Event =
+                A::Event::PAYLOAD_FORMAT.decode(&event.event_type, &event.payload)?;
+            aggregate.apply(&payload);
+        }
+    }
+
+    Ok(())
+}
+
+fn accepts<A: Aggregate>(policy: &UnknownEventPolicy, event: &SerializedEvent) -> bool {
+    match policy {
+        UnknownEventPolicy::Fail => true,
+        UnknownEventPolicy::Skip => is_known::<A>(event),
+        UnknownEventPolicy::Collect(log) => {
+            let known = is_known::<A>(event);
+            if !known {
+                log.record(event);
+            }
+            known
+        }
+    }
+}
+
+/// 事件类型是否在聚合事件的描述中；未声明描述时无法判定，视为已知
+fn is_known<A: Aggregate>(event: &SerializedEvent) -> bool {
+    let descriptors = <A::Event as DomainEvent>::DESCRIPTORS;
+    descriptors.is_empty() || EventDescriptor::find(descriptors, event.event_type()).is_some()
+}
@@ -1,14 +1,21 @@
 //! 快照持久化模型（SerializedSnapshot）
 //!
 //! 定义聚合快照在持久化层的标准形态与与聚合实例之间的转换。
+//! 负载可经 `SnapshotCodec` 编码（见 `snapshot_codec`），`to_aggregate` 透明解码内置编码。
 //!
 use crate::{
     aggregate::Aggregate,
-    error::{DomainError, DomainResult as Result},
+    error::{DomainError, DomainResult as Result, ErrorKind},
+    persist::snapshot_codec::{
+        JSON_SNAPSHOT_ENCODING, SNAPSHOT_CODEC_ERROR, SnapshotCodec, builtin_snapshot_codec,
+        codec_error,
+    },
 };
+use base64::{Engine as _, engine::general_purpose::STANDARD};
 use bon::Builder;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
+use sha2::{Digest, Sha256};
 
 #[derive(Debug, Clone, Builder, Serialize, Deserialize)]
 pub struct SerializedSnapshot {
@@ -16,6 +23,9 @@ pub struct SerializedSnapshot {
     aggregate_type: String,
     aggregate_version: usize,
     payload: Value,
+    /// 负载编码方式（如 `json+zstd`），为空表示原始 JSON
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    content_encoding: Option<String>,
 }
 
 impl SerializedSnapshot {
@@ -35,7 +45,67 @@ impl SerializedSnapshot {
         &self.payload
     }
 
-    /// 将快照反序列化为聚合实例
+    pub fn content_encoding(&self) -> Option<&str> {
+        self.content_encoding.as_deref()
+    }
+
+    /// 以 `codec` 编码负载（base64 字符串承载）；`json` 编码或已编码时原样返回
+    pub fn encode_with(self, codec: &dyn SnapshotCodec) -> Result<Self> {
+        if self.content_encoding.is_some() || codec.encoding() == JSON_SNAPSHOT_ENCODING {
+            return Ok(self);
+        }
+
+        let bytes = codec.encode(&self.payload)?;
+        Ok(Self {
+            payload: Value::String(STANDARD.encode(bytes)),
+            content_encoding: Some(codec.encoding().to_string()),
+            ..self
+        })
+    }
+
+    /// 以 `codec` 解码负载；未编码时原样返回，编码标记与 `codec` 不符时返回错误
+    pub fn decode_with(self, codec: &dyn SnapshotCodec) -> Result<Self> {
+        let Some(encoding) = self.content_encoding.as_deref() else {
+            return Ok(self);
+        };
+        if encoding != codec.encoding() {
+            return Err(unsupported_encoding(encoding));
+        }
+
+        let encoded = self.payload.as_str().ok_or_else(|| {
+            DomainError::new(
+                ErrorKind::Internal,
+                "encoded snapshot payload must be a string",
+            )
+            .with_code(SNAPSHOT_CODEC_ERROR)
+        })?;
+        let bytes = STANDARD.decode(encoded).map_err(codec_error)?;
+        Ok(Self {
+            payload: codec.decode(&bytes)?,
+            content_encoding: None,
+            ..self
+        })
+    }
+
+    /// 以内置编解码器解码负载；未编码时原样返回
+    pub fn decode(self) -> Result<Self> {
+        match self.content_encoding.as_deref() {
+            None => Ok(self),
+            Some(encoding) => {
+                let codec = builtin_snapshot_codec(encoding)
+                    .ok_or_else(|| unsupported_encoding(encoding))?;
+                self.decode_with(codec)
+            }
+        }
+    }
+
+    /// 快照内容的 SHA-256 校验和（十六进制），用于导出/导入时校验完整性
+    pub fn checksum(&self) -> Result<String> {
+        let bytes = serde_json::to_vec(self)?;
+        Ok(format!("{:x}", Sha256::digest(bytes)))
+    }
+
+    /// 将快照反序列化为聚合实例（内置编码透明解码）
     pub fn to_aggregate<A>(&self) -> Result<A>
     where
         A: Aggregate,
@@ -47,7 +117,11 @@ impl SerializedSnapshot {
             ));
         }
 
-        let aggregate = serde_json::from_value(self.payload.clone())?;
+        let payload = match self.content_encoding {
+            None => self.payload.clone(),
+            Some(_) => self.clone().decode()?.payload,
+        };
+        let aggregate = serde_json::from_value(payload)?;
         Ok(aggregate)
     }
 
@@ -61,6 +135,15 @@ impl SerializedSnapshot {
             aggregate_type: A::TYPE.to_string(),
             aggregate_version: aggregate.version().value(),
             payload: serde_json::to_value(aggregate)?,
+            content_encoding: None,
         })
     }
 }
+
+fn unsupported_encoding(encoding: &str) -> DomainError {
+    DomainError::new(
+        ErrorKind::Internal,
+        format!("unsupported snapshot encoding: {encoding}"),
+    )
+    .with_code(SNAPSHOT_CODEC_ERROR)
+}
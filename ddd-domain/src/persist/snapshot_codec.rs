@@ -0,0 +1,159 @@
+//! 快照负载编解码（SnapshotCodec）
+//!
+//! 大聚合的 JSON 快照会迅速撑大快照表，`SnapshotRepositoryWithPolicy::with_codec` 在落盘前
+//! 以可替换的编解码器编码快照负载：编码后的字节以 base64 字符串承载，并通过
+//! `SerializedSnapshot::content_encoding` 标记编码方式。
+//!
+//! 内置编解码器：
+//! - `JsonCodec`（`json`）：原始 JSON 负载，不做转换；
+//! - `ZstdJsonCodec`（`json+zstd`，需启用 `snapshot-zstd`）：JSON 经 zstd 压缩；
+//! - `MessagePackCodec`（`msgpack`，需启用 `snapshot-msgpack`）：MessagePack 二进制编码。
+//!
+//! `SerializedSnapshot::to_aggregate` 对内置编码透明解码；自定义编解码器由配置它的
+//! `SnapshotRepositoryWithPolicy` 在 `get_snapshot` 时解码。
+//!
+use crate::error::{DomainError, DomainResult as Result, ErrorKind};
+use serde_json::Value;
+
+/// 原始 JSON 编码标记
+pub const JSON_SNAPSHOT_ENCODING: &str = "json";
+
+/// JSON + zstd 编码标记
+pub const ZSTD_JSON_SNAPSHOT_ENCODING: &str = "json+zstd";
+
+/// MessagePack 编码标记
+pub const MSGPACK_SNAPSHOT_ENCODING: &str = "msgpack";
+
+/// 快照编解码失败的错误码
+pub const SNAPSHOT_CODEC_ERROR: &str = "SNAPSHOT_CODEC_ERROR";
+
+/// 快照负载编解码器
+pub trait SnapshotCodec: Send + Sync {
+    /// 编码标记，写入 `SerializedSnapshot::content_encoding`
+    fn encoding(&self) -> &str;
+
+    fn encode(&self, payload: &Value) -> Result<Vec<u8>>;
+
+    fn decode(&self, bytes: &[u8]) -> Result<Value>;
+}
+
+/// 原始 JSON（默认）
+#[derive(Clone, Copy, Debug, Default)]
+pub struct JsonCodec;
+
+impl SnapshotCodec for JsonCodec {
+    fn encoding(&self) -> &str {
+        JSON_SNAPSHOT_ENCODING
+    }
+
+    fn encode(&self, payload: &Value) -> Result<Vec<u8>> {
+        Ok(serde_json::to_vec(payload)?)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Value> {
+        Ok(serde_json::from_slice(bytes)?)
+    }
+}
+
+/// JSON + zstd 压缩
+#[cfg(feature = "snapshot-zstd")]
+#[derive(Clone, Copy, Debug)]
+pub struct ZstdJsonCodec {
+    level: i32,
+}
+
+#[cfg(feature = "snapshot-zstd")]
+impl Default for ZstdJsonCodec {
+    fn default() -> Self {
+        Self { level: 3 }
+    }
+}
+
+#[cfg(feature = "snapshot-zstd")]
+impl ZstdJsonCodec {
+    /// 指定压缩级别（1-22，默认 3）
+    pub fn new(level: i32) -> Self {
+        Self { level }
+    }
+}
+
+#[cfg(feature = "snapshot-zstd")]
+impl SnapshotCodec for ZstdJsonCodec {
+    fn encoding(&self) -> &str {
+        ZSTD_JSON_SNAPSHOT_ENCODING
+    }
+
+    fn encode(&self, payload: &Value) -> Result<Vec<u8>> {
+        let raw = serde_json::to_vec(payload)?;
+        zstd::encode_all(raw.as_slice(), self.level).map_err(codec_error)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Value> {
+        let raw = zstd::decode_all(bytes).map_err(codec_error)?;
+        Ok(serde_json::from_slice(&raw)?)
+    }
+}
+
+/// MessagePack 二进制编码
+#[cfg(feature = "snapshot-msgpack")]
+#[derive(Clone, Copy, Debug, Default)]
+pub struct MessagePackCodec;
+
+#[cfg(feature = "snapshot-msgpack")]
+impl SnapshotCodec for MessagePackCodec {
+    fn encoding(&self) -> &str {
+        MSGPACK_SNAPSHOT_ENCODING
+    }
+
+    fn encode(&self, payload: &Value) -> Result<Vec<u8>> {
+        rmp_serde::to_vec(payload).map_err(codec_error)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> Result<Value> {
+        rmp_serde::from_slice(bytes).map_err(codec_error)
+    }
+}
+
+/// 按编码标记查找内置编解码器；未知或未启用对应特性时返回 `None`
+pub fn builtin_snapshot_codec(encoding: &str) -> Option<&'static dyn SnapshotCodec> {
+    match encoding {
+        JSON_SNAPSHOT_ENCODING => Some(&JsonCodec),
+        #[cfg(feature = "snapshot-zstd")]
+        ZSTD_JSON_SNAPSHOT_ENCODING => Some(&ZstdJsonCodec { level: 3 }),
+        #[cfg(feature = "snapshot-msgpack")]
+        MSGPACK_SNAPSHOT_ENCODING => Some(&MessagePackCodec),
+        _ => None,
+    }
+}
+
+pub(crate) fn codec_error<E>(err: E) -> DomainError
+where
+    E: std::error::Error + Send + Sync + 'static,
+{
+    DomainError::custom(ErrorKind::Internal, err).with_code(SNAPSHOT_CODEC_ERROR)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    #[test]
+    fn builtin_codecs_round_trip() {
+        let payload = json!({"id": "a-1", "lines": [1, 2, 3], "note": "x".repeat(1024)});
+        for encoding in [
+            JSON_SNAPSHOT_ENCODING,
+            ZSTD_JSON_SNAPSHOT_ENCODING,
+            MSGPACK_SNAPSHOT_ENCODING,
+        ] {
+            let codec = builtin_snapshot_codec(encoding).unwrap();
+            assert_eq!(codec.encoding(), encoding);
+            let bytes = codec.encode(&payload).unwrap();
+            assert_eq!(codec.decode(&bytes).unwrap(), payload);
+        }
+
+        let raw = JsonCodec.encode(&payload).unwrap().len();
+        assert!(ZstdJsonCodec::default().encode(&payload).unwrap().len() < raw / 4);
+        assert!(builtin_snapshot_codec("brotli").is_none());
+    }
+}
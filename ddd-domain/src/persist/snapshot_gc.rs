@@ -0,0 +1,170 @@
+//! 快照分代回收（SnapshotGc）
+//!
+//! 早于某个快照的事件被归档（移出在线事件存储）后，更早的快照既不再是最新状态，
+//! 也无法作为重放起点，继续保留只会占用存储。`SnapshotGc` 协调归档与快照仓储：
+//! - `EventArchive`：报告聚合已归档到的版本（归档水位），由归档流程维护；
+//! - `SnapshotRetention`：始终保留最新的 `keep_latest` 份快照；
+//! - 其余快照中，版本不超过归档水位的（同时被更新的快照与已归档事件取代）被删除，
+//!   水位之上的快照仍可作为在线事件的重放起点，予以保留。
+//!
+//! 快照仓储需实现 `SnapshotHistory`（列举与删除历史快照）。
+//!
+use crate::{aggregate::Aggregate, error::DomainResult as Result, persist::SnapshotHistory};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// 事件归档水位
+#[async_trait]
+pub trait EventArchive: Send + Sync {
+    /// 聚合已归档到的版本（含）；未归档返回 `0`
+    async fn archived_through(&self, aggregate_type: &str, aggregate_id: &str) -> Result<usize>;
+}
+
+/// 基于内存的 EventArchive 实现
+#[derive(Default)]
+pub struct InMemoryEventArchive {
+    watermarks: Mutex<HashMap<(String, String), usize>>,
+}
+
+impl InMemoryEventArchive {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 记录聚合已归档到 `version`（水位只前进不后退）
+    pub fn archive<A: Aggregate>(&self, aggregate_id: &A::Id, version: usize) {
+        let mut watermarks = self.watermarks.lock().unwrap();
+        let watermark = watermarks
+            .entry((A::TYPE.to_string(), aggregate_id.to_string()))
+            .or_default();
+        *watermark = (*watermark).max(version);
+    }
+}
+
+#[async_trait]
+impl EventArchive for InMemoryEventArchive {
+    async fn archived_through(&self, aggregate_type: &str, aggregate_id: &str) -> Result<usize> {
+        Ok(self
+            .watermarks
+            .lock()
+            .unwrap()
+            .get(&(aggregate_type.to_string(), aggregate_id.to_string()))
+            .copied()
+            .unwrap_or(0))
+    }
+}
+
+/// 快照保留配置
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct SnapshotRetention {
+    /// 始终保留的最新快照份数（至少 1）
+    pub keep_latest: usize,
+}
+
+impl Default for SnapshotRetention {
+    fn default() -> Self {
+        Self { keep_latest: 1 }
+    }
+}
+
+/// 一次回收的结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct SnapshotGcReport {
+    /// 检查的聚合数
+    pub aggregates: usize,
+    /// 删除的快照数
+    pub deleted: usize,
+    /// 保留的快照数
+    pub retained: usize,
+}
+
+impl SnapshotGcReport {
+    fn merge(&mut self, other: SnapshotGcReport) {
+        self.aggregates += other.aggregates;
+        self.deleted += other.deleted;
+        self.retained += other.retained;
+    }
+}
+
+/// 按归档水位回收被取代的快照
+pub struct SnapshotGc<S> {
+    snapshots: S,
+    archive: Arc<dyn EventArchive>,
+    retention: SnapshotRetention,
+}
+
+impl<S> SnapshotGc<S>
+where
+    S: SnapshotHistory,
+{
+    pub fn new(snapshots: S, archive: Arc<dyn EventArchive>) -> Self {
+        Self {
+            snapshots,
+            archive,
+            retention: SnapshotRetention::default(),
+        }
+    }
+
+    pub fn with_retention(mut self, retention: SnapshotRetention) -> Self {
+        self.retention = retention;
+        self
+    }
+
+    /// 待删除的快照版本：最新 `keep_latest` 份之外、且不超过归档水位的版本
+    pub async fn plan<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<Vec<usize>> {
+        let versions = self.snapshots.snapshot_versions::<A>(aggregate_id).await?;
+        self.superseded::<A>(aggregate_id, versions).await
+    }
+
+    /// 回收单个聚合被取代的快照
+    pub async fn collect<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<SnapshotGcReport> {
+        let versions = self.snapshots.snapshot_versions::<A>(aggregate_id).await?;
+        let total = versions.len();
+        let doomed = self.superseded::<A>(aggregate_id, versions).await?;
+        let deleted = if doomed.is_empty() {
+            0
+        } else {
+            self.snapshots
+                .delete_snapshots::<A>(aggregate_id, &doomed)
+                .await?
+        };
+        Ok(SnapshotGcReport {
+            aggregates: 1,
+            deleted,
+            retained: total.saturating_sub(deleted),
+        })
+    }
+
+    /// 依次回收多个聚合，遇错即停
+    pub async fn collect_many<A: Aggregate>(
+        &self,
+        aggregate_ids: &[A::Id],
+    ) -> Result<SnapshotGcReport> {
+        let mut report = SnapshotGcReport::default();
+        for aggregate_id in aggregate_ids {
+            report.merge(self.collect::<A>(aggregate_id).await?);
+        }
+        Ok(report)
+    }
+
+    async fn superseded<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        mut versions: Vec<usize>,
+    ) -> Result<Vec<usize>> {
+        versions.sort_unstable();
+        let keep = self.retention.keep_latest.max(1);
+        if versions.len() <= keep {
+            return Ok(Vec::new());
+        }
+
+        let archived = self
+            .archive
+            .archived_through(A::TYPE, &aggregate_id.to_string())
+            .await?;
+        versions.truncate(versions.len() - keep);
+        versions.retain(|v| *v <= archived);
+        Ok(versions)
+    }
+}
@@ -0,0 +1,40 @@
+//! 快照历史（SnapshotHistory）
+//!
+//! `SnapshotRepository` 只读写聚合的最新快照；快照回收（`SnapshotGc`）需要列举并删除历史快照。
+//! 保留历史快照的存储后端实现该能力，快照仓储装饰器在内层支持时同样实现并转发。
+//!
+use crate::{aggregate::Aggregate, error::DomainResult as Result};
+use async_trait::async_trait;
+use std::sync::Arc;
+
+/// 列举与删除聚合的历史快照
+#[async_trait]
+pub trait SnapshotHistory: Send + Sync {
+    /// 聚合已存储的全部快照版本（升序）
+    async fn snapshot_versions<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<Vec<usize>>;
+
+    /// 删除聚合指定版本的快照，返回实际删除的数量（不存在的版本忽略）
+    async fn delete_snapshots<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        versions: &[usize],
+    ) -> Result<usize>;
+}
+
+#[async_trait]
+impl<T> SnapshotHistory for Arc<T>
+where
+    T: SnapshotHistory + ?Sized,
+{
+    async fn snapshot_versions<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<Vec<usize>> {
+        (**self).snapshot_versions::<A>(aggregate_id).await
+    }
+
+    async fn delete_snapshots<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        versions: &[usize],
+    ) -> Result<usize> {
+        (**self).delete_snapshots::<A>(aggregate_id, versions).await
+    }
+}
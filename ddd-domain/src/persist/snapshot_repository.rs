@@ -1,10 +1,19 @@
 //! 快照仓储协议与策略
 //!
 //! 定义聚合快照读写接口与简单的落盘策略（按版本间隔）。
+//! 决策可经 `SnapshotStrategy` 替换，如 `UpcastCostStrategy` 按上抬链成本缩短快照间隔。
+//! `SnapshotRepositoryWithPolicy::with_codec` 以 `SnapshotCodec` 编码落盘的快照负载。
 //!
-use crate::{aggregate::Aggregate, error::DomainResult as Result, persist::SerializedSnapshot};
+use crate::{
+    aggregate::Aggregate,
+    domain_event::{DomainEvent, EventDescriptor},
+    error::DomainResult as Result,
+    event_upcaster::EventUpcasterChain,
+    persist::{SerializedSnapshot, SnapshotCodec, SnapshotHistory},
+};
 use async_trait::async_trait;
-use std::sync::Arc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
 
 #[async_trait]
 pub trait SnapshotRepository: Send + Sync {
@@ -14,7 +23,34 @@ pub trait SnapshotRepository: Send + Sync {
         version: Option<usize>,
     ) -> Result<Option<SerializedSnapshot>>;
 
-    async fn save<A: Aggregate>(&self, aggregate: &A) -> Result<()>;
+    /// 写入单个已序列化的快照
+    ///
+    /// 负载可能已由 `SnapshotCodec` 编码（`SerializedSnapshot::content_encoding`），后端须原样存储，
+    /// 读取时由配置编解码器的仓储解码。同一聚合同一版本的快照覆盖写入。
+    async fn save_serialized(&self, snapshot: SerializedSnapshot) -> Result<()>;
+
+    /// 保存聚合的当前状态；默认序列化后经 `save_serialized` 写入
+    async fn save<A: Aggregate>(&self, aggregate: &A) -> Result<()> {
+        self.save_serialized(SerializedSnapshot::from_aggregate(aggregate)?)
+            .await
+    }
+
+    /// 批量写入已序列化的快照（如由 `import_snapshots_ndjson` 导入），返回写入数量
+    ///
+    /// 同一聚合同一版本的快照覆盖写入；默认逐条经 `save_serialized` 写入，
+    /// 支持批量写入的后端可覆盖以提速。
+    async fn bulk_load(&self, snapshots: Vec<SerializedSnapshot>) -> Result<usize> {
+        let count = snapshots.len();
+        for snapshot in snapshots {
+            self.save_serialized(snapshot).await?;
+        }
+        Ok(count)
+    }
+
+    /// 探测存储是否可用（启动自检使用）；默认视为可用，存储后端应覆盖为轻量查询
+    async fn ping(&self) -> Result<()> {
+        Ok(())
+    }
 }
 
 #[async_trait]
@@ -30,9 +66,21 @@ where
         (**self).get_snapshot::<A>(aggregate_id, version).await
     }
 
+    async fn save_serialized(&self, snapshot: SerializedSnapshot) -> Result<()> {
+        (**self).save_serialized(snapshot).await
+    }
+
     async fn save<A: Aggregate>(&self, aggregate: &A) -> Result<()> {
         (**self).save::<A>(aggregate).await
     }
+
+    async fn bulk_load(&self, snapshots: Vec<SerializedSnapshot>) -> Result<usize> {
+        (**self).bulk_load(snapshots).await
+    }
+
+    async fn ping(&self) -> Result<()> {
+        (**self).ping().await
+    }
 }
 
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
@@ -53,15 +101,140 @@ impl SnapshotPolicy {
     }
 }
 
+/// 快照决策的输入
+#[derive(Debug, Clone, Copy)]
+pub struct SnapshotDecision<'a> {
+    pub aggregate_type: &'a str,
+    /// 聚合当前版本
+    pub version: usize,
+    /// 聚合的事件类型（`DomainEvent::DESCRIPTORS`）
+    pub event_types: &'a [EventDescriptor],
+}
+
+/// 可替换的快照决策策略
+pub trait SnapshotStrategy: Send + Sync {
+    fn should_snapshot(&self, decision: &SnapshotDecision<'_>) -> bool;
+}
+
+impl SnapshotStrategy for SnapshotPolicy {
+    fn should_snapshot(&self, decision: &SnapshotDecision<'_>) -> bool {
+        SnapshotPolicy::should_snapshot(self, decision.version)
+    }
+}
+
+/// 感知上抬成本的快照策略
+///
+/// 上抬链对聚合事件类型的处理步数（`EventUpcasterChain::depth`，取各事件类型的最大值）越多，
+/// 重放同样数量的事件越昂贵，因此按 `base_interval / (1 + depth)` 缩短快照间隔
+/// （不低于 `min_interval`）。间隔按聚合类型缓存。
+pub struct UpcastCostStrategy {
+    chain: Arc<EventUpcasterChain>,
+    base_interval: usize,
+    min_interval: usize,
+    intervals: Mutex<HashMap<String, usize>>,
+}
+
+impl UpcastCostStrategy {
+    pub fn new(chain: Arc<EventUpcasterChain>, base_interval: usize) -> Self {
+        Self {
+            chain,
+            base_interval: base_interval.max(1),
+            min_interval: 1,
+            intervals: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// 间隔下限（默认 1）
+    pub fn with_min_interval(mut self, min_interval: usize) -> Self {
+        self.min_interval = min_interval.max(1);
+        self
+    }
+
+    /// 聚合类型对应的快照间隔
+    pub fn interval(&self, aggregate_type: &str, event_types: &[EventDescriptor]) -> usize {
+        if let Some(interval) = self.intervals.lock().unwrap().get(aggregate_type) {
+            return *interval;
+        }
+
+        let depth = event_types
+            .iter()
+            .map(|d| self.chain.depth(d.event_type, d.event_version))
+            .max()
+            .unwrap_or(0);
+        let interval = (self.base_interval / (1 + depth)).max(self.min_interval);
+        self.intervals
+            .lock()
+            .unwrap()
+            .insert(aggregate_type.to_string(), interval);
+        interval
+    }
+}
+
+impl SnapshotStrategy for UpcastCostStrategy {
+    fn should_snapshot(&self, decision: &SnapshotDecision<'_>) -> bool {
+        let interval = self.interval(decision.aggregate_type, decision.event_types);
+        SnapshotPolicy::Every(interval).should_snapshot(decision.version)
+    }
+}
+
 /// SnapshotRepository 的装饰器，根据策略决定是否落盘快照
 pub struct SnapshotRepositoryWithPolicy<R> {
     inner: R,
     policy: SnapshotPolicy,
+    strategy: Option<Arc<dyn SnapshotStrategy>>,
+    codec: Option<Arc<dyn SnapshotCodec>>,
 }
 
 impl<R> SnapshotRepositoryWithPolicy<R> {
     pub fn new(inner: R, policy: SnapshotPolicy) -> Self {
-        Self { inner, policy }
+        Self {
+            inner,
+            policy,
+            strategy: None,
+            codec: None,
+        }
+    }
+
+    /// 落盘前以 `codec` 编码快照负载，读取时透明解码
+    ///
+    /// 编码后的快照经内层仓储的 `save_serialized` 原样写入。
+    pub fn with_codec(mut self, codec: Arc<dyn SnapshotCodec>) -> Self {
+        self.codec = Some(codec);
+        self
+    }
+
+    /// 以自定义策略替代 `SnapshotPolicy` 做出快照决策
+    pub fn with_strategy(mut self, strategy: Arc<dyn SnapshotStrategy>) -> Self {
+        self.strategy = Some(strategy);
+        self
+    }
+
+    pub fn policy(&self) -> SnapshotPolicy {
+        self.policy
+    }
+
+    /// 聚合在 `version` 时是否应落盘快照
+    pub fn should_snapshot<A: Aggregate>(&self, version: usize) -> bool {
+        let decision = SnapshotDecision {
+            aggregate_type: A::TYPE,
+            version,
+            event_types: A::Event::DESCRIPTORS,
+        };
+        match &self.strategy {
+            Some(strategy) => strategy.should_snapshot(&decision),
+            None => SnapshotStrategy::should_snapshot(&self.policy, &decision),
+        }
+    }
+}
+
+impl<R: SnapshotRepository> SnapshotRepositoryWithPolicy<R> {
+    /// 按配置的编解码器编码后写入已序列化的快照，不再经策略判断（供后台快照使用）
+    pub async fn save_encoded(&self, snapshot: SerializedSnapshot) -> Result<()> {
+        let snapshot = match &self.codec {
+            Some(codec) => snapshot.encode_with(codec.as_ref())?,
+            None => snapshot,
+        };
+        self.inner.save_serialized(snapshot).await
     }
 }
 
@@ -75,14 +248,63 @@ where
         aggregate_id: &A::Id,
         version: Option<usize>,
     ) -> Result<Option<SerializedSnapshot>> {
-        self.inner.get_snapshot::<A>(aggregate_id, version).await
+        let snapshot = self.inner.get_snapshot::<A>(aggregate_id, version).await?;
+        match (snapshot, &self.codec) {
+            (Some(snapshot), Some(codec))
+                if snapshot.content_encoding() == Some(codec.encoding()) =>
+            {
+                snapshot.decode_with(codec.as_ref()).map(Some)
+            }
+            (snapshot, _) => snapshot.map(SerializedSnapshot::decode).transpose(),
+        }
     }
 
     async fn save<A: Aggregate>(&self, aggregate: &A) -> Result<()> {
-        if !self.policy.should_snapshot(aggregate.version().value()) {
+        if !self.should_snapshot::<A>(aggregate.version().value()) {
             return Ok(());
         }
 
-        self.inner.save::<A>(aggregate).await
+        match &self.codec {
+            Some(codec) => {
+                let snapshot =
+                    SerializedSnapshot::from_aggregate(aggregate)?.encode_with(codec.as_ref())?;
+                self.inner.save_serialized(snapshot).await
+            }
+            None => self.inner.save::<A>(aggregate).await,
+        }
+    }
+
+    /// 已序列化的快照不受策略与编解码器影响，原样写入
+    async fn save_serialized(&self, snapshot: SerializedSnapshot) -> Result<()> {
+        self.inner.save_serialized(snapshot).await
+    }
+
+    /// 批量写入不受策略限制
+    async fn bulk_load(&self, snapshots: Vec<SerializedSnapshot>) -> Result<usize> {
+        self.inner.bulk_load(snapshots).await
+    }
+
+    async fn ping(&self) -> Result<()> {
+        self.inner.ping().await
+    }
+}
+
+#[async_trait]
+impl<R> SnapshotHistory for SnapshotRepositoryWithPolicy<R>
+where
+    R: SnapshotHistory,
+{
+    async fn snapshot_versions<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<Vec<usize>> {
+        self.inner.snapshot_versions::<A>(aggregate_id).await
+    }
+
+    async fn delete_snapshots<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        versions: &[usize],
+    ) -> Result<usize> {
+        self.inner
+            .delete_snapshots::<A>(aggregate_id, versions)
+            .await
     }
 }
@@ -0,0 +1,134 @@
+//! 快照导出/导入（NDJSON）
+//!
+//! 将快照批量导出为 NDJSON，用于预热备用环境（预发刷新、灾备恢复），
+//! 导入后配合 `SnapshotRepository::bulk_load` 写入目标仓储，远快于重放完整事件历史。
+//!
+//! 文件格式：每行一个快照 `{"checksum": "...", "snapshot": {...}}`，
+//! 末行为汇总 `{"count": N, "checksum": "..."}`（全部行校验和拼接后的 SHA-256），
+//! 导入时逐行校验并核对汇总，可发现内容篡改与文件截断。
+//!
+use crate::{
+    error::{DomainError, DomainResult as Result, ErrorKind},
+    persist::SerializedSnapshot,
+};
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+use std::io::{BufRead, Write};
+
+/// 导出汇总
+#[derive(Clone, Debug, PartialEq, Eq, Serialize, Deserialize)]
+pub struct SnapshotExportSummary {
+    /// 导出的快照数
+    pub count: usize,
+    /// 全部行校验和拼接后的 SHA-256
+    pub checksum: String,
+}
+
+#[derive(Serialize, Deserialize)]
+#[serde(untagged)]
+enum NdjsonRecord {
+    Snapshot {
+        checksum: String,
+        snapshot: SerializedSnapshot,
+    },
+    Summary(SnapshotExportSummary),
+}
+
+/// 将快照逐行导出为 NDJSON，返回写入末行的汇总
+pub fn export_snapshots_ndjson<'a, I, W>(
+    snapshots: I,
+    mut writer: W,
+) -> Result<SnapshotExportSummary>
+where
+    I: IntoIterator<Item = &'a SerializedSnapshot>,
+    W: Write,
+{
+    let mut batch = Sha256::new();
+    let mut count = 0;
+
+    for snapshot in snapshots {
+        let checksum = snapshot.checksum()?;
+        batch.update(checksum.as_bytes());
+        count += 1;
+
+        write_record(
+            &mut writer,
+            &NdjsonRecord::Snapshot {
+                checksum,
+                snapshot: snapshot.clone(),
+            },
+        )?;
+    }
+
+    let summary = SnapshotExportSummary {
+        count,
+        checksum: format!("{:x}", batch.finalize()),
+    };
+    write_record(&mut writer, &NdjsonRecord::Summary(summary.clone()))?;
+    writer.flush().map_err(io_error)?;
+
+    Ok(summary)
+}
+
+/// 读取 NDJSON 导出文件并校验每行及汇总的校验和
+pub fn import_snapshots_ndjson<R>(reader: R) -> Result<Vec<SerializedSnapshot>>
+where
+    R: BufRead,
+{
+    let mut batch = Sha256::new();
+    let mut snapshots = Vec::new();
+    let mut summary = None;
+
+    for (index, line) in reader.lines().enumerate() {
+        let line_no = index + 1;
+        let line = line.map_err(io_error)?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        if summary.is_some() {
+            return Err(integrity_error(format!(
+                "line {line_no}: unexpected content after summary line"
+            )));
+        }
+
+        match serde_json::from_str::<NdjsonRecord>(&line)? {
+            NdjsonRecord::Snapshot { checksum, snapshot } => {
+                if snapshot.checksum()? != checksum {
+                    return Err(integrity_error(format!(
+                        "line {line_no}: checksum mismatch for {} {}",
+                        snapshot.aggregate_type(),
+                        snapshot.aggregate_id()
+                    )));
+                }
+                batch.update(checksum.as_bytes());
+                snapshots.push(snapshot);
+            }
+            NdjsonRecord::Summary(s) => summary = Some(s),
+        }
+    }
+
+    let summary =
+        summary.ok_or_else(|| integrity_error("missing summary line; export may be truncated"))?;
+    if summary.count != snapshots.len() || summary.checksum != format!("{:x}", batch.finalize()) {
+        return Err(integrity_error(format!(
+            "summary mismatch: expected {} snapshots, read {}",
+            summary.count,
+            snapshots.len()
+        )));
+    }
+
+    Ok(snapshots)
+}
+
+fn write_record<W: Write>(writer: &mut W, record: &NdjsonRecord) -> Result<()> {
+    serde_json::to_writer(&mut *writer, record)?;
+    writer.write_all(b"\n").map_err(io_error)
+}
+
+fn io_error(err: std::io::Error) -> DomainError {
+    DomainError::custom(ErrorKind::Internal, err).with_code("SNAPSHOT_TRANSFER_ERROR")
+}
+
+fn integrity_error(msg: impl Into<Box<str>>) -> DomainError {
+    DomainError::invalid_value(msg).with_code("SNAPSHOT_INTEGRITY_ERROR")
+}
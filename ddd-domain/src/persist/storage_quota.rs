@@ -0,0 +1,499 @@
+//! 按租户的存储配额（StorageQuota）
+//!
+//! SaaS 套餐按存储量计费或限额时，`QuotaRepository` 作为事件仓储/快照仓储的装饰器，
+//! 按租户累计写入的事件与快照字节数（JSON 序列化后的大小），并按 `QuotaPolicy` 处理超额写入：
+//! - `QuotaEnforcement::Refuse`：拒绝写入，返回 `ErrorKind::InvalidCommand`（错误码 `QUOTA_EXCEEDED`）；
+//! - `QuotaEnforcement::Warn`：照常写入，并以 `QuotaWarning` 回调告警。
+//!
+//! 事件只追加，用量为累计写入量；快照按聚合保留最新一份的大小。租户默认取自事件上下文扩展字段
+//! （`context.extensions.tenant_id`）与快照负载的 `tenant_id` 字段，无法识别租户的写入不计量。
+//! 用量保存在内存中，重启后以 `seed_usage` 按存储中的实际用量恢复。
+//!
+use crate::{
+    aggregate::Aggregate,
+    error::{DomainError, DomainResult as Result, ErrorKind},
+    persist::{
+        AggregateIdLister, EventExclusion, EventRepository, SerializedEvent, SerializedSnapshot,
+        SnapshotHistory, SnapshotRepository,
+    },
+};
+use async_trait::async_trait;
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::sync::{Arc, Mutex};
+
+type EventTenantFn = Arc<dyn Fn(&SerializedEvent) -> Option<String> + Send + Sync>;
+type SnapshotTenantFn = Arc<dyn Fn(&SerializedSnapshot) -> Option<String> + Send + Sync>;
+type WarningFn = Arc<dyn Fn(&QuotaWarning) + Send + Sync>;
+
+/// 存储类别
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+pub enum StorageKind {
+    Events,
+    Snapshots,
+}
+
+impl fmt::Display for StorageKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Events => write!(f, "events"),
+            Self::Snapshots => write!(f, "snapshots"),
+        }
+    }
+}
+
+/// 单个租户的存储上限（字节），`None` 表示不限
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StorageQuota {
+    pub max_event_bytes: Option<u64>,
+    pub max_snapshot_bytes: Option<u64>,
+}
+
+impl StorageQuota {
+    pub fn unlimited() -> Self {
+        Self::default()
+    }
+
+    pub fn with_event_bytes(mut self, max: u64) -> Self {
+        self.max_event_bytes = Some(max);
+        self
+    }
+
+    pub fn with_snapshot_bytes(mut self, max: u64) -> Self {
+        self.max_snapshot_bytes = Some(max);
+        self
+    }
+
+    fn limit(&self, kind: StorageKind) -> Option<u64> {
+        match kind {
+            StorageKind::Events => self.max_event_bytes,
+            StorageKind::Snapshots => self.max_snapshot_bytes,
+        }
+    }
+}
+
+/// 单个租户的存储用量
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct StorageUsage {
+    pub event_bytes: u64,
+    pub events: u64,
+    pub snapshot_bytes: u64,
+    pub snapshots: u64,
+}
+
+/// 超额处理方式
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub enum QuotaEnforcement {
+    /// 拒绝写入（默认）
+    #[default]
+    Refuse,
+    /// 照常写入并告警
+    Warn,
+}
+
+/// 超额告警
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub struct QuotaWarning {
+    pub tenant_id: String,
+    pub kind: StorageKind,
+    /// 写入后的用量
+    pub used_bytes: u64,
+    pub limit_bytes: u64,
+}
+
+/// 配额策略：默认配额、按租户覆盖的配额与超额处理方式
+#[derive(Clone, Default)]
+pub struct QuotaPolicy {
+    default: StorageQuota,
+    tenants: HashMap<String, StorageQuota>,
+    enforcement: QuotaEnforcement,
+    on_warning: Option<WarningFn>,
+}
+
+impl QuotaPolicy {
+    /// 以 `default` 作为未单独配置租户的配额
+    pub fn new(default: StorageQuota) -> Self {
+        Self {
+            default,
+            ..Self::default()
+        }
+    }
+
+    /// 为租户设置配额（如按套餐）
+    pub fn with_tenant(mut self, tenant_id: impl Into<String>, quota: StorageQuota) -> Self {
+        self.tenants.insert(tenant_id.into(), quota);
+        self
+    }
+
+    pub fn with_enforcement(mut self, enforcement: QuotaEnforcement) -> Self {
+        self.enforcement = enforcement;
+        self
+    }
+
+    /// 超额告警回调（`Warn` 模式下每次超额写入调用一次）
+    pub fn on_warning<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&QuotaWarning) + Send + Sync + 'static,
+    {
+        self.on_warning = Some(Arc::new(f));
+        self
+    }
+
+    pub fn quota(&self, tenant_id: &str) -> StorageQuota {
+        self.tenants.get(tenant_id).copied().unwrap_or(self.default)
+    }
+}
+
+#[derive(Default)]
+struct Ledger {
+    usage: HashMap<String, StorageUsage>,
+    // (聚合类型, 聚合 ID) -> (租户, 最新快照字节数)
+    snapshots: HashMap<(String, String), (String, u64)>,
+}
+
+/// 按租户计量并限制存储用量的仓储装饰器
+///
+/// 同时实现 `EventRepository`（`R: EventRepository`）与 `SnapshotRepository`（`R: SnapshotRepository`）；
+/// 事件与快照分别装饰时各持有独立的用量账本。
+pub struct QuotaRepository<R> {
+    inner: Arc<R>,
+    policy: QuotaPolicy,
+    event_tenant: EventTenantFn,
+    snapshot_tenant: SnapshotTenantFn,
+    ledger: Mutex<Ledger>,
+}
+
+impl<R> QuotaRepository<R> {
+    pub fn new(inner: Arc<R>, policy: QuotaPolicy) -> Self {
+        Self {
+            inner,
+            policy,
+            event_tenant: Arc::new(|event| {
+                event.context()["extensions"]["tenant_id"]
+                    .as_str()
+                    .map(ToString::to_string)
+            }),
+            snapshot_tenant: Arc::new(|snapshot| {
+                snapshot.payload()["tenant_id"]
+                    .as_str()
+                    .map(ToString::to_string)
+            }),
+            ledger: Mutex::new(Ledger::default()),
+        }
+    }
+
+    /// 自定义事件的租户识别
+    pub fn with_event_tenant<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&SerializedEvent) -> Option<String> + Send + Sync + 'static,
+    {
+        self.event_tenant = Arc::new(f);
+        self
+    }
+
+    /// 自定义快照的租户识别
+    pub fn with_snapshot_tenant<F>(mut self, f: F) -> Self
+    where
+        F: Fn(&SerializedSnapshot) -> Option<String> + Send + Sync + 'static,
+    {
+        self.snapshot_tenant = Arc::new(f);
+        self
+    }
+
+    pub fn inner(&self) -> &Arc<R> {
+        &self.inner
+    }
+
+    pub fn policy(&self) -> &QuotaPolicy {
+        &self.policy
+    }
+
+    pub fn usage(&self, tenant_id: &str) -> StorageUsage {
+        let ledger = self.ledger.lock().unwrap();
+        ledger.usage.get(tenant_id).copied().unwrap_or_default()
+    }
+
+    /// 全部租户的用量（按租户排序）
+    pub fn usages(&self) -> BTreeMap<String, StorageUsage> {
+        let ledger = self.ledger.lock().unwrap();
+        ledger
+            .usage
+            .iter()
+            .map(|(tenant, usage)| (tenant.clone(), *usage))
+            .collect()
+    }
+
+    /// 以存储中的实际用量初始化租户账本（如启动时按租户统计）
+    pub fn seed_usage(&self, tenant_id: impl Into<String>, usage: StorageUsage) {
+        let mut ledger = self.ledger.lock().unwrap();
+        ledger.usage.insert(tenant_id.into(), usage);
+    }
+
+    /// 按策略校验新增用量：超额时拒绝或告警
+    fn admit(&self, tenant_id: &str, kind: StorageKind, used: u64, added: u64) -> Result<()> {
+        let Some(limit) = self.policy.quota(tenant_id).limit(kind) else {
+            return Ok(());
+        };
+        let after = used.saturating_add(added);
+        if after <= limit {
+            return Ok(());
+        }
+
+        match self.policy.enforcement {
+            QuotaEnforcement::Refuse => Err(DomainError::new(
+                ErrorKind::InvalidCommand,
+                format!(
+                    "tenant {tenant_id} {kind} storage quota exceeded: {after} of {limit} bytes"
+                ),
+            )
+            .with_code("QUOTA_EXCEEDED")),
+            QuotaEnforcement::Warn => {
+                if let Some(on_warning) = &self.policy.on_warning {
+                    on_warning(&QuotaWarning {
+                        tenant_id: tenant_id.to_string(),
+                        kind,
+                        used_bytes: after,
+                        limit_bytes: limit,
+                    });
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+fn byte_len<T: serde::Serialize>(value: &T) -> Result<u64> {
+    Ok(serde_json::to_vec(value)?.len() as u64)
+}
+
+#[async_trait]
+impl<R> EventRepository for QuotaRepository<R>
+where
+    R: EventRepository,
+{
+    async fn get_events<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<Vec<SerializedEvent>> {
+        self.inner.get_events::<A>(aggregate_id).await
+    }
+
+    async fn get_last_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        last_version: usize,
+    ) -> Result<Vec<SerializedEvent>> {
+        self.inner
+            .get_last_events::<A>(aggregate_id, last_version)
+            .await
+    }
+
+    async fn save(&self, events: Vec<SerializedEvent>) -> Result<()> {
+        let mut added: HashMap<String, (u64, u64)> = HashMap::new();
+        for event in &events {
+            if let Some(tenant) = (self.event_tenant)(event) {
+                let entry = added.entry(tenant).or_default();
+                entry.0 += byte_len(event)?;
+                entry.1 += 1;
+            }
+        }
+
+        // 先预留用量，写入失败时回滚，避免并发写入同时越过上限
+        {
+            let mut ledger = self.ledger.lock().unwrap();
+            for (tenant, (bytes, _)) in &added {
+                let used = ledger.usage.get(tenant).map_or(0, |u| u.event_bytes);
+                self.admit(tenant, StorageKind::Events, used, *bytes)?;
+            }
+            for (tenant, (bytes, count)) in &added {
+                let usage = ledger.usage.entry(tenant.clone()).or_default();
+                usage.event_bytes += bytes;
+                usage.events += count;
+            }
+        }
+
+        if let Err(err) = self.inner.save(events).await {
+            let mut ledger = self.ledger.lock().unwrap();
+            for (tenant, (bytes, count)) in &added {
+                if let Some(usage) = ledger.usage.get_mut(tenant) {
+                    usage.event_bytes = usage.event_bytes.saturating_sub(*bytes);
+                    usage.events = usage.events.saturating_sub(*count);
+                }
+            }
+            return Err(err);
+        }
+        Ok(())
+    }
+
+    async fn ping(&self) -> Result<()> {
+        self.inner.ping().await
+    }
+
+    async fn current_version<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<usize> {
+        self.inner.current_version::<A>(aggregate_id).await
+    }
+
+    async fn exists<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<bool> {
+        self.inner.exists::<A>(aggregate_id).await
+    }
+
+    async fn exists_many<A: Aggregate>(&self, aggregate_ids: &[A::Id]) -> Result<Vec<bool>> {
+        self.inner.exists_many::<A>(aggregate_ids).await
+    }
+
+    async fn mark_excluded(&self, event_id: &str, reason: &str, actor: &str) -> Result<()> {
+        self.inner.mark_excluded(event_id, reason, actor).await
+    }
+
+    async fn exclusions(&self) -> Result<Vec<EventExclusion>> {
+        self.inner.exclusions().await
+    }
+}
+
+#[async_trait]
+impl<R> AggregateIdLister for QuotaRepository<R>
+where
+    R: EventRepository + AggregateIdLister,
+{
+    async fn list_aggregate_ids<A: Aggregate>(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>> {
+        self.inner.list_aggregate_ids::<A>(after, limit).await
+    }
+}
+
+impl<R> QuotaRepository<R>
+where
+    R: SnapshotRepository,
+{
+    /// 计入快照用量后执行写入，写入失败时回滚账本
+    ///
+    /// 租户按 `view`（未编码的负载）识别，用量按实际落盘的 `snapshot` 计量。
+    async fn save_accounted(
+        &self,
+        view: &SerializedSnapshot,
+        snapshot: &SerializedSnapshot,
+        write: impl Future<Output = Result<()>>,
+    ) -> Result<()> {
+        let Some(tenant) = (self.snapshot_tenant)(view) else {
+            return write.await;
+        };
+        let bytes = byte_len(snapshot)?;
+        let key = (
+            snapshot.aggregate_type().to_string(),
+            snapshot.aggregate_id().to_string(),
+        );
+
+        // 快照替换同一聚合的上一份，只计入大小差值
+        let previous = {
+            let mut ledger = self.ledger.lock().unwrap();
+            let previous = ledger.snapshots.get(&key).cloned();
+            let replaced = previous
+                .as_ref()
+                .filter(|(owner, _)| *owner == tenant)
+                .map_or(0, |(_, bytes)| *bytes);
+            let used = ledger.usage.get(&tenant).map_or(0, |u| u.snapshot_bytes);
+            self.admit(
+                &tenant,
+                StorageKind::Snapshots,
+                used.saturating_sub(replaced),
+                bytes,
+            )?;
+            ledger
+                .snapshots
+                .insert(key.clone(), (tenant.clone(), bytes));
+            if let Some((owner, old)) = &previous
+                && let Some(usage) = ledger.usage.get_mut(owner)
+            {
+                usage.snapshot_bytes = usage.snapshot_bytes.saturating_sub(*old);
+                usage.snapshots = usage.snapshots.saturating_sub(1);
+            }
+            let usage = ledger.usage.entry(tenant.clone()).or_default();
+            usage.snapshot_bytes += bytes;
+            usage.snapshots += 1;
+            previous
+        };
+
+        if let Err(err) = write.await {
+            let mut ledger = self.ledger.lock().unwrap();
+            if let Some(usage) = ledger.usage.get_mut(&tenant) {
+                usage.snapshot_bytes = usage.snapshot_bytes.saturating_sub(bytes);
+                usage.snapshots = usage.snapshots.saturating_sub(1);
+            }
+            match previous {
+                Some((owner, old)) => {
+                    let usage = ledger.usage.entry(owner.clone()).or_default();
+                    usage.snapshot_bytes += old;
+                    usage.snapshots += 1;
+                    ledger.snapshots.insert(key, (owner, old));
+                }
+                None => {
+                    ledger.snapshots.remove(&key);
+                }
+            }
+            return Err(err);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl<R> SnapshotRepository for QuotaRepository<R>
+where
+    R: SnapshotRepository,
+{
+    async fn get_snapshot<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        version: Option<usize>,
+    ) -> Result<Option<SerializedSnapshot>> {
+        self.inner.get_snapshot::<A>(aggregate_id, version).await
+    }
+
+    async fn save<A: Aggregate>(&self, aggregate: &A) -> Result<()> {
+        let snapshot = SerializedSnapshot::from_aggregate(aggregate)?;
+        self.save_accounted(&snapshot, &snapshot, self.inner.save::<A>(aggregate))
+            .await
+    }
+
+    async fn save_serialized(&self, snapshot: SerializedSnapshot) -> Result<()> {
+        let view = snapshot
+            .clone()
+            .decode()
+            .unwrap_or_else(|_| snapshot.clone());
+        self.save_accounted(
+            &view,
+            &snapshot,
+            self.inner.save_serialized(snapshot.clone()),
+        )
+        .await
+    }
+
+    async fn bulk_load(&self, snapshots: Vec<SerializedSnapshot>) -> Result<usize> {
+        self.inner.bulk_load(snapshots).await
+    }
+
+    async fn ping(&self) -> Result<()> {
+        self.inner.ping().await
+    }
+}
+
+#[async_trait]
+impl<R> SnapshotHistory for QuotaRepository<R>
+where
+    R: SnapshotRepository + SnapshotHistory,
+{
+    async fn snapshot_versions<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<Vec<usize>> {
+        self.inner.snapshot_versions::<A>(aggregate_id).await
+    }
+
+    /// 配额只计入每个聚合的最新快照，删除历史快照不影响用量
+    async fn delete_snapshots<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        versions: &[usize],
+    ) -> Result<usize> {
+        self.inner
+            .delete_snapshots::<A>(aggregate_id, versions)
+            .await
+    }
+}
@@ -0,0 +1,335 @@
+//! 数据主体访问报告（Subject Access Report）
+//!
+//! 处理 GDPR 数据主体访问请求时，从事件流中提取与某个主体相关的全部事件：
+//! - 事件元数据中 `actor_id` 等于主体 ID；
+//! - 载荷中登记为主体标识的字段（`(event_type, JSON Pointer)`）等于主体 ID。
+//!
+//! 生成结构化报告（可序列化为 JSON 导出），并依据 `PiiRegistry` 给出擦除计划：
+//! 已登记策略的字段按策略给出处置方式，未登记的主体标识字段标记为需擦除，提示补充登记。
+//!
+use crate::persist::{PiiPolicy, PiiRegistry, PiiRule, SerializedEvent};
+use chrono::{DateTime, Utc};
+use serde::Serialize;
+use serde_json::Value;
+use std::collections::HashMap;
+
+/// 事件与主体的关联方式
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "by", content = "pointer", rename_all = "snake_case")]
+pub enum SubjectMatch {
+    /// 事件由该主体触发（`actor_id`）
+    Actor,
+    /// 载荷中的主体标识字段
+    Field(String),
+}
+
+/// 报告中的单个事件
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SubjectEvent {
+    pub event_id: String,
+    pub event_type: String,
+    pub event_version: usize,
+    pub aggregate_type: String,
+    pub aggregate_id: String,
+    pub occurred_at: DateTime<Utc>,
+    pub correlation_id: Option<String>,
+    pub matched_by: Vec<SubjectMatch>,
+    pub payload: Value,
+}
+
+/// 擦除处置方式
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum RedactionAction {
+    /// 加密字段：销毁主体密钥（crypto-shredding）
+    ShredKey,
+    /// 令牌化字段：删除外部令牌库中的映射
+    DeleteToken,
+    /// 明文字段：擦除为 `null`
+    Erase,
+}
+
+/// 擦除计划中的单个字段
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct RedactionStep {
+    pub event_id: String,
+    pub event_type: String,
+    /// 字段位置（JSON Pointer，相对事件载荷）
+    pub pointer: String,
+    pub action: RedactionAction,
+    /// 字段是否已在 `PiiRegistry` 中登记
+    pub registered: bool,
+    /// 登记的数据分类（来自 `#[pii(category = ...)]`）
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub category: Option<String>,
+}
+
+/// 数据主体访问报告
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct SubjectAccessReport {
+    pub subject_id: String,
+    pub generated_at: DateTime<Utc>,
+    /// 相关事件，按发生时间排序
+    pub events: Vec<SubjectEvent>,
+    pub redaction_plan: Vec<RedactionStep>,
+}
+
+impl SubjectAccessReport {
+    /// 未在 `PiiRegistry` 中登记的主体标识字段
+    pub fn unregistered_fields(&self) -> impl Iterator<Item = &RedactionStep> {
+        self.redaction_plan.iter().filter(|s| !s.registered)
+    }
+}
+
+/// 数据主体访问报告生成器
+///
+/// ```rust
+/// use ddd_domain::persist::{PiiPolicy, PiiRegistry, SubjectAccessReporter};
+///
+/// let pii = PiiRegistry::new().register("user.registered", "/email", PiiPolicy::Encrypt);
+/// let reporter = SubjectAccessReporter::new()
+///     .subject_field("order.placed", "/customer_id")
+///     .with_pii(pii);
+///
+/// let report = reporter.report("u-1", Vec::new());
+/// assert!(report.events.is_empty());
+/// ```
+#[derive(Debug, Clone, Default)]
+pub struct SubjectAccessReporter {
+    subject_fields: HashMap<String, Vec<String>>,
+    pii: PiiRegistry,
+}
+
+impl SubjectAccessReporter {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 登记载荷中的主体标识字段
+    pub fn subject_field(
+        mut self,
+        event_type: impl Into<String>,
+        pointer: impl Into<String>,
+    ) -> Self {
+        let pointer = pointer.into();
+        let fields = self.subject_fields.entry(event_type.into()).or_default();
+        if !fields.contains(&pointer) {
+            fields.push(pointer);
+        }
+        self
+    }
+
+    /// 用于生成擦除计划的 PII 策略登记表
+    pub fn with_pii(mut self, pii: PiiRegistry) -> Self {
+        self.pii = pii;
+        self
+    }
+
+    /// 从事件流中提取与主体相关的事件并生成报告
+    pub fn report<I>(&self, subject_id: &str, events: I) -> SubjectAccessReport
+    where
+        I: IntoIterator<Item = SerializedEvent>,
+    {
+        let mut matched: Vec<SubjectEvent> = events
+            .into_iter()
+            .filter_map(|event| self.match_event(subject_id, event))
+            .collect();
+        matched.sort_by_key(|e| e.occurred_at);
+
+        let redaction_plan = matched
+            .iter()
+            .flat_map(|e| self.redaction_steps(e))
+            .collect();
+
+        SubjectAccessReport {
+            subject_id: subject_id.to_string(),
+            generated_at: Utc::now(),
+            events: matched,
+            redaction_plan,
+        }
+    }
+
+    fn match_event(&self, subject_id: &str, event: SerializedEvent) -> Option<SubjectEvent> {
+        let mut matched_by = Vec::new();
+        if event.actor_id() == Some(subject_id) {
+            matched_by.push(SubjectMatch::Actor);
+        }
+        for pointer in self.fields_for(event.event_type()) {
+            if event
+                .payload()
+                .pointer(pointer)
+                .is_some_and(|v| matches_subject(v, subject_id))
+            {
+                matched_by.push(SubjectMatch::Field(pointer.clone()));
+            }
+        }
+        if matched_by.is_empty() {
+            return None;
+        }
+
+        Some(SubjectEvent {
+            event_id: event.event_id().to_string(),
+            event_type: event.event_type().to_string(),
+            event_version: event.event_version(),
+            aggregate_type: event.aggregate_type().to_string(),
+            aggregate_id: event.aggregate_id().to_string(),
+            occurred_at: event.occurred_at(),
+            correlation_id: event.correlation_id().map(ToString::to_string),
+            matched_by,
+            payload: event.payload().clone(),
+        })
+    }
+
+    fn redaction_steps(&self, event: &SubjectEvent) -> Vec<RedactionStep> {
+        let step = |pointer: &str, action, rule: Option<&PiiRule>| RedactionStep {
+            event_id: event.event_id.clone(),
+            event_type: event.event_type.clone(),
+            pointer: pointer.to_string(),
+            action,
+            registered: rule.is_some(),
+            category: rule.and_then(|r| r.category.clone()),
+        };
+
+        let rules = self.pii.rules_for(&event.event_type);
+        let mut steps: Vec<RedactionStep> = rules
+            .iter()
+            .filter(|r| {
+                event
+                    .payload
+                    .pointer(&r.pointer)
+                    .is_some_and(|v| !v.is_null())
+            })
+            .map(|r| {
+                let action = match r.policy {
+                    PiiPolicy::Encrypt => RedactionAction::ShredKey,
+                    PiiPolicy::Tokenize => RedactionAction::DeleteToken,
+                    PiiPolicy::EraseAfter(_) => RedactionAction::Erase,
+                };
+                step(&r.pointer, action, Some(r))
+            })
+            .collect();
+
+        for m in &event.matched_by {
+            if let SubjectMatch::Field(pointer) = m
+                && !rules.iter().any(|r| &r.pointer == pointer)
+            {
+                steps.push(step(pointer, RedactionAction::Erase, None));
+            }
+        }
+        steps
+    }
+
+    fn fields_for(&self, event_type: &str) -> &[String] {
+        self.subject_fields
+            .get(event_type)
+            .map(Vec::as_slice)
+            .unwrap_or(&[])
+    }
+}
+
+/// 字段值等于主体 ID（字符串或数字），或为包含主体 ID 的数组
+fn matches_subject(value: &Value, subject_id: &str) -> bool {
+    match value {
+        Value::String(s) => s == subject_id,
+        Value::Number(n) => n.to_string() == subject_id,
+        Value::Array(items) => items.iter().any(|v| matches_subject(v, subject_id)),
+        _ => false,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn mk_event(
+        id: &str,
+        event_type: &str,
+        actor: Option<&str>,
+        payload: Value,
+    ) -> SerializedEvent {
+        SerializedEvent::builder()
+            .event_id(id.to_string())
+            .event_type(event_type.to_string())
+            .event_version(1)
+            .aggregate_id("a-1".to_string())
+            .aggregate_type("demo".to_string())
+            .aggregate_version(1)
+            .maybe_actor_id(actor.map(ToString::to_string))
+            .occurred_at(Utc::now())
+            .payload(payload)
+            .context(json!({}))
+            .build()
+    }
+
+    #[test]
+    fn collects_events_by_actor_and_subject_fields() {
+        let pii = PiiRegistry::new()
+            .register("user.registered", "/email", PiiPolicy::Encrypt)
+            .register("order.placed", "/phone", PiiPolicy::Tokenize);
+        let reporter = SubjectAccessReporter::new()
+            .subject_field("order.placed", "/customer_id")
+            .subject_field("group.updated", "/members")
+            .with_pii(pii);
+
+        let events = vec![
+            mk_event("e1", "user.registered", Some("u-1"), json!({"email": "x"})),
+            mk_event(
+                "e2",
+                "order.placed",
+                Some("clerk"),
+                json!({"customer_id": "u-1", "phone": "tok"}),
+            ),
+            mk_event(
+                "e3",
+                "group.updated",
+                None,
+                json!({"members": ["u-2", "u-1"]}),
+            ),
+            mk_event(
+                "e4",
+                "order.placed",
+                Some("u-2"),
+                json!({"customer_id": "u-2"}),
+            ),
+        ];
+        let report = reporter.report("u-1", events);
+
+        let ids: Vec<_> = report.events.iter().map(|e| e.event_id.as_str()).collect();
+        assert_eq!(ids, ["e1", "e2", "e3"]);
+        assert_eq!(report.events[0].matched_by, vec![SubjectMatch::Actor]);
+        assert_eq!(
+            report.events[1].matched_by,
+            vec![SubjectMatch::Field("/customer_id".into())]
+        );
+
+        let plan: Vec<_> = report
+            .redaction_plan
+            .iter()
+            .map(|s| {
+                (
+                    s.event_id.as_str(),
+                    s.pointer.as_str(),
+                    s.action,
+                    s.registered,
+                )
+            })
+            .collect();
+        assert_eq!(
+            plan,
+            [
+                ("e1", "/email", RedactionAction::ShredKey, true),
+                ("e2", "/phone", RedactionAction::DeleteToken, true),
+                ("e2", "/customer_id", RedactionAction::Erase, false),
+                ("e3", "/members", RedactionAction::Erase, false),
+            ]
+        );
+        assert_eq!(report.unregistered_fields().count(), 2);
+
+        let exported = serde_json::to_value(&report).unwrap();
+        assert_eq!(
+            exported["events"][1]["matched_by"],
+            json!([{"by": "field", "pointer": "/customer_id"}])
+        );
+    }
+}
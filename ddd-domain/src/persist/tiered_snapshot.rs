@@ -0,0 +1,128 @@
+//! 冷热分层快照仓储（TieredSnapshotRepository）
+//!
+//! 每次保存都写入热存储（如 Redis/内存），按策略每隔 N 个版本写入持久化冷存储；
+//! 读取优先命中热存储，未命中或出错时回退冷存储，并回填热存储。
+//! 在不牺牲持久性的前提下降低热点聚合的加载延迟。
+//!
+use crate::{
+    aggregate::Aggregate,
+    error::DomainResult as Result,
+    persist::{SerializedSnapshot, SnapshotHistory, SnapshotPolicy, SnapshotRepository},
+};
+use async_trait::async_trait;
+
+/// `SnapshotRepository` 的冷热分层组合
+///
+/// - `H`：热存储，视为缓存，读写失败均不影响结果；
+/// - `C`：冷存储，按 `cold_policy` 落盘，写入失败向上返回。
+pub struct TieredSnapshotRepository<H, C> {
+    hot: H,
+    cold: C,
+    cold_policy: SnapshotPolicy,
+}
+
+impl<H, C> TieredSnapshotRepository<H, C>
+where
+    H: SnapshotRepository,
+    C: SnapshotRepository,
+{
+    pub fn new(hot: H, cold: C, cold_policy: SnapshotPolicy) -> Self {
+        Self {
+            hot,
+            cold,
+            cold_policy,
+        }
+    }
+}
+
+#[async_trait]
+impl<H, C> SnapshotRepository for TieredSnapshotRepository<H, C>
+where
+    H: SnapshotRepository,
+    C: SnapshotRepository,
+{
+    async fn get_snapshot<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        version: Option<usize>,
+    ) -> Result<Option<SerializedSnapshot>> {
+        if let Ok(Some(snapshot)) = self.hot.get_snapshot::<A>(aggregate_id, version).await {
+            return Ok(Some(snapshot));
+        }
+
+        let snapshot = self.cold.get_snapshot::<A>(aggregate_id, version).await?;
+
+        // 仅在读取最新快照时回填热存储，避免以历史版本覆盖
+        if version.is_none()
+            && let Some(snapshot) = &snapshot
+        {
+            let _ = self.hot.save_serialized(snapshot.clone()).await;
+        }
+
+        Ok(snapshot)
+    }
+
+    async fn save<A: Aggregate>(&self, aggregate: &A) -> Result<()> {
+        let _ = self.hot.save::<A>(aggregate).await;
+
+        if self
+            .cold_policy
+            .should_snapshot(aggregate.version().value())
+        {
+            self.cold.save::<A>(aggregate).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn save_serialized(&self, snapshot: SerializedSnapshot) -> Result<()> {
+        let _ = self.hot.save_serialized(snapshot.clone()).await;
+
+        if self
+            .cold_policy
+            .should_snapshot(snapshot.aggregate_version())
+        {
+            self.cold.save_serialized(snapshot).await?;
+        }
+
+        Ok(())
+    }
+
+    async fn ping(&self) -> Result<()> {
+        self.hot.ping().await?;
+        self.cold.ping().await
+    }
+
+    /// 写入冷存储，并尽力预热热存储
+    async fn bulk_load(&self, snapshots: Vec<SerializedSnapshot>) -> Result<usize> {
+        let count = self.cold.bulk_load(snapshots.clone()).await?;
+        let _ = self.hot.bulk_load(snapshots).await;
+        Ok(count)
+    }
+}
+
+#[async_trait]
+impl<H, C> SnapshotHistory for TieredSnapshotRepository<H, C>
+where
+    H: SnapshotRepository + SnapshotHistory,
+    C: SnapshotRepository + SnapshotHistory,
+{
+    /// 以冷存储为准
+    async fn snapshot_versions<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<Vec<usize>> {
+        self.cold.snapshot_versions::<A>(aggregate_id).await
+    }
+
+    /// 从冷存储删除，并尽力同步删除热存储中的同版本快照
+    async fn delete_snapshots<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        versions: &[usize],
+    ) -> Result<usize> {
+        let deleted = self
+            .cold
+            .delete_snapshots::<A>(aggregate_id, versions)
+            .await?;
+        let _ = self.hot.delete_snapshots::<A>(aggregate_id, versions).await;
+        Ok(deleted)
+    }
+}
@@ -0,0 +1,102 @@
+//! 未知事件类型处理（UnknownEventPolicy）
+//!
+//! 重放时遇到当前版本不认识的事件类型（如由更新版本的服务写入），按策略失败、跳过或跳过并记录，
+//! 记录的事件经 `UnknownEventLog` 暴露给观测。
+//!
+use crate::persist::SerializedEvent;
+use std::collections::{BTreeMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// 未知事件类型的处理策略
+///
+/// 事件类型不在当前事件枚举的 `DESCRIPTORS` 中（如由更新版本的服务写入）即视为未知；
+/// 未声明 `DESCRIPTORS` 的事件类型无法判定，始终按 `Fail` 处理。
+///
+/// 注意：聚合重建时跳过事件会使聚合版本落后于存储，后续写入将以并发冲突失败，
+/// 因此 `Skip`/`Collect` 主要用于只读场景（查询、投影、审计）。
+#[derive(Clone, Debug, Default)]
+pub enum UnknownEventPolicy {
+    /// 反序列化失败（默认，与未配置策略时一致）
+    #[default]
+    Fail,
+    /// 静默跳过
+    Skip,
+    /// 跳过并记录到日志，便于观测
+    Collect(Arc<UnknownEventLog>),
+}
+
+impl UnknownEventPolicy {
+    /// 跳过并记录到新建的日志（保留最近 `capacity` 条）
+    pub fn collect(capacity: usize) -> Self {
+        Self::Collect(Arc::new(UnknownEventLog::new(capacity)))
+    }
+
+    /// 收集策略的日志（其他策略为空）
+    pub fn log(&self) -> Option<&Arc<UnknownEventLog>> {
+        match self {
+            Self::Collect(log) => Some(log),
+            _ => None,
+        }
+    }
+}
+
+/// 未知事件日志：保留最近的未知事件，并按事件类型累计次数
+#[derive(Debug)]
+pub struct UnknownEventLog {
+    capacity: usize,
+    state: Mutex<LogState>,
+}
+
+#[derive(Debug, Default)]
+struct LogState {
+    recent: VecDeque<SerializedEvent>,
+    counts: BTreeMap<String, u64>,
+}
+
+impl Default for UnknownEventLog {
+    fn default() -> Self {
+        Self::new(1000)
+    }
+}
+
+impl UnknownEventLog {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            capacity: capacity.max(1),
+            state: Mutex::new(LogState::default()),
+        }
+    }
+
+    /// 记录一个未知事件，超出容量时丢弃最旧的记录（计数不受影响）
+    pub fn record(&self, event: &SerializedEvent) {
+        let mut state = self.state.lock().unwrap();
+        *state
+            .counts
+            .entry(event.event_type().to_string())
+            .or_default() += 1;
+        if state.recent.len() == self.capacity {
+            state.recent.pop_front();
+        }
+        state.recent.push_back(event.clone());
+    }
+
+    /// 最近记录的未知事件（按记录顺序）
+    pub fn recent(&self) -> Vec<SerializedEvent> {
+        self.state.lock().unwrap().recent.iter().cloned().collect()
+    }
+
+    /// 各未知事件类型的累计次数
+    pub fn counts(&self) -> BTreeMap<String, u64> {
+        self.state.lock().unwrap().counts.clone()
+    }
+
+    /// 累计记录的未知事件总数
+    pub fn total(&self) -> u64 {
+        self.state.lock().unwrap().counts.values().sum()
+    }
+
+    /// 取出并清空最近记录（计数保留）
+    pub fn drain(&self) -> Vec<SerializedEvent> {
+        self.state.lock().unwrap().recent.drain(..).collect()
+    }
+}
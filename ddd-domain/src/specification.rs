@@ -2,6 +2,21 @@
 //!
 //! 用于封装业务规则并支持 AND/OR/NOT 组合，便于复用与测试。
 //!
+//! 需要访问仓储的规约（如“邮箱唯一”“引用的聚合存在”）实现 `AsyncSpecification`：
+//! - `SpecCache` 在一次命令执行范围内记忆化求值结果（按规约给出的 `cache_key`），
+//!   同一候选对象被多个规则重复检查时只查询一次；
+//! - `AggregateExists` 检查聚合存在性，`check_all` 将多个 ID 合并为一次
+//!   `EventRepository::exists_many` 调用，并与缓存共享结果。
+//!
+use crate::aggregate::Aggregate;
+use crate::error::DomainResult;
+use crate::persist::EventRepository;
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::marker::PhantomData;
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
 /// 规约模式的核心 trait
 ///
 /// 用于封装业务规则，使其可复用、可组合和可测试
@@ -103,6 +118,166 @@ impl<T> Specification<T> for NotSpecification<T> {
     }
 }
 
+/// 异步规约：求值可能访问仓储等外部资源
+#[async_trait]
+pub trait AsyncSpecification<T: ?Sized + Sync>: Send + Sync {
+    async fn is_satisfied_by(&self, candidate: &T) -> DomainResult<bool>;
+
+    /// 记忆化键（应包含规约名称与候选对象标识）；返回 `None` 表示不缓存
+    fn cache_key(&self, candidate: &T) -> Option<String> {
+        let _ = candidate;
+        None
+    }
+}
+
+/// 异步规约求值结果缓存
+///
+/// 生命周期应限定在一次命令执行内（应用层由 `AppContext::spec_cache` 提供），
+/// 避免跨命令复用过期结果；求值出错时不缓存。
+#[derive(Debug, Default)]
+pub struct SpecCache {
+    results: Mutex<HashMap<String, bool>>,
+    hits: AtomicUsize,
+}
+
+impl SpecCache {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 求值规约；存在缓存键时优先读取缓存，未命中则求值并记录
+    pub async fn evaluate<T, S>(&self, spec: &S, candidate: &T) -> DomainResult<bool>
+    where
+        T: ?Sized + Sync,
+        S: AsyncSpecification<T> + ?Sized,
+    {
+        let Some(key) = spec.cache_key(candidate) else {
+            return spec.is_satisfied_by(candidate).await;
+        };
+        if let Some(satisfied) = self.get(&key) {
+            return Ok(satisfied);
+        }
+        let satisfied = spec.is_satisfied_by(candidate).await?;
+        self.insert(key, satisfied);
+        Ok(satisfied)
+    }
+
+    /// 读取缓存结果（命中时计数）
+    pub fn get(&self, key: &str) -> Option<bool> {
+        let found = self.results.lock().unwrap().get(key).copied();
+        if found.is_some() {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+        }
+        found
+    }
+
+    pub fn insert(&self, key: impl Into<String>, satisfied: bool) {
+        self.results.lock().unwrap().insert(key.into(), satisfied);
+    }
+
+    /// 使指定结果失效（如本命令内创建了被检查的聚合）
+    pub fn invalidate(&self, key: &str) {
+        self.results.lock().unwrap().remove(key);
+    }
+
+    pub fn len(&self) -> usize {
+        self.results.lock().unwrap().len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 缓存命中次数
+    pub fn hits(&self) -> usize {
+        self.hits.load(Ordering::Relaxed)
+    }
+}
+
+/// 聚合存在性规约：候选聚合 ID 至少有一个事件时满足
+pub struct AggregateExists<A, R> {
+    repo: R,
+    _aggregate: PhantomData<fn() -> A>,
+}
+
+impl<A, R> AggregateExists<A, R>
+where
+    A: Aggregate,
+    R: EventRepository,
+{
+    pub fn new(repo: R) -> Self {
+        Self {
+            repo,
+            _aggregate: PhantomData,
+        }
+    }
+
+    /// 缓存键：`exists:<聚合类型>:<聚合 ID>`
+    pub fn key(aggregate_id: &A::Id) -> String {
+        format!("exists:{}:{}", A::TYPE, aggregate_id)
+    }
+
+    /// 批量检查，结果与 `aggregate_ids` 一一对应
+    ///
+    /// 已缓存的 ID 直接取缓存，其余合并为一次 `exists_many` 调用并写回缓存。
+    pub async fn check_all(
+        &self,
+        cache: &SpecCache,
+        aggregate_ids: &[A::Id],
+    ) -> DomainResult<Vec<bool>> {
+        let mut found: Vec<Option<bool>> = aggregate_ids
+            .iter()
+            .map(|id| cache.get(&Self::key(id)))
+            .collect();
+
+        let missing: Vec<A::Id> = aggregate_ids
+            .iter()
+            .zip(&found)
+            .filter(|(_, cached)| cached.is_none())
+            .map(|(id, _)| id.clone())
+            .collect();
+        if !missing.is_empty() {
+            let mut fetched = self.repo.exists_many::<A>(&missing).await?.into_iter();
+            for (id, slot) in aggregate_ids.iter().zip(found.iter_mut()) {
+                if slot.is_none() {
+                    let exists = fetched.next().unwrap_or(false);
+                    cache.insert(Self::key(id), exists);
+                    *slot = Some(exists);
+                }
+            }
+        }
+        Ok(found.into_iter().map(|f| f.unwrap_or(false)).collect())
+    }
+
+    /// 全部聚合均存在时返回 `true`
+    pub async fn all_exist(
+        &self,
+        cache: &SpecCache,
+        aggregate_ids: &[A::Id],
+    ) -> DomainResult<bool> {
+        Ok(self
+            .check_all(cache, aggregate_ids)
+            .await?
+            .into_iter()
+            .all(|exists| exists))
+    }
+}
+
+#[async_trait]
+impl<A, R> AsyncSpecification<A::Id> for AggregateExists<A, R>
+where
+    A: Aggregate,
+    R: EventRepository,
+{
+    async fn is_satisfied_by(&self, candidate: &A::Id) -> DomainResult<bool> {
+        self.repo.exists::<A>(candidate).await
+    }
+
+    fn cache_key(&self, candidate: &A::Id) -> Option<String> {
+        Some(Self::key(candidate))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -154,6 +329,47 @@ mod tests {
         assert!(spec.is_satisfied_by(&42));
     }
 
+    struct EvenSpec {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl AsyncSpecification<i32> for EvenSpec {
+        async fn is_satisfied_by(&self, candidate: &i32) -> DomainResult<bool> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            if *candidate < 0 {
+                return Err(crate::error::DomainError::invalid_value("negative"));
+            }
+            Ok(candidate % 2 == 0)
+        }
+
+        fn cache_key(&self, candidate: &i32) -> Option<String> {
+            Some(format!("even:{candidate}"))
+        }
+    }
+
+    #[tokio::test]
+    async fn test_spec_cache_memoizes_results_but_not_errors() {
+        let spec = EvenSpec {
+            calls: AtomicUsize::new(0),
+        };
+        let cache = SpecCache::new();
+
+        assert!(cache.evaluate(&spec, &4).await.unwrap());
+        assert!(cache.evaluate(&spec, &4).await.unwrap());
+        assert!(!cache.evaluate(&spec, &3).await.unwrap());
+        assert_eq!(spec.calls.load(Ordering::SeqCst), 2);
+        assert_eq!((cache.len(), cache.hits()), (2, 1));
+
+        assert!(cache.evaluate(&spec, &-1).await.is_err());
+        assert!(cache.evaluate(&spec, &-1).await.is_err());
+        assert_eq!(spec.calls.load(Ordering::SeqCst), 4);
+
+        cache.invalidate("even:4");
+        assert!(cache.evaluate(&spec, &4).await.unwrap());
+        assert_eq!(spec.calls.load(Ordering::SeqCst), 5);
+    }
+
     #[test]
     fn test_complex_combination() {
         // (TRUE AND FALSE) OR (NOT FALSE) = FALSE OR TRUE = TRUE
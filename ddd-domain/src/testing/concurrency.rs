@@ -0,0 +1,300 @@
+use crate::{
+    aggregate::Aggregate,
+    aggregate_root::AggregateRoot,
+    domain_event::{DomainEvent, EventContext},
+    error::{DomainError, DomainResult as Result, ErrorCode, ErrorKind},
+    event_upcaster::EventUpcasterChain,
+    persist::{EventRepository, EventSourcedRepo},
+    value_object::Version,
+};
+use bon::Builder;
+use std::collections::HashMap;
+use std::sync::Arc;
+use tokio::sync::Semaphore;
+
+/// 聚合根并发测试套件
+///
+/// 通过 `AggregateRoot` + `EventSourcedRepo` 对同一聚合并发执行多个命令批次，
+/// 遇到可重试错误（乐观锁冲突）时自动重试；结束后校验：
+/// - 事件流版本从 1 开始连续、无重复；
+/// - 每个成功批次的事件均在流中且彼此相邻（未与其他批次交错），无丢失更新；
+/// - 按提交顺序串行重放成功批次，产生相同数量的事件；
+/// - （可选）串行结果状态与仓储加载状态一致。
+///
+/// 命令执行依赖随机数/时间等非确定性输入时，应关闭 `compare_state`。
+#[derive(Builder)]
+pub struct ConcurrencyTestKit<E> {
+    event_repo: Arc<E>,
+    #[builder(default = Arc::new(EventUpcasterChain::default()))]
+    upcaster_chain: Arc<EventUpcasterChain>,
+    /// 同时执行的最大批次数
+    #[builder(default = 8)]
+    concurrency: usize,
+    /// 单个批次的最大重试次数
+    #[builder(default = 32)]
+    max_retries: usize,
+    /// 是否比较串行重放状态与仓储加载状态
+    #[builder(default = true)]
+    compare_state: bool,
+}
+
+/// 并发执行结果与校验报告
+#[derive(Debug, Clone, Default)]
+pub struct LinearizabilityReport {
+    /// 成功提交的批次数
+    pub committed: usize,
+    /// 最终失败（业务拒绝或重试耗尽）的批次数
+    pub rejected: usize,
+    /// 累计重试次数
+    pub retries: usize,
+    /// 事件流中的事件数
+    pub events: usize,
+    /// 违反线性一致性的描述
+    pub violations: Vec<String>,
+}
+
+impl LinearizabilityReport {
+    pub fn is_linearizable(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// 断言结果可线性化，否则 panic 并列出全部违例
+    pub fn assert_linearizable(&self) {
+        assert!(
+            self.is_linearizable(),
+            "non-linearizable outcome:\n{}",
+            self.violations.join("\n")
+        );
+    }
+}
+
+/// 单个批次的执行结果：成功时为产生的事件 ID
+struct Outcome {
+    batch: usize,
+    result: std::result::Result<Vec<String>, String>,
+    retries: usize,
+}
+
+impl<E> ConcurrencyTestKit<E>
+where
+    E: EventRepository + 'static,
+{
+    /// 并发执行命令批次并校验最终事件流
+    pub async fn run<A>(
+        &self,
+        aggregate_id: &A::Id,
+        batches: Vec<Vec<A::Command>>,
+    ) -> Result<LinearizabilityReport>
+    where
+        A: Aggregate + 'static,
+        A::Id: 'static,
+        A::Command: Clone + Send + Sync + 'static,
+        A::Error: ErrorCode + From<DomainError>,
+    {
+        let root = Arc::new(AggregateRoot::<A, _>::new(EventSourcedRepo::new(
+            Arc::clone(&self.event_repo),
+            Arc::clone(&self.upcaster_chain),
+        )));
+        let outcomes = self
+            .execute_concurrently(&root, aggregate_id, &batches)
+            .await?;
+
+        let mut report = LinearizabilityReport::default();
+        for o in &outcomes {
+            report.retries += o.retries;
+            match &o.result {
+                Ok(_) => report.committed += 1,
+                Err(_) => report.rejected += 1,
+            }
+        }
+
+        let stream = self.event_repo.get_events::<A>(aggregate_id).await?;
+        report.events = stream.len();
+
+        // 1. 版本连续
+        for (i, e) in stream.iter().enumerate() {
+            if e.aggregate_version() != i + 1 {
+                report.violations.push(format!(
+                    "event {} at position {i} has version {}, expected {}",
+                    e.event_id(),
+                    e.aggregate_version(),
+                    i + 1
+                ));
+            }
+        }
+
+        // 2. 无丢失更新，批次不交错
+        let mut positions: HashMap<&str, usize> = HashMap::new();
+        for (i, e) in stream.iter().enumerate() {
+            if positions.insert(e.event_id(), i).is_some() {
+                report
+                    .violations
+                    .push(format!("event {} appears more than once", e.event_id()));
+            }
+        }
+
+        let mut order: Vec<(usize, usize, usize)> = Vec::new(); // (起始位置, 批次, 事件数)
+        let mut produced = 0;
+        for o in &outcomes {
+            let Ok(ids) = &o.result else { continue };
+            produced += ids.len();
+
+            let found: Vec<usize> = ids
+                .iter()
+                .filter_map(|id| positions.get(id.as_str()).copied())
+                .collect();
+
+            if found.len() != ids.len() {
+                report.violations.push(format!(
+                    "lost update: batch {} committed {} events but only {} are in the stream",
+                    o.batch,
+                    ids.len(),
+                    found.len()
+                ));
+                continue;
+            }
+
+            if let Some(&start) = found.first() {
+                if found.iter().enumerate().any(|(k, &p)| p != start + k) {
+                    report.violations.push(format!(
+                        "batch {} is interleaved with other batches",
+                        o.batch
+                    ));
+                }
+                order.push((start, o.batch, ids.len()));
+            }
+        }
+
+        if produced != stream.len() {
+            report.violations.push(format!(
+                "stream has {} events but committed batches produced {produced}",
+                stream.len()
+            ));
+        }
+
+        // 3. 串行重放
+        order.sort_unstable();
+        let mut serial = A::new(aggregate_id.clone(), Version::new());
+        let mut serial_ok = true;
+        for (_, batch, expected) in order {
+            let mut produced = 0;
+            for cmd in batches[batch].iter().cloned() {
+                match serial.execute(cmd) {
+                    Ok(events) => {
+                        produced += events.len();
+                        events.iter().for_each(|e| serial.apply(e));
+                    }
+                    Err(e) => {
+                        report.violations.push(format!(
+                            "batch {batch} is rejected when replayed serially: {e}"
+                        ));
+                        serial_ok = false;
+                        break;
+                    }
+                }
+            }
+            if !serial_ok {
+                break;
+            }
+            if produced != expected {
+                report.violations.push(format!(
+                    "batch {batch} produced {produced} events serially, {expected} concurrently"
+                ));
+            }
+        }
+
+        // 4. 状态一致
+        if self.compare_state && serial_ok {
+            // 重放拒绝分叉的事件流（如同一版本的多个事件）时记为违例，而非中断检查
+            match root.load(aggregate_id).await {
+                Ok(loaded) => {
+                    let loaded =
+                        loaded.unwrap_or_else(|| A::new(aggregate_id.clone(), Version::new()));
+                    if serde_json::to_value(&serial)? != serde_json::to_value(&loaded)? {
+                        report.violations.push(
+                            "state replayed serially differs from state loaded from repository"
+                                .into(),
+                        );
+                    }
+                }
+                Err(e) => report
+                    .violations
+                    .push(format!("state cannot be loaded from repository: {e}")),
+            }
+        }
+
+        Ok(report)
+    }
+
+    async fn execute_concurrently<A>(
+        &self,
+        root: &Arc<AggregateRoot<A, EventSourcedRepo<E>>>,
+        aggregate_id: &A::Id,
+        batches: &[Vec<A::Command>],
+    ) -> Result<Vec<Outcome>>
+    where
+        A: Aggregate + 'static,
+        A::Id: 'static,
+        A::Command: Clone + Send + Sync + 'static,
+        A::Error: ErrorCode + From<DomainError>,
+    {
+        let semaphore = Arc::new(Semaphore::new(self.concurrency.max(1)));
+        let max_retries = self.max_retries;
+
+        let tasks: Vec<_> = batches
+            .iter()
+            .cloned()
+            .enumerate()
+            .map(|(batch, commands)| {
+                let root = Arc::clone(root);
+                let semaphore = Arc::clone(&semaphore);
+                let id = aggregate_id.clone();
+
+                tokio::spawn(async move {
+                    let _permit = semaphore.acquire_owned().await;
+                    let mut retries = 0;
+
+                    loop {
+                        match root
+                            .execute(&id, commands.clone(), EventContext::default())
+                            .await
+                        {
+                            Ok(envelopes) => {
+                                let ids = envelopes
+                                    .iter()
+                                    .map(|e| e.payload.event_id().to_string())
+                                    .collect();
+                                return Outcome {
+                                    batch,
+                                    result: Ok(ids),
+                                    retries,
+                                };
+                            }
+                            Err(e) if e.is_retryable() && retries < max_retries => {
+                                retries += 1;
+                                tokio::task::yield_now().await;
+                            }
+                            Err(e) => {
+                                return Outcome {
+                                    batch,
+                                    result: Err(e.to_string()),
+                                    retries,
+                                };
+                            }
+                        }
+                    }
+                })
+            })
+            .collect();
+
+        let mut outcomes = Vec::with_capacity(tasks.len());
+        for task in tasks {
+            outcomes.push(
+                task.await
+                    .map_err(|e| DomainError::custom(ErrorKind::Internal, e))?,
+            );
+        }
+
+        Ok(outcomes)
+    }
+}
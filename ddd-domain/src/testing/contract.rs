@@ -0,0 +1,277 @@
+//! 消费者驱动的事件契约测试（Consumer-Driven Contracts）
+//!
+//! 消费者（任意语言）以 JSON 文件提交期望：依赖的事件类型、可选的事件版本，以及所依赖载荷结构的
+//! JSON Schema 子集；生产者在测试中以 `EventContractRegistry::verify` 按当前序列化结果校验全部期望，
+//! 生产者的变更会破坏某个消费者时测试失败。
+//!
+//! 期望文件格式（单个对象或对象数组）：
+//!
+//! ```json
+//! {
+//!   "consumer": "billing",
+//!   "event_type": "OrderEvent.Placed",
+//!   "event_version": 1,
+//!   "schema": {
+//!     "type": "object",
+//!     "required": ["Placed"],
+//!     "properties": {
+//!       "Placed": {
+//!         "type": "object",
+//!         "required": ["amount"],
+//!         "properties": { "amount": { "type": "integer" } }
+//!       }
+//!     }
+//!   }
+//! }
+//! ```
+//!
+//! 支持的 Schema 关键字：`type`（字符串或数组）、`required`、`properties`、`items`、`enum`；
+//! 其余关键字忽略。校验对象为事件在存储/总线上的载荷（按 `DomainEvent::PAYLOAD_FORMAT` 编码）。
+//!
+use crate::domain_event::{DomainEvent, EventDescriptor};
+use crate::error::{DomainError, DomainResult};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::fmt;
+use std::path::Path;
+
+/// 单个消费者对某事件类型的期望
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct EventExpectation {
+    pub consumer: String,
+    pub event_type: String,
+    /// 期望的事件版本；省略时不校验版本
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub event_version: Option<usize>,
+    /// 所依赖载荷结构的 JSON Schema 子集
+    pub schema: Value,
+}
+
+/// 契约违例
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ContractViolation {
+    pub consumer: String,
+    pub event_type: String,
+    /// 载荷中出错位置（JSON Pointer），为空表示事件级别的问题
+    pub path: String,
+    pub message: String,
+}
+
+impl fmt::Display for ContractViolation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} expects {}", self.consumer, self.event_type)?;
+        if !self.path.is_empty() {
+            write!(f, " at {}", self.path)?;
+        }
+        write!(f, ": {}", self.message)
+    }
+}
+
+/// 契约校验结果
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ContractReport {
+    /// 参与校验的期望数
+    pub checked: usize,
+    pub violations: Vec<ContractViolation>,
+}
+
+impl ContractReport {
+    pub fn is_ok(&self) -> bool {
+        self.violations.is_empty()
+    }
+
+    /// 存在违例时 panic，并列出全部违例（供测试断言）
+    #[track_caller]
+    pub fn assert_ok(&self) {
+        if !self.is_ok() {
+            panic!("event contracts broken:\n{self}");
+        }
+    }
+}
+
+impl fmt::Display for ContractReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, violation) in self.violations.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "- {violation}")?;
+        }
+        Ok(())
+    }
+}
+
+/// 消费者期望登记表
+#[derive(Debug, Clone, Default)]
+pub struct EventContractRegistry {
+    expectations: Vec<EventExpectation>,
+}
+
+impl EventContractRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn expectation(mut self, expectation: EventExpectation) -> Self {
+        self.expectations.push(expectation);
+        self
+    }
+
+    /// 从 JSON 文本加载期望（单个对象或数组）
+    pub fn load_json(mut self, json: &str) -> DomainResult<Self> {
+        let value: Value = serde_json::from_str(json)?;
+        match value {
+            Value::Array(items) => {
+                for item in items {
+                    self.expectations.push(serde_json::from_value(item)?);
+                }
+            }
+            item => self.expectations.push(serde_json::from_value(item)?),
+        }
+        Ok(self)
+    }
+
+    /// 加载目录下全部 `*.json` 期望文件（按文件名排序）
+    pub fn load_dir(mut self, dir: impl AsRef<Path>) -> DomainResult<Self> {
+        let dir = dir.as_ref();
+        let read_err = |e: std::io::Error| {
+            DomainError::invalid_state(format!("cannot read contracts from {}: {e}", dir.display()))
+        };
+        let mut paths: Vec<_> = std::fs::read_dir(dir)
+            .map_err(read_err)?
+            .filter_map(|entry| entry.ok().map(|e| e.path()))
+            .filter(|path| path.extension().is_some_and(|ext| ext == "json"))
+            .collect();
+        paths.sort();
+        for path in paths {
+            let json = std::fs::read_to_string(&path).map_err(read_err)?;
+            self = self.load_json(&json)?;
+        }
+        Ok(self)
+    }
+
+    pub fn expectations(&self) -> &[EventExpectation] {
+        &self.expectations
+    }
+
+    /// 以事件枚举 `E` 的样例事件校验针对其事件类型的全部期望
+    ///
+    /// 每个被期望的事件类型至少需要一个样例；同一类型的每个样例都须满足期望。
+    pub fn verify<E: DomainEvent>(&self, samples: &[E]) -> DomainResult<ContractReport> {
+        let mut encoded = Vec::with_capacity(samples.len());
+        for sample in samples {
+            encoded.push((sample.event_type(), E::PAYLOAD_FORMAT.encode(sample)?));
+        }
+
+        let mut report = ContractReport::default();
+        for expectation in &self.expectations {
+            let declared = EventDescriptor::find(E::DESCRIPTORS, &expectation.event_type);
+            let sampled = encoded
+                .iter()
+                .filter(|(event_type, _)| *event_type == expectation.event_type)
+                .map(|(_, payload)| payload)
+                .collect::<Vec<_>>();
+            if declared.is_none() && sampled.is_empty() {
+                continue;
+            }
+
+            report.checked += 1;
+            let mut violation = |path: String, message: String| {
+                report.violations.push(ContractViolation {
+                    consumer: expectation.consumer.clone(),
+                    event_type: expectation.event_type.clone(),
+                    path,
+                    message,
+                });
+            };
+
+            if let (Some(expected), Some(declared)) = (expectation.event_version, declared)
+                && expected != declared.event_version
+            {
+                violation(
+                    String::new(),
+                    format!(
+                        "expects version {expected}, producer emits version {}",
+                        declared.event_version
+                    ),
+                );
+            }
+            if sampled.is_empty() {
+                violation(String::new(), "no sample event provided".to_string());
+            }
+            for payload in sampled {
+                let mut errors = Vec::new();
+                check(&expectation.schema, payload, "", &mut errors);
+                for (path, message) in errors {
+                    violation(path, message);
+                }
+            }
+        }
+        Ok(report)
+    }
+}
+
+fn type_name(value: &Value) -> &'static str {
+    match value {
+        Value::Null => "null",
+        Value::Bool(_) => "boolean",
+        Value::Number(n) if n.is_i64() || n.is_u64() => "integer",
+        Value::Number(_) => "number",
+        Value::String(_) => "string",
+        Value::Array(_) => "array",
+        Value::Object(_) => "object",
+    }
+}
+
+fn type_matches(expected: &str, actual: &str) -> bool {
+    expected == actual || (expected == "number" && actual == "integer")
+}
+
+/// 按 Schema 子集校验，错误以 (JSON Pointer, 描述) 收集
+fn check(schema: &Value, value: &Value, path: &str, errors: &mut Vec<(String, String)>) {
+    let actual = type_name(value);
+    let allowed: Vec<&str> = match schema.get("type") {
+        Some(Value::String(t)) => vec![t.as_str()],
+        Some(Value::Array(ts)) => ts.iter().filter_map(Value::as_str).collect(),
+        _ => Vec::new(),
+    };
+    if !allowed.is_empty() && !allowed.iter().any(|t| type_matches(t, actual)) {
+        errors.push((
+            path.to_string(),
+            format!("expected {}, found {actual}", allowed.join(" | ")),
+        ));
+        return;
+    }
+
+    if let Some(Value::Array(options)) = schema.get("enum")
+        && !options.contains(value)
+    {
+        errors.push((path.to_string(), format!("value {value} is not allowed")));
+    }
+
+    if let Value::Object(fields) = value {
+        if let Some(Value::Array(required)) = schema.get("required") {
+            for name in required.iter().filter_map(Value::as_str) {
+                if !fields.contains_key(name) {
+                    errors.push((
+                        format!("{path}/{name}"),
+                        "required field is missing".to_string(),
+                    ));
+                }
+            }
+        }
+        if let Some(Value::Object(properties)) = schema.get("properties") {
+            for (name, property) in properties {
+                if let Some(field) = fields.get(name) {
+                    check(property, field, &format!("{path}/{name}"), errors);
+                }
+            }
+        }
+    }
+
+    if let (Value::Array(items), Some(item_schema)) = (value, schema.get("items")) {
+        for (i, item) in items.iter().enumerate() {
+            check(item_schema, item, &format!("{path}/{i}"), errors);
+        }
+    }
+}
@@ -0,0 +1,44 @@
+use crate::domain_event::{EventIdGenerator, EventIdGeneratorGuard, scoped_event_id_generator};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+
+/// 基于种子的确定性事件 ID 生成器
+///
+/// 相同种子按相同顺序产生相同的 ID 序列，格式与默认生成器一致（UUID 字符串），
+/// 便于在黄金文件与断言中直接比较事件 ID。
+#[derive(Debug)]
+pub struct DeterministicEventIdGenerator {
+    seed: u64,
+    counter: AtomicU64,
+}
+
+impl DeterministicEventIdGenerator {
+    pub fn new(seed: u64) -> Self {
+        Self {
+            seed,
+            counter: AtomicU64::new(0),
+        }
+    }
+
+    /// 在当前线程安装种子为 `seed` 的生成器，守卫析构后恢复
+    pub fn install(seed: u64) -> EventIdGeneratorGuard {
+        scoped_event_id_generator(Arc::new(Self::new(seed)))
+    }
+}
+
+/// SplitMix64 混淆函数
+fn mix(mut z: u64) -> u64 {
+    z = z.wrapping_add(0x9E37_79B9_7F4A_7C15);
+    z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+    z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+    z ^ (z >> 31)
+}
+
+impl EventIdGenerator for DeterministicEventIdGenerator {
+    fn next_id(&self) -> String {
+        let n = self.counter.fetch_add(1, Ordering::Relaxed);
+        let high = mix(self.seed ^ mix(n));
+        let low = mix(high ^ n);
+        uuid::Uuid::from_u64_pair(high, low).to_string()
+    }
+}
@@ -0,0 +1,277 @@
+#[cfg(feature = "eventing")]
+use crate::eventing::{ReplaySource, SubscribeFrom};
+#[cfg(feature = "eventing")]
+use crate::persist::{EventPosition, EventStreamReader};
+use crate::{
+    aggregate::Aggregate,
+    error::{DomainError, DomainResult as Result},
+    persist::{
+        AggregateIdLister, EventExclusion, EventRepository, SerializedEvent,
+        check_expected_versions,
+    },
+};
+use async_trait::async_trait;
+use chrono::Utc;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// 按 (聚合类型, 聚合 ID) 分组的事件流
+type Streams = HashMap<(String, String), Vec<SerializedEvent>>;
+
+/// 遵循乐观并发控制的内存事件仓储
+///
+/// 保存时要求同一聚合的事件版本从“当前版本 + 1”开始连续递增，
+/// 否则返回 `ErrorKind::Conflict`（整批拒绝，不产生部分写入）。
+///
+/// 被排除的事件仍保留在流中（`all_events` 可见），按聚合读取与回放时跳过；
+/// 不允许排除流中最新的事件，否则重建出的聚合版本落后于存储，后续保存将冲突。
+#[derive(Default, Clone)]
+pub struct InMemoryEventRepository {
+    inner: Arc<Mutex<Streams>>,
+    excluded: Arc<Mutex<HashMap<String, EventExclusion>>>,
+}
+
+impl InMemoryEventRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 全部聚合的事件总数
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn is_excluded(&self, event: &SerializedEvent) -> bool {
+        self.excluded.lock().unwrap().contains_key(event.event_id())
+    }
+
+    /// 全局事件流：全部聚合的事件（含已排除的事件）按发生时间排序
+    pub fn all_events(&self) -> Vec<SerializedEvent> {
+        let mut events: Vec<SerializedEvent> = self
+            .inner
+            .lock()
+            .unwrap()
+            .values()
+            .flatten()
+            .cloned()
+            .collect();
+        events.sort_by_key(SerializedEvent::occurred_at);
+        events
+    }
+}
+
+/// 以全局事件流作为回放数据源；未分配全局位点的事件以其在全局流中的序号（从 1 开始）近似
+#[cfg(feature = "eventing")]
+#[async_trait]
+impl ReplaySource for InMemoryEventRepository {
+    async fn replay(&self, from: SubscribeFrom) -> Result<Vec<SerializedEvent>> {
+        Ok(self
+            .all_events()
+            .into_iter()
+            .enumerate()
+            .filter(|(_, event)| !self.is_excluded(event))
+            .filter(|(index, event)| {
+                let position = event
+                    .sequence_number()
+                    .map_or(*index as u64 + 1, |s| s.max(0) as u64);
+                from.includes(position, event)
+            })
+            .map(|(_, event)| event)
+            .collect())
+    }
+}
+
+/// 按全局事件流分页读取（跳过已排除的事件）；未分配全局位点的事件以其在全局流中的序号（从 1 开始）
+/// 作为位点返回
+#[cfg(feature = "eventing")]
+#[async_trait]
+impl EventStreamReader for InMemoryEventRepository {
+    async fn read_page(&self, from_sequence: u64, limit: usize) -> Result<Vec<SerializedEvent>> {
+        Ok(self
+            .all_events()
+            .into_iter()
+            .enumerate()
+            .map(|(index, event)| match event.sequence_number() {
+                Some(_) => event,
+                None => event.with_position(EventPosition::global(index as i64 + 1)),
+            })
+            .filter(|event| {
+                !self.is_excluded(event)
+                    && event.sequence_number().unwrap_or_default() >= from_sequence as i64
+            })
+            .take(limit)
+            .collect())
+    }
+}
+
+#[async_trait]
+impl EventRepository for InMemoryEventRepository {
+    async fn get_events<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<Vec<SerializedEvent>> {
+        let events = self
+            .inner
+            .lock()
+            .unwrap()
+            .get(&(A::TYPE.to_string(), aggregate_id.to_string()))
+            .cloned()
+            .unwrap_or_default();
+
+        Ok(events
+            .into_iter()
+            .filter(|e| !self.is_excluded(e))
+            .collect())
+    }
+
+    async fn get_last_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        last_version: usize,
+    ) -> Result<Vec<SerializedEvent>> {
+        let events = self.get_events::<A>(aggregate_id).await?;
+
+        Ok(events
+            .into_iter()
+            .filter(|e| e.aggregate_version() > last_version)
+            .collect())
+    }
+
+    async fn save(&self, events: Vec<SerializedEvent>) -> Result<()> {
+        let mut inner = self.inner.lock().unwrap();
+
+        // 先整体校验，再写入，保证批次原子性
+        let mut next: HashMap<(String, String), usize> = HashMap::new();
+        for e in &events {
+            let key = (e.aggregate_type().to_string(), e.aggregate_id().to_string());
+            let expected = *next.entry(key.clone()).or_insert_with(|| {
+                inner
+                    .get(&key)
+                    .and_then(|s| s.last())
+                    .map_or(0, SerializedEvent::aggregate_version)
+                    + 1
+            });
+
+            if e.aggregate_version() != expected {
+                return Err(DomainError::conflict(expected, e.aggregate_version()));
+            }
+
+            next.insert(key, expected + 1);
+        }
+
+        for e in events {
+            let key = (e.aggregate_type().to_string(), e.aggregate_id().to_string());
+            inner.entry(key).or_default().push(e);
+        }
+
+        Ok(())
+    }
+
+    /// 在同一把锁内校验版本并写入
+    async fn save_with_expected_version<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        events: Vec<SerializedEvent>,
+        expected_version: usize,
+    ) -> Result<()> {
+        check_expected_versions::<A>(aggregate_id, &events, expected_version)?;
+        let mut inner = self.inner.lock().unwrap();
+        let stream = inner
+            .entry((A::TYPE.to_string(), aggregate_id.to_string()))
+            .or_default();
+        let current = stream.last().map_or(0, SerializedEvent::aggregate_version);
+        if current != expected_version {
+            return Err(DomainError::conflict(expected_version, current));
+        }
+        stream.extend(events);
+        Ok(())
+    }
+
+    async fn current_version<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<usize> {
+        Ok(self
+            .inner
+            .lock()
+            .unwrap()
+            .get(&(A::TYPE.to_string(), aggregate_id.to_string()))
+            .and_then(|s| s.last())
+            .map_or(0, SerializedEvent::aggregate_version))
+    }
+
+    async fn exists_many<A: Aggregate>(&self, aggregate_ids: &[A::Id]) -> Result<Vec<bool>> {
+        let inner = self.inner.lock().unwrap();
+        Ok(aggregate_ids
+            .iter()
+            .map(|id| {
+                inner
+                    .get(&(A::TYPE.to_string(), id.to_string()))
+                    .is_some_and(|s| !s.is_empty())
+            })
+            .collect())
+    }
+
+    async fn mark_excluded(&self, event_id: &str, reason: &str, actor: &str) -> Result<()> {
+        let inner = self.inner.lock().unwrap();
+        let (stream, event) = inner
+            .values()
+            .find_map(|s| s.iter().find(|e| e.event_id() == event_id).map(|e| (s, e)))
+            .ok_or_else(|| DomainError::not_found(format!("event {event_id} not found")))?;
+
+        if stream.last().map(SerializedEvent::event_id) == Some(event_id) {
+            return Err(DomainError::invalid_state(format!(
+                "event {event_id} is the latest in its stream; append a correcting event first"
+            ))
+            .with_code("EXCLUSION_OF_HEAD"));
+        }
+
+        let mut excluded = self.excluded.lock().unwrap();
+        if excluded.contains_key(event_id) {
+            return Err(DomainError::invalid_state(format!(
+                "event {event_id} is already excluded"
+            ))
+            .with_code("EVENT_ALREADY_EXCLUDED"));
+        }
+        excluded.insert(
+            event_id.to_string(),
+            EventExclusion {
+                event_id: event_id.to_string(),
+                aggregate_type: event.aggregate_type().to_string(),
+                aggregate_id: event.aggregate_id().to_string(),
+                reason: reason.to_string(),
+                actor: actor.to_string(),
+                excluded_at: Utc::now(),
+            },
+        );
+        Ok(())
+    }
+
+    async fn exclusions(&self) -> Result<Vec<EventExclusion>> {
+        let mut exclusions: Vec<EventExclusion> =
+            self.excluded.lock().unwrap().values().cloned().collect();
+        exclusions.sort_by_key(|e| e.excluded_at);
+        Ok(exclusions)
+    }
+}
+
+#[async_trait]
+impl AggregateIdLister for InMemoryEventRepository {
+    async fn list_aggregate_ids<A: Aggregate>(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>> {
+        let mut ids: Vec<String> = self
+            .inner
+            .lock()
+            .unwrap()
+            .keys()
+            .filter(|(aggregate_type, id)| {
+                aggregate_type == A::TYPE && after.is_none_or(|after| id.as_str() > after)
+            })
+            .map(|(_, id)| id.clone())
+            .collect();
+        ids.sort_unstable();
+        ids.truncate(limit);
+        Ok(ids)
+    }
+}
@@ -0,0 +1,306 @@
+#[cfg(feature = "eventing")]
+use crate::eventing::{EventBus, OrderingGuarantee, SubscribeOptions};
+use crate::{
+    aggregate::Aggregate,
+    error::{DomainError, DomainResult as Result, ErrorKind},
+    persist::{AggregateIdLister, EventExclusion, EventRepository, SerializedEvent},
+};
+use async_trait::async_trait;
+#[cfg(feature = "eventing")]
+use futures_core::stream::BoxStream;
+use std::collections::BTreeSet;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+/// 故障计划：按调用序号（从 1 开始）决定哪些调用失败，并可为每次调用注入延迟
+///
+/// 多条规则同时生效，任一命中即失败：
+/// - `fail_first(n)`：前 n 次调用失败；
+/// - `fail_every(k)`：第 k、2k、3k… 次调用失败；
+/// - `fail_on(calls)`：指定序号的调用失败。
+#[derive(Debug, Clone)]
+pub struct FaultSchedule {
+    fail_first: u64,
+    fail_every: Option<u64>,
+    fail_on: BTreeSet<u64>,
+    latency: Option<Duration>,
+    kind: ErrorKind,
+}
+
+impl Default for FaultSchedule {
+    fn default() -> Self {
+        Self {
+            fail_first: 0,
+            fail_every: None,
+            fail_on: BTreeSet::new(),
+            latency: None,
+            kind: ErrorKind::Internal,
+        }
+    }
+}
+
+impl FaultSchedule {
+    /// 不注入任何故障
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn fail_first(mut self, n: u64) -> Self {
+        self.fail_first = n;
+        self
+    }
+
+    pub fn fail_every(mut self, k: u64) -> Self {
+        self.fail_every = (k > 0).then_some(k);
+        self
+    }
+
+    pub fn fail_on(mut self, calls: impl IntoIterator<Item = u64>) -> Self {
+        self.fail_on.extend(calls);
+        self
+    }
+
+    /// 每次调用（无论成败）前等待的时长
+    pub fn with_latency(mut self, latency: Duration) -> Self {
+        self.latency = Some(latency);
+        self
+    }
+
+    /// 注入错误的类别，默认为 `ErrorKind::Internal`；设为 `Conflict` 可触发重试路径
+    pub fn with_error_kind(mut self, kind: ErrorKind) -> Self {
+        self.kind = kind;
+        self
+    }
+
+    /// 第 `call` 次调用是否失败
+    pub fn fails(&self, call: u64) -> bool {
+        call <= self.fail_first
+            || self.fail_every.is_some_and(|k| call.is_multiple_of(k))
+            || self.fail_on.contains(&call)
+    }
+}
+
+/// 按计划注入故障并统计调用次数
+#[derive(Debug, Default)]
+struct FaultInjector {
+    schedule: Mutex<FaultSchedule>,
+    calls: AtomicU64,
+    failures: AtomicU64,
+}
+
+impl FaultInjector {
+    fn new(schedule: FaultSchedule) -> Self {
+        Self {
+            schedule: Mutex::new(schedule),
+            ..Default::default()
+        }
+    }
+
+    async fn check(&self, operation: &str) -> Result<()> {
+        let call = self.calls.fetch_add(1, Ordering::SeqCst) + 1;
+        let (latency, fails, kind) = {
+            let schedule = self.schedule.lock().unwrap();
+            (schedule.latency, schedule.fails(call), schedule.kind)
+        };
+
+        if let Some(latency) = latency {
+            tokio::time::sleep(latency).await;
+        }
+        if !fails {
+            return Ok(());
+        }
+
+        self.failures.fetch_add(1, Ordering::SeqCst);
+        Err(
+            DomainError::new(kind, format!("injected fault: {operation} call {call}"))
+                .with_code("INJECTED_FAULT"),
+        )
+    }
+
+    fn set_schedule(&self, schedule: FaultSchedule) {
+        *self.schedule.lock().unwrap() = schedule;
+    }
+
+    fn calls(&self) -> u64 {
+        self.calls.load(Ordering::SeqCst)
+    }
+
+    fn failures(&self) -> u64 {
+        self.failures.load(Ordering::SeqCst)
+    }
+}
+
+/// 按计划使发布失败的事件总线包装
+///
+/// 每次 `publish`/`publish_batch` 计为一次调用，失败时事件不会转发给内部总线；
+/// 订阅直接转发。
+#[cfg(feature = "eventing")]
+pub struct FlakyBus<B> {
+    inner: Arc<B>,
+    publish: FaultInjector,
+}
+
+#[cfg(feature = "eventing")]
+impl<B: EventBus> FlakyBus<B> {
+    pub fn new(inner: Arc<B>, schedule: FaultSchedule) -> Self {
+        Self {
+            inner,
+            publish: FaultInjector::new(schedule),
+        }
+    }
+
+    pub fn inner(&self) -> &Arc<B> {
+        &self.inner
+    }
+
+    /// 替换故障计划（调用计数不清零）
+    pub fn set_schedule(&self, schedule: FaultSchedule) {
+        self.publish.set_schedule(schedule);
+    }
+
+    /// 发布调用次数（含失败）
+    pub fn publish_calls(&self) -> u64 {
+        self.publish.calls()
+    }
+
+    pub fn injected_failures(&self) -> u64 {
+        self.publish.failures()
+    }
+}
+
+#[cfg(feature = "eventing")]
+#[async_trait]
+impl<B: EventBus> EventBus for FlakyBus<B> {
+    async fn publish(&self, event: &SerializedEvent) -> Result<()> {
+        self.publish.check("publish").await?;
+        self.inner.publish(event).await
+    }
+
+    async fn publish_batch(&self, events: &[SerializedEvent]) -> Result<()> {
+        self.publish.check("publish").await?;
+        self.inner.publish_batch(events).await
+    }
+
+    fn ordering(&self) -> OrderingGuarantee {
+        self.inner.ordering()
+    }
+
+    async fn subscribe(&self) -> BoxStream<'static, Result<SerializedEvent>> {
+        self.inner.subscribe().await
+    }
+
+    async fn subscribe_with(
+        &self,
+        options: SubscribeOptions,
+    ) -> Result<BoxStream<'static, Result<SerializedEvent>>> {
+        self.inner.subscribe_with(options).await
+    }
+}
+
+/// 按计划使保存（及可选的读取）失败的事件仓储包装
+///
+/// 失败的保存不会写入内部仓储；读取故障默认关闭，由 `with_read_faults` 开启。
+pub struct FlakyRepository<E> {
+    inner: Arc<E>,
+    saves: FaultInjector,
+    reads: FaultInjector,
+}
+
+impl<E: EventRepository> FlakyRepository<E> {
+    pub fn new(inner: Arc<E>, schedule: FaultSchedule) -> Self {
+        Self {
+            inner,
+            saves: FaultInjector::new(schedule),
+            reads: FaultInjector::new(FaultSchedule::none()),
+        }
+    }
+
+    /// 为 `get_events`/`get_last_events` 设置故障计划
+    pub fn with_read_faults(self, schedule: FaultSchedule) -> Self {
+        self.reads.set_schedule(schedule);
+        self
+    }
+
+    pub fn inner(&self) -> &Arc<E> {
+        &self.inner
+    }
+
+    /// 替换保存的故障计划（调用计数不清零）
+    pub fn set_schedule(&self, schedule: FaultSchedule) {
+        self.saves.set_schedule(schedule);
+    }
+
+    pub fn save_calls(&self) -> u64 {
+        self.saves.calls()
+    }
+
+    pub fn read_calls(&self) -> u64 {
+        self.reads.calls()
+    }
+
+    /// 注入的保存与读取失败总数
+    pub fn injected_failures(&self) -> u64 {
+        self.saves.failures() + self.reads.failures()
+    }
+}
+
+#[async_trait]
+impl<E: EventRepository> EventRepository for FlakyRepository<E> {
+    async fn get_events<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<Vec<SerializedEvent>> {
+        self.reads.check("get_events").await?;
+        self.inner.get_events::<A>(aggregate_id).await
+    }
+
+    async fn get_last_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        last_version: usize,
+    ) -> Result<Vec<SerializedEvent>> {
+        self.reads.check("get_last_events").await?;
+        self.inner
+            .get_last_events::<A>(aggregate_id, last_version)
+            .await
+    }
+
+    async fn save(&self, events: Vec<SerializedEvent>) -> Result<()> {
+        self.saves.check("save").await?;
+        self.inner.save(events).await
+    }
+
+    async fn ping(&self) -> Result<()> {
+        self.reads.check("ping").await?;
+        self.inner.ping().await
+    }
+
+    async fn current_version<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<usize> {
+        self.inner.current_version::<A>(aggregate_id).await
+    }
+
+    async fn exists<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<bool> {
+        self.inner.exists::<A>(aggregate_id).await
+    }
+
+    async fn exists_many<A: Aggregate>(&self, aggregate_ids: &[A::Id]) -> Result<Vec<bool>> {
+        self.inner.exists_many::<A>(aggregate_ids).await
+    }
+
+    async fn mark_excluded(&self, event_id: &str, reason: &str, actor: &str) -> Result<()> {
+        self.inner.mark_excluded(event_id, reason, actor).await
+    }
+
+    async fn exclusions(&self) -> Result<Vec<EventExclusion>> {
+        self.inner.exclusions().await
+    }
+}
+
+#[async_trait]
+impl<E: EventRepository + AggregateIdLister> AggregateIdLister for FlakyRepository<E> {
+    async fn list_aggregate_ids<A: Aggregate>(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>> {
+        self.inner.list_aggregate_ids::<A>(after, limit).await
+    }
+}
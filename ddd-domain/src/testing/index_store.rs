@@ -0,0 +1,106 @@
+use crate::{
+    error::{DomainError, DomainResult as Result, ErrorKind},
+    persist::AggregateIndexStore,
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::Mutex;
+
+/// (聚合类型, 索引名, 业务键或聚合 ID)
+type Slot = (String, String, String);
+
+#[derive(Default)]
+struct Indexes {
+    by_key: HashMap<Slot, String>,
+    by_aggregate: HashMap<Slot, String>,
+}
+
+/// 内存聚合索引存储，业务键在同一聚合类型的同一索引内唯一
+#[derive(Default)]
+pub struct InMemoryAggregateIndexStore {
+    inner: Mutex<Indexes>,
+}
+
+impl InMemoryAggregateIndexStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 已索引的业务键总数
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().by_key.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+fn slot(aggregate_type: &str, index: &str, value: &str) -> Slot {
+    (
+        aggregate_type.to_string(),
+        index.to_string(),
+        value.to_string(),
+    )
+}
+
+#[async_trait]
+impl AggregateIndexStore for InMemoryAggregateIndexStore {
+    async fn lookup(&self, aggregate_type: &str, index: &str, key: &str) -> Result<Option<String>> {
+        Ok(self
+            .inner
+            .lock()
+            .unwrap()
+            .by_key
+            .get(&slot(aggregate_type, index, key))
+            .cloned())
+    }
+
+    async fn assign(
+        &self,
+        aggregate_type: &str,
+        index: &str,
+        key: &str,
+        aggregate_id: &str,
+    ) -> Result<Option<String>> {
+        let mut inner = self.inner.lock().unwrap();
+
+        if let Some(owner) = inner.by_key.get(&slot(aggregate_type, index, key))
+            && owner != aggregate_id
+        {
+            return Err(DomainError::new(
+                ErrorKind::Conflict,
+                format!("{aggregate_type} index {index}: key {key} is taken by {owner}"),
+            )
+            .with_code("INDEX_KEY_TAKEN"));
+        }
+
+        let previous = inner
+            .by_aggregate
+            .insert(slot(aggregate_type, index, aggregate_id), key.to_string());
+        if let Some(old) = &previous {
+            inner.by_key.remove(&slot(aggregate_type, index, old));
+        }
+        inner
+            .by_key
+            .insert(slot(aggregate_type, index, key), aggregate_id.to_string());
+        Ok(previous)
+    }
+
+    async fn release(
+        &self,
+        aggregate_type: &str,
+        index: &str,
+        aggregate_id: &str,
+    ) -> Result<Option<String>> {
+        let mut inner = self.inner.lock().unwrap();
+
+        let previous = inner
+            .by_aggregate
+            .remove(&slot(aggregate_type, index, aggregate_id));
+        if let Some(old) = &previous {
+            inner.by_key.remove(&slot(aggregate_type, index, old));
+        }
+        Ok(previous)
+    }
+}
@@ -0,0 +1,42 @@
+//! 测试工具（testing）
+//!
+//! 面向库使用者的测试构件，需启用 `testing` 特性：
+//! - `InMemoryEventRepository`：遵循乐观并发控制的内存事件仓储；
+//! - `InMemorySnapshotRepository`：保留历史版本的内存快照仓储；
+//! - `InMemoryOutboxRepository`（需启用 `eventing`）：事件流写入与 Outbox 入队同时生效的事务性 Outbox 仓储；
+//! - `InMemoryAggregateIndexStore`：业务键唯一的内存聚合索引存储；
+//! - `ConcurrencyTestKit`：对单个聚合并发执行命令组合，并校验最终事件流
+//!   是否为命令的可线性化结果（无丢失更新、版本连续），用于验证自定义仓储实现；
+//! - `FlakyBus`/`FlakyRepository`：按 `FaultSchedule`（前 N 次失败、每第 k 次失败、注入延迟）
+//!   确定性地注入发布与保存故障，用于测试事件引擎、重试与回收器的容错路径；
+//! - `DeterministicEventIdGenerator`：基于种子的确定性事件 ID，使录制的事件流可复现。
+//! - `EventContractRegistry`：加载消费者提交的 JSON 期望文件（事件类型 + 依赖的 JSON Schema 子集），
+//!   以当前事件序列化结果校验全部期望，生产者变更破坏消费者时测试失败。
+//! - `PgTestTx`（需同时启用 `infra-sqlx`）：在单个事务内建好事件表与发件箱表并预置数据，
+//!   丢弃即回滚，配合 `#[sqlx::test]` 编写不留残余的 Postgres 集成测试。
+//!
+mod concurrency;
+mod contract;
+mod event_id;
+mod event_repository;
+mod flaky;
+mod index_store;
+#[cfg(feature = "eventing")]
+mod outbox;
+#[cfg(feature = "infra-sqlx")]
+mod pg;
+mod snapshot_repository;
+
+pub use concurrency::{ConcurrencyTestKit, LinearizabilityReport};
+pub use contract::{ContractReport, ContractViolation, EventContractRegistry, EventExpectation};
+pub use event_id::DeterministicEventIdGenerator;
+pub use event_repository::InMemoryEventRepository;
+#[cfg(feature = "eventing")]
+pub use flaky::FlakyBus;
+pub use flaky::{FaultSchedule, FlakyRepository};
+pub use index_store::InMemoryAggregateIndexStore;
+#[cfg(feature = "eventing")]
+pub use outbox::InMemoryOutboxRepository;
+#[cfg(feature = "infra-sqlx")]
+pub use pg::{EVENTS_TABLE, OUTBOX_TABLE, PgTestTx};
+pub use snapshot_repository::InMemorySnapshotRepository;
@@ -0,0 +1,172 @@
+use crate::{
+    aggregate::Aggregate,
+    error::DomainResult as Result,
+    eventing::OutboxRepository,
+    persist::{AggregateIdLister, EventExclusion, EventRepository, SerializedEvent},
+    testing::InMemoryEventRepository,
+};
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+
+#[derive(Clone, Debug)]
+struct OutboxEntry {
+    event: SerializedEvent,
+    published: bool,
+    attempts: u32,
+    last_error: Option<String>,
+}
+
+/// 遵循事务性 Outbox 契约的内存仓储
+///
+/// 事件写入 `InMemoryEventRepository`（乐观并发校验失败时整批拒绝）成功后，
+/// 同一批事件即入队，二者要么都生效、要么都不生效。
+#[derive(Default, Clone)]
+pub struct InMemoryOutboxRepository {
+    events: InMemoryEventRepository,
+    outbox: Arc<Mutex<Vec<OutboxEntry>>>,
+}
+
+impl InMemoryOutboxRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 事件流所在的内存仓储
+    pub fn events(&self) -> &InMemoryEventRepository {
+        &self.events
+    }
+
+    /// 尚未发布的事件数
+    pub fn pending_count(&self) -> usize {
+        self.outbox
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| !e.published)
+            .count()
+    }
+
+    /// 事件的发布失败次数与最近一次失败原因
+    pub fn failures(&self, event_id: &str) -> Option<(u32, Option<String>)> {
+        self.outbox
+            .lock()
+            .unwrap()
+            .iter()
+            .find(|e| e.event.event_id() == event_id)
+            .map(|e| (e.attempts, e.last_error.clone()))
+    }
+
+    fn push(&self, events: &[SerializedEvent]) {
+        self.outbox
+            .lock()
+            .unwrap()
+            .extend(events.iter().map(|event| OutboxEntry {
+                event: event.clone(),
+                published: false,
+                attempts: 0,
+                last_error: None,
+            }));
+    }
+}
+
+#[async_trait]
+impl EventRepository for InMemoryOutboxRepository {
+    async fn get_events<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<Vec<SerializedEvent>> {
+        self.events.get_events::<A>(aggregate_id).await
+    }
+
+    async fn get_last_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        last_version: usize,
+    ) -> Result<Vec<SerializedEvent>> {
+        self.events
+            .get_last_events::<A>(aggregate_id, last_version)
+            .await
+    }
+
+    async fn save(&self, events: Vec<SerializedEvent>) -> Result<()> {
+        self.events.save(events.clone()).await?;
+        self.push(&events);
+        Ok(())
+    }
+
+    async fn save_with_expected_version<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        events: Vec<SerializedEvent>,
+        expected_version: usize,
+    ) -> Result<()> {
+        self.events
+            .save_with_expected_version::<A>(aggregate_id, events.clone(), expected_version)
+            .await?;
+        self.push(&events);
+        Ok(())
+    }
+
+    async fn current_version<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<usize> {
+        self.events.current_version::<A>(aggregate_id).await
+    }
+
+    async fn exists_many<A: Aggregate>(&self, aggregate_ids: &[A::Id]) -> Result<Vec<bool>> {
+        self.events.exists_many::<A>(aggregate_ids).await
+    }
+
+    async fn mark_excluded(&self, event_id: &str, reason: &str, actor: &str) -> Result<()> {
+        self.events.mark_excluded(event_id, reason, actor).await
+    }
+
+    async fn exclusions(&self) -> Result<Vec<EventExclusion>> {
+        self.events.exclusions().await
+    }
+}
+
+#[async_trait]
+impl AggregateIdLister for InMemoryOutboxRepository {
+    async fn list_aggregate_ids<A: Aggregate>(
+        &self,
+        after: Option<&str>,
+        limit: usize,
+    ) -> Result<Vec<String>> {
+        self.events.list_aggregate_ids::<A>(after, limit).await
+    }
+}
+
+#[async_trait]
+impl OutboxRepository for InMemoryOutboxRepository {
+    async fn enqueue(&self, events: &[SerializedEvent]) -> Result<()> {
+        self.push(events);
+        Ok(())
+    }
+
+    async fn fetch_pending(&self, limit: usize) -> Result<Vec<SerializedEvent>> {
+        Ok(self
+            .outbox
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| !e.published)
+            .take(limit)
+            .map(|e| e.event.clone())
+            .collect())
+    }
+
+    async fn mark_published(&self, event_ids: &[&str]) -> Result<()> {
+        for entry in self.outbox.lock().unwrap().iter_mut() {
+            if event_ids.contains(&entry.event.event_id()) {
+                entry.published = true;
+            }
+        }
+        Ok(())
+    }
+
+    async fn mark_failed(&self, event_ids: &[&str], reason: &str) -> Result<()> {
+        for entry in self.outbox.lock().unwrap().iter_mut() {
+            if !entry.published && event_ids.contains(&entry.event.event_id()) {
+                entry.attempts += 1;
+                entry.last_error = Some(reason.to_string());
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,140 @@
+use crate::{
+    error::{DomainError, DomainResult as Result},
+    persist::{EVENT_STORE_MIGRATION, SerializedEvent},
+};
+use sqlx::types::Json;
+use sqlx::{PgConnection, PgPool, Postgres, Transaction};
+
+/// 事件表名
+pub const EVENTS_TABLE: &str = "ddd_events";
+/// 发件箱表名
+pub const OUTBOX_TABLE: &str = "ddd_outbox";
+
+const CREATE_OUTBOX_TABLE: &str = "CREATE TABLE IF NOT EXISTS ddd_outbox (
+    id BIGSERIAL PRIMARY KEY,
+    event_id TEXT NOT NULL UNIQUE,
+    body JSONB NOT NULL,
+    published_at TIMESTAMPTZ
+)";
+
+/// 在单个事务内运行的 Postgres 测试夹具
+///
+/// - `begin` 开启事务并在其中建好事件表（与 `PgEventRepository` 同一表结构）与发件箱表；
+/// - 被测适配器通过 `conn()` 复用同一连接，所有写入只在事务内可见；
+/// - 夹具被丢弃（或显式 `rollback`）时事务回滚，数据库不留残余。
+///
+/// 可与 `#[sqlx::test]` 配合使用：后者为每个测试提供独立的 `PgPool`。
+pub struct PgTestTx {
+    tx: Transaction<'static, Postgres>,
+}
+
+impl PgTestTx {
+    pub async fn begin(pool: &PgPool) -> Result<Self> {
+        let mut tx = pool.begin().await?;
+        sqlx::raw_sql(EVENT_STORE_MIGRATION)
+            .execute(&mut *tx)
+            .await?;
+        sqlx::query(CREATE_OUTBOX_TABLE).execute(&mut *tx).await?;
+        Ok(Self { tx })
+    }
+
+    /// 事务内的连接，供被测适配器执行语句
+    pub fn conn(&mut self) -> &mut PgConnection {
+        &mut self.tx
+    }
+
+    /// 预置事件（按聚合版本唯一，冲突时报错）
+    pub async fn insert_events(&mut self, events: &[SerializedEvent]) -> Result<()> {
+        for event in events {
+            sqlx::query(
+                "INSERT INTO ddd_events
+                    (event_id, event_type, aggregate_type, aggregate_id, aggregate_version, occurred_at, body)
+                 VALUES ($1, $2, $3, $4, $5, $6, $7)",
+            )
+            .bind(event.event_id())
+            .bind(event.event_type())
+            .bind(event.aggregate_type())
+            .bind(event.aggregate_id())
+            .bind(event.aggregate_version() as i64)
+            .bind(event.occurred_at())
+            .bind(Json(event))
+            .execute(&mut *self.tx)
+            .await?;
+        }
+        Ok(())
+    }
+
+    /// 按聚合版本顺序读取事件
+    pub async fn events(
+        &mut self,
+        aggregate_type: &str,
+        aggregate_id: &str,
+    ) -> Result<Vec<SerializedEvent>> {
+        let rows: Vec<Json<SerializedEvent>> = sqlx::query_scalar(
+            "SELECT body FROM ddd_events
+             WHERE aggregate_type = $1 AND aggregate_id = $2
+             ORDER BY aggregate_version",
+        )
+        .bind(aggregate_type)
+        .bind(aggregate_id)
+        .fetch_all(&mut *self.tx)
+        .await?;
+        Ok(rows.into_iter().map(|Json(event)| event).collect())
+    }
+
+    /// 将事件写入发件箱（待发布）
+    pub async fn enqueue_outbox(&mut self, events: &[SerializedEvent]) -> Result<()> {
+        for event in events {
+            sqlx::query("INSERT INTO ddd_outbox (event_id, body) VALUES ($1, $2)")
+                .bind(event.event_id())
+                .bind(Json(event))
+                .execute(&mut *self.tx)
+                .await?;
+        }
+        Ok(())
+    }
+
+    /// 按入箱顺序读取尚未发布的发件箱事件
+    pub async fn pending_outbox(&mut self) -> Result<Vec<SerializedEvent>> {
+        let rows: Vec<Json<SerializedEvent>> = sqlx::query_scalar(
+            "SELECT body FROM ddd_outbox WHERE published_at IS NULL ORDER BY id",
+        )
+        .fetch_all(&mut *self.tx)
+        .await?;
+        Ok(rows.into_iter().map(|Json(event)| event).collect())
+    }
+
+    /// 标记发件箱事件已发布，事件不存在或已发布时返回 `NotFound`
+    pub async fn mark_published(&mut self, event_id: &str) -> Result<()> {
+        let done = sqlx::query(
+            "UPDATE ddd_outbox SET published_at = now()
+             WHERE event_id = $1 AND published_at IS NULL",
+        )
+        .bind(event_id)
+        .execute(&mut *self.tx)
+        .await?;
+        if done.rows_affected() == 0 {
+            return Err(DomainError::not_found(format!(
+                "pending outbox event not found: {event_id}"
+            )));
+        }
+        Ok(())
+    }
+
+    /// 表中的行数（`EVENTS_TABLE`/`OUTBOX_TABLE`）
+    pub async fn count(&mut self, table: &str) -> Result<i64> {
+        if table != EVENTS_TABLE && table != OUTBOX_TABLE {
+            return Err(DomainError::invalid_value(format!(
+                "unknown fixture table: {table}"
+            )));
+        }
+        Ok(sqlx::query_scalar(&format!("SELECT COUNT(*) FROM {table}"))
+            .fetch_one(&mut *self.tx)
+            .await?)
+    }
+
+    /// 显式回滚（丢弃夹具时同样回滚）
+    pub async fn rollback(self) -> Result<()> {
+        self.tx.rollback().await.map_err(DomainError::from)
+    }
+}
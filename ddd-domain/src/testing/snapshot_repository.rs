@@ -0,0 +1,126 @@
+use crate::{
+    aggregate::Aggregate,
+    error::DomainResult as Result,
+    persist::{SerializedSnapshot, SnapshotHistory, SnapshotRepository},
+};
+use async_trait::async_trait;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// 按 (聚合类型, 聚合 ID) 分组的快照历史（按版本升序）
+type Snapshots = HashMap<(String, String), Vec<SerializedSnapshot>>;
+
+/// 内存快照仓储：保留全部历史快照
+#[derive(Default, Clone)]
+pub struct InMemorySnapshotRepository {
+    inner: Arc<Mutex<Snapshots>>,
+}
+
+impl InMemorySnapshotRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// 全部聚合的快照总数
+    pub fn len(&self) -> usize {
+        self.inner.lock().unwrap().values().map(Vec::len).sum()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// 全部快照，按 (聚合类型, 聚合 ID, 版本) 排序（用于导出）
+    pub fn all_snapshots(&self) -> Vec<SerializedSnapshot> {
+        let mut snapshots: Vec<SerializedSnapshot> = self
+            .inner
+            .lock()
+            .unwrap()
+            .values()
+            .flatten()
+            .cloned()
+            .collect();
+        snapshots.sort_by(|a, b| {
+            (a.aggregate_type(), a.aggregate_id(), a.aggregate_version()).cmp(&(
+                b.aggregate_type(),
+                b.aggregate_id(),
+                b.aggregate_version(),
+            ))
+        });
+        snapshots
+    }
+
+    fn insert(inner: &mut Snapshots, snapshot: SerializedSnapshot) {
+        let key = (
+            snapshot.aggregate_type().to_string(),
+            snapshot.aggregate_id().to_string(),
+        );
+        let snapshots = inner.entry(key).or_default();
+        snapshots.retain(|s| s.aggregate_version() != snapshot.aggregate_version());
+        snapshots.push(snapshot);
+        snapshots.sort_by_key(SerializedSnapshot::aggregate_version);
+    }
+}
+
+#[async_trait]
+impl SnapshotRepository for InMemorySnapshotRepository {
+    /// `version` 为空时返回最新快照，否则返回版本不超过 `version` 的最新快照
+    async fn get_snapshot<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        version: Option<usize>,
+    ) -> Result<Option<SerializedSnapshot>> {
+        let inner = self.inner.lock().unwrap();
+        let Some(snapshots) = inner.get(&(A::TYPE.to_string(), aggregate_id.to_string())) else {
+            return Ok(None);
+        };
+
+        Ok(snapshots
+            .iter()
+            .rev()
+            .find(|s| version.is_none_or(|v| s.aggregate_version() <= v))
+            .cloned())
+    }
+
+    async fn save_serialized(&self, snapshot: SerializedSnapshot) -> Result<()> {
+        Self::insert(&mut self.inner.lock().unwrap(), snapshot);
+        Ok(())
+    }
+
+    async fn bulk_load(&self, snapshots: Vec<SerializedSnapshot>) -> Result<usize> {
+        let count = snapshots.len();
+        let mut inner = self.inner.lock().unwrap();
+        for snapshot in snapshots {
+            Self::insert(&mut inner, snapshot);
+        }
+        Ok(count)
+    }
+}
+
+#[async_trait]
+impl SnapshotHistory for InMemorySnapshotRepository {
+    async fn snapshot_versions<A: Aggregate>(&self, aggregate_id: &A::Id) -> Result<Vec<usize>> {
+        Ok(self
+            .inner
+            .lock()
+            .unwrap()
+            .get(&(A::TYPE.to_string(), aggregate_id.to_string()))
+            .map(|snapshots| snapshots.iter().map(|s| s.aggregate_version()).collect())
+            .unwrap_or_default())
+    }
+
+    async fn delete_snapshots<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        versions: &[usize],
+    ) -> Result<usize> {
+        let mut inner = self.inner.lock().unwrap();
+        let Some(snapshots) = inner.get_mut(&(A::TYPE.to_string(), aggregate_id.to_string()))
+        else {
+            return Ok(0);
+        };
+        let before = snapshots.len();
+        snapshots.retain(|s| !versions.contains(&s.aggregate_version()));
+        Ok(before - snapshots.len())
+    }
+}
@@ -192,3 +192,41 @@ async fn aggregate_persist_and_load_flow() -> AnyResult<()> {
     assert_eq!(loaded2.version(), Version::from_value(3));
     Ok(())
 }
+
+#[tokio::test]
+async fn multiple_commands_commit_atomically() -> AnyResult<()> {
+    let event_repo = Arc::new(InMemoryEventRepository::default());
+    let upcasters = Arc::new(ddd_domain::event_upcaster::EventUpcasterChain::default());
+    let repo = Arc::new(EventSourcedRepo::new(event_repo.clone(), upcasters));
+    let root = AggregateRoot::<BankAccount, _>::new(repo.clone());
+    let id = "acc-2".to_string();
+
+    // 后续命令基于前序命令应用后的状态执行：存入后才有余额可取
+    let envelopes = root
+        .execute(
+            &id,
+            vec![
+                Cmd::Deposit { amount: 100 },
+                Cmd::Withdraw { amount: 60 },
+                Cmd::Withdraw { amount: 40 },
+            ],
+            EventContext::default(),
+        )
+        .await?;
+    assert_eq!(envelopes.len(), 3);
+
+    // 任一命令失败：前序命令产生的事件也不保存
+    let err = root
+        .execute(
+            &id,
+            vec![Cmd::Deposit { amount: 50 }, Cmd::Withdraw { amount: 80 }],
+            EventContext::default(),
+        )
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ddd_domain::error::ErrorKind::InvalidState);
+    assert_eq!(event_repo.get_events::<BankAccount>(&id).await?.len(), 3);
+    let loaded: BankAccount = repo.load(&id).await?.unwrap();
+    assert_eq!(loaded.balance, 0);
+    Ok(())
+}
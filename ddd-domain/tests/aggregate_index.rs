@@ -0,0 +1,210 @@
+#![cfg(feature = "testing")]
+use anyhow::Result as AnyResult;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::aggregate_root::AggregateRoot;
+use ddd_domain::domain_event::EventContext;
+use ddd_domain::entity::Entity;
+use ddd_domain::error::{DomainError, ErrorKind};
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::persist::{
+    AggregateIndexStoreExt, AggregateIndexes, EventRepository, EventSourcedRepo, IndexedEventRepo,
+};
+use ddd_domain::testing::{InMemoryAggregateIndexStore, InMemoryEventRepository};
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct User {
+    email: String,
+    deleted: bool,
+}
+
+#[derive(Debug)]
+enum Cmd {
+    Register { email: String },
+    ChangeEmail { email: String },
+    Delete,
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Evt {
+    #[event(event_type = "user.registered")]
+    Registered { email: String },
+    #[event(event_type = "user.email_changed")]
+    EmailChanged { email: String },
+    #[event(event_type = "user.deleted")]
+    Deleted {},
+}
+
+impl Aggregate for User {
+    const TYPE: &'static str = "user";
+    type Command = Cmd;
+    type Event = Evt;
+    type Error = DomainError;
+
+    fn execute(&self, command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        let id = ulid::Ulid::new().to_string();
+        let aggregate_version = self.version().next();
+
+        Ok(vec![match command {
+            Cmd::Register { email } => Evt::Registered {
+                id,
+                aggregate_version,
+                email,
+            },
+            Cmd::ChangeEmail { email } => Evt::EmailChanged {
+                id,
+                aggregate_version,
+                email,
+            },
+            Cmd::Delete => Evt::Deleted {
+                id,
+                aggregate_version,
+            },
+        }])
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        match event {
+            Evt::Registered {
+                aggregate_version,
+                email,
+                ..
+            }
+            | Evt::EmailChanged {
+                aggregate_version,
+                email,
+                ..
+            } => {
+                self.email = email.clone();
+                self.version = *aggregate_version;
+            }
+            Evt::Deleted {
+                aggregate_version, ..
+            } => {
+                self.deleted = true;
+                self.version = *aggregate_version;
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn maintains_unique_business_key_index_from_events() -> AnyResult<()> {
+    let events = Arc::new(InMemoryEventRepository::new());
+    let store = Arc::new(InMemoryAggregateIndexStore::new());
+    let indexes = AggregateIndexes::new()
+        .field("email", "user.registered", "/Registered/email")
+        .field("email", "user.email_changed", "/EmailChanged/email")
+        .release_on("email", "user.deleted");
+    let indexed = Arc::new(IndexedEventRepo::new(
+        events.clone(),
+        store.clone(),
+        indexes,
+    ));
+    let root = AggregateRoot::<User, _>::new(EventSourcedRepo::new(
+        indexed,
+        Arc::new(EventUpcasterChain::default()),
+    ));
+    let run = |id: &str, cmd: Cmd| {
+        let (root, id) = (&root, id.to_string());
+        async move { root.execute(&id, vec![cmd], EventContext::default()).await }
+    };
+
+    run(
+        "u-1",
+        Cmd::Register {
+            email: "a@x.io".into(),
+        },
+    )
+    .await?;
+    assert_eq!(
+        store.find::<User>("email", "a@x.io").await?,
+        Some("u-1".to_string())
+    );
+
+    // 键被其他聚合占用时拒绝，且不写入事件
+    let err = run(
+        "u-2",
+        Cmd::Register {
+            email: "a@x.io".into(),
+        },
+    )
+    .await
+    .unwrap_err();
+    assert!(err.matches(ErrorKind::Conflict, "INDEX_KEY_TAKEN"));
+    assert!(!events.exists::<User>(&"u-2".to_string()).await?);
+
+    // 修改邮箱释放旧键
+    run(
+        "u-1",
+        Cmd::ChangeEmail {
+            email: "b@x.io".into(),
+        },
+    )
+    .await?;
+    assert_eq!(store.find::<User>("email", "a@x.io").await?, None);
+    assert_eq!(
+        store.find::<User>("email", "b@x.io").await?,
+        Some("u-1".to_string())
+    );
+    run(
+        "u-2",
+        Cmd::Register {
+            email: "a@x.io".into(),
+        },
+    )
+    .await?;
+
+    // 删除聚合释放键
+    run("u-1", Cmd::Delete).await?;
+    assert_eq!(store.find::<User>("email", "b@x.io").await?, None);
+    assert_eq!(store.len(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn rolls_back_index_changes_when_events_fail_to_save() -> AnyResult<()> {
+    let events = Arc::new(InMemoryEventRepository::new());
+    let store = Arc::new(InMemoryAggregateIndexStore::new());
+    let indexed = IndexedEventRepo::new(
+        events.clone(),
+        store.clone(),
+        AggregateIndexes::new().field("email", "user.registered", "/Registered/email"),
+    );
+    let root = AggregateRoot::<User, _>::new(EventSourcedRepo::new(
+        Arc::new(indexed),
+        Arc::new(EventUpcasterChain::default()),
+    ));
+    let id = "u-1".to_string();
+    root.execute(
+        &id,
+        vec![Cmd::Register {
+            email: "a@x.io".into(),
+        }],
+        EventContext::default(),
+    )
+    .await?;
+
+    // 复用版本 1 的事件触发版本冲突：新键分配被撤销，旧键恢复
+    let mut stale = events.get_events::<User>(&id).await?;
+    let mut event = serde_json::to_value(stale.remove(0))?;
+    event["event_id"] = "e-dup".into();
+    event["payload"]["Registered"]["email"] = "c@x.io".into();
+    let indexed = IndexedEventRepo::new(
+        events.clone(),
+        store.clone(),
+        AggregateIndexes::new().field("email", "user.registered", "/Registered/email"),
+    );
+    let err = indexed
+        .save(vec![serde_json::from_value(event)?])
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Conflict);
+    assert_eq!(store.find::<User>("email", "c@x.io").await?, None);
+    assert_eq!(store.find::<User>("email", "a@x.io").await?, Some(id));
+    Ok(())
+}
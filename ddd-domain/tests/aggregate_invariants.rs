@@ -0,0 +1,170 @@
+#![cfg(feature = "testing")]
+use anyhow::Result as AnyResult;
+use ddd_domain::aggregate::{
+    Aggregate, INVARIANT_VIOLATED, InvariantViolation, InvariantViolations,
+};
+use ddd_domain::aggregate_root::AggregateRoot;
+use ddd_domain::domain_event::EventContext;
+use ddd_domain::entity::Entity;
+use ddd_domain::error::{DomainError, ErrorKind};
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::persist::{EventRepository, EventSourcedRepo};
+use ddd_domain::testing::InMemoryEventRepository;
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Wallet {
+    balance: i64,
+    holds: i64,
+}
+
+#[derive(Debug)]
+enum Cmd {
+    Deposit(i64),
+    Withdraw(i64),
+    Hold(i64),
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Evt {
+    Deposited { amount: i64 },
+    Withdrawn { amount: i64 },
+    Held { amount: i64 },
+}
+
+impl Aggregate for Wallet {
+    const TYPE: &'static str = "wallet";
+    type Command = Cmd;
+    type Event = Evt;
+    type Error = DomainError;
+
+    /// 命令本身不做余额校验，交由不变量兜底
+    fn execute(&self, command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        let id = ulid::Ulid::new().to_string();
+        let aggregate_version = self.version().next();
+        Ok(vec![match command {
+            Cmd::Deposit(amount) => Evt::Deposited {
+                id,
+                aggregate_version,
+                amount,
+            },
+            Cmd::Withdraw(amount) => Evt::Withdrawn {
+                id,
+                aggregate_version,
+                amount,
+            },
+            Cmd::Hold(amount) => Evt::Held {
+                id,
+                aggregate_version,
+                amount,
+            },
+        }])
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        match event {
+            Evt::Deposited {
+                aggregate_version,
+                amount,
+                ..
+            } => {
+                self.balance += amount;
+                self.version = *aggregate_version;
+            }
+            Evt::Withdrawn {
+                aggregate_version,
+                amount,
+                ..
+            } => {
+                self.balance -= amount;
+                self.version = *aggregate_version;
+            }
+            Evt::Held {
+                aggregate_version,
+                amount,
+                ..
+            } => {
+                self.holds += amount;
+                self.version = *aggregate_version;
+            }
+        }
+    }
+
+    fn invariants(&self) -> Result<(), Self::Error> {
+        InvariantViolations::new(
+            Self::TYPE,
+            [
+                InvariantViolation::check(
+                    self.balance >= 0,
+                    "non_negative_balance",
+                    format!("balance is {}", self.balance),
+                ),
+                InvariantViolation::check(
+                    self.holds <= self.balance.max(0),
+                    "holds_covered",
+                    format!("holds {} exceed balance {}", self.holds, self.balance),
+                ),
+            ]
+            .into_iter()
+            .flatten(),
+        )
+        .into_result()
+    }
+}
+
+#[tokio::test]
+async fn violated_invariants_roll_back_the_command() -> AnyResult<()> {
+    let events = Arc::new(InMemoryEventRepository::new());
+    let root = AggregateRoot::<Wallet, _>::new(Arc::new(EventSourcedRepo::new(
+        events.clone(),
+        Arc::new(EventUpcasterChain::default()),
+    )));
+    let id = "w-1".to_string();
+
+    root.execute(&id, vec![Cmd::Deposit(100)], EventContext::default())
+        .await?;
+
+    // 整批命令中只要最终状态违反不变量，全部事件都不保存
+    let err = root
+        .execute(
+            &id,
+            vec![Cmd::Hold(30), Cmd::Withdraw(150)],
+            EventContext::default(),
+        )
+        .await
+        .unwrap_err();
+    assert!(err.matches(ErrorKind::InvalidState, INVARIANT_VIOLATED));
+    let violations = err.downcast_ref::<InvariantViolations>().unwrap();
+    assert_eq!(violations.aggregate_type, "wallet");
+    assert_eq!(
+        violations
+            .violations
+            .iter()
+            .map(|v| v.rule)
+            .collect::<Vec<_>>(),
+        ["non_negative_balance", "holds_covered"]
+    );
+    assert_eq!(
+        err.to_string(),
+        "wallet invariants violated: non_negative_balance: balance is -50; \
+         holds_covered: holds 30 exceed balance -50"
+    );
+
+    assert_eq!(events.get_events::<Wallet>(&id).await?.len(), 1);
+    let wallet = root.load(&id).await?.unwrap();
+    assert_eq!((wallet.balance, wallet.holds), (100, 0));
+
+    // 中间状态不受约束，只校验应用全部事件后的状态
+    root.execute(
+        &id,
+        vec![Cmd::Withdraw(150), Cmd::Deposit(80)],
+        EventContext::default(),
+    )
+    .await?;
+    assert_eq!(root.load(&id).await?.unwrap().balance, 30);
+    Ok(())
+}
@@ -0,0 +1,332 @@
+#![cfg(feature = "testing")]
+use anyhow::Result as AnyResult;
+use async_trait::async_trait;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::aggregate_lock::{AggregateLocks, LockMode};
+use ddd_domain::aggregate_mailbox::{AggregateMailbox, MailboxConfig};
+use ddd_domain::aggregate_root::{AggregateRoot, RetryOptions};
+use ddd_domain::domain_event::EventContext;
+use ddd_domain::entity::Entity;
+use ddd_domain::error::{DomainError, DomainResult, ErrorKind};
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::persist::{EventRepository, EventSourcedRepo, SerializedEvent};
+use ddd_domain::testing::InMemoryEventRepository;
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Counter {
+    value: i64,
+}
+
+#[derive(Debug, Clone)]
+enum Cmd {
+    Add(i64),
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Evt {
+    Added { amount: i64 },
+}
+
+impl Aggregate for Counter {
+    const TYPE: &'static str = "counter";
+    type Command = Cmd;
+    type Event = Evt;
+    type Error = DomainError;
+
+    fn execute(&self, command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        let Cmd::Add(amount) = command;
+        assert!(amount >= 0, "negative amount");
+        Ok(vec![Evt::Added {
+            id: ulid::Ulid::new().to_string(),
+            aggregate_version: self.version().next(),
+            amount,
+        }])
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        let Evt::Added {
+            aggregate_version,
+            amount,
+            ..
+        } = event;
+        self.value += amount;
+        self.version = *aggregate_version;
+    }
+}
+
+/// 读取后让出执行权，使未串行化的并发命令必然在保存时冲突
+struct Interleaved(InMemoryEventRepository);
+
+#[async_trait]
+impl EventRepository for Interleaved {
+    async fn get_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+    ) -> DomainResult<Vec<SerializedEvent>> {
+        let events = self.0.get_events::<A>(aggregate_id).await?;
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        Ok(events)
+    }
+
+    async fn get_last_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        last_version: usize,
+    ) -> DomainResult<Vec<SerializedEvent>> {
+        let events = self
+            .0
+            .get_last_events::<A>(aggregate_id, last_version)
+            .await?;
+        tokio::time::sleep(Duration::from_millis(1)).await;
+        Ok(events)
+    }
+
+    async fn save(&self, events: Vec<SerializedEvent>) -> DomainResult<()> {
+        self.0.save(events).await
+    }
+}
+
+fn root() -> AggregateRoot<Counter, Arc<EventSourcedRepo<Interleaved>>> {
+    AggregateRoot::new(Arc::new(EventSourcedRepo::new(
+        Arc::new(Interleaved(InMemoryEventRepository::new())),
+        Arc::new(EventUpcasterChain::default()),
+    )))
+}
+
+async fn add_concurrently<F, Fut>(n: i64, add: F) -> usize
+where
+    F: Fn(i64) -> Fut,
+    Fut: Future<Output = Result<(), DomainError>> + Send + 'static,
+{
+    let tasks: Vec<_> = (1..=n).map(|i| tokio::spawn(add(i))).collect();
+    let mut failures = 0;
+    for task in tasks {
+        if task.await.unwrap().is_err() {
+            failures += 1;
+        }
+    }
+    failures
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn serializes_commands_per_aggregate_and_evicts_idle_mailboxes() -> AnyResult<()> {
+    // 对照：直接并发执行会因乐观并发控制冲突
+    let direct = Arc::new(root());
+    let failures = add_concurrently(10, |i| {
+        let root = direct.clone();
+        async move {
+            root.execute(
+                &"c-1".to_string(),
+                vec![Cmd::Add(i)],
+                EventContext::default(),
+            )
+            .await
+            .map(drop)
+        }
+    })
+    .await;
+    assert!(failures > 0);
+
+    let mailbox = Arc::new(AggregateMailbox::with_config(
+        root(),
+        MailboxConfig {
+            capacity: 4,
+            idle_timeout: Duration::from_millis(50),
+        },
+    ));
+    let failures = add_concurrently(20, |i| {
+        let mailbox = mailbox.clone();
+        async move {
+            mailbox
+                .execute(
+                    &"c-1".to_string(),
+                    vec![Cmd::Add(i)],
+                    EventContext::default(),
+                )
+                .await
+                .map(drop)
+        }
+    })
+    .await;
+    assert_eq!(failures, 0);
+    assert_eq!(mailbox.active_mailboxes(), 1);
+
+    let counter = mailbox.root().load(&"c-1".to_string()).await?.unwrap();
+    assert_eq!(counter.value, (1..=20).sum::<i64>());
+    assert_eq!(counter.version().value(), 20);
+
+    // 空闲超时后邮箱被回收，新命令重新创建邮箱
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert_eq!(mailbox.active_mailboxes(), 0);
+    let envelopes = mailbox
+        .execute(
+            &"c-1".to_string(),
+            vec![Cmd::Add(1)],
+            EventContext::default(),
+        )
+        .await?;
+    assert_eq!(envelopes.len(), 1);
+    assert_eq!(mailbox.active_mailboxes(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn panicking_commands_reply_with_an_error_and_reset_the_mailbox() -> AnyResult<()> {
+    let mailbox = AggregateMailbox::new(root());
+    let id = "c-9".to_string();
+
+    let err = tokio::time::timeout(
+        Duration::from_secs(1),
+        mailbox.execute(&id, vec![Cmd::Add(-1)], EventContext::default()),
+    )
+    .await?
+    .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Internal);
+    assert!(err.to_string().contains("negative amount"));
+
+    // 停止的邮箱被移除，后续命令投递到新建的邮箱而不是空转
+    let envelopes = tokio::time::timeout(
+        Duration::from_secs(1),
+        mailbox.execute(&id, vec![Cmd::Add(2)], EventContext::default()),
+    )
+    .await??;
+    assert_eq!(envelopes.len(), 1);
+    assert_eq!(mailbox.active_mailboxes(), 1);
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn per_aggregate_locking_serializes_direct_execute() -> AnyResult<()> {
+    let root = Arc::new(root().with_locking(LockMode::PerAggregate));
+    let failures = add_concurrently(10, |i| {
+        let root = root.clone();
+        async move {
+            let id = format!("c-{}", i % 2);
+            root.execute(&id, vec![Cmd::Add(i)], EventContext::default())
+                .await
+                .map(drop)
+        }
+    })
+    .await;
+    assert_eq!(failures, 0);
+
+    let even = root.load(&"c-0".to_string()).await?.unwrap();
+    let odd = root.load(&"c-1".to_string()).await?.unwrap();
+    assert_eq!(even.value, 2 + 4 + 6 + 8 + 10);
+    assert_eq!(odd.value, 1 + 3 + 5 + 7 + 9);
+    assert_eq!(even.version().value() + odd.version().value(), 10);
+    Ok(())
+}
+
+#[tokio::test]
+async fn aggregate_locks_are_released_after_use() {
+    let locks = Arc::new(AggregateLocks::new());
+    let guard = locks.acquire("c-1").await;
+
+    let waiter = tokio::spawn({
+        let locks = locks.clone();
+        async move { drop(locks.acquire("c-1").await) }
+    });
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert!(!waiter.is_finished());
+    // 其他聚合不受影响
+    drop(locks.acquire("c-2").await);
+    assert_eq!(locks.len(), 1);
+
+    drop(guard);
+    waiter.await.unwrap();
+    assert!(locks.is_empty());
+}
+
+#[tokio::test]
+async fn cancelled_lock_waiters_do_not_leak_entries() {
+    let locks = Arc::new(AggregateLocks::new());
+    let guard = locks.acquire("c-1").await;
+
+    // 等待中被取消
+    let timed_out = tokio::time::timeout(Duration::from_millis(10), locks.acquire("c-1")).await;
+    assert!(timed_out.is_err());
+    let aborted = tokio::spawn({
+        let locks = locks.clone();
+        async move { drop(locks.acquire("c-1").await) }
+    });
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    aborted.abort();
+    assert!(aborted.await.unwrap_err().is_cancelled());
+    assert_eq!(locks.len(), 1);
+    drop(guard);
+    assert!(locks.is_empty());
+
+    // 持有者先释放、等待者随后被取消
+    let guard = locks.acquire("c-2").await;
+    let waiter = tokio::spawn({
+        let locks = locks.clone();
+        async move {
+            let _guard = locks.acquire("c-2").await;
+            std::future::pending::<()>().await
+        }
+    });
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    drop(guard);
+    tokio::time::sleep(Duration::from_millis(10)).await;
+    assert_eq!(locks.len(), 1);
+    waiter.abort();
+    let _ = waiter.await;
+    assert!(locks.is_empty());
+}
+
+#[tokio::test(flavor = "multi_thread", worker_threads = 4)]
+async fn execute_with_retry_reloads_and_reruns_on_version_conflicts() -> AnyResult<()> {
+    let retrying = Arc::new(root());
+    let failures = add_concurrently(5, |i| {
+        let root = retrying.clone();
+        async move {
+            root.execute_with_retry(
+                &"c-1".to_string(),
+                vec![Cmd::Add(i)],
+                EventContext::default(),
+                RetryOptions::new(20),
+            )
+            .await
+            .map(drop)
+        }
+    })
+    .await;
+    assert_eq!(failures, 0);
+
+    let counter = retrying.load(&"c-1".to_string()).await?.unwrap();
+    assert_eq!(counter.value, 1 + 2 + 3 + 4 + 5);
+    assert_eq!(counter.version().value(), 5);
+
+    // 只执行一次时冲突原样返回
+    let single = Arc::new(root());
+    let conflicts = Arc::new(AtomicUsize::new(0));
+    add_concurrently(5, |i| {
+        let (root, conflicts) = (single.clone(), conflicts.clone());
+        async move {
+            let result = root
+                .execute_with_retry(
+                    &"c-1".to_string(),
+                    vec![Cmd::Add(i)],
+                    EventContext::default(),
+                    RetryOptions::new(1),
+                )
+                .await;
+            if let Err(err) = &result {
+                assert_eq!(err.kind(), ErrorKind::Conflict);
+                conflicts.fetch_add(1, Ordering::SeqCst);
+            }
+            result.map(drop)
+        }
+    })
+    .await;
+    assert!(conflicts.load(Ordering::SeqCst) > 0);
+    Ok(())
+}
@@ -0,0 +1,140 @@
+#![cfg(all(feature = "testing", feature = "eventing"))]
+use anyhow::Result as AnyResult;
+use async_trait::async_trait;
+use chrono::Utc;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::error::{DomainError, DomainResult, ErrorKind};
+use ddd_domain::persist::{
+    BufferedOutboxConfig, BufferedOutboxWriter, EventRepository, SerializedEvent, WriteAck,
+};
+use ddd_domain::testing::InMemoryEventRepository;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::time::Duration;
+
+/// 统计底层 `save` 调用次数
+#[derive(Default)]
+struct Counting {
+    inner: InMemoryEventRepository,
+    saves: AtomicUsize,
+}
+
+#[async_trait]
+impl EventRepository for Counting {
+    async fn get_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+    ) -> DomainResult<Vec<SerializedEvent>> {
+        self.inner.get_events::<A>(aggregate_id).await
+    }
+
+    async fn get_last_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        last_version: usize,
+    ) -> DomainResult<Vec<SerializedEvent>> {
+        self.inner
+            .get_last_events::<A>(aggregate_id, last_version)
+            .await
+    }
+
+    async fn save(&self, events: Vec<SerializedEvent>) -> DomainResult<()> {
+        self.saves.fetch_add(1, Ordering::Relaxed);
+        self.inner.save(events).await
+    }
+}
+
+fn event(aggregate_id: &str, version: usize) -> SerializedEvent {
+    SerializedEvent::builder()
+        .event_id(format!("{aggregate_id}-{version}"))
+        .event_type("counter.incremented".to_string())
+        .event_version(1)
+        .aggregate_id(aggregate_id.to_string())
+        .aggregate_type("counter".to_string())
+        .aggregate_version(version)
+        .occurred_at(Utc::now())
+        .payload(serde_json::json!({}))
+        .context(serde_json::json!({}))
+        .build()
+}
+
+fn writer(
+    repo: &Arc<Counting>,
+    max_batch_size: usize,
+    ack: WriteAck,
+) -> Arc<BufferedOutboxWriter<Counting>> {
+    Arc::new(BufferedOutboxWriter::new(
+        repo.clone(),
+        BufferedOutboxConfig {
+            max_batch_size,
+            max_latency: Duration::from_millis(20),
+            ack,
+        },
+    ))
+}
+
+#[tokio::test]
+async fn concurrent_commands_share_one_insert() -> AnyResult<()> {
+    let repo = Arc::new(Counting::default());
+    let writer = writer(&repo, 100, WriteAck::Flushed);
+
+    let saves = (0..4).map(|i| {
+        let writer = writer.clone();
+        async move { writer.save(vec![event(&format!("c-{i}"), 1)]).await }
+    });
+    for result in futures_util::future::join_all(saves).await {
+        result?;
+    }
+
+    assert_eq!(repo.saves.load(Ordering::Relaxed), 1);
+    assert_eq!(repo.inner.len(), 4);
+    Ok(())
+}
+
+#[tokio::test]
+async fn full_batch_is_written_without_waiting() -> AnyResult<()> {
+    let repo = Arc::new(Counting::default());
+    let writer = writer(&repo, 2, WriteAck::Flushed);
+
+    tokio::time::timeout(
+        Duration::from_millis(10),
+        writer.save(vec![event("c-1", 1), event("c-1", 2)]),
+    )
+    .await??;
+    assert_eq!(repo.inner.len(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn failed_batch_is_split_per_command() -> AnyResult<()> {
+    let repo = Arc::new(Counting::default());
+    let writer = writer(&repo, 100, WriteAck::Flushed);
+
+    let ok = writer.save(vec![event("c-1", 1)]);
+    let conflicting = writer.save(vec![event("c-2", 5)]);
+    let (ok, conflicting) = tokio::join!(ok, conflicting);
+
+    ok?;
+    let err: DomainError = conflicting.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Conflict);
+    assert_eq!(repo.inner.len(), 1);
+    Ok(())
+}
+
+#[tokio::test]
+async fn buffered_ack_reports_errors_on_flush() -> AnyResult<()> {
+    let repo = Arc::new(Counting::default());
+    let writer = writer(&repo, 100, WriteAck::Buffered);
+
+    writer.save(vec![event("c-1", 1)]).await?;
+    writer.save(vec![event("c-2", 3)]).await?;
+    assert_eq!(writer.pending_len(), 2);
+    assert!(repo.inner.is_empty());
+
+    let err = writer.flush().await.unwrap_err();
+    assert!(err.to_string().contains("buffered outbox write failed"));
+    assert_eq!(repo.inner.len(), 1);
+    assert_eq!(writer.pending_len(), 0);
+    writer.flush().await?;
+    Ok(())
+}
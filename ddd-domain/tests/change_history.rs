@@ -0,0 +1,157 @@
+#![cfg(feature = "testing")]
+use anyhow::Result as AnyResult;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::aggregate_root::AggregateRoot;
+use ddd_domain::domain_event::{EventContext, FieldChanged};
+use ddd_domain::entity::Entity;
+use ddd_domain::error::DomainError;
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::persist::{EventRepositoryExt, EventSourcedRepo};
+use ddd_domain::testing::InMemoryEventRepository;
+use ddd_macros::{TrackChanges, domain_event, entity};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+
+#[entity]
+#[derive(Clone, Serialize, Deserialize, TrackChanges)]
+struct Customer {
+    name: String,
+    tier: Option<String>,
+    #[track(skip)]
+    touched: u32,
+}
+
+#[derive(Debug)]
+enum Cmd {
+    Register { name: String },
+    Upgrade { tier: String },
+    Touch,
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Evt {
+    #[event(event_type = "customer.registered")]
+    Registered { name: String },
+    #[event(event_type = "customer.upgraded")]
+    Upgraded { tier: String },
+    #[event(event_type = "customer.touched")]
+    Touched,
+}
+
+impl Aggregate for Customer {
+    const TYPE: &'static str = "customer";
+    type Command = Cmd;
+    type Event = Evt;
+    type Error = DomainError;
+
+    fn execute(&self, command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        let id = ulid::Ulid::new().to_string();
+        let aggregate_version = self.version().next();
+        Ok(vec![match command {
+            Cmd::Register { name } => Evt::Registered {
+                id,
+                aggregate_version,
+                name,
+            },
+            Cmd::Upgrade { tier } => Evt::Upgraded {
+                id,
+                aggregate_version,
+                tier,
+            },
+            Cmd::Touch => Evt::Touched {
+                id,
+                aggregate_version,
+            },
+        }])
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        match event {
+            Evt::Registered {
+                aggregate_version,
+                name,
+                ..
+            } => {
+                self.name = name.clone();
+                self.version = *aggregate_version;
+            }
+            Evt::Upgraded {
+                aggregate_version,
+                tier,
+                ..
+            } => {
+                self.tier = Some(tier.clone());
+                self.version = *aggregate_version;
+            }
+            Evt::Touched {
+                aggregate_version, ..
+            } => {
+                self.touched += 1;
+                self.version = *aggregate_version;
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn change_history_reports_field_changes_per_event() -> AnyResult<()> {
+    let event_repo = Arc::new(InMemoryEventRepository::new());
+    let upcasters = Arc::new(EventUpcasterChain::default());
+    let root = AggregateRoot::<Customer, _>::new(Arc::new(EventSourcedRepo::new(
+        event_repo.clone(),
+        upcasters.clone(),
+    )));
+    let id = "c-1".to_string();
+
+    root.execute(
+        &id,
+        vec![
+            Cmd::Register {
+                name: "Alice".into(),
+            },
+            Cmd::Upgrade {
+                tier: "gold".into(),
+            },
+            Cmd::Touch,
+        ],
+        EventContext::builder()
+            .maybe_actor_id(Some("u-1".into()))
+            .build(),
+    )
+    .await?;
+
+    let history = event_repo
+        .get_aggregate_events_upcasted::<Customer>(&id, &upcasters)
+        .await?
+        .change_history();
+
+    let summary: Vec<(&str, Vec<&str>)> = history
+        .iter()
+        .map(|record| {
+            (
+                record.event_type.as_str(),
+                record
+                    .changes
+                    .iter()
+                    .map(|(field, _)| field.as_str())
+                    .collect(),
+            )
+        })
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("customer.registered", vec!["name"]),
+            ("customer.upgraded", vec!["tier"]),
+            ("customer.touched", vec![]),
+        ]
+    );
+    assert_eq!(
+        history[1].changes[0].1,
+        FieldChanged::new(json!(null), json!("gold"))
+    );
+    assert_eq!(history[0].actor_id.as_deref(), Some("u-1"));
+    Ok(())
+}
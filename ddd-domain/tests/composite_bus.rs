@@ -0,0 +1,150 @@
+#![cfg(all(feature = "testing", feature = "eventing"))]
+use chrono::Utc;
+use ddd_domain::error::ErrorKind;
+use ddd_domain::eventing::{BusTarget, CompositeEventBus, EventBus, InMemoryEventBus};
+use ddd_domain::persist::SerializedEvent;
+use ddd_domain::testing::{FaultSchedule, FlakyBus};
+use futures_util::StreamExt;
+use std::sync::Arc;
+use std::time::Duration;
+
+fn event(id: &str, event_type: &str) -> SerializedEvent {
+    SerializedEvent::builder()
+        .event_id(id.to_string())
+        .event_type(event_type.to_string())
+        .event_version(1)
+        .aggregate_id("a-1".to_string())
+        .aggregate_type("order".to_string())
+        .aggregate_version(1)
+        .occurred_at(Utc::now())
+        .payload(serde_json::json!({}))
+        .context(serde_json::json!({}))
+        .build()
+}
+
+#[tokio::test]
+async fn routes_events_to_targets_by_filter() {
+    let analytics = Arc::new(InMemoryEventBus::new(16));
+    let fulfillment = Arc::new(InMemoryEventBus::new(16));
+    let bus = CompositeEventBus::new()
+        .target(BusTarget::new("analytics", analytics.clone()))
+        .target(BusTarget::new("fulfillment", fulfillment.clone()).prefix("order."))
+        .subscribe_via("fulfillment");
+
+    let mut all = analytics.subscribe().await;
+    let mut orders = bus.subscribe().await;
+    bus.publish_batch(&[
+        event("e-1", "user.registered"),
+        event("e-2", "order.placed"),
+    ])
+    .await
+    .unwrap();
+
+    assert_eq!(all.next().await.unwrap().unwrap().event_id(), "e-1");
+    assert_eq!(all.next().await.unwrap().unwrap().event_id(), "e-2");
+    assert_eq!(orders.next().await.unwrap().unwrap().event_id(), "e-2");
+
+    let stats = bus.stats();
+    assert_eq!((stats[0].published, stats[1].published), (2, 1));
+}
+
+#[tokio::test]
+async fn retries_only_targets_that_failed() {
+    let analytics = Arc::new(InMemoryEventBus::new(16));
+    let fulfillment = Arc::new(FlakyBus::new(
+        Arc::new(InMemoryEventBus::new(16)),
+        FaultSchedule::none().fail_first(1),
+    ));
+    let audit = Arc::new(FlakyBus::new(
+        Arc::new(InMemoryEventBus::new(16)),
+        FaultSchedule::none().fail_every(1),
+    ));
+    let bus = CompositeEventBus::new()
+        .target(BusTarget::new("analytics", analytics.clone()))
+        .target(BusTarget::new("fulfillment", fulfillment.clone()))
+        .target(BusTarget::new("audit", audit.clone()).best_effort());
+
+    let mut all = analytics.subscribe().await;
+    let placed = event("e-1", "order.placed");
+
+    // Required 目标失败使发布失败；BestEffort 目标失败不影响结果
+    let err = bus.publish(&placed).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Internal);
+    assert!(err.to_string().contains("fulfillment"));
+    assert!(!err.to_string().contains("audit"));
+    assert_eq!(bus.partially_published(), 1);
+
+    // 重试时跳过已成功的 analytics
+    bus.publish(&placed).await.unwrap();
+    assert_eq!(bus.partially_published(), 0);
+    assert_eq!(fulfillment.publish_calls(), 2);
+
+    let stats = bus.stats();
+    assert_eq!((stats[0].published, stats[0].failed), (1, 0));
+    assert_eq!((stats[1].published, stats[1].failed), (1, 1));
+    assert_eq!((stats[2].published, stats[2].failed), (0, 2));
+
+    bus.publish(&event("e-2", "order.shipped")).await.unwrap();
+    assert_eq!(all.next().await.unwrap().unwrap().event_id(), "e-1");
+    assert_eq!(all.next().await.unwrap().unwrap().event_id(), "e-2");
+}
+
+#[tokio::test]
+async fn bounds_partially_published_records() {
+    let fulfillment = Arc::new(FlakyBus::new(
+        Arc::new(InMemoryEventBus::new(16)),
+        FaultSchedule::none().fail_every(1),
+    ));
+    let bus = CompositeEventBus::new()
+        .target(BusTarget::new(
+            "analytics",
+            Arc::new(InMemoryEventBus::new(16)),
+        ))
+        .target(BusTarget::new("fulfillment", fulfillment.clone()))
+        .partial_capacity(2);
+
+    for id in ["e-1", "e-2", "e-3"] {
+        assert!(bus.publish(&event(id, "order.placed")).await.is_err());
+    }
+    // 超出上限时淘汰最早的记录
+    assert_eq!(bus.partially_published(), 2);
+    assert!(!bus.forget("e-1"));
+    assert!(bus.forget("e-3"));
+    assert_eq!(bus.partially_published(), 1);
+
+    // 没有任何目标成功的事件无需记录
+    let only_failing =
+        CompositeEventBus::new().target(BusTarget::new("fulfillment", fulfillment.clone()));
+    assert!(
+        only_failing
+            .publish(&event("e-4", "order.placed"))
+            .await
+            .is_err()
+    );
+    assert_eq!(only_failing.partially_published(), 0);
+}
+
+#[tokio::test]
+async fn expired_records_are_republished_to_all_targets() {
+    let fulfillment = Arc::new(FlakyBus::new(
+        Arc::new(InMemoryEventBus::new(16)),
+        FaultSchedule::none().fail_every(1),
+    ));
+    let bus = CompositeEventBus::new()
+        .target(BusTarget::new(
+            "analytics",
+            Arc::new(InMemoryEventBus::new(16)),
+        ))
+        .target(BusTarget::new("fulfillment", fulfillment))
+        .partial_ttl(Duration::from_millis(10));
+    let placed = event("e-1", "order.placed");
+
+    assert!(bus.publish(&placed).await.is_err());
+    assert!(bus.publish(&placed).await.is_err());
+    assert_eq!(bus.stats()[0].published, 1);
+
+    tokio::time::sleep(Duration::from_millis(20)).await;
+    assert!(bus.publish(&placed).await.is_err());
+    assert_eq!(bus.stats()[0].published, 2);
+    assert_eq!(bus.partially_published(), 1);
+}
@@ -0,0 +1,192 @@
+#![cfg(feature = "testing")]
+use anyhow::Result as AnyResult;
+use async_trait::async_trait;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::entity::Entity;
+use ddd_domain::error::{DomainError, DomainResult};
+use ddd_domain::persist::{EventRepository, SerializedEvent};
+use ddd_domain::testing::{ConcurrencyTestKit, InMemoryEventRepository};
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Wallet {
+    balance: i64,
+}
+
+#[derive(Debug, Clone)]
+enum Cmd {
+    Deposit(i64),
+    Withdraw(i64),
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Evt {
+    Deposited { amount: i64 },
+    Withdrawn { amount: i64 },
+}
+
+impl Aggregate for Wallet {
+    const TYPE: &'static str = "wallet";
+    type Command = Cmd;
+    type Event = Evt;
+    type Error = DomainError;
+
+    fn execute(&self, command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        let id = ulid::Ulid::new().to_string();
+        let aggregate_version = self.version().next();
+
+        match command {
+            Cmd::Deposit(amount) => Ok(vec![Evt::Deposited {
+                id,
+                aggregate_version,
+                amount,
+            }]),
+            Cmd::Withdraw(amount) if amount <= self.balance => Ok(vec![Evt::Withdrawn {
+                id,
+                aggregate_version,
+                amount,
+            }]),
+            Cmd::Withdraw(_) => Err(DomainError::invalid_state("insufficient balance")),
+        }
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        match event {
+            Evt::Deposited {
+                aggregate_version,
+                amount,
+                ..
+            } => {
+                self.balance += amount;
+                self.version = *aggregate_version;
+            }
+            Evt::Withdrawn {
+                aggregate_version,
+                amount,
+                ..
+            } => {
+                self.balance -= amount;
+                self.version = *aggregate_version;
+            }
+        }
+    }
+}
+
+/// 读取后让出执行权，制造“读-写”之间的交错
+struct Interleaved<R>(R);
+
+#[async_trait]
+impl<R: EventRepository> EventRepository for Interleaved<R> {
+    async fn get_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+    ) -> DomainResult<Vec<SerializedEvent>> {
+        let events = self.0.get_events::<A>(aggregate_id).await?;
+        tokio::task::yield_now().await;
+        Ok(events)
+    }
+
+    async fn get_last_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        last_version: usize,
+    ) -> DomainResult<Vec<SerializedEvent>> {
+        let events = self
+            .0
+            .get_last_events::<A>(aggregate_id, last_version)
+            .await?;
+        tokio::task::yield_now().await;
+        Ok(events)
+    }
+
+    async fn save(&self, events: Vec<SerializedEvent>) -> DomainResult<()> {
+        self.0.save(events).await
+    }
+}
+
+/// 未实现乐观并发控制的仓储
+#[derive(Default, Clone)]
+struct NaiveEventRepository {
+    inner: Arc<Mutex<HashMap<String, Vec<SerializedEvent>>>>,
+}
+
+#[async_trait]
+impl EventRepository for NaiveEventRepository {
+    async fn get_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+    ) -> DomainResult<Vec<SerializedEvent>> {
+        Ok(self
+            .inner
+            .lock()
+            .unwrap()
+            .get(&aggregate_id.to_string())
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn get_last_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        last_version: usize,
+    ) -> DomainResult<Vec<SerializedEvent>> {
+        let events = self.get_events::<A>(aggregate_id).await?;
+        Ok(events
+            .into_iter()
+            .filter(|e| e.aggregate_version() > last_version)
+            .collect())
+    }
+
+    async fn save(&self, events: Vec<SerializedEvent>) -> DomainResult<()> {
+        let mut g = self.inner.lock().unwrap();
+        for e in events {
+            g.entry(e.aggregate_id().to_string()).or_default().push(e);
+        }
+        Ok(())
+    }
+}
+
+fn command_mix() -> Vec<Vec<Cmd>> {
+    let mut batches = vec![vec![Cmd::Deposit(100)]];
+    for i in 0..20 {
+        batches.push(match i % 3 {
+            0 => vec![Cmd::Deposit(10), Cmd::Withdraw(5)],
+            1 => vec![Cmd::Withdraw(30)],
+            _ => vec![Cmd::Deposit(1)],
+        });
+    }
+    batches
+}
+
+#[tokio::test]
+async fn optimistic_repository_is_linearizable() -> AnyResult<()> {
+    let kit = ConcurrencyTestKit::builder()
+        .event_repo(Arc::new(Interleaved(InMemoryEventRepository::new())))
+        .build();
+
+    let report = kit.run::<Wallet>(&"w-1".to_string(), command_mix()).await?;
+
+    report.assert_linearizable();
+    assert_eq!(report.committed + report.rejected, 21);
+    assert!(report.retries > 0);
+    assert!(report.events > 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn repository_without_concurrency_control_is_detected() -> AnyResult<()> {
+    let kit = ConcurrencyTestKit::builder()
+        .event_repo(Arc::new(Interleaved(NaiveEventRepository::default())))
+        .build();
+
+    let report = kit.run::<Wallet>(&"w-1".to_string(), command_mix()).await?;
+
+    assert!(!report.is_linearizable());
+    assert!(report.violations.iter().any(|v| v.contains("version")));
+    Ok(())
+}
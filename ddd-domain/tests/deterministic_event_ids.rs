@@ -0,0 +1,92 @@
+#![cfg(feature = "testing")]
+use anyhow::Result as AnyResult;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::aggregate_root::AggregateRoot;
+use ddd_domain::domain_event::{DomainEvent, EventContext, next_event_id};
+use ddd_domain::entity::Entity;
+use ddd_domain::error::DomainError;
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::persist::EventSourcedRepo;
+use ddd_domain::testing::{DeterministicEventIdGenerator, InMemoryEventRepository};
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Counter {
+    value: i64,
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum CounterEvent {
+    Incremented { by: i64 },
+}
+
+impl Aggregate for Counter {
+    const TYPE: &'static str = "counter";
+    type Command = i64;
+    type Event = CounterEvent;
+    type Error = DomainError;
+
+    fn execute(&self, by: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        Ok(vec![CounterEvent::Incremented {
+            id: next_event_id(),
+            aggregate_version: self.version().next(),
+            by,
+        }])
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        let CounterEvent::Incremented {
+            aggregate_version,
+            by,
+            ..
+        } = event;
+        self.value += by;
+        self.version = *aggregate_version;
+    }
+}
+
+async fn record_stream() -> AnyResult<Vec<String>> {
+    let repo = EventSourcedRepo::new(
+        Arc::new(InMemoryEventRepository::new()),
+        Arc::new(EventUpcasterChain::default()),
+    );
+    let root = AggregateRoot::<Counter, _>::new(repo);
+
+    let envelopes = root
+        .execute(&"c-1".to_string(), vec![1, 2, 3], EventContext::default())
+        .await?;
+
+    Ok(envelopes
+        .iter()
+        .map(|e| e.payload.event_id().to_string())
+        .collect())
+}
+
+#[tokio::test]
+async fn seeded_generator_makes_event_ids_reproducible() -> AnyResult<()> {
+    let first = {
+        let _ids = DeterministicEventIdGenerator::install(42);
+        record_stream().await?
+    };
+    let second = {
+        let _ids = DeterministicEventIdGenerator::install(42);
+        record_stream().await?
+    };
+    assert_eq!(first, second);
+    assert_eq!(first.len(), 3);
+    assert!(first.iter().all(|id| uuid::Uuid::parse_str(id).is_ok()));
+
+    let other_seed = {
+        let _ids = DeterministicEventIdGenerator::install(7);
+        record_stream().await?
+    };
+    assert_ne!(first, other_seed);
+
+    // 守卫析构后恢复随机 ID
+    assert_ne!(record_stream().await?, record_stream().await?);
+    Ok(())
+}
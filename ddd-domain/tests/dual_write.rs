@@ -0,0 +1,120 @@
+#![cfg(feature = "testing")]
+use anyhow::Result as AnyResult;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::aggregate_root::AggregateRoot;
+use ddd_domain::domain_event::EventContext;
+use ddd_domain::entity::Entity;
+use ddd_domain::error::DomainError;
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::persist::{
+    DualWriteRepo, DualWriteStats, EventRepository, EventSourcedRepo, ReadSource,
+};
+use ddd_domain::testing::InMemoryEventRepository;
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Counter {
+    value: i64,
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum CounterEvent {
+    Incr { by: i64 },
+}
+
+impl Aggregate for Counter {
+    const TYPE: &'static str = "counter";
+    type Command = i64;
+    type Event = CounterEvent;
+    type Error = DomainError;
+
+    fn execute(&self, by: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        Ok(vec![CounterEvent::Incr {
+            id: ulid::Ulid::new().to_string(),
+            aggregate_version: self.version().next(),
+            by,
+        }])
+    }
+
+    fn apply(&mut self, e: &Self::Event) {
+        match e {
+            CounterEvent::Incr {
+                aggregate_version,
+                by,
+                ..
+            } => {
+                self.value += *by;
+                self.version = *aggregate_version;
+            }
+        }
+    }
+}
+
+type Root = AggregateRoot<
+    Counter,
+    EventSourcedRepo<DualWriteRepo<InMemoryEventRepository, InMemoryEventRepository>>,
+>;
+
+fn root(dual: Arc<DualWriteRepo<InMemoryEventRepository, InMemoryEventRepository>>) -> Root {
+    AggregateRoot::new(EventSourcedRepo::new(
+        dual,
+        Arc::new(EventUpcasterChain::default()),
+    ))
+}
+
+#[tokio::test]
+async fn shadow_writes_compares_reads_and_cuts_over() -> AnyResult<()> {
+    let old = Arc::new(InMemoryEventRepository::new());
+    let new = Arc::new(InMemoryEventRepository::new());
+    let dual = Arc::new(DualWriteRepo::new(old.clone(), new.clone()).with_compare_reads(true));
+    let root = root(dual.clone());
+    let id = "c-1".to_string();
+
+    root.execute(&id, vec![1, 2], EventContext::default())
+        .await?;
+    assert_eq!(old.len(), 2);
+    assert_eq!(new.len(), 2);
+    assert_eq!(
+        dual.stats(),
+        DualWriteStats {
+            shadow_write_failures: 0,
+            compared_reads: 1,
+            read_mismatches: 0,
+        }
+    );
+
+    // 迁移前已存在、尚未回填到新库的聚合：读取不一致，影子写因版本冲突失败
+    let legacy = "c-legacy".to_string();
+    let legacy_events = old.get_events::<Counter>(&id).await?;
+    let backfilled: Vec<_> = legacy_events
+        .iter()
+        .map(|e| {
+            let mut v = serde_json::to_value(e).unwrap();
+            v["aggregate_id"] = serde_json::json!(legacy);
+            serde_json::from_value(v).unwrap()
+        })
+        .collect();
+    old.save(backfilled).await?;
+
+    root.execute(&legacy, vec![5], EventContext::default())
+        .await?;
+    let stats = dual.stats();
+    assert_eq!(stats.read_mismatches, 1);
+    assert_eq!(stats.shadow_write_failures, 1);
+    assert_eq!(old.get_events::<Counter>(&legacy).await?.len(), 3);
+
+    // 切换后从新库读取、以新库为主写
+    dual.cutover();
+    assert_eq!(dual.read_source(), ReadSource::New);
+    let loaded = root.load(&id).await?.unwrap();
+    assert_eq!(loaded.value, 3);
+
+    root.execute(&id, vec![10], EventContext::default()).await?;
+    assert_eq!(new.current_version::<Counter>(&id).await?, 3);
+    assert_eq!(old.current_version::<Counter>(&id).await?, 3);
+    Ok(())
+}
@@ -0,0 +1,197 @@
+#![cfg(feature = "chaos")]
+use anyhow::Result as AnyResult;
+use chrono::Utc;
+use ddd_domain::error::DomainResult;
+use ddd_domain::eventing::{
+    CHAOS_FAILURE_REASON, ChaosConfig, ChaosStats, EngineChaos, EventDeliverer, EventEngine,
+    EventEngineConfig, EventHandler, EventReclaimer, HandledEventType, HandlerContext,
+    InMemoryEventBus,
+};
+use ddd_domain::persist::SerializedEvent;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[derive(Default)]
+struct Outbox {
+    pending: Mutex<Vec<SerializedEvent>>,
+}
+
+#[async_trait::async_trait]
+impl EventDeliverer for Outbox {
+    async fn fetch_events(&self) -> DomainResult<Vec<SerializedEvent>> {
+        Ok(std::mem::take(&mut *self.pending.lock().unwrap()))
+    }
+    async fn mark_delivered(&self, _events: &[&SerializedEvent]) -> DomainResult<()> {
+        Ok(())
+    }
+    async fn mark_failed(&self, _events: &[&SerializedEvent], _reason: &str) -> DomainResult<()> {
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct Reclaimer {
+    failed: Mutex<Vec<SerializedEvent>>,
+    reasons: Mutex<Vec<String>>,
+}
+
+#[async_trait::async_trait]
+impl EventReclaimer for Reclaimer {
+    async fn fetch_events(&self) -> DomainResult<Vec<SerializedEvent>> {
+        Ok(std::mem::take(&mut *self.failed.lock().unwrap()))
+    }
+    async fn mark_reclaimed(&self, _events: &[&SerializedEvent]) -> DomainResult<()> {
+        Ok(())
+    }
+    async fn mark_failed(&self, _events: &[&SerializedEvent], _reason: &str) -> DomainResult<()> {
+        Ok(())
+    }
+    async fn mark_handler_failed(
+        &self,
+        _handler_name: &str,
+        events: &[&SerializedEvent],
+        reason: &str,
+    ) -> DomainResult<()> {
+        self.reasons.lock().unwrap().push(reason.to_string());
+        self.failed
+            .lock()
+            .unwrap()
+            .extend(events.iter().map(|e| (*e).clone()));
+        Ok(())
+    }
+}
+
+/// 按事件 ID 去重的读模型：重复投递不改变结果
+#[derive(Default)]
+struct IdempotentProjection {
+    deliveries: Mutex<HashMap<String, usize>>,
+}
+
+#[async_trait::async_trait]
+impl EventHandler for IdempotentProjection {
+    async fn handle(&self, event: &SerializedEvent, _ctx: &HandlerContext) -> anyhow::Result<()> {
+        *self
+            .deliveries
+            .lock()
+            .unwrap()
+            .entry(event.event_id().to_string())
+            .or_default() += 1;
+        Ok(())
+    }
+    fn handled_event_type(&self) -> HandledEventType {
+        HandledEventType::All
+    }
+    fn handler_name(&self) -> &str {
+        "projection"
+    }
+}
+
+fn event(n: usize) -> SerializedEvent {
+    SerializedEvent::builder()
+        .event_id(format!("e-{n}"))
+        .event_type("Counted".to_string())
+        .event_version(1)
+        .aggregate_id(format!("agg-{n}"))
+        .aggregate_type("counter".to_string())
+        .aggregate_version(1)
+        .occurred_at(Utc::now())
+        .payload(serde_json::json!({ "n": n }))
+        .context(serde_json::json!({}))
+        .build()
+}
+
+async fn run(
+    chaos: Arc<EngineChaos>,
+    events: usize,
+) -> AnyResult<(Arc<IdempotentProjection>, Arc<Reclaimer>)> {
+    let outbox = Arc::new(Outbox::default());
+    let reclaimer = Arc::new(Reclaimer::default());
+    let projection = Arc::new(IdempotentProjection::default());
+    let engine = Arc::new(
+        EventEngine::builder()
+            .event_bus(Arc::new(InMemoryEventBus::new(1024)))
+            .event_deliverer(outbox.clone())
+            .event_reclaimer(reclaimer.clone())
+            .event_handlers(vec![projection.clone() as Arc<dyn EventHandler>])
+            .config(EventEngineConfig {
+                deliver_interval: Duration::from_millis(20),
+                reclaim_interval: Duration::from_millis(20),
+                ..Default::default()
+            })
+            .chaos(chaos)
+            .build(),
+    );
+    outbox
+        .pending
+        .lock()
+        .unwrap()
+        .extend((0..events).map(event));
+
+    let handle = engine.start();
+    tokio::time::timeout(Duration::from_secs(5), async {
+        while projection.deliveries.lock().unwrap().len() < events {
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+    })
+    .await?;
+    handle.shutdown();
+    handle.join().await;
+    Ok((projection, reclaimer))
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn injects_faults_and_projection_still_converges() -> AnyResult<()> {
+    let chaos = Arc::new(EngineChaos::new(ChaosConfig {
+        seed: 42,
+        handler_failure_rate: 0.3,
+        publish_delay_rate: 0.5,
+        max_publish_delay: Duration::from_millis(5),
+        duplicate_rate: 0.5,
+        reorder_rate: 1.0,
+    }));
+    let (projection, reclaimer) = run(chaos.clone(), 30).await?;
+
+    let stats = chaos.stats();
+    assert!(stats.handler_failures > 0, "{stats:?}");
+    assert!(stats.duplicated_deliveries > 0, "{stats:?}");
+    assert!(stats.reordered_batches > 0, "{stats:?}");
+
+    // 注入的失败经回收器补偿后，每个事件最终都被处理
+    let deliveries = projection.deliveries.lock().unwrap();
+    assert_eq!(deliveries.len(), 30);
+    assert!(deliveries.values().sum::<usize>() > 30);
+    assert!(
+        reclaimer
+            .reasons
+            .lock()
+            .unwrap()
+            .iter()
+            .all(|r| r == CHAOS_FAILURE_REASON)
+    );
+    Ok(())
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn disabled_chaos_injects_nothing() -> AnyResult<()> {
+    let chaos = Arc::new(EngineChaos::new(ChaosConfig {
+        handler_failure_rate: 1.0,
+        duplicate_rate: 1.0,
+        reorder_rate: 1.0,
+        ..Default::default()
+    }));
+    chaos.set_enabled(false);
+    let (projection, reclaimer) = run(chaos.clone(), 10).await?;
+
+    assert_eq!(chaos.stats(), ChaosStats::default());
+    assert!(
+        projection
+            .deliveries
+            .lock()
+            .unwrap()
+            .values()
+            .all(|n| *n == 1)
+    );
+    assert!(reclaimer.reasons.lock().unwrap().is_empty());
+    Ok(())
+}
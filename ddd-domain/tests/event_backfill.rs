@@ -0,0 +1,163 @@
+#![cfg(feature = "testing")]
+use anyhow::Result as AnyResult;
+use chrono::{DateTime, Duration, TimeZone, Utc};
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::aggregate_root::AggregateRoot;
+use ddd_domain::domain_event::{EventContext, EventEnvelope};
+use ddd_domain::entity::Entity;
+use ddd_domain::error::{DomainError, ErrorCode};
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::persist::{
+    BackfillEvent, EventImporter, EventRepository, EventRepositoryExt, EventSourcedRepo,
+    SerializedEvent,
+};
+use ddd_domain::testing::InMemoryEventRepository;
+use ddd_domain::value_object::Version;
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Ledger {
+    balance: i64,
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum LedgerEvent {
+    Posted { amount: i64 },
+}
+
+impl Aggregate for Ledger {
+    const TYPE: &'static str = "ledger";
+    type Command = i64;
+    type Event = LedgerEvent;
+    type Error = DomainError;
+
+    fn execute(&self, amount: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        Ok(vec![posted(self.version().next(), amount)])
+    }
+
+    fn apply(&mut self, e: &Self::Event) {
+        let LedgerEvent::Posted {
+            aggregate_version,
+            amount,
+            ..
+        } = e;
+        self.balance += amount;
+        self.version = *aggregate_version;
+    }
+}
+
+fn posted(version: Version, amount: i64) -> LedgerEvent {
+    LedgerEvent::Posted {
+        id: ulid::Ulid::new().to_string(),
+        aggregate_version: version,
+        amount,
+    }
+}
+
+fn at(day: u32) -> DateTime<Utc> {
+    Utc.with_ymd_and_hms(2019, 3, day, 9, 0, 0).unwrap()
+}
+
+fn backfill(version: usize, amount: i64, day: u32) -> BackfillEvent<LedgerEvent> {
+    BackfillEvent::new(posted(Version::from_value(version), amount), at(day))
+}
+
+#[tokio::test]
+async fn imports_events_with_original_timestamps() -> AnyResult<()> {
+    let repo = Arc::new(InMemoryEventRepository::new());
+    let importer = EventImporter::new(repo.clone());
+    let id = "l-1".to_string();
+
+    let imported = importer
+        .import::<Ledger>(&id, vec![backfill(1, 100, 1), backfill(2, -30, 5)])
+        .await?;
+    assert_eq!(imported, 2);
+
+    let stored = repo.get_events::<Ledger>(&id).await?;
+    assert_eq!(
+        stored
+            .iter()
+            .map(SerializedEvent::occurred_at)
+            .collect::<Vec<_>>(),
+        [at(1), at(5)]
+    );
+    assert!(stored.iter().all(SerializedEvent::is_backfilled));
+
+    // 回填标记在反序列化后的信封元数据中保留
+    let history = repo
+        .get_aggregate_events_upcasted::<Ledger>(&id, &EventUpcasterChain::default())
+        .await?;
+    assert!(history.iter().all(|e| e.metadata.is_backfilled()));
+    assert_eq!(history.created_at(), Some(at(1)));
+
+    // 导入后的常规写入不带回填标记
+    let root = AggregateRoot::<Ledger, _>::new(EventSourcedRepo::new(
+        repo.clone(),
+        Arc::new(EventUpcasterChain::default()),
+    ));
+    root.execute(&id, vec![10], EventContext::default()).await?;
+    let stored = repo.get_events::<Ledger>(&id).await?;
+    assert!(!stored[2].is_backfilled());
+    assert!(
+        serde_json::to_value(&stored[2])?
+            .get("backfilled")
+            .is_none()
+    );
+    assert_eq!(root.load(&id).await?.map(|l| l.balance), Some(80));
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_invalid_backfills_without_writing() -> AnyResult<()> {
+    let repo = Arc::new(InMemoryEventRepository::new());
+    let importer = EventImporter::new(repo.clone());
+    let id = "l-1".to_string();
+    importer
+        .import::<Ledger>(&id, vec![backfill(1, 100, 10)])
+        .await?;
+
+    let cases = [
+        vec![backfill(3, 1, 11)],
+        vec![backfill(2, 1, 9)],
+        vec![
+            backfill(2, 1, 11),
+            BackfillEvent::new(
+                posted(Version::from_value(3), 1),
+                Utc::now() + Duration::hours(1),
+            ),
+        ],
+    ];
+    for events in cases {
+        let err = importer.import::<Ledger>(&id, events).await.unwrap_err();
+        assert_eq!(err.code(), "BACKFILL_REJECTED");
+    }
+    assert_eq!(repo.get_events::<Ledger>(&id).await?.len(), 1);
+
+    // 允许的时钟偏差内的时间可导入
+    let skewed = EventImporter::new(repo.clone()).with_max_clock_skew(Duration::minutes(5));
+    let soon = Utc::now() + Duration::minutes(1);
+    skewed
+        .import::<Ledger>(
+            &id,
+            vec![BackfillEvent::new(posted(Version::from_value(2), 1), soon)],
+        )
+        .await?;
+    Ok(())
+}
+
+#[test]
+fn new_with_marks_envelope_as_backfilled() {
+    let id = "l-1".to_string();
+    let event = posted(Version::from_value(1), 1);
+
+    let live = EventEnvelope::<Ledger>::new(&id, event.clone(), EventContext::default());
+    assert!(!live.metadata.is_backfilled());
+
+    let backfilled = EventEnvelope::<Ledger>::new_with(&id, event, EventContext::default(), at(1));
+    assert!(backfilled.metadata.is_backfilled());
+    assert_eq!(*backfilled.metadata.occurred_at(), at(1));
+}
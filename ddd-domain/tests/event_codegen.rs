@@ -0,0 +1,123 @@
+#![cfg(feature = "codegen")]
+use anyhow::Result as AnyResult;
+use chrono::Utc;
+use ddd_domain::codegen::EventCodegen;
+use ddd_domain::domain_event::{DomainEvent, EventDescriptor};
+use ddd_domain::error::ErrorCode;
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::persist::SerializedEvent;
+use serde_json::json;
+
+mod generated {
+    include!("generated/order_events.rs");
+}
+
+use generated::{OrderEvent, order_event_upcasters};
+
+const SCHEMA_DIR: &str = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/event_schemas");
+const GENERATED: &str = concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/tests/generated/order_events.rs"
+);
+
+#[test]
+fn generated_code_matches_schemas() -> AnyResult<()> {
+    EventCodegen::new()
+        .load_dir(SCHEMA_DIR)?
+        .verify(GENERATED)?;
+    Ok(())
+}
+
+#[test]
+fn generated_enum_follows_latest_schema_versions() {
+    let descriptors: Vec<_> = OrderEvent::DESCRIPTORS
+        .iter()
+        .map(|d| (d.variant, d.event_type, d.event_version))
+        .collect();
+    assert_eq!(
+        descriptors,
+        [
+            ("Archived", "OrderEvent.Archived", 1),
+            ("Cancelled", "order.cancelled", 1),
+            ("Placed", "OrderEvent.Placed", 2),
+        ]
+    );
+    assert!(EventDescriptor::find(OrderEvent::DESCRIPTORS, "order.cancelled").is_some());
+}
+
+#[test]
+fn generated_upcaster_skeleton_migrates_v1_payload() -> AnyResult<()> {
+    let chain: EventUpcasterChain = order_event_upcasters().into_iter().collect();
+    let v1 = SerializedEvent::builder()
+        .event_id("e-1".to_string())
+        .event_type("OrderEvent.Placed".to_string())
+        .event_version(1)
+        .aggregate_id("o-1".to_string())
+        .aggregate_type("order".to_string())
+        .aggregate_version(1)
+        .occurred_at(Utc::now())
+        .payload(json!({
+            "Placed": { "id": "e-1", "aggregate_version": 1, "amount": 1200, "coupon": "SPRING" }
+        }))
+        .context(json!({}))
+        .build();
+
+    let upcasted = chain.upcast_all(vec![v1])?.remove(0);
+    assert_eq!(upcasted.event_version(), 2);
+    let OrderEvent::Placed {
+        amount,
+        currency,
+        lines,
+        note,
+        ..
+    } = serde_json::from_value(upcasted.payload().clone())?
+    else {
+        panic!("expected Placed");
+    };
+    assert_eq!((amount, currency.as_str()), (1200, ""));
+    assert!(lines.is_empty() && note.is_none());
+    assert!(upcasted.payload()["Placed"].get("coupon").is_none());
+    Ok(())
+}
+
+#[test]
+fn stale_output_and_invalid_schemas_are_rejected() -> AnyResult<()> {
+    let codegen = EventCodegen::new().load_dir(SCHEMA_DIR)?;
+    let stale = std::env::temp_dir().join(format!("ddd-codegen-{}.rs", std::process::id()));
+    assert!(codegen.write_to(&stale)?);
+    assert!(!codegen.write_to(&stale)?);
+    std::fs::write(&stale, "// edited by hand\n")?;
+    assert_eq!(
+        codegen.verify(&stale).unwrap_err().code(),
+        "EVENT_CODEGEN_STALE"
+    );
+    std::fs::remove_file(&stale)?;
+
+    let schema = |event_type: &str, version: usize, properties: serde_json::Value| {
+        json!({
+            "event_type": event_type,
+            "event_version": version,
+            "schema": { "type": "object", "properties": properties }
+        })
+        .to_string()
+    };
+    let cases = [
+        vec![
+            schema("Cart.Added", 1, json!({})),
+            schema("Cart.Added", 3, json!({})),
+        ],
+        vec![schema("Cart.Added", 1, json!({ "first-name": {} }))],
+        vec![schema("cart_added", 1, json!({}))],
+    ];
+    for case in cases {
+        let mut codegen = EventCodegen::new();
+        for json in &case {
+            codegen = codegen.load_json(json)?;
+        }
+        assert_eq!(
+            codegen.generate().unwrap_err().code(),
+            "EVENT_SCHEMA_INVALID"
+        );
+    }
+    Ok(())
+}
@@ -0,0 +1,111 @@
+#![cfg(feature = "testing")]
+use anyhow::Result as AnyResult;
+use ddd_domain::testing::{EventContractRegistry, EventExpectation};
+use ddd_domain::value_object::Version;
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+struct Line {
+    sku: String,
+    quantity: u32,
+}
+
+fn line() -> Line {
+    Line {
+        sku: "sku-1".into(),
+        quantity: 2,
+    }
+}
+
+mod current {
+    use super::*;
+    use ddd_macros::domain_event;
+
+    #[domain_event(version = 1)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub enum OrderEvent {
+        Placed {
+            amount: i64,
+            currency: String,
+            lines: Vec<Line>,
+        },
+        Cancelled {
+            reason: String,
+        },
+    }
+}
+
+/// 生产者的破坏性变更：金额改为小数、移除币种、版本升级
+mod breaking {
+    use super::*;
+    use ddd_macros::domain_event;
+
+    #[domain_event(version = 2)]
+    #[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+    pub enum OrderEvent {
+        Placed { amount: f64, lines: Vec<Line> },
+        Cancelled { reason: String },
+    }
+}
+
+fn registry() -> AnyResult<EventContractRegistry> {
+    let dir = concat!(env!("CARGO_MANIFEST_DIR"), "/tests/contracts");
+    Ok(EventContractRegistry::new().load_dir(dir)?)
+}
+
+#[test]
+fn current_events_satisfy_all_consumers() -> AnyResult<()> {
+    let samples = [
+        current::OrderEvent::Placed {
+            id: "e-1".into(),
+            aggregate_version: Version::from_value(1),
+            amount: 100,
+            currency: "CNY".into(),
+            lines: vec![line()],
+        },
+        current::OrderEvent::Cancelled {
+            id: "e-2".into(),
+            aggregate_version: Version::from_value(2),
+            reason: "changed mind".into(),
+        },
+    ];
+
+    let report = registry()?.verify(&samples)?;
+    assert_eq!(report.checked, 3);
+    report.assert_ok();
+    Ok(())
+}
+
+#[test]
+fn breaking_changes_are_reported_per_consumer() -> AnyResult<()> {
+    let samples = [breaking::OrderEvent::Placed {
+        id: "e-1".into(),
+        aggregate_version: Version::from_value(1),
+        amount: 99.5,
+        lines: vec![line()],
+    }];
+
+    let report = registry()?
+        .expectation(EventExpectation {
+            consumer: "analytics".into(),
+            event_type: "InvoiceEvent.Issued".into(),
+            event_version: None,
+            schema: json!({ "type": "object" }),
+        })
+        .verify(&samples)?;
+
+    // 未涉及该事件枚举的期望不参与校验
+    assert_eq!(report.checked, 3);
+    let violations: Vec<String> = report.violations.iter().map(ToString::to_string).collect();
+    assert_eq!(
+        violations,
+        [
+            "billing expects OrderEvent.Placed: expects version 1, producer emits version 2",
+            "billing expects OrderEvent.Placed at /Placed/currency: required field is missing",
+            "billing expects OrderEvent.Placed at /Placed/amount: expected integer, found number",
+            "shipping expects OrderEvent.Cancelled: no sample event provided",
+        ]
+    );
+    Ok(())
+}
@@ -0,0 +1,180 @@
+#![cfg(feature = "testing")]
+use anyhow::Result as AnyResult;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::aggregate_root::AggregateRoot;
+use ddd_domain::domain_event::{DomainEvent, EventContext};
+use ddd_domain::entity::Entity;
+use ddd_domain::error::{DomainError, ErrorKind};
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::persist::admin::{
+    CorrectionAuditStore, EventAdmin, InMemoryCorrectionAuditStore, supersedes,
+};
+use ddd_domain::persist::{AggregateRepository, EventRepository, EventSourcedRepo};
+use ddd_domain::testing::InMemoryEventRepository;
+use ddd_domain::value_object::Version;
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Account {
+    balance: i64,
+}
+
+#[derive(Debug)]
+enum Cmd {
+    Deposit { amount: i64 },
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Evt {
+    #[event(event_type = "account.deposited")]
+    Deposited { amount: i64 },
+    /// 更正：以 `amount` 取代原入账金额
+    #[event(event_type = "account.deposit_corrected")]
+    DepositCorrected { original: i64, amount: i64 },
+}
+
+impl Aggregate for Account {
+    const TYPE: &'static str = "account";
+    type Command = Cmd;
+    type Event = Evt;
+    type Error = DomainError;
+
+    fn execute(&self, command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        let Cmd::Deposit { amount } = command;
+        Ok(vec![Evt::Deposited {
+            id: ulid::Ulid::new().to_string(),
+            aggregate_version: self.version().next(),
+            amount,
+        }])
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        match event {
+            Evt::Deposited {
+                aggregate_version,
+                amount,
+                ..
+            } => {
+                self.balance += amount;
+                self.version = *aggregate_version;
+            }
+            Evt::DepositCorrected {
+                aggregate_version,
+                original,
+                amount,
+                ..
+            } => {
+                self.balance += amount - original;
+                self.version = *aggregate_version;
+            }
+        }
+    }
+}
+
+fn correction(version: usize, original: i64, amount: i64) -> Evt {
+    Evt::DepositCorrected {
+        id: ulid::Ulid::new().to_string(),
+        aggregate_version: Version::from_value(version),
+        original,
+        amount,
+    }
+}
+
+#[tokio::test]
+async fn supersede_appends_correction_with_audit_trail() -> AnyResult<()> {
+    let events = Arc::new(InMemoryEventRepository::new());
+    let repo = Arc::new(EventSourcedRepo::new(
+        events.clone(),
+        Arc::new(EventUpcasterChain::default()),
+    ));
+    let root = AggregateRoot::<Account, _>::new(repo.clone());
+    let id = "a-1".to_string();
+    let envelopes = root
+        .execute(
+            &id,
+            vec![Cmd::Deposit { amount: 100 }, Cmd::Deposit { amount: 1000 }],
+            EventContext::default(),
+        )
+        .await?;
+    let typo = envelopes[1].payload.event_id().to_string();
+
+    let audit = Arc::new(InMemoryCorrectionAuditStore::new());
+    let admin = EventAdmin::new(events.clone(), audit.clone());
+
+    let record = admin
+        .supersede::<Account>(&id, &typo, correction(3, 1000, 10), "ops-1", "INC-42 typo")
+        .await?;
+    assert_eq!(record.supersedes_event_id, typo);
+    assert_eq!(record.correction_event_type, "account.deposit_corrected");
+    assert_eq!(audit.list().await?, std::slice::from_ref(&record));
+    assert_eq!(
+        admin.corrections_of(&typo).await?,
+        std::slice::from_ref(&record)
+    );
+
+    // 原事件保留，更正事件追加在流尾并引用原事件
+    let stream = events.get_events::<Account>(&id).await?;
+    assert_eq!(stream.len(), 3);
+    assert_eq!(stream[1].event_id(), typo);
+    let appended = &stream[2];
+    assert_eq!(appended.event_id(), record.correction_event_id);
+    assert_eq!(appended.causation_id(), Some(typo.as_str()));
+    assert_eq!(appended.actor_id(), Some("ops-1"));
+    assert_eq!(supersedes(appended), Some(typo.as_str()));
+    assert_eq!(supersedes(&stream[0]), None);
+
+    let account: Account = repo.load(&id).await?.unwrap();
+    assert_eq!(account.balance, 110);
+    Ok(())
+}
+
+#[tokio::test]
+async fn supersede_rejects_invalid_requests() -> AnyResult<()> {
+    let events = Arc::new(InMemoryEventRepository::new());
+    let repo = Arc::new(EventSourcedRepo::new(
+        events.clone(),
+        Arc::new(EventUpcasterChain::default()),
+    ));
+    let root = AggregateRoot::<Account, _>::new(repo);
+    let id = "a-1".to_string();
+    let envelopes = root
+        .execute(
+            &id,
+            vec![Cmd::Deposit { amount: 5 }],
+            EventContext::default(),
+        )
+        .await?;
+    let original = envelopes[0].payload.event_id().to_string();
+
+    let audit = Arc::new(InMemoryCorrectionAuditStore::new());
+    let admin = EventAdmin::new(events.clone(), audit.clone());
+
+    let err = admin
+        .supersede::<Account>(&id, &original, correction(2, 5, 50), " ", "typo")
+        .await
+        .unwrap_err();
+    assert!(err.matches(ErrorKind::InvalidValue, "CORRECTION_ACTOR_REQUIRED"));
+    let err = admin
+        .supersede::<Account>(&id, &original, correction(2, 5, 50), "ops", "")
+        .await
+        .unwrap_err();
+    assert!(err.matches(ErrorKind::InvalidValue, "CORRECTION_REASON_REQUIRED"));
+    let err = admin
+        .supersede::<Account>(&id, "missing", correction(2, 5, 50), "ops", "typo")
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    let err = admin
+        .supersede::<Account>(&id, &original, correction(5, 5, 50), "ops", "typo")
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Conflict);
+
+    assert_eq!(events.len(), 1);
+    assert!(audit.records().is_empty());
+    Ok(())
+}
@@ -5,7 +5,7 @@ use ddd_domain::domain_event::EventContext;
 use ddd_domain::error::{DomainError, DomainResult};
 use ddd_domain::eventing::{
     EventBus, EventDeliverer, EventEngine, EventEngineConfig, EventHandler, EventReclaimer,
-    HandledEventType,
+    HandledEventType, HandlerContext,
 };
 use ddd_domain::persist::SerializedEvent;
 use futures_core::stream::BoxStream;
@@ -109,7 +109,7 @@ struct FlakyHandler {
 }
 #[async_trait::async_trait]
 impl EventHandler for FlakyHandler {
-    async fn handle(&self, event: &SerializedEvent) -> anyhow::Result<()> {
+    async fn handle(&self, event: &SerializedEvent, _ctx: &HandlerContext) -> anyhow::Result<()> {
         if event.event_type() == "Bad" {
             let mut g = self.seen.lock().unwrap();
             if !g.contains(event.event_id()) {
@@ -0,0 +1,167 @@
+#![cfg(feature = "testing")]
+use anyhow::Result as AnyResult;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::aggregate_root::AggregateRoot;
+use ddd_domain::domain_event::{DomainEvent, EventContext};
+use ddd_domain::entity::Entity;
+use ddd_domain::error::{DomainError, ErrorKind};
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::persist::{AggregateRepository, EventRepository, EventSourcedRepo};
+use ddd_domain::testing::InMemoryEventRepository;
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Customer {
+    name: String,
+    nickname: Option<String>,
+}
+
+#[derive(Debug)]
+enum Cmd {
+    Register { name: String },
+    SetNickname { nickname: Option<String> },
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Evt {
+    #[event(event_type = "customer.registered")]
+    Registered { name: String },
+    #[event(event_type = "customer.nickname_set")]
+    NicknameSet { nickname: Option<String> },
+}
+
+impl Aggregate for Customer {
+    const TYPE: &'static str = "customer";
+    type Command = Cmd;
+    type Event = Evt;
+    type Error = DomainError;
+
+    fn execute(&self, command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        let id = ulid::Ulid::new().to_string();
+        let aggregate_version = self.version().next();
+
+        Ok(vec![match command {
+            Cmd::Register { name } => Evt::Registered {
+                id,
+                aggregate_version,
+                name,
+            },
+            Cmd::SetNickname { nickname } => Evt::NicknameSet {
+                id,
+                aggregate_version,
+                nickname,
+            },
+        }])
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        match event {
+            Evt::Registered {
+                aggregate_version,
+                name,
+                ..
+            } => {
+                self.name = name.clone();
+                self.version = *aggregate_version;
+            }
+            Evt::NicknameSet {
+                aggregate_version,
+                nickname,
+                ..
+            } => {
+                self.nickname = nickname.clone();
+                self.version = *aggregate_version;
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn excluded_events_are_skipped_on_read_but_kept_for_audit() -> AnyResult<()> {
+    let event_repo = Arc::new(InMemoryEventRepository::new());
+    let repo = Arc::new(EventSourcedRepo::new(
+        event_repo.clone(),
+        Arc::new(EventUpcasterChain::default()),
+    ));
+    let root = AggregateRoot::<Customer, _>::new(repo.clone());
+    let id = "c-1".to_string();
+
+    let envelopes = root
+        .execute(
+            &id,
+            vec![
+                Cmd::Register {
+                    name: "Alice".into(),
+                },
+                Cmd::SetNickname {
+                    nickname: Some("written by mistake".into()),
+                },
+                Cmd::SetNickname { nickname: None },
+                Cmd::SetNickname {
+                    nickname: Some("Al".into()),
+                },
+            ],
+            EventContext::default(),
+        )
+        .await?;
+    let wrong = envelopes[1].payload.event_id().to_string();
+    let head = envelopes[3].payload.event_id().to_string();
+
+    // 最新事件不能被排除
+    let err = event_repo
+        .mark_excluded(&head, "typo", "ops")
+        .await
+        .unwrap_err();
+    assert!(err.matches(ErrorKind::InvalidState, "EXCLUSION_OF_HEAD"));
+    let err = event_repo
+        .mark_excluded("missing", "typo", "ops")
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+
+    event_repo
+        .mark_excluded(
+            &wrong,
+            "nickname entered for wrong customer",
+            "ops@example.com",
+        )
+        .await?;
+    let err = event_repo
+        .mark_excluded(&wrong, "again", "ops")
+        .await
+        .unwrap_err();
+    assert!(err.matches(ErrorKind::InvalidState, "EVENT_ALREADY_EXCLUDED"));
+
+    // 读取路径跳过被排除的事件，版本不受影响
+    let stored = event_repo.get_events::<Customer>(&id).await?;
+    assert_eq!(stored.len(), 3);
+    assert!(stored.iter().all(|e| e.event_id() != wrong));
+    assert_eq!(event_repo.current_version::<Customer>(&id).await?, 4);
+    let customer: Customer = repo.load(&id).await?.unwrap();
+    assert_eq!(customer.version().value(), 4);
+    assert_eq!(customer.nickname.as_deref(), Some("Al"));
+
+    // 事件与排除记录保留以供审计
+    assert_eq!(event_repo.all_events().len(), 4);
+    let exclusions = event_repo.exclusions().await?;
+    assert_eq!(exclusions.len(), 1);
+    assert_eq!(exclusions[0].event_id, wrong);
+    assert_eq!(exclusions[0].aggregate_id, "c-1");
+    assert_eq!(exclusions[0].actor, "ops@example.com");
+
+    // 排除后继续写入不产生版本冲突
+    root.execute(
+        &id,
+        vec![Cmd::SetNickname {
+            nickname: Some("Ally".into()),
+        }],
+        EventContext::default(),
+    )
+    .await?;
+    assert_eq!(event_repo.current_version::<Customer>(&id).await?, 5);
+    Ok(())
+}
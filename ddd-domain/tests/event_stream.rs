@@ -0,0 +1,75 @@
+#![cfg(all(feature = "testing", feature = "eventing"))]
+use chrono::{Duration, Utc};
+use ddd_domain::persist::{
+    EVENT_STREAM_PAGE_SIZE, EventRepository, EventStreamReader, SerializedEvent,
+};
+use ddd_domain::testing::InMemoryEventRepository;
+use futures_util::StreamExt;
+
+/// 三个聚合交替写入，发生时间依次递增
+async fn seed(repo: &InMemoryEventRepository, total: usize) -> Vec<String> {
+    let start = Utc::now();
+    let events: Vec<_> = (0..total)
+        .map(|i| {
+            SerializedEvent::builder()
+                .event_id(format!("e-{i}"))
+                .event_type("ledger.posted".to_string())
+                .event_version(1)
+                .aggregate_id(format!("l-{}", i % 3))
+                .aggregate_type("ledger".to_string())
+                .aggregate_version(i / 3 + 1)
+                .occurred_at(start + Duration::milliseconds(i as i64))
+                .payload(serde_json::json!({ "n": i }))
+                .context(serde_json::json!({}))
+                .build()
+        })
+        .collect();
+    let ids = events.iter().map(|e| e.event_id().to_string()).collect();
+    repo.save(events).await.unwrap();
+    ids
+}
+
+async fn collect_ids(repo: &InMemoryEventRepository, from: u64) -> Vec<String> {
+    repo.stream_all(from)
+        .map(|e| e.unwrap().event_id().to_string())
+        .collect()
+        .await
+}
+
+#[tokio::test]
+async fn streams_all_aggregates_in_global_order_across_pages() {
+    let repo = InMemoryEventRepository::new();
+    let total = EVENT_STREAM_PAGE_SIZE * 2 + 7;
+    let ids = seed(&repo, total).await;
+
+    assert_eq!(collect_ids(&repo, 0).await, ids);
+
+    // 从中间位点（含）开始，跨越页边界
+    let from = EVENT_STREAM_PAGE_SIZE as u64;
+    assert_eq!(collect_ids(&repo, from).await, ids[from as usize - 1..]);
+
+    // 超过末尾时流为空
+    assert!(collect_ids(&repo, total as u64 + 1).await.is_empty());
+}
+
+#[tokio::test]
+async fn page_boundary_ends_stream_without_extra_events() {
+    let repo = InMemoryEventRepository::new();
+    let ids = seed(&repo, EVENT_STREAM_PAGE_SIZE).await;
+
+    assert_eq!(collect_ids(&repo, 0).await, ids);
+    let page = repo.read_page(1, 10).await.unwrap();
+    assert_eq!(page.len(), 10);
+    assert_eq!(page[9].sequence_number(), Some(10));
+}
+
+#[tokio::test]
+async fn excluded_events_are_not_streamed() {
+    let repo = InMemoryEventRepository::new();
+    let ids = seed(&repo, 6).await;
+    repo.mark_excluded(&ids[2], "poison", "ops").await.unwrap();
+
+    let streamed = collect_ids(&repo, 0).await;
+    assert_eq!(streamed.len(), 5);
+    assert!(!streamed.contains(&ids[2]));
+}
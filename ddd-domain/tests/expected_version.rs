@@ -0,0 +1,195 @@
+#![cfg(feature = "testing")]
+use anyhow::Result as AnyResult;
+use async_trait::async_trait;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::domain_event::{EventContext, EventEnvelope};
+use ddd_domain::entity::Entity;
+use ddd_domain::error::{DomainError, DomainResult, ErrorKind};
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::persist::{
+    AggregateRepository, EventRepository, EventSourcedRepo, SerializedEvent, SnapshotPolicy,
+    SnapshotPolicyRepo, SnapshotRepositoryWithPolicy, serialize_events,
+};
+use ddd_domain::testing::{InMemoryEventRepository, InMemorySnapshotRepository};
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Counter {
+    value: i64,
+}
+
+#[derive(Debug)]
+enum Cmd {
+    Add(i64),
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Evt {
+    #[event(event_type = "counter.added")]
+    Added { by: i64 },
+}
+
+impl Aggregate for Counter {
+    const TYPE: &'static str = "counter";
+    type Command = Cmd;
+    type Event = Evt;
+    type Error = DomainError;
+
+    fn execute(&self, command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        let Cmd::Add(by) = command;
+        Ok(vec![Evt::Added {
+            id: ulid::Ulid::new().to_string(),
+            aggregate_version: self.version().next(),
+            by,
+        }])
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        let Evt::Added {
+            aggregate_version,
+            by,
+            ..
+        } = event;
+        self.value += by;
+        self.version = *aggregate_version;
+    }
+}
+
+/// 自身不做版本校验的仓储（如演示用的简单实现）
+#[derive(Default, Clone)]
+struct AppendOnly {
+    inner: Arc<Mutex<HashMap<String, Vec<SerializedEvent>>>>,
+}
+
+#[async_trait]
+impl EventRepository for AppendOnly {
+    async fn get_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+    ) -> DomainResult<Vec<SerializedEvent>> {
+        Ok(self
+            .inner
+            .lock()
+            .unwrap()
+            .get(&aggregate_id.to_string())
+            .cloned()
+            .unwrap_or_default())
+    }
+
+    async fn get_last_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        last_version: usize,
+    ) -> DomainResult<Vec<SerializedEvent>> {
+        let events = self.get_events::<A>(aggregate_id).await?;
+        Ok(events
+            .into_iter()
+            .filter(|e| e.aggregate_version() > last_version)
+            .collect())
+    }
+
+    async fn save(&self, events: Vec<SerializedEvent>) -> DomainResult<()> {
+        let mut inner = self.inner.lock().unwrap();
+        for e in events {
+            inner
+                .entry(e.aggregate_id().to_string())
+                .or_default()
+                .push(e);
+        }
+        Ok(())
+    }
+}
+
+fn decide(counter: &mut Counter, by: i64) -> Vec<Evt> {
+    let events = counter.execute(Cmd::Add(by)).unwrap();
+    events.iter().for_each(|e| counter.apply(e));
+    events
+}
+
+/// 两个会话基于同一版本加载聚合，后提交者收到版本冲突
+async fn assert_stale_save_rejected<R>(repo: &R) -> AnyResult<()>
+where
+    R: AggregateRepository<Counter>,
+{
+    let id = "c-1".to_string();
+    let mut seed = Counter::new(id.clone(), Default::default());
+    let events = decide(&mut seed, 1);
+    repo.save(&seed, events, EventContext::default()).await?;
+
+    let mut first = repo.load(&id).await?.unwrap();
+    let mut second = repo.load(&id).await?.unwrap();
+
+    let events = decide(&mut first, 10);
+    repo.save(&first, events, EventContext::default()).await?;
+
+    let events = decide(&mut second, 100);
+    let err = repo
+        .save(&second, events, EventContext::default())
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Conflict);
+
+    let reloaded = repo.load(&id).await?.unwrap();
+    assert_eq!(reloaded.value, 11);
+    assert_eq!(reloaded.version().value(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn event_sourced_repo_rejects_interleaved_writes_on_plain_store() -> AnyResult<()> {
+    let repo = EventSourcedRepo::new(
+        Arc::new(AppendOnly::default()),
+        Arc::new(EventUpcasterChain::default()),
+    );
+    assert_stale_save_rejected(&repo).await
+}
+
+#[tokio::test]
+async fn snapshot_policy_repo_passes_loaded_version_through() -> AnyResult<()> {
+    let repo = SnapshotPolicyRepo::new(
+        Arc::new(InMemoryEventRepository::new()),
+        Arc::new(SnapshotRepositoryWithPolicy::new(
+            InMemorySnapshotRepository::new(),
+            SnapshotPolicy::Every(1),
+        )),
+        Arc::new(EventUpcasterChain::default()),
+    );
+    assert_stale_save_rejected(&repo).await
+}
+
+#[tokio::test]
+async fn save_with_expected_version_validates_batch() -> AnyResult<()> {
+    let events = InMemoryEventRepository::new();
+    let id = "c-1".to_string();
+    let mut counter = Counter::new(id.clone(), Default::default());
+    let envelopes = decide(&mut counter, 1)
+        .into_iter()
+        .map(|e| EventEnvelope::<Counter>::new(&id, e, EventContext::default()))
+        .collect::<Vec<_>>();
+    let batch = serialize_events(&envelopes)?;
+
+    // 期望版本与存储不符
+    let err = events
+        .save_with_expected_version::<Counter>(&id, batch.clone(), 1)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Conflict);
+
+    // 事件不属于该聚合
+    let err = events
+        .save_with_expected_version::<Counter>(&"c-2".to_string(), batch.clone(), 0)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::InvalidValue);
+
+    events
+        .save_with_expected_version::<Counter>(&id, batch, 0)
+        .await?;
+    assert_eq!(events.current_version::<Counter>(&id).await?, 1);
+    Ok(())
+}
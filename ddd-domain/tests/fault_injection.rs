@@ -0,0 +1,79 @@
+#![cfg(all(feature = "testing", feature = "eventing"))]
+use anyhow::Result as AnyResult;
+use chrono::Utc;
+use ddd_domain::error::ErrorKind;
+use ddd_domain::eventing::{EventBus, InMemoryEventBus};
+use ddd_domain::persist::{EventRepository, SerializedEvent};
+use ddd_domain::testing::{FaultSchedule, FlakyBus, FlakyRepository, InMemoryEventRepository};
+use futures_util::StreamExt;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+fn event(version: usize) -> SerializedEvent {
+    SerializedEvent::builder()
+        .event_id(format!("e-{version}"))
+        .event_type("counter.incremented".to_string())
+        .event_version(1)
+        .aggregate_id("c-1".to_string())
+        .aggregate_type("counter".to_string())
+        .aggregate_version(version)
+        .occurred_at(Utc::now())
+        .payload(serde_json::json!({}))
+        .context(serde_json::json!({}))
+        .build()
+}
+
+#[tokio::test]
+async fn bus_fails_first_publishes_then_recovers() -> AnyResult<()> {
+    let bus = FlakyBus::new(
+        Arc::new(InMemoryEventBus::new(16)),
+        FaultSchedule::none().fail_first(2),
+    );
+    let mut stream = bus.subscribe().await;
+
+    // 调用方按需重试，直到发布成功
+    let mut attempts = 0;
+    loop {
+        attempts += 1;
+        match bus.publish(&event(1)).await {
+            Ok(()) => break,
+            Err(err) => assert!(err.matches(ErrorKind::Internal, "INJECTED_FAULT")),
+        }
+    }
+    assert_eq!(attempts, 3);
+    assert_eq!(bus.publish_calls(), 3);
+    assert_eq!(bus.injected_failures(), 2);
+    // 失败的发布不会转发给内部总线
+    assert_eq!(stream.next().await.unwrap()?.event_id(), "e-1");
+
+    bus.set_schedule(FaultSchedule::none().fail_on([4]));
+    assert!(bus.publish_batch(&[event(2), event(3)]).await.is_err());
+    assert!(bus.publish(&event(2)).await.is_ok());
+    Ok(())
+}
+
+#[tokio::test]
+async fn repository_fails_every_kth_save_with_latency() -> AnyResult<()> {
+    let inner = Arc::new(InMemoryEventRepository::new());
+    let repo = FlakyRepository::new(
+        inner.clone(),
+        FaultSchedule::none()
+            .fail_every(2)
+            .with_error_kind(ErrorKind::Conflict)
+            .with_latency(Duration::from_millis(5)),
+    );
+
+    let started = Instant::now();
+    repo.save(vec![event(1)]).await?;
+    let err = repo.save(vec![event(2)]).await.unwrap_err();
+    assert!(err.kind().is_retryable());
+    repo.save(vec![event(2)]).await?;
+    assert!(repo.save(vec![event(3)]).await.is_err());
+    assert!(started.elapsed() >= Duration::from_millis(20));
+
+    assert_eq!(repo.save_calls(), 4);
+    assert_eq!(repo.injected_failures(), 2);
+    assert_eq!(inner.len(), 2);
+
+    Ok(())
+}
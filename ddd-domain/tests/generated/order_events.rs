@@ -0,0 +1,43 @@
+// @generated 由 `ddd_domain::codegen` 根据事件 JSON Schema 生成，请勿手工修改。
+
+#[::ddd_macros::domain_event]
+#[derive(Debug, Clone, PartialEq, ::serde::Serialize, ::serde::Deserialize)]
+pub enum OrderEvent {
+    Archived,
+    #[event(event_type = "order.cancelled")]
+    Cancelled {
+        reason: String,
+        r#type: String,
+    },
+    /// 订单已提交
+    #[event(event_version = 2)]
+    Placed {
+        /// 金额（分）
+        amount: i64,
+        currency: String,
+        lines: Vec<String>,
+        note: Option<String>,
+    },
+}
+
+/// `OrderEvent.Placed` v1 -> v2 的上抬骨架（由 Schema 差异生成，按需补充迁移逻辑）
+#[::ddd_macros::upcaster(event_type = "OrderEvent.Placed", from = 1, to = 2, variant = "Placed")]
+pub fn order_event_placed_v1_to_v2(payload: &mut ::serde_json::Value) {
+    let Some(fields) = payload.as_object_mut() else {
+        return;
+    };
+    // TODO: 字段 `amount` 类型由 `f64` 变更为 `i64`，需补充转换
+    // 新增字段 `currency`：以类型默认值补齐
+    fields.insert("currency".to_string(), ::serde_json::json!(""));
+    // 新增字段 `lines`：以类型默认值补齐
+    fields.insert("lines".to_string(), ::serde_json::json!([]));
+    // 移除字段 `coupon`
+    fields.remove("coupon");
+}
+
+/// `OrderEvent` 的全部生成上抬器
+pub fn order_event_upcasters() -> Vec<::std::sync::Arc<dyn ::ddd_domain::event_upcaster::EventUpcaster>> {
+    vec![
+        ::std::sync::Arc::new(OrderEventPlacedV1ToV2),
+    ]
+}
@@ -0,0 +1,250 @@
+#![cfg(feature = "testing")]
+use anyhow::Result as AnyResult;
+use async_trait::async_trait;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::aggregate_root::AggregateRoot;
+use ddd_domain::domain_event::{EventContext, next_event_id};
+use ddd_domain::entity::Entity;
+use ddd_domain::error::{DomainError, DomainResult};
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::persist::{
+    AggregateRepository, BackgroundSnapshotter, EventRepository, LifecycleEventKind,
+    LifecycleEventSink, LifecycleEvents, SerializedEvent, SnapshotPolicy, SnapshotPolicyRepo,
+    SnapshotRepository, SnapshotRepositoryWithPolicy,
+};
+use ddd_domain::testing::{InMemoryEventRepository, InMemorySnapshotRepository};
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Counter {
+    value: i64,
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum CounterEvent {
+    #[event(event_type = "counter.incremented")]
+    Incremented { by: i64 },
+}
+
+impl Aggregate for Counter {
+    const TYPE: &'static str = "counter";
+    type Command = i64;
+    type Event = CounterEvent;
+    type Error = DomainError;
+
+    fn execute(&self, by: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        Ok(vec![CounterEvent::Incremented {
+            id: next_event_id(),
+            aggregate_version: self.version().next(),
+            by,
+        }])
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        let CounterEvent::Incremented {
+            aggregate_version,
+            by,
+            ..
+        } = event;
+        self.value += by;
+        self.version = *aggregate_version;
+    }
+}
+
+#[derive(Default)]
+struct SystemStream {
+    events: Mutex<Vec<SerializedEvent>>,
+}
+
+impl SystemStream {
+    fn event_types(&self) -> Vec<String> {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .map(|e| e.event_type().to_string())
+            .collect()
+    }
+}
+
+#[async_trait]
+impl LifecycleEventSink for SystemStream {
+    async fn append(&self, events: Vec<SerializedEvent>) -> DomainResult<()> {
+        self.events.lock().unwrap().extend(events);
+        Ok(())
+    }
+}
+
+/// 始终写入失败的系统事件流
+struct BrokenStream;
+
+#[async_trait]
+impl LifecycleEventSink for BrokenStream {
+    async fn append(&self, _events: Vec<SerializedEvent>) -> DomainResult<()> {
+        Err(DomainError::internal("system stream unavailable"))
+    }
+}
+
+fn repo(
+    lifecycle: LifecycleEvents,
+) -> SnapshotPolicyRepo<InMemoryEventRepository, InMemorySnapshotRepository> {
+    SnapshotPolicyRepo::new(
+        Arc::new(InMemoryEventRepository::new()),
+        Arc::new(SnapshotRepositoryWithPolicy::new(
+            InMemorySnapshotRepository::new(),
+            SnapshotPolicy::Every(2),
+        )),
+        Arc::new(EventUpcasterChain::default()),
+    )
+    .with_lifecycle_events(lifecycle)
+}
+
+/// 后台快照只约束 `with_background_snapshots`，仓储实现仍只要求原有约束（无 `'static`）
+fn _as_repository<A, E, S>(repo: &SnapshotPolicyRepo<E, S>) -> &dyn AggregateRepository<A>
+where
+    A: Aggregate,
+    E: EventRepository + Send + Sync,
+    S: SnapshotRepository + Send + Sync,
+    A::Error: From<DomainError> + Send + Sync,
+{
+    repo
+}
+
+#[tokio::test]
+async fn emits_created_and_snapshot_taken_into_system_stream() -> AnyResult<()> {
+    let stream = Arc::new(SystemStream::default());
+    let root = AggregateRoot::<Counter, _>::new(repo(LifecycleEvents::new(stream.clone())));
+    let id = "c-1".to_string();
+    let ctx = EventContext::builder()
+        .correlation_id("cor-1".into())
+        .build();
+
+    for by in 1..=4 {
+        root.execute(&id, vec![by], ctx.clone()).await?;
+    }
+
+    assert_eq!(
+        stream.event_types(),
+        vec![
+            "counter.created",
+            "counter.snapshot_taken",
+            "counter.snapshot_taken"
+        ]
+    );
+
+    let events = stream.events.lock().unwrap().clone();
+    assert_eq!(events[0].aggregate_id(), "c-1");
+    assert_eq!(events[0].correlation_id(), Some("cor-1"));
+    assert_eq!(events[0].payload()["event_type"], "counter.incremented");
+    assert_eq!(events[2].payload()["snapshot_version"], 4);
+
+    let counter = root.load(&id).await?.unwrap();
+    let lifecycle = LifecycleEvents::new(stream.clone());
+    lifecycle
+        .archived(&counter, &EventContext::default())
+        .await?;
+    assert_eq!(stream.event_types().last().unwrap(), "counter.archived");
+    Ok(())
+}
+
+#[tokio::test]
+async fn lifecycle_failures_do_not_fail_the_command() -> AnyResult<()> {
+    let root = AggregateRoot::<Counter, _>::new(repo(LifecycleEvents::new(Arc::new(BrokenStream))));
+    let id = "c-1".to_string();
+
+    for by in 1..=2 {
+        root.execute(&id, vec![by], EventContext::default()).await?;
+    }
+
+    assert_eq!(root.load(&id).await?.unwrap().version().value(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn disabled_kinds_are_not_emitted() -> AnyResult<()> {
+    let stream = Arc::new(SystemStream::default());
+    let lifecycle = LifecycleEvents::new(stream.clone()).disable(LifecycleEventKind::SnapshotTaken);
+    let root = AggregateRoot::<Counter, _>::new(repo(lifecycle));
+    let id = "c-1".to_string();
+
+    for by in 1..=4 {
+        root.execute(&id, vec![by], EventContext::default()).await?;
+    }
+
+    assert_eq!(stream.event_types(), vec!["counter.created"]);
+    Ok(())
+}
+
+#[tokio::test]
+async fn background_snapshots_land_after_flush() -> AnyResult<()> {
+    let stream = Arc::new(SystemStream::default());
+    let snapshots = Arc::new(SnapshotRepositoryWithPolicy::new(
+        InMemorySnapshotRepository::new(),
+        SnapshotPolicy::Every(2),
+    ));
+    let background = Arc::new(BackgroundSnapshotter::new(16));
+    let repo = SnapshotPolicyRepo::new(
+        Arc::new(InMemoryEventRepository::new()),
+        snapshots.clone(),
+        Arc::new(EventUpcasterChain::default()),
+    )
+    .with_lifecycle_events(LifecycleEvents::new(stream.clone()))
+    .with_background_snapshots(background.clone());
+    let root = AggregateRoot::<Counter, _>::new(repo);
+    let id = "c-1".to_string();
+
+    for by in 1..=4 {
+        root.execute(&id, vec![by], EventContext::default()).await?;
+    }
+    background.flush().await;
+
+    let snapshot = snapshots.get_snapshot::<Counter>(&id, None).await?.unwrap();
+    assert_eq!(snapshot.aggregate_version(), 4);
+    assert_eq!(
+        stream.event_types().last().unwrap(),
+        "counter.snapshot_taken"
+    );
+
+    // 只有满足策略的版本（2、4）入队
+    let stats = background.stats();
+    assert_eq!(stats.enqueued, 2);
+    assert_eq!(stats.completed + stats.coalesced, 2);
+    assert_eq!(background.pending_len(), 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn background_snapshots_coalesce_per_aggregate_and_bound_queue() {
+    let background = BackgroundSnapshotter::new(2);
+    let written = Arc::new(Mutex::new(Vec::new()));
+    let job = |id: &'static str, version: usize| {
+        let written = written.clone();
+        Box::pin(async move {
+            written.lock().unwrap().push((id, version));
+            Ok(())
+        })
+    };
+
+    // 未让出执行权前后台任务不会运行，请求全部排队
+    assert!(background.enqueue("counter", "c-1", job("c-1", 1)));
+    assert!(background.enqueue("counter", "c-2", job("c-2", 1)));
+    assert!(background.enqueue("counter", "c-1", job("c-1", 2)));
+    assert!(!background.enqueue("counter", "c-3", job("c-3", 1)));
+    background.flush().await;
+
+    assert_eq!(*written.lock().unwrap(), vec![("c-1", 2), ("c-2", 1)]);
+    let stats = background.stats();
+    assert_eq!(
+        (
+            stats.enqueued,
+            stats.coalesced,
+            stats.dropped,
+            stats.completed
+        ),
+        (4, 1, 1, 2)
+    );
+}
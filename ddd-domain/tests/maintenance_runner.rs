@@ -0,0 +1,196 @@
+#![cfg(all(feature = "testing", feature = "eventing"))]
+use anyhow::Result as AnyResult;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::aggregate_root::AggregateRoot;
+use ddd_domain::domain_event::EventContext;
+use ddd_domain::entity::Entity;
+use ddd_domain::error::DomainError;
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::persist::{
+    AggregateIdLister, AggregateRepository, EventSourcedRepo, InMemoryMaintenanceCheckpointStore,
+    MaintenanceCheckpointStore, MaintenanceConfig, MaintenanceRunner,
+};
+use ddd_domain::testing::InMemoryEventRepository;
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::{Duration, Instant};
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Order {
+    total: i64,
+    total_with_tax: i64,
+}
+
+#[derive(Debug)]
+enum Cmd {
+    Place { total: i64 },
+    RecomputeTax,
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Evt {
+    Placed { total: i64 },
+    TaxRecomputed { total_with_tax: i64 },
+}
+
+impl Aggregate for Order {
+    const TYPE: &'static str = "order";
+    type Command = Cmd;
+    type Event = Evt;
+    type Error = DomainError;
+
+    fn execute(&self, command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        let id = ulid::Ulid::new().to_string();
+        let aggregate_version = self.version().next();
+        match command {
+            Cmd::Place { total } => Ok(vec![Evt::Placed {
+                id,
+                aggregate_version,
+                total,
+            }]),
+            Cmd::RecomputeTax if self.total < 0 => {
+                Err(DomainError::invalid_state("negative total"))
+            }
+            Cmd::RecomputeTax => Ok(vec![Evt::TaxRecomputed {
+                id,
+                aggregate_version,
+                total_with_tax: self.total * 11 / 10,
+            }]),
+        }
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        match event {
+            Evt::Placed {
+                aggregate_version,
+                total,
+                ..
+            } => {
+                self.total = *total;
+                self.version = *aggregate_version;
+            }
+            Evt::TaxRecomputed {
+                aggregate_version,
+                total_with_tax,
+                ..
+            } => {
+                self.total_with_tax = *total_with_tax;
+                self.version = *aggregate_version;
+            }
+        }
+    }
+}
+
+type Repo = EventSourcedRepo<InMemoryEventRepository>;
+
+async fn seed(totals: &[(&str, i64)]) -> AnyResult<(Arc<InMemoryEventRepository>, Arc<Repo>)> {
+    let events = Arc::new(InMemoryEventRepository::new());
+    let repo = Arc::new(EventSourcedRepo::new(
+        events.clone(),
+        Arc::new(EventUpcasterChain::default()),
+    ));
+    let root = AggregateRoot::<Order, _>::new(repo.clone());
+    for (id, total) in totals {
+        root.execute(
+            &id.to_string(),
+            vec![Cmd::Place { total: *total }],
+            EventContext::default(),
+        )
+        .await?;
+    }
+    Ok((events, repo))
+}
+
+#[tokio::test]
+async fn lists_aggregate_ids_in_pages() -> AnyResult<()> {
+    let (events, _) = seed(&[("o-3", 1), ("o-1", 1), ("o-2", 1)]).await?;
+
+    assert_eq!(
+        events.list_aggregate_ids::<Order>(None, 2).await?,
+        vec!["o-1", "o-2"]
+    );
+    assert_eq!(
+        events.list_aggregate_ids::<Order>(Some("o-2"), 2).await?,
+        vec!["o-3"]
+    );
+    assert!(
+        events
+            .list_aggregate_ids::<Order>(Some("o-3"), 2)
+            .await?
+            .is_empty()
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn migrates_every_aggregate_and_reports_progress() -> AnyResult<()> {
+    let (events, repo) = seed(&[("o-1", 100), ("o-2", -5), ("o-3", 0), ("o-4", 50)]).await?;
+    let progress = Arc::new(Mutex::new(Vec::new()));
+    let seen = progress.clone();
+    let runner = MaintenanceRunner::new("recompute-tax", events, repo.clone())
+        .with_config(MaintenanceConfig {
+            batch_size: 3,
+            concurrency: 2,
+            rate_per_second: None,
+        })
+        .with_progress(move |p| seen.lock().unwrap().push(p.checkpoint.clone()));
+
+    let summary = runner
+        .run::<Order, _>(|order| (order.total != 0).then_some(Cmd::RecomputeTax))
+        .await?;
+
+    assert_eq!(summary.processed, 4);
+    assert_eq!(summary.migrated, 2);
+    assert_eq!(summary.unchanged, 1);
+    assert_eq!(summary.failures.len(), 1);
+    assert_eq!(summary.failures[0].aggregate_id, "o-2");
+    assert_eq!(*progress.lock().unwrap(), vec!["o-3", "o-4"]);
+
+    let order: Order = repo.load(&"o-1".to_string()).await?.unwrap();
+    assert_eq!(order.total_with_tax, 110);
+    assert_eq!(order.version().value(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn resumes_from_checkpoint() -> AnyResult<()> {
+    let (events, repo) = seed(&[("o-1", 10), ("o-2", 20), ("o-3", 30)]).await?;
+    let checkpoints = Arc::new(InMemoryMaintenanceCheckpointStore::new());
+    checkpoints.save("recompute-tax", "o-1").await?;
+    let runner = MaintenanceRunner::new("recompute-tax", events, repo.clone())
+        .with_checkpoints(checkpoints.clone());
+
+    let summary = runner.run::<Order, _>(|_| Some(Cmd::RecomputeTax)).await?;
+    assert_eq!(summary.migrated, 2);
+    let untouched: Order = repo.load(&"o-1".to_string()).await?.unwrap();
+    assert_eq!(untouched.total_with_tax, 0);
+    assert_eq!(
+        checkpoints.load("recompute-tax").await?.as_deref(),
+        Some("o-3")
+    );
+
+    // 已完成的任务再次运行不会重复迁移
+    let summary = runner.run::<Order, _>(|_| Some(Cmd::RecomputeTax)).await?;
+    assert_eq!(summary.processed, 0);
+    Ok(())
+}
+
+#[tokio::test]
+async fn throttles_to_configured_rate() -> AnyResult<()> {
+    let (events, repo) = seed(&[("o-1", 1), ("o-2", 1), ("o-3", 1)]).await?;
+    let runner = MaintenanceRunner::new("touch", events, repo).with_config(MaintenanceConfig {
+        batch_size: 10,
+        concurrency: 3,
+        rate_per_second: Some(20),
+    });
+
+    let started = Instant::now();
+    let summary = runner.run::<Order, _>(|_| None).await?;
+    assert_eq!(summary.unchanged, 3);
+    // 第一个立即开始，其后每 50ms 开始一个
+    assert!(started.elapsed() >= Duration::from_millis(100));
+    Ok(())
+}
@@ -0,0 +1,205 @@
+#![cfg(feature = "infra-sqlx")]
+//! 需要 `DATABASE_URL` 指向可创建测试库的 Postgres 实例，默认忽略，
+//! 以 `cargo test --features infra-sqlx -- --ignored` 运行
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::aggregate_root::AggregateRoot;
+use ddd_domain::domain_event::{DomainEvent, EventContext};
+use ddd_domain::entity::Entity;
+use ddd_domain::error::{DomainError, ErrorKind};
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::eventing::{ReplaySource, SubscribeFrom};
+use ddd_domain::persist::{
+    AggregateRepository, EventRepository, EventSourcedRepo, EventStreamReader, PgEventRepository,
+};
+use ddd_macros::{domain_event, entity};
+use futures_util::StreamExt;
+use serde::{Deserialize, Serialize};
+use sqlx::PgPool;
+use std::sync::Arc;
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Counter {
+    value: i64,
+}
+
+#[derive(Debug)]
+enum Cmd {
+    Add { by: i64 },
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Evt {
+    #[event(event_type = "counter.added")]
+    Added { by: i64 },
+}
+
+impl Aggregate for Counter {
+    const TYPE: &'static str = "counter";
+    type Command = Cmd;
+    type Event = Evt;
+    type Error = DomainError;
+
+    fn execute(&self, command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        let Cmd::Add { by } = command;
+        Ok(vec![Evt::Added {
+            id: ulid::Ulid::new().to_string(),
+            aggregate_version: self.version().next(),
+            by,
+        }])
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        let Evt::Added {
+            aggregate_version,
+            by,
+            ..
+        } = event;
+        self.value += by;
+        self.version = *aggregate_version;
+    }
+}
+
+async fn setup(pool: PgPool) -> Arc<PgEventRepository> {
+    let events = Arc::new(PgEventRepository::new(pool));
+    events.migrate().await.unwrap();
+    // 迁移可重复执行
+    events.migrate().await.unwrap();
+    events
+}
+
+#[sqlx::test(migrations = false)]
+#[ignore = "requires DATABASE_URL pointing at Postgres"]
+async fn appends_events_with_sequence_numbers_and_optimistic_concurrency(pool: PgPool) {
+    let events = setup(pool).await;
+    events.ping().await.unwrap();
+    let repo = Arc::new(EventSourcedRepo::new(
+        events.clone(),
+        Arc::new(EventUpcasterChain::default()),
+    ));
+    let root = AggregateRoot::<Counter, _>::new(repo.clone());
+    let id = "c-1".to_string();
+
+    root.execute(
+        &id,
+        vec![Cmd::Add { by: 1 }, Cmd::Add { by: 2 }],
+        EventContext::default(),
+    )
+    .await
+    .unwrap();
+    root.execute(
+        &"c-2".to_string(),
+        vec![Cmd::Add { by: 5 }],
+        EventContext::default(),
+    )
+    .await
+    .unwrap();
+
+    let counter: Counter = repo.load(&id).await.unwrap().unwrap();
+    assert_eq!(counter.value, 3);
+    assert_eq!(events.current_version::<Counter>(&id).await.unwrap(), 2);
+    assert_eq!(
+        events
+            .exists_many::<Counter>(&["c-2".to_string(), "c-9".to_string(), id.clone()])
+            .await
+            .unwrap(),
+        [true, false, true]
+    );
+
+    let stored = events.get_events::<Counter>(&id).await.unwrap();
+    let sequences: Vec<_> = stored.iter().map(|e| e.sequence_number()).collect();
+    assert!(sequences.iter().all(Option::is_some));
+    assert!(sequences.windows(2).all(|w| w[0] < w[1]));
+    assert_eq!(
+        events
+            .get_last_events::<Counter>(&id, 1)
+            .await
+            .unwrap()
+            .len(),
+        1
+    );
+
+    // 以过期版本保存：整批拒绝，不产生部分写入
+    let stale = stored[1].clone();
+    let err = events.save(vec![stale.clone()]).await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Conflict);
+    let err = events
+        .save_with_expected_version::<Counter>(&id, vec![stale], 1)
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Conflict);
+    assert_eq!(events.current_version::<Counter>(&id).await.unwrap(), 2);
+}
+
+#[sqlx::test(migrations = false)]
+#[ignore = "requires DATABASE_URL pointing at Postgres"]
+async fn excluded_events_are_skipped_on_read_and_replay(pool: PgPool) {
+    let events = setup(pool).await;
+    let repo = Arc::new(EventSourcedRepo::new(
+        events.clone(),
+        Arc::new(EventUpcasterChain::default()),
+    ));
+    let root = AggregateRoot::<Counter, _>::new(repo);
+    let id = "c-1".to_string();
+    let envelopes = root
+        .execute(
+            &id,
+            vec![Cmd::Add { by: 1 }, Cmd::Add { by: 100 }, Cmd::Add { by: 2 }],
+            EventContext::default(),
+        )
+        .await
+        .unwrap();
+    let wrong = envelopes[1].payload.event_id().to_string();
+    let head = envelopes[2].payload.event_id().to_string();
+
+    let err = events
+        .mark_excluded(&head, "typo", "ops")
+        .await
+        .unwrap_err();
+    assert!(err.matches(ErrorKind::InvalidState, "EXCLUSION_OF_HEAD"));
+    events.mark_excluded(&wrong, "typo", "ops").await.unwrap();
+    let err = events
+        .mark_excluded(&wrong, "typo", "ops")
+        .await
+        .unwrap_err();
+    assert!(err.matches(ErrorKind::InvalidState, "EVENT_ALREADY_EXCLUDED"));
+
+    assert_eq!(events.get_events::<Counter>(&id).await.unwrap().len(), 2);
+    assert_eq!(events.current_version::<Counter>(&id).await.unwrap(), 3);
+    let exclusions = events.exclusions().await.unwrap();
+    assert_eq!(exclusions.len(), 1);
+    assert_eq!(exclusions[0].event_id, wrong);
+
+    let all = events.replay(SubscribeFrom::Sequence(0)).await.unwrap();
+    assert_eq!(all.len(), 2);
+    let from_head = all[1].sequence_number().unwrap() as u64;
+    let tail = events
+        .replay(SubscribeFrom::Sequence(from_head))
+        .await
+        .unwrap();
+    assert_eq!(tail.len(), 1);
+    assert_eq!(tail[0].event_id(), head);
+    assert!(
+        events
+            .replay(SubscribeFrom::Latest)
+            .await
+            .unwrap()
+            .is_empty()
+    );
+
+    let page = events.read_page(0, 1).await.unwrap();
+    assert_eq!(page.len(), 1);
+    assert_eq!(page[0].event_id(), all[0].event_id());
+    let streamed: Vec<_> = events
+        .stream_all(0)
+        .map(|e| e.unwrap().event_id().to_string())
+        .collect()
+        .await;
+    assert_eq!(
+        streamed,
+        all.iter()
+            .map(|e| e.event_id().to_string())
+            .collect::<Vec<_>>()
+    );
+}
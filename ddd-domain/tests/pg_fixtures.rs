@@ -0,0 +1,59 @@
+#![cfg(all(feature = "testing", feature = "infra-sqlx"))]
+//! 需要 `DATABASE_URL` 指向可创建测试库的 Postgres 实例，默认忽略，
+//! 以 `cargo test --features testing,infra-sqlx -- --ignored` 运行
+use chrono::Utc;
+use ddd_domain::error::ErrorKind;
+use ddd_domain::persist::SerializedEvent;
+use ddd_domain::testing::{EVENTS_TABLE, OUTBOX_TABLE, PgTestTx};
+use sqlx::PgPool;
+
+fn event(version: usize) -> SerializedEvent {
+    SerializedEvent::builder()
+        .event_id(format!("e-{version}"))
+        .event_type("counter.incremented".to_string())
+        .event_version(1)
+        .aggregate_id("c-1".to_string())
+        .aggregate_type("counter".to_string())
+        .aggregate_version(version)
+        .occurred_at(Utc::now())
+        .payload(serde_json::json!({"by": version}))
+        .context(serde_json::json!({}))
+        .build()
+}
+
+#[sqlx::test(migrations = false)]
+#[ignore = "requires DATABASE_URL pointing at Postgres"]
+async fn fixtures_roll_back_and_leave_no_residue(pool: PgPool) -> sqlx::Result<()> {
+    let mut tx = PgTestTx::begin(&pool).await.unwrap();
+    let events = [event(1), event(2)];
+    tx.insert_events(&events).await.unwrap();
+    tx.enqueue_outbox(&events).await.unwrap();
+
+    let stored = tx.events("counter", "c-1").await.unwrap();
+    assert_eq!(stored.len(), 2);
+    assert_eq!(stored[1].payload(), events[1].payload());
+
+    // 版本冲突由唯一约束拒绝
+    assert!(tx.insert_events(&[event(2)]).await.is_err());
+    tx.rollback().await.unwrap();
+
+    let mut tx = PgTestTx::begin(&pool).await.unwrap();
+    tx.enqueue_outbox(&[event(1), event(2)]).await.unwrap();
+    tx.mark_published("e-1").await.unwrap();
+    let err = tx.mark_published("e-1").await.unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::NotFound);
+    assert_eq!(tx.pending_outbox().await.unwrap()[0].event_id(), "e-2");
+    assert_eq!(tx.count(EVENTS_TABLE).await.unwrap(), 0);
+    drop(tx);
+
+    // 丢弃夹具即回滚，表本身也不残留
+    let tables: i64 = sqlx::query_scalar(
+        "SELECT COUNT(*) FROM information_schema.tables WHERE table_name = $1 OR table_name = $2",
+    )
+    .bind(EVENTS_TABLE)
+    .bind(OUTBOX_TABLE)
+    .fetch_one(&pool)
+    .await?;
+    assert_eq!(tables, 0);
+    Ok(())
+}
@@ -0,0 +1,186 @@
+use anyhow::Result as AnyResult;
+use chrono::{Duration, Utc};
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::domain_event::{EventContext, EventEnvelope, next_event_id};
+use ddd_domain::entity::Entity;
+use ddd_domain::error::{DomainError, DomainResult};
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::persist::{
+    PiiCipher, PiiPolicy, PiiRegistry, RedactionAction, SerializedSnapshot, SubjectAccessReporter,
+};
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Customer {
+    #[pii(category = "email")]
+    email: String,
+    tier: String,
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum CustomerEvent {
+    #[event(event_type = "customer.registered")]
+    Registered {
+        #[pii(category = "email")]
+        email: String,
+        #[pii(category = "phone", policy = "tokenize")]
+        phone: String,
+        #[pii(category = "ip", erase_after_days = 30)]
+        signup_ip: Option<String>,
+        tier: String,
+    },
+}
+
+impl Aggregate for Customer {
+    const TYPE: &'static str = "customer";
+    type Command = ();
+    type Event = CustomerEvent;
+    type Error = DomainError;
+
+    fn execute(&self, _command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        Ok(vec![])
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        let CustomerEvent::Registered {
+            aggregate_version,
+            email,
+            tier,
+            ..
+        } = event;
+        self.email = email.clone();
+        self.tier = tier.clone();
+        self.version = *aggregate_version;
+    }
+}
+
+/// 加密为 `enc:<原文>`，令牌化为固定令牌
+struct PrefixCipher;
+
+impl PiiCipher for PrefixCipher {
+    fn encrypt(&self, value: &Value) -> DomainResult<Value> {
+        Ok(format!("enc:{}", value.as_str().unwrap_or_default()).into())
+    }
+
+    fn decrypt(&self, value: &Value) -> DomainResult<Value> {
+        let s = value.as_str().unwrap_or_default();
+        Ok(s.strip_prefix("enc:").unwrap_or(s).into())
+    }
+
+    fn tokenize(&self, _value: &Value) -> DomainResult<Value> {
+        Ok("tok_1".into())
+    }
+}
+
+fn registry() -> PiiRegistry {
+    PiiRegistry::new()
+        .register_event::<CustomerEvent>()
+        .register_state::<Customer>()
+}
+
+fn registered() -> EventEnvelope<Customer> {
+    EventEnvelope::new_with(
+        &"c-1".to_string(),
+        CustomerEvent::Registered {
+            id: next_event_id(),
+            aggregate_version: 1.into(),
+            email: "ada@example.com".into(),
+            phone: "555-0100".into(),
+            signup_ip: Some("10.0.0.1".into()),
+            tier: "gold".into(),
+        },
+        EventContext::builder().actor_id("c-1".into()).build(),
+        Utc::now() - Duration::days(31),
+    )
+}
+
+#[test]
+fn annotations_populate_registry() {
+    let registry = registry();
+    let rules = registry.rules_for("customer.registered");
+    let summary: Vec<_> = rules
+        .iter()
+        .map(|r| (r.pointer.as_str(), r.category.as_deref(), r.policy))
+        .collect();
+    assert_eq!(
+        summary,
+        [
+            ("/Registered/email", Some("email"), PiiPolicy::Encrypt),
+            ("/Registered/phone", Some("phone"), PiiPolicy::Tokenize),
+            (
+                "/Registered/signup_ip",
+                Some("ip"),
+                PiiPolicy::EraseAfter(Duration::days(30))
+            ),
+        ]
+    );
+    assert_eq!(registry.state_rules_for(Customer::TYPE).len(), 1);
+}
+
+#[test]
+fn annotated_event_fields_are_protected_and_redacted() -> AnyResult<()> {
+    let registry = registry();
+    let stored = registry.serialize_events(&PrefixCipher, &[registered()])?;
+    let payload = &stored[0].payload()["Registered"];
+    assert_eq!(payload["email"], "enc:ada@example.com");
+    assert_eq!(payload["phone"], "tok_1");
+    assert_eq!(payload["tier"], "gold");
+
+    let redacted = registry
+        .redact_expired(&stored[0], Utc::now())?
+        .expect("signup ip past retention");
+    assert!(redacted.payload()["Registered"]["signup_ip"].is_null());
+
+    let loaded = registry.deserialize_events::<Customer>(
+        &PrefixCipher,
+        &EventUpcasterChain::default(),
+        vec![redacted],
+    )?;
+    let CustomerEvent::Registered {
+        email, signup_ip, ..
+    } = &loaded[0].payload;
+    assert_eq!((email.as_str(), signup_ip), ("ada@example.com", &None));
+
+    let report = SubjectAccessReporter::new()
+        .with_pii(registry)
+        .report("c-1", stored);
+    let plan: Vec<_> = report
+        .redaction_plan
+        .iter()
+        .map(|s| (s.category.as_deref(), s.action))
+        .collect();
+    assert_eq!(
+        plan,
+        [
+            (Some("email"), RedactionAction::ShredKey),
+            (Some("phone"), RedactionAction::DeleteToken),
+            (Some("ip"), RedactionAction::Erase),
+        ]
+    );
+    Ok(())
+}
+
+#[test]
+fn annotated_state_fields_are_protected_in_snapshots() -> AnyResult<()> {
+    let registry = registry();
+    let mut customer = Customer::new("c-1".to_string(), 1.into());
+    customer.email = "ada@example.com".into();
+    customer.tier = "gold".into();
+
+    let snapshot = registry.protect_snapshot(
+        SerializedSnapshot::from_aggregate(&customer)?,
+        &PrefixCipher,
+    )?;
+    assert_eq!(snapshot.payload()["email"], "enc:ada@example.com");
+    assert_eq!(snapshot.payload()["tier"], "gold");
+
+    let restored: Customer = registry
+        .reveal_snapshot(snapshot, &PrefixCipher)?
+        .to_aggregate()?;
+    assert_eq!(restored.email, "ada@example.com");
+    Ok(())
+}
@@ -0,0 +1,234 @@
+#![cfg(feature = "eventing")]
+use anyhow::Result as AnyResult;
+use async_trait::async_trait;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::aggregate_root::AggregateRoot;
+use ddd_domain::domain_event::EventContext;
+use ddd_domain::entity::Entity;
+use ddd_domain::error::{DomainError, DomainResult};
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::persist::{
+    AggregateRepository, EventRepository, EventSourcedRepo, ReadWriteSplitRepo, SerializedEvent,
+};
+use ddd_domain::value_object::Version;
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Counter {
+    value: i64,
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum CounterEvent {
+    Incr { by: i64 },
+}
+
+impl Aggregate for Counter {
+    const TYPE: &'static str = "counter";
+    type Command = i64;
+    type Event = CounterEvent;
+    type Error = DomainError;
+
+    fn execute(&self, by: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        Ok(vec![CounterEvent::Incr {
+            id: ulid::Ulid::new().to_string(),
+            aggregate_version: self.version().next(),
+            by,
+        }])
+    }
+
+    fn apply(&mut self, e: &Self::Event) {
+        match e {
+            CounterEvent::Incr {
+                aggregate_version,
+                by,
+                ..
+            } => {
+                self.value += *by;
+                self.version = *aggregate_version;
+            }
+        }
+    }
+}
+
+/// 共享存储：主库写入，副本仅可见已“复制”的前 `replicated` 条事件
+#[derive(Default, Clone)]
+struct Store {
+    events: Arc<Mutex<HashMap<String, Vec<SerializedEvent>>>>,
+    replicated: Arc<Mutex<usize>>,
+}
+
+#[derive(Clone)]
+struct Primary(Store);
+
+#[derive(Clone)]
+struct Replica(Store);
+
+impl Store {
+    fn visible(&self, aggregate_id: &str, limit: Option<usize>) -> Vec<SerializedEvent> {
+        let all = self
+            .events
+            .lock()
+            .unwrap()
+            .get(aggregate_id)
+            .cloned()
+            .unwrap_or_default();
+
+        match limit {
+            Some(n) => all.into_iter().take(n).collect(),
+            None => all,
+        }
+    }
+}
+
+#[async_trait]
+impl EventRepository for Primary {
+    async fn get_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+    ) -> DomainResult<Vec<SerializedEvent>> {
+        Ok(self.0.visible(&aggregate_id.to_string(), None))
+    }
+
+    async fn get_last_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        last_version: usize,
+    ) -> DomainResult<Vec<SerializedEvent>> {
+        Ok(self
+            .0
+            .visible(&aggregate_id.to_string(), None)
+            .into_iter()
+            .filter(|e| e.aggregate_version() > last_version)
+            .collect())
+    }
+
+    async fn save(&self, events: Vec<SerializedEvent>) -> DomainResult<()> {
+        let mut g = self.0.events.lock().unwrap();
+        for e in events {
+            g.entry(e.aggregate_id().to_string()).or_default().push(e);
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventRepository for Replica {
+    async fn get_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+    ) -> DomainResult<Vec<SerializedEvent>> {
+        let limit = *self.0.replicated.lock().unwrap();
+        Ok(self.0.visible(&aggregate_id.to_string(), Some(limit)))
+    }
+
+    async fn get_last_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        last_version: usize,
+    ) -> DomainResult<Vec<SerializedEvent>> {
+        let limit = *self.0.replicated.lock().unwrap();
+        Ok(self
+            .0
+            .visible(&aggregate_id.to_string(), Some(limit))
+            .into_iter()
+            .filter(|e| e.aggregate_version() > last_version)
+            .collect())
+    }
+
+    async fn save(&self, _events: Vec<SerializedEvent>) -> DomainResult<()> {
+        Err(DomainError::internal("replica is read-only"))
+    }
+}
+
+#[tokio::test]
+async fn stale_replica_falls_back_to_primary() -> AnyResult<()> {
+    let store = Store::default();
+    let split = Arc::new(ReadWriteSplitRepo::new(
+        Arc::new(Replica(store.clone())),
+        Arc::new(Primary(store.clone())),
+    ));
+    let repo = Arc::new(EventSourcedRepo::new(
+        split.clone(),
+        Arc::new(EventUpcasterChain::default()),
+    ));
+    let root = AggregateRoot::<Counter, _>::new(repo.clone());
+    let id = "c-1".to_string();
+
+    // 写入走主库（副本拒绝写入）
+    root.execute(&id, vec![1, 2, 3], EventContext::default())
+        .await?;
+    assert_eq!(split.written_version::<Counter>(&id), Some(3));
+
+    // 副本尚未复制任何事件：回退主库
+    let loaded: Counter = repo.load(&id).await?.unwrap();
+    assert_eq!(loaded.version(), Version::from_value(3));
+    assert_eq!(loaded.value, 6);
+
+    // 快速查询同样回退主库
+    assert_eq!(split.current_version::<Counter>(&id).await?, 3);
+    assert!(split.exists::<Counter>(&id).await?);
+    assert!(!split.exists::<Counter>(&"missing".to_string()).await?);
+
+    // 副本追平后直接读取副本
+    *store.replicated.lock().unwrap() = 3;
+    let from_replica = split.get_events::<Counter>(&id).await?;
+    assert_eq!(from_replica.len(), 3);
+
+    // 副本部分落后：增量读取同样回退主库
+    root.execute(&id, vec![4], EventContext::default()).await?;
+    let tail = split.get_last_events::<Counter>(&id, 2).await?;
+    assert_eq!(
+        tail.iter()
+            .map(|e| e.aggregate_version())
+            .collect::<Vec<_>>(),
+        vec![3, 4]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn written_versions_expire_after_replication_lag() -> AnyResult<()> {
+    let store = Store::default();
+    let split = Arc::new(
+        ReadWriteSplitRepo::new(
+            Arc::new(Replica(store.clone())),
+            Arc::new(Primary(store.clone())),
+        )
+        .with_replication_lag(Duration::from_millis(50)),
+    );
+    let root = AggregateRoot::<Counter, _>::new(Arc::new(EventSourcedRepo::new(
+        split.clone(),
+        Arc::new(EventUpcasterChain::default()),
+    )));
+
+    root.execute(&"c-1".to_string(), vec![1], EventContext::default())
+        .await?;
+    assert_eq!(
+        split.written_version::<Counter>(&"c-1".to_string()),
+        Some(1)
+    );
+
+    // 窗口过后写入记录过期：读取不再回退主库，新的写入顺带清理其他过期条目
+    tokio::time::sleep(Duration::from_millis(80)).await;
+    assert_eq!(split.written_version::<Counter>(&"c-1".to_string()), None);
+    root.execute(&"c-2".to_string(), vec![1], EventContext::default())
+        .await?;
+    tokio::time::sleep(Duration::from_millis(80)).await;
+    root.execute(&"c-3".to_string(), vec![1], EventContext::default())
+        .await?;
+    assert_eq!(split.tracked_aggregates(), 1);
+    assert!(
+        split
+            .get_events::<Counter>(&"c-2".to_string())
+            .await?
+            .is_empty()
+    );
+    Ok(())
+}
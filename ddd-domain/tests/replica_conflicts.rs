@@ -0,0 +1,233 @@
+#![cfg(feature = "testing")]
+use anyhow::Result as AnyResult;
+use async_trait::async_trait;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::aggregate_root::AggregateRoot;
+use ddd_domain::domain_event::EventContext;
+use ddd_domain::entity::Entity;
+use ddd_domain::error::{DomainError, DomainResult, ErrorCode};
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::persist::{
+    AggregateRepository, ConflictResolution, ConflictResolver, DivergentHistory, EventRepository,
+    EventSourcedRepo, LastWriterWins, ReplicaClock, SerializedEvent, detect_divergence,
+};
+use ddd_domain::testing::InMemoryEventRepository;
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Profile {
+    name: String,
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum ProfileEvent {
+    Renamed { name: String },
+}
+
+impl Aggregate for Profile {
+    const TYPE: &'static str = "profile";
+    type Command = String;
+    type Event = ProfileEvent;
+    type Error = DomainError;
+
+    fn execute(&self, name: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        Ok(vec![ProfileEvent::Renamed {
+            id: ulid::Ulid::new().to_string(),
+            aggregate_version: self.version().next(),
+            name,
+        }])
+    }
+
+    fn apply(&mut self, e: &Self::Event) {
+        let ProfileEvent::Renamed {
+            aggregate_version,
+            name,
+            ..
+        } = e;
+        self.name = name.clone();
+        self.version = *aggregate_version;
+    }
+}
+
+/// 复制合并后的存储视图：按到达顺序保存全部副本的事件，不校验版本
+#[derive(Default)]
+struct MergedStore {
+    events: Mutex<Vec<SerializedEvent>>,
+}
+
+impl MergedStore {
+    async fn replicate(&self, from: &InMemoryEventRepository, id: &str) -> DomainResult<()> {
+        let incoming = from.get_events::<Profile>(&id.to_string()).await?;
+        let mut events = self.events.lock().unwrap();
+        for event in incoming {
+            if !events.iter().any(|e| e.event_id() == event.event_id()) {
+                events.push(event);
+            }
+        }
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventRepository for MergedStore {
+    async fn get_events<A: Aggregate>(&self, id: &A::Id) -> DomainResult<Vec<SerializedEvent>> {
+        self.get_last_events::<A>(id, 0).await
+    }
+
+    async fn get_last_events<A: Aggregate>(
+        &self,
+        id: &A::Id,
+        last_version: usize,
+    ) -> DomainResult<Vec<SerializedEvent>> {
+        Ok(self
+            .events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|e| e.aggregate_id() == id.to_string() && e.aggregate_version() > last_version)
+            .cloned()
+            .collect())
+    }
+
+    async fn save(&self, events: Vec<SerializedEvent>) -> DomainResult<()> {
+        self.events.lock().unwrap().extend(events);
+        Ok(())
+    }
+}
+
+struct Replica {
+    store: Arc<InMemoryEventRepository>,
+    clock: Arc<ReplicaClock>,
+}
+
+impl Replica {
+    fn new(name: &str) -> Self {
+        Self {
+            store: Arc::new(InMemoryEventRepository::new()),
+            clock: Arc::new(ReplicaClock::new(name)),
+        }
+    }
+
+    fn root(&self) -> AggregateRoot<Profile, EventSourcedRepo<InMemoryEventRepository>> {
+        AggregateRoot::new(
+            EventSourcedRepo::new(self.store.clone(), Arc::new(EventUpcasterChain::default()))
+                .with_replica_clock(self.clock.clone()),
+        )
+    }
+
+    async fn rename(&self, id: &str, names: &[&str]) -> AnyResult<()> {
+        for name in names {
+            self.root()
+                .execute(
+                    &id.to_string(),
+                    vec![name.to_string()],
+                    EventContext::default(),
+                )
+                .await?;
+        }
+        Ok(())
+    }
+}
+
+/// 在 eu 创建后复制到 us，两个副本随后各自写入
+async fn diverged() -> AnyResult<(Replica, Replica, Arc<MergedStore>)> {
+    let (eu, us) = (Replica::new("eu"), Replica::new("us"));
+    let id = "p-1";
+    eu.rename(id, &["Ada"]).await?;
+    us.store
+        .save(eu.store.get_events::<Profile>(&id.to_string()).await?)
+        .await?;
+
+    eu.rename(id, &["Ada L."]).await?;
+    us.rename(id, &["Ada Lovelace", "Countess"]).await?;
+
+    let merged = Arc::new(MergedStore::default());
+    merged.replicate(&eu.store, id).await?;
+    merged.replicate(&us.store, id).await?;
+    Ok((eu, us, merged))
+}
+
+fn merged_repo(merged: &Arc<MergedStore>) -> EventSourcedRepo<MergedStore> {
+    EventSourcedRepo::new(merged.clone(), Arc::new(EventUpcasterChain::default()))
+}
+
+#[tokio::test]
+async fn stamps_events_and_advances_clock_on_replay() -> AnyResult<()> {
+    let (eu, us, _) = diverged().await?;
+    let stored = us.store.get_events::<Profile>(&"p-1".to_string()).await?;
+    let stamps: Vec<_> = stored
+        .iter()
+        .map(|e| (e.origin_replica(), e.lamport()))
+        .collect();
+    assert_eq!(
+        stamps,
+        [
+            (Some("eu"), Some(1)),
+            (Some("us"), Some(2)),
+            (Some("us"), Some(3))
+        ]
+    );
+    assert_eq!(eu.clock.current(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn rejects_divergent_history_by_default() -> AnyResult<()> {
+    let (_, _, merged) = diverged().await?;
+
+    let history = detect_divergence(&merged.get_events::<Profile>(&"p-1".to_string()).await?)
+        .expect("histories diverged");
+    assert_eq!(history.fork_version, 2);
+    assert_eq!(
+        history
+            .branches
+            .iter()
+            .map(|b| (b.origin_replica.as_deref(), b.events.len()))
+            .collect::<Vec<_>>(),
+        [(Some("eu"), 1), (Some("us"), 2)]
+    );
+
+    let err = AggregateRepository::<Profile>::load(&merged_repo(&merged), &"p-1".to_string())
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "REPLICA_CONFLICT");
+    Ok(())
+}
+
+/// 指定优先副本的自定义策略
+struct PreferReplica(&'static str);
+
+impl ConflictResolver for PreferReplica {
+    fn resolve(&self, history: &DivergentHistory) -> ConflictResolution {
+        history
+            .branches
+            .iter()
+            .position(|b| b.origin_replica.as_deref() == Some(self.0))
+            .map_or(ConflictResolution::Reject, ConflictResolution::Branch)
+    }
+}
+
+#[tokio::test]
+async fn resolves_divergence_with_pluggable_resolver() -> AnyResult<()> {
+    let (_, _, merged) = diverged().await?;
+    let id = "p-1".to_string();
+
+    let lww = merged_repo(&merged).with_conflict_resolver(Arc::new(LastWriterWins));
+    let profile = AggregateRepository::<Profile>::load(&lww, &id)
+        .await?
+        .expect("profile exists");
+    assert_eq!(profile.name, "Countess");
+    assert_eq!(profile.version().value(), 3);
+
+    let prefer_eu = merged_repo(&merged).with_conflict_resolver(Arc::new(PreferReplica("eu")));
+    let profile = AggregateRepository::<Profile>::load(&prefer_eu, &id)
+        .await?
+        .expect("profile exists");
+    assert_eq!(profile.name, "Ada L.");
+    assert_eq!(profile.version().value(), 2);
+    Ok(())
+}
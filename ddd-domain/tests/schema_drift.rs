@@ -0,0 +1,135 @@
+#![cfg(feature = "testing")]
+use anyhow::Result as AnyResult;
+use chrono::{Duration, Utc};
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::error::{DomainError, DomainResult};
+use ddd_domain::event_upcaster::{EventUpcaster, EventUpcasterChain, EventUpcasterResult};
+use ddd_domain::persist::{EventRepository, SchemaDriftDetector, SerializedEvent};
+use ddd_domain::testing::InMemoryEventRepository;
+use ddd_domain::value_object::Version;
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Wallet {
+    balance: i64,
+}
+
+#[domain_event(version = 2)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum WalletEvent {
+    Deposited { amount: i64, currency: String },
+}
+
+impl Aggregate for Wallet {
+    const TYPE: &'static str = "wallet";
+    type Command = ();
+    type Event = WalletEvent;
+    type Error = DomainError;
+
+    fn execute(&self, _c: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        Ok(vec![])
+    }
+
+    fn apply(&mut self, _e: &Self::Event) {}
+}
+
+// v1 事件缺少 currency 字段
+struct AddCurrency;
+
+impl EventUpcaster for AddCurrency {
+    fn applies(&self, event_type: &str, event_version: usize) -> bool {
+        event_type == "WalletEvent.Deposited" && event_version == 1
+    }
+
+    fn upcast(&self, event: SerializedEvent) -> DomainResult<EventUpcasterResult> {
+        let mut payload = event.payload().clone();
+        payload["Deposited"]["currency"] = json!("CNY");
+        Ok(EventUpcasterResult::One(stored(
+            event.aggregate_type(),
+            event.aggregate_version(),
+            2,
+            payload,
+        )))
+    }
+}
+
+fn stored(
+    aggregate_type: &str,
+    aggregate_version: usize,
+    event_version: usize,
+    payload: Value,
+) -> SerializedEvent {
+    SerializedEvent::builder()
+        .event_id(format!("{aggregate_type}-{aggregate_version}"))
+        .event_type("WalletEvent.Deposited".to_string())
+        .event_version(event_version)
+        .aggregate_id("w-1".to_string())
+        .aggregate_type(aggregate_type.to_string())
+        .aggregate_version(aggregate_version)
+        .occurred_at(Utc::now() + Duration::milliseconds(aggregate_version as i64))
+        .payload(payload)
+        .context(json!({}))
+        .build()
+}
+
+fn deposited(aggregate_version: usize, currency: Option<&str>) -> AnyResult<Value> {
+    let mut payload = serde_json::to_value(WalletEvent::Deposited {
+        id: format!("e-{aggregate_version}"),
+        aggregate_version: Version::from_value(aggregate_version),
+        amount: 10,
+        currency: currency.unwrap_or_default().to_string(),
+    })?;
+    if currency.is_none() {
+        payload["Deposited"]
+            .as_object_mut()
+            .unwrap()
+            .remove("currency");
+    }
+    Ok(payload)
+}
+
+#[tokio::test]
+async fn reports_failures_grouped_by_type_and_version() -> AnyResult<()> {
+    let repo = InMemoryEventRepository::new();
+    let mut events = Vec::new();
+    for v in 1..=3 {
+        events.push(stored("wallet", v, 1, deposited(v, None)?));
+    }
+    events.push(stored("wallet", 4, 2, deposited(4, Some("USD"))?));
+    events.push(stored("ledger", 1, 1, json!({})));
+    repo.save(events).await?;
+
+    // 缺少上抬器：v1 事件无法反序列化
+    let report = SchemaDriftDetector::new(Arc::new(EventUpcasterChain::default()))
+        .sample_size(2)
+        .aggregate::<Wallet>()
+        .detect(repo.all_events());
+
+    assert!(!report.is_clean());
+    assert_eq!(report.sampled, 3);
+    assert_eq!(report.unchecked_aggregate_types, vec!["ledger".to_string()]);
+    assert_eq!(report.groups.len(), 1);
+
+    let group = &report.groups[0];
+    assert_eq!(group.event_type, "WalletEvent.Deposited");
+    assert_eq!(group.event_version, 1);
+    assert_eq!(group.sampled, 2);
+    // 保留最近的事件
+    let failed: Vec<&str> = group.failures.iter().map(|(id, _)| id.as_str()).collect();
+    assert_eq!(failed, vec!["wallet-2", "wallet-3"]);
+    assert!(group.failures[0].1.contains("currency"));
+
+    // 补上上抬器后无漂移
+    let chain: EventUpcasterChain = [Arc::new(AddCurrency) as Arc<dyn EventUpcaster>]
+        .into_iter()
+        .collect();
+    SchemaDriftDetector::new(Arc::new(chain))
+        .aggregate::<Wallet>()
+        .detect(repo.all_events())
+        .assert_clean();
+    Ok(())
+}
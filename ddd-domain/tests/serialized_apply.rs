@@ -0,0 +1,196 @@
+#![cfg(feature = "testing")]
+use anyhow::Result as AnyResult;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::aggregate_root::AggregateRoot;
+use ddd_domain::domain_event::{EventContext, EventEnvelope};
+use ddd_domain::entity::Entity;
+use ddd_domain::error::DomainError;
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::persist::{
+    AggregateRepository, EventRepository, EventSourcedRepo, SerializedEvent,
+};
+use ddd_domain::testing::InMemoryEventRepository;
+use ddd_domain::value_object::Version;
+use ddd_macros::{apply_serialized, domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Document {
+    title: String,
+    total_bytes: u64,
+    #[serde(skip)]
+    full_applies: usize,
+}
+
+#[derive(Debug)]
+enum Cmd {
+    Create { title: String },
+    Attach { blob: String },
+    Rename { title: String },
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Evt {
+    #[event(event_type = "document.created")]
+    Created { title: String },
+    #[event(event_type = "document.attached")]
+    Attached { blob: String, size: u64 },
+    #[event(event_type = "document.renamed")]
+    Renamed { title: String },
+}
+
+/// 只声明重建需要的字段，`blob` 不会被反序列化
+#[derive(Deserialize)]
+struct AttachedFields {
+    aggregate_version: Version,
+    size: u64,
+}
+
+#[derive(Deserialize)]
+struct CreatedFields<'a> {
+    aggregate_version: Version,
+    title: &'a str,
+}
+
+impl Document {
+    fn on_created(&mut self, fields: CreatedFields<'_>) {
+        self.title = fields.title.to_string();
+        self.version = fields.aggregate_version;
+    }
+
+    fn on_attached(&mut self, fields: AttachedFields) {
+        self.total_bytes += fields.size;
+        self.version = fields.aggregate_version;
+    }
+}
+
+#[apply_serialized(
+    "document.created" => on_created,
+    "document.attached" => on_attached,
+)]
+impl Aggregate for Document {
+    const TYPE: &'static str = "document";
+    type Command = Cmd;
+    type Event = Evt;
+    type Error = DomainError;
+
+    fn execute(&self, command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        let id = ulid::Ulid::new().to_string();
+        let aggregate_version = self.version().next();
+        Ok(vec![match command {
+            Cmd::Create { title } => Evt::Created {
+                id,
+                aggregate_version,
+                title,
+            },
+            Cmd::Attach { blob } => Evt::Attached {
+                id,
+                aggregate_version,
+                size: blob.len() as u64,
+                blob,
+            },
+            Cmd::Rename { title } => Evt::Renamed {
+                id,
+                aggregate_version,
+                title,
+            },
+        }])
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        self.full_applies += 1;
+        match event {
+            Evt::Created {
+                aggregate_version,
+                title,
+                ..
+            }
+            | Evt::Renamed {
+                aggregate_version,
+                title,
+                ..
+            } => {
+                self.title = title.clone();
+                self.version = *aggregate_version;
+            }
+            Evt::Attached {
+                aggregate_version,
+                size,
+                ..
+            } => {
+                self.total_bytes += size;
+                self.version = *aggregate_version;
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn replay_uses_serialized_fast_path_and_falls_back_to_apply() -> AnyResult<()> {
+    let events = Arc::new(InMemoryEventRepository::new());
+    let repo = Arc::new(EventSourcedRepo::new(
+        events.clone(),
+        Arc::new(EventUpcasterChain::default()),
+    ));
+    let root = AggregateRoot::<Document, _>::new(repo.clone());
+    let id = "d-1".to_string();
+    root.execute(
+        &id,
+        vec![
+            Cmd::Create {
+                title: "draft".into(),
+            },
+            Cmd::Attach {
+                blob: "x".repeat(4096),
+            },
+            Cmd::Attach {
+                blob: "y".repeat(1024),
+            },
+            Cmd::Rename {
+                title: "final".into(),
+            },
+        ],
+        EventContext::default(),
+    )
+    .await?;
+
+    let document: Document = repo.load(&id).await?.unwrap();
+    assert_eq!(document.title, "final");
+    assert_eq!(document.total_bytes, 5120);
+    assert_eq!(document.version().value(), 4);
+    // 仅未列出的 `document.renamed` 走完整反序列化
+    assert_eq!(document.full_applies, 1);
+
+    // 快速路径与完整反序列化得到相同状态
+    let mut full = Document::new(id.clone(), Version::new());
+    for event in events.get_events::<Document>(&id).await? {
+        let envelope: EventEnvelope<Document> = (&event).try_into()?;
+        full.apply(&envelope.payload);
+    }
+    assert_eq!(full.title, document.title);
+    assert_eq!(full.total_bytes, document.total_bytes);
+    assert_eq!(full.version(), document.version());
+    Ok(())
+}
+
+#[test]
+fn malformed_fields_fail_instead_of_falling_back() {
+    let mut document = Document::default();
+    let event = SerializedEvent::builder()
+        .event_id("e-1".to_string())
+        .event_type("document.attached".to_string())
+        .event_version(1)
+        .aggregate_id("d-1".to_string())
+        .aggregate_type(Document::TYPE.to_string())
+        .aggregate_version(1)
+        .occurred_at(chrono::Utc::now())
+        .payload(serde_json::json!({ "Attached": { "aggregate_version": 1 } }))
+        .context(serde_json::json!({}))
+        .build();
+
+    assert!(document.apply_serialized(&event).is_err());
+    assert_eq!(document.full_applies, 0);
+}
@@ -0,0 +1,188 @@
+#![cfg(all(
+    feature = "testing",
+    feature = "snapshot-zstd",
+    feature = "snapshot-msgpack"
+))]
+use anyhow::Result as AnyResult;
+use async_trait::async_trait;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::entity::Entity;
+use ddd_domain::error::{DomainError, DomainResult, ErrorCode};
+use ddd_domain::persist::{
+    MessagePackCodec, SNAPSHOT_CODEC_ERROR, SerializedSnapshot, SnapshotCodec, SnapshotPolicy,
+    SnapshotRepository, SnapshotRepositoryWithPolicy, ZSTD_JSON_SNAPSHOT_ENCODING, ZstdJsonCodec,
+};
+use ddd_domain::testing::InMemorySnapshotRepository;
+use ddd_domain::value_object::Version;
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use std::sync::{Arc, Mutex};
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Catalog {
+    items: Vec<String>,
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum CatalogEvent {
+    Added { item: String },
+}
+
+impl Aggregate for Catalog {
+    const TYPE: &'static str = "catalog";
+    type Command = ();
+    type Event = CatalogEvent;
+    type Error = DomainError;
+
+    fn execute(&self, _c: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        Ok(vec![])
+    }
+
+    fn apply(&mut self, _e: &Self::Event) {}
+}
+
+fn catalog(id: &str, items: usize) -> Catalog {
+    let mut c = Catalog::new(id.to_string(), Version::from_value(1));
+    c.items = (0..items).map(|i| format!("sku-{:04}", i % 50)).collect();
+    c
+}
+
+#[tokio::test]
+async fn compresses_snapshots_and_decodes_on_load() -> AnyResult<()> {
+    let store = InMemorySnapshotRepository::new();
+    let repo = SnapshotRepositoryWithPolicy::new(store.clone(), SnapshotPolicy::Every(1))
+        .with_codec(Arc::new(ZstdJsonCodec::default()));
+    let aggregate = catalog("cat-1", 2_000);
+    repo.save(&aggregate).await?;
+
+    // 落盘的是压缩后的 base64 负载
+    let stored = store
+        .get_snapshot::<Catalog>(&"cat-1".to_string(), None)
+        .await?
+        .unwrap();
+    assert_eq!(stored.content_encoding(), Some(ZSTD_JSON_SNAPSHOT_ENCODING));
+    let raw_len = serde_json::to_vec(&aggregate)?.len();
+    assert!(stored.payload().as_str().unwrap().len() < raw_len / 4);
+
+    // 内置编码：直接从内层仓储读取也能透明还原
+    assert_eq!(stored.to_aggregate::<Catalog>()?.items, aggregate.items);
+
+    // 经装饰器读取时返回解码后的快照
+    let loaded = repo
+        .get_snapshot::<Catalog>(&"cat-1".to_string(), None)
+        .await?
+        .unwrap();
+    assert_eq!(loaded.content_encoding(), None);
+    assert_eq!(loaded.payload()["items"][1], "sku-0001");
+
+    // 切换编解码器后仍可读取旧编码的快照
+    let msgpack = SnapshotRepositoryWithPolicy::new(store.clone(), SnapshotPolicy::Every(1))
+        .with_codec(Arc::new(MessagePackCodec));
+    let old = msgpack
+        .get_snapshot::<Catalog>(&"cat-1".to_string(), None)
+        .await?
+        .unwrap();
+    assert_eq!(old.to_aggregate::<Catalog>()?.items.len(), 2_000);
+    Ok(())
+}
+
+/// 自定义编解码器：反转 JSON 字节
+struct Reversed;
+
+impl SnapshotCodec for Reversed {
+    fn encoding(&self) -> &str {
+        "reversed"
+    }
+
+    fn encode(&self, payload: &Value) -> DomainResult<Vec<u8>> {
+        let mut bytes = serde_json::to_vec(payload)?;
+        bytes.reverse();
+        Ok(bytes)
+    }
+
+    fn decode(&self, bytes: &[u8]) -> DomainResult<Value> {
+        let mut bytes = bytes.to_vec();
+        bytes.reverse();
+        Ok(serde_json::from_slice(&bytes)?)
+    }
+}
+
+#[tokio::test]
+async fn custom_codecs_are_decoded_by_the_configured_repository() -> AnyResult<()> {
+    let store = InMemorySnapshotRepository::new();
+    let repo = SnapshotRepositoryWithPolicy::new(store.clone(), SnapshotPolicy::Every(1))
+        .with_codec(Arc::new(Reversed));
+    repo.save(&catalog("cat-2", 3)).await?;
+
+    let loaded = repo
+        .get_snapshot::<Catalog>(&"cat-2".to_string(), None)
+        .await?
+        .unwrap();
+    assert_eq!(loaded.to_aggregate::<Catalog>()?.items.len(), 3);
+
+    // 未配置该编解码器时无法识别编码
+    let stored = store
+        .get_snapshot::<Catalog>(&"cat-2".to_string(), None)
+        .await?
+        .unwrap();
+    let err = stored.to_aggregate::<Catalog>().unwrap_err();
+    assert_eq!(err.code(), SNAPSHOT_CODEC_ERROR);
+    Ok(())
+}
+
+/// 只实现必需方法的快照仓储
+#[derive(Default)]
+struct MinimalSnapshotRepository {
+    latest: Mutex<Option<SerializedSnapshot>>,
+}
+
+#[async_trait]
+impl SnapshotRepository for MinimalSnapshotRepository {
+    async fn get_snapshot<A: Aggregate>(
+        &self,
+        _aggregate_id: &A::Id,
+        _version: Option<usize>,
+    ) -> DomainResult<Option<SerializedSnapshot>> {
+        Ok(self.latest.lock().unwrap().clone())
+    }
+
+    async fn save_serialized(&self, snapshot: SerializedSnapshot) -> DomainResult<()> {
+        *self.latest.lock().unwrap() = Some(snapshot);
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn minimal_backends_store_encoded_snapshots() -> AnyResult<()> {
+    let store = Arc::new(MinimalSnapshotRepository::default());
+    let repo = SnapshotRepositoryWithPolicy::new(store.clone(), SnapshotPolicy::Every(1))
+        .with_codec(Arc::new(ZstdJsonCodec::default()));
+    repo.save(&catalog("cat-3", 10)).await?;
+
+    let stored = store.latest.lock().unwrap().clone().unwrap();
+    assert_eq!(stored.content_encoding(), Some(ZSTD_JSON_SNAPSHOT_ENCODING));
+
+    let loaded = repo
+        .get_snapshot::<Catalog>(&"cat-3".to_string(), None)
+        .await?
+        .unwrap();
+    assert_eq!(loaded.to_aggregate::<Catalog>()?.items.len(), 10);
+    Ok(())
+}
+
+#[tokio::test]
+async fn minimal_backends_bulk_load_through_save_serialized() -> AnyResult<()> {
+    let store = MinimalSnapshotRepository::default();
+    let snapshots = vec![
+        SerializedSnapshot::from_aggregate(&catalog("cat-4", 2))?,
+        SerializedSnapshot::from_aggregate(&catalog("cat-4", 5))?,
+    ];
+
+    assert_eq!(store.bulk_load(snapshots).await?, 2);
+    let stored = store.latest.lock().unwrap().clone().unwrap();
+    assert_eq!(stored.to_aggregate::<Catalog>()?.items.len(), 5);
+    Ok(())
+}
@@ -0,0 +1,141 @@
+#![cfg(feature = "testing")]
+use anyhow::Result as AnyResult;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::entity::Entity;
+use ddd_domain::error::DomainError;
+use ddd_domain::persist::{
+    InMemoryEventArchive, SnapshotGc, SnapshotGcReport, SnapshotHistory, SnapshotPolicy,
+    SnapshotRepository, SnapshotRetention, TieredSnapshotRepository,
+};
+use ddd_domain::testing::InMemorySnapshotRepository;
+use ddd_domain::value_object::Version;
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Counter {
+    value: i64,
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum CounterEvent {
+    Incr { by: i64 },
+}
+
+impl Aggregate for Counter {
+    const TYPE: &'static str = "counter";
+    type Command = ();
+    type Event = CounterEvent;
+    type Error = DomainError;
+
+    fn execute(&self, _c: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        Ok(vec![])
+    }
+
+    fn apply(&mut self, _e: &Self::Event) {}
+}
+
+fn counter_at(id: &str, version: usize) -> Counter {
+    let mut c = Counter::new(id.to_string(), Version::from_value(version));
+    c.value = version as i64 * 10;
+    c
+}
+
+#[tokio::test]
+async fn deletes_snapshots_superseded_by_newer_snapshot_and_archive() -> AnyResult<()> {
+    let snapshots = InMemorySnapshotRepository::new();
+    for v in [10, 20, 30, 40] {
+        snapshots.save(&counter_at("c-1", v)).await?;
+    }
+    let archive = Arc::new(InMemoryEventArchive::new());
+    let gc = SnapshotGc::new(snapshots.clone(), archive.clone());
+    let id = "c-1".to_string();
+
+    // 未归档：历史快照仍是在线事件的重放起点
+    assert_eq!(gc.collect::<Counter>(&id).await?.deleted, 0);
+    assert_eq!(snapshots.len(), 4);
+
+    // 归档到 25：10、20 被 40 与已归档事件共同取代，30 仍可作为在线事件的起点
+    archive.archive::<Counter>(&id, 25);
+    assert_eq!(gc.plan::<Counter>(&id).await?, vec![10, 20]);
+    let report = gc.collect::<Counter>(&id).await?;
+    assert_eq!(
+        report,
+        SnapshotGcReport {
+            aggregates: 1,
+            deleted: 2,
+            retained: 2
+        }
+    );
+    assert_eq!(
+        snapshots.snapshot_versions::<Counter>(&id).await?,
+        vec![30, 40]
+    );
+
+    // 全部归档后仍保留最新快照
+    archive.archive::<Counter>(&id, 40);
+    gc.collect::<Counter>(&id).await?;
+    assert_eq!(snapshots.snapshot_versions::<Counter>(&id).await?, vec![40]);
+    let latest = snapshots.get_snapshot::<Counter>(&id, None).await?.unwrap();
+    assert_eq!(latest.to_aggregate::<Counter>()?.value, 400);
+    Ok(())
+}
+
+#[tokio::test]
+async fn retention_keeps_latest_snapshots_across_aggregates() -> AnyResult<()> {
+    let snapshots = InMemorySnapshotRepository::new();
+    for id in ["c-1", "c-2"] {
+        for v in [5, 10, 15] {
+            snapshots.save(&counter_at(id, v)).await?;
+        }
+    }
+    let archive = Arc::new(InMemoryEventArchive::new());
+    archive.archive::<Counter>(&"c-1".to_string(), 15);
+    let gc = SnapshotGc::new(snapshots.clone(), archive)
+        .with_retention(SnapshotRetention { keep_latest: 2 });
+
+    let ids = ["c-1".to_string(), "c-2".to_string()];
+    let report = gc.collect_many::<Counter>(&ids).await?;
+    assert_eq!(
+        report,
+        SnapshotGcReport {
+            aggregates: 2,
+            deleted: 1,
+            retained: 5
+        }
+    );
+    assert_eq!(
+        snapshots.snapshot_versions::<Counter>(&ids[0]).await?,
+        vec![10, 15]
+    );
+    assert_eq!(
+        snapshots.snapshot_versions::<Counter>(&ids[1]).await?,
+        vec![5, 10, 15]
+    );
+    Ok(())
+}
+
+#[tokio::test]
+async fn tiered_repository_shrinks_both_tiers() -> AnyResult<()> {
+    let hot = InMemorySnapshotRepository::new();
+    let cold = InMemorySnapshotRepository::new();
+    let tiered = TieredSnapshotRepository::new(hot.clone(), cold.clone(), SnapshotPolicy::Every(2));
+    for v in 1..=6 {
+        tiered.save(&counter_at("c-1", v)).await?;
+    }
+    assert_eq!((hot.len(), cold.len()), (6, 3));
+
+    let archive = Arc::new(InMemoryEventArchive::new());
+    archive.archive::<Counter>(&"c-1".to_string(), 4);
+    let report = SnapshotGc::new(tiered, archive)
+        .collect::<Counter>(&"c-1".to_string())
+        .await?;
+
+    // 以冷存储为准删除 2、4，热存储同步删除同版本
+    assert_eq!(report.deleted, 2);
+    assert_eq!((hot.len(), cold.len()), (4, 1));
+    Ok(())
+}
@@ -121,12 +121,11 @@ impl SnapshotRepository for InMemorySnapshotPolicyRepo {
             .get(&aggregate_id.to_string())
             .cloned())
     }
-    async fn save<A: Aggregate>(&self, aggregate: &A) -> DomainResult<()> {
-        let snap = SerializedSnapshot::from_aggregate(aggregate)?;
+    async fn save_serialized(&self, snapshot: SerializedSnapshot) -> DomainResult<()> {
         self.snaps
             .lock()
             .unwrap()
-            .insert(aggregate.id().to_string(), snap);
+            .insert(snapshot.aggregate_id().to_string(), snapshot);
         Ok(())
     }
 }
@@ -0,0 +1,87 @@
+#![cfg(feature = "testing")]
+use anyhow::Result as AnyResult;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::entity::Entity;
+use ddd_domain::error::DomainError;
+use ddd_domain::persist::{
+    SnapshotPolicy, SnapshotRepository, SnapshotRepositoryWithPolicy, export_snapshots_ndjson,
+    import_snapshots_ndjson,
+};
+use ddd_domain::testing::InMemorySnapshotRepository;
+use ddd_domain::value_object::Version;
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Counter {
+    value: i64,
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum CounterEvent {
+    Incr { by: i64 },
+}
+
+impl Aggregate for Counter {
+    const TYPE: &'static str = "counter";
+    type Command = ();
+    type Event = CounterEvent;
+    type Error = DomainError;
+
+    fn execute(&self, _c: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        Ok(vec![])
+    }
+
+    fn apply(&mut self, _e: &Self::Event) {}
+}
+
+fn counter(id: &str, version: usize) -> Counter {
+    let mut c = Counter::new(id.to_string(), Version::from_value(version));
+    c.value = version as i64 * 10;
+    c
+}
+
+#[tokio::test]
+async fn exports_and_bulk_loads_snapshots_into_new_environment() -> AnyResult<()> {
+    let source = InMemorySnapshotRepository::new();
+    source.save(&counter("c-1", 5)).await?;
+    source.save(&counter("c-2", 3)).await?;
+
+    let mut ndjson = Vec::new();
+    let summary = export_snapshots_ndjson(&source.all_snapshots(), &mut ndjson)?;
+    assert_eq!(summary.count, 2);
+    assert_eq!(String::from_utf8(ndjson.clone())?.lines().count(), 3);
+
+    // 目标仓储带快照策略：批量写入不受策略限制
+    let target = InMemorySnapshotRepository::new();
+    let policy_repo = SnapshotRepositoryWithPolicy::new(target.clone(), SnapshotPolicy::Never);
+    let snapshots = import_snapshots_ndjson(ndjson.as_slice())?;
+    assert_eq!(policy_repo.bulk_load(snapshots).await?, 2);
+
+    let loaded = target
+        .get_snapshot::<Counter>(&"c-1".to_string(), None)
+        .await?
+        .unwrap();
+    assert_eq!(loaded.aggregate_version(), 5);
+    assert_eq!(loaded.to_aggregate::<Counter>()?.value, 50);
+    Ok(())
+}
+
+#[test]
+fn rejects_tampered_or_truncated_exports() -> AnyResult<()> {
+    let snapshot = ddd_domain::persist::SerializedSnapshot::from_aggregate(&counter("c-1", 2))?;
+    let mut ndjson = Vec::new();
+    export_snapshots_ndjson([&snapshot], &mut ndjson)?;
+    let text = String::from_utf8(ndjson)?;
+
+    let tampered = text.replace("\"value\":20", "\"value\":99");
+    let err = import_snapshots_ndjson(tampered.as_bytes()).unwrap_err();
+    assert!(err.to_string().contains("checksum mismatch"), "{err}");
+
+    let truncated = text.lines().next().unwrap();
+    let err = import_snapshots_ndjson(truncated.as_bytes()).unwrap_err();
+    assert!(err.to_string().contains("truncated"), "{err}");
+    Ok(())
+}
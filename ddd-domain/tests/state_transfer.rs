@@ -0,0 +1,141 @@
+#![cfg(feature = "testing")]
+use anyhow::Result as AnyResult;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::aggregate_root::AggregateRoot;
+use ddd_domain::domain_event::{EventContext, StateTransfer};
+use ddd_domain::entity::Entity;
+use ddd_domain::error::DomainError;
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::persist::{EventRepository, EventSourcedRepo};
+use ddd_domain::testing::InMemoryEventRepository;
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::sync::Arc;
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Customer {
+    name: String,
+    email: String,
+}
+
+#[derive(Debug)]
+enum Cmd {
+    Register { name: String, email: String },
+    ChangeEmail { email: String },
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Evt {
+    #[event(event_type = "customer.registered")]
+    Registered { name: String, email: String },
+    #[event(event_type = "customer.email_changed")]
+    EmailChanged { email: String },
+}
+
+impl Aggregate for Customer {
+    const TYPE: &'static str = "customer";
+    type Command = Cmd;
+    type Event = Evt;
+    type Error = DomainError;
+
+    fn execute(&self, command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        let id = ulid::Ulid::new().to_string();
+        let aggregate_version = self.version().next();
+
+        Ok(vec![match command {
+            Cmd::Register { name, email } => Evt::Registered {
+                id,
+                aggregate_version,
+                name,
+                email,
+            },
+            Cmd::ChangeEmail { email } => Evt::EmailChanged {
+                id,
+                aggregate_version,
+                email,
+            },
+        }])
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        match event {
+            Evt::Registered {
+                aggregate_version,
+                name,
+                email,
+                ..
+            } => {
+                self.name = name.clone();
+                self.email = email.clone();
+                self.version = *aggregate_version;
+            }
+            Evt::EmailChanged {
+                aggregate_version,
+                email,
+                ..
+            } => {
+                self.email = email.clone();
+                self.version = *aggregate_version;
+            }
+        }
+    }
+}
+
+#[tokio::test]
+async fn state_snapshot_is_attached_per_event_type() -> AnyResult<()> {
+    let event_repo = Arc::new(InMemoryEventRepository::new());
+    let repo = EventSourcedRepo::new(event_repo.clone(), Arc::new(EventUpcasterChain::default()));
+    let root = AggregateRoot::<Customer, _>::new(repo).with_state_transfer(
+        StateTransfer::new().fields("customer.email_changed", ["name", "email"]),
+    );
+    let id = "c-1".to_string();
+
+    let envelopes = root
+        .execute(
+            &id,
+            vec![
+                Cmd::Register {
+                    name: "Alice".into(),
+                    email: "a@old.io".into(),
+                },
+                Cmd::ChangeEmail {
+                    email: "a@new.io".into(),
+                },
+            ],
+            EventContext::default(),
+        )
+        .await?;
+
+    assert!(envelopes[0].state_snapshot().is_none());
+    assert_eq!(
+        envelopes[1].state_snapshot(),
+        Some(&json!({"name": "Alice", "email": "a@new.io"}))
+    );
+
+    // 附件随事件持久化，下游可直接读取
+    let stored = event_repo.get_events::<Customer>(&id).await?;
+    assert_eq!(stored[0].state_snapshot(), None);
+    assert_eq!(
+        stored[1].state_snapshot(),
+        Some(&json!({"name": "Alice", "email": "a@new.io"}))
+    );
+
+    // 全量快照包含版本等完整状态
+    let root = root.with_state_transfer(StateTransfer::new().full("customer.email_changed"));
+    let envelopes = root
+        .execute(
+            &id,
+            vec![Cmd::ChangeEmail {
+                email: "a@final.io".into(),
+            }],
+            EventContext::default(),
+        )
+        .await?;
+    let state = envelopes[0].state_snapshot().unwrap();
+    assert_eq!(state["email"], "a@final.io");
+    assert_eq!(state["id"], "c-1");
+    Ok(())
+}
@@ -0,0 +1,161 @@
+#![cfg(feature = "testing")]
+use anyhow::Result as AnyResult;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::aggregate_root::AggregateRoot;
+use ddd_domain::domain_event::EventContext;
+use ddd_domain::entity::Entity;
+use ddd_domain::error::{DomainError, ErrorCode, ErrorKind};
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::persist::{
+    EventRepository, EventSourcedRepo, QuotaEnforcement, QuotaPolicy, QuotaRepository,
+    QuotaWarning, SnapshotRepository, StorageKind, StorageQuota,
+};
+use ddd_domain::testing::{InMemoryEventRepository, InMemorySnapshotRepository};
+use ddd_domain::value_object::Version;
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Note {
+    tenant_id: String,
+    text: String,
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum NoteEvent {
+    Written { text: String },
+}
+
+impl Aggregate for Note {
+    const TYPE: &'static str = "note";
+    type Command = String;
+    type Event = NoteEvent;
+    type Error = DomainError;
+
+    fn execute(&self, text: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        Ok(vec![NoteEvent::Written {
+            id: ulid::Ulid::new().to_string(),
+            aggregate_version: self.version().next(),
+            text,
+        }])
+    }
+
+    fn apply(&mut self, e: &Self::Event) {
+        let NoteEvent::Written {
+            aggregate_version,
+            text,
+            ..
+        } = e;
+        self.text = text.clone();
+        self.version = *aggregate_version;
+    }
+}
+
+type Repo = QuotaRepository<InMemoryEventRepository>;
+
+fn root(repo: Arc<Repo>) -> AggregateRoot<Note, EventSourcedRepo<Repo>> {
+    AggregateRoot::new(EventSourcedRepo::new(
+        repo,
+        Arc::new(EventUpcasterChain::default()),
+    ))
+}
+
+fn tenant(tenant_id: &str) -> EventContext {
+    EventContext::builder()
+        .extensions(serde_json::json!({ "tenant_id": tenant_id }))
+        .build()
+}
+
+#[tokio::test]
+async fn refuses_event_writes_over_tenant_quota() -> AnyResult<()> {
+    let policy = QuotaPolicy::new(StorageQuota::unlimited())
+        .with_tenant("free", StorageQuota::unlimited().with_event_bytes(1500));
+    let repo = Arc::new(QuotaRepository::new(
+        Arc::new(InMemoryEventRepository::new()),
+        policy,
+    ));
+    let root = root(repo.clone());
+
+    let id = "n-1".to_string();
+    root.execute(&id, vec!["hello".into()], tenant("free"))
+        .await?;
+    let used = repo.usage("free");
+    assert_eq!(used.events, 1);
+    assert!(used.event_bytes > 0);
+
+    // 继续写入直到超额：超额的写入被拒绝且不计入用量
+    let err = loop {
+        match root
+            .execute(&id, vec!["more text".into()], tenant("free"))
+            .await
+        {
+            Ok(_) => continue,
+            Err(err) => break err,
+        }
+    };
+    assert_eq!(err.kind(), ErrorKind::InvalidCommand);
+    assert_eq!(err.code(), "QUOTA_EXCEEDED");
+    let used = repo.usage("free");
+    assert!(used.event_bytes <= 1500);
+    assert_eq!(
+        repo.inner().get_events::<Note>(&id).await?.len() as u64,
+        used.events
+    );
+
+    // 其他租户与无法识别租户的写入不受影响
+    root.execute(&"n-2".to_string(), vec!["pro".into()], tenant("pro"))
+        .await?;
+    root.execute(
+        &"n-3".to_string(),
+        vec!["anon".into()],
+        EventContext::default(),
+    )
+    .await?;
+    assert_eq!(repo.usage("pro").events, 1);
+    assert_eq!(repo.usages().len(), 2);
+    Ok(())
+}
+
+#[tokio::test]
+async fn warns_and_tracks_latest_snapshot_size() -> AnyResult<()> {
+    let warnings: Arc<Mutex<Vec<QuotaWarning>>> = Arc::default();
+    let sink = warnings.clone();
+    let policy = QuotaPolicy::new(StorageQuota::unlimited().with_snapshot_bytes(200))
+        .with_enforcement(QuotaEnforcement::Warn)
+        .on_warning(move |w| sink.lock().unwrap().push(w.clone()));
+    let repo = QuotaRepository::new(Arc::new(InMemorySnapshotRepository::new()), policy);
+
+    let note = |version: usize, text: &str| {
+        let mut note = Note::new("n-1".to_string(), Version::from_value(version));
+        note.tenant_id = "acme".to_string();
+        note.text = text.to_string();
+        note
+    };
+
+    repo.save(&note(1, "short")).await?;
+    let first = repo.usage("acme");
+    assert_eq!(first.snapshots, 1);
+
+    // 同一聚合的新快照替换旧快照的用量
+    repo.save(&note(2, "short")).await?;
+    assert_eq!(repo.usage("acme").snapshots, 1);
+    assert_eq!(repo.usage("acme").snapshot_bytes, first.snapshot_bytes);
+    assert!(warnings.lock().unwrap().is_empty());
+
+    // 超额仍写入，但发出告警
+    repo.save(&note(3, &"x".repeat(300))).await?;
+    let warnings = warnings.lock().unwrap().clone();
+    assert_eq!(warnings.len(), 1);
+    assert_eq!(warnings[0].tenant_id, "acme");
+    assert_eq!(warnings[0].kind, StorageKind::Snapshots);
+    assert_eq!(warnings[0].limit_bytes, 200);
+    assert!(
+        repo.get_snapshot::<Note>(&"n-1".to_string(), None)
+            .await?
+            .is_some_and(|s| s.aggregate_version() == 3)
+    );
+    Ok(())
+}
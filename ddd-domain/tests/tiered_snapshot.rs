@@ -0,0 +1,73 @@
+#![cfg(feature = "testing")]
+use anyhow::Result as AnyResult;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::entity::Entity;
+use ddd_domain::error::DomainError;
+use ddd_domain::persist::{SnapshotPolicy, SnapshotRepository, TieredSnapshotRepository};
+use ddd_domain::testing::InMemorySnapshotRepository;
+use ddd_domain::value_object::Version;
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Counter {
+    value: i64,
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum CounterEvent {
+    Incr { by: i64 },
+}
+
+impl Aggregate for Counter {
+    const TYPE: &'static str = "counter";
+    type Command = ();
+    type Event = CounterEvent;
+    type Error = DomainError;
+
+    fn execute(&self, _c: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        Ok(vec![])
+    }
+
+    fn apply(&mut self, _e: &Self::Event) {}
+}
+
+fn counter_at(version: usize) -> Counter {
+    let mut c = Counter::new("c-1".to_string(), Version::from_value(version));
+    c.value = version as i64 * 10;
+    c
+}
+
+#[tokio::test]
+async fn writes_every_version_hot_and_every_nth_cold() -> AnyResult<()> {
+    let hot = InMemorySnapshotRepository::new();
+    let cold = InMemorySnapshotRepository::new();
+    let tiered = TieredSnapshotRepository::new(hot.clone(), cold.clone(), SnapshotPolicy::Every(2));
+
+    for v in 1..=5 {
+        tiered.save(&counter_at(v)).await?;
+    }
+    assert_eq!(hot.len(), 5);
+    assert_eq!(cold.len(), 2);
+
+    let id = "c-1".to_string();
+    let latest = tiered.get_snapshot::<Counter>(&id, None).await?.unwrap();
+    assert_eq!(latest.aggregate_version(), 5);
+
+    // 热存储丢失（如重启）：回退冷存储并回填热存储
+    let empty_hot = InMemorySnapshotRepository::new();
+    let tiered = TieredSnapshotRepository::new(empty_hot.clone(), cold, SnapshotPolicy::Every(2));
+
+    let from_cold = tiered.get_snapshot::<Counter>(&id, None).await?.unwrap();
+    assert_eq!(from_cold.aggregate_version(), 4);
+    assert_eq!(from_cold.to_aggregate::<Counter>()?.value, 40);
+    assert_eq!(empty_hot.len(), 1);
+
+    // 指定版本读取同样回退，但不回填
+    let older = tiered.get_snapshot::<Counter>(&id, Some(3)).await?.unwrap();
+    assert_eq!(older.aggregate_version(), 2);
+    assert_eq!(empty_hot.len(), 1);
+    Ok(())
+}
@@ -0,0 +1,205 @@
+#![cfg(all(feature = "eventing", feature = "testing"))]
+use anyhow::Result as AnyResult;
+use async_trait::async_trait;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::aggregate_root::AggregateRoot;
+use ddd_domain::domain_event::{DomainEvent, EventContext};
+use ddd_domain::entity::Entity;
+use ddd_domain::error::{DomainError, DomainResult, ErrorKind};
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::eventing::{
+    EventDeliverer, EventEngine, EventEngineConfig, EventHandler, EventReclaimer, HandledEventType,
+    HandlerContext, InMemoryEventBus, OutboxRepository, TransactionalOutboxDeliverer,
+};
+use ddd_domain::persist::{AggregateRepository, EventSourcedRepo, SerializedEvent};
+use ddd_domain::testing::InMemoryOutboxRepository;
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Counter {
+    value: i64,
+}
+
+#[derive(Debug)]
+enum Cmd {
+    Add(i64),
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Evt {
+    #[event(event_type = "counter.added")]
+    Added { by: i64 },
+}
+
+impl Aggregate for Counter {
+    const TYPE: &'static str = "counter";
+    type Command = Cmd;
+    type Event = Evt;
+    type Error = DomainError;
+
+    fn execute(&self, command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        let Cmd::Add(by) = command;
+        Ok(vec![Evt::Added {
+            id: ulid::Ulid::new().to_string(),
+            aggregate_version: self.version().next(),
+            by,
+        }])
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        let Evt::Added {
+            aggregate_version,
+            by,
+            ..
+        } = event;
+        self.value += by;
+        self.version = *aggregate_version;
+    }
+}
+
+#[derive(Default)]
+struct Recorder {
+    seen: Mutex<Vec<String>>,
+}
+
+#[async_trait]
+impl EventHandler for Recorder {
+    fn handler_name(&self) -> &str {
+        "recorder"
+    }
+
+    fn handled_event_type(&self) -> HandledEventType {
+        HandledEventType::All
+    }
+
+    async fn handle(&self, event: &SerializedEvent, _ctx: &HandlerContext) -> anyhow::Result<()> {
+        self.seen.lock().unwrap().push(event.event_id().to_string());
+        Ok(())
+    }
+}
+
+struct NoReclaim;
+
+#[async_trait]
+impl EventReclaimer for NoReclaim {
+    async fn fetch_events(&self) -> DomainResult<Vec<SerializedEvent>> {
+        Ok(Vec::new())
+    }
+
+    async fn mark_reclaimed(&self, _events: &[&SerializedEvent]) -> DomainResult<()> {
+        Ok(())
+    }
+
+    async fn mark_failed(&self, _events: &[&SerializedEvent], _reason: &str) -> DomainResult<()> {
+        Ok(())
+    }
+
+    async fn mark_handler_failed(
+        &self,
+        _handler_name: &str,
+        _events: &[&SerializedEvent],
+        _reason: &str,
+    ) -> DomainResult<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn committed_events_are_enqueued_atomically() -> AnyResult<()> {
+    let store = Arc::new(InMemoryOutboxRepository::new());
+    let repo = Arc::new(EventSourcedRepo::new(
+        store.clone(),
+        Arc::new(EventUpcasterChain::default()),
+    ));
+    let root = AggregateRoot::<Counter, _>::new(repo.clone());
+    let id = "c-1".to_string();
+    root.execute(&id, vec![Cmd::Add(1), Cmd::Add(2)], EventContext::default())
+        .await?;
+    assert_eq!(store.pending_count(), 2);
+
+    // 版本冲突的提交既不写入事件流，也不入队
+    let mut stale: Counter = repo.load(&id).await?.unwrap();
+    root.execute(&id, vec![Cmd::Add(3)], EventContext::default())
+        .await?;
+    let events = stale.execute(Cmd::Add(100))?;
+    events.iter().for_each(|e| stale.apply(e));
+    let err = repo
+        .save(&stale, events, EventContext::default())
+        .await
+        .unwrap_err();
+    assert_eq!(err.kind(), ErrorKind::Conflict);
+    assert_eq!(store.pending_count(), 3);
+
+    // 失败后保持待投递，发布后不再拉取
+    let deliverer = TransactionalOutboxDeliverer::new(store.clone()).with_batch_size(2);
+    let batch = deliverer.fetch_events().await?;
+    assert_eq!(batch.len(), 2);
+    deliverer.mark_failed(&[&batch[0]], "bus down").await?;
+    assert_eq!(
+        store.failures(batch[0].event_id()),
+        Some((1, Some("bus down".to_string())))
+    );
+    deliverer.mark_delivered(&[&batch[1]]).await?;
+    let next = deliverer.fetch_events().await?;
+    assert_eq!(next.len(), 2);
+    assert_eq!(next[0].event_id(), batch[0].event_id());
+    assert!(next.iter().all(|e| e.event_id() != batch[1].event_id()));
+
+    // 派生事件只入队，不写入事件流
+    store.enqueue(&next[..1]).await?;
+    assert_eq!(store.pending_count(), 3);
+    assert_eq!(store.events().len(), 3);
+    Ok(())
+}
+
+#[tokio::test]
+async fn engine_delivers_from_transactional_outbox() -> AnyResult<()> {
+    let store = Arc::new(InMemoryOutboxRepository::new());
+    let root = AggregateRoot::<Counter, _>::new(Arc::new(EventSourcedRepo::new(
+        store.clone(),
+        Arc::new(EventUpcasterChain::default()),
+    )));
+    let envelopes = root
+        .execute(
+            &"c-1".to_string(),
+            vec![Cmd::Add(1), Cmd::Add(2)],
+            EventContext::default(),
+        )
+        .await?;
+
+    let recorder = Arc::new(Recorder::default());
+    let engine = Arc::new(
+        EventEngine::builder()
+            .event_bus(Arc::new(InMemoryEventBus::new(16)))
+            .event_deliverer(Arc::new(TransactionalOutboxDeliverer::new(store.clone())))
+            .event_reclaimer(Arc::new(NoReclaim))
+            .event_handlers(vec![recorder.clone() as Arc<dyn EventHandler>])
+            .config(EventEngineConfig {
+                deliver_interval: Duration::from_millis(10),
+                ..Default::default()
+            })
+            .build(),
+    );
+    let handle = engine.start();
+
+    tokio::time::timeout(Duration::from_secs(2), async {
+        while store.pending_count() > 0 || recorder.seen.lock().unwrap().len() < 2 {
+            tokio::time::sleep(Duration::from_millis(5)).await;
+        }
+    })
+    .await?;
+    handle.shutdown();
+    handle.join().await;
+
+    let expected: Vec<String> = envelopes
+        .iter()
+        .map(|e| e.payload.event_id().to_string())
+        .collect();
+    assert_eq!(*recorder.seen.lock().unwrap(), expected);
+    Ok(())
+}
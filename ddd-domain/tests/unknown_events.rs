@@ -0,0 +1,120 @@
+#![cfg(feature = "testing")]
+use anyhow::Result as AnyResult;
+use chrono::Utc;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::aggregate_root::AggregateRoot;
+use ddd_domain::domain_event::EventContext;
+use ddd_domain::entity::Entity;
+use ddd_domain::error::DomainError;
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::persist::{
+    AggregateRepository, EventRepository, EventSourcedRepo, SerializedEvent, UnknownEventPolicy,
+};
+use ddd_domain::testing::InMemoryEventRepository;
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Customer {
+    name: String,
+}
+
+#[derive(Debug)]
+enum Cmd {
+    Register { name: String },
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum Evt {
+    #[event(event_type = "customer.registered")]
+    Registered { name: String },
+}
+
+impl Aggregate for Customer {
+    const TYPE: &'static str = "customer";
+    type Command = Cmd;
+    type Event = Evt;
+    type Error = DomainError;
+
+    fn execute(&self, command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        let Cmd::Register { name } = command;
+        Ok(vec![Evt::Registered {
+            id: ulid::Ulid::new().to_string(),
+            aggregate_version: self.version().next(),
+            name,
+        }])
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        let Evt::Registered {
+            aggregate_version,
+            name,
+            ..
+        } = event;
+        self.name = name.clone();
+        self.version = *aggregate_version;
+    }
+}
+
+/// 由更新版本的服务写入、当前版本不认识的事件
+fn newer_event(id: &str) -> SerializedEvent {
+    SerializedEvent::builder()
+        .event_id("e-newer".to_string())
+        .event_type("customer.tier_upgraded".to_string())
+        .event_version(1)
+        .aggregate_id(id.to_string())
+        .aggregate_type(Customer::TYPE.to_string())
+        .aggregate_version(2)
+        .occurred_at(Utc::now())
+        .payload(json!({ "TierUpgraded": { "tier": "gold" } }))
+        .context(json!({}))
+        .build()
+}
+
+#[tokio::test]
+async fn replay_applies_unknown_event_policy() -> AnyResult<()> {
+    let event_repo = Arc::new(InMemoryEventRepository::new());
+    let upcasters = Arc::new(EventUpcasterChain::default());
+    let repo = Arc::new(EventSourcedRepo::new(event_repo.clone(), upcasters.clone()));
+    let id = "c-1".to_string();
+
+    AggregateRoot::<Customer, _>::new(repo.clone())
+        .execute(
+            &id,
+            vec![Cmd::Register {
+                name: "Alice".into(),
+            }],
+            EventContext::default(),
+        )
+        .await?;
+    event_repo.save(vec![newer_event(&id)]).await?;
+
+    // 默认策略：反序列化失败
+    assert!(
+        AggregateRepository::<Customer>::load(repo.as_ref(), &id)
+            .await
+            .is_err()
+    );
+
+    let skipping = EventSourcedRepo::new(event_repo.clone(), upcasters.clone())
+        .with_unknown_event_policy(UnknownEventPolicy::Skip);
+    let customer: Customer = skipping.load(&id).await?.unwrap();
+    assert_eq!(customer.name, "Alice");
+
+    let policy = UnknownEventPolicy::collect(10);
+    let log = policy.log().unwrap().clone();
+    let collecting = EventSourcedRepo::new(event_repo, upcasters).with_unknown_event_policy(policy);
+    let customer: Customer = collecting.load(&id).await?.unwrap();
+    assert_eq!(customer.name, "Alice");
+    assert_eq!(
+        log.counts(),
+        BTreeMap::from([("customer.tier_upgraded".to_string(), 1)])
+    );
+    assert_eq!(log.recent()[0].event_id(), "e-newer");
+    Ok(())
+}
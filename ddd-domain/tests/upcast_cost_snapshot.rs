@@ -0,0 +1,103 @@
+#![cfg(feature = "testing")]
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::domain_event::DomainEvent;
+use ddd_domain::entity::Entity;
+use ddd_domain::error::{DomainError, DomainResult};
+use ddd_domain::event_upcaster::{EventUpcaster, EventUpcasterChain, EventUpcasterResult};
+use ddd_domain::persist::{
+    SerializedEvent, SnapshotPolicy, SnapshotRepository, SnapshotRepositoryWithPolicy,
+    UpcastCostStrategy,
+};
+use ddd_domain::testing::InMemorySnapshotRepository;
+use ddd_domain::value_object::Version;
+use ddd_macros::{domain_event, entity};
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Ledger {
+    balance: i64,
+}
+
+#[domain_event]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum LedgerEvent {
+    #[event(event_type = "ledger.posted", event_version = 3)]
+    Posted { amount: i64 },
+    #[event(event_type = "ledger.noted", event_version = 1)]
+    Noted { note: String },
+}
+
+impl Aggregate for Ledger {
+    const TYPE: &'static str = "ledger";
+    type Command = ();
+    type Event = LedgerEvent;
+    type Error = DomainError;
+
+    fn execute(&self, _command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        Ok(vec![])
+    }
+
+    fn apply(&mut self, _event: &Self::Event) {}
+}
+
+/// 将 `ledger.posted` 从 `from` 版本升级一级
+struct BumpPosted {
+    from: usize,
+}
+
+impl EventUpcaster for BumpPosted {
+    fn applies(&self, event_type: &str, event_version: usize) -> bool {
+        event_type == "ledger.posted" && event_version == self.from
+    }
+
+    fn upcast(&self, event: SerializedEvent) -> DomainResult<EventUpcasterResult> {
+        let payload = event.payload().clone();
+        Ok(EventUpcasterResult::One(
+            event.upcasted(payload, self.from + 1),
+        ))
+    }
+}
+
+fn ledger(version: usize) -> Ledger {
+    Ledger::new("l-1".to_string(), Version::from_value(version))
+}
+
+#[tokio::test]
+async fn snapshots_sooner_when_upcast_chain_is_long() {
+    let chain: EventUpcasterChain = [
+        Arc::new(BumpPosted { from: 1 }) as Arc<dyn EventUpcaster>,
+        Arc::new(BumpPosted { from: 2 }),
+    ]
+    .into_iter()
+    .collect();
+    assert_eq!(chain.depth("ledger.posted", 3), 2);
+    assert_eq!(chain.depth("ledger.noted", 1), 0);
+
+    // 上抬两步：间隔 12 / (1 + 2) = 4
+    let strategy = Arc::new(UpcastCostStrategy::new(Arc::new(chain), 12));
+    let inner = Arc::new(InMemorySnapshotRepository::new());
+    let repo = SnapshotRepositoryWithPolicy::new(inner.clone(), SnapshotPolicy::Every(12))
+        .with_strategy(strategy.clone());
+    assert_eq!(strategy.interval(Ledger::TYPE, LedgerEvent::DESCRIPTORS), 4);
+
+    for version in 1..=12 {
+        repo.save(&ledger(version)).await.unwrap();
+    }
+    let versions: Vec<usize> = inner
+        .all_snapshots()
+        .iter()
+        .map(|s| s.aggregate_version())
+        .collect();
+    assert_eq!(versions, [4, 8, 12]);
+
+    // 无上抬时退化为基础间隔，且不低于下限
+    let flat =
+        UpcastCostStrategy::new(Arc::new(EventUpcasterChain::default()), 12).with_min_interval(5);
+    assert_eq!(flat.interval(Ledger::TYPE, LedgerEvent::DESCRIPTORS), 12);
+    let repo = SnapshotRepositoryWithPolicy::new(inner, SnapshotPolicy::Never)
+        .with_strategy(Arc::new(flat));
+    assert!(repo.should_snapshot::<Ledger>(24));
+    assert!(!repo.should_snapshot::<Ledger>(4));
+}
@@ -0,0 +1,107 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::punctuated::Punctuated;
+use syn::{
+    Ident, ImplItem, ItemImpl, LitStr, Result, Token, parse::Parse, parse::ParseStream,
+    parse_macro_input,
+};
+
+/// #[apply_serialized] 宏实现
+/// - 作用于 `impl Aggregate for X` 块，向其中生成 `apply_serialized` 快速路径
+/// - 参数：`#[apply_serialized("order.placed" => on_placed, ...)]`，按事件类型分派到聚合的方法
+/// - 方法签名为 `fn(&mut self, fields: T)`，`T: Deserialize` 只声明需要的字段（可借用字符串），
+///   由 `SerializedEvent::payload_fields` 按事件的载荷格式反序列化
+/// - 未列出的事件类型返回 `false`，回退为完整反序列化后调用 `apply`
+pub(crate) fn expand(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let routes = parse_macro_input!(attr as ApplyRoutes);
+    let item_impl = parse_macro_input!(item as ItemImpl);
+
+    match expand_apply_serialized(routes, item_impl) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand_apply_serialized(
+    routes: ApplyRoutes,
+    mut item_impl: ItemImpl,
+) -> Result<proc_macro2::TokenStream> {
+    if item_impl.trait_.is_none() {
+        return Err(syn::Error::new_spanned(
+            &item_impl.self_ty,
+            "#[apply_serialized] must be placed on `impl Aggregate for ...`",
+        ));
+    }
+    if routes.0.is_empty() {
+        return Err(syn::Error::new(
+            proc_macro2::Span::call_site(),
+            "#[apply_serialized] requires at least one `\"event_type\" => method` route",
+        ));
+    }
+
+    let mut seen = std::collections::HashSet::new();
+    for route in &routes.0 {
+        if !seen.insert(route.event_type.value()) {
+            return Err(syn::Error::new(
+                route.event_type.span(),
+                format!("duplicate event type '{}'", route.event_type.value()),
+            ));
+        }
+    }
+
+    let arms = routes.0.iter().map(|route| {
+        let event_type = &route.event_type;
+        let method = &route.method;
+        quote! {
+            #event_type => {
+                let fields = event.payload_fields::<
+                    <Self as ::ddd_domain::aggregate::Aggregate>::Event,
+                    _,
+                >()?;
+                self.#method(fields);
+                ::core::result::Result::Ok(true)
+            }
+        }
+    });
+
+    let method: ImplItem = syn::parse_quote! {
+        fn apply_serialized(
+            &mut self,
+            event: &::ddd_domain::persist::SerializedEvent,
+        ) -> ::serde_json::Result<bool> {
+            match event.event_type() {
+                #(#arms)*
+                _ => ::core::result::Result::Ok(false),
+            }
+        }
+    };
+    item_impl.items.push(method);
+
+    Ok(quote! { #item_impl })
+}
+
+// -------- parsing --------
+
+struct ApplyRoutes(Punctuated<ApplyRoute, Token![,]>);
+
+impl Parse for ApplyRoutes {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self(Punctuated::parse_terminated(input)?))
+    }
+}
+
+struct ApplyRoute {
+    event_type: LitStr,
+    method: Ident,
+}
+
+impl Parse for ApplyRoute {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let event_type = input.parse()?;
+        input.parse::<Token![=>]>()?;
+        Ok(Self {
+            event_type,
+            method: input.parse()?,
+        })
+    }
+}
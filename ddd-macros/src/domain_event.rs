@@ -1,3 +1,4 @@
+use crate::pii::{PiiAnnotation, json_pointer, serde_name, take_pii_fields};
 use crate::utils::{apply_derives, ensure_required_fields};
 use proc_macro::TokenStream;
 use quote::{ToTokens, quote};
@@ -13,8 +14,14 @@ use syn::{
 /// - 单元变体和元组变体自动转换为命名字段变体
 /// - 确保每个变体具备字段：`id: IdType`, `aggregate_version: Version`
 /// - 生成 `::ddd_domain::domain_event::DomainEvent` 实现（event_id/type/version/aggregate_version）
+/// - 为每个变体生成 `EventDescriptor` 关联常量（变体名的大写蛇形，如 `AccountOpened` -> `ACCOUNT_OPENED`），
+///   并汇总到 `DomainEvent::DESCRIPTORS`
 /// - 支持：`#[event(id = IdType, version = N)]`（枚举级默认值）
+/// - 支持：`#[domain_event(payload = "event_type")]`，载荷按 `event_type` 存储（不含变体名），
+///   生成 `DomainEvent::PAYLOAD_FORMAT`；默认 `"variant"` 保持 serde 外部标签
 /// - 变体可覆写：`#[event(event_type = "...", event_version = N)]`
+/// - 字段可标注：`#[pii(category = "...", policy = "encrypt" | "tokenize" | erase_after_days = N)]`，
+///   生成 `DomainEvent::PII_FIELDS`（指针按载荷格式计算，尊重 `#[serde(rename)]`）
 pub(crate) fn expand(attr: TokenStream, item: TokenStream) -> TokenStream {
     let cfg = parse_macro_input!(attr as EventAttrConfig);
     let mut input = parse_macro_input!(item as Item);
@@ -33,6 +40,25 @@ pub(crate) fn expand(attr: TokenStream, item: TokenStream) -> TokenStream {
 
     let id_type = cfg.id_ty.unwrap_or_else(|| syn::parse_quote! { String });
     let version_lit = cfg.version.unwrap_or_else(|| syn::parse_quote! { 1 });
+    let event_type_payload = cfg
+        .payload
+        .as_ref()
+        .is_some_and(|l| l.value() == "event_type");
+    let payload_format = match &cfg.payload {
+        None => quote! { VariantTagged },
+        Some(lit) => match lit.value().as_str() {
+            "variant" => quote! { VariantTagged },
+            "event_type" => quote! { EventType },
+            _ => {
+                return syn::Error::new(
+                    lit.span(),
+                    "unknown payload format; expected \"variant\" | \"event_type\"",
+                )
+                .to_compile_error()
+                .into();
+            }
+        },
+    };
 
     // 合并/追加默认派生：Debug, Clone, PartialEq, Serialize, Deserialize
     let required: Vec<syn::Path> = vec![
@@ -46,6 +72,7 @@ pub(crate) fn expand(attr: TokenStream, item: TokenStream) -> TokenStream {
 
     let mut variant_types: HashMap<String, syn::LitStr> = HashMap::new();
     let mut variant_versions: HashMap<String, syn::LitInt> = HashMap::new();
+    let mut variant_pii: Vec<(Ident, String, Vec<PiiAnnotation>)> = Vec::new();
 
     for v in &mut enum_item.variants {
         // 先处理变体属性（所有变体类型通用）
@@ -118,24 +145,67 @@ pub(crate) fn expand(attr: TokenStream, item: TokenStream) -> TokenStream {
                 v.fields = syn::Fields::Named(convert_tuple_to_named(fields_unnamed, &id_type));
             }
         }
+
+        if let syn::Fields::Named(fields_named) = &mut v.fields {
+            match take_pii_fields(fields_named) {
+                Ok(fields) if !fields.is_empty() => {
+                    let tag = serde_name(&v.attrs).unwrap_or_else(|| v.ident.to_string());
+                    variant_pii.push((v.ident.clone(), tag, fields));
+                }
+                Ok(_) => {}
+                Err(err) => return err.to_compile_error().into(),
+            }
+        }
     }
 
     // 生成 DomainEvent 实现
     let enum_ident = &enum_item.ident;
     let enum_name_string = enum_ident.to_string();
 
-    let type_match_arms = enum_item.variants.iter().map(|v| {
-        let v_ident = &v.ident;
+    let type_lit_of = |v_ident: &Ident| -> syn::LitStr {
         let key = v_ident.to_string();
-        if let Some(lit) = variant_types.get(&key) {
-            quote! { Self::#v_ident { .. } => #lit }
-        } else {
+        variant_types.get(&key).cloned().unwrap_or_else(|| {
             let combined = format!("{}.{}", enum_name_string, key);
-            let lit = syn::LitStr::new(&combined, v_ident.span());
-            quote! { Self::#v_ident { .. } => #lit }
-        }
+            syn::LitStr::new(&combined, v_ident.span())
+        })
+    };
+    let version_lit_of = |v_ident: &Ident| -> syn::LitInt {
+        variant_versions
+            .get(&v_ident.to_string())
+            .cloned()
+            .unwrap_or_else(|| version_lit.clone())
+    };
+
+    let type_match_arms = enum_item.variants.iter().map(|v| {
+        let v_ident = &v.ident;
+        let lit = type_lit_of(v_ident);
+        quote! { Self::#v_ident { .. } => #lit }
     });
 
+    let enum_vis = &enum_item.vis;
+    let descriptor_consts: Vec<Ident> = enum_item
+        .variants
+        .iter()
+        .map(|v| Ident::new(&to_screaming_snake(&v.ident.to_string()), v.ident.span()))
+        .collect();
+    let descriptor_defs = enum_item
+        .variants
+        .iter()
+        .zip(&descriptor_consts)
+        .map(|(v, c)| {
+            let name = v.ident.to_string();
+            let ty = type_lit_of(&v.ident);
+            let ver = version_lit_of(&v.ident);
+            quote! {
+                #enum_vis const #c: ::ddd_domain::domain_event::EventDescriptor =
+                    ::ddd_domain::domain_event::EventDescriptor {
+                        variant: #name,
+                        event_type: #ty,
+                        event_version: #ver,
+                    };
+            }
+        });
+
     let id_match_arms = enum_item.variants.iter().map(|v| {
         let v_ident = &v.ident;
         quote! { Self::#v_ident { id, .. } => id.as_str() }
@@ -156,10 +226,39 @@ pub(crate) fn expand(attr: TokenStream, item: TokenStream) -> TokenStream {
         quote! { Self::#v_ident { aggregate_version, .. } => *aggregate_version }
     });
 
+    let pii_fields: Vec<proc_macro2::TokenStream> = variant_pii
+        .iter()
+        .flat_map(|(v_ident, tag, fields)| {
+            let ty = type_lit_of(v_ident);
+            fields
+                .iter()
+                .map(|f| {
+                    let pointer = if event_type_payload {
+                        json_pointer([f.name.as_str()])
+                    } else {
+                        json_pointer([tag.as_str(), f.name.as_str()])
+                    };
+                    f.to_field(Some(&ty), &pointer)
+                })
+                .collect::<Vec<_>>()
+        })
+        .collect();
+
     let out = quote! {
         #enum_item
 
+        #[allow(dead_code)]
+        impl #enum_ident {
+            #( #descriptor_defs )*
+        }
+
         impl ::ddd_domain::domain_event::DomainEvent for #enum_ident {
+            const DESCRIPTORS: &'static [::ddd_domain::domain_event::EventDescriptor] =
+                &[ #( Self::#descriptor_consts, )* ];
+            const PAYLOAD_FORMAT: ::ddd_domain::domain_event::PayloadFormat =
+                ::ddd_domain::domain_event::PayloadFormat::#payload_format;
+            const PII_FIELDS: &'static [::ddd_domain::persist::PiiField] = &[ #( #pii_fields, )* ];
+
             fn event_id(&self) -> &str { match self { #( #id_match_arms, )* } }
             fn event_type(&self) -> &str { match self { #( #type_match_arms, )* } }
             fn event_version(&self) -> usize { match self { #( #ver_match_arms, )* } }
@@ -172,6 +271,23 @@ pub(crate) fn expand(attr: TokenStream, item: TokenStream) -> TokenStream {
 
 // -------- utils & parsing --------
 
+/// `AccountOpened` -> `ACCOUNT_OPENED`，`HTTPRequestSent` -> `HTTP_REQUEST_SENT`
+fn to_screaming_snake(name: &str) -> String {
+    let chars: Vec<char> = name.chars().collect();
+    let mut out = String::with_capacity(name.len() + 4);
+    for (i, &ch) in chars.iter().enumerate() {
+        if ch.is_uppercase() && i > 0 {
+            let prev_lower = !chars[i - 1].is_uppercase() && chars[i - 1] != '_';
+            let next_lower = chars.get(i + 1).is_some_and(|c| c.is_lowercase());
+            if prev_lower || (chars[i - 1].is_uppercase() && next_lower) {
+                out.push('_');
+            }
+        }
+        out.extend(ch.to_uppercase());
+    }
+    out
+}
+
 struct VariantEventAttrConfig {
     ty: Option<syn::LitStr>,
     version: Option<syn::LitInt>,
@@ -330,19 +446,25 @@ fn convert_tuple_to_named(fields_unnamed: &syn::FieldsUnnamed, id_type: &Type) -
     }
 }
 
-// 枚举级配置：id 类型、默认版本号
+// 枚举级配置：id 类型、默认版本号、载荷格式
 struct EventAttrConfig {
     id_ty: Option<Type>,
     version: Option<syn::LitInt>,
+    payload: Option<syn::LitStr>,
 }
 
 impl Parse for EventAttrConfig {
     fn parse(input: ParseStream) -> Result<Self> {
         let mut id_ty: Option<Type> = None;
         let mut version: Option<syn::LitInt> = None;
+        let mut payload: Option<syn::LitStr> = None;
 
         if input.is_empty() {
-            return Ok(Self { id_ty, version });
+            return Ok(Self {
+                id_ty,
+                version,
+                payload,
+            });
         }
 
         let pairs: Punctuated<syn::ExprAssign, Token![,]> =
@@ -376,15 +498,29 @@ impl Parse for EventAttrConfig {
                     let lit: syn::LitInt = syn::parse2(assign.right.to_token_stream())?;
                     version = Some(lit);
                 }
+                "payload" => {
+                    if payload.is_some() {
+                        return Err(syn::Error::new(
+                            key_ident.span(),
+                            "duplicate key 'payload' in attribute",
+                        ));
+                    }
+                    let lit: syn::LitStr = syn::parse2(assign.right.to_token_stream())?;
+                    payload = Some(lit);
+                }
                 _ => {
                     return Err(syn::Error::new(
                         key_ident.span(),
-                        "unknown key; expected 'id' | 'version'",
+                        "unknown key; expected 'id' | 'version' | 'payload'",
                     ));
                 }
             }
         }
 
-        Ok(Self { id_ty, version })
+        Ok(Self {
+            id_ty,
+            version,
+            payload,
+        })
     }
 }
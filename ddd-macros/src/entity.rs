@@ -1,3 +1,4 @@
+use crate::pii::{json_pointer, take_pii_fields};
 use crate::utils::{apply_derives, ensure_required_fields};
 use proc_macro::TokenStream;
 use quote::quote;
@@ -13,6 +14,7 @@ use syn::{
 /// - 支持参数：`#[entity(id = IdType, debug = true|false)]`；
 ///   - `id` 默认 `String`
 ///   - `debug` 默认 `true`（派生 Debug）。当为 `false` 时不派生 Debug，便于用户自定义实现。
+/// - 字段可标注 `#[pii(category = "...", ...)]`，生成 `Entity::PII_FIELDS`（指针相对实体状态）
 pub(crate) fn expand(attr: TokenStream, item: TokenStream) -> TokenStream {
     let cfg = parse_macro_input!(attr as EntityAttrConfig);
     let input = parse_macro_input!(item as Item);
@@ -47,6 +49,14 @@ pub(crate) fn expand(attr: TokenStream, item: TokenStream) -> TokenStream {
         /*reposition_existing*/ true,
     );
 
+    let pii_fields: Vec<proc_macro2::TokenStream> = match take_pii_fields(fields_named) {
+        Ok(fields) => fields
+            .iter()
+            .map(|f| f.to_field(None, &json_pointer([f.name.as_str()])))
+            .collect(),
+        Err(err) => return err.to_compile_error().into(),
+    };
+
     // 合并/规范 derive：默认添加 Debug（可通过 debug=false 关闭）、Default、Serialize、Deserialize
     let mut required: Vec<syn::Path> = vec![
         syn::parse_quote!(Default),
@@ -73,6 +83,8 @@ pub(crate) fn expand(attr: TokenStream, item: TokenStream) -> TokenStream {
         impl #impl_generics ::ddd_domain::entity::Entity for #ident #ty_generics #where_clause {
             type Id = #id_type;
 
+            const PII_FIELDS: &'static [::ddd_domain::persist::PiiField] = &[ #( #pii_fields, )* ];
+
             fn new(aggregate_id: Self::Id, version: ::ddd_domain::value_object::Version) -> Self {
                 Self { id: aggregate_id, version, ..Default::default() }
             }
@@ -1,8 +1,12 @@
 //! DDD 辅助宏（拆分模块版）
 //! - 每个宏放置在独立文件，根仅做入口与转发
+mod apply_serialized;
 mod domain_event;
 mod entity;
 mod entity_id;
+mod pii;
+mod track_changes;
+mod upcaster;
 mod utils;
 mod value_object;
 
@@ -31,3 +35,21 @@ pub fn domain_event(attr: TokenStream, item: TokenStream) -> TokenStream {
 pub fn value_object(attr: TokenStream, item: TokenStream) -> TokenStream {
     value_object::expand(attr, item)
 }
+
+/// 事件上抬宏（由迁移函数生成 `EventUpcaster` 实现）
+#[proc_macro_attribute]
+pub fn upcaster(attr: TokenStream, item: TokenStream) -> TokenStream {
+    upcaster::expand(attr, item)
+}
+
+/// 按需反序列化的重建快速路径（向 `impl Aggregate` 生成 `apply_serialized`）
+#[proc_macro_attribute]
+pub fn apply_serialized(attr: TokenStream, item: TokenStream) -> TokenStream {
+    apply_serialized::expand(attr, item)
+}
+
+/// 字段变更跟踪派生（比较前后两个实例，生成 `TrackChanges` 实现）
+#[proc_macro_derive(TrackChanges, attributes(track))]
+pub fn track_changes(input: TokenStream) -> TokenStream {
+    track_changes::expand(input)
+}
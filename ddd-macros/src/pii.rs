@@ -0,0 +1,159 @@
+use proc_macro2::TokenStream as TokenStream2;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{Attribute, FieldsNamed, LitInt, LitStr, Result};
+
+/// 字段上的 `#[pii(...)]` 声明
+/// - `category = "email"`（必填）
+/// - `policy = "encrypt" | "tokenize"`，或 `erase_after_days = N`；缺省为加密
+pub(crate) struct PiiAnnotation {
+    /// 序列化后的字段名（尊重 `#[serde(rename = "...")]`）
+    pub(crate) name: String,
+    category: LitStr,
+    policy: TokenStream2,
+}
+
+impl PiiAnnotation {
+    /// 生成 `::ddd_domain::persist::PiiField` 常量表达式
+    pub(crate) fn to_field(&self, event_type: Option<&LitStr>, pointer: &str) -> TokenStream2 {
+        let event_type = match event_type {
+            Some(lit) => quote! { ::core::option::Option::Some(#lit) },
+            None => quote! { ::core::option::Option::None },
+        };
+        let category = &self.category;
+        let policy = &self.policy;
+        quote! {
+            ::ddd_domain::persist::PiiField {
+                event_type: #event_type,
+                pointer: #pointer,
+                category: #category,
+                policy: #policy,
+            }
+        }
+    }
+}
+
+/// 取出并移除具名字段上的 `#[pii(...)]`
+pub(crate) fn take_pii_fields(fields: &mut FieldsNamed) -> Result<Vec<PiiAnnotation>> {
+    let mut out = Vec::new();
+    for field in fields.named.iter_mut() {
+        let Some(ident) = &field.ident else {
+            continue;
+        };
+        let name = serde_name(&field.attrs).unwrap_or_else(|| ident.to_string());
+
+        let mut retained = Vec::new();
+        let mut annotation: Option<PiiAnnotation> = None;
+        for attr in field.attrs.drain(..) {
+            if !attr.path().is_ident("pii") {
+                retained.push(attr);
+                continue;
+            }
+            if annotation.is_some() {
+                return Err(syn::Error::new(
+                    attr.span(),
+                    "duplicate #[pii] attribute on field",
+                ));
+            }
+            annotation = Some(parse_pii_attr(&attr, name.clone())?);
+        }
+        field.attrs = retained;
+        out.extend(annotation);
+    }
+    Ok(out)
+}
+
+/// `#[serde(rename = "...")]` 指定的名称
+pub(crate) fn serde_name(attrs: &[Attribute]) -> Option<String> {
+    let mut name = None;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("serde")) {
+        // 其他 serde 键不关心，解析失败时沿用已取得的名称
+        let _ = attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("rename") && meta.input.peek(syn::Token![=]) {
+                let lit: LitStr = meta.value()?.parse()?;
+                name = Some(lit.value());
+            } else if meta.input.peek(syn::Token![=]) {
+                let _: syn::Expr = meta.value()?.parse()?;
+            } else if meta.input.peek(syn::token::Paren) {
+                meta.parse_nested_meta(|nested| {
+                    if nested.input.peek(syn::Token![=]) {
+                        let _: syn::Expr = nested.value()?.parse()?;
+                    }
+                    Ok(())
+                })?;
+            }
+            Ok(())
+        });
+    }
+    name
+}
+
+/// 按 RFC 6901 拼接 JSON Pointer
+pub(crate) fn json_pointer<'a>(segments: impl IntoIterator<Item = &'a str>) -> String {
+    segments
+        .into_iter()
+        .map(|s| format!("/{}", s.replace('~', "~0").replace('/', "~1")))
+        .collect()
+}
+
+fn parse_pii_attr(attr: &Attribute, name: String) -> Result<PiiAnnotation> {
+    let mut category: Option<LitStr> = None;
+    let mut policy: Option<TokenStream2> = None;
+
+    attr.parse_nested_meta(|meta| {
+        let key = meta
+            .path
+            .get_ident()
+            .map(ToString::to_string)
+            .unwrap_or_default();
+        match key.as_str() {
+            "category" => {
+                if category.is_some() {
+                    return Err(meta.error("duplicate key 'category' in attribute"));
+                }
+                category = Some(meta.value()?.parse()?);
+            }
+            "policy" => {
+                if policy.is_some() {
+                    return Err(
+                        meta.error("duplicate policy; use either 'policy' or 'erase_after_days'")
+                    );
+                }
+                let lit: LitStr = meta.value()?.parse()?;
+                policy = Some(match lit.value().as_str() {
+                    "encrypt" => quote! { ::ddd_domain::persist::PiiPolicy::Encrypt },
+                    "tokenize" => quote! { ::ddd_domain::persist::PiiPolicy::Tokenize },
+                    _ => {
+                        return Err(syn::Error::new(
+                            lit.span(),
+                            "unknown policy; expected \"encrypt\" | \"tokenize\"",
+                        ));
+                    }
+                });
+            }
+            "erase_after_days" => {
+                if policy.is_some() {
+                    return Err(
+                        meta.error("duplicate policy; use either 'policy' or 'erase_after_days'")
+                    );
+                }
+                let days: LitInt = meta.value()?.parse()?;
+                policy = Some(quote! { ::ddd_domain::persist::PiiPolicy::erase_after_days(#days) });
+            }
+            _ => {
+                return Err(
+                    meta.error("unknown key; expected 'category' | 'policy' | 'erase_after_days'")
+                );
+            }
+        }
+        Ok(())
+    })?;
+
+    let category = category
+        .ok_or_else(|| syn::Error::new(attr.span(), "#[pii] requires 'category = \"...\"'"))?;
+    Ok(PiiAnnotation {
+        name,
+        category,
+        policy: policy.unwrap_or_else(|| quote! { ::ddd_domain::persist::PiiPolicy::Encrypt }),
+    })
+}
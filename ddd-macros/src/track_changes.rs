@@ -0,0 +1,83 @@
+use proc_macro::TokenStream;
+use quote::quote;
+use syn::spanned::Spanned;
+use syn::{Data, DeriveInput, Fields, parse_macro_input};
+
+/// 实体自带的标识与版本字段，不视为业务状态变更
+const IMPLICIT_SKIP: &[&str] = &["id", "version"];
+
+/// #[derive(TrackChanges)] 实现
+/// - 仅支持具名字段结构体，逐字段以 `PartialEq` 比较，变化字段的新旧值以 JSON 记录（需 `Serialize`）
+/// - 跳过 `id`/`version` 与标注 `#[track(skip)]` 的字段
+pub(crate) fn expand(input: TokenStream) -> TokenStream {
+    let input = parse_macro_input!(input as DeriveInput);
+
+    let fields = match &input.data {
+        Data::Struct(data) => match &data.fields {
+            Fields::Named(fields) => &fields.named,
+            _ => {
+                return syn::Error::new(input.span(), "only supports named-field struct")
+                    .to_compile_error()
+                    .into();
+            }
+        },
+        _ => {
+            return syn::Error::new(input.span(), "#[derive(TrackChanges)] only on struct")
+                .to_compile_error()
+                .into();
+        }
+    };
+
+    let mut comparisons = Vec::new();
+    for field in fields {
+        let ident = field.ident.as_ref().expect("named field");
+        let skip = match track_skip(&field.attrs) {
+            Ok(skip) => skip,
+            Err(e) => return e.to_compile_error().into(),
+        };
+        if skip || IMPLICIT_SKIP.iter().any(|name| ident == name) {
+            continue;
+        }
+
+        let name = ident.to_string();
+        comparisons.push(quote! {
+            if self.#ident != new.#ident {
+                changes.push((
+                    #name.to_string(),
+                    ::ddd_domain::domain_event::FieldChanged::json(&self.#ident, &new.#ident),
+                ));
+            }
+        });
+    }
+
+    let ident = &input.ident;
+    let (impl_generics, ty_generics, where_clause) = input.generics.split_for_impl();
+
+    let expanded = quote! {
+        impl #impl_generics ::ddd_domain::domain_event::TrackChanges for #ident #ty_generics #where_clause {
+            fn changes(&self, new: &Self) -> ::ddd_domain::domain_event::FieldChanges {
+                let mut changes = ::std::vec::Vec::new();
+                #( #comparisons )*
+                changes
+            }
+        }
+    };
+
+    TokenStream::from(expanded)
+}
+
+// 解析字段上的 `#[track(skip)]`
+fn track_skip(attrs: &[syn::Attribute]) -> syn::Result<bool> {
+    let mut skip = false;
+    for attr in attrs.iter().filter(|a| a.path().is_ident("track")) {
+        attr.parse_nested_meta(|meta| {
+            if meta.path.is_ident("skip") {
+                skip = true;
+                Ok(())
+            } else {
+                Err(meta.error("unsupported track attribute, expected `skip`"))
+            }
+        })?;
+    }
+    Ok(skip)
+}
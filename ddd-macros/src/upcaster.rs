@@ -0,0 +1,266 @@
+use proc_macro::TokenStream;
+use quote::{format_ident, quote};
+use syn::punctuated::Punctuated;
+use syn::spanned::Spanned;
+use syn::{
+    Expr, FnArg, Ident, ItemFn, Result, ReturnType, Token, Type, parse::Parse, parse::ParseStream,
+    parse_macro_input,
+};
+
+/// #[upcaster] 宏实现
+/// - 作用于函数，保留原函数并生成同名大驼峰单元结构体（如 `credit_v1_to_v2` -> `CreditV1ToV2`），
+///   实现 `::ddd_domain::event_upcaster::EventUpcaster`
+/// - 参数：`#[upcaster(event_type = "...", from = N, to = M, variant = "...")]`，`variant` 可选
+/// - 函数签名两种形式：
+///   - 原地修改：`fn(&mut serde_json::Value)`，返回 `()` 或 `Result<(), E>`
+///   - 类型化：`fn(Old) -> New` 或 `fn(Old) -> Result<New, E>`，`Old: Deserialize`、`New: Serialize`
+/// - 指定 `variant` 时函数作用于 `payload[variant]`（`#[domain_event]` 枚举的外部标签负载）
+/// - 生成的 `upcast` 仅替换负载与版本，其余信封字段原样保留；错误统一转换为 `DomainError::upcast_failed`
+pub(crate) fn expand(attr: TokenStream, item: TokenStream) -> TokenStream {
+    let cfg = parse_macro_input!(attr as UpcasterAttrConfig);
+    let func = parse_macro_input!(item as ItemFn);
+
+    match expand_upcaster(cfg, &func) {
+        Ok(ts) => ts.into(),
+        Err(e) => e.to_compile_error().into(),
+    }
+}
+
+fn expand_upcaster(cfg: UpcasterAttrConfig, func: &ItemFn) -> Result<proc_macro2::TokenStream> {
+    let span = proc_macro2::Span::call_site();
+    let event_type = cfg
+        .event_type
+        .ok_or_else(|| syn::Error::new(span, "missing required key 'event_type'"))?;
+    let from = cfg
+        .from
+        .ok_or_else(|| syn::Error::new(span, "missing required key 'from'"))?;
+    let to = cfg
+        .to
+        .ok_or_else(|| syn::Error::new(span, "missing required key 'to'"))?;
+    if from.base10_parse::<usize>()? >= to.base10_parse::<usize>()? {
+        return Err(syn::Error::new(
+            to.span(),
+            "'to' must be greater than 'from'",
+        ));
+    }
+
+    let sig = &func.sig;
+    if sig.asyncness.is_some() || !sig.generics.params.is_empty() {
+        return Err(syn::Error::new(
+            sig.span(),
+            "#[upcaster] requires a non-async, non-generic function",
+        ));
+    }
+    let arg_ty = match (sig.inputs.len(), sig.inputs.first()) {
+        (1, Some(FnArg::Typed(pat))) => &*pat.ty,
+        _ => {
+            return Err(syn::Error::new(
+                sig.inputs.span(),
+                "#[upcaster] function must take exactly one argument: `&mut serde_json::Value` or a payload struct",
+            ));
+        }
+    };
+
+    let fn_name = &sig.ident;
+    let vis = &func.vis;
+    let struct_name = format_ident!("{}", to_upper_camel(&fn_name.to_string()));
+    let stage = fn_name.to_string();
+
+    let fail = quote! {
+        |e| ::ddd_domain::error::DomainError::upcast_failed(
+            event.event_type(),
+            event.event_version(),
+            ::core::option::Option::Some(#stage),
+            e.to_string(),
+        )
+    };
+    let returns_result = returns_result(&sig.output);
+
+    // 将 `target: &mut Value` 转换为目标负载
+    let convert = match arg_ty {
+        Type::Reference(r) if r.mutability.is_some() => {
+            if returns_result {
+                quote! { #fn_name(target).map_err(#fail)?; }
+            } else {
+                quote! { #fn_name(target); }
+            }
+        }
+        Type::Reference(_) => {
+            return Err(syn::Error::new(
+                arg_ty.span(),
+                "expected `&mut serde_json::Value` or an owned payload type",
+            ));
+        }
+        old_ty => {
+            let call = if returns_result {
+                quote! { #fn_name(old).map_err(#fail)? }
+            } else {
+                quote! { #fn_name(old) }
+            };
+            quote! {
+                let old: #old_ty = ::serde_json::from_value(target.take()).map_err(#fail)?;
+                *target = ::serde_json::to_value(#call).map_err(#fail)?;
+            }
+        }
+    };
+
+    let select = match &cfg.variant {
+        Some(variant) => quote! {
+            let target = payload.get_mut(#variant).ok_or_else(|| {
+                ::ddd_domain::error::DomainError::upcast_failed(
+                    event.event_type(),
+                    event.event_version(),
+                    ::core::option::Option::Some(#stage),
+                    ::std::format!("payload has no variant `{}`", #variant),
+                )
+            })?;
+        },
+        None => quote! { let target = &mut payload; },
+    };
+
+    let doc = format!(
+        "`{}` 由 v{} 上抬至 v{}（由 `#[upcaster]` 生成）",
+        event_type.value(),
+        from,
+        to
+    );
+
+    Ok(quote! {
+        #func
+
+        #[doc = #doc]
+        #[derive(Clone, Copy, Debug, Default)]
+        #vis struct #struct_name;
+
+        impl ::ddd_domain::event_upcaster::EventUpcaster for #struct_name {
+            fn applies(&self, event_type: &str, event_version: usize) -> bool {
+                event_type == #event_type && event_version == #from
+            }
+
+            fn upcast(
+                &self,
+                event: ::ddd_domain::persist::SerializedEvent,
+            ) -> ::ddd_domain::error::DomainResult<::ddd_domain::event_upcaster::EventUpcasterResult> {
+                let mut payload = event.payload().clone();
+                {
+                    #select
+                    #convert
+                }
+                ::core::result::Result::Ok(::ddd_domain::event_upcaster::EventUpcasterResult::One(
+                    event.upcasted(payload, #to),
+                ))
+            }
+        }
+    })
+}
+
+/// 返回类型末段以 `Result` 结尾（`Result`、`DomainResult` 等）视为可失败
+fn returns_result(output: &ReturnType) -> bool {
+    match output {
+        ReturnType::Type(_, ty) => match &**ty {
+            Type::Path(p) => p
+                .path
+                .segments
+                .last()
+                .is_some_and(|s| s.ident.to_string().ends_with("Result")),
+            _ => false,
+        },
+        ReturnType::Default => false,
+    }
+}
+
+fn to_upper_camel(name: &str) -> String {
+    name.split('_')
+        .filter(|s| !s.is_empty())
+        .map(|s| {
+            let mut chars = s.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect::<String>(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+// -------- parsing --------
+
+#[derive(Default)]
+struct UpcasterAttrConfig {
+    event_type: Option<syn::LitStr>,
+    from: Option<syn::LitInt>,
+    to: Option<syn::LitInt>,
+    variant: Option<syn::LitStr>,
+}
+
+impl Parse for UpcasterAttrConfig {
+    fn parse(input: ParseStream) -> Result<Self> {
+        let mut cfg = Self::default();
+        let pairs: Punctuated<UpcasterAttrKv, Token![,]> = Punctuated::parse_terminated(input)?;
+
+        for kv in pairs {
+            let key = kv.key.to_string();
+            let duplicate = match key.as_str() {
+                "event_type" => cfg.event_type.replace(expect_str(&kv)?).is_some(),
+                "variant" => cfg.variant.replace(expect_str(&kv)?).is_some(),
+                "from" => cfg.from.replace(expect_int(&kv)?).is_some(),
+                "to" => cfg.to.replace(expect_int(&kv)?).is_some(),
+                _ => {
+                    return Err(syn::Error::new(
+                        kv.key.span(),
+                        "unknown key; expected 'event_type' | 'from' | 'to' | 'variant'",
+                    ));
+                }
+            };
+            if duplicate {
+                return Err(syn::Error::new(
+                    kv.key.span(),
+                    format!("duplicate key '{key}' in attribute"),
+                ));
+            }
+        }
+        Ok(cfg)
+    }
+}
+
+fn expect_str(kv: &UpcasterAttrKv) -> Result<syn::LitStr> {
+    match &kv.value {
+        Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Str(lit),
+            ..
+        }) => Ok(lit.clone()),
+        other => Err(syn::Error::new(
+            other.span(),
+            format!("expected string literal for '{}'", kv.key),
+        )),
+    }
+}
+
+fn expect_int(kv: &UpcasterAttrKv) -> Result<syn::LitInt> {
+    match &kv.value {
+        Expr::Lit(syn::ExprLit {
+            lit: syn::Lit::Int(lit),
+            ..
+        }) => Ok(lit.clone()),
+        other => Err(syn::Error::new(
+            other.span(),
+            format!("expected integer literal for '{}'", kv.key),
+        )),
+    }
+}
+
+struct UpcasterAttrKv {
+    key: Ident,
+    #[allow(dead_code)]
+    eq: Token![=],
+    value: Expr,
+}
+
+impl Parse for UpcasterAttrKv {
+    fn parse(input: ParseStream) -> Result<Self> {
+        Ok(Self {
+            key: input.parse()?,
+            eq: input.parse()?,
+            value: input.parse()?,
+        })
+    }
+}
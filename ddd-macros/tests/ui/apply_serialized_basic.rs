@@ -0,0 +1,84 @@
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::error::DomainError;
+use ddd_domain::persist::SerializedEvent;
+use ddd_domain::value_object::Version;
+use ddd_macros::{apply_serialized, domain_event, entity};
+use serde::{Deserialize, Serialize};
+use serde_json::json;
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Account {
+    balance: i64,
+}
+
+#[domain_event(version = 1, payload = "event_type")]
+#[derive(Debug, Clone, Serialize, Deserialize)]
+enum AccountEvent {
+    #[event(event_type = "account.credited")]
+    Credited { amount: i64, memo: String },
+    #[event(event_type = "account.closed")]
+    Closed,
+}
+
+#[derive(Deserialize)]
+struct CreditedFields {
+    aggregate_version: Version,
+    amount: i64,
+}
+
+impl Account {
+    fn on_credited(&mut self, fields: CreditedFields) {
+        self.balance += fields.amount;
+        self.version = fields.aggregate_version;
+    }
+}
+
+#[apply_serialized("account.credited" => on_credited)]
+impl Aggregate for Account {
+    const TYPE: &'static str = "account";
+    type Command = ();
+    type Event = AccountEvent;
+    type Error = DomainError;
+
+    fn execute(&self, _command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        Ok(vec![])
+    }
+
+    fn apply(&mut self, _event: &Self::Event) {}
+}
+
+fn event(event_type: &str, payload: serde_json::Value) -> SerializedEvent {
+    serde_json::from_value(json!({
+        "event_id": "e-1",
+        "event_type": event_type,
+        "event_version": 1,
+        "sequence_number": null,
+        "aggregate_id": "a-1",
+        "aggregate_type": "account",
+        "aggregate_version": 1,
+        "correlation_id": null,
+        "causation_id": null,
+        "actor_type": null,
+        "actor_id": null,
+        "occurred_at": "2024-01-01T00:00:00Z",
+        "payload": payload,
+        "context": {}
+    }))
+    .unwrap()
+}
+
+fn main() {
+    let mut account = Account::default();
+    let credited = event(
+        "account.credited",
+        json!({"id": "e-1", "aggregate_version": 1, "amount": 5, "memo": "ignored"}),
+    );
+    assert!(account.apply_serialized(&credited).unwrap());
+    assert_eq!(account.balance, 5);
+    assert_eq!(account.version, Version::from_value(1));
+
+    // 未列出的事件类型回退为完整反序列化
+    let closed = event("account.closed", json!({"id": "e-2", "aggregate_version": 2}));
+    assert!(!account.apply_serialized(&closed).unwrap());
+}
@@ -0,0 +1,44 @@
+use ddd_domain::domain_event::{DomainEvent, EventDescriptor};
+use ddd_macros::domain_event;
+
+#[domain_event(version = 1)]
+pub enum AccountEvent {
+    #[event(event_type = "account.opened")]
+    AccountOpened { owner: String },
+    #[event(event_version = 3)]
+    HTTPLimitRaised,
+    Closed,
+}
+
+fn main() {
+    // 无需构造事件实例即可访问元信息
+    assert_eq!(AccountEvent::ACCOUNT_OPENED.event_type, "account.opened");
+    assert_eq!(AccountEvent::ACCOUNT_OPENED.event_version, 1);
+    assert_eq!(AccountEvent::ACCOUNT_OPENED.variant, "AccountOpened");
+
+    assert_eq!(
+        AccountEvent::HTTP_LIMIT_RAISED.event_type,
+        "AccountEvent.HTTPLimitRaised"
+    );
+    assert_eq!(AccountEvent::HTTP_LIMIT_RAISED.event_version, 3);
+
+    assert_eq!(
+        AccountEvent::DESCRIPTORS,
+        &[
+            AccountEvent::ACCOUNT_OPENED,
+            AccountEvent::HTTP_LIMIT_RAISED,
+            AccountEvent::CLOSED
+        ]
+    );
+    assert_eq!(
+        EventDescriptor::find(AccountEvent::DESCRIPTORS, "AccountEvent.Closed"),
+        Some(&AccountEvent::CLOSED)
+    );
+
+    // 与实例上的元信息一致
+    let event = AccountEvent::Closed {
+        id: "e1".to_string(),
+        aggregate_version: Default::default(),
+    };
+    assert_eq!(event.event_type(), AccountEvent::CLOSED.event_type);
+}
@@ -0,0 +1,70 @@
+use ddd_domain::domain_event::DomainEvent;
+use ddd_domain::entity::Entity;
+use ddd_domain::persist::{PiiField, PiiPolicy};
+use ddd_macros::{domain_event, entity};
+
+#[domain_event(version = 1)]
+pub enum UserEvent {
+    #[event(event_type = "user.registered")]
+    Registered {
+        #[pii(category = "email")]
+        email: String,
+        #[pii(category = "phone", policy = "tokenize")]
+        #[serde(rename = "mobile")]
+        phone: String,
+        name: String,
+    },
+    Logged(#[pii(category = "ip", erase_after_days = 30)] String),
+}
+
+#[domain_event(payload = "event_type")]
+pub enum ContactEvent {
+    Changed {
+        #[pii(category = "email")]
+        email: String,
+    },
+}
+
+#[entity]
+pub struct User {
+    #[pii(category = "email", policy = "encrypt")]
+    email: String,
+    nickname: String,
+}
+
+fn main() {
+    assert_eq!(
+        UserEvent::PII_FIELDS,
+        &[
+            PiiField {
+                event_type: Some("user.registered"),
+                pointer: "/Registered/email",
+                category: "email",
+                policy: PiiPolicy::Encrypt,
+            },
+            PiiField {
+                event_type: Some("user.registered"),
+                pointer: "/Registered/mobile",
+                category: "phone",
+                policy: PiiPolicy::Tokenize,
+            },
+            PiiField {
+                event_type: Some("UserEvent.Logged"),
+                pointer: "/Logged/value",
+                category: "ip",
+                policy: PiiPolicy::erase_after_days(30),
+            },
+        ]
+    );
+    assert_eq!(ContactEvent::PII_FIELDS[0].pointer, "/email");
+    assert_eq!(
+        User::PII_FIELDS,
+        &[PiiField {
+            event_type: None,
+            pointer: "/email",
+            category: "email",
+            policy: PiiPolicy::Encrypt,
+        }]
+    );
+    let _ = User::default().nickname;
+}
@@ -0,0 +1,33 @@
+use ddd_domain::domain_event::{FieldChanged, TrackChanges};
+use ddd_macros::{TrackChanges, entity};
+use serde::{Deserialize, Serialize};
+
+#[entity]
+#[derive(Clone, PartialEq, Serialize, Deserialize, TrackChanges)]
+struct Profile {
+    name: String,
+    tags: Vec<String>,
+    #[track(skip)]
+    last_seen: u64,
+}
+
+fn main() {
+    let old = Profile {
+        name: "Alice".to_string(),
+        ..Default::default()
+    };
+    let mut new = old.clone();
+    new.name = "Alicia".to_string();
+    new.last_seen = 42;
+    new.version = new.version.next();
+
+    // id/version 与 #[track(skip)] 字段不计入变更
+    assert_eq!(
+        old.changes(&new),
+        vec![(
+            "name".to_string(),
+            FieldChanged::new(serde_json::json!("Alice"), serde_json::json!("Alicia"))
+        )]
+    );
+    assert!(new.changes(&new).is_empty());
+}
@@ -0,0 +1,96 @@
+use ddd_domain::error::DomainError;
+use ddd_domain::event_upcaster::{EventUpcaster, EventUpcasterChain, EventUpcasterResult};
+use ddd_domain::persist::SerializedEvent;
+use ddd_macros::upcaster;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::sync::Arc;
+
+#[upcaster(event_type = "account.credited", from = 1, to = 2, variant = "Credited")]
+fn credited_add_currency(payload: &mut Value) {
+    payload["currency"] = json!("CNY");
+}
+
+#[derive(Deserialize)]
+struct CreditedV2 {
+    amount: i64,
+    currency: String,
+}
+
+#[derive(Serialize)]
+struct CreditedV3 {
+    minor_units: i64,
+    currency: String,
+}
+
+#[upcaster(event_type = "account.credited", from = 2, to = 3, variant = "Credited")]
+fn credited_to_minor_units(old: CreditedV2) -> Result<CreditedV3, DomainError> {
+    if old.amount < 0 {
+        return Err(DomainError::invalid_value("negative amount"));
+    }
+    Ok(CreditedV3 {
+        minor_units: old.amount * 100,
+        currency: old.currency,
+    })
+}
+
+fn event(version: usize, payload: Value) -> SerializedEvent {
+    serde_json::from_value(json!({
+        "event_id": "e-1",
+        "event_type": "account.credited",
+        "event_version": version,
+        "sequence_number": 7,
+        "aggregate_id": "a-1",
+        "aggregate_type": "account",
+        "aggregate_version": 3,
+        "correlation_id": "c-1",
+        "causation_id": null,
+        "actor_type": "user",
+        "actor_id": "u-1",
+        "occurred_at": "2024-01-01T00:00:00Z",
+        "payload": payload,
+        "context": {"correlation_id": "c-1"}
+    }))
+    .unwrap()
+}
+
+fn main() {
+    assert!(CreditedAddCurrency.applies("account.credited", 1));
+    assert!(!CreditedAddCurrency.applies("account.credited", 2));
+    assert!(!CreditedAddCurrency.applies("account.debited", 1));
+
+    let chain: EventUpcasterChain = vec![
+        Arc::new(CreditedAddCurrency) as Arc<dyn EventUpcaster>,
+        Arc::new(CreditedToMinorUnits),
+    ]
+    .into_iter()
+    .collect();
+
+    let original = event(1, json!({"Credited": {"amount": 5}}));
+    let upcasted = chain.upcast_all(vec![original.clone()]).unwrap();
+    assert_eq!(upcasted.len(), 1);
+    let e = &upcasted[0];
+    assert_eq!(e.event_version(), 3);
+    assert_eq!(
+        e.payload(),
+        &json!({"Credited": {"minor_units": 500, "currency": "CNY"}})
+    );
+    // 信封字段原样保留
+    assert_eq!(e.event_id(), original.event_id());
+    assert_eq!(e.sequence_number(), original.sequence_number());
+    assert_eq!(e.aggregate_version(), original.aggregate_version());
+    assert_eq!(e.correlation_id(), Some("c-1"));
+    assert_eq!(e.actor_id(), Some("u-1"));
+    assert_eq!(e.occurred_at(), original.occurred_at());
+    assert_eq!(e.context(), original.context());
+
+    // 迁移函数返回错误或负载缺少变体时上抬失败
+    let negative = event(2, json!({"Credited": {"amount": -1, "currency": "CNY"}}));
+    assert!(CreditedToMinorUnits.upcast(negative).is_err());
+    let other = event(1, json!({"Debited": {"amount": 1}}));
+    assert!(matches!(CreditedAddCurrency.upcast(other), Err(_)));
+    assert!(matches!(
+        CreditedAddCurrency.upcast(event(1, json!({"Credited": {}}))),
+        Ok(EventUpcasterResult::One(_))
+    ));
+}
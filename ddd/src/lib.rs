@@ -0,0 +1,20 @@
+//! DDD 门面库（ddd）
+//!
+//! 聚合 `ddd-domain`、`ddd-application` 与 `ddd-macros` 的常用导出，并提供：
+//! - `prelude`：一次性导入聚合、事件、仓储、总线与宏等常用类型；
+//! - `DddRuntimeBuilder`：以流式构建器装配仓储、上抬链、事件引擎与命令/查询总线，
+//!   未指定的组件使用内存默认实现（需 `inmemory` 特性，默认开启）；
+//! - `DddRuntime::self_check`：启动自检，返回逐项结果的 `SelfCheckReport`。
+//!
+//! 注意：过程宏生成的代码引用 `::ddd_domain` 路径，使用宏的 crate 仍需直接依赖 `ddd-domain`。
+//!
+pub mod prelude;
+mod runtime;
+mod self_check;
+
+pub use ddd_application as application;
+pub use ddd_domain as domain;
+pub use ddd_macros as macros;
+
+pub use runtime::{DddRuntime, DddRuntimeBuilder};
+pub use self_check::{CheckResult, CheckStatus, SelfCheckReport};
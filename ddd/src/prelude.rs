@@ -0,0 +1,32 @@
+//! 常用类型一次性导入：`use ddd::prelude::*;`
+//!
+pub use crate::runtime::{DddRuntime, DddRuntimeBuilder};
+pub use crate::self_check::{CheckResult, CheckStatus, SelfCheckReport};
+
+pub use ddd_macros::{TrackChanges, domain_event, entity, entity_id, value_object};
+
+pub use ddd_domain::aggregate::Aggregate;
+pub use ddd_domain::aggregate_root::AggregateRoot;
+pub use ddd_domain::domain_event::{
+    DomainEvent, EventContext, EventEnvelope, FieldChanged, TrackChanges, next_event_id,
+};
+pub use ddd_domain::entity::Entity;
+pub use ddd_domain::error::{DomainError, DomainResult, ErrorCode, ErrorKind};
+pub use ddd_domain::event_upcaster::{EventUpcaster, EventUpcasterChain, EventUpcasterResult};
+pub use ddd_domain::eventing::{
+    EventBus, EventDeliverer, EventEngine, EventEngineConfig, EventHandler, EventReclaimer,
+    HandledEventType, HandlerContext, HandlerSubscription, InMemoryEventBus, OrderingGuarantee,
+};
+pub use ddd_domain::persist::{
+    AggregateRepository, EventRepository, EventSourcedRepo, SerializedEvent, SerializedSnapshot,
+    SnapshotPolicy, SnapshotPolicyRepo, SnapshotRepository,
+};
+pub use ddd_domain::value_object::Version;
+
+pub use ddd_application::command_bus::CommandBus;
+pub use ddd_application::command_handler::CommandHandler;
+pub use ddd_application::context::AppContext;
+pub use ddd_application::error::AppError;
+pub use ddd_application::query_bus::QueryBus;
+pub use ddd_application::query_handler::QueryHandler;
+pub use ddd_application::{InMemoryCommandBus, InMemoryQueryBus};
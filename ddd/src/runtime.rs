@@ -0,0 +1,384 @@
+use ddd_application::command_handler::CommandHandler;
+use ddd_application::error::AppError;
+use ddd_application::query_handler::QueryHandler;
+use ddd_application::{InMemoryCommandBus, InMemoryQueryBus};
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::aggregate_root::AggregateRoot;
+use ddd_domain::bounded_context::BoundedContext;
+#[cfg(feature = "config")]
+use ddd_domain::config::DddConfig;
+use ddd_domain::event_upcaster::{EventUpcaster, EventUpcasterChain};
+use ddd_domain::eventing::{
+    CheckpointStore, EngineHandle, EngineTuning, EventBus, EventDeliverer, EventEngine,
+    EventEngineConfig, EventHandler, EventReclaimer, InMemoryEventBus,
+};
+use ddd_domain::persist::{
+    AggregateRepository, EventRepository, SnapshotPolicy, SnapshotPolicyRepo, SnapshotRepository,
+    SnapshotRepositoryWithPolicy,
+};
+#[cfg(feature = "inmemory")]
+use ddd_domain::testing::{InMemoryEventRepository, InMemorySnapshotRepository};
+use std::sync::Arc;
+
+use crate::self_check::{AggregateRegistration, SelfCheckRegistry, SelfCheckReport};
+
+/// 内存事件总线的默认广播容量
+const DEFAULT_EVENT_BUS_CAPACITY: usize = 1024;
+
+/// 装配完成的运行时：仓储、上抬链、命令/查询总线与（可选的）事件引擎
+pub struct DddRuntime<E, S>
+where
+    E: EventRepository,
+    S: SnapshotRepository,
+{
+    event_repo: Arc<E>,
+    snapshot_repo: Arc<SnapshotRepositoryWithPolicy<Arc<S>>>,
+    upcaster_chain: Arc<EventUpcasterChain>,
+    command_bus: Arc<InMemoryCommandBus>,
+    query_bus: Arc<InMemoryQueryBus>,
+    event_bus: Arc<dyn EventBus>,
+    engine: Option<Arc<EventEngine>>,
+    self_check: SelfCheckRegistry,
+}
+
+#[cfg(feature = "inmemory")]
+impl DddRuntime<InMemoryEventRepository, InMemorySnapshotRepository> {
+    /// 以内存默认实现开始构建
+    pub fn builder() -> DddRuntimeBuilder<InMemoryEventRepository, InMemorySnapshotRepository> {
+        DddRuntimeBuilder::new(
+            Arc::new(InMemoryEventRepository::new()),
+            Arc::new(InMemorySnapshotRepository::new()),
+        )
+    }
+}
+
+impl<E, S> DddRuntime<E, S>
+where
+    E: EventRepository,
+    S: SnapshotRepository,
+{
+    pub fn event_repository(&self) -> &Arc<E> {
+        &self.event_repo
+    }
+
+    pub fn snapshot_repository(&self) -> &Arc<SnapshotRepositoryWithPolicy<Arc<S>>> {
+        &self.snapshot_repo
+    }
+
+    pub fn upcaster_chain(&self) -> &Arc<EventUpcasterChain> {
+        &self.upcaster_chain
+    }
+
+    pub fn command_bus(&self) -> &Arc<InMemoryCommandBus> {
+        &self.command_bus
+    }
+
+    pub fn query_bus(&self) -> &Arc<InMemoryQueryBus> {
+        &self.query_bus
+    }
+
+    pub fn event_bus(&self) -> &Arc<dyn EventBus> {
+        &self.event_bus
+    }
+
+    /// 事件引擎（仅在配置了 outbox 时存在）
+    pub fn engine(&self) -> Option<&Arc<EventEngine>> {
+        self.engine.as_ref()
+    }
+
+    /// 基于事件存储 + 快照策略的聚合仓储
+    pub fn aggregate_repository(&self) -> SnapshotPolicyRepo<E, Arc<S>> {
+        SnapshotPolicyRepo::new(
+            Arc::clone(&self.event_repo),
+            Arc::clone(&self.snapshot_repo),
+            Arc::clone(&self.upcaster_chain),
+        )
+    }
+
+    /// 指定聚合类型的命令编排器
+    pub fn aggregate_root<A>(&self) -> AggregateRoot<A, SnapshotPolicyRepo<E, Arc<S>>>
+    where
+        A: Aggregate,
+        SnapshotPolicyRepo<E, Arc<S>>: AggregateRepository<A>,
+    {
+        AggregateRoot::new(self.aggregate_repository())
+    }
+
+    /// 启动事件引擎；未配置 outbox 时返回 `None`
+    pub fn start_engine(&self) -> Option<EngineHandle> {
+        self.engine
+            .as_ref()
+            .map(|engine| Arc::clone(engine).start())
+    }
+
+    /// 启动自检：校验上抬器覆盖、事件类型登记、处理器订阅、检查点存储、仓储连通性与快照结构
+    ///
+    /// 启动时以 `runtime.self_check().await.into_result()?` 在装配错误时快速失败。
+    pub async fn self_check(&self) -> SelfCheckReport {
+        self.self_check
+            .run(
+                &self.upcaster_chain,
+                &*self.event_repo,
+                &*self.snapshot_repo,
+            )
+            .await
+    }
+}
+
+/// `DddRuntime` 的流式构建器
+///
+/// - 仓储：默认内存实现，可通过 `event_repository`/`snapshot_repository` 替换；
+/// - 快照策略：默认 `SnapshotPolicy::Never`；
+/// - 事件总线：默认 `InMemoryEventBus`；
+/// - 配置文件：`settings` 一次应用引擎参数、快照策略与内存总线容量（需 `config` 特性）；
+/// - 事件引擎：配置 `outbox(deliverer, reclaimer)` 后装配，注册的事件处理器由引擎调度，
+///   总线的顺序保证不满足处理器要求时 `build` 报错；
+/// - 命令/查询处理器：构建时注册到内存总线，重复注册在 `build` 时报错；
+/// - 启动自检：`aggregate`/`bounded_context`/`checkpoint_store` 登记的组件参与 `DddRuntime::self_check`。
+pub struct DddRuntimeBuilder<E, S> {
+    event_repo: Arc<E>,
+    snapshot_repo: Arc<S>,
+    snapshot_policy: SnapshotPolicy,
+    upcasters: Vec<Arc<dyn EventUpcaster>>,
+    command_bus: Arc<InMemoryCommandBus>,
+    query_bus: Arc<InMemoryQueryBus>,
+    event_bus: Option<Arc<dyn EventBus>>,
+    event_handlers: Vec<Arc<dyn EventHandler>>,
+    outbox: Option<(Arc<dyn EventDeliverer>, Arc<dyn EventReclaimer>)>,
+    engine_config: EventEngineConfig,
+    engine_tuning: EngineTuning,
+    engine_name: String,
+    event_bus_capacity: usize,
+    registration_error: Option<AppError>,
+    self_check: SelfCheckRegistry,
+}
+
+impl<E, S> DddRuntimeBuilder<E, S>
+where
+    E: EventRepository,
+    S: SnapshotRepository,
+{
+    pub fn new(event_repo: Arc<E>, snapshot_repo: Arc<S>) -> Self {
+        Self {
+            event_repo,
+            snapshot_repo,
+            snapshot_policy: SnapshotPolicy::Never,
+            upcasters: Vec::new(),
+            command_bus: Arc::new(InMemoryCommandBus::new()),
+            query_bus: Arc::new(InMemoryQueryBus::new()),
+            event_bus: None,
+            event_handlers: Vec::new(),
+            outbox: None,
+            engine_config: EventEngineConfig::default(),
+            engine_tuning: EngineTuning::default(),
+            engine_name: "default".to_string(),
+            event_bus_capacity: DEFAULT_EVENT_BUS_CAPACITY,
+            registration_error: None,
+            self_check: SelfCheckRegistry::default(),
+        }
+    }
+
+    /// 替换事件仓储
+    pub fn event_repository<E2>(self, event_repo: Arc<E2>) -> DddRuntimeBuilder<E2, S>
+    where
+        E2: EventRepository,
+    {
+        DddRuntimeBuilder {
+            event_repo,
+            snapshot_repo: self.snapshot_repo,
+            snapshot_policy: self.snapshot_policy,
+            upcasters: self.upcasters,
+            command_bus: self.command_bus,
+            query_bus: self.query_bus,
+            event_bus: self.event_bus,
+            event_handlers: self.event_handlers,
+            outbox: self.outbox,
+            engine_config: self.engine_config,
+            engine_tuning: self.engine_tuning,
+            engine_name: self.engine_name,
+            event_bus_capacity: self.event_bus_capacity,
+            registration_error: self.registration_error,
+            self_check: self.self_check,
+        }
+    }
+
+    /// 替换快照仓储
+    pub fn snapshot_repository<S2>(self, snapshot_repo: Arc<S2>) -> DddRuntimeBuilder<E, S2>
+    where
+        S2: SnapshotRepository,
+    {
+        DddRuntimeBuilder {
+            event_repo: self.event_repo,
+            snapshot_repo,
+            snapshot_policy: self.snapshot_policy,
+            upcasters: self.upcasters,
+            command_bus: self.command_bus,
+            query_bus: self.query_bus,
+            event_bus: self.event_bus,
+            event_handlers: self.event_handlers,
+            outbox: self.outbox,
+            engine_config: self.engine_config,
+            engine_tuning: self.engine_tuning,
+            engine_name: self.engine_name,
+            event_bus_capacity: self.event_bus_capacity,
+            registration_error: self.registration_error,
+            self_check: self.self_check,
+        }
+    }
+
+    pub fn snapshot_policy(mut self, policy: SnapshotPolicy) -> Self {
+        self.snapshot_policy = policy;
+        self
+    }
+
+    /// 追加事件上抬器（按追加顺序组成上抬链）
+    pub fn upcaster(mut self, upcaster: Arc<dyn EventUpcaster>) -> Self {
+        self.upcasters.push(upcaster);
+        self
+    }
+
+    pub fn event_bus(mut self, event_bus: Arc<dyn EventBus>) -> Self {
+        self.event_bus = Some(event_bus);
+        self
+    }
+
+    pub fn event_handler(mut self, handler: Arc<dyn EventHandler>) -> Self {
+        self.event_handlers.push(handler);
+        self
+    }
+
+    /// 登记聚合以参与启动自检（上抬器覆盖、事件类型登记与快照结构）
+    pub fn aggregate<A: Aggregate>(mut self) -> Self {
+        self.self_check
+            .aggregates
+            .push(AggregateRegistration::of::<A>());
+        self
+    }
+
+    /// 登记限界上下文，自检时校验其装配（`ContextMap::issues`）
+    pub fn bounded_context(mut self, context: BoundedContext) -> Self {
+        self.self_check.context_map =
+            std::mem::take(&mut self.self_check.context_map).context(context);
+        self
+    }
+
+    /// 登记投影的检查点存储，自检时确认可读取
+    pub fn checkpoint_store(
+        mut self,
+        projection: impl Into<String>,
+        store: Arc<dyn CheckpointStore>,
+    ) -> Self {
+        self.self_check
+            .checkpoint_stores
+            .push((projection.into(), store));
+        self
+    }
+
+    /// 配置事件引擎的投递源与回收器
+    pub fn outbox(
+        mut self,
+        deliverer: Arc<dyn EventDeliverer>,
+        reclaimer: Arc<dyn EventReclaimer>,
+    ) -> Self {
+        self.outbox = Some((deliverer, reclaimer));
+        self
+    }
+
+    pub fn engine_config(mut self, config: EventEngineConfig) -> Self {
+        self.engine_config = config;
+        self
+    }
+
+    /// 引擎可选行为（重试、死信、压缩、日志等）
+    pub fn engine_tuning(mut self, tuning: EngineTuning) -> Self {
+        self.engine_tuning = tuning;
+        self
+    }
+
+    /// 应用加载的配置：引擎名称与参数、快照策略与默认内存总线的容量
+    #[cfg(feature = "config")]
+    pub fn settings(mut self, config: &DddConfig) -> Self {
+        self.engine_name = config.engine.name.clone();
+        self.engine_config = config.engine_config();
+        self.engine_tuning = config.engine_tuning();
+        self.snapshot_policy = config.snapshot_policy();
+        self.event_bus_capacity = config.bus.capacity;
+        self
+    }
+
+    pub fn command_handler<C, H>(mut self, handler: Arc<H>) -> Self
+    where
+        C: Send + 'static,
+        H: CommandHandler<C> + Send + Sync + 'static,
+    {
+        if let Err(e) = self.command_bus.register::<C, H>(handler) {
+            self.registration_error.get_or_insert(e);
+        }
+        self
+    }
+
+    pub fn query_handler<Q, R, H>(mut self, handler: Arc<H>) -> Self
+    where
+        Q: Send + 'static,
+        R: Send + 'static,
+        H: QueryHandler<Q, R> + Send + Sync + 'static,
+    {
+        if let Err(e) = self.query_bus.register::<Q, R, H>(handler) {
+            self.registration_error.get_or_insert(e);
+        }
+        self
+    }
+
+    pub fn build(mut self) -> Result<DddRuntime<E, S>, AppError> {
+        if let Some(e) = self.registration_error {
+            return Err(e);
+        }
+
+        self.self_check.handlers = self
+            .event_handlers
+            .iter()
+            .map(|h| (h.handler_name().to_string(), h.handled_event_type()))
+            .collect();
+
+        let event_bus = self
+            .event_bus
+            .unwrap_or_else(|| Arc::new(InMemoryEventBus::new(self.event_bus_capacity)));
+
+        let engine = match self.outbox {
+            Some((deliverer, reclaimer)) => Some(Arc::new(
+                EventEngine::builder()
+                    .event_bus(Arc::clone(&event_bus))
+                    .event_deliverer(deliverer)
+                    .event_reclaimer(reclaimer)
+                    .event_handlers(self.event_handlers)
+                    .config(self.engine_config)
+                    .tuning(self.engine_tuning)
+                    .name(self.engine_name)
+                    .build(),
+            )),
+            None if !self.event_handlers.is_empty() => {
+                return Err(AppError::validation(
+                    "event handlers require an outbox (deliverer and reclaimer)",
+                ));
+            }
+            None => None,
+        };
+        if let Some(engine) = &engine {
+            engine.check_ordering()?;
+        }
+
+        Ok(DddRuntime {
+            event_repo: self.event_repo,
+            snapshot_repo: Arc::new(SnapshotRepositoryWithPolicy::new(
+                self.snapshot_repo,
+                self.snapshot_policy,
+            )),
+            upcaster_chain: Arc::new(self.upcasters.into_iter().collect()),
+            command_bus: self.command_bus,
+            query_bus: self.query_bus,
+            event_bus,
+            engine,
+            self_check: self.self_check,
+        })
+    }
+}
@@ -0,0 +1,312 @@
+//! 启动自检（Self Check）
+//!
+//! `DddRuntime::self_check` 在对外提供服务前校验装配，返回逐项结果组成的 `SelfCheckReport`：
+//! - `upcaster_coverage`：登记聚合的每个事件类型，从 v1 到当前版本的每一步都有上抬器；
+//! - `event_registry`：限界上下文装配问题（`ContextMap::issues`），以及同一事件类型被多个聚合声明；
+//! - `handler_event_types`：事件处理器订阅的事件类型均在登记表中（登记表为空时跳过）；
+//! - `checkpoint_stores`：登记的检查点存储可读取；
+//! - `event_repository`/`snapshot_repository`：仓储响应 `ping`；
+//! - `snapshot_schema`：登记聚合的默认实例可序列化为快照并还原。
+//!
+//! `SelfCheckReport::into_result` 在存在失败项时返回错误，便于启动时快速失败。
+//!
+use ddd_application::error::AppError;
+use ddd_domain::aggregate::Aggregate;
+use ddd_domain::bounded_context::ContextMap;
+use ddd_domain::domain_event::{DomainEvent, EventDescriptor};
+use ddd_domain::error::DomainResult;
+use ddd_domain::event_upcaster::EventUpcasterChain;
+use ddd_domain::eventing::{CheckpointStore, HandledEventType};
+use ddd_domain::persist::{EventRepository, SerializedSnapshot, SnapshotRepository};
+use std::collections::{BTreeMap, BTreeSet};
+use std::fmt;
+use std::sync::Arc;
+
+/// 登记参与自检的聚合
+#[derive(Clone)]
+pub(crate) struct AggregateRegistration {
+    aggregate_type: &'static str,
+    descriptors: &'static [EventDescriptor],
+    snapshot_roundtrip: fn() -> DomainResult<()>,
+}
+
+impl AggregateRegistration {
+    pub(crate) fn of<A: Aggregate>() -> Self {
+        Self {
+            aggregate_type: A::TYPE,
+            descriptors: A::Event::DESCRIPTORS,
+            snapshot_roundtrip: snapshot_roundtrip::<A>,
+        }
+    }
+}
+
+fn snapshot_roundtrip<A: Aggregate>() -> DomainResult<()> {
+    let snapshot = SerializedSnapshot::from_aggregate(&A::default())?;
+    snapshot.to_aggregate::<A>().map(|_: A| ())
+}
+
+/// 单项检查的结果
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CheckStatus {
+    Passed,
+    /// 未登记相关组件，检查未执行
+    Skipped,
+    /// 检查失败，附带全部问题
+    Failed(Vec<String>),
+}
+
+/// 单项检查
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CheckResult {
+    pub name: &'static str,
+    pub status: CheckStatus,
+}
+
+impl CheckResult {
+    fn from_problems(name: &'static str, problems: Vec<String>) -> Self {
+        let status = if problems.is_empty() {
+            CheckStatus::Passed
+        } else {
+            CheckStatus::Failed(problems)
+        };
+        Self { name, status }
+    }
+
+    fn probe(name: &'static str, result: DomainResult<()>) -> Self {
+        Self::from_problems(
+            name,
+            result.err().map(|e| e.to_string()).into_iter().collect(),
+        )
+    }
+
+    fn skipped(name: &'static str) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Skipped,
+        }
+    }
+
+    pub fn is_failed(&self) -> bool {
+        matches!(self.status, CheckStatus::Failed(_))
+    }
+}
+
+/// 自检报告（按检查执行顺序）
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SelfCheckReport {
+    checks: Vec<CheckResult>,
+}
+
+impl SelfCheckReport {
+    pub fn checks(&self) -> &[CheckResult] {
+        &self.checks
+    }
+
+    /// 按名称取检查结果
+    pub fn check(&self, name: &str) -> Option<&CheckResult> {
+        self.checks.iter().find(|c| c.name == name)
+    }
+
+    /// 全部检查通过（或跳过）
+    pub fn is_ok(&self) -> bool {
+        !self.checks.iter().any(CheckResult::is_failed)
+    }
+
+    /// 失败的检查
+    pub fn failures(&self) -> impl Iterator<Item = &CheckResult> {
+        self.checks.iter().filter(|c| c.is_failed())
+    }
+
+    /// 存在失败项时返回 `AppError::validation`，消息列出全部问题
+    pub fn into_result(self) -> Result<Self, AppError> {
+        if self.is_ok() {
+            Ok(self)
+        } else {
+            Err(AppError::validation(format!(
+                "runtime self check failed:\n{self}"
+            )))
+        }
+    }
+}
+
+impl fmt::Display for SelfCheckReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, check) in self.checks.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            match &check.status {
+                CheckStatus::Passed => write!(f, "[ok] {}", check.name)?,
+                CheckStatus::Skipped => write!(f, "[skip] {}", check.name)?,
+                CheckStatus::Failed(problems) => {
+                    write!(f, "[fail] {}", check.name)?;
+                    for problem in problems {
+                        write!(f, "\n  - {problem}")?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// 自检所需的装配信息（由构建器收集）
+#[derive(Default)]
+pub(crate) struct SelfCheckRegistry {
+    pub(crate) aggregates: Vec<AggregateRegistration>,
+    pub(crate) context_map: ContextMap,
+    pub(crate) checkpoint_stores: Vec<(String, Arc<dyn CheckpointStore>)>,
+    pub(crate) handlers: Vec<(String, HandledEventType)>,
+}
+
+impl SelfCheckRegistry {
+    pub(crate) async fn run<E, S>(
+        &self,
+        upcaster_chain: &EventUpcasterChain,
+        event_repo: &E,
+        snapshot_repo: &S,
+    ) -> SelfCheckReport
+    where
+        E: EventRepository,
+        S: SnapshotRepository,
+    {
+        let mut checks = vec![
+            self.upcaster_coverage(upcaster_chain),
+            self.event_registry(),
+            self.handler_event_types(),
+            self.checkpoint_stores().await,
+        ];
+        checks.push(CheckResult::probe(
+            "event_repository",
+            event_repo.ping().await,
+        ));
+        checks.push(CheckResult::probe(
+            "snapshot_repository",
+            snapshot_repo.ping().await,
+        ));
+        checks.push(self.snapshot_schema());
+        SelfCheckReport { checks }
+    }
+
+    fn upcaster_coverage(&self, chain: &EventUpcasterChain) -> CheckResult {
+        const NAME: &str = "upcaster_coverage";
+        if self.aggregates.is_empty() {
+            return CheckResult::skipped(NAME);
+        }
+
+        let problems = self
+            .aggregates
+            .iter()
+            .flat_map(|a| a.descriptors.iter().map(move |d| (a.aggregate_type, d)))
+            .filter_map(|(aggregate_type, d)| {
+                let missing = chain.uncovered_versions(d.event_type, d.event_version);
+                (!missing.is_empty()).then(|| {
+                    format!(
+                        "{aggregate_type} event {} (v{}) has no upcaster from {}",
+                        d.event_type,
+                        d.event_version,
+                        missing
+                            .iter()
+                            .map(|v| format!("v{v}"))
+                            .collect::<Vec<_>>()
+                            .join(", ")
+                    )
+                })
+            })
+            .collect();
+        CheckResult::from_problems(NAME, problems)
+    }
+
+    fn event_registry(&self) -> CheckResult {
+        let mut problems: Vec<String> = self
+            .context_map
+            .issues()
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+
+        let mut owners: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for aggregate in &self.aggregates {
+            for d in aggregate.descriptors {
+                owners
+                    .entry(d.event_type)
+                    .or_default()
+                    .insert(aggregate.aggregate_type);
+            }
+        }
+        problems.extend(
+            owners
+                .into_iter()
+                .filter(|(_, aggregates)| aggregates.len() > 1)
+                .map(|(event_type, aggregates)| {
+                    format!(
+                        "event type {event_type} is declared by multiple aggregates: {}",
+                        aggregates.into_iter().collect::<Vec<_>>().join(", ")
+                    )
+                }),
+        );
+        CheckResult::from_problems("event_registry", problems)
+    }
+
+    fn handler_event_types(&self) -> CheckResult {
+        const NAME: &str = "handler_event_types";
+        let registry: BTreeSet<&str> = self
+            .aggregates
+            .iter()
+            .flat_map(|a| a.descriptors.iter().map(|d| d.event_type))
+            .chain(self.context_map.event_types())
+            .collect();
+        if registry.is_empty() || self.handlers.is_empty() {
+            return CheckResult::skipped(NAME);
+        }
+
+        let mut problems = Vec::new();
+        for (handler, handled) in &self.handlers {
+            let event_types = match handled {
+                HandledEventType::One(event_type) => std::slice::from_ref(event_type),
+                HandledEventType::Many(event_types) => event_types.as_slice(),
+                HandledEventType::All => &[],
+            };
+            problems.extend(
+                event_types
+                    .iter()
+                    .filter(|t| !registry.contains(t.as_str()))
+                    .map(|t| format!("handler {handler} subscribes to unknown event type {t}")),
+            );
+        }
+        CheckResult::from_problems(NAME, problems)
+    }
+
+    async fn checkpoint_stores(&self) -> CheckResult {
+        const NAME: &str = "checkpoint_stores";
+        if self.checkpoint_stores.is_empty() {
+            return CheckResult::skipped(NAME);
+        }
+
+        let mut problems = Vec::new();
+        for (projection, store) in &self.checkpoint_stores {
+            if let Err(e) = store.load(projection).await {
+                problems.push(format!("checkpoint store for {projection}: {e}"));
+            }
+        }
+        CheckResult::from_problems(NAME, problems)
+    }
+
+    fn snapshot_schema(&self) -> CheckResult {
+        const NAME: &str = "snapshot_schema";
+        if self.aggregates.is_empty() {
+            return CheckResult::skipped(NAME);
+        }
+
+        let problems = self
+            .aggregates
+            .iter()
+            .filter_map(|a| {
+                (a.snapshot_roundtrip)()
+                    .err()
+                    .map(|e| format!("{} snapshot does not round-trip: {e}", a.aggregate_type))
+            })
+            .collect();
+        CheckResult::from_problems(NAME, problems)
+    }
+}
@@ -0,0 +1,763 @@
+//! 端到端参考应用：账户间转账
+//!
+//! 同时作为可执行文档，串联整个 crate 的主要能力：
+//! - 两个聚合：`Account`（开户/扣款/入账）与 `Transfer`（发起/完成/失败）；
+//! - Saga：`TransferSaga` 订阅 `transfer.initiated`，经命令总线依次扣款、入账并完成转账，
+//!   扣款失败时将转账标记为失败；
+//! - 投影：`BalancesProjection` 维护账户余额读模型，通过查询总线读取；
+//! - Outbox + 引擎：`OutboxRepository` 在事件落库时写入 outbox，由 `EventEngine` 投递到总线；
+//! - 上抬：历史遗留的 v1 `account.opened` 事件（无币种）经 `#[upcaster]` 升级为 v2 后重建。
+//!
+use async_trait::async_trait;
+use ddd::prelude::*;
+use ddd_domain::eventing::EngineHandle;
+use ddd_domain::testing::InMemoryEventRepository;
+use ddd_macros::upcaster;
+use serde::{Deserialize, Serialize};
+use serde_json::{Value, json};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, OnceLock};
+use std::time::Duration;
+
+// ---------------------------------------------------------------------------
+// 领域层：账户
+// ---------------------------------------------------------------------------
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Account {
+    owner: String,
+    currency: String,
+    balance: i64,
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum AccountEvent {
+    /// v2 新增 `currency`
+    #[event(event_type = "account.opened", event_version = 2)]
+    Opened {
+        owner: String,
+        currency: String,
+        balance: i64,
+    },
+    #[event(event_type = "account.debited")]
+    Debited { transfer_id: String, amount: i64 },
+    #[event(event_type = "account.credited")]
+    Credited { transfer_id: String, amount: i64 },
+}
+
+#[derive(Debug, Clone)]
+enum AccountCommand {
+    Open { owner: String, balance: i64 },
+    Debit { transfer_id: String, amount: i64 },
+    Credit { transfer_id: String, amount: i64 },
+}
+
+impl Aggregate for Account {
+    const TYPE: &'static str = "account";
+    type Command = AccountCommand;
+    type Event = AccountEvent;
+    type Error = DomainError;
+
+    fn execute(&self, command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        let aggregate_version = self.version().next();
+        if !matches!(command, AccountCommand::Open { .. }) && self.version().is_new() {
+            return Err(DomainError::not_found(format!("account {}", self.id())));
+        }
+
+        let event = match command {
+            AccountCommand::Open { owner, balance } => {
+                if !self.version().is_new() {
+                    return Err(DomainError::invalid_state("account already opened"));
+                }
+                AccountEvent::Opened {
+                    id: next_event_id(),
+                    aggregate_version,
+                    owner,
+                    currency: "CNY".to_string(),
+                    balance,
+                }
+            }
+            AccountCommand::Debit {
+                transfer_id,
+                amount,
+            } => {
+                if self.balance < amount {
+                    return Err(DomainError::invalid_state(format!(
+                        "insufficient funds: balance {}, requested {amount}",
+                        self.balance
+                    ))
+                    .with_code("INSUFFICIENT_FUNDS"));
+                }
+                AccountEvent::Debited {
+                    id: next_event_id(),
+                    aggregate_version,
+                    transfer_id,
+                    amount,
+                }
+            }
+            AccountCommand::Credit {
+                transfer_id,
+                amount,
+            } => AccountEvent::Credited {
+                id: next_event_id(),
+                aggregate_version,
+                transfer_id,
+                amount,
+            },
+        };
+        Ok(vec![event])
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        match event {
+            AccountEvent::Opened {
+                aggregate_version,
+                owner,
+                currency,
+                balance,
+                ..
+            } => {
+                self.owner = owner.clone();
+                self.currency = currency.clone();
+                self.balance = *balance;
+                self.version = *aggregate_version;
+            }
+            AccountEvent::Debited {
+                aggregate_version,
+                amount,
+                ..
+            } => {
+                self.balance -= amount;
+                self.version = *aggregate_version;
+            }
+            AccountEvent::Credited {
+                aggregate_version,
+                amount,
+                ..
+            } => {
+                self.balance += amount;
+                self.version = *aggregate_version;
+            }
+        }
+    }
+}
+
+/// v1 的开户事件没有币种，历史账户均为人民币
+#[upcaster(event_type = "account.opened", from = 1, to = 2, variant = "Opened")]
+fn opened_add_currency(payload: &mut Value) {
+    payload["currency"] = json!("CNY");
+}
+
+// ---------------------------------------------------------------------------
+// 领域层：转账
+// ---------------------------------------------------------------------------
+
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+enum TransferStatus {
+    #[default]
+    Draft,
+    Pending,
+    Completed,
+    Failed,
+}
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Transfer {
+    from: String,
+    to: String,
+    amount: i64,
+    status: TransferStatus,
+    failure: Option<String>,
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum TransferEvent {
+    #[event(event_type = "transfer.initiated")]
+    Initiated {
+        from: String,
+        to: String,
+        amount: i64,
+    },
+    #[event(event_type = "transfer.completed")]
+    Completed {},
+    #[event(event_type = "transfer.failed")]
+    Failed { reason: String },
+}
+
+#[derive(Debug, Clone)]
+enum TransferCommand {
+    Initiate {
+        from: String,
+        to: String,
+        amount: i64,
+    },
+    Complete,
+    Fail {
+        reason: String,
+    },
+}
+
+impl Aggregate for Transfer {
+    const TYPE: &'static str = "transfer";
+    type Command = TransferCommand;
+    type Event = TransferEvent;
+    type Error = DomainError;
+
+    fn execute(&self, command: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        let aggregate_version = self.version().next();
+        let expected = match command {
+            TransferCommand::Initiate { .. } => TransferStatus::Draft,
+            _ => TransferStatus::Pending,
+        };
+        if self.status != expected {
+            return Err(DomainError::invalid_state(format!(
+                "transfer {} is {:?}",
+                self.id(),
+                self.status
+            )));
+        }
+
+        let event = match command {
+            TransferCommand::Initiate { from, to, amount } => {
+                if amount <= 0 || from == to {
+                    return Err(DomainError::invalid_command("invalid transfer"));
+                }
+                TransferEvent::Initiated {
+                    id: next_event_id(),
+                    aggregate_version,
+                    from,
+                    to,
+                    amount,
+                }
+            }
+            TransferCommand::Complete => TransferEvent::Completed {
+                id: next_event_id(),
+                aggregate_version,
+            },
+            TransferCommand::Fail { reason } => TransferEvent::Failed {
+                id: next_event_id(),
+                aggregate_version,
+                reason,
+            },
+        };
+        Ok(vec![event])
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        match event {
+            TransferEvent::Initiated {
+                aggregate_version,
+                from,
+                to,
+                amount,
+                ..
+            } => {
+                self.from = from.clone();
+                self.to = to.clone();
+                self.amount = *amount;
+                self.status = TransferStatus::Pending;
+                self.version = *aggregate_version;
+            }
+            TransferEvent::Completed {
+                aggregate_version, ..
+            } => {
+                self.status = TransferStatus::Completed;
+                self.version = *aggregate_version;
+            }
+            TransferEvent::Failed {
+                aggregate_version,
+                reason,
+                ..
+            } => {
+                self.status = TransferStatus::Failed;
+                self.failure = Some(reason.clone());
+                self.version = *aggregate_version;
+            }
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// 应用层：命令与查询
+// ---------------------------------------------------------------------------
+
+/// 对聚合 `A` 执行一条领域命令
+struct Execute<A: Aggregate> {
+    id: A::Id,
+    command: A::Command,
+}
+
+impl<A: Aggregate> Execute<A> {
+    fn new(id: impl Into<A::Id>, command: A::Command) -> Self {
+        Self {
+            id: id.into(),
+            command,
+        }
+    }
+}
+
+struct ExecuteHandler<A, R>
+where
+    A: Aggregate,
+    R: AggregateRepository<A>,
+{
+    root: AggregateRoot<A, R>,
+}
+
+#[async_trait]
+impl<A, R> CommandHandler<Execute<A>> for ExecuteHandler<A, R>
+where
+    A: Aggregate<Error = DomainError> + 'static,
+    A::Command: Send,
+    R: AggregateRepository<A> + 'static,
+{
+    async fn handle(&self, ctx: &AppContext, cmd: Execute<A>) -> Result<(), AppError> {
+        self.root
+            .execute(&cmd.id, vec![cmd.command], ctx.event_context.clone())
+            .await?;
+        Ok(())
+    }
+}
+
+struct GetBalance(String);
+
+struct GetBalanceHandler(Arc<BalancesProjection>);
+
+#[async_trait]
+impl QueryHandler<GetBalance, Option<i64>> for GetBalanceHandler {
+    async fn handle(&self, _ctx: &AppContext, q: GetBalance) -> Result<Option<i64>, AppError> {
+        Ok(self.0.balances.lock().unwrap().get(&q.0).copied())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// 事件处理：Saga 与投影
+// ---------------------------------------------------------------------------
+
+/// 转账流程编排：扣款 → 入账 → 完成；扣款被拒绝时标记失败
+#[derive(Default)]
+struct TransferSaga {
+    commands: OnceLock<Arc<InMemoryCommandBus>>,
+}
+
+#[derive(Deserialize)]
+struct Initiated {
+    from: String,
+    to: String,
+    amount: i64,
+}
+
+#[async_trait]
+impl EventHandler for TransferSaga {
+    fn handler_name(&self) -> &str {
+        "transfer_saga"
+    }
+
+    fn handled_event_type(&self) -> HandledEventType {
+        HandledEventType::One("transfer.initiated".to_string())
+    }
+
+    async fn handle(&self, event: &SerializedEvent, _ctx: &HandlerContext) -> anyhow::Result<()> {
+        let bus = self.commands.get().expect("saga is connected");
+        let transfer_id = event.aggregate_id().to_string();
+        let Initiated { from, to, amount } =
+            serde_json::from_value(event.payload()["Initiated"].clone())?;
+        // 后续命令产生的事件以本事件为因
+        let ctx = AppContext::from(event);
+
+        let debit = AccountCommand::Debit {
+            transfer_id: transfer_id.clone(),
+            amount,
+        };
+        if let Err(err) = bus
+            .dispatch(&ctx, Execute::<Account>::new(from, debit))
+            .await
+        {
+            let fail = TransferCommand::Fail {
+                reason: err.code().to_string(),
+            };
+            bus.dispatch(&ctx, Execute::<Transfer>::new(transfer_id, fail))
+                .await?;
+            return Ok(());
+        }
+
+        let credit = AccountCommand::Credit {
+            transfer_id: transfer_id.clone(),
+            amount,
+        };
+        bus.dispatch(&ctx, Execute::<Account>::new(to, credit))
+            .await?;
+        bus.dispatch(
+            &ctx,
+            Execute::<Transfer>::new(transfer_id, TransferCommand::Complete),
+        )
+        .await?;
+        Ok(())
+    }
+}
+
+/// 账户余额读模型（直接读取 JSON 负载，兼容各版本的开户事件）
+#[derive(Default)]
+struct BalancesProjection {
+    balances: Mutex<HashMap<String, i64>>,
+}
+
+#[async_trait]
+impl EventHandler for BalancesProjection {
+    fn handler_name(&self) -> &str {
+        "balances_view"
+    }
+
+    fn handled_event_type(&self) -> HandledEventType {
+        HandledEventType::Many(vec![
+            "account.opened".to_string(),
+            "account.debited".to_string(),
+            "account.credited".to_string(),
+        ])
+    }
+
+    async fn handle(&self, event: &SerializedEvent, _ctx: &HandlerContext) -> anyhow::Result<()> {
+        let payload = event.payload();
+        let mut balances = self.balances.lock().unwrap();
+        let balance = balances
+            .entry(event.aggregate_id().to_string())
+            .or_default();
+        match event.event_type() {
+            "account.opened" => *balance = payload["Opened"]["balance"].as_i64().unwrap_or(0),
+            "account.debited" => *balance -= payload["Debited"]["amount"].as_i64().unwrap_or(0),
+            "account.credited" => *balance += payload["Credited"]["amount"].as_i64().unwrap_or(0),
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// 基础设施：事件存储 + outbox
+// ---------------------------------------------------------------------------
+
+/// 事件落库的同时写入 outbox（同一「事务」），由引擎投递到总线
+#[derive(Default)]
+struct OutboxRepository {
+    events: InMemoryEventRepository,
+    outbox: Mutex<Vec<SerializedEvent>>,
+}
+
+#[async_trait]
+impl EventRepository for OutboxRepository {
+    async fn get_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+    ) -> DomainResult<Vec<SerializedEvent>> {
+        self.events.get_events::<A>(aggregate_id).await
+    }
+
+    async fn get_last_events<A: Aggregate>(
+        &self,
+        aggregate_id: &A::Id,
+        last_version: usize,
+    ) -> DomainResult<Vec<SerializedEvent>> {
+        self.events
+            .get_last_events::<A>(aggregate_id, last_version)
+            .await
+    }
+
+    async fn save(&self, events: Vec<SerializedEvent>) -> DomainResult<()> {
+        self.events.save(events.clone()).await?;
+        self.outbox.lock().unwrap().extend(events);
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventDeliverer for OutboxRepository {
+    async fn fetch_events(&self) -> DomainResult<Vec<SerializedEvent>> {
+        Ok(std::mem::take(&mut *self.outbox.lock().unwrap()))
+    }
+
+    async fn mark_delivered(&self, _events: &[&SerializedEvent]) -> DomainResult<()> {
+        Ok(())
+    }
+
+    async fn mark_failed(&self, events: &[&SerializedEvent], _reason: &str) -> DomainResult<()> {
+        let mut outbox = self.outbox.lock().unwrap();
+        outbox.extend(events.iter().map(|e| (*e).clone()));
+        Ok(())
+    }
+}
+
+/// 记录处理失败；参考应用中不应出现任何失败
+#[derive(Default)]
+struct FailureLog {
+    failures: Mutex<Vec<(String, String)>>,
+}
+
+#[async_trait]
+impl EventReclaimer for FailureLog {
+    async fn fetch_events(&self) -> DomainResult<Vec<SerializedEvent>> {
+        Ok(Vec::new())
+    }
+
+    async fn mark_reclaimed(&self, _events: &[&SerializedEvent]) -> DomainResult<()> {
+        Ok(())
+    }
+
+    async fn mark_failed(&self, events: &[&SerializedEvent], reason: &str) -> DomainResult<()> {
+        self.mark_handler_failed("engine", events, reason).await
+    }
+
+    async fn mark_handler_failed(
+        &self,
+        handler_name: &str,
+        events: &[&SerializedEvent],
+        reason: &str,
+    ) -> DomainResult<()> {
+        let mut failures = self.failures.lock().unwrap();
+        for event in events {
+            failures.push((
+                format!("{handler_name}:{}", event.event_type()),
+                reason.to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+// ---------------------------------------------------------------------------
+// 装配
+// ---------------------------------------------------------------------------
+
+type Runtime = DddRuntime<OutboxRepository, ddd_domain::testing::InMemorySnapshotRepository>;
+
+struct App {
+    runtime: Runtime,
+    store: Arc<OutboxRepository>,
+    failures: Arc<FailureLog>,
+    engine: EngineHandle,
+}
+
+impl App {
+    fn start() -> anyhow::Result<Self> {
+        let store = Arc::new(OutboxRepository::default());
+        let saga = Arc::new(TransferSaga::default());
+        let balances = Arc::new(BalancesProjection::default());
+        let failures = Arc::new(FailureLog::default());
+
+        let runtime = DddRuntime::builder()
+            .event_repository(store.clone())
+            .snapshot_policy(SnapshotPolicy::Every(2))
+            .upcaster(Arc::new(OpenedAddCurrency))
+            .event_handler(saga.clone())
+            .event_handler(balances.clone())
+            .outbox(store.clone(), failures.clone())
+            .engine_config(EventEngineConfig {
+                deliver_interval: Duration::from_millis(5),
+                ..Default::default()
+            })
+            .query_handler::<GetBalance, Option<i64>, _>(Arc::new(GetBalanceHandler(
+                balances.clone(),
+            )))
+            .build()?;
+
+        let commands = runtime.command_bus();
+        commands.register::<Execute<Account>, _>(Arc::new(ExecuteHandler {
+            root: runtime.aggregate_root::<Account>(),
+        }))?;
+        commands.register::<Execute<Transfer>, _>(Arc::new(ExecuteHandler {
+            root: runtime.aggregate_root::<Transfer>(),
+        }))?;
+        let _ = saga.commands.set(Arc::clone(commands));
+
+        let engine = runtime.start_engine().expect("outbox is configured");
+        Ok(Self {
+            runtime,
+            store,
+            failures,
+            engine,
+        })
+    }
+
+    async fn execute<A: Aggregate + 'static>(
+        &self,
+        ctx: &AppContext,
+        id: &str,
+        command: A::Command,
+    ) -> Result<(), AppError>
+    where
+        A::Id: From<String>,
+        A::Command: Send,
+    {
+        self.runtime
+            .command_bus()
+            .dispatch(ctx, Execute::<A>::new(id.to_string(), command))
+            .await
+    }
+
+    async fn transfer(&self, id: &str) -> anyhow::Result<Transfer> {
+        let root = self.runtime.aggregate_root::<Transfer>();
+        Ok(root.load(&id.to_string()).await?.expect("transfer exists"))
+    }
+
+    async fn account(&self, id: &str) -> anyhow::Result<Account> {
+        let root = self.runtime.aggregate_root::<Account>();
+        Ok(root.load(&id.to_string()).await?.expect("account exists"))
+    }
+
+    async fn balance(&self, id: &str) -> anyhow::Result<Option<i64>> {
+        let q = GetBalance(id.to_string());
+        Ok(self
+            .runtime
+            .query_bus()
+            .dispatch(&AppContext::default(), q)
+            .await?)
+    }
+
+    /// 等待 outbox 清空且转账全部结束
+    async fn settle(&self, transfers: &[&str]) -> anyhow::Result<()> {
+        tokio::time::timeout(Duration::from_secs(5), async {
+            loop {
+                let mut done = self.store.outbox.lock().unwrap().is_empty();
+                for id in transfers {
+                    done &= self.transfer(id).await?.status != TransferStatus::Pending;
+                }
+                if done {
+                    // 留出最后一批事件的处理时间
+                    tokio::time::sleep(Duration::from_millis(20)).await;
+                    return anyhow::Ok(());
+                }
+                tokio::time::sleep(Duration::from_millis(5)).await;
+            }
+        })
+        .await?
+    }
+}
+
+/// 上线前写入的 v1 开户事件（无币种字段）
+fn legacy_opened(account_id: &str, owner: &str, balance: i64) -> SerializedEvent {
+    serde_json::from_value(json!({
+        "event_id": next_event_id(),
+        "event_type": "account.opened",
+        "event_version": 1,
+        "sequence_number": null,
+        "aggregate_id": account_id,
+        "aggregate_type": "account",
+        "aggregate_version": 1,
+        "correlation_id": null,
+        "causation_id": null,
+        "actor_type": null,
+        "actor_id": null,
+        "occurred_at": "2020-01-01T00:00:00Z",
+        "payload": {"Opened": {
+            "id": "legacy",
+            "aggregate_version": 1,
+            "owner": owner,
+            "balance": balance
+        }},
+        "context": {}
+    }))
+    .unwrap()
+}
+
+// ---------------------------------------------------------------------------
+// 场景
+// ---------------------------------------------------------------------------
+
+#[tokio::test]
+async fn transfers_money_between_accounts_end_to_end() -> anyhow::Result<()> {
+    let app = App::start()?;
+    let ctx = AppContext::default();
+
+    // 历史账户 alice（v1 事件）与新开户的 bob
+    app.store
+        .save(vec![legacy_opened("alice", "alice", 100)])
+        .await?;
+    let open = AccountCommand::Open {
+        owner: "bob".into(),
+        balance: 0,
+    };
+    app.execute::<Account>(&ctx, "bob", open).await?;
+
+    // 成功的转账：alice → bob 30
+    let initiate = TransferCommand::Initiate {
+        from: "alice".into(),
+        to: "bob".into(),
+        amount: 30,
+    };
+    let ctx = AppContext::new(
+        EventContext::builder()
+            .correlation_id("req-1".to_string())
+            .build(),
+    );
+    app.execute::<Transfer>(&ctx, "t-1", initiate).await?;
+
+    // 余额不足的转账：bob → alice 1000
+    let initiate = TransferCommand::Initiate {
+        from: "bob".into(),
+        to: "alice".into(),
+        amount: 1000,
+    };
+    app.execute::<Transfer>(&AppContext::default(), "t-2", initiate)
+        .await?;
+
+    app.settle(&["t-1", "t-2"]).await?;
+
+    // 聚合状态
+    let t1 = app.transfer("t-1").await?;
+    assert_eq!(t1.status, TransferStatus::Completed);
+    let t2 = app.transfer("t-2").await?;
+    assert_eq!(t2.status, TransferStatus::Failed);
+    assert_eq!(t2.failure.as_deref(), Some("INSUFFICIENT_FUNDS"));
+
+    let alice = app.account("alice").await?;
+    assert_eq!((alice.balance, alice.currency.as_str()), (70, "CNY"));
+    assert_eq!(alice.owner, "alice");
+    assert_eq!(app.account("bob").await?.balance, 30);
+
+    // 读模型与聚合一致
+    assert_eq!(app.balance("alice").await?, Some(70));
+    assert_eq!(app.balance("bob").await?, Some(30));
+    assert_eq!(app.balance("carol").await?, None);
+
+    // Saga 产生的事件沿用发起请求的关联 ID，并以 transfer.initiated 为因
+    let saga_events = app
+        .store
+        .events
+        .all_events()
+        .into_iter()
+        .filter(|e| e.correlation_id() == Some("req-1"))
+        .map(|e| e.event_type().to_string())
+        .collect::<Vec<_>>();
+    assert_eq!(
+        saga_events,
+        [
+            "transfer.initiated",
+            "account.debited",
+            "account.credited",
+            "transfer.completed"
+        ]
+    );
+
+    // 快照按策略落盘（alice 版本 2）
+    let snapshot = app
+        .runtime
+        .snapshot_repository()
+        .get_snapshot::<Account>(&"alice".to_string(), None)
+        .await?;
+    assert_eq!(snapshot.map(|s| s.aggregate_version()), Some(2));
+
+    // 转账状态机拒绝重复完成
+    let err = app
+        .execute::<Transfer>(&AppContext::default(), "t-1", TransferCommand::Complete)
+        .await
+        .unwrap_err();
+    assert_eq!(err.code(), "INVALID_STATE");
+
+    assert!(app.failures.failures.lock().unwrap().is_empty());
+    app.engine.shutdown();
+    app.engine.join().await;
+    Ok(())
+}
@@ -0,0 +1,304 @@
+use async_trait::async_trait;
+use ddd::domain::eventing::InMemoryCheckpointStore;
+use ddd::prelude::*;
+use serde::{Deserialize, Serialize};
+use std::sync::Arc;
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Counter {
+    value: i64,
+}
+
+#[domain_event(version = 1)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum CounterEvent {
+    Incremented { by: i64 },
+}
+
+impl Aggregate for Counter {
+    const TYPE: &'static str = "counter";
+    type Command = i64;
+    type Event = CounterEvent;
+    type Error = DomainError;
+
+    fn execute(&self, by: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        Ok(vec![CounterEvent::Incremented {
+            id: next_event_id(),
+            aggregate_version: self.version().next(),
+            by,
+        }])
+    }
+
+    fn apply(&mut self, event: &Self::Event) {
+        let CounterEvent::Incremented {
+            aggregate_version,
+            by,
+            ..
+        } = event;
+        self.value += by;
+        self.version = *aggregate_version;
+    }
+}
+
+struct Increment {
+    id: String,
+    by: i64,
+}
+
+struct GetValue {
+    id: String,
+}
+
+struct IncrementHandler<R: AggregateRepository<Counter>> {
+    root: AggregateRoot<Counter, R>,
+}
+
+#[async_trait]
+impl<R: AggregateRepository<Counter>> CommandHandler<Increment> for IncrementHandler<R> {
+    async fn handle(&self, ctx: &AppContext, cmd: Increment) -> Result<(), AppError> {
+        self.root
+            .execute(&cmd.id, vec![cmd.by], ctx.event_context.clone())
+            .await?;
+        Ok(())
+    }
+}
+
+struct GetValueHandler<R: AggregateRepository<Counter>> {
+    root: AggregateRoot<Counter, R>,
+}
+
+#[async_trait]
+impl<R: AggregateRepository<Counter>> QueryHandler<GetValue, i64> for GetValueHandler<R> {
+    async fn handle(&self, _ctx: &AppContext, q: GetValue) -> Result<i64, AppError> {
+        Ok(self.root.load(&q.id).await?.map_or(0, |c| c.value))
+    }
+}
+
+#[tokio::test]
+async fn builds_in_memory_stack_with_defaults() -> anyhow::Result<()> {
+    let runtime = DddRuntime::builder()
+        .snapshot_policy(SnapshotPolicy::Every(2))
+        .build()?;
+
+    runtime
+        .command_bus()
+        .register::<Increment, _>(Arc::new(IncrementHandler {
+            root: runtime.aggregate_root::<Counter>(),
+        }))?;
+    runtime
+        .query_bus()
+        .register::<GetValue, i64, _>(Arc::new(GetValueHandler {
+            root: runtime.aggregate_root::<Counter>(),
+        }))?;
+
+    let ctx = AppContext::default();
+    for by in [1, 2, 3] {
+        let cmd = Increment {
+            id: "c-1".into(),
+            by,
+        };
+        runtime.command_bus().dispatch(&ctx, cmd).await?;
+    }
+
+    let value: i64 = runtime
+        .query_bus()
+        .dispatch(&ctx, GetValue { id: "c-1".into() })
+        .await?;
+    assert_eq!(value, 6);
+
+    let snapshot = runtime
+        .snapshot_repository()
+        .get_snapshot::<Counter>(&"c-1".to_string(), None)
+        .await?;
+    assert_eq!(snapshot.map(|s| s.aggregate_version()), Some(2));
+    assert!(runtime.engine().is_none());
+    Ok(())
+}
+
+struct Noop;
+
+#[async_trait]
+impl QueryHandler<GetValue, i64> for Noop {
+    async fn handle(&self, _ctx: &AppContext, _q: GetValue) -> Result<i64, AppError> {
+        Ok(0)
+    }
+}
+
+#[async_trait]
+impl EventHandler for Noop {
+    async fn handle(&self, _event: &SerializedEvent, _ctx: &HandlerContext) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn handled_event_type(&self) -> HandledEventType {
+        HandledEventType::All
+    }
+
+    fn handler_name(&self) -> &str {
+        "noop"
+    }
+}
+
+#[test]
+fn build_reports_misconfiguration() {
+    let err = DddRuntime::builder()
+        .query_handler::<GetValue, i64, _>(Arc::new(Noop))
+        .query_handler::<GetValue, i64, _>(Arc::new(Noop))
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(err.code(), "HANDLER_ALREADY_REGISTERED");
+
+    let err = DddRuntime::builder()
+        .event_handler(Arc::new(Noop))
+        .build()
+        .err()
+        .unwrap();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+}
+
+#[entity]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+struct Meter {
+    reading: i64,
+}
+
+#[domain_event(version = 3)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+enum MeterEvent {
+    Read { reading: i64 },
+}
+
+impl Aggregate for Meter {
+    const TYPE: &'static str = "meter";
+    type Command = i64;
+    type Event = MeterEvent;
+    type Error = DomainError;
+
+    fn execute(&self, _reading: Self::Command) -> Result<Vec<Self::Event>, Self::Error> {
+        Ok(Vec::new())
+    }
+
+    fn apply(&mut self, _event: &Self::Event) {}
+}
+
+/// 只覆盖 v2 -> v3 的上抬器，v1 缺失
+struct MeterReadV2;
+
+impl EventUpcaster for MeterReadV2 {
+    fn applies(&self, event_type: &str, event_version: usize) -> bool {
+        event_type == MeterEvent::DESCRIPTORS[0].event_type && event_version == 2
+    }
+
+    fn upcast(&self, event: SerializedEvent) -> DomainResult<EventUpcasterResult> {
+        Ok(EventUpcasterResult::One(event))
+    }
+}
+
+struct Subscriber(HandledEventType);
+
+#[async_trait]
+impl EventHandler for Subscriber {
+    async fn handle(&self, _event: &SerializedEvent, _ctx: &HandlerContext) -> anyhow::Result<()> {
+        Ok(())
+    }
+
+    fn handled_event_type(&self) -> HandledEventType {
+        self.0.clone()
+    }
+
+    fn handler_name(&self) -> &str {
+        "subscriber"
+    }
+}
+
+struct EmptyOutbox;
+
+#[async_trait]
+impl EventDeliverer for EmptyOutbox {
+    async fn fetch_events(&self) -> DomainResult<Vec<SerializedEvent>> {
+        Ok(Vec::new())
+    }
+
+    async fn mark_delivered(&self, _events: &[&SerializedEvent]) -> DomainResult<()> {
+        Ok(())
+    }
+
+    async fn mark_failed(&self, _events: &[&SerializedEvent], _reason: &str) -> DomainResult<()> {
+        Ok(())
+    }
+}
+
+#[async_trait]
+impl EventReclaimer for EmptyOutbox {
+    async fn fetch_events(&self) -> DomainResult<Vec<SerializedEvent>> {
+        Ok(Vec::new())
+    }
+
+    async fn mark_reclaimed(&self, _events: &[&SerializedEvent]) -> DomainResult<()> {
+        Ok(())
+    }
+
+    async fn mark_failed(&self, _events: &[&SerializedEvent], _reason: &str) -> DomainResult<()> {
+        Ok(())
+    }
+
+    async fn mark_handler_failed(
+        &self,
+        _handler_name: &str,
+        _events: &[&SerializedEvent],
+        _reason: &str,
+    ) -> DomainResult<()> {
+        Ok(())
+    }
+}
+
+#[tokio::test]
+async fn self_check_reports_wiring_problems() -> anyhow::Result<()> {
+    let outbox = Arc::new(EmptyOutbox);
+    let runtime = DddRuntime::builder()
+        .aggregate::<Counter>()
+        .checkpoint_store("counter_view", Arc::new(InMemoryCheckpointStore::new()))
+        .event_handler(Arc::new(Subscriber(HandledEventType::of(
+            CounterEvent::DESCRIPTORS,
+        ))))
+        .outbox(outbox.clone(), outbox.clone())
+        .build()?;
+    let report = runtime.self_check().await.into_result()?;
+    assert!(
+        report
+            .checks()
+            .iter()
+            .all(|c| c.status == CheckStatus::Passed)
+    );
+
+    let runtime = DddRuntime::builder()
+        .aggregate::<Counter>()
+        .aggregate::<Meter>()
+        .upcaster(Arc::new(MeterReadV2))
+        .event_handler(Arc::new(Subscriber(HandledEventType::One(
+            "CounterEvent.Decremented".to_string(),
+        ))))
+        .outbox(outbox.clone(), outbox)
+        .build()?;
+    let report = runtime.self_check().await;
+    assert!(!report.is_ok());
+    assert_eq!(
+        report.failures().map(|c| c.name).collect::<Vec<_>>(),
+        ["upcaster_coverage", "handler_event_types"]
+    );
+    let CheckStatus::Failed(problems) = &report.check("upcaster_coverage").unwrap().status else {
+        panic!("upcaster coverage should fail");
+    };
+    assert_eq!(problems.len(), 1);
+    assert!(problems[0].contains("from v1"));
+    assert_eq!(
+        report.check("checkpoint_stores").map(|c| &c.status),
+        Some(&CheckStatus::Skipped)
+    );
+
+    let err = report.into_result().err().unwrap();
+    assert_eq!(err.code(), "VALIDATION_ERROR");
+    Ok(())
+}